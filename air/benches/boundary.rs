@@ -0,0 +1,59 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use math::fields::f64::BaseElement;
+use rand_utils::rand_vector;
+use std::time::Duration;
+use winter_air::{
+    AirContext, Assertion, BoundaryConstraints, FieldExtension, ProofOptions, TraceInfo,
+    TransitionConstraintDegree,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+const TRACE_LENGTH: usize = 1 << 16;
+const NUM_COLUMNS: [usize; 3] = [16, 64, 128];
+
+fn build_boundary_constraints(c: &mut Criterion) {
+    let mut group = c.benchmark_group("boundary_constraints");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(10));
+
+    for &num_columns in NUM_COLUMNS.iter() {
+        // place a sequence assertion against every column; assertions against different columns
+        // start at different steps, and thus fall into different constraint groups
+        let assertions = (0..num_columns)
+            .map(|column| {
+                let stride = 1 << (column % 4 + 1);
+                let values = rand_vector::<BaseElement>(TRACE_LENGTH / stride);
+                Assertion::sequence(column, column % stride, stride, values)
+            })
+            .collect::<Vec<_>>();
+        let context = build_context(num_columns, assertions.len());
+        let coefficients = rand_vector::<BaseElement>(assertions.len());
+
+        group.bench_function(BenchmarkId::from_parameter(num_columns), |bench| {
+            bench.iter_with_large_drop(|| {
+                BoundaryConstraints::new(&context, assertions.clone(), vec![], &coefficients)
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(boundary_group, build_boundary_constraints);
+criterion_main!(boundary_group);
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_context(trace_width: usize, num_assertions: usize) -> AirContext<BaseElement> {
+    let options = ProofOptions::new(32, 8, 0, FieldExtension::None, 4, 31);
+    let t_degrees = vec![TransitionConstraintDegree::new(2)];
+    let trace_info = TraceInfo::new(trace_width, TRACE_LENGTH);
+    AirContext::new(trace_info, t_degrees, num_assertions, options)
+}
@@ -8,8 +8,11 @@ use core::{
     cmp::{Ord, Ordering, PartialOrd},
     fmt::{Display, Formatter},
 };
-use math::FieldElement;
-use utils::collections::Vec;
+use math::{ExtensionOf, FieldElement, StarkField};
+use utils::{
+    collections::Vec,
+    string::{String, ToString},
+};
 
 #[cfg(test)]
 mod tests;
@@ -25,8 +28,8 @@ const NO_STRIDE: usize = 0;
 
 /// An assertion made against an execution trace.
 ///
-/// An assertion is always placed against a single column of an execution trace, but can cover
-/// multiple steps and multiple values. Specifically, there are three kinds of assertions:
+/// An assertion is usually placed against a single column of an execution trace, but can cover
+/// multiple steps and multiple values. Specifically, there are three kinds of such assertions:
 ///
 /// 1. **Single** assertion - which requires that a value in a single cell of an execution trace
 ///    is equal to the specified value.
@@ -41,6 +44,11 @@ const NO_STRIDE: usize = 0;
 ///   be equal to a sequence 1, 2, 3, 4 at steps 0, 8, 16, 24. That is, value at step 0 should be
 ///   equal to 1, value at step 8 should be equal to 2 etc.
 ///
+/// In addition to the above, a **linear combination** assertion requires that a linear
+/// combination of values in several columns at a single step is equal to the specified value
+/// (e.g., `2 * col_0 + col_1 = 5`). Such an assertion is still a constraint of degree 1, and thus,
+/// it does not require an extra trace column to hold the value of the combination.
+///
 /// Note that single and periodic assertions are succinct. That is, a verifier can evaluate them
 /// very efficiently. However, sequence assertions have liner complexity in the number of
 /// asserted values. Though, unless many thousands of values are asserted, practical impact of
@@ -51,6 +59,7 @@ pub struct Assertion<E: FieldElement> {
     pub(super) first_step: usize,
     pub(super) stride: usize,
     pub(super) values: Vec<E>,
+    pub(super) combination: Vec<(usize, E)>,
 }
 
 impl<E: FieldElement> Assertion<E> {
@@ -66,6 +75,7 @@ impl<E: FieldElement> Assertion<E> {
             first_step: step,
             stride: NO_STRIDE,
             values: vec![value],
+            combination: Vec::new(),
         }
     }
 
@@ -86,6 +96,7 @@ impl<E: FieldElement> Assertion<E> {
             first_step,
             stride,
             values: vec![value],
+            combination: Vec::new(),
         }
     }
 
@@ -117,6 +128,51 @@ impl<E: FieldElement> Assertion<E> {
             first_step,
             stride: if values.len() == 1 { NO_STRIDE } else { stride },
             values,
+            combination: Vec::new(),
+        }
+    }
+
+    /// Returns an assertion against a linear combination of cells in a single row of an execution
+    /// trace.
+    ///
+    /// The returned assertion requires that the sum of values in the specified `columns` at the
+    /// specified `step`, each multiplied by the corresponding coefficient from `coefficients`, is
+    /// equal to the provided `value`.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `columns` is empty.
+    /// * The number of coefficients is not equal to the number of columns.
+    /// * `columns` contains duplicate column indexes.
+    pub fn linear_combination(
+        coefficients: Vec<E>,
+        columns: Vec<usize>,
+        step: usize,
+        value: E,
+    ) -> Self {
+        assert!(
+            !columns.is_empty(),
+            "invalid linear combination assertion: number of columns must be greater than zero"
+        );
+        assert_eq!(
+            coefficients.len(),
+            columns.len(),
+            "invalid linear combination assertion: expected {} coefficients, but was {}",
+            columns.len(),
+            coefficients.len()
+        );
+        for (i, column) in columns.iter().enumerate() {
+            assert!(
+                !columns[..i].contains(column),
+                "invalid linear combination assertion: column {column} is included more than once"
+            );
+        }
+        Assertion {
+            column: columns[0],
+            first_step: step,
+            stride: NO_STRIDE,
+            values: vec![value],
+            combination: columns.into_iter().zip(coefficients).collect(),
         }
     }
 
@@ -124,6 +180,8 @@ impl<E: FieldElement> Assertion<E> {
     // --------------------------------------------------------------------------------------------
 
     /// Returns index of the column against which this assertion is placed.
+    ///
+    /// For linear combination assertions, this is the first column of the combination.
     pub fn column(&self) -> usize {
         self.column
     }
@@ -164,16 +222,34 @@ impl<E: FieldElement> Assertion<E> {
         self.values.len() > 1
     }
 
+    /// Returns true if this is an assertion against a linear combination of several columns.
+    pub fn is_linear_combination(&self) -> bool {
+        !self.combination.is_empty()
+    }
+
+    /// Returns (column, coefficient) pairs describing the linear combination against which this
+    /// assertion is placed.
+    ///
+    /// For assertions against a single column, this will be an empty slice.
+    pub fn combination(&self) -> &[(usize, E)] {
+        &self.combination
+    }
+
     // PUBLIC METHODS
     // --------------------------------------------------------------------------------------------
 
     /// Checks if this assertion overlaps with the provided assertion.
     ///
-    /// Overlap is defined as asserting a value for the same step in the same column.
+    /// Overlap is defined as asserting a value for the same step in the same column. For linear
+    /// combination assertions, overlap is defined as asserting a value for the same step and the
+    /// same linear combination.
     pub fn overlaps_with(&self, other: &Assertion<E>) -> bool {
         if self.column != other.column {
             return false;
         }
+        if self.is_linear_combination() || other.is_linear_combination() {
+            return self.first_step == other.first_step && self.combination == other.combination;
+        }
         if self.first_step == other.first_step {
             return true;
         }
@@ -210,6 +286,9 @@ impl<E: FieldElement> Assertion<E> {
         if self.column >= trace_width {
             return Err(AssertionError::TraceWidthTooShort(self.column, trace_width));
         }
+        if let Some(&(column, _)) = self.combination.iter().find(|(c, _)| *c >= trace_width) {
+            return Err(AssertionError::TraceWidthTooShort(column, trace_width));
+        }
         Ok(())
     }
 
@@ -270,6 +349,50 @@ impl<E: FieldElement> Assertion<E> {
         }
     }
 
+    /// Returns a human-readable summary of this assertion instantiated against an execution
+    /// trace of the specified length.
+    ///
+    /// # Panics
+    /// Panics if the specified trace length is not valid for this assertion.
+    pub fn summarize(&self, trace_length: usize) -> AssertionSummary<E> {
+        let mut steps = Vec::new();
+        let mut values = Vec::new();
+        self.apply(trace_length, |step, value| {
+            steps.push(step);
+            values.push(value);
+        });
+        let columns = if self.is_linear_combination() {
+            self.combination.iter().map(|&(column, _)| column).collect()
+        } else {
+            vec![self.column]
+        };
+        AssertionSummary {
+            columns,
+            steps,
+            values,
+            description: self.to_string(),
+        }
+    }
+
+    /// Returns the value of the expression against which this assertion is placed in the
+    /// specified row of an execution trace.
+    ///
+    /// For assertions against a single column, this is the value of the column in the row; for
+    /// linear combination assertions, this is the linear combination of the values of the
+    /// asserted columns in the row.
+    pub fn get_trace_value<S>(&self, row: &[S]) -> S
+    where
+        S: FieldElement<BaseField = E::BaseField> + ExtensionOf<E>,
+    {
+        if self.combination.is_empty() {
+            row[self.column]
+        } else {
+            self.combination.iter().fold(S::ZERO, |acc, &(column, coefficient)| {
+                acc + S::from(coefficient) * row[column]
+            })
+        }
+    }
+
     /// Returns the number of steps against which this assertion will be applied given an
     /// execution trace of the specified length.
     ///
@@ -293,16 +416,46 @@ impl<E: FieldElement> Assertion<E> {
     }
 }
 
+impl<B: StarkField> Assertion<B> {
+    // EXTENSION FIELD CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+    /// Returns assertions against a single extension field cell of an execution trace.
+    ///
+    /// An execution trace over the base field can hold an extension field value in
+    /// `E::EXTENSION_DEGREE` consecutive columns, with the column at offset `i` holding the `i`th
+    /// base field component of the value (see [FieldElement::base_element()]). The returned
+    /// assertions require that the value held in the columns starting at `column` at the
+    /// specified `step` is equal to the provided `value`; that is, the i-th assertion requires
+    /// that the value in column `column + i` is equal to the i-th component of `value`.
+    ///
+    /// For a base field `value`, this returns a single assertion which is the same as the one
+    /// returned by [single()](Assertion::single).
+    pub fn single_ext<E: FieldElement<BaseField = B>>(
+        column: usize,
+        step: usize,
+        value: E,
+    ) -> Vec<Self> {
+        (0..E::EXTENSION_DEGREE)
+            .map(|i| Self::single(column + i, step, value.base_element(i)))
+            .collect()
+    }
+}
+
 // OTHER TRAIT IMPLEMENTATIONS
 // =================================================================================================
 
 /// We define ordering of assertions to be first by stride, then by first_step, and finally by
-/// column in ascending order.
+/// column in ascending order. Linear combination assertions with the same first column are
+/// further ordered by the columns and the coefficients of their combinations.
 impl<E: FieldElement> Ord for Assertion<E> {
     fn cmp(&self, other: &Self) -> Ordering {
         if self.stride == other.stride {
             if self.first_step == other.first_step {
-                self.column.partial_cmp(&other.column).unwrap()
+                self.column.cmp(&other.column).then_with(|| {
+                    let lhs = self.combination.iter().map(|(c, v)| (c, v.as_bytes()));
+                    let rhs = other.combination.iter().map(|(c, v)| (c, v.as_bytes()));
+                    lhs.cmp(rhs)
+                })
             } else {
                 self.first_step.partial_cmp(&other.first_step).unwrap()
             }
@@ -320,6 +473,16 @@ impl<E: FieldElement> PartialOrd for Assertion<E> {
 
 impl<E: FieldElement> Display for Assertion<E> {
     fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        if self.is_linear_combination() {
+            write!(f, "(combination=[")?;
+            for (i, (column, coefficient)) in self.combination.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " + ")?;
+                }
+                write!(f, "{coefficient} * column {column}")?;
+            }
+            return write!(f, "], step={}, value={})", self.first_step, self.values[0]);
+        }
         write!(f, "(column={}, ", self.column)?;
         match self.stride {
             0 => write!(f, "step={}, ", self.first_step)?,
@@ -336,6 +499,34 @@ impl<E: FieldElement> Display for Assertion<E> {
     }
 }
 
+// ASSERTION SUMMARY
+// =================================================================================================
+
+/// A human-readable description of an assertion instantiated against an execution trace of a
+/// specific length.
+///
+/// Unlike [Assertion], which describes the steps of periodic and sequence assertions via the
+/// first step and the stride, a summary lists all steps at which values are asserted. Thus, it
+/// is intended for debugging and inspection rather than for constraint evaluation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssertionSummary<E: FieldElement> {
+    /// Columns against which the assertion is placed; for linear combination assertions, these
+    /// are the columns of the combination.
+    pub columns: Vec<usize>,
+    /// Steps of the execution trace at which values are asserted, in ascending order.
+    pub steps: Vec<usize>,
+    /// Values asserted at the corresponding steps.
+    pub values: Vec<E>,
+    /// Description of the assertion, e.g., `(column=1, step=63, value=42)`.
+    pub description: String,
+}
+
+impl<E: FieldElement> Display for AssertionSummary<E> {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
 // HELPER FUNCTIONS
 // =================================================================================================
 
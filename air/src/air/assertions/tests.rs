@@ -4,9 +4,12 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{Assertion, AssertionError};
-use math::{fields::f128::BaseElement, FieldElement};
+use math::{
+    fields::{f128::BaseElement, QuadExtension},
+    FieldElement,
+};
 use rand_utils::{rand_value, rand_vector};
-use utils::collections::Vec;
+use utils::{collections::Vec, string::ToString};
 
 // SINGLE ASSERTIONS
 // ================================================================================================
@@ -33,6 +36,25 @@ fn single_assertion() {
     assert_eq!(Err(AssertionError::TraceLengthTooShort(16, 8)), a.validate_trace_length(8));
 }
 
+#[test]
+fn single_ext_assertion() {
+    let a0 = rand_value::<BaseElement>();
+    let a1 = rand_value::<BaseElement>();
+    let assertions = Assertion::single_ext(2, 8, QuadExtension::new(a0, a1));
+    assert_eq!(vec![Assertion::single(2, 8, a0), Assertion::single(3, 8, a1)], assertions);
+
+    // the assertions must fit into the trace together
+    assert!(assertions.iter().all(|a| a.validate_trace_width(4).is_ok()));
+    assert_eq!(
+        Err(AssertionError::TraceWidthTooShort(3, 3)),
+        assertions[1].validate_trace_width(3)
+    );
+
+    // for a base field value, this is the same as a single assertion
+    let assertions = Assertion::single_ext(2, 8, a0);
+    assert_eq!(vec![Assertion::single(2, 8, a0)], assertions);
+}
+
 // PERIODIC ASSERTIONS
 // ================================================================================================
 
@@ -174,6 +196,70 @@ fn sequence_assertion_num_values_not_power_of_two() {
         Assertion::sequence(3, 2, 4, vec![BaseElement::ONE, BaseElement::ZERO, BaseElement::ONE]);
 }
 
+// LINEAR COMBINATION ASSERTIONS
+// ================================================================================================
+
+#[test]
+fn linear_combination_assertion() {
+    let value = rand_value::<BaseElement>();
+    let coefficients = vec![BaseElement::new(2), BaseElement::ONE];
+    let a = Assertion::linear_combination(coefficients, vec![3, 1], 8, value);
+    assert_eq!(3, a.column);
+    assert_eq!(8, a.first_step);
+    assert_eq!(vec![value], a.values);
+    assert_eq!(0, a.stride);
+    assert!(a.is_single());
+    assert!(a.is_linear_combination());
+    assert_eq!(&[(3, BaseElement::new(2)), (1, BaseElement::ONE)], a.combination());
+    assert_eq!(1, a.get_num_steps(16));
+
+    // the asserted expression is evaluated as 2 * col_3 + col_1
+    let row = vec![
+        BaseElement::new(7),
+        BaseElement::new(5),
+        BaseElement::new(7),
+        BaseElement::new(3),
+    ];
+    assert_eq!(BaseElement::new(11), a.get_trace_value(&row));
+
+    // all columns of the combination must fit into the trace
+    assert_eq!(Ok(()), a.validate_trace_width(4));
+    assert_eq!(Err(AssertionError::TraceWidthTooShort(3, 3)), a.validate_trace_width(3));
+    let a = Assertion::linear_combination(vec![BaseElement::ONE; 2], vec![1, 3], 8, value);
+    assert_eq!(Err(AssertionError::TraceWidthTooShort(3, 2)), a.validate_trace_width(2));
+
+    // single-column assertions are not linear combinations
+    let a = Assertion::single(3, 8, value);
+    assert!(!a.is_linear_combination());
+    assert!(a.combination().is_empty());
+    assert_eq!(BaseElement::new(3), a.get_trace_value(&row));
+}
+
+#[test]
+#[should_panic(
+    expected = "invalid linear combination assertion: number of columns must be greater than zero"
+)]
+fn linear_combination_assertion_no_columns() {
+    let _ = Assertion::linear_combination(Vec::new(), Vec::new(), 8, BaseElement::ONE);
+}
+
+#[test]
+#[should_panic(
+    expected = "invalid linear combination assertion: expected 2 coefficients, but was 1"
+)]
+fn linear_combination_assertion_inconsistent_coefficients() {
+    let _ = Assertion::linear_combination(vec![BaseElement::ONE], vec![0, 1], 8, BaseElement::ONE);
+}
+
+#[test]
+#[should_panic(
+    expected = "invalid linear combination assertion: column 1 is included more than once"
+)]
+fn linear_combination_assertion_duplicate_columns() {
+    let coefficients = vec![BaseElement::ONE; 3];
+    let _ = Assertion::linear_combination(coefficients, vec![1, 0, 1], 8, BaseElement::ONE);
+}
+
 // OVERLAPPING ASSERTIONS
 // ================================================================================================
 
@@ -337,4 +423,66 @@ fn assertion_overlap() {
     let b = Assertion::periodic(0, 0, 16, BaseElement::ONE);
     assert!(!a.overlaps_with(&b));
     assert!(!b.overlaps_with(&a));
+
+    // ----- linear combination overlap -----------------------------------------------------------
+
+    let coefficients = vec![BaseElement::new(2), BaseElement::ONE];
+    let a = Assertion::linear_combination(coefficients.clone(), vec![3, 1], 2, BaseElement::ONE);
+    let b = Assertion::linear_combination(coefficients.clone(), vec![3, 1], 2, BaseElement::ZERO);
+    assert!(a.overlaps_with(&b));
+    assert!(b.overlaps_with(&a));
+
+    // different combinations: no overlap
+    let b =
+        Assertion::linear_combination(vec![BaseElement::ONE; 2], vec![3, 1], 2, BaseElement::ONE);
+    assert!(!a.overlaps_with(&b));
+    assert!(!b.overlaps_with(&a));
+
+    // different steps: no overlap
+    let b = Assertion::linear_combination(coefficients, vec![3, 1], 3, BaseElement::ONE);
+    assert!(!a.overlaps_with(&b));
+    assert!(!b.overlaps_with(&a));
+
+    // a combination does not overlap with a single-column assertion against the same cell
+    let b = Assertion::single(3, 2, BaseElement::ONE);
+    assert!(!a.overlaps_with(&b));
+    assert!(!b.overlaps_with(&a));
+}
+
+// ASSERTION SUMMARIES
+// ================================================================================================
+
+#[test]
+fn assertion_summary() {
+    let value = BaseElement::new(7);
+    let summary = Assertion::single(2, 8, value).summarize(16);
+    assert_eq!(vec![2], summary.columns);
+    assert_eq!(vec![8], summary.steps);
+    assert_eq!(vec![value], summary.values);
+    assert_eq!("(column=2, step=8, value=7)", summary.to_string());
+
+    // periodic assertions are expanded into all steps at which they apply
+    let summary = Assertion::periodic(1, 1, 4, value).summarize(16);
+    assert_eq!(vec![1], summary.columns);
+    assert_eq!(vec![1, 5, 9, 13], summary.steps);
+    assert_eq!(vec![value; 4], summary.values);
+
+    // each value of a sequence assertion is matched with its step
+    let values = vec![BaseElement::new(1), BaseElement::new(2)];
+    let summary = Assertion::sequence(0, 2, 8, values.clone()).summarize(16);
+    assert_eq!(vec![0], summary.columns);
+    assert_eq!(vec![2, 10], summary.steps);
+    assert_eq!(values, summary.values);
+    assert_eq!("(column=0, steps=[2, 10, ...], values=[1, 2])", summary.description);
+
+    // all columns of a linear combination are listed
+    let coefficients = vec![BaseElement::new(2), BaseElement::ONE];
+    let summary = Assertion::linear_combination(coefficients, vec![3, 1], 4, value).summarize(16);
+    assert_eq!(vec![3, 1], summary.columns);
+    assert_eq!(vec![4], summary.steps);
+    assert_eq!(vec![value], summary.values);
+    assert_eq!(
+        "(combination=[2 * column 3 + 1 * column 1], step=4, value=7)",
+        summary.description
+    );
 }
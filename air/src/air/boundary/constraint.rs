@@ -12,7 +12,9 @@ use math::{fft, polynom};
 ///
 /// A boundary constraint is described by a rational function $\frac{f(x) - b(x)}{z(x)}$, where:
 ///
-/// * $f(x)$ is a trace polynomial for the column against which the constraint is placed.
+/// * $f(x)$ is a trace polynomial for the column against which the constraint is placed (or a
+///   linear combination of trace polynomials for constraints derived from linear combination
+///   assertions).
 /// * $b(b)$ is the value polynomial for this constraint.
 /// * $z(x)$ is the constraint divisor polynomial.
 ///
@@ -34,6 +36,7 @@ where
     E: FieldElement<BaseField = F::BaseField> + ExtensionOf<F>,
 {
     column: usize,
+    combination: Vec<(usize, F)>,
     poly: Vec<F>,
     poly_offset: (usize, F::BaseField),
     cc: E,
@@ -47,11 +50,42 @@ where
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
     /// Creates a new boundary constraint from the specified assertion.
+    ///
+    /// If inverse twiddles needed to interpolate the asserted values are not in `twiddle_map`,
+    /// they are built and added to the map.
+    #[cfg(test)]
     pub(super) fn new(
         assertion: Assertion<F>,
         inv_g: F::BaseField,
         twiddle_map: &mut BTreeMap<usize, Vec<F::BaseField>>,
         composition_coefficient: E,
+    ) -> Self {
+        // if twiddles for the domain of the value polynomial haven't been built yet, build them
+        // and add them to the map
+        let num_values = assertion.values.len();
+        if num_values > 1 {
+            twiddle_map
+                .entry(num_values)
+                .or_insert_with(|| fft::get_inv_twiddles(num_values));
+        }
+
+        Self::with_twiddles(assertion, inv_g, twiddle_map, composition_coefficient)
+    }
+
+    /// Creates a new boundary constraint from the specified assertion using inverse twiddles
+    /// from the provided map.
+    ///
+    /// Unlike [new()](BoundaryConstraint::new), this does not modify the twiddle map, and thus,
+    /// can be used to build constraints for different assertions concurrently.
+    ///
+    /// # Panics
+    /// Panics if the assertion is a multi-value assertion and `twiddle_map` does not contain
+    /// inverse twiddles for a domain of the same size as the number of asserted values.
+    pub(super) fn with_twiddles(
+        assertion: Assertion<F>,
+        inv_g: F::BaseField,
+        twiddle_map: &BTreeMap<usize, Vec<F::BaseField>>,
+        composition_coefficient: E,
     ) -> Self {
         // build a polynomial which evaluates to constraint values at asserted steps; for
         // single-value assertions we use the value as constant coefficient of degree 0
@@ -60,11 +94,10 @@ where
         let mut poly_offset = (0, F::BaseField::ONE);
         let mut poly = assertion.values;
         if poly.len() > 1 {
-            // get the twiddles from the map; if twiddles for this domain haven't been built
-            // yet, build them and add them to the map
+            // get the twiddles for this domain from the map
             let inv_twiddles = twiddle_map
-                .entry(poly.len())
-                .or_insert_with(|| fft::get_inv_twiddles(poly.len()));
+                .get(&poly.len())
+                .expect("inverse twiddles for value polynomial domain have not been built");
             // interpolate the values into a polynomial
             fft::interpolate_poly(&mut poly, inv_twiddles);
             if assertion.first_step != 0 {
@@ -79,6 +112,7 @@ where
 
         BoundaryConstraint {
             column: assertion.column,
+            combination: assertion.combination,
             poly,
             poly_offset,
             cc: composition_coefficient,
@@ -93,6 +127,14 @@ where
         self.column
     }
 
+    /// Returns (column, coefficient) pairs of the linear combination against which this
+    /// constraint applies.
+    ///
+    /// For constraints placed against a single column, this will be an empty slice.
+    pub fn combination(&self) -> &[(usize, F)] {
+        &self.combination
+    }
+
     /// Returns a value polynomial for this constraint.
     pub fn poly(&self) -> &[F] {
         &self.poly
@@ -114,6 +156,21 @@ where
 
     // CONSTRAINT EVALUATOR
     // --------------------------------------------------------------------------------------------
+    /// Returns the value of the expression against which this constraint applies in the specified
+    /// row of an execution trace.
+    ///
+    /// This is the value of the constrained column for constraints placed against a single
+    /// column, and the linear combination of the values of the constrained columns otherwise.
+    pub fn get_trace_value(&self, row: &[E]) -> E {
+        if self.combination.is_empty() {
+            row[self.column]
+        } else {
+            self.combination.iter().fold(E::ZERO, |acc, &(column, coefficient)| {
+                acc + row[column].mul_base(coefficient)
+            })
+        }
+    }
+
     /// Evaluates this constraint at the specified point `x`.
     ///
     /// The constraint is evaluated by computing $f(x) - b(x)$, where:
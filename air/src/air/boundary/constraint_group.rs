@@ -75,14 +75,17 @@ where
     // --------------------------------------------------------------------------------------------
 
     /// Creates a new boundary constraint from the specified assertion and adds it to the group.
+    ///
+    /// Inverse twiddles required to interpolate the values of multi-value assertions must already
+    /// be present in `twiddle_map`.
     pub(super) fn add(
         &mut self,
         assertion: Assertion<F>,
         inv_g: F::BaseField,
-        twiddle_map: &mut BTreeMap<usize, Vec<F::BaseField>>,
+        twiddle_map: &BTreeMap<usize, Vec<F::BaseField>>,
         composition_coefficients: E,
     ) {
-        self.constraints.push(BoundaryConstraint::new(
+        self.constraints.push(BoundaryConstraint::with_twiddles(
             assertion,
             inv_g,
             twiddle_map,
@@ -104,7 +107,7 @@ where
     pub fn evaluate_at(&self, state: &[E], x: E) -> E {
         let mut numerator = E::ZERO;
         for constraint in self.constraints().iter() {
-            let trace_value = state[constraint.column()];
+            let trace_value = constraint.get_trace_value(state);
             let evaluation = constraint.evaluate_at(x, trace_value);
             numerator += evaluation * *constraint.cc();
         }
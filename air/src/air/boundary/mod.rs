@@ -4,9 +4,12 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{AirContext, Assertion, ConstraintDivisor};
-use math::{ExtensionOf, FieldElement};
+use math::{fft, polynom, ExtensionOf, FieldElement};
 use utils::collections::{BTreeMap, BTreeSet, Vec};
 
+#[cfg(feature = "concurrent")]
+use utils::iterators::*;
+
 mod constraint;
 pub use constraint::BoundaryConstraint;
 
@@ -128,6 +131,131 @@ impl<E: FieldElement> BoundaryConstraints<E> {
         }
     }
 
+    // LAZY EVALUATION
+    // --------------------------------------------------------------------------------------------
+    /// Evaluates boundary constraints described by the provided assertions at the specified
+    /// point `x`, and returns a random linear combination of the evaluations.
+    ///
+    /// The result is the same as building [BoundaryConstraints] from the assertions, evaluating
+    /// each of the constraint groups at `x` and summing the results. However, boundary
+    /// constraints are never materialized: value polynomials of multi-value assertions are not
+    /// interpolated, but are evaluated directly from the asserted values (without allocating any
+    /// memory), and only the accumulated numerator is retained for each divisor. This reduces
+    /// peak memory for computations with very long sequence assertions.
+    ///
+    /// The assertions themselves are still materialized, since they are passed to this function
+    /// as vectors; thus, memory required to hold the assertions is not reduced.
+    ///
+    /// Constraints against auxiliary trace segments are evaluated only if `aux_state` is
+    /// provided.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [new()](BoundaryConstraints::new), and also if `x`
+    /// is in the trace domain.
+    pub fn evaluate_lazily(
+        context: &AirContext<E::BaseField>,
+        main_assertions: Vec<Assertion<E::BaseField>>,
+        aux_assertions: Vec<Assertion<E>>,
+        composition_coefficients: &[E],
+        main_state: &[E],
+        aux_state: Option<&[E]>,
+        x: E,
+    ) -> E {
+        let (main_assertions, aux_assertions) = prepare_lazy_assertions(
+            context,
+            main_assertions,
+            aux_assertions,
+            composition_coefficients,
+        );
+
+        let inv_g = context.trace_domain_generator.inv();
+        let (main_composition_coefficients, aux_composition_coefficients) =
+            composition_coefficients.split_at(main_assertions.len());
+
+        let mut result = evaluate_assertions(
+            main_assertions,
+            context,
+            main_composition_coefficients,
+            main_state,
+            inv_g,
+            x,
+        );
+
+        if let Some(aux_state) = aux_state {
+            result += evaluate_assertions(
+                aux_assertions,
+                context,
+                aux_composition_coefficients,
+                aux_state,
+                inv_g,
+                x,
+            );
+        }
+
+        result
+    }
+
+    /// Evaluates boundary constraints described by the provided assertions at the specified
+    /// point `x`, and returns the contribution of each constraint to the random linear
+    /// combination computed by [evaluate_lazily()](BoundaryConstraints::evaluate_lazily).
+    ///
+    /// The contribution of a constraint is its evaluation multiplied by its composition
+    /// coefficient and divided by its divisor; thus, contributions of all constraints add up to
+    /// the value returned from [evaluate_lazily()](BoundaryConstraints::evaluate_lazily).
+    /// Contributions are returned together with their assertions, separately for assertions
+    /// against the main trace segment and against auxiliary trace segments; within each list,
+    /// assertions are sorted in their natural order (i.e., by stride, then by first step, and
+    /// finally by column). Contributions of constraints against auxiliary trace segments are
+    /// computed only if `aux_state` is provided.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as
+    /// [evaluate_lazily()](BoundaryConstraints::evaluate_lazily).
+    #[allow(clippy::type_complexity)]
+    pub fn evaluate_contributions(
+        context: &AirContext<E::BaseField>,
+        main_assertions: Vec<Assertion<E::BaseField>>,
+        aux_assertions: Vec<Assertion<E>>,
+        composition_coefficients: &[E],
+        main_state: &[E],
+        aux_state: Option<&[E]>,
+        x: E,
+    ) -> (Vec<(Assertion<E::BaseField>, E)>, Vec<(Assertion<E>, E)>) {
+        let (main_assertions, aux_assertions) = prepare_lazy_assertions(
+            context,
+            main_assertions,
+            aux_assertions,
+            composition_coefficients,
+        );
+
+        let inv_g = context.trace_domain_generator.inv();
+        let (main_composition_coefficients, aux_composition_coefficients) =
+            composition_coefficients.split_at(main_assertions.len());
+
+        let main_contributions = evaluate_assertion_contributions(
+            main_assertions,
+            context,
+            main_composition_coefficients,
+            main_state,
+            inv_g,
+            x,
+        );
+
+        let aux_contributions = match aux_state {
+            Some(aux_state) => evaluate_assertion_contributions(
+                aux_assertions,
+                context,
+                aux_composition_coefficients,
+                aux_state,
+                inv_g,
+                x,
+            ),
+            None => Vec::new(),
+        };
+
+        (main_contributions, aux_contributions)
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -149,6 +277,10 @@ impl<E: FieldElement> BoundaryConstraints<E> {
 
 /// Translates the provided assertions into boundary constraints, groups the constraints by their
 /// divisor, and sorts the resulting groups by the degree adjustment factor.
+///
+/// Groups are built independently of each other; thus, when `concurrent` feature is enabled,
+/// value polynomials of constraints in different groups are interpolated in multiple threads.
+/// The resulting groups are the same as when they are built in a single thread.
 fn group_constraints<F, E>(
     assertions: Vec<Assertion<F>>,
     context: &AirContext<F::BaseField>,
@@ -161,23 +293,174 @@ where
     E: FieldElement<BaseField = F::BaseField> + ExtensionOf<F>,
 {
     // iterate over all assertions, which are sorted first by stride and then by first_step
-    // in ascending order
+    // in ascending order, and split them by their divisor; also make sure inverse twiddles are
+    // available for all domains over which value polynomials will be interpolated
+    let mut assertion_groups = BTreeMap::new();
+    for (assertion, &cc) in assertions.into_iter().zip(composition_coefficients) {
+        let num_values = assertion.values().len();
+        if num_values > 1 {
+            twiddle_map
+                .entry(num_values)
+                .or_insert_with(|| fft::get_inv_twiddles(num_values));
+        }
+
+        let key = (assertion.stride(), assertion.first_step());
+        assertion_groups.entry(key).or_insert_with(Vec::new).push((assertion, cc));
+    }
+    let assertion_groups = assertion_groups.into_values().collect::<Vec<_>>();
+
+    #[cfg(not(feature = "concurrent"))]
+    let assertion_groups = assertion_groups.into_iter();
+
+    #[cfg(feature = "concurrent")]
+    let assertion_groups = assertion_groups.into_par_iter();
+
+    // build constraint groups; the order of the groups is preserved
+    let twiddle_map = &*twiddle_map;
+    assertion_groups
+        .map(|assertions| {
+            let divisor = ConstraintDivisor::from_assertion(&assertions[0].0, context.trace_len());
+            let mut group = BoundaryConstraintGroup::new(divisor);
+            for (assertion, cc) in assertions {
+                group.add(assertion, inv_g, twiddle_map, cc);
+            }
+            group
+        })
+        .collect()
+}
+
+/// Makes sure the provided assertions are consistent with the specified context, and validates
+/// and sorts them in the same way as when building the constraints so that the same composition
+/// coefficients get assigned to them.
+fn prepare_lazy_assertions<E: FieldElement>(
+    context: &AirContext<E::BaseField>,
+    main_assertions: Vec<Assertion<E::BaseField>>,
+    aux_assertions: Vec<Assertion<E>>,
+    composition_coefficients: &[E],
+) -> (Vec<Assertion<E::BaseField>>, Vec<Assertion<E>>) {
+    // make sure the provided assertions are consistent with the specified context
+    assert_eq!(
+        main_assertions.len(),
+        context.num_main_assertions,
+        "expected {} assertions against main trace segment, but received {}",
+        context.num_main_assertions,
+        main_assertions.len(),
+    );
+
+    assert_eq!(
+        aux_assertions.len(),
+        context.num_aux_assertions,
+        "expected {} assertions against auxiliary trace segments, but received {}",
+        context.num_aux_assertions,
+        aux_assertions.len(),
+    );
+
+    assert_eq!(
+        context.num_assertions(),
+        composition_coefficients.len(),
+        "number of assertions must match the number of composition coefficient tuples"
+    );
+
+    let trace_length = context.trace_info.length();
+    let main_trace_width = context.trace_info.layout().main_trace_width();
+    let aux_trace_width = context.trace_info.layout().aux_trace_width();
+
+    // validate and sort the assertions in the same way as when building the constraints so
+    // that the same composition coefficients get assigned to them
+    let main_assertions = prepare_assertions(main_assertions, main_trace_width, trace_length);
+    let aux_assertions = prepare_assertions(aux_assertions, aux_trace_width, trace_length);
+
+    (main_assertions, aux_assertions)
+}
+
+/// Evaluates boundary constraints described by the provided assertions at point `x` without
+/// building the constraints, and returns a random linear combination of the evaluations.
+///
+/// Constraint evaluations are accumulated per divisor, and each accumulated numerator is divided
+/// by its divisor once all assertions have been processed.
+fn evaluate_assertions<F, E>(
+    assertions: Vec<Assertion<F>>,
+    context: &AirContext<F::BaseField>,
+    composition_coefficients: &[E],
+    state: &[E],
+    inv_g: F::BaseField,
+    x: E,
+) -> E
+where
+    F: FieldElement,
+    E: FieldElement<BaseField = F::BaseField> + ExtensionOf<F>,
+{
     let mut groups = BTreeMap::new();
     for (assertion, &cc) in assertions.into_iter().zip(composition_coefficients) {
         let key = (assertion.stride(), assertion.first_step());
-        let group = groups.entry(key).or_insert_with(|| {
-            BoundaryConstraintGroup::new(ConstraintDivisor::from_assertion(
-                &assertion,
-                context.trace_len(),
-            ))
+        let (_, numerator) = groups.entry(key).or_insert_with(|| {
+            let divisor = ConstraintDivisor::from_assertion(&assertion, context.trace_len());
+            (divisor, E::ZERO)
         });
 
-        // add a new assertion constraint to the current group (last group in the list)
-        group.add(assertion, inv_g, twiddle_map, cc);
+        let assertion_value = evaluate_assertion_value(&assertion, inv_g, x);
+        *numerator += (assertion.get_trace_value(state) - assertion_value) * cc;
+    }
+
+    groups.into_values().fold(E::ZERO, |result, (divisor, numerator)| {
+        result + numerator / divisor.evaluate_at(x)
+    })
+}
+
+/// Evaluates boundary constraints described by the provided assertions at point `x` without
+/// building the constraints, and returns the contribution of each constraint to the random linear
+/// combination of the evaluations together with the corresponding assertion.
+fn evaluate_assertion_contributions<F, E>(
+    assertions: Vec<Assertion<F>>,
+    context: &AirContext<F::BaseField>,
+    composition_coefficients: &[E],
+    state: &[E],
+    inv_g: F::BaseField,
+    x: E,
+) -> Vec<(Assertion<F>, E)>
+where
+    F: FieldElement,
+    E: FieldElement<BaseField = F::BaseField> + ExtensionOf<F>,
+{
+    assertions
+        .into_iter()
+        .zip(composition_coefficients)
+        .map(|(assertion, &cc)| {
+            let divisor = ConstraintDivisor::from_assertion(&assertion, context.trace_len());
+            let assertion_value = evaluate_assertion_value(&assertion, inv_g, x);
+            let numerator = (assertion.get_trace_value(state) - assertion_value) * cc;
+            let contribution = numerator / divisor.evaluate_at(x);
+            (assertion, contribution)
+        })
+        .collect()
+}
+
+/// Evaluates the value polynomial of the provided assertion at point `x`.
+///
+/// For multi-value assertions, the value polynomial is the polynomial which interpolates asserted
+/// values over a subgroup of size $n$ (the number of values), and shifted by the first asserted
+/// step. Instead of interpolating the polynomial, it is evaluated directly at
+/// $z = x \cdot g^{-s}$ (where $s$ is the first asserted step) using Lagrange evaluation over the
+/// subgroup (see [polynom::eval_lagrange_subgroup()]).
+fn evaluate_assertion_value<F, E>(assertion: &Assertion<F>, inv_g: F::BaseField, x: E) -> E
+where
+    F: FieldElement,
+    E: FieldElement<BaseField = F::BaseField> + ExtensionOf<F>,
+{
+    let values = assertion.values();
+    if values.len() == 1 {
+        return E::from(values[0]);
     }
 
-    //return a vector of groups
-    groups.into_iter().map(|e| e.1).collect::<Vec<_>>()
+    let n = values.len();
+    let z = x.mul_base(inv_g.exp((assertion.first_step() as u64).into()));
+    assert_ne!(
+        z.exp((n as u64).into()),
+        E::ONE,
+        "evaluation point must be outside of trace domain"
+    );
+
+    polynom::eval_lagrange_subgroup(values, z)
 }
 
 /// Makes sure the assertions are valid in the context of this computation and don't overlap with
@@ -4,11 +4,14 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{
-    super::tests::{build_prng, build_sequence_poly},
-    Assertion, BoundaryConstraint,
+    super::tests::{build_context, build_prng, build_sequence_poly},
+    Assertion, BoundaryConstraint, BoundaryConstraints, ConstraintDivisor,
 };
 use crypto::{hashers::Blake3_256, DefaultRandomCoin, RandomCoin};
-use math::{fields::f64::BaseElement, polynom, FieldElement, StarkField};
+use math::{
+    fields::{f64::BaseElement, QuadExtension},
+    polynom, FieldElement, StarkField,
+};
 use rand_utils::{rand_value, rand_vector, shuffle};
 use utils::collections::{BTreeMap, Vec};
 
@@ -173,6 +176,41 @@ fn boundary_constraint_from_sequence_assertion() {
     );
 }
 
+#[test]
+fn boundary_constraint_from_linear_combination_assertion() {
+    let mut test_prng = build_prng();
+    let (inv_g, mut twiddle_map, mut prng) = build_constraint_params(16);
+
+    // constraint should be built correctly for 2 * col_1 + col_3 at step 8
+    let value = rand_value::<BaseElement>();
+    let two = BaseElement::new(2);
+    let assertion =
+        Assertion::linear_combination(vec![two, BaseElement::ONE], vec![1, 3], 8, value);
+    let constraint = BoundaryConstraint::<BaseElement, BaseElement>::new(
+        assertion,
+        inv_g,
+        &mut twiddle_map,
+        prng.draw().unwrap(),
+    );
+    assert_eq!(1, constraint.column());
+    assert_eq!(&[(1, two), (3, BaseElement::ONE)], constraint.combination());
+    assert_eq!(vec![value], constraint.poly());
+    assert_eq!((0, BaseElement::ONE), constraint.poly_offset());
+    assert_eq!(&test_prng.draw::<BaseElement>().unwrap(), constraint.cc());
+
+    // the constraint evaluates to zero for a row which satisfies the assertion
+    let mut row = rand_vector::<BaseElement>(4);
+    row[3] = value - two * row[1];
+    let trace_value = constraint.get_trace_value(&row);
+    assert_eq!(value, trace_value);
+    assert_eq!(BaseElement::ZERO, constraint.evaluate_at(rand_value(), trace_value));
+
+    // but not for a row which violates it
+    row[1] += BaseElement::ONE;
+    let trace_value = constraint.get_trace_value(&row);
+    assert_eq!(two, constraint.evaluate_at(rand_value(), trace_value));
+}
+
 // PREPARE ASSERTIONS
 // ================================================================================================
 
@@ -248,6 +286,151 @@ fn prepare_assertions_with_invalid_trace_width() {
     let _ = super::prepare_assertions(assertions, 2, 16);
 }
 
+// LAZY EVALUATION TESTS
+// ================================================================================================
+
+#[test]
+fn evaluate_boundary_constraints_lazily() {
+    type E = QuadExtension<BaseElement>;
+
+    let trace_length = 1 << 14;
+    let trace_width = 4;
+
+    let assertions = vec![
+        Assertion::single(0, 0, rand_value::<BaseElement>()),
+        Assertion::single(1, trace_length - 1, rand_value::<BaseElement>()),
+        Assertion::periodic(2, 1, 8, rand_value::<BaseElement>()),
+        Assertion::periodic(3, 3, 16, rand_value::<BaseElement>()),
+        // a large sequence assertion which does not start at step 0
+        Assertion::sequence(0, 1, 4, rand_vector::<BaseElement>(trace_length / 4)),
+        Assertion::sequence(1, 0, 2, rand_vector::<BaseElement>(trace_length / 2)),
+        // a linear combination of several columns
+        Assertion::linear_combination(rand_vector(3), vec![3, 0, 2], 5, rand_value()),
+    ];
+    let context = build_context::<BaseElement>(trace_length, trace_width, assertions.len());
+    let coefficients = rand_vector::<E>(assertions.len());
+    let state = rand_vector::<E>(trace_width);
+    let x = rand_value::<E>();
+
+    // evaluate constraints eagerly
+    let constraints = BoundaryConstraints::new(&context, assertions.clone(), vec![], &coefficients);
+    let expected = constraints
+        .main_constraints()
+        .iter()
+        .fold(E::ZERO, |result, group| result + group.evaluate_at(&state, x));
+
+    // evaluate constraints lazily; shuffling assertions must not affect the result
+    let mut shuffled = assertions;
+    shuffle(&mut shuffled);
+    let actual = BoundaryConstraints::evaluate_lazily(
+        &context,
+        shuffled,
+        vec![],
+        &coefficients,
+        &state,
+        None,
+        x,
+    );
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn evaluate_boundary_constraint_contributions() {
+    type E = QuadExtension<BaseElement>;
+
+    let trace_length = 1 << 8;
+    let trace_width = 4;
+
+    let assertions = vec![
+        Assertion::single(1, trace_length - 1, rand_value::<BaseElement>()),
+        Assertion::single(0, 0, rand_value::<BaseElement>()),
+        Assertion::periodic(2, 1, 8, rand_value::<BaseElement>()),
+        Assertion::sequence(3, 1, 4, rand_vector::<BaseElement>(trace_length / 4)),
+    ];
+    let context = build_context::<BaseElement>(trace_length, trace_width, assertions.len());
+    let coefficients = rand_vector::<E>(assertions.len());
+    let state = rand_vector::<E>(trace_width);
+    let x = rand_value::<E>();
+
+    let expected = BoundaryConstraints::evaluate_lazily(
+        &context,
+        assertions.clone(),
+        vec![],
+        &coefficients,
+        &state,
+        None,
+        x,
+    );
+    let (main_contributions, aux_contributions) = BoundaryConstraints::evaluate_contributions(
+        &context,
+        assertions.clone(),
+        vec![],
+        &coefficients,
+        &state,
+        None,
+        x,
+    );
+    assert!(aux_contributions.is_empty());
+
+    // contributions are listed in the natural order of assertions and add up to the result of
+    // lazy evaluation
+    let sorted_assertions = super::prepare_assertions(assertions, trace_width, trace_length);
+    assert_eq!(
+        sorted_assertions,
+        main_contributions
+            .iter()
+            .map(|(assertion, _)| assertion.clone())
+            .collect::<Vec<_>>()
+    );
+    let actual = main_contributions.iter().fold(E::ZERO, |acc, &(_, value)| acc + value);
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn build_boundary_constraints_from_many_assertions() {
+    type E = QuadExtension<BaseElement>;
+
+    let trace_length = 1 << 10;
+    let trace_width = 64;
+
+    // build assertions which fall into many groups with different divisors; assertions against
+    // the same column are placed on steps which do not overlap
+    let mut assertions = Vec::new();
+    for column in 0..trace_width {
+        let stride = 1 << (column % 4 + 1);
+        let values = rand_vector::<BaseElement>(trace_length / stride);
+        assertions.push(Assertion::sequence(column, column % stride, stride, values));
+        let step = (column + 1) % 32;
+        assertions.push(Assertion::periodic(column, step, 32, rand_value::<BaseElement>()));
+        let step = column + 1 + stride;
+        assertions.push(Assertion::single(column, step, rand_value::<BaseElement>()));
+    }
+    let context = build_context::<BaseElement>(trace_length, trace_width, assertions.len());
+    let coefficients = rand_vector::<E>(assertions.len());
+
+    let constraints = BoundaryConstraints::new(&context, assertions.clone(), vec![], &coefficients);
+
+    // build the same constraint groups one assertion at a time
+    let inv_g = context.trace_domain_generator.inv();
+    let mut twiddle_map = BTreeMap::new();
+    let mut expected = BTreeMap::new();
+    let assertions = super::prepare_assertions(assertions, trace_width, trace_length);
+    for (assertion, &cc) in assertions.into_iter().zip(coefficients.iter()) {
+        let key = (assertion.stride(), assertion.first_step());
+        let divisor = ConstraintDivisor::from_assertion(&assertion, trace_length);
+        let constraint =
+            BoundaryConstraint::<BaseElement, E>::new(assertion, inv_g, &mut twiddle_map, cc);
+        expected.entry(key).or_insert_with(|| (divisor, Vec::new())).1.push(constraint);
+    }
+
+    let groups = constraints.main_constraints();
+    assert_eq!(expected.len(), groups.len());
+    for (group, (divisor, expected_constraints)) in groups.iter().zip(expected.into_values()) {
+        assert_eq!(&divisor, group.divisor());
+        assert_eq!(expected_constraints, group.constraints());
+    }
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
@@ -16,7 +16,7 @@ use utils::collections::Vec;
 /// function for each auxiliary trace segment. In the interactive version of the protocol, the
 /// verifier draws these elements uniformly at random from the extension field of the protocol
 /// after the prover commits to a previous trace segment.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AuxTraceRandElements<E: FieldElement>(Vec<Vec<E>>);
 
 impl<E: FieldElement> AuxTraceRandElements<E> {
@@ -25,6 +25,11 @@ impl<E: FieldElement> AuxTraceRandElements<E> {
         Self(Vec::new())
     }
 
+    /// Returns the number of auxiliary segments for which random elements have been added.
+    pub fn num_segments(&self) -> usize {
+        self.0.len()
+    }
+
     /// Returns a list of random elements for an auxiliary segment with the specified index.
     pub fn get_segment_elements(&self, aux_segment_idx: usize) -> &[E] {
         &self.0[aux_segment_idx]
@@ -60,12 +65,16 @@ impl<E: FieldElement> Default for AuxTraceRandElements<E> {
 /// * $\alpha_i$ is the coefficient for the $i$th constraint.
 /// * $C_i(x)$ is an evaluation of the $i$th constraint at $x$.
 ///
-/// The coefficients are separated into two lists: one for transition constraints and another one
-/// for boundary constraints. This separation is done for convenience only.
-#[derive(Debug, Clone)]
+/// The coefficients are separated into four lists: one for transition constraints, one for
+/// boundary constraints, one for wraparound constraints (which is empty unless the AIR defines
+/// such constraints), and one for range check constraints (which is empty unless the AIR defines
+/// range checks). This separation is done for convenience only.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ConstraintCompositionCoefficients<E: FieldElement> {
     pub transition: Vec<E>,
     pub boundary: Vec<E>,
+    pub wraparound: Vec<E>,
+    pub range_check: Vec<E>,
 }
 
 // DEEP COMPOSITION COEFFICIENTS
@@ -84,13 +93,24 @@ pub struct ConstraintCompositionCoefficients<E: FieldElement> {
 ///     \frac{T_i(x) - T_i(z \cdot g)}{x - z \cdot g})
 /// )} + \sum_{j=0}^m{\beta_j \cdot \frac{H_j(x) - H_j(z)}{x - z}}
 /// $$
-/// where:
+/// When the AIR opens trace polynomials at more than two consecutive rows (see
+/// [AirContext::set_frame_width()](crate::AirContext::set_frame_width)), the inner sum runs over
+/// all shifts of the frame, and the same coefficient $\alpha_i$ is used for every shift:
+/// $$
+/// Y(x) = \sum_{i=0}^k{(
+///     \alpha_i \cdot \sum_{s=0}^{w-1}{\frac{T_i(x) - T_i(z \cdot g^s)}{x - z \cdot g^s}}
+/// )} + \sum_{j=0}^m{\beta_j \cdot \frac{H_j(x) - H_j(z)}{x - z}}
+/// $$
+/// where $w$ is the width of the frame.
+///
+/// In the above:
 /// * $z$ is an out-of-domain point drawn randomly from the entire field. In the interactive
 ///   version of the protocol, $z$ is provided by the verifier.
 /// * $g$ is the generator of the trace domain. This is the $n$th root of unity where
 ///   $n$ is the length of the execution trace.
 /// * $T_i(x)$ is an evaluation of the $i$th trace polynomial at $x$, and $k$ is the total
-///   number of trace polynomials (which is equal to the width of the execution trace).
+///   number of trace polynomials (which is equal to the width of the execution trace, plus the
+///   width of the auxiliary trace segments when auxiliary conjugates are enabled).
 /// * $H_i(x)$ is an evaluation of the $j$th constraint composition column polynomial at $x$,
 ///   and $m$ is the total number of column polynomials.
 /// * $\alpha_i$ is a composition coefficient for the $i$th trace polynomial.
@@ -102,12 +122,14 @@ pub struct ConstraintCompositionCoefficients<E: FieldElement> {
 /// 1. The evaluation proofs for each trace polynomial at $z$ and $g \cdot z$ can be batched using
 /// the non-normalized Lagrange kernel over the set $\{z, g \cdot z\}$. This, however, requires
 /// that the FRI protocol is run with rate $\rho^{+} := \frac{\kappa + 2}{\nu}$ where $\kappa$ and
-/// $\nu$ are the length of the execution trace and the LDE domain size, respectively.
+/// $\nu$ are the length of the execution trace and the LDE domain size, respectively. For a frame
+/// of width $w$, the set is $\{z, g \cdot z, \dots, g^{w-1} \cdot z\}$ and the rate is
+/// $\frac{\kappa + w}{\nu}$.
 /// 2. The resulting $Y(x)$ do not need to be degree adjusted but the soundness error of the
 /// protocol needs to be updated. For most combinations of batching parameters, this leads to a
 /// negligible increase in soundness error. The formula for the updated error can be found in
 /// Theorem 8 of https://eprint.iacr.org/2022/1216.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DeepCompositionCoefficients<E: FieldElement> {
     /// Trace polynomial composition coefficients $\alpha_i$.
     pub trace: Vec<E>,
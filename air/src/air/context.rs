@@ -3,10 +3,13 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::{air::TransitionConstraintDegree, ProofOptions, TraceInfo};
+use crate::{
+    air::{RationalConstraint, TransitionConstraintDegree},
+    ProofOptions, TraceInfo,
+};
 use core::cmp;
 use math::StarkField;
-use utils::collections::Vec;
+use utils::{collections::Vec, string::String};
 
 // AIR CONTEXT
 // ================================================================================================
@@ -17,12 +20,16 @@ pub struct AirContext<B: StarkField> {
     pub(super) trace_info: TraceInfo,
     pub(super) main_transition_constraint_degrees: Vec<TransitionConstraintDegree>,
     pub(super) aux_transition_constraint_degrees: Vec<TransitionConstraintDegree>,
+    pub(super) wraparound_constraint_degrees: Vec<TransitionConstraintDegree>,
+    pub(super) rational_constraints: Vec<RationalConstraint>,
     pub(super) num_main_assertions: usize,
     pub(super) num_aux_assertions: usize,
     pub(super) ce_blowup_factor: usize,
     pub(super) trace_domain_generator: B,
     pub(super) lde_domain_generator: B,
     pub(super) num_transition_exemptions: usize,
+    pub(super) aux_conjugates: bool,
+    pub(super) frame_width: usize,
 }
 
 impl<B: StarkField> AirContext<B> {
@@ -43,6 +50,7 @@ impl<B: StarkField> AirContext<B> {
     /// * Blowup factor specified by the provided `options` is too small to accommodate degrees
     ///   of the specified transition constraints.
     /// * `trace_info` describes a multi-segment execution trace.
+    /// * log2 of the LDE domain size is greater than two-adicity of the base field.
     pub fn new(
         trace_info: TraceInfo,
         transition_constraint_degrees: Vec<TransitionConstraintDegree>,
@@ -85,6 +93,7 @@ impl<B: StarkField> AirContext<B> {
     ///   - `num_aux_assertions` is greater than zero.
     /// * Blowup factor specified by the provided `options` is too small to accommodate degrees
     ///   of the specified transition constraints.
+    /// * log2 of the LDE domain size is greater than two-adicity of the base field.
     pub fn new_multi_segment(
         trace_info: TraceInfo,
         main_transition_constraint_degrees: Vec<TransitionConstraintDegree>,
@@ -134,27 +143,54 @@ impl<B: StarkField> AirContext<B> {
             }
         }
 
+        // if the base field cannot accommodate the blowup factor needed to evaluate transition
+        // constraints, high-degree constraints must be split using intermediate trace columns;
+        // in this case, the needed columns are described in the panic messages below
+        let trace_length = trace_info.length();
+        let max_blowup_factor = get_max_blowup_factor::<B>(trace_length);
+        let splitting_hint = if ce_blowup_factor > max_blowup_factor {
+            describe_splitting(
+                &main_transition_constraint_degrees,
+                &aux_transition_constraint_degrees,
+                trace_length,
+                max_blowup_factor,
+            )
+        } else {
+            String::new()
+        };
+
         assert!(
             options.blowup_factor() >= ce_blowup_factor,
-            "blowup factor too small; expected at least {}, but was {}",
+            "blowup factor too small; expected at least {}, but was {}{}",
             ce_blowup_factor,
-            options.blowup_factor()
+            options.blowup_factor(),
+            splitting_hint
         );
 
-        let trace_length = trace_info.length();
         let lde_domain_size = trace_length * options.blowup_factor();
+        assert!(
+            lde_domain_size.ilog2() <= B::TWO_ADICITY,
+            "LDE domain of size 2^{} cannot be formed in a base field with two-adicity {}{}",
+            lde_domain_size.ilog2(),
+            B::TWO_ADICITY,
+            splitting_hint
+        );
 
         AirContext {
             options,
             trace_info,
             main_transition_constraint_degrees,
             aux_transition_constraint_degrees,
+            wraparound_constraint_degrees: Vec::new(),
+            rational_constraints: Vec::new(),
             num_main_assertions,
             num_aux_assertions,
             ce_blowup_factor,
             trace_domain_generator: B::get_root_of_unity(trace_length.ilog2()),
             lde_domain_generator: B::get_root_of_unity(lde_domain_size.ilog2()),
             num_transition_exemptions: 1,
+            aux_conjugates: false,
+            frame_width: 2,
         }
     }
 
@@ -189,6 +225,19 @@ impl<B: StarkField> AirContext<B> {
         self.trace_info.length() * self.options.blowup_factor()
     }
 
+    /// Returns the size, the generator, and the offset of the low-degree extension domain.
+    ///
+    /// The LDE domain is defined as a coset of the multiplicative subgroup of the base field with
+    /// the returned size, generated by the returned generator and shifted by the returned offset.
+    /// That is, the i-th element of the domain is `offset * generator^i`.
+    pub fn lde_domain_info(&self) -> LdeDomainInfo<B> {
+        LdeDomainInfo {
+            size: self.lde_domain_size(),
+            generator: self.lde_domain_generator,
+            offset: self.options.domain_offset(),
+        }
+    }
+
     /// Returns the number of transition constraints for a computation.
     ///
     /// The number of transition constraints is defined by the total number of transition
@@ -209,6 +258,30 @@ impl<B: StarkField> AirContext<B> {
         self.aux_transition_constraint_degrees.len()
     }
 
+    /// Returns the number of wraparound constraints for a computation.
+    ///
+    /// Wraparound constraints relate the last row of the main trace segment to its first row;
+    /// they are defined via
+    /// [set_wraparound_constraint_degrees()](Self::set_wraparound_constraint_degrees), and there
+    /// are none by default.
+    pub fn num_wraparound_constraints(&self) -> usize {
+        self.wraparound_constraint_degrees.len()
+    }
+
+    /// Returns degree descriptors of wraparound constraints for a computation.
+    pub fn wraparound_constraint_degrees(&self) -> &[TransitionConstraintDegree] {
+        &self.wraparound_constraint_degrees
+    }
+
+    /// Returns rational constraints of a computation.
+    ///
+    /// Rational constraints are defined via
+    /// [set_rational_constraints()](Self::set_rational_constraints), and there are none by
+    /// default.
+    pub fn rational_constraints(&self) -> &[RationalConstraint] {
+        &self.rational_constraints
+    }
+
     /// Returns the total number of assertions defined for a computation.
     ///
     /// The number of assertions consists of the assertions placed against the main segment of an
@@ -227,6 +300,36 @@ impl<B: StarkField> AirContext<B> {
         self.num_transition_exemptions
     }
 
+    /// Returns true if auxiliary trace frames passed to
+    /// [Air::evaluate_aux_transition()](crate::Air::evaluate_aux_transition) provide conjugated
+    /// views of their rows.
+    pub fn has_aux_conjugates(&self) -> bool {
+        self.aux_conjugates
+    }
+
+    /// Returns the number of consecutive trace rows at which trace polynomials are opened during
+    /// DEEP composition.
+    ///
+    /// Trace polynomials are opened at points $z \cdot g^k$ for all $k$ in `0..frame_width`, where
+    /// $z$ is the out-of-domain point and $g$ is the generator of the trace domain. This is 2 by
+    /// default (i.e., the current and the next rows).
+    pub fn frame_width(&self) -> usize {
+        self.frame_width
+    }
+
+    /// Returns the number of trace polynomials opened at the out-of-domain point.
+    ///
+    /// This is the width of the execution trace plus, when auxiliary conjugates are enabled, the
+    /// width of the auxiliary trace segments (one conjugate polynomial per auxiliary column).
+    pub fn num_ood_trace_polys(&self) -> usize {
+        let num_aux_conjugates = if self.aux_conjugates {
+            self.trace_info.layout().aux_trace_width()
+        } else {
+            0
+        };
+        self.trace_info.width() + num_aux_conjugates
+    }
+
     /// Returns the number of columns needed to store the constraint composition polynomial.
     ///
     /// This is the maximum of:
@@ -316,4 +419,202 @@ impl<B: StarkField> AirContext<B> {
         self.num_transition_exemptions = n;
         self
     }
+
+    /// Sets degrees of wraparound constraints for this context.
+    ///
+    /// Wraparound constraints are evaluated by
+    /// [Air::evaluate_wraparound()](crate::Air::evaluate_wraparound) against a frame in which
+    /// the current row is the last row of the main trace segment and the next row is the first
+    /// row; evaluations are expected to be in the order defined by the `degrees` list. This makes
+    /// it possible to enforce relations such as `last == first` for cyclic computations.
+    ///
+    /// Since a wraparound constraint holds on a single step, the degree of its quotient is close
+    /// to the degree of the constraint itself (rather than one trace length lower as is the case
+    /// for transition constraints).
+    ///
+    /// # Panics
+    /// Panics if the quotient of any of the constraints does not fit into the constraint
+    /// composition polynomial defined by the transition constraints of this context.
+    pub fn set_wraparound_constraint_degrees(
+        mut self,
+        degrees: Vec<TransitionConstraintDegree>,
+    ) -> Self {
+        let trace_length = self.trace_len();
+        let max_composition_degree = self.num_constraint_composition_columns() * trace_length - 1;
+        for (i, degree) in degrees.iter().enumerate() {
+            // the divisor of wraparound constraints has degree 1
+            let quotient_degree = degree.get_evaluation_degree(trace_length) - 1;
+            assert!(
+                quotient_degree <= max_composition_degree,
+                "degree of wraparound constraint {i} quotient cannot exceed \
+                {max_composition_degree}, but was {quotient_degree}"
+            );
+        }
+        self.wraparound_constraint_degrees = degrees;
+        self
+    }
+
+    /// Sets rational constraints for this context.
+    ///
+    /// Each rational constraint is described by a tuple `(numerator, denominator)` of column
+    /// indexes in the main trace segment, and defines a column holding the quotient of the values
+    /// in these columns (see [RationalConstraint]). For each rational constraint, the prover
+    /// appends two columns to the main trace segment: a column holding the inverse of the
+    /// denominator and a column holding the quotient. These columns are placed right after the
+    /// columns of the execution trace in the order in which rational constraints are specified,
+    /// and are present in evaluation frames passed to
+    /// [Air::evaluate_transition()](crate::Air::evaluate_transition); their indexes can be
+    /// retrieved via [rational_constraints()](Self::rational_constraints).
+    ///
+    /// Two transition constraints of degree 2 are added for each rational constraint after the
+    /// transition constraints of the main trace segment defined at construction time. These
+    /// constraints are evaluated by the framework, and thus, the constraint evaluations computed by
+    /// [Air::evaluate_transition()](crate::Air::evaluate_transition) should not include them.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * Rational constraints have already been set for this context.
+    /// * A numerator or a denominator column is outside of the main trace segment.
+    /// * Blowup factor of this context is too small to accommodate degree 2 constraints.
+    pub fn set_rational_constraints(mut self, quotients: Vec<(usize, usize)>) -> Self {
+        assert!(
+            self.rational_constraints.is_empty(),
+            "rational constraints have already been set"
+        );
+        let main_trace_width = self.trace_info.layout().main_trace_width();
+        for (i, &(numerator, denominator)) in quotients.iter().enumerate() {
+            for column in [numerator, denominator] {
+                assert!(
+                    column < main_trace_width,
+                    "column {column} of rational constraint {i} is outside of the main trace \
+                    segment of width {main_trace_width}"
+                );
+            }
+            let inverse = main_trace_width + i * RationalConstraint::NUM_COLUMNS;
+            let constraint = RationalConstraint::new(numerator, denominator, inverse, inverse + 1);
+            self.main_transition_constraint_degrees.extend(constraint.degrees());
+            self.rational_constraints.push(constraint);
+        }
+
+        // make sure the blowup factor can accommodate the added constraints
+        for degree in self.main_transition_constraint_degrees.iter() {
+            self.ce_blowup_factor = cmp::max(self.ce_blowup_factor, degree.min_blowup_factor());
+        }
+        assert!(
+            self.options.blowup_factor() >= self.ce_blowup_factor,
+            "blowup factor too small; expected at least {}, but was {}",
+            self.ce_blowup_factor,
+            self.options.blowup_factor()
+        );
+        self
+    }
+
+    /// Sets the number of consecutive trace rows at which trace polynomials are opened during
+    /// DEEP composition.
+    ///
+    /// With the frame width set to $w$, the prover opens all trace polynomials at points
+    /// $z \cdot g^k$ for $k = 0, \dots, w - 1$, and the DEEP composition polynomial (as well as
+    /// its evaluations computed by the verifier) combines the openings at all of these points.
+    /// Transition constraints are still evaluated against the first two rows of the frame.
+    ///
+    /// The frame width is recorded in the context of a proof, and wider frames reduce the
+    /// conjectured and proven security levels reported for the proof (see
+    /// [StarkProof::security_level()](crate::proof::StarkProof::security_level)). A proof is
+    /// rejected by the verifier if its frame width differs from the frame width of the AIR.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * The frame width is smaller than 2.
+    /// * The frame width is greater than 255 or than the trace length.
+    pub fn set_frame_width(mut self, width: usize) -> Self {
+        assert!(width >= 2, "frame width must be at least 2, but was {width}");
+        let max_width = cmp::min(u8::MAX as usize, self.trace_len());
+        assert!(width <= max_width, "frame width cannot exceed {max_width}, but was {width}");
+        self.frame_width = width;
+        self
+    }
+
+    /// Enables conjugated views of auxiliary trace frames for this context.
+    ///
+    /// When enabled, auxiliary constraints may refer to the conjugates of auxiliary trace
+    /// polynomials (i.e., polynomials with [frobenius](math::FieldElement::frobenius) applied to
+    /// their coefficients) via [EvaluationFrame::current_conjugate()](crate::EvaluationFrame::current_conjugate)
+    /// and [EvaluationFrame::next_conjugate()](crate::EvaluationFrame::next_conjugate). The prover then opens
+    /// the conjugate polynomials at the out-of-domain point alongside the trace polynomials.
+    ///
+    /// # Panics
+    /// Panics if the execution trace does not have any auxiliary segments.
+    pub fn with_aux_conjugates(mut self) -> Self {
+        assert!(
+            self.trace_info.is_multi_segment(),
+            "auxiliary conjugates require at least one auxiliary trace segment"
+        );
+        self.aux_conjugates = true;
+        self
+    }
+}
+
+// LDE DOMAIN INFO
+// ================================================================================================
+
+/// Read-only description of the low-degree extension domain of a computation.
+///
+/// This can be used by AIR authors to align custom logic (e.g., periodic columns or custom
+/// divisors) with the domain over which the execution trace is extended.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LdeDomainInfo<B: StarkField> {
+    /// Number of elements in the LDE domain; this is always a power of two.
+    pub size: usize,
+    /// Generator of the multiplicative subgroup underlying the LDE domain; the order of the
+    /// generator is equal to the domain size.
+    pub generator: B,
+    /// Offset by which the LDE domain is shifted in relation to the subgroup.
+    pub offset: B,
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the largest blowup factor for which an LDE domain of a trace with the specified
+/// length can be formed in the base field `B`, or zero if no such blowup factor exists.
+fn get_max_blowup_factor<B: StarkField>(trace_length: usize) -> usize {
+    let trace_length_log2 = trace_length.ilog2();
+    if trace_length_log2 >= B::TWO_ADICITY {
+        return 0;
+    }
+    let max_blowup_log2 = B::TWO_ADICITY - trace_length_log2;
+    1 << cmp::min(max_blowup_log2, ProofOptions::MAX_BLOWUP_FACTOR.ilog2())
+}
+
+/// Returns a description of intermediate trace columns needed to split the transition
+/// constraints with the specified degrees into constraints which can be evaluated with
+/// `max_blowup_factor`.
+fn describe_splitting(
+    main_degrees: &[TransitionConstraintDegree],
+    aux_degrees: &[TransitionConstraintDegree],
+    trace_length: usize,
+    max_blowup_factor: usize,
+) -> String {
+    if max_blowup_factor < ProofOptions::MIN_BLOWUP_FACTOR {
+        return format!(
+            "; the base field does not support any blowup factor for traces of length {trace_length}"
+        );
+    }
+
+    let mut splits = Vec::new();
+    let degrees = main_degrees.iter().map(|d| ("main", d)).enumerate();
+    let aux_degrees = aux_degrees.iter().map(|d| ("auxiliary", d)).enumerate();
+    for (i, (segment, degree)) in degrees.chain(aux_degrees) {
+        let num_columns = degree.num_splitting_columns(max_blowup_factor);
+        if num_columns > 0 {
+            splits.push(format!("{num_columns} for {segment} transition constraint {i}"));
+        }
+    }
+
+    format!(
+        "; the base field supports blowup factor of at most {max_blowup_factor} for traces of \
+        length {trace_length}, and thus, high-degree transition constraints must be split using \
+        intermediate trace columns ({})",
+        splits.join(", ")
+    )
 }
@@ -21,7 +21,8 @@ use utils::collections::Vec;
 /// numerator: `[(a, 1), (b, 2)]`, exemptions: `[3]`.
 ///
 /// A divisor cannot be instantiated directly, and instead must be created either for an
-/// [Assertion] or for a transition constraint.
+/// [Assertion], for a transition constraint, for a wraparound constraint, or for a range check
+/// constraint.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ConstraintDivisor<B: StarkField> {
     pub(super) numerator: Vec<(usize, B)>,
@@ -64,6 +65,38 @@ impl<B: StarkField> ConstraintDivisor<B> {
         Self::new(vec![(trace_length, B::ONE)], exemptions)
     }
 
+    /// Builds a divisor for wraparound constraints.
+    ///
+    /// For wraparound constraints, the divisor polynomial is defined as:
+    ///
+    /// $$
+    /// z(x) = x - g^{n - 1}
+    /// $$
+    ///
+    /// where $n$ is the length of the execution trace and $g$ is the generator of the trace
+    /// domain. Since $g^n = 1$, the next row of the frame at step $n - 1$ is the first row of the
+    /// trace; thus, this divisor specifies that wraparound constraints must hold between the last
+    /// and the first rows of the execution trace.
+    pub fn from_wraparound(trace_length: usize) -> Self {
+        let last = get_trace_domain_value_at::<B>(trace_length, trace_length - 1);
+        Self::new(vec![(1, last)], vec![])
+    }
+
+    /// Builds a divisor for range check constraints.
+    ///
+    /// For range check constraints, the divisor polynomial is defined as:
+    ///
+    /// $$
+    /// z(x) = x^n - 1
+    /// $$
+    ///
+    /// where $n$ is the length of the execution trace. Thus, unlike transition constraints, range
+    /// check constraints must hold on all steps of the execution trace, including the last one
+    /// (where the next row of the frame is the first row of the trace).
+    pub fn from_range_check(trace_length: usize) -> Self {
+        Self::new(vec![(trace_length, B::ONE)], vec![])
+    }
+
     /// Builds a divisor for a boundary constraint described by the assertion.
     ///
     /// For boundary constraints, the divisor polynomial is defined as:
@@ -128,7 +161,7 @@ impl<B: StarkField> ConstraintDivisor<B> {
         // compute the numerator value
         let mut numerator = E::ONE;
         for (degree, constant) in self.numerator.iter() {
-            let v = x.exp((*degree as u32).into());
+            let v = x.exp((*degree as u64).into());
             let v = v - E::from(*constant);
             numerator *= v;
         }
@@ -226,6 +259,26 @@ mod tests {
         assert_eq!(expected, div.evaluate_at(BaseElement::new(4)));
     }
 
+    #[test]
+    fn constraint_divisor_large_trace() {
+        // for a trace of 2^32 steps, the degree of the transition divisor numerator does not fit
+        // into 32 bits
+        let n = 1_usize << 32;
+        let g = BaseElement::get_root_of_unity(n.ilog2());
+        let divisor = ConstraintDivisor::<BaseElement>::from_transition(n, 1);
+        assert_eq!(vec![(n, BaseElement::ONE)], divisor.numerator());
+        assert_eq!(n - 1, divisor.degree());
+
+        // z(x) = (x^n - 1) / (x - g^(n - 1))
+        let x = BaseElement::new(11);
+        let expected =
+            (x.exp((n as u64).into()) - BaseElement::ONE) / (x - g.exp((n as u64 - 1).into()));
+        assert_eq!(expected, divisor.evaluate_at(x));
+
+        // the divisor vanishes on the steps of the trace
+        assert_eq!(BaseElement::ZERO, divisor.evaluate_at(g.exp(((1_u64 << 31) + 7).into())));
+    }
+
     #[test]
     fn constraint_divisor_equivalence() {
         let n = 8_usize;
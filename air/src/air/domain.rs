@@ -0,0 +1,34 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use math::StarkField;
+
+// LDE DOMAIN
+// ================================================================================================
+
+/// Returns the offset by which the low-degree extension domain is shifted in relation to the
+/// multiplicative subgroup of the same size in the base field `B`.
+///
+/// This is the multiplicative generator of the base field (i.e., `B::GENERATOR`). Thus, for a
+/// trace of length $n$ and blowup factor $k$, the LDE domain is the coset
+/// $\{o \cdot g^j : 0 \leq j < n \cdot k\}$, where $o$ is the offset and $g$ is a generator of
+/// the subgroup of size $n \cdot k$.
+pub const fn lde_coset_offset<B: StarkField>() -> B {
+    B::GENERATOR
+}
+
+/// Returns the position in the low-degree extension domain of the element which corresponds to
+/// the specified step of the trace domain.
+///
+/// If $h$ is a generator of the trace domain and $g$ is a generator of the LDE domain, then
+/// $h = g^k$, where $k$ is the blowup factor. Thus, the element $o \cdot h^i$ of the trace domain
+/// shifted by the LDE coset offset $o$ is located at position $i \cdot k$ of the LDE domain.
+///
+/// # Panics
+/// Panics if `blowup_factor` is not a power of two.
+pub fn lde_domain_position(trace_step: usize, blowup_factor: usize) -> usize {
+    assert!(blowup_factor.is_power_of_two(), "blowup factor must be a power of two");
+    trace_step * blowup_factor
+}
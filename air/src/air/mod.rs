@@ -5,23 +5,30 @@
 
 use crate::ProofOptions;
 use crypto::{RandomCoin, RandomCoinError};
-use math::{fft, ExtensibleField, ExtensionOf, FieldElement, StarkField, ToElements};
+use math::{fft, polynom, ExtensibleField, ExtensionOf, FieldElement, StarkField, ToElements};
 use utils::collections::{BTreeMap, Vec};
 
 mod trace_info;
 pub use trace_info::{TraceInfo, TraceLayout};
 
 mod context;
-pub use context::AirContext;
+pub use context::{AirContext, LdeDomainInfo};
+
+mod domain;
+pub use domain::{lde_coset_offset, lde_domain_position};
 
 mod assertions;
-pub use assertions::Assertion;
+pub use assertions::{Assertion, AssertionSummary};
 
 mod boundary;
 pub use boundary::{BoundaryConstraint, BoundaryConstraintGroup, BoundaryConstraints};
 
 mod transition;
-pub use transition::{EvaluationFrame, TransitionConstraintDegree, TransitionConstraints};
+pub use transition::{
+    ConstraintExpr, EvaluationFrame, HashAccumulator, HashFunction, PolynomialConstraint,
+    RationalConstraint, SymbolicConstraints, SymbolicFrame, TransitionConstraintDegree,
+    TransitionConstraintGroup, TransitionConstraints, WraparoundConstraints,
+};
 
 mod coefficients;
 pub use coefficients::{
@@ -31,6 +38,12 @@ pub use coefficients::{
 mod divisor;
 pub use divisor::ConstraintDivisor;
 
+mod periodic;
+pub use periodic::SparsePeriodicColumn;
+
+mod range_check;
+pub use range_check::RangeCheckConstraints;
+
 #[cfg(test)]
 mod tests;
 
@@ -133,6 +146,9 @@ const MIN_CYCLE_LENGTH: usize = 2;
 ///   intervals must be equal to a sequence of provided values. For example: *values in column 0,
 ///   at step 0 must be equal to 1, at step 8 must be equal to 2, at step 16 must be equal to 3
 ///   etc.*
+/// * A linear combination assertion - such assertion specifies that a linear combination of
+///   values in several columns at a single step must be equal to some value. For example:
+///   *two times the value in column 0 plus the value in column 1, at step 0, must be equal to 5*.
 ///
 /// ### Periodic values
 /// Sometimes, it may be useful to define a column in an execution trace which contains a set of
@@ -145,6 +161,9 @@ const MIN_CYCLE_LENGTH: usize = 2;
 /// step of the computation will be supplied to the [Air::evaluate_transition()] method via the
 /// `periodic_values` parameter.
 ///
+/// If most values within a cycle of a periodic column are the same, the column can instead be
+/// described more compactly via [Air::get_sparse_periodic_column_values()] method.
+///
 /// ### Randomized AIR
 /// Randomized AIR is a powerful extension of AIR which enables, among other things, multiset and
 /// permutation checks similar to the ones available in PLONKish systems. These, in turn, allow
@@ -260,6 +279,29 @@ pub trait Air: Send + Sync {
         unimplemented!("evaluation of auxiliary transition constraints has not been implemented");
     }
 
+    /// Evaluates wraparound constraints over the specified evaluation frame.
+    ///
+    /// Wraparound constraints must hold only at the last step of the main trace segment; at this
+    /// step, the current row of the frame is the last row of the trace, and the next row of the
+    /// frame is the first row of the trace. Thus, for example, a constraint `next[0] - current[0]`
+    /// enforces that the first and the last values in column 0 are the same.
+    ///
+    /// The evaluations should be written into the `result` slice in the same order as the order
+    /// of wraparound constraint degree descriptors set via
+    /// [AirContext::set_wraparound_constraint_degrees()]. Values of periodic columns are not
+    /// available to wraparound constraints.
+    ///
+    /// The default implementation of this function panics. It must be overridden for AIRs which
+    /// define wraparound constraints.
+    #[allow(unused_variables)]
+    fn evaluate_wraparound<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        result: &mut [E],
+    ) {
+        unimplemented!("evaluation of wraparound constraints has not been implemented");
+    }
+
     /// Returns a set of assertions placed against auxiliary trace segments.
     ///
     /// The default implementation of this function returns an empty vector. It should be
@@ -298,17 +340,80 @@ pub trait Air: Send + Sync {
         Vec::new()
     }
 
+    /// Returns sparse descriptions of periodic columns used in the computation.
+    ///
+    /// This is an alternative to [get_periodic_column_values()](Air::get_periodic_column_values)
+    /// for periodic columns in which most values within a cycle are the same: such columns are
+    /// described by a default value and a list of overrides, and are expanded into full cycles
+    /// only when periodic column polynomials are interpolated. Values of sparse periodic columns
+    /// are passed in to the [evaluate_transition()](Air::evaluate_transition) method after the
+    /// values of the columns returned from
+    /// [get_periodic_column_values()](Air::get_periodic_column_values).
+    ///
+    /// The default implementation of this method returns an empty vector. Cycle length of each
+    /// sparse periodic column must be a power of two.
+    fn get_sparse_periodic_column_values(&self) -> Vec<SparsePeriodicColumn<Self::BaseField>> {
+        Vec::new()
+    }
+
+    /// Returns a list of range checks placed against columns of the main trace segment.
+    ///
+    /// Each range check is a tuple `(column, num_bits)` which asserts that all values in the
+    /// specified column of the main trace segment are smaller than `2^num_bits`; `2^num_bits` must
+    /// not exceed the trace length.
+    ///
+    /// Range checks are enforced by the framework via a lookup into a table of all values in the
+    /// range (see [RangeCheckConstraints]). To support this, the prover appends one column per
+    /// range check to the main trace segment, and two columns per range check to the first
+    /// auxiliary trace segment (creating this segment if the computation does not define it);
+    /// one additional random element is also drawn for the first auxiliary segment. These
+    /// columns are appended after the columns of the execution trace (and after the columns of
+    /// rational constraints, if any; see [AirContext::set_rational_constraints()]), and are also
+    /// present in
+    /// evaluation frames passed to [evaluate_transition()](Air::evaluate_transition) and
+    /// [evaluate_aux_transition()](Air::evaluate_aux_transition) methods.
+    ///
+    /// The default implementation of this method returns an empty vector.
+    fn get_range_checks(&self) -> Vec<(usize, u32)> {
+        Vec::new()
+    }
+
+    /// Returns degree adjustments for transition constraints of this computation.
+    ///
+    /// When building the constraint composition polynomial, the quotient of each transition
+    /// constraint by the transition divisor is multiplied by $x^a$, where $a$ is the degree
+    /// adjustment of the constraint. The returned vector must contain one adjustment per
+    /// transition constraint, listing the constraints of the main trace segment first followed by
+    /// the constraints of auxiliary trace segments, and the adjustment of any constraint must not
+    /// exceed the adjustment required to bring its group to the degree of the composition
+    /// polynomial (see [TransitionConstraintGroup::degree_adjustment()]).
+    ///
+    /// Since both the prover and the verifier apply adjustments returned from this method,
+    /// alternative adjustment strategies (e.g., shifting each group of constraints by a different
+    /// amount) can be used without changing the protocol otherwise.
+    ///
+    /// The default implementation of this method returns an empty vector, in which case no
+    /// degree adjustment is applied to any of the constraints.
+    fn get_transition_degree_adjustments(&self) -> Vec<usize> {
+        Vec::new()
+    }
+
     /// Returns polynomial for all periodic columns.
     ///
     /// These polynomials are interpolated from the values returned from the
-    /// [get_periodic_column_values()](Air::get_periodic_column_values) method.
+    /// [get_periodic_column_values()](Air::get_periodic_column_values) method, followed by the
+    /// expanded columns returned from the
+    /// [get_sparse_periodic_column_values()](Air::get_sparse_periodic_column_values) method.
     fn get_periodic_column_polys(&self) -> Vec<Vec<Self::BaseField>> {
         // cache inverse twiddles for each cycle length so that we don't have to re-build them
         // for columns with identical cycle lengths
         let mut twiddle_map = BTreeMap::new();
-        // iterate over all periodic columns and convert column values into polynomials
+        // iterate over all periodic columns and convert column values into polynomials; sparse
+        // columns are expanded into full cycles right before interpolation
+        let sparse_columns = self.get_sparse_periodic_column_values();
         self.get_periodic_column_values()
             .into_iter()
+            .chain(sparse_columns.iter().map(|column| column.to_dense()))
             .map(|mut column| {
                 let cycle_length = column.len();
                 assert!(
@@ -335,17 +440,164 @@ pub trait Air: Send + Sync {
             .collect()
     }
 
+    /// Returns values of all periodic columns at the specified point `x`.
+    ///
+    /// The values are computed by evaluating polynomials returned from the
+    /// [get_periodic_column_polys()](Air::get_periodic_column_polys) method at `x^(n / k)`, where
+    /// `n` is the trace length and `k` is the cycle length of a given periodic column.
+    ///
+    /// This is the same computation the verifier performs to obtain periodic values at the
+    /// out-of-domain point when evaluating constraints, and thus, it can be used by external
+    /// verifiers to reproduce this evaluation.
+    fn get_periodic_column_values_at<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        x: E,
+    ) -> Vec<E> {
+        self.get_periodic_column_polys()
+            .iter()
+            .map(|poly| {
+                let num_cycles = self.trace_length() / poly.len();
+                let x = x.exp_vartime((num_cycles as u64).into());
+                polynom::eval(poly, x)
+            })
+            .collect()
+    }
+
+    /// Returns a description of how columns of the execution trace committed to by the prover are
+    /// arranged into segments.
+    ///
+    /// This is the same as [trace_layout()](Air::trace_layout) unless the computation defines
+    /// rational constraints (see [AirContext::set_rational_constraints()]) or range checks (see
+    /// [get_range_checks()](Air::get_range_checks)), in which case the columns and the random
+    /// element required for these are added to the layout.
+    fn committed_trace_layout(&self) -> TraceLayout {
+        let layout = self.trace_layout();
+        let num_rational_columns =
+            self.context().rational_constraints().len() * RationalConstraint::NUM_COLUMNS;
+        let num_range_checks = self.get_range_checks().len();
+        let main_width = layout.main_trace_width() + num_rational_columns + num_range_checks;
+        if num_range_checks == 0 {
+            if num_rational_columns == 0 {
+                return layout.clone();
+            }
+            return TraceLayout::new(
+                main_width,
+                [layout.get_aux_segment_width(0)],
+                [layout.get_aux_segment_rand_elements(0)],
+            );
+        }
+
+        // range check columns are placed into the first auxiliary segment, and the random element
+        // of the lookup is drawn after all other random elements of this segment
+        let aux_width = layout.aux_trace_width() + 2 * num_range_checks;
+        let aux_rands = if layout.num_aux_segments() == 0 {
+            1
+        } else {
+            layout.get_aux_segment_rand_elements(0) + 1
+        };
+
+        TraceLayout::new(main_width, [aux_width], [aux_rands])
+    }
+
+    /// Returns the number of trace polynomials committed to by the prover which are opened at the
+    /// out-of-domain point.
+    ///
+    /// This includes polynomials of all columns described by
+    /// [committed_trace_layout()](Air::committed_trace_layout), and (when auxiliary conjugates are
+    /// enabled) conjugates of auxiliary trace polynomials.
+    fn num_committed_ood_trace_polys(&self) -> usize {
+        let layout = self.committed_trace_layout();
+        let num_aux_polys = if self.context().has_aux_conjugates() {
+            2 * layout.aux_trace_width()
+        } else {
+            layout.aux_trace_width()
+        };
+        layout.main_trace_width() + num_aux_polys
+    }
+
+    /// Evaluates transition constraints of the main trace segment over the specified evaluation
+    /// frame and returns the results.
+    ///
+    /// The frame may be located at an arbitrary point (e.g., an out-of-domain point); in such
+    /// a case, `periodic_values` must contain values of periodic columns at the same point (see
+    /// [get_periodic_column_values_at()](Air::get_periodic_column_values_at)).
+    ///
+    /// This is a convenience wrapper around [evaluate_main_transition()](Air::evaluate_main_transition)
+    /// which allocates the result vector.
+    fn evaluate_transition_at<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+    ) -> Vec<E> {
+        let mut result = E::zeroed_vector(self.context().num_main_transition_constraints());
+        self.evaluate_main_transition(frame, periodic_values, &mut result);
+        result
+    }
+
+    /// Evaluates all transition constraints of the main trace segment over the specified
+    /// evaluation frame and writes the results into `result`.
+    ///
+    /// This writes evaluations of constraints defined by
+    /// [evaluate_transition()](Air::evaluate_transition) followed by evaluations of rational
+    /// constraints (see [AirContext::set_rational_constraints()]); `result` must contain one
+    /// element per transition constraint of the main trace segment.
+    fn evaluate_main_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let rational_constraints = self.context().rational_constraints();
+        let num_rational = rational_constraints.len() * RationalConstraint::NUM_CONSTRAINTS;
+        let (air_result, rational_result) = result.split_at_mut(result.len() - num_rational);
+        self.evaluate_transition(frame, periodic_values, air_result);
+        for (constraint, result) in rational_constraints
+            .iter()
+            .zip(rational_result.chunks_mut(RationalConstraint::NUM_CONSTRAINTS))
+        {
+            constraint.evaluate(frame, result);
+        }
+    }
+
     /// Groups transition constraints together by their degree.
     ///
-    /// This function also assigns composition coefficients to each constraint. These coefficients
-    /// will be used to compute a random linear combination of transition constraints evaluations
-    /// during constraint merging performed by [TransitionConstraintGroup::merge_evaluations()]
-    /// function.
+    /// This function also assigns composition coefficients and degree adjustments (see
+    /// [get_transition_degree_adjustments()](Air::get_transition_degree_adjustments)) to each
+    /// constraint. These will be used to compute a random linear combination of transition
+    /// constraints evaluations during constraint merging performed by
+    /// [TransitionConstraints::combine_evaluations()] function.
     fn get_transition_constraints<E: FieldElement<BaseField = Self::BaseField>>(
         &self,
         composition_coefficients: &[E],
     ) -> TransitionConstraints<E> {
-        TransitionConstraints::new(self.context(), composition_coefficients)
+        TransitionConstraints::with_degree_adjustments(
+            self.context(),
+            composition_coefficients,
+            &self.get_transition_degree_adjustments(),
+        )
+    }
+
+    /// Builds wraparound constraint metadata and assigns composition coefficients to each
+    /// wraparound constraint.
+    fn get_wraparound_constraints<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        composition_coefficients: &[E],
+    ) -> WraparoundConstraints<E> {
+        WraparoundConstraints::new(self.context(), composition_coefficients)
+    }
+
+    /// Builds range check constraint metadata for the range checks returned from
+    /// [get_range_checks()](Air::get_range_checks) and assigns composition coefficients to each
+    /// range check constraint.
+    fn get_range_check_constraints<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        composition_coefficients: &[E],
+    ) -> RangeCheckConstraints<E> {
+        RangeCheckConstraints::new(
+            self.context(),
+            self.get_range_checks(),
+            composition_coefficients,
+        )
     }
 
     /// Convert assertions returned from [get_assertions()](Air::get_assertions) and
@@ -354,6 +606,11 @@ pub trait Air: Send + Sync {
     /// This function also assigns composition coefficients to each constraint, and groups the
     /// constraints by their divisors. The coefficients will be used to compute random linear
     /// combination of boundary constraints during constraint merging.
+    ///
+    /// These constraints are used by the prover; the verifier evaluates boundary constraints via
+    /// [evaluate_boundary_constraints()](Air::evaluate_boundary_constraints) instead. Thus, if
+    /// this method is overridden, that method must be overridden as well so that the prover and
+    /// the verifier evaluate the same constraints.
     fn get_boundary_constraints<E: FieldElement<BaseField = Self::BaseField>>(
         &self,
         aux_rand_elements: &AuxTraceRandElements<E>,
@@ -367,6 +624,52 @@ pub trait Air: Send + Sync {
         )
     }
 
+    /// Evaluates boundary constraints for assertions returned from
+    /// [get_assertions()](Air::get_assertions) and [get_aux_assertions()](Air::get_aux_assertions)
+    /// methods at the specified point `x`, and returns a random linear combination of the
+    /// evaluations.
+    ///
+    /// The result is the same as the sum of evaluations of constraint groups returned from
+    /// [get_boundary_constraints()](Air::get_boundary_constraints) at `x`, but value polynomials
+    /// of multi-value assertions are not interpolated (see
+    /// [BoundaryConstraints::evaluate_lazily()]). This is used by the verifier, which needs to
+    /// evaluate boundary constraints at a single point only. Constraints against auxiliary trace
+    /// segments are evaluated only if `aux_state` is provided.
+    fn evaluate_boundary_constraints<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        aux_rand_elements: &AuxTraceRandElements<E>,
+        composition_coefficients: &[E],
+        main_state: &[E],
+        aux_state: Option<&[E]>,
+        x: E,
+    ) -> E {
+        BoundaryConstraints::evaluate_lazily(
+            self.context(),
+            self.get_assertions(),
+            self.get_aux_assertions(aux_rand_elements),
+            composition_coefficients,
+            main_state,
+            aux_state,
+            x,
+        )
+    }
+
+    /// Returns human-readable summaries of assertions placed against the main trace segment.
+    ///
+    /// The summaries are built from assertions returned from
+    /// [get_assertions()](Air::get_assertions), and are listed in the same order. Since this AIR
+    /// is instantiated for specific public inputs, this makes it possible to inspect which
+    /// values a proof generated for these inputs is bound to (e.g., by instantiating the AIR with
+    /// trace info and options taken from the proof). Auxiliary assertions are not included, as
+    /// they depend on random elements drawn during proof generation.
+    fn assertion_summary(&self) -> Vec<AssertionSummary<Self::BaseField>> {
+        let trace_length = self.trace_length();
+        self.get_assertions()
+            .iter()
+            .map(|assertion| assertion.summarize(trace_length))
+            .collect()
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -477,7 +780,7 @@ pub trait Air: Send + Sync {
         R: RandomCoin<BaseField = Self::BaseField>,
     {
         let num_elements =
-            self.trace_info().layout().get_aux_segment_rand_elements(aux_segment_idx);
+            self.committed_trace_layout().get_aux_segment_rand_elements(aux_segment_idx);
         let mut result = Vec::with_capacity(num_elements);
         for _ in 0..num_elements {
             result.push(public_coin.draw()?);
@@ -508,9 +811,21 @@ pub trait Air: Send + Sync {
             b_coefficients.push(public_coin.draw()?);
         }
 
+        let mut w_coefficients = Vec::new();
+        for _ in 0..self.context().num_wraparound_constraints() {
+            w_coefficients.push(public_coin.draw()?);
+        }
+
+        let mut r_coefficients = Vec::new();
+        for _ in 0..2 * self.get_range_checks().len() {
+            r_coefficients.push(public_coin.draw()?);
+        }
+
         Ok(ConstraintCompositionCoefficients {
             transition: t_coefficients,
             boundary: b_coefficients,
+            wraparound: w_coefficients,
+            range_check: r_coefficients,
         })
     }
 
@@ -525,7 +840,7 @@ pub trait Air: Send + Sync {
         R: RandomCoin<BaseField = Self::BaseField>,
     {
         let mut t_coefficients = Vec::new();
-        for _ in 0..self.trace_info().width() {
+        for _ in 0..self.num_committed_ood_trace_polys() {
             t_coefficients.push(public_coin.draw()?);
         }
 
@@ -0,0 +1,98 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use math::StarkField;
+use utils::collections::Vec;
+
+// SPARSE PERIODIC COLUMN
+// ================================================================================================
+/// Describes a periodic column in which most values within a cycle are the same.
+///
+/// Instead of storing all values of a cycle, the column stores a default value together with a
+/// list of `(index, value)` overrides for the steps at which the value within a cycle differs
+/// from the default. For example, a column which contains value 1 on every 256th step and 0
+/// otherwise can be described as a column with cycle length 256, default value 0, and a single
+/// override `(0, 1)`.
+///
+/// Sparse periodic columns are specified via
+/// [Air::get_sparse_periodic_column_values()](crate::Air::get_sparse_periodic_column_values), and
+/// are expanded into full cycles only when periodic column polynomials are interpolated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparsePeriodicColumn<B: StarkField> {
+    cycle_length: usize,
+    default_value: B,
+    overrides: Vec<(usize, B)>,
+}
+
+impl<B: StarkField> SparsePeriodicColumn<B> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new sparse periodic column with the specified cycle length, in which the value
+    /// at every step of a cycle is `default_value`, except for the steps listed in `overrides`.
+    ///
+    /// The overrides are sorted by index; thus, they can be provided in any order.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * Any of the override indexes is greater than or equal to `cycle_length`.
+    /// * The same index is overridden more than once.
+    pub fn new(cycle_length: usize, default_value: B, mut overrides: Vec<(usize, B)>) -> Self {
+        overrides.sort_unstable_by_key(|&(index, _)| index);
+        for (i, &(index, _)) in overrides.iter().enumerate() {
+            assert!(
+                index < cycle_length,
+                "override index must be smaller than cycle length {cycle_length}, but was {index}"
+            );
+            assert!(
+                i == 0 || overrides[i - 1].0 != index,
+                "value at index {index} is overridden more than once"
+            );
+        }
+
+        SparsePeriodicColumn {
+            cycle_length,
+            default_value,
+            overrides,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of values in a single cycle of this column.
+    pub fn cycle_length(&self) -> usize {
+        self.cycle_length
+    }
+
+    /// Returns the value of this column at all steps which are not overridden.
+    pub fn default_value(&self) -> B {
+        self.default_value
+    }
+
+    /// Returns `(index, value)` overrides of this column sorted by index.
+    pub fn overrides(&self) -> &[(usize, B)] {
+        &self.overrides
+    }
+
+    /// Returns the value of this column at the specified step of a cycle.
+    pub fn get(&self, index: usize) -> B {
+        match self.overrides.binary_search_by_key(&index, |&(index, _)| index) {
+            Ok(position) => self.overrides[position].1,
+            Err(_) => self.default_value,
+        }
+    }
+
+    // EXPANSION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns all values of a single cycle of this column.
+    pub fn to_dense(&self) -> Vec<B> {
+        let mut result = vec![self.default_value; self.cycle_length];
+        for &(index, value) in self.overrides.iter() {
+            result[index] = value;
+        }
+        result
+    }
+}
@@ -0,0 +1,237 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{AirContext, ConstraintDivisor, EvaluationFrame, RationalConstraint};
+use math::{fft, polynom, ExtensionOf, FieldElement, StarkField};
+use utils::collections::Vec;
+
+// RANGE CHECK CONSTRAINTS
+// ================================================================================================
+/// Metadata for range check constraints of a computation.
+///
+/// A range check asserts that all values in a column of the main trace segment are smaller than
+/// $2^k$ for some number of bits $k$. Range checks are enforced via a lookup of column values into
+/// a table containing all values in $[0, 2^k)$ using a logarithmic derivative argument. For each
+/// range check, the following columns are appended to the execution trace:
+/// - A column of the main trace segment containing multiplicities $m$ of table values; that is,
+///   the value at step $i < 2^k$ is the number of times $i$ appears in the checked column, and all
+///   other values are zeros.
+/// - Two columns of the first auxiliary trace segment: a column $h$ of inverses
+///   $1 / (\alpha - v)$, where $v$ is the checked value, and a column $s$ of running sums.
+///
+/// The random element $\alpha$ is shared by all range checks and is the last random element of
+/// the first auxiliary trace segment. Each range check is enforced by two constraints which must
+/// hold on all steps of the execution trace:
+/// - $h \cdot (\alpha - v) - 1 = 0$.
+/// - $(s' - s - h) \cdot (\alpha - t) + m = 0$, where $t$ is the table value at a given step.
+///
+/// Since the trace domain is cyclic, the second constraint implies that
+/// $\sum{1 / (\alpha - v)} = \sum{m / (\alpha - t)}$ over all steps of the trace, which (with high
+/// probability) holds only if all checked values are in the table.
+///
+/// The table is described by a periodic column with cycle length $2^k$ (and thus, $2^k$ cannot
+/// exceed the length of the trace), and the divisor of range check constraints is $(x^n - 1)$.
+pub struct RangeCheckConstraints<E: FieldElement> {
+    checks: Vec<(usize, u32)>,
+    coefficients: Vec<E>,
+    table_polys: Vec<Vec<E::BaseField>>,
+    main_offset: usize,
+    aux_offset: usize,
+    trace_length: usize,
+    divisor: ConstraintDivisor<E::BaseField>,
+}
+
+impl<E: FieldElement> RangeCheckConstraints<E> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new instance of [RangeCheckConstraints] for the specified range checks of a
+    /// computation described by the specified AIR context.
+    ///
+    /// Each range check is a tuple `(column, num_bits)` where `column` is the index of a column in
+    /// the main trace segment, and `num_bits` is the number of bits to which values in the column
+    /// are restricted.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * The number of composition coefficients is not twice the number of range checks.
+    /// * A checked column is outside of the main trace segment.
+    /// * The number of bits of any range check is zero, or 2^num_bits is greater than the trace
+    ///   length.
+    pub fn new(
+        context: &AirContext<E::BaseField>,
+        range_checks: Vec<(usize, u32)>,
+        composition_coefficients: &[E],
+    ) -> Self {
+        assert_eq!(
+            2 * range_checks.len(),
+            composition_coefficients.len(),
+            "number of range check constraints must match the number of composition coefficients"
+        );
+
+        let layout = context.trace_info.layout();
+        let trace_length = context.trace_len();
+        for &(column, num_bits) in range_checks.iter() {
+            assert!(
+                column < layout.main_trace_width(),
+                "range checked column {} is outside of the main trace segment of width {}",
+                column,
+                layout.main_trace_width()
+            );
+            assert!(
+                num_bits > 0 && num_bits <= trace_length.ilog2(),
+                "number of bits of a range check must be between 1 and {}, but was {}",
+                trace_length.ilog2(),
+                num_bits
+            );
+        }
+
+        let table_polys =
+            range_checks.iter().map(|&(_, num_bits)| build_table_poly(num_bits)).collect();
+
+        Self {
+            checks: range_checks,
+            coefficients: composition_coefficients.to_vec(),
+            table_polys,
+            main_offset: layout.main_trace_width()
+                + context.rational_constraints().len() * RationalConstraint::NUM_COLUMNS,
+            aux_offset: layout.aux_trace_width(),
+            trace_length,
+            divisor: ConstraintDivisor::from_range_check(trace_length),
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of range checks of a computation.
+    pub fn num_checks(&self) -> usize {
+        self.checks.len()
+    }
+
+    /// Returns the number of range check constraints of a computation; this is twice the number
+    /// of range checks.
+    pub fn num_constraints(&self) -> usize {
+        2 * self.checks.len()
+    }
+
+    /// Returns true if a computation does not define any range checks.
+    pub fn is_empty(&self) -> bool {
+        self.checks.is_empty()
+    }
+
+    /// Returns a list of range checks as tuples `(column, num_bits)`.
+    pub fn checks(&self) -> &[(usize, u32)] {
+        &self.checks
+    }
+
+    /// Returns the random coefficients for range check constraints.
+    pub fn coefficients(&self) -> &[E] {
+        &self.coefficients
+    }
+
+    /// Returns the divisor of range check constraints.
+    pub fn divisor(&self) -> &ConstraintDivisor<E::BaseField> {
+        &self.divisor
+    }
+
+    /// Returns polynomials describing lookup tables of range checks in coefficient form, one per
+    /// range check.
+    ///
+    /// These polynomials describe periodic columns, and thus, the table value at point `x` is the
+    /// evaluation of a polynomial at `x^(n / k)`, where `n` is the trace length and `k` is the
+    /// length of the polynomial.
+    pub fn table_polys(&self) -> &[Vec<E::BaseField>] {
+        &self.table_polys
+    }
+
+    /// Returns values of lookup tables of all range checks at the specified point `x`.
+    pub fn get_table_values_at<F>(&self, x: F) -> Vec<F>
+    where
+        F: FieldElement<BaseField = E::BaseField>,
+    {
+        self.table_polys
+            .iter()
+            .map(|poly| {
+                let num_cycles = self.trace_length / poly.len();
+                polynom::eval(poly, x.exp_vartime((num_cycles as u64).into()))
+            })
+            .collect()
+    }
+
+    // CONSTRAINT EVALUATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates range check constraints over the specified frames and writes the results into
+    /// `result`.
+    ///
+    /// The frames must contain all columns of the execution trace, including the columns appended
+    /// for range checks; `table_values` must contain values of lookup tables at the same point
+    /// (see [get_table_values_at()](Self::get_table_values_at)), and `alpha` must be the random
+    /// element of the lookup.
+    pub fn evaluate<F>(
+        &self,
+        main_frame: &EvaluationFrame<F>,
+        aux_frame: &EvaluationFrame<E>,
+        table_values: &[F],
+        alpha: E,
+        result: &mut [E],
+    ) where
+        F: FieldElement<BaseField = E::BaseField>,
+        E: ExtensionOf<F>,
+    {
+        let main = main_frame.current();
+        let aux = aux_frame.current();
+        let aux_next = aux_frame.next();
+        for (i, &(column, _)) in self.checks.iter().enumerate() {
+            let value = E::from(main[column]);
+            let multiplicity = E::from(main[self.main_offset + i]);
+            let inverse = aux[self.aux_offset + 2 * i];
+            let sum = aux[self.aux_offset + 2 * i + 1];
+            let sum_next = aux_next[self.aux_offset + 2 * i + 1];
+
+            result[2 * i] = inverse * (alpha - value) - E::ONE;
+            result[2 * i + 1] =
+                (sum_next - sum - inverse) * (alpha - E::from(table_values[i])) + multiplicity;
+        }
+    }
+
+    // CONSTRAINT COMPOSITION
+    // --------------------------------------------------------------------------------------------
+
+    /// Computes a linear combination of range check constraint evaluations.
+    ///
+    /// The result is not divided by the divisor; this is left to the caller so that evaluations
+    /// over a domain can be divided in bulk.
+    pub fn merge_evaluations(&self, evaluations: &[E]) -> E {
+        evaluations
+            .iter()
+            .zip(self.coefficients.iter())
+            .fold(E::ZERO, |acc, (&eval, &coef)| acc + coef * eval)
+    }
+
+    /// Computes a linear combination of range check constraint evaluations and divides the result
+    /// by the range check constraint divisor evaluated at `x`.
+    pub fn combine_evaluations<F>(&self, evaluations: &[E], x: F) -> E
+    where
+        F: FieldElement<BaseField = E::BaseField>,
+        E: ExtensionOf<F>,
+    {
+        let z = E::from(self.divisor.evaluate_at(x));
+        self.merge_evaluations(evaluations) / z
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns a polynomial in coefficient form which evaluates to values 0, 1, ..., 2^num_bits - 1
+/// over the domain of 2^num_bits roots of unity.
+fn build_table_poly<B: StarkField>(num_bits: u32) -> Vec<B> {
+    let table_size = 1usize << num_bits;
+    let mut values = (0..table_size as u64).map(B::from).collect::<Vec<_>>();
+    let inv_twiddles = fft::get_inv_twiddles::<B>(table_size);
+    fft::interpolate_poly(&mut values, &inv_twiddles);
+    values
+}
@@ -4,8 +4,9 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{
-    Air, AirContext, Assertion, EvaluationFrame, ProofOptions, TraceInfo,
-    TransitionConstraintDegree,
+    lde_coset_offset, lde_domain_position, Air, AirContext, Assertion, EvaluationFrame,
+    ProofOptions, SparsePeriodicColumn, TraceInfo, TransitionConstraintDegree,
+    TransitionConstraintGroup, TransitionConstraints, WraparoundConstraints,
 };
 use crate::{AuxTraceRandElements, FieldExtension};
 use crypto::{hashers::Blake3_256, DefaultRandomCoin, RandomCoin};
@@ -50,10 +51,143 @@ fn get_periodic_column_polys_num_values_not_power_of_two() {
     assert_eq!(0, column_polys.len());
 }
 
+#[test]
+fn get_sparse_periodic_column_polys() {
+    let sparse_col = SparsePeriodicColumn::new(
+        16,
+        BaseElement::ZERO,
+        vec![(5, BaseElement::new(7)), (0, BaseElement::ONE)],
+    );
+    let mut dense_col = vec![BaseElement::ZERO; 16];
+    dense_col[0] = BaseElement::ONE;
+    dense_col[5] = BaseElement::new(7);
+    assert_eq!(dense_col, sparse_col.to_dense());
+    for (i, &value) in dense_col.iter().enumerate() {
+        assert_eq!(value, sparse_col.get(i));
+    }
+
+    // sparse columns are placed after dense columns, and are evaluated in the same way as the
+    // equivalent dense columns
+    let col1 = vec![BaseElement::ONE, BaseElement::ZERO];
+    let sparse_air =
+        MockAir::with_sparse_periodic_columns(vec![col1.clone()], vec![sparse_col], 64);
+    let dense_air = MockAir::with_periodic_columns(vec![col1, dense_col.clone()], 64);
+
+    let column_polys = sparse_air.get_periodic_column_polys();
+    assert_eq!(2, column_polys.len());
+    assert_eq!(build_periodic_column_poly(&dense_col), column_polys[1]);
+    assert_eq!(dense_air.get_periodic_column_polys(), column_polys);
+
+    let x = BaseElement::new(12345);
+    assert_eq!(
+        dense_air.get_periodic_column_values_at(x),
+        sparse_air.get_periodic_column_values_at(x)
+    );
+}
+
+#[test]
+#[should_panic(expected = "override index must be smaller than cycle length 8, but was 8")]
+fn sparse_periodic_column_index_out_of_bounds() {
+    SparsePeriodicColumn::new(8, BaseElement::ZERO, vec![(8, BaseElement::ONE)]);
+}
+
+#[test]
+#[should_panic(expected = "value at index 3 is overridden more than once")]
+fn sparse_periodic_column_duplicate_index() {
+    let overrides = vec![(3, BaseElement::ONE), (1, BaseElement::ONE), (3, BaseElement::ZERO)];
+    SparsePeriodicColumn::new(8, BaseElement::ZERO, overrides);
+}
+
 // TRANSITION CONSTRAINTS
 // ================================================================================================
 
-// TODO
+#[test]
+fn get_transition_constraint_groups() {
+    let degrees = vec![
+        TransitionConstraintDegree::new(2),
+        TransitionConstraintDegree::new(3),
+        TransitionConstraintDegree::new(2),
+        TransitionConstraintDegree::with_cycles(1, vec![4]),
+    ];
+    let context = build_context_with_degrees::<BaseElement>(8, degrees.clone(), 8);
+    let coefficients = (1..5u8).map(BaseElement::from).collect::<Vec<_>>();
+    let constraints = TransitionConstraints::new(&context, &coefficients);
+
+    // constraints of the same degree are grouped together in order of first appearance
+    let groups = constraints.main_constraint_groups();
+    assert_eq!(
+        vec![
+            TransitionConstraintGroup::new(degrees[0].clone(), vec![0, 2]),
+            TransitionConstraintGroup::new(degrees[1].clone(), vec![1]),
+            TransitionConstraintGroup::new(degrees[3].clone(), vec![3]),
+        ],
+        groups
+    );
+    assert!(constraints.aux_constraint_groups().is_empty());
+
+    // the composition polynomial has 2 columns of length 8, and thus, its degree is 15; quotient
+    // degrees are 14 - 7 = 7, 21 - 7 = 14, and 13 - 7 = 6 respectively
+    assert_eq!(2, context.num_constraint_composition_columns());
+    let adjustments = groups.iter().map(|group| group.degree_adjustment(&context));
+    assert_eq!(vec![8, 1, 9], adjustments.collect::<Vec<_>>());
+
+    // composition coefficients are selected by constraint index
+    let main_coef = constraints.main_constraint_coef();
+    assert_eq!(
+        vec![BaseElement::from(1u8), BaseElement::from(3u8)],
+        groups[0].composition_coefficients(&main_coef)
+    );
+    assert_eq!(vec![BaseElement::from(2u8)], groups[1].composition_coefficients(&main_coef));
+    assert_eq!(vec![BaseElement::from(4u8)], groups[2].composition_coefficients(&main_coef));
+}
+
+#[test]
+fn transition_constraint_degree_adjustments() {
+    let degrees = vec![TransitionConstraintDegree::new(2), TransitionConstraintDegree::new(3)];
+    let context = build_context_with_degrees::<BaseElement>(8, degrees, 8);
+    let coefficients = vec![BaseElement::new(3), BaseElement::new(5)];
+    let evaluations = [BaseElement::new(7), BaseElement::new(11)];
+    let x = BaseElement::new(13);
+
+    // without degree adjustments, evaluations are combined using composition coefficients only
+    let constraints = TransitionConstraints::new(&context, &coefficients);
+    assert!(constraints.main_degree_adjustments().is_empty());
+    let expected = coefficients[0] * evaluations[0] + coefficients[1] * evaluations[1];
+    assert_eq!(expected, constraints.merge_main_evaluations::<BaseElement>(&evaluations, x));
+
+    // with degree adjustments, each evaluation is also multiplied by x^adjustment
+    let constraints =
+        TransitionConstraints::with_degree_adjustments(&context, &coefficients, &[8, 1]);
+    assert_eq!(&[8, 1], constraints.main_degree_adjustments());
+    let expected =
+        coefficients[0] * evaluations[0] * x.exp(8) + coefficients[1] * evaluations[1] * x;
+    assert_eq!(expected, constraints.merge_main_evaluations::<BaseElement>(&evaluations, x));
+
+    let z = constraints.divisor().evaluate_at(x);
+    assert_eq!(
+        expected / z,
+        constraints.combine_evaluations::<BaseElement>(&evaluations, &[], x)
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "degree adjustment of transition constraint 1 cannot exceed 1, but was 2"
+)]
+fn transition_constraint_degree_adjustment_too_high() {
+    // the composition polynomial has 2 columns of length 8; the quotient of the degree 3
+    // constraint has degree 14, and thus, can be adjusted by at most 1
+    let degrees = vec![TransitionConstraintDegree::new(2), TransitionConstraintDegree::new(3)];
+    let context = build_context_with_degrees::<BaseElement>(8, degrees, 8);
+    let coefficients = vec![BaseElement::ONE, BaseElement::ONE];
+    let _ = TransitionConstraints::with_degree_adjustments(&context, &coefficients, &[0, 2]);
+}
+
+#[test]
+#[should_panic(expected = "a transition constraint group must contain at least one constraint")]
+fn empty_transition_constraint_group() {
+    TransitionConstraintGroup::new(TransitionConstraintDegree::new(2), Vec::new());
+}
 
 // BOUNDARY CONSTRAINTS
 // ================================================================================================
@@ -184,6 +318,190 @@ fn get_boundary_constraints() {
     assert_eq!(vec![BaseElement::new(7)], constraint.poly());
     assert_eq!(no_poly_offset, constraint.poly_offset());
     assert_eq!(expected_cc[&5], constraint.cc().clone());
+
+    // evaluating boundary constraints via the AIR yields the same result as evaluating the
+    // constraint groups
+    let state = vec![BaseElement::new(11), BaseElement::new(13)];
+    let x = BaseElement::new(17);
+    let expected = groups
+        .iter()
+        .fold(BaseElement::ZERO, |result, group| result + group.evaluate_at(&state, x));
+    let actual = air.evaluate_boundary_constraints(
+        &AuxTraceRandElements::new(),
+        &coefficients,
+        &state,
+        None,
+        x,
+    );
+    assert_eq!(expected, actual);
+}
+
+// LDE DOMAIN INFO
+// ================================================================================================
+
+#[test]
+fn lde_domain_info() {
+    let trace_length = 16;
+    let air = MockAir::with_periodic_columns(vec![], trace_length);
+    let info = air.context().lde_domain_info();
+
+    // blowup factor is 8, and thus, the LDE domain size is 8x of the trace length
+    assert_eq!(trace_length * 8, info.size);
+    assert_eq!(air.lde_domain_size(), info.size);
+    assert_eq!(BaseElement::GENERATOR, info.offset);
+
+    // the generator must have the order equal to the domain size
+    assert_eq!(BaseElement::ONE, info.generator.exp(info.size as u64));
+    assert_ne!(BaseElement::ONE, info.generator.exp(info.size as u64 / 2));
+}
+
+#[test]
+fn lde_domain_at_two_adicity_boundary() {
+    // blowup factor is 8, and thus, the LDE domain for this trace has exactly 2^TWO_ADICITY
+    // elements
+    let trace_length = 1 << (BaseElement::TWO_ADICITY - 3);
+    let context = build_context::<BaseElement>(trace_length, 1, 1);
+    let info = context.lde_domain_info();
+
+    assert_eq!(1 << BaseElement::TWO_ADICITY, info.size);
+    assert_eq!(BaseElement::TWO_ADIC_ROOT_OF_UNITY, info.generator);
+}
+
+#[test]
+#[should_panic(
+    expected = "LDE domain of size 2^33 cannot be formed in a base field with two-adicity 32"
+)]
+fn lde_domain_exceeds_two_adicity() {
+    let trace_length = 1 << (BaseElement::TWO_ADICITY - 2);
+    let _ = build_context::<BaseElement>(trace_length, 1, 1);
+}
+
+// CONSTRAINT SPLITTING
+// ================================================================================================
+
+#[test]
+fn num_splitting_columns() {
+    // a constraint of degree 8 can be evaluated with blowup factor 8 without splitting
+    let degree = TransitionConstraintDegree::new(8);
+    assert_eq!(8, degree.min_blowup_factor());
+    assert_eq!(0, degree.num_splitting_columns(8));
+
+    // with blowup factor 4, constraints of degree 5 and 4 are needed
+    assert_eq!(1, degree.num_splitting_columns(4));
+
+    // with blowup factor 2, constraints of degree 3, 3, 3, and 2 are needed
+    assert_eq!(3, degree.num_splitting_columns(2));
+
+    // periodic columns count towards constraint degree
+    let degree = TransitionConstraintDegree::with_cycles(3, vec![32]);
+    assert_eq!(0, degree.num_splitting_columns(4));
+    assert_eq!(1, degree.num_splitting_columns(2));
+}
+
+#[test]
+#[should_panic(expected = "blowup factor too small; expected at least 8, but was 4; the base \
+    field supports blowup factor of at most 4 for traces of length 1073741824, and thus, \
+    high-degree transition constraints must be split using intermediate trace columns \
+    (1 for main transition constraint 1)")]
+fn constraint_degree_exceeds_two_adicity() {
+    // for a trace of 2^30 steps, the base field supports blowup factor of at most 4, but a
+    // constraint of degree 8 requires blowup factor of 8
+    let trace_length = 1 << (BaseElement::TWO_ADICITY - 2);
+    let degrees = vec![TransitionConstraintDegree::new(2), TransitionConstraintDegree::new(8)];
+    let _ = build_context_with_degrees::<BaseElement>(trace_length, degrees, 4);
+}
+
+#[test]
+#[should_panic(expected = "LDE domain of size 2^33 cannot be formed in a base field with \
+    two-adicity 32; the base field supports blowup factor of at most 4 for traces of length \
+    1073741824, and thus, high-degree transition constraints must be split using intermediate \
+    trace columns (1 for main transition constraint 1)")]
+fn constraint_degree_exceeds_two_adicity_with_large_blowup() {
+    let trace_length = 1 << (BaseElement::TWO_ADICITY - 2);
+    let degrees = vec![TransitionConstraintDegree::new(2), TransitionConstraintDegree::new(8)];
+    let _ = build_context_with_degrees::<BaseElement>(trace_length, degrees, 8);
+}
+
+#[test]
+fn split_constraint_within_two_adicity() {
+    // splitting the constraint of degree 8 into constraints of degree 5 and 4 using a single
+    // intermediate column makes it possible to evaluate it with blowup factor 4
+    let trace_length = 1 << (BaseElement::TWO_ADICITY - 2);
+    let degrees = vec![
+        TransitionConstraintDegree::new(2),
+        TransitionConstraintDegree::new(5),
+        TransitionConstraintDegree::new(4),
+    ];
+    let context = build_context_with_degrees::<BaseElement>(trace_length, degrees, 4);
+    assert_eq!(trace_length * 4, context.ce_domain_size());
+    assert_eq!(1 << BaseElement::TWO_ADICITY, context.lde_domain_size());
+}
+
+// WRAPAROUND CONSTRAINTS
+// ================================================================================================
+
+#[test]
+fn get_wraparound_constraints() {
+    let trace_length = 16;
+    let context = build_context::<BaseElement>(trace_length, 2, 1)
+        .set_wraparound_constraint_degrees(vec![TransitionConstraintDegree::new(1)]);
+    assert_eq!(1, context.num_wraparound_constraints());
+
+    let coefficients = vec![BaseElement::new(3)];
+    let constraints = WraparoundConstraints::new(&context, &coefficients);
+    assert_eq!(1, constraints.divisor().degree());
+
+    // the divisor vanishes only at the last step of the trace
+    let g = BaseElement::get_root_of_unity(trace_length.ilog2());
+    for (step, x) in get_power_series(g, trace_length).into_iter().enumerate() {
+        let z = constraints.divisor().evaluate_at(x);
+        assert_eq!(step == trace_length - 1, z == BaseElement::ZERO);
+    }
+
+    let x = BaseElement::new(11);
+    let evaluations = [BaseElement::new(5)];
+    let expected = coefficients[0] * evaluations[0] / (x - g.exp(trace_length as u64 - 1));
+    assert_eq!(expected, constraints.combine_evaluations::<BaseElement>(&evaluations, x));
+}
+
+#[test]
+#[should_panic(
+    expected = "degree of wraparound constraint 0 quotient cannot exceed 15, but was 29"
+)]
+fn wraparound_constraint_degree_too_high() {
+    // transition constraints have degree 2, and thus, the composition polynomial has a single
+    // column of degree 15; a wraparound constraint of degree 2 does not fit into it
+    let _ = build_context::<BaseElement>(16, 2, 1)
+        .set_wraparound_constraint_degrees(vec![TransitionConstraintDegree::new(2)]);
+}
+
+// LDE DOMAIN
+// ================================================================================================
+
+#[test]
+fn lde_coset_offset_and_positions() {
+    let trace_length = 16;
+    let blowup_factor = 8;
+    let context = build_context::<BaseElement>(trace_length, 4, 1);
+    let domain_info = context.lde_domain_info();
+    assert_eq!(lde_coset_offset::<BaseElement>(), domain_info.offset);
+    assert_eq!(BaseElement::GENERATOR, lde_coset_offset::<BaseElement>());
+    assert_eq!(trace_length * blowup_factor, domain_info.size);
+
+    // the LDE domain is the subgroup of size n * k shifted by the offset
+    let offset = lde_coset_offset::<BaseElement>();
+    let lde_domain = get_power_series(domain_info.generator, domain_info.size)
+        .into_iter()
+        .map(|x| x * offset)
+        .collect::<Vec<_>>();
+
+    // the trace domain shifted by the offset is located at positions i * k of the LDE domain
+    let trace_generator = BaseElement::get_root_of_unity(trace_length.ilog2());
+    for (i, x) in get_power_series(trace_generator, trace_length).into_iter().enumerate() {
+        let position = lde_domain_position(i, blowup_factor);
+        assert_eq!(i * blowup_factor, position);
+        assert_eq!(offset * x, lde_domain[position]);
+    }
 }
 
 // MOCK AIR
@@ -193,6 +511,7 @@ struct MockAir {
     context: AirContext<BaseElement>,
     assertions: Vec<Assertion<BaseElement>>,
     periodic_columns: Vec<Vec<BaseElement>>,
+    sparse_periodic_columns: Vec<SparsePeriodicColumn<BaseElement>>,
 }
 
 impl MockAir {
@@ -209,6 +528,16 @@ impl MockAir {
         result
     }
 
+    pub fn with_sparse_periodic_columns(
+        column_values: Vec<Vec<BaseElement>>,
+        sparse_columns: Vec<SparsePeriodicColumn<BaseElement>>,
+        trace_length: usize,
+    ) -> Self {
+        let mut result = Self::with_periodic_columns(column_values, trace_length);
+        result.sparse_periodic_columns = sparse_columns;
+        result
+    }
+
     pub fn with_assertions(assertions: Vec<Assertion<BaseElement>>, trace_length: usize) -> Self {
         let mut result = Self::new(
             TraceInfo::with_meta(4, trace_length, vec![assertions.len() as u8]),
@@ -231,6 +560,7 @@ impl Air for MockAir {
             context,
             assertions: Vec::new(),
             periodic_columns: Vec::new(),
+            sparse_periodic_columns: Vec::new(),
         }
     }
 
@@ -242,6 +572,10 @@ impl Air for MockAir {
         self.periodic_columns.clone()
     }
 
+    fn get_sparse_periodic_column_values(&self) -> Vec<SparsePeriodicColumn<Self::BaseField>> {
+        self.sparse_periodic_columns.clone()
+    }
+
     fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
         self.assertions.clone()
     }
@@ -269,6 +603,16 @@ pub fn build_context<B: StarkField>(
     AirContext::new(trace_info, t_degrees, num_assertions, options)
 }
 
+pub fn build_context_with_degrees<B: StarkField>(
+    trace_length: usize,
+    t_degrees: Vec<TransitionConstraintDegree>,
+    blowup_factor: usize,
+) -> AirContext<B> {
+    let options = ProofOptions::new(32, blowup_factor, 0, FieldExtension::None, 4, 31);
+    let trace_info = TraceInfo::new(3, trace_length);
+    AirContext::new(trace_info, t_degrees, 1, options)
+}
+
 pub fn build_prng() -> DefaultRandomCoin<Blake3_256<BaseElement>> {
     RandomCoin::new(&[BaseElement::ZERO; 32])
 }
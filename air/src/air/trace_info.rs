@@ -145,6 +145,10 @@ impl TraceInfo {
 /// have a non-zero main segment, and may have additional auxiliary trace segments. Currently, the
 /// number of auxiliary trace segments is limited to one.
 ///
+/// Each segment is committed to separately, and is opened by the verifier at its own width. Thus,
+/// widths of the segments are independent of each other: for example, a narrow main segment can
+/// be combined with a much wider auxiliary segment.
+///
 /// Additionally, a layout contains information on how many random elements are required to build a
 /// given auxiliary trace segment. This information is used to construct
 /// [AuxTraceRandElements](crate::AuxTraceRandElements) struct which is passed in as one of the
@@ -0,0 +1,534 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{EvaluationFrame, FieldElement, TransitionConstraintDegree, Vec};
+use crate::Assertion;
+use core::{marker::PhantomData, ops::Range};
+use crypto::hashers::Rp64_256;
+use math::{fields::f64::BaseElement, StarkField};
+
+// HASH FUNCTION
+// ================================================================================================
+/// Describes an arithmetization-friendly hash function whose permutation consists of a fixed
+/// number of rounds, each of which can be enforced by a single set of transition constraints.
+///
+/// This is used by [HashAccumulator] to enforce applications of the hash function within an
+/// execution trace.
+pub trait HashFunction {
+    /// Base field over which the hash function is defined.
+    type BaseField: StarkField;
+
+    /// Number of field elements in the state of the hash function.
+    const STATE_WIDTH: usize;
+
+    /// Range of the state elements into which the data is absorbed.
+    const RATE_RANGE: Range<usize>;
+
+    /// Range of the state elements from which the digest is read.
+    const DIGEST_RANGE: Range<usize>;
+
+    /// Number of rounds in a single application of the permutation.
+    const NUM_ROUNDS: usize;
+
+    /// Number of round constants used in a single round.
+    const NUM_ROUND_CONSTANTS: usize;
+
+    /// Degree of the constraints enforcing a single round.
+    const ROUND_CONSTRAINT_DEGREE: usize;
+
+    /// Returns the state of the hash function before any data is absorbed into it when a
+    /// sequence of the specified number of elements is hashed.
+    fn init_state(num_elements: usize) -> Vec<Self::BaseField>;
+
+    /// Returns constants used in the specified round.
+    fn round_constants(round: usize) -> Vec<Self::BaseField>;
+
+    /// Applies the specified round to the provided state.
+    fn apply_round(state: &mut [Self::BaseField], round: usize);
+
+    /// Evaluates constraints for a single round against the provided states, and writes the
+    /// results into `result`; the constraints evaluate to zeros if and only if `next` is the
+    /// result of applying the round with the specified constants to `current`.
+    fn evaluate_round<E: FieldElement<BaseField = Self::BaseField>>(
+        current: &[E],
+        next: &[E],
+        round_constants: &[E],
+        result: &mut [E],
+    );
+}
+
+impl HashFunction for Rp64_256 {
+    type BaseField = BaseElement;
+
+    const STATE_WIDTH: usize = Rp64_256::STATE_WIDTH;
+    const RATE_RANGE: Range<usize> = Rp64_256::RATE_RANGE;
+    const DIGEST_RANGE: Range<usize> = Rp64_256::DIGEST_RANGE;
+    const NUM_ROUNDS: usize = Rp64_256::NUM_ROUNDS;
+    const NUM_ROUND_CONSTANTS: usize = 2 * Rp64_256::STATE_WIDTH;
+    const ROUND_CONSTRAINT_DEGREE: usize = 7;
+
+    fn init_state(num_elements: usize) -> Vec<BaseElement> {
+        // this mirrors the initialization of the state in Rp64_256::hash_elements()
+        let mut state = vec![BaseElement::ZERO; Self::STATE_WIDTH];
+        state[Rp64_256::CAPACITY_RANGE.start] = BaseElement::new(num_elements as u64);
+        state
+    }
+
+    fn round_constants(round: usize) -> Vec<BaseElement> {
+        let mut result = Rp64_256::ARK1[round].to_vec();
+        result.extend_from_slice(&Rp64_256::ARK2[round]);
+        result
+    }
+
+    fn apply_round(state: &mut [BaseElement], round: usize) {
+        let state = state.try_into().expect("invalid state width");
+        Rp64_256::apply_round(state, round);
+    }
+
+    fn evaluate_round<E: FieldElement<BaseField = BaseElement>>(
+        current: &[E],
+        next: &[E],
+        round_constants: &[E],
+        result: &mut [E],
+    ) {
+        const WIDTH: usize = Rp64_256::STATE_WIDTH;
+        let (ark1, ark2) = round_constants.split_at(WIDTH);
+
+        // the first half of the round is computed forward from the current state, while the
+        // second half is computed backward from the next state; this way, the inverse S-box
+        // does not need to be evaluated
+        let mut sbox_current = [E::ZERO; WIDTH];
+        let mut next_minus_ark = [E::ZERO; WIDTH];
+        for i in 0..WIDTH {
+            sbox_current[i] = exp7(current[i]);
+            next_minus_ark[i] = next[i] - ark2[i];
+        }
+
+        for (i, result) in result.iter_mut().enumerate().take(WIDTH) {
+            let mut forward = ark1[i];
+            let mut backward = E::ZERO;
+            for j in 0..WIDTH {
+                forward += E::from(Rp64_256::MDS[i][j]) * sbox_current[j];
+                backward += E::from(Rp64_256::INV_MDS[i][j]) * next_minus_ark[j];
+            }
+            *result = forward - exp7(backward);
+        }
+    }
+}
+
+// HASH ACCUMULATOR
+// ================================================================================================
+/// Describes a set of trace columns holding the state of a hash function `H` which accumulates a
+/// hash over data read from other trace columns.
+///
+/// The execution trace is split into cycles of [cycle_length()](Self::cycle_length) steps. On the
+/// first `H::NUM_ROUNDS` steps of each cycle, the rounds of the permutation of `H` are applied to
+/// the state. On the last step of each cycle, values of the input columns are added to the rate
+/// portion of the state; the capacity portion is carried over unchanged. On all other steps (if
+/// any), the state is copied to the next step. Thus, the state at the first step of cycle $k$
+/// commits to the data placed into the state at the first step of the trace, and to the data
+/// absorbed at the last steps of all previous cycles.
+///
+/// When all elements of the rate are read from the input columns, and the trace is initialized
+/// via [init_row()](Self::init_row), the digest computed after absorbing $m$ elements is equal to
+/// the digest returned by `H` for the sequence of these elements (e.g., via `hash_elements()`
+/// function for [Rp64_256]); the digest can be tied to public inputs via assertions returned
+/// from [get_assertions()](Self::get_assertions).
+///
+/// To use this helper, an AIR should:
+/// * Reserve `H::STATE_WIDTH` columns for the state in its execution trace.
+/// * Include the descriptors returned by [degrees()](Self::degrees) in its transition constraint
+///   degrees, and the columns returned by [get_periodic_column_values()](Self::get_periodic_column_values)
+///   in its periodic columns.
+/// * Call [evaluate()](Self::evaluate) from
+///   [Air::evaluate_transition()](crate::Air::evaluate_transition), passing to it the values of
+///   the periodic columns of the accumulator.
+///
+/// The trace builder can populate the state columns via [init_row()](Self::init_row) and
+/// [fill_next_row()](Self::fill_next_row).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HashAccumulator<H: HashFunction> {
+    first_column: usize,
+    input_columns: Vec<usize>,
+    _hash: PhantomData<H>,
+}
+
+impl<H: HashFunction> HashAccumulator<H> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new hash accumulator with the state located in `H::STATE_WIDTH` columns starting
+    /// with `first_column`, absorbing values from the specified input columns.
+    ///
+    /// Values of the input columns are absorbed into the rate portion of the state in the order
+    /// in which the columns are listed; rate elements beyond the number of input columns are
+    /// left unchanged.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * The number of input columns is greater than the width of the rate portion of the state.
+    /// * Any of the input columns is one of the state columns.
+    pub fn new(first_column: usize, input_columns: Vec<usize>) -> Self {
+        let rate_width = H::RATE_RANGE.len();
+        assert!(
+            input_columns.len() <= rate_width,
+            "number of input columns cannot exceed {rate_width}, but was {}",
+            input_columns.len()
+        );
+        let state_columns = first_column..first_column + H::STATE_WIDTH;
+        for &column in input_columns.iter() {
+            assert!(
+                !state_columns.contains(&column),
+                "input column {column} must be distinct from the state columns"
+            );
+        }
+        HashAccumulator {
+            first_column,
+            input_columns,
+            _hash: PhantomData,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the range of trace columns holding the state of the hash function.
+    pub fn state_columns(&self) -> Range<usize> {
+        self.first_column..self.first_column + H::STATE_WIDTH
+    }
+
+    /// Returns the indexes of the input columns.
+    pub fn input_columns(&self) -> &[usize] {
+        &self.input_columns
+    }
+
+    /// Returns the number of steps in a single cycle of the accumulator.
+    ///
+    /// This is the smallest power of two greater than the number of rounds of `H`.
+    pub fn cycle_length(&self) -> usize {
+        (H::NUM_ROUNDS + 1).next_power_of_two()
+    }
+
+    /// Returns the number of cycles required to absorb a sequence of the specified number of
+    /// elements, given that the first chunk of the sequence is placed into the state at the first
+    /// step of the trace via [init_row()](Self::init_row).
+    pub fn num_cycles(&self, num_elements: usize) -> usize {
+        let rate_width = H::RATE_RANGE.len();
+        if num_elements <= rate_width || self.input_columns.is_empty() {
+            return 1;
+        }
+        1 + (num_elements - rate_width).div_ceil(self.input_columns.len())
+    }
+
+    /// Returns the step at which the digest of a sequence of the specified number of elements
+    /// can be read from the state; this is the step right after the last round of the last cycle
+    /// required to absorb the sequence.
+    pub fn digest_step(&self, num_elements: usize) -> usize {
+        (self.num_cycles(num_elements) - 1) * self.cycle_length() + H::NUM_ROUNDS
+    }
+
+    // AIR DESCRIPTION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns degree descriptors of the transition constraints enforced by this accumulator;
+    /// there is one constraint per element of the state.
+    pub fn degrees(&self) -> Vec<TransitionConstraintDegree> {
+        (0..H::STATE_WIDTH)
+            .map(|_| {
+                TransitionConstraintDegree::with_cycles(
+                    H::ROUND_CONSTRAINT_DEGREE,
+                    vec![self.cycle_length()],
+                )
+            })
+            .collect()
+    }
+
+    /// Returns values of the periodic columns used by this accumulator.
+    ///
+    /// The first column is a flag set on the steps at which a round is applied, the second
+    /// column is a flag set on the steps at which the input columns are absorbed, and the
+    /// remaining `H::NUM_ROUND_CONSTANTS` columns contain round constants of `H`.
+    pub fn get_periodic_column_values(&self) -> Vec<Vec<H::BaseField>> {
+        let cycle_length = self.cycle_length();
+        let mut round_flags = vec![H::BaseField::ZERO; cycle_length];
+        let mut absorb_flags = vec![H::BaseField::ZERO; cycle_length];
+        let mut constants = vec![vec![H::BaseField::ZERO; cycle_length]; H::NUM_ROUND_CONSTANTS];
+        for round in 0..H::NUM_ROUNDS {
+            round_flags[round] = H::BaseField::ONE;
+            for (column, value) in constants.iter_mut().zip(H::round_constants(round)) {
+                column[round] = value;
+            }
+        }
+        absorb_flags[cycle_length - 1] = H::BaseField::ONE;
+
+        let mut result = vec![round_flags, absorb_flags];
+        result.append(&mut constants);
+        result
+    }
+
+    /// Returns the number of periodic columns used by this accumulator.
+    pub fn num_periodic_columns(&self) -> usize {
+        H::NUM_ROUND_CONSTANTS + 2
+    }
+
+    /// Returns assertions binding the state of this accumulator to a hash of a sequence of the
+    /// specified number of elements with the specified digest.
+    ///
+    /// The assertions place the initial state of `H` (except for the rate portion) at the first
+    /// step of the trace, and the `digest` at the [digest_step()](Self::digest_step).
+    ///
+    /// # Panics
+    /// Panics if the length of `digest` is not equal to the length of `H::DIGEST_RANGE`.
+    pub fn get_assertions(
+        &self,
+        num_elements: usize,
+        digest: &[H::BaseField],
+    ) -> Vec<Assertion<H::BaseField>> {
+        assert_eq!(H::DIGEST_RANGE.len(), digest.len(), "invalid digest length");
+        let init_state = H::init_state(num_elements);
+        let mut result = Vec::new();
+        for (i, &value) in init_state.iter().enumerate() {
+            if !H::RATE_RANGE.contains(&i) {
+                result.push(Assertion::single(self.first_column + i, 0, value));
+            }
+        }
+        let digest_step = self.digest_step(num_elements);
+        for (i, &value) in H::DIGEST_RANGE.zip(digest.iter()) {
+            result.push(Assertion::single(self.first_column + i, digest_step, value));
+        }
+        result
+    }
+
+    // CONSTRAINT EVALUATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates the transition constraints enforced by this accumulator against the provided
+    /// evaluation frame and writes the results into the first `H::STATE_WIDTH` elements of
+    /// `result`.
+    ///
+    /// `periodic_values` must contain values of the periodic columns returned by
+    /// [get_periodic_column_values()](Self::get_periodic_column_values), in the same order.
+    pub fn evaluate<E>(&self, frame: &EvaluationFrame<E>, periodic_values: &[E], result: &mut [E])
+    where
+        E: FieldElement<BaseField = H::BaseField>,
+    {
+        let current = &frame.current()[self.state_columns()];
+        let next = &frame.next()[self.state_columns()];
+        let round_flag = periodic_values[0];
+        let absorb_flag = periodic_values[1];
+        let copy_flag = E::ONE - round_flag - absorb_flag;
+        let round_constants = &periodic_values[2..self.num_periodic_columns()];
+
+        H::evaluate_round(current, next, round_constants, &mut result[..H::STATE_WIDTH]);
+        for (i, result) in result.iter_mut().enumerate().take(H::STATE_WIDTH) {
+            let copy = next[i] - current[i];
+            let absorb = match self.absorbed_column(i) {
+                Some(column) => copy - frame.current()[column],
+                None => copy,
+            };
+            *result = round_flag * *result + absorb_flag * absorb + copy_flag * copy;
+        }
+    }
+
+    // TRACE GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Initializes the state columns of the provided trace row with the initial state of `H`
+    /// for a sequence of `num_elements` elements, and absorbs the first `chunk` of the sequence
+    /// into the rate portion of the state.
+    ///
+    /// # Panics
+    /// Panics if `chunk` is longer than the rate portion of the state.
+    pub fn init_row(&self, row: &mut [H::BaseField], num_elements: usize, chunk: &[H::BaseField]) {
+        assert!(chunk.len() <= H::RATE_RANGE.len(), "chunk is longer than the rate");
+        let state = &mut row[self.state_columns()];
+        state.copy_from_slice(&H::init_state(num_elements));
+        for (s, &value) in state[H::RATE_RANGE].iter_mut().zip(chunk) {
+            *s += value;
+        }
+    }
+
+    /// Populates the state columns of the `next` row from the `current` row, where `step` is the
+    /// index of the `current` row in the trace.
+    ///
+    /// The input columns of the `current` row must be populated before this method is called.
+    pub fn fill_next_row(&self, step: usize, current: &[H::BaseField], next: &mut [H::BaseField]) {
+        let columns = self.state_columns();
+        next[columns.clone()].copy_from_slice(&current[columns.clone()]);
+        let cycle_step = step % self.cycle_length();
+        if cycle_step < H::NUM_ROUNDS {
+            H::apply_round(&mut next[columns], cycle_step);
+        } else if cycle_step == self.cycle_length() - 1 {
+            for i in 0..H::STATE_WIDTH {
+                if let Some(column) = self.absorbed_column(i) {
+                    next[self.first_column + i] += current[column];
+                }
+            }
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the input column absorbed into the specified element of the state, if any.
+    fn absorbed_column(&self, state_idx: usize) -> Option<usize> {
+        if H::RATE_RANGE.contains(&state_idx) {
+            self.input_columns.get(state_idx - H::RATE_RANGE.start).copied()
+        } else {
+            None
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns x^7.
+#[inline(always)]
+fn exp7<E: FieldElement>(x: E) -> E {
+    let x2 = x.square();
+    let x4 = x2.square();
+    x4 * x2 * x
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto::ElementHasher;
+    use math::FieldElement;
+
+    const STATE_WIDTH: usize = Rp64_256::STATE_WIDTH;
+    const RATE_WIDTH: usize = 8;
+
+    /// Returns an accumulator with the state in columns 0..12, absorbing columns 12..20.
+    fn build_accumulator() -> HashAccumulator<Rp64_256> {
+        HashAccumulator::new(0, (STATE_WIDTH..STATE_WIDTH + RATE_WIDTH).collect())
+    }
+
+    /// Builds a trace of the specified length which hashes the provided sequence.
+    fn build_trace(
+        accumulator: &HashAccumulator<Rp64_256>,
+        sequence: &[BaseElement],
+        trace_length: usize,
+    ) -> Vec<Vec<BaseElement>> {
+        let cycle_length = accumulator.cycle_length();
+        let mut trace = vec![vec![BaseElement::ZERO; STATE_WIDTH + RATE_WIDTH]; trace_length];
+        let (first_chunk, rest) = sequence.split_at(RATE_WIDTH.min(sequence.len()));
+        accumulator.init_row(&mut trace[0], sequence.len(), first_chunk);
+        for (i, chunk) in rest.chunks(RATE_WIDTH).enumerate() {
+            let step = (i + 1) * cycle_length - 1;
+            trace[step][STATE_WIDTH..STATE_WIDTH + chunk.len()].copy_from_slice(chunk);
+        }
+        for step in 0..trace_length - 1 {
+            let (current, next) = trace.split_at_mut(step + 1);
+            accumulator.fill_next_row(step, &current[step], &mut next[0]);
+        }
+        trace
+    }
+
+    /// Evaluates constraints of the accumulator against all transitions of the provided trace.
+    fn evaluate_trace(
+        accumulator: &HashAccumulator<Rp64_256>,
+        trace: &[Vec<BaseElement>],
+    ) -> Vec<Vec<BaseElement>> {
+        let periodic_columns = accumulator.get_periodic_column_values();
+        trace
+            .windows(2)
+            .enumerate()
+            .map(|(step, rows)| {
+                let frame = EvaluationFrame::from_rows(rows[0].clone(), rows[1].clone());
+                let periodic_values = periodic_columns
+                    .iter()
+                    .map(|column| column[step % column.len()])
+                    .collect::<Vec<_>>();
+                let mut result = vec![BaseElement::ZERO; STATE_WIDTH];
+                accumulator.evaluate(&frame, &periodic_values, &mut result);
+                result
+            })
+            .collect()
+    }
+
+    #[test]
+    fn hash_accumulator_layout() {
+        let accumulator = build_accumulator();
+        assert_eq!(0..STATE_WIDTH, accumulator.state_columns());
+        assert_eq!(8, accumulator.cycle_length());
+        assert_eq!(26, accumulator.num_periodic_columns());
+        assert_eq!(26, accumulator.get_periodic_column_values().len());
+        assert_eq!(
+            vec![TransitionConstraintDegree::with_cycles(7, vec![8]); STATE_WIDTH],
+            accumulator.degrees()
+        );
+
+        assert_eq!(1, accumulator.num_cycles(8));
+        assert_eq!(7, accumulator.digest_step(8));
+        assert_eq!(2, accumulator.num_cycles(9));
+        assert_eq!(3, accumulator.num_cycles(20));
+        assert_eq!(23, accumulator.digest_step(20));
+    }
+
+    #[test]
+    fn hash_accumulator_sequence() {
+        let accumulator = build_accumulator();
+        let sequence = (0..20u64).map(|i| BaseElement::new(i * i + 3)).collect::<Vec<_>>();
+        let trace = build_trace(&accumulator, &sequence, 32);
+
+        // all constraints are satisfied
+        for evaluations in evaluate_trace(&accumulator, &trace) {
+            assert!(evaluations.iter().all(|&value| value == BaseElement::ZERO));
+        }
+
+        // the state at the digest step contains the hash of the sequence
+        let digest = Rp64_256::hash_elements(&sequence);
+        let digest_step = accumulator.digest_step(sequence.len());
+        assert_eq!(digest.as_elements(), &trace[digest_step][Rp64_256::DIGEST_RANGE]);
+
+        // the assertions bind the initial capacity and the digest
+        let assertions = accumulator.get_assertions(sequence.len(), digest.as_elements());
+        assert_eq!(8, assertions.len());
+        for assertion in assertions {
+            assert_eq!(trace[assertion.first_step()][assertion.column()], assertion.values()[0]);
+        }
+
+        // a single invalid round is detected by the constraints
+        let mut trace = trace;
+        trace[3][5] += BaseElement::ONE;
+        let evaluations = evaluate_trace(&accumulator, &trace);
+        assert!(evaluations[2].iter().any(|&value| value != BaseElement::ZERO));
+        assert!(evaluations[3].iter().any(|&value| value != BaseElement::ZERO));
+    }
+
+    #[test]
+    fn hash_accumulator_skips_unabsorbed_inputs() {
+        let accumulator = build_accumulator();
+        let sequence = (0..16u64).map(BaseElement::new).collect::<Vec<_>>();
+        let mut trace = build_trace(&accumulator, &sequence, 16);
+
+        // values of the input columns are ignored on steps other than the absorption steps
+        let digest_step = accumulator.digest_step(sequence.len());
+        let digest = trace[digest_step][Rp64_256::DIGEST_RANGE].to_vec();
+        trace[3][STATE_WIDTH] = BaseElement::new(42);
+        trace[12][STATE_WIDTH] = BaseElement::new(42);
+        for evaluations in evaluate_trace(&accumulator, &trace) {
+            assert!(evaluations.iter().all(|&value| value == BaseElement::ZERO));
+        }
+        assert_eq!(digest, trace[digest_step][Rp64_256::DIGEST_RANGE]);
+        assert_eq!(Rp64_256::hash_elements(&sequence).as_elements(), &digest[..]);
+    }
+
+    #[test]
+    #[should_panic(expected = "input column 3 must be distinct from the state columns")]
+    fn hash_accumulator_overlapping_columns() {
+        HashAccumulator::<Rp64_256>::new(0, vec![12, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "number of input columns cannot exceed 8, but was 9")]
+    fn hash_accumulator_too_many_inputs() {
+        HashAccumulator::<Rp64_256>::new(0, (12..21).collect());
+    }
+}
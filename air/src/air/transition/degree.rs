@@ -117,4 +117,28 @@ impl TransitionConstraintDegree {
         let degree_bound = self.base + self.cycles.len() - 1;
         cmp::max(degree_bound.next_power_of_two(), ProofOptions::MIN_BLOWUP_FACTOR)
     }
+
+    /// Returns the number of intermediate trace columns needed to split a constraint of this
+    /// degree into constraints which can be evaluated with the specified blowup factor.
+    ///
+    /// A constraint can be evaluated with blowup factor $b$ if its degree is at most $b + 1$.
+    /// A constraint which multiplies $d$ trace and periodic columns can be split by introducing
+    /// intermediate columns $t_1, ..., t_k$ such that:
+    /// * $t_1$ is constrained to equal the product of the first $b + 1$ columns.
+    /// * $t_{i + 1}$ is constrained to equal the product of $t_i$ and the next $b$ columns.
+    /// * The original constraint multiplies $t_k$ by the remaining columns.
+    ///
+    /// Each of the resulting constraints has degree at most $b + 1$; thus, the number of needed
+    /// intermediate columns is $\lceil (d - b - 1) / b \rceil$, or zero if $d \le b + 1$.
+    ///
+    /// # Panics
+    /// Panics if `blowup_factor` is smaller than 2 or is not a power of two.
+    pub fn num_splitting_columns(&self, blowup_factor: usize) -> usize {
+        assert!(
+            blowup_factor >= ProofOptions::MIN_BLOWUP_FACTOR && blowup_factor.is_power_of_two(),
+            "blowup factor must be a power of two greater than one, but was {blowup_factor}"
+        );
+        let degree = self.base + self.cycles.len();
+        degree.saturating_sub(blowup_factor + 1).div_ceil(blowup_factor)
+    }
 }
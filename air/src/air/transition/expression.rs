@@ -0,0 +1,497 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{EvaluationFrame, FieldElement, TransitionConstraintDegree, Vec};
+use core::{
+    cmp::{self, Ordering},
+    marker::PhantomData,
+    ops::{Add, Mul, Neg, Sub},
+};
+use math::StarkField;
+use utils::Box;
+
+// CONSTANTS
+// ================================================================================================
+
+/// log2 of the trace length at which degrees of summands are compared; see [ExprDegree].
+const DEGREE_COMPARISON_LENGTH_LOG2: u32 = 32;
+
+// CONSTRAINT EXPRESSION
+// ================================================================================================
+/// A symbolic expression over cells of an evaluation frame and values of periodic columns.
+///
+/// Expressions are built from leaves obtained via [SymbolicFrame] (or via the constructors of this
+/// type) and combined with `+`, `-`, `*` operators, unary `-`, and [exp()](Self::exp). For
+/// example, the following expression describes the transition $a' = a + b$ for a trace with
+/// columns $a$ and $b$:
+///
+/// ```
+/// # use winter_air::{ConstraintExpr, SymbolicFrame};
+/// # use math::fields::f128::BaseElement;
+/// let frame = SymbolicFrame::<BaseElement>::new();
+/// let expr = frame.next(0) - frame.current(0) - frame.current(1);
+/// ```
+///
+/// The degree of an expression is inferred from the expression tree via
+/// [degree()](Self::degree), and the expression can be evaluated against an [EvaluationFrame] via
+/// [evaluate()](Self::evaluate).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConstraintExpr<B: StarkField> {
+    /// Value of the column with the specified index in the current row of the frame.
+    Current(usize),
+    /// Value of the column with the specified index in the next row of the frame.
+    Next(usize),
+    /// Value of the periodic column with the specified index and cycle length.
+    Periodic(usize, usize),
+    /// A constant value.
+    Constant(B),
+    /// Sum of two expressions.
+    Add(Box<ConstraintExpr<B>>, Box<ConstraintExpr<B>>),
+    /// Difference of two expressions.
+    Sub(Box<ConstraintExpr<B>>, Box<ConstraintExpr<B>>),
+    /// Product of two expressions.
+    Mul(Box<ConstraintExpr<B>>, Box<ConstraintExpr<B>>),
+    /// Negation of an expression.
+    Neg(Box<ConstraintExpr<B>>),
+    /// An expression raised to the specified power.
+    Exp(Box<ConstraintExpr<B>>, u32),
+}
+
+impl<B: StarkField> ConstraintExpr<B> {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an expression for the value of the specified column in the current row.
+    pub fn current(column: usize) -> Self {
+        Self::Current(column)
+    }
+
+    /// Returns an expression for the value of the specified column in the next row.
+    pub fn next(column: usize) -> Self {
+        Self::Next(column)
+    }
+
+    /// Returns an expression for the value of the specified periodic column.
+    ///
+    /// `index` is the index of the column in the list of periodic columns returned by
+    /// [Air::get_periodic_column_values()](crate::Air::get_periodic_column_values), and
+    /// `cycle_length` is the number of values in this column.
+    ///
+    /// # Panics
+    /// Panics if `cycle_length` is not a power of two, or is smaller than 2.
+    pub fn periodic(index: usize, cycle_length: usize) -> Self {
+        assert!(
+            cycle_length >= 2 && cycle_length.is_power_of_two(),
+            "cycle length must be a power of two greater than one, but was {cycle_length}"
+        );
+        Self::Periodic(index, cycle_length)
+    }
+
+    /// Returns an expression for the specified constant.
+    pub fn constant(value: B) -> Self {
+        Self::Constant(value)
+    }
+
+    /// Returns this expression raised to the specified power.
+    pub fn exp(self, power: u32) -> Self {
+        Self::Exp(Box::new(self), power)
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the degree of the transition constraint described by this expression.
+    ///
+    /// The degree is inferred from the expression tree: the degree of a product is the sum of the
+    /// degrees of its factors, and the degree of a sum is the degree of the summand with the
+    /// larger degree. Terms which cancel out are not detected, and thus, the inferred degree is
+    /// an upper bound on the actual degree of the constraint. When the degrees of summands
+    /// involving periodic columns are not ordered the same way for all trace lengths, the degree
+    /// of the summand with the larger degree for long traces is used. If that summand depends
+    /// only on periodic columns, the base degree of the sum is taken from the other summands.
+    ///
+    /// # Panics
+    /// Panics if the expression does not depend on any trace columns.
+    pub fn degree(&self) -> TransitionConstraintDegree {
+        let degree = self.expr_degree();
+        assert!(
+            degree.base > 0,
+            "constraint expression must depend on at least one trace column"
+        );
+        TransitionConstraintDegree::with_cycles(degree.base, degree.cycles)
+    }
+
+    // EVALUATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates this expression against the provided evaluation frame and values of periodic
+    /// columns.
+    ///
+    /// # Panics
+    /// Panics if the expression refers to a column outside of the frame, or to a periodic column
+    /// outside of `periodic_values`.
+    pub fn evaluate<E>(&self, frame: &EvaluationFrame<E>, periodic_values: &[E]) -> E
+    where
+        E: FieldElement<BaseField = B>,
+    {
+        match self {
+            Self::Current(column) => frame.current()[*column],
+            Self::Next(column) => frame.next()[*column],
+            Self::Periodic(index, _) => periodic_values[*index],
+            Self::Constant(value) => E::from(*value),
+            Self::Add(lhs, rhs) => {
+                lhs.evaluate(frame, periodic_values) + rhs.evaluate(frame, periodic_values)
+            }
+            Self::Sub(lhs, rhs) => {
+                lhs.evaluate(frame, periodic_values) - rhs.evaluate(frame, periodic_values)
+            }
+            Self::Mul(lhs, rhs) => {
+                lhs.evaluate(frame, periodic_values) * rhs.evaluate(frame, periodic_values)
+            }
+            Self::Neg(expr) => -expr.evaluate(frame, periodic_values),
+            Self::Exp(expr, power) => expr.evaluate(frame, periodic_values).exp((*power).into()),
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the degree of this expression; the base degree is zero for expressions which do
+    /// not depend on trace columns.
+    fn expr_degree(&self) -> ExprDegree {
+        match self {
+            Self::Current(_) | Self::Next(_) => ExprDegree::new(1, Vec::new()),
+            Self::Periodic(_, cycle_length) => ExprDegree::new(0, vec![*cycle_length]),
+            Self::Constant(_) => ExprDegree::new(0, Vec::new()),
+            Self::Add(lhs, rhs) | Self::Sub(lhs, rhs) => lhs.expr_degree().add(&rhs.expr_degree()),
+            Self::Mul(lhs, rhs) => lhs.expr_degree().mul(&rhs.expr_degree()),
+            Self::Neg(expr) => expr.expr_degree(),
+            Self::Exp(expr, power) => {
+                let degree = expr.expr_degree();
+                (0..*power).fold(ExprDegree::new(0, Vec::new()), |acc, _| acc.mul(&degree))
+            }
+        }
+    }
+}
+
+impl<B: StarkField> Add for ConstraintExpr<B> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::Add(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<B: StarkField> Sub for ConstraintExpr<B> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::Sub(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<B: StarkField> Mul for ConstraintExpr<B> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::Mul(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<B: StarkField> Neg for ConstraintExpr<B> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::Neg(Box::new(self))
+    }
+}
+
+// SYMBOLIC FRAME
+// ================================================================================================
+/// A symbolic counterpart of an [EvaluationFrame] used to build constraint expressions.
+///
+/// Cells of the frame are referred to via [current()](Self::current) and [next()](Self::next)
+/// in the same way as values of an [EvaluationFrame] are accessed in
+/// [Air::evaluate_transition()](crate::Air::evaluate_transition), but the returned values are
+/// [ConstraintExpr] leaves rather than field elements.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SymbolicFrame<B: StarkField> {
+    _base_field: PhantomData<B>,
+}
+
+impl<B: StarkField> SymbolicFrame<B> {
+    /// Returns a new symbolic frame.
+    pub fn new() -> Self {
+        SymbolicFrame {
+            _base_field: PhantomData,
+        }
+    }
+
+    /// Returns an expression for the value of the specified column in the current row.
+    pub fn current(&self, column: usize) -> ConstraintExpr<B> {
+        ConstraintExpr::current(column)
+    }
+
+    /// Returns an expression for the value of the specified column in the next row.
+    pub fn next(&self, column: usize) -> ConstraintExpr<B> {
+        ConstraintExpr::next(column)
+    }
+
+    /// Returns an expression for the value of the specified periodic column with the specified
+    /// cycle length.
+    ///
+    /// # Panics
+    /// Panics if `cycle_length` is not a power of two, or is smaller than 2.
+    pub fn periodic(&self, index: usize, cycle_length: usize) -> ConstraintExpr<B> {
+        ConstraintExpr::periodic(index, cycle_length)
+    }
+}
+
+// SYMBOLIC CONSTRAINTS
+// ================================================================================================
+/// A list of transition constraints described by [ConstraintExpr] expressions.
+///
+/// Each expression describes a constraint which must evaluate to zero on all steps of a valid
+/// execution trace. Constraint degrees are inferred from the expressions, and thus, an AIR built
+/// with this helper does not need to specify degrees by hand. To use this helper, an AIR should
+/// pass [degrees()](Self::degrees) to [AirContext::new()](crate::AirContext::new), and call
+/// [evaluate()](Self::evaluate) from [Air::evaluate_transition()](crate::Air::evaluate_transition).
+/// Constraints are written into the result in the order in which they were added.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymbolicConstraints<B: StarkField> {
+    constraints: Vec<ConstraintExpr<B>>,
+}
+
+impl<B: StarkField> SymbolicConstraints<B> {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an empty list of constraints.
+    pub fn new() -> Self {
+        SymbolicConstraints {
+            constraints: Vec::new(),
+        }
+    }
+
+    /// Returns this list of constraints with a constraint requiring that `expr` evaluates to
+    /// zero appended to it.
+    pub fn with_constraint(mut self, expr: ConstraintExpr<B>) -> Self {
+        self.constraints.push(expr);
+        self
+    }
+
+    /// Returns this list of constraints with a constraint requiring that `lhs` is equal to `rhs`
+    /// appended to it; the constraint is described by the expression `lhs - rhs`.
+    pub fn with_equality(self, lhs: ConstraintExpr<B>, rhs: ConstraintExpr<B>) -> Self {
+        self.with_constraint(lhs - rhs)
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns expressions describing the constraints in this list.
+    pub fn constraints(&self) -> &[ConstraintExpr<B>] {
+        &self.constraints
+    }
+
+    /// Returns the number of constraints in this list.
+    pub fn num_constraints(&self) -> usize {
+        self.constraints.len()
+    }
+
+    /// Returns degrees of the constraints in this list inferred from their expressions.
+    ///
+    /// # Panics
+    /// Panics if any of the expressions does not depend on any trace columns.
+    pub fn degrees(&self) -> Vec<TransitionConstraintDegree> {
+        self.constraints.iter().map(|expr| expr.degree()).collect()
+    }
+
+    // CONSTRAINT EVALUATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates the constraints in this list against the provided evaluation frame and values of
+    /// periodic columns, and writes the results into the first elements of `result`.
+    ///
+    /// # Panics
+    /// Panics if `result` is shorter than the number of constraints in this list.
+    pub fn evaluate<E>(&self, frame: &EvaluationFrame<E>, periodic_values: &[E], result: &mut [E])
+    where
+        E: FieldElement<BaseField = B>,
+    {
+        for (expr, result) in
+            self.constraints.iter().zip(result[..self.constraints.len()].iter_mut())
+        {
+            *result = expr.evaluate(frame, periodic_values);
+        }
+    }
+}
+
+impl<B: StarkField> Default for SymbolicConstraints<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// EXPRESSION DEGREE
+// ================================================================================================
+/// Degree of a constraint expression; this has the same meaning as the degree described by
+/// [TransitionConstraintDegree], but the base degree may be zero.
+///
+/// Degrees are ordered by the evaluation degree of the expression over a trace of length
+/// 2^[DEGREE_COMPARISON_LENGTH_LOG2], which orders degrees in the same way as for all long
+/// enough traces.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ExprDegree {
+    base: usize,
+    cycles: Vec<usize>,
+}
+
+impl ExprDegree {
+    fn new(base: usize, cycles: Vec<usize>) -> Self {
+        ExprDegree { base, cycles }
+    }
+
+    /// Returns the degree of a sum of expressions with this degree and the `other` degree.
+    ///
+    /// This is the larger of the two degrees. If the larger degree does not depend on trace
+    /// columns (e.g., it comes from a summand built only from periodic columns), its base degree
+    /// is raised to the base degree of the other summand so that the result remains an upper
+    /// bound on the degrees of both summands.
+    fn add(&self, other: &Self) -> Self {
+        let mut result = cmp::max(self, other).clone();
+        if result.base == 0 {
+            result.base = cmp::max(self.base, other.base);
+        }
+        result
+    }
+
+    /// Returns the degree of a product of expressions with this degree and the `other` degree.
+    fn mul(&self, other: &Self) -> Self {
+        let mut cycles = self.cycles.clone();
+        cycles.extend_from_slice(&other.cycles);
+        ExprDegree::new(self.base + other.base, cycles)
+    }
+
+    /// Returns the evaluation degree of an expression with this degree over a trace of length
+    /// 2^[DEGREE_COMPARISON_LENGTH_LOG2].
+    fn comparison_degree(&self) -> u128 {
+        let trace_length = 1u128 << DEGREE_COMPARISON_LENGTH_LOG2;
+        let mut result = self.base as u128 * (trace_length - 1);
+        for &cycle_length in self.cycles.iter() {
+            let cycle_length = cycle_length as u128;
+            result += (trace_length / cycle_length) * (cycle_length - 1);
+        }
+        result
+    }
+}
+
+impl PartialOrd for ExprDegree {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ExprDegree {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.comparison_degree()
+            .cmp(&other.comparison_degree())
+            .then_with(|| self.base.cmp(&other.base))
+            .then_with(|| self.cycles.cmp(&other.cycles))
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use math::fields::f128::BaseElement;
+
+    #[test]
+    fn constraint_expr_degree() {
+        let frame = SymbolicFrame::<BaseElement>::new();
+
+        // linear expressions have degree 1
+        let expr = frame.next(0) - frame.current(0) - frame.current(1);
+        assert_eq!(TransitionConstraintDegree::new(1), expr.degree());
+
+        // degrees of factors are added up, and constants do not contribute to the degree
+        let two = ConstraintExpr::constant(BaseElement::new(2));
+        let expr = frame.next(0) - two * frame.current(0) * frame.current(1);
+        assert_eq!(TransitionConstraintDegree::new(2), expr.degree());
+        let expr = frame.next(0) - frame.current(0).exp(3) + frame.current(1);
+        assert_eq!(TransitionConstraintDegree::new(3), expr.degree());
+
+        // periodic columns contribute cycles
+        let k = frame.periodic(0, 8);
+        let expr = k.clone() * (frame.next(0) - frame.current(0).exp(2));
+        assert_eq!(TransitionConstraintDegree::with_cycles(2, vec![8]), expr.degree());
+        let expr = frame.next(0) - frame.current(0) - k.clone();
+        assert_eq!(TransitionConstraintDegree::new(1), expr.degree());
+
+        // a summand which depends only on periodic columns may dominate the degree of a sum
+        let expr = frame.next(0) - frame.current(0) - k.exp(2);
+        let degree = expr.degree();
+        assert_eq!(TransitionConstraintDegree::with_cycles(1, vec![8, 8]), degree);
+        for trace_length in [8, 16, 1024] {
+            let periodic_degree = 2 * (trace_length / 8) * 7;
+            assert!(degree.get_evaluation_degree(trace_length) >= periodic_degree);
+            assert!(degree.get_evaluation_degree(trace_length) >= trace_length - 1);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "constraint expression must depend on at least one trace column")]
+    fn constraint_expr_degree_constant() {
+        let frame = SymbolicFrame::<BaseElement>::new();
+        let expr = frame.periodic(0, 4) - ConstraintExpr::constant(BaseElement::ONE);
+        expr.degree();
+    }
+
+    #[test]
+    fn constraint_expr_evaluate() {
+        let frame = SymbolicFrame::<BaseElement>::new();
+        let two = ConstraintExpr::constant(BaseElement::new(2));
+        let expr = frame.next(1) - two * frame.current(0).exp(2) + -frame.periodic(0, 2);
+
+        // 7 - 2 * 3^2 - 5 = -16
+        let rows = vec![BaseElement::new(3), BaseElement::new(4)];
+        let next = vec![BaseElement::new(6), BaseElement::new(7)];
+        let eval_frame = EvaluationFrame::from_rows(rows, next);
+        let periodic_values = [BaseElement::new(5)];
+        assert_eq!(-BaseElement::new(16), expr.evaluate(&eval_frame, &periodic_values));
+    }
+
+    #[test]
+    fn symbolic_constraints() {
+        let frame = SymbolicFrame::<BaseElement>::new();
+        let constraints = SymbolicConstraints::new()
+            .with_equality(frame.next(0), frame.current(0) + frame.current(1))
+            .with_constraint(frame.next(1) - frame.current(1) * frame.next(0));
+        assert_eq!(2, constraints.num_constraints());
+        assert_eq!(
+            vec![TransitionConstraintDegree::new(1), TransitionConstraintDegree::new(2)],
+            constraints.degrees()
+        );
+
+        // a valid transition evaluates to zeros; (2, 3) -> (5, 15)
+        let current = vec![BaseElement::new(2), BaseElement::new(3)];
+        let next = vec![BaseElement::new(5), BaseElement::new(15)];
+        let eval_frame = EvaluationFrame::from_rows(current, next);
+        let mut result = vec![BaseElement::ONE; 3];
+        constraints.evaluate(&eval_frame, &[], &mut result);
+        assert_eq!(vec![BaseElement::ZERO, BaseElement::ZERO, BaseElement::ONE], result);
+
+        // an invalid transition does not
+        let current = vec![BaseElement::new(2), BaseElement::new(3)];
+        let next = vec![BaseElement::new(5), BaseElement::new(16)];
+        let eval_frame = EvaluationFrame::from_rows(current, next);
+        constraints.evaluate(&eval_frame, &[], &mut result);
+        assert_eq!(BaseElement::ZERO, result[0]);
+        assert_eq!(BaseElement::ONE, result[1]);
+    }
+}
@@ -12,10 +12,18 @@ use super::{FieldElement, Vec};
 /// In the current implementation, an evaluation frame always contains two consecutive rows of the
 /// execution trace. It is passed in as one of the parameters into
 /// [Air::evaluate_transition()](crate::Air::evaluate_transition) function.
+///
+/// A frame can optionally carry conjugated views of its rows. The conjugated rows contain
+/// evaluations of trace polynomials with [frobenius](FieldElement::frobenius) applied to each of
+/// their coefficients; over the trace and LDE domains this is the same as applying frobenius
+/// to the values of the rows. Conjugated views are available only for frames created via
+/// [EvaluationFrame::with_conjugates()].
 #[derive(Debug, Clone)]
 pub struct EvaluationFrame<E: FieldElement> {
     current: Vec<E>,
     next: Vec<E>,
+    current_conjugate: Vec<E>,
+    next_conjugate: Vec<E>,
 }
 
 impl<E: FieldElement> EvaluationFrame<E> {
@@ -31,9 +39,23 @@ impl<E: FieldElement> EvaluationFrame<E> {
         EvaluationFrame {
             current: E::zeroed_vector(num_columns),
             next: E::zeroed_vector(num_columns),
+            current_conjugate: Vec::new(),
+            next_conjugate: Vec::new(),
         }
     }
 
+    /// Returns a new evaluation frame instantiated with the specified number of columns which
+    /// also provides conjugated views of its rows.
+    ///
+    /// # Panics
+    /// Panics if `num_columns` is zero.
+    pub fn with_conjugates(num_columns: usize) -> Self {
+        let mut frame = Self::new(num_columns);
+        frame.current_conjugate = E::zeroed_vector(num_columns);
+        frame.next_conjugate = E::zeroed_vector(num_columns);
+        frame
+    }
+
     /// Returns a new evaluation frame instantiated from the provided rows.
     ///
     /// # Panics
@@ -43,7 +65,41 @@ impl<E: FieldElement> EvaluationFrame<E> {
     pub fn from_rows(current: Vec<E>, next: Vec<E>) -> Self {
         assert!(!current.is_empty(), "a row must contain at least one value");
         assert_eq!(current.len(), next.len(), "number of values in the rows must be the same");
-        Self { current, next }
+        Self {
+            current,
+            next,
+            current_conjugate: Vec::new(),
+            next_conjugate: Vec::new(),
+        }
+    }
+
+    /// Returns a new evaluation frame instantiated from the provided rows and their conjugated
+    /// views.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * Lengths of the provided rows are zero.
+    /// * Lengths of the provided rows are not the same.
+    pub fn from_rows_with_conjugates(
+        current: Vec<E>,
+        next: Vec<E>,
+        current_conjugate: Vec<E>,
+        next_conjugate: Vec<E>,
+    ) -> Self {
+        let mut frame = Self::from_rows(current, next);
+        assert_eq!(
+            frame.current.len(),
+            current_conjugate.len(),
+            "number of values in the conjugated rows must be the same as in the rows"
+        );
+        assert_eq!(
+            frame.next.len(),
+            next_conjugate.len(),
+            "number of values in the conjugated rows must be the same as in the rows"
+        );
+        frame.current_conjugate = current_conjugate;
+        frame.next_conjugate = next_conjugate;
+        frame
     }
 
     // ROW ACCESSORS
@@ -61,6 +117,27 @@ impl<E: FieldElement> EvaluationFrame<E> {
         &self.next
     }
 
+    /// Returns true if this frame provides conjugated views of its rows.
+    pub fn has_conjugates(&self) -> bool {
+        !self.current_conjugate.is_empty()
+    }
+
+    /// Returns a reference to the conjugated view of the current row.
+    ///
+    /// The returned slice is empty if this frame does not provide conjugated views.
+    #[inline(always)]
+    pub fn current_conjugate(&self) -> &[E] {
+        &self.current_conjugate
+    }
+
+    /// Returns a reference to the conjugated view of the next row.
+    ///
+    /// The returned slice is empty if this frame does not provide conjugated views.
+    #[inline(always)]
+    pub fn next_conjugate(&self) -> &[E] {
+        &self.next_conjugate
+    }
+
     // DATA MUTATORS
     // --------------------------------------------------------------------------------------------
 
@@ -75,4 +152,21 @@ impl<E: FieldElement> EvaluationFrame<E> {
     pub fn next_mut(&mut self) -> &mut [E] {
         &mut self.next
     }
+
+    /// Recomputes conjugated views of the rows by applying frobenius to each row value.
+    ///
+    /// This is valid only when the rows hold evaluations of trace polynomials at points in the
+    /// base field (e.g., over the trace or LDE domains). If this frame does not provide
+    /// conjugated views, this is a no-op.
+    pub fn update_conjugates(&mut self) {
+        if !self.has_conjugates() {
+            return;
+        }
+        for (conjugate, value) in self.current_conjugate.iter_mut().zip(self.current.iter()) {
+            *conjugate = value.frobenius();
+        }
+        for (conjugate, value) in self.next_conjugate.iter_mut().zip(self.next.iter()) {
+            *conjugate = value.frobenius();
+        }
+    }
 }
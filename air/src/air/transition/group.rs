@@ -0,0 +1,110 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{AirContext, FieldElement, TransitionConstraintDegree, Vec};
+use math::StarkField;
+
+// TRANSITION CONSTRAINT GROUP
+// ================================================================================================
+/// A group of transition constraints all having the same degree.
+///
+/// A group refers to its constraints by their indexes in the list of transition constraints of
+/// a single trace segment (i.e., the order in which constraint degrees are listed in
+/// [AirContext] and in which constraint evaluations are written by the AIR). Since all
+/// constraints in a group have the same degree, the quotients of these constraints by the
+/// transition divisor need the same degree adjustment to reach the degree of the constraint
+/// composition polynomial. Thus, custom constraint evaluators can process all constraints of a
+/// group together.
+///
+/// Groups for all transition constraints of a computation can be obtained via
+/// [TransitionConstraints::main_constraint_groups()](super::TransitionConstraints::main_constraint_groups)
+/// and
+/// [TransitionConstraints::aux_constraint_groups()](super::TransitionConstraints::aux_constraint_groups).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransitionConstraintGroup {
+    degree: TransitionConstraintDegree,
+    indexes: Vec<usize>,
+}
+
+impl TransitionConstraintGroup {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new group of transition constraints with the specified degree located at the
+    /// specified indexes.
+    ///
+    /// # Panics
+    /// Panics if `constraint_indices` is empty.
+    pub fn new(degree: TransitionConstraintDegree, constraint_indices: Vec<usize>) -> Self {
+        assert!(
+            !constraint_indices.is_empty(),
+            "a transition constraint group must contain at least one constraint"
+        );
+        TransitionConstraintGroup {
+            degree,
+            indexes: constraint_indices,
+        }
+    }
+
+    /// Groups transition constraints with the specified degrees by degree.
+    ///
+    /// The groups are listed in the order in which their degrees first appear in `degrees`, and
+    /// the indexes within each group are sorted in increasing order.
+    pub fn group_by_degree(degrees: &[TransitionConstraintDegree]) -> Vec<Self> {
+        let mut groups: Vec<Self> = Vec::new();
+        for (index, degree) in degrees.iter().enumerate() {
+            match groups.iter_mut().find(|group| group.degree == *degree) {
+                Some(group) => group.indexes.push(index),
+                None => groups.push(Self::new(degree.clone(), vec![index])),
+            }
+        }
+        groups
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the degree of all constraints in this group.
+    pub fn degree(&self) -> &TransitionConstraintDegree {
+        &self.degree
+    }
+
+    /// Returns indexes of the constraints in this group.
+    pub fn indexes(&self) -> &[usize] {
+        &self.indexes
+    }
+
+    /// Returns the number of constraints in this group.
+    pub fn num_constraints(&self) -> usize {
+        self.indexes.len()
+    }
+
+    /// Returns the number by which the degree of the quotients of the constraints in this group
+    /// by the transition divisor must be increased to reach the degree of the constraint
+    /// composition polynomial of a computation described by the specified context.
+    ///
+    /// For a trace of length $n$, the degree of the constraint composition polynomial is
+    /// $m \cdot n - 1$, where $m$ is the number of composition columns (see
+    /// [AirContext::num_constraint_composition_columns()]). Thus, for constraints with evaluation
+    /// degree $d$ and a transition divisor of degree $t$, the adjustment is
+    /// $m \cdot n - 1 - (d - t)$.
+    pub fn degree_adjustment<B: StarkField>(&self, context: &AirContext<B>) -> usize {
+        let trace_length = context.trace_len();
+        let composition_degree = context.num_constraint_composition_columns() * trace_length - 1;
+        let divisor_degree = trace_length - context.num_transition_exemptions();
+        let quotient_degree = self.degree.get_evaluation_degree(trace_length) - divisor_degree;
+        composition_degree - quotient_degree
+    }
+
+    /// Returns composition coefficients of the constraints in this group, given composition
+    /// coefficients of all transition constraints of the same trace segment.
+    ///
+    /// The coefficients are listed in the same order as [indexes()](Self::indexes).
+    ///
+    /// # Panics
+    /// Panics if any of the constraint indexes is out of bounds for `coefficients`.
+    pub fn composition_coefficients<E: FieldElement>(&self, coefficients: &[E]) -> Vec<E> {
+        self.indexes.iter().map(|&index| coefficients[index]).collect()
+    }
+}
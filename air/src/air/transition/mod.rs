@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{AirContext, ConstraintDivisor, ExtensionOf, FieldElement, Vec};
+use super::{AirContext, ConstraintDivisor, ExtensionOf, FieldElement, StarkField, Vec};
 
 mod frame;
 pub use frame::EvaluationFrame;
@@ -11,6 +11,24 @@ pub use frame::EvaluationFrame;
 mod degree;
 pub use degree::TransitionConstraintDegree;
 
+mod polynomial;
+pub use polynomial::PolynomialConstraint;
+
+mod rational;
+pub use rational::RationalConstraint;
+
+mod group;
+pub use group::TransitionConstraintGroup;
+
+mod accumulator;
+pub use accumulator::{HashAccumulator, HashFunction};
+
+mod expression;
+pub use expression::{ConstraintExpr, SymbolicConstraints, SymbolicFrame};
+
+mod wraparound;
+pub use wraparound::WraparoundConstraints;
+
 // CONSTANTS
 // ================================================================================================
 
@@ -25,12 +43,15 @@ const MIN_CYCLE_LENGTH: usize = 2;
 ///   trace segments (if any).
 /// - Groupings of random composition constraint coefficients separately for the main trace segment
 ///   and for auxiliary tace segment.
+/// - Degree adjustments applied to transition constraints during composition.
 /// - Divisor of transition constraints for a computation.
 pub struct TransitionConstraints<E: FieldElement> {
     main_constraint_coef: Vec<E>,
     main_constraint_degrees: Vec<TransitionConstraintDegree>,
+    main_degree_adjustments: Vec<usize>,
     aux_constraint_coef: Vec<E>,
     aux_constraint_degrees: Vec<TransitionConstraintDegree>,
+    aux_degree_adjustments: Vec<usize>,
     divisor: ConstraintDivisor<E::BaseField>,
 }
 
@@ -40,10 +61,38 @@ impl<E: FieldElement> TransitionConstraints<E> {
     /// Returns a new instance of [TransitionConstraints] for a computation described by the
     /// specified AIR context.
     ///
+    /// No degree adjustment is applied to any of the constraints.
+    ///
     /// # Panics
     /// Panics if the number of transition constraints in the context does not match the number of
     /// provided composition coefficients.
     pub fn new(context: &AirContext<E::BaseField>, composition_coefficients: &[E]) -> Self {
+        Self::with_degree_adjustments(context, composition_coefficients, &[])
+    }
+
+    /// Returns a new instance of [TransitionConstraints] for a computation described by the
+    /// specified AIR context, with the specified degree adjustments applied to the constraints.
+    ///
+    /// `degree_adjustments` must contain one value per transition constraint, listing the
+    /// constraints of the main trace segment first followed by the constraints of auxiliary trace
+    /// segments. During composition, the quotient of the $i$th constraint by the transition
+    /// divisor is multiplied by $x^{a_i}$, where $a_i$ is its degree adjustment. An empty list is
+    /// equivalent to all adjustments being zero.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * The number of transition constraints in the context does not match the number of
+    ///   provided composition coefficients.
+    /// * `degree_adjustments` is not empty and its length does not match the number of transition
+    ///   constraints.
+    /// * The degree adjustment of any constraint is greater than the degree adjustment of its
+    ///   group (see [TransitionConstraintGroup::degree_adjustment()]), as the adjusted constraint
+    ///   would then exceed the degree of the constraint composition polynomial.
+    pub fn with_degree_adjustments(
+        context: &AirContext<E::BaseField>,
+        composition_coefficients: &[E],
+        degree_adjustments: &[usize],
+    ) -> Self {
         assert_eq!(
             context.num_transition_constraints(),
             composition_coefficients.len(),
@@ -61,11 +110,30 @@ impl<E: FieldElement> TransitionConstraints<E> {
 
         let (main_constraint_coef, aux_constraint_coef) =
             composition_coefficients.split_at(context.main_transition_constraint_degrees.len());
+
+        // make sure adjusted constraints do not exceed the degree of the composition polynomial
+        let (main_degree_adjustments, aux_degree_adjustments) = if degree_adjustments.is_empty() {
+            (Vec::new(), Vec::new())
+        } else {
+            assert_eq!(
+                context.num_transition_constraints(),
+                degree_adjustments.len(),
+                "number of transition constraints must match the number of degree adjustments"
+            );
+            let (main_adjustments, aux_adjustments) =
+                degree_adjustments.split_at(main_constraint_degrees.len());
+            validate_degree_adjustments(context, &main_constraint_degrees, main_adjustments);
+            validate_degree_adjustments(context, &aux_constraint_degrees, aux_adjustments);
+            (main_adjustments.to_vec(), aux_adjustments.to_vec())
+        };
+
         Self {
             main_constraint_coef: main_constraint_coef.to_vec(),
             main_constraint_degrees,
+            main_degree_adjustments,
             aux_constraint_coef: aux_constraint_coef.to_vec(),
             aux_constraint_degrees,
+            aux_degree_adjustments,
             divisor,
         }
     }
@@ -94,6 +162,24 @@ impl<E: FieldElement> TransitionConstraints<E> {
         self.main_constraint_coef.clone()
     }
 
+    /// Returns degree adjustments of constraints applied against the main trace segment of a
+    /// computation.
+    ///
+    /// The returned slice is empty if no degree adjustment is applied to the constraints.
+    pub fn main_degree_adjustments(&self) -> &[usize] {
+        &self.main_degree_adjustments
+    }
+
+    /// Returns constraints applied against the main trace segment of a computation grouped by
+    /// their degree.
+    ///
+    /// Composition coefficients of the constraints in each group can be obtained by passing
+    /// [main_constraint_coef()](Self::main_constraint_coef) to
+    /// [TransitionConstraintGroup::composition_coefficients()].
+    pub fn main_constraint_groups(&self) -> Vec<TransitionConstraintGroup> {
+        TransitionConstraintGroup::group_by_degree(&self.main_constraint_degrees)
+    }
+
     /// Returns a list of transition constraint degree descriptors for auxiliary trace segments of
     /// a computation.
     ///
@@ -115,6 +201,25 @@ impl<E: FieldElement> TransitionConstraints<E> {
         self.aux_constraint_coef.clone()
     }
 
+    /// Returns degree adjustments of constraints applied against auxiliary trace segments of a
+    /// computation.
+    ///
+    /// The returned slice is empty if no degree adjustment is applied to the constraints.
+    pub fn aux_degree_adjustments(&self) -> &[usize] {
+        &self.aux_degree_adjustments
+    }
+
+    /// Returns constraints applied against auxiliary trace segments of a computation grouped by
+    /// their degree.
+    ///
+    /// Constraint indexes are relative to the list of auxiliary constraints; composition
+    /// coefficients of the constraints in each group can be obtained by passing
+    /// [aux_constraint_coef()](Self::aux_constraint_coef) to
+    /// [TransitionConstraintGroup::composition_coefficients()].
+    pub fn aux_constraint_groups(&self) -> Vec<TransitionConstraintGroup> {
+        TransitionConstraintGroup::group_by_degree(&self.aux_constraint_degrees)
+    }
+
     /// Returns a divisor for transition constraints.
     ///
     /// All transition constraints have the same divisor which has the form:
@@ -133,6 +238,60 @@ impl<E: FieldElement> TransitionConstraints<E> {
     // CONSTRAINT COMPOSITION
     // --------------------------------------------------------------------------------------------
 
+    /// Computes a linear combination of transition constraint evaluations for the main trace
+    /// segment at the specified `x`.
+    ///
+    /// If degree adjustments are defined for the constraints, the evaluation of each constraint is
+    /// also multiplied by `x` raised to the power of its degree adjustment.
+    pub fn merge_main_evaluations<F>(&self, evaluations: &[F], x: F) -> E
+    where
+        F: FieldElement<BaseField = E::BaseField>,
+        E: ExtensionOf<F>,
+    {
+        if self.main_degree_adjustments.is_empty() {
+            return evaluations
+                .iter()
+                .zip(self.main_constraint_coef.iter())
+                .fold(E::ZERO, |acc, (&const_eval, &coef)| acc + coef.mul_base(const_eval));
+        }
+
+        let mut adjustment = AdjustmentCache::new(x);
+        evaluations
+            .iter()
+            .zip(self.main_constraint_coef.iter())
+            .zip(self.main_degree_adjustments.iter())
+            .fold(E::ZERO, |acc, ((&const_eval, &coef), &degree_adjustment)| {
+                acc + coef.mul_base(const_eval * adjustment.get(degree_adjustment))
+            })
+    }
+
+    /// Computes a linear combination of transition constraint evaluations for auxiliary trace
+    /// segments at the specified `x`.
+    ///
+    /// If degree adjustments are defined for the constraints, the evaluation of each constraint is
+    /// also multiplied by `x` raised to the power of its degree adjustment.
+    pub fn merge_aux_evaluations<F>(&self, evaluations: &[E], x: F) -> E
+    where
+        F: FieldElement<BaseField = E::BaseField>,
+        E: ExtensionOf<F>,
+    {
+        if self.aux_degree_adjustments.is_empty() {
+            return evaluations
+                .iter()
+                .zip(self.aux_constraint_coef.iter())
+                .fold(E::ZERO, |acc, (&const_eval, &coef)| acc + coef * const_eval);
+        }
+
+        let mut adjustment = AdjustmentCache::new(x);
+        evaluations
+            .iter()
+            .zip(self.aux_constraint_coef.iter())
+            .zip(self.aux_degree_adjustments.iter())
+            .fold(E::ZERO, |acc, ((&const_eval, &coef), &degree_adjustment)| {
+                acc + (coef * const_eval).mul_base(adjustment.get(degree_adjustment))
+            })
+    }
+
     /// Computes a linear combination of all transition constraint evaluations and divides the
     /// result by transition constraint divisor.
     ///
@@ -141,7 +300,9 @@ impl<E: FieldElement> TransitionConstraints<E> {
     /// * $C(x)$ is the constraint polynomial.
     /// * $z(x)$ is the constraint divisor polynomial.
     ///
-    /// Thus, this function computes a linear combination of $C(x)$ evaluations.
+    /// Thus, this function computes a linear combination of $C(x)$ evaluations, adjusting the
+    /// degree of each evaluation as described in
+    /// [with_degree_adjustments()](Self::with_degree_adjustments).
     ///
     /// Since, the divisor polynomial is the same for all transition constraints (see
     /// [ConstraintDivisor::from_transition]), we can divide the linear combination by the
@@ -153,16 +314,10 @@ impl<E: FieldElement> TransitionConstraints<E> {
         E: ExtensionOf<F>,
     {
         // merge constraint evaluations for the main trace segment
-        let mut result = main_evaluations
-            .iter()
-            .zip(self.main_constraint_coef.iter())
-            .fold(E::ZERO, |acc, (&const_eval, &coef)| acc + coef.mul_base(const_eval));
+        let mut result = self.merge_main_evaluations(main_evaluations, x);
 
         if !self.aux_constraint_coef.is_empty() {
-            result += aux_evaluations
-                .iter()
-                .zip(self.aux_constraint_coef.iter())
-                .fold(E::ZERO, |acc, (&const_eval, &coef)| acc + coef * const_eval);
+            result += self.merge_aux_evaluations(aux_evaluations, x);
         };
         // divide out the evaluation of divisor at x and return the result
         let z = E::from(self.divisor.evaluate_at(x));
@@ -170,3 +325,51 @@ impl<E: FieldElement> TransitionConstraints<E> {
         result / z
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Makes sure that the degree adjustment of each constraint does not exceed the degree adjustment
+/// of its group.
+fn validate_degree_adjustments<B: StarkField>(
+    context: &AirContext<B>,
+    degrees: &[TransitionConstraintDegree],
+    degree_adjustments: &[usize],
+) {
+    for group in TransitionConstraintGroup::group_by_degree(degrees) {
+        let max_adjustment = group.degree_adjustment(context);
+        for &index in group.indexes() {
+            assert!(
+                degree_adjustments[index] <= max_adjustment,
+                "degree adjustment of transition constraint {index} cannot exceed {max_adjustment}, but was {}",
+                degree_adjustments[index]
+            );
+        }
+    }
+}
+
+/// Caches the most recently computed power of `x`; constraints with the same degree adjustment
+/// are usually listed next to each other, and thus, this avoids most exponentiations.
+struct AdjustmentCache<F: FieldElement> {
+    x: F,
+    degree_adjustment: usize,
+    value: F,
+}
+
+impl<F: FieldElement> AdjustmentCache<F> {
+    fn new(x: F) -> Self {
+        Self {
+            x,
+            degree_adjustment: 0,
+            value: F::ONE,
+        }
+    }
+
+    fn get(&mut self, degree_adjustment: usize) -> F {
+        if degree_adjustment != self.degree_adjustment {
+            self.degree_adjustment = degree_adjustment;
+            self.value = self.x.exp((degree_adjustment as u64).into());
+        }
+        self.value
+    }
+}
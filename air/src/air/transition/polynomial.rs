@@ -0,0 +1,183 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{EvaluationFrame, FieldElement, TransitionConstraintDegree, Vec};
+use core::cmp;
+use math::{polynom, StarkField};
+
+// POLYNOMIAL CONSTRAINT
+// ================================================================================================
+/// Describes a relation $c = f(a)$ between two trace columns, where $f$ is a fixed polynomial.
+///
+/// The relation is enforced via a single transition constraint evaluated against the current row
+/// of the evaluation frame:
+///
+/// $$
+/// c - f(a) = 0
+/// $$
+///
+/// As with all transition constraints, this constraint is not enforced on the last row of the
+/// trace (or on the last `n` rows if `n` transition exemptions were specified via
+/// [AirContext::set_num_transition_exemptions()]). Thus, the relation is guaranteed to hold on all
+/// other rows only; if it must hold on the last row as well, the value of the output column in
+/// that row should be fixed via an assertion.
+///
+/// The degree of the constraint is equal to the degree of $f$ (or to 1 if $f$ is a constant).
+///
+/// To use this helper, an AIR should include the descriptor returned by
+/// [degree()](Self::degree) in its transition constraint degrees, and call
+/// [evaluate()](Self::evaluate) from [Air::evaluate_transition()](crate::Air::evaluate_transition).
+/// The trace builder can populate the output column via [fill_row()](Self::fill_row).
+///
+/// [AirContext::set_num_transition_exemptions()]: crate::AirContext::set_num_transition_exemptions
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolynomialConstraint<B: StarkField> {
+    input: usize,
+    output: usize,
+    coefficients: Vec<B>,
+}
+
+impl<B: StarkField> PolynomialConstraint<B> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new polynomial constraint which enforces that the `output` column is equal to
+    /// the polynomial with the specified `coefficients` evaluated at the `input` column.
+    ///
+    /// The coefficients are in reverse order; that is, `coefficients[i]` is the coefficient of
+    /// $a^i$. Leading zero coefficients do not contribute to the degree of the constraint.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `coefficients` is empty.
+    /// * The `input` column is the same as the `output` column.
+    pub fn new(input: usize, output: usize, coefficients: Vec<B>) -> Self {
+        assert!(!coefficients.is_empty(), "polynomial coefficients cannot be empty");
+        assert!(input != output, "output column {output} must be distinct from the input column");
+        PolynomialConstraint {
+            input,
+            output,
+            coefficients,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the index of the input column.
+    pub fn input(&self) -> usize {
+        self.input
+    }
+
+    /// Returns the index of the output column.
+    pub fn output(&self) -> usize {
+        self.output
+    }
+
+    /// Returns coefficients of the polynomial in reverse order.
+    pub fn coefficients(&self) -> &[B] {
+        &self.coefficients
+    }
+
+    /// Returns the degree descriptor of the transition constraint enforced by this polynomial
+    /// constraint.
+    pub fn degree(&self) -> TransitionConstraintDegree {
+        let degree = polynom::degree_of(&self.coefficients);
+        TransitionConstraintDegree::new(cmp::max(degree, 1))
+    }
+
+    // CONSTRAINT EVALUATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates the transition constraint enforced by this polynomial constraint against the
+    /// current row of the provided evaluation frame and returns the result.
+    pub fn evaluate<E>(&self, frame: &EvaluationFrame<E>) -> E
+    where
+        E: FieldElement<BaseField = B>,
+    {
+        let row = frame.current();
+        row[self.output] - polynom::eval(&self.coefficients, row[self.input])
+    }
+
+    // TRACE GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Populates the output column of the provided trace row from the input column of the row.
+    pub fn fill_row<E>(&self, row: &mut [E])
+    where
+        E: FieldElement<BaseField = B>,
+    {
+        row[self.output] = polynom::eval(&self.coefficients, row[self.input]);
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use math::fields::f128::BaseElement;
+
+    /// Returns a constraint for c = 3 * a^2 + 2 * a + 1 between columns 0 and 1.
+    fn build_quadratic_constraint() -> PolynomialConstraint<BaseElement> {
+        let coefficients = vec![BaseElement::new(1), BaseElement::new(2), BaseElement::new(3)];
+        PolynomialConstraint::new(0, 1, coefficients)
+    }
+
+    #[test]
+    fn polynomial_constraint_degree() {
+        let constraint = build_quadratic_constraint();
+        assert_eq!(TransitionConstraintDegree::new(2), constraint.degree());
+
+        // leading zeros do not contribute to the degree
+        let coefficients = vec![BaseElement::new(1), BaseElement::new(2), BaseElement::ZERO];
+        let constraint = PolynomialConstraint::new(0, 1, coefficients);
+        assert_eq!(TransitionConstraintDegree::new(1), constraint.degree());
+
+        // a constant polynomial results in a constraint of degree 1
+        let constraint = PolynomialConstraint::new(0, 1, vec![BaseElement::new(5)]);
+        assert_eq!(TransitionConstraintDegree::new(1), constraint.degree());
+    }
+
+    #[test]
+    fn polynomial_constraint_quadratic() {
+        let constraint = build_quadratic_constraint();
+
+        // 3 * 4^2 + 2 * 4 + 1 = 57
+        let mut row = vec![BaseElement::new(4), BaseElement::ZERO];
+        constraint.fill_row(&mut row);
+        assert_eq!(BaseElement::new(57), row[1]);
+
+        // build a trace in which every row satisfies the relation
+        let mut trace = (0..8u128)
+            .map(|i| vec![BaseElement::new(i * 7 + 3), BaseElement::ZERO])
+            .collect::<Vec<_>>();
+        for row in trace.iter_mut() {
+            constraint.fill_row(row);
+        }
+        for rows in trace.windows(2) {
+            let frame = EvaluationFrame::from_rows(rows[0].clone(), rows[1].clone());
+            assert_eq!(BaseElement::ZERO, constraint.evaluate(&frame));
+        }
+
+        // a trace with a single violating row fails the constraint at that row only
+        trace[5][1] += BaseElement::ONE;
+        for (i, rows) in trace.windows(2).enumerate() {
+            let frame = EvaluationFrame::from_rows(rows[0].clone(), rows[1].clone());
+            let evaluation = constraint.evaluate(&frame);
+            if i == 5 {
+                assert_eq!(BaseElement::ONE, evaluation);
+            } else {
+                assert_eq!(BaseElement::ZERO, evaluation);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "output column 2 must be distinct from the input column")]
+    fn polynomial_constraint_overlapping_columns() {
+        PolynomialConstraint::new(2, 2, vec![BaseElement::ONE]);
+    }
+}
@@ -0,0 +1,217 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{EvaluationFrame, FieldElement, TransitionConstraintDegree};
+
+// RATIONAL CONSTRAINT
+// ================================================================================================
+/// Describes a quotient of two trace columns enforced via a committed inverse column.
+///
+/// Constraints cannot express division directly. Instead, for a quotient $r = a / b$, the
+/// execution trace is extended with a column $b^{-1}$ holding the inverse of the denominator and
+/// a column $r$ holding the quotient, and the following two transition constraints are enforced
+/// on all steps to which transition constraints apply:
+///
+/// $$
+/// b \cdot b^{-1} - 1 = 0 \\
+/// r - a \cdot b^{-1} = 0
+/// $$
+///
+/// The first constraint ensures that the inverse column is the inverse of the denominator, and
+/// thus, that the denominator is never zero (a trace with a zero denominator cannot satisfy it).
+/// The second constraint ensures that the result column holds the quotient. Both constraints
+/// have degree 2.
+///
+/// Rational constraints are registered with an AIR via
+/// [AirContext::set_rational_constraints()](crate::AirContext::set_rational_constraints), which
+/// assigns the inverse and the result columns and adds the above constraints to the transition
+/// constraints of the main trace segment. The prover then appends these columns to the main trace
+/// segment and populates them via [fill_row()](Self::fill_row), and both the prover and the
+/// verifier evaluate the constraints via [evaluate()](Self::evaluate). Thus, an AIR only needs to
+/// refer to the [result()](Self::result) column in its own constraints.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RationalConstraint {
+    numerator: usize,
+    denominator: usize,
+    inverse: usize,
+    result: usize,
+}
+
+impl RationalConstraint {
+    /// Number of transition constraints enforced by a rational constraint.
+    pub const NUM_CONSTRAINTS: usize = 2;
+
+    /// Number of columns appended to the main trace segment for a rational constraint.
+    pub const NUM_COLUMNS: usize = 2;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new rational constraint for the quotient of the `numerator` and `denominator`
+    /// columns, where the `inverse` column holds the inverse of the denominator and the `result`
+    /// column holds the quotient.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * The `inverse` column is the same as any of the other columns.
+    /// * The `result` column is the same as any of the other columns.
+    pub(crate) fn new(numerator: usize, denominator: usize, inverse: usize, result: usize) -> Self {
+        assert!(
+            inverse != numerator && inverse != denominator && inverse != result,
+            "inverse column {inverse} must be distinct from all other columns"
+        );
+        assert!(
+            result != numerator && result != denominator,
+            "result column {result} must be distinct from all other columns"
+        );
+        RationalConstraint {
+            numerator,
+            denominator,
+            inverse,
+            result,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the index of the numerator column.
+    pub fn numerator(&self) -> usize {
+        self.numerator
+    }
+
+    /// Returns the index of the denominator column.
+    pub fn denominator(&self) -> usize {
+        self.denominator
+    }
+
+    /// Returns the index of the column holding the inverse of the denominator.
+    pub fn inverse(&self) -> usize {
+        self.inverse
+    }
+
+    /// Returns the index of the column holding the quotient.
+    pub fn result(&self) -> usize {
+        self.result
+    }
+
+    /// Returns degree descriptors of the transition constraints enforced by this rational
+    /// constraint, in the same order as they are evaluated by [evaluate()](Self::evaluate).
+    pub fn degrees(&self) -> [TransitionConstraintDegree; Self::NUM_CONSTRAINTS] {
+        [TransitionConstraintDegree::new(2), TransitionConstraintDegree::new(2)]
+    }
+
+    // CONSTRAINT EVALUATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates the transition constraints enforced by this rational constraint against the
+    /// current row of the provided evaluation frame and writes the results into `result`.
+    ///
+    /// # Panics
+    /// Panics if `result` does not contain exactly [NUM_CONSTRAINTS](Self::NUM_CONSTRAINTS)
+    /// elements.
+    pub fn evaluate<E: FieldElement>(&self, frame: &EvaluationFrame<E>, result: &mut [E]) {
+        assert_eq!(
+            Self::NUM_CONSTRAINTS,
+            result.len(),
+            "expected {} constraint evaluations, but received {}",
+            Self::NUM_CONSTRAINTS,
+            result.len()
+        );
+        let row = frame.current();
+        let inverse = row[self.inverse];
+        result[0] = row[self.denominator] * inverse - E::ONE;
+        result[1] = row[self.result] - row[self.numerator] * inverse;
+    }
+
+    // TRACE GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Populates the inverse and the result columns of the provided trace row from the numerator
+    /// and denominator columns of the row.
+    ///
+    /// If the denominator is zero, the inverse and the result are set to zero; such a row does
+    /// not satisfy the constraints.
+    pub fn fill_row<E: FieldElement>(&self, row: &mut [E]) {
+        let inverse = row[self.denominator].inv();
+        row[self.inverse] = inverse;
+        row[self.result] = row[self.numerator] * inverse;
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use math::fields::f128::BaseElement;
+    use rand_utils::rand_vector;
+    use utils::collections::Vec;
+
+    #[test]
+    fn rational_constraint_satisfied() {
+        let constraint = RationalConstraint::new(0, 1, 2, 3);
+        assert_eq!(
+            [TransitionConstraintDegree::new(2), TransitionConstraintDegree::new(2)],
+            constraint.degrees()
+        );
+
+        let mut row =
+            vec![BaseElement::new(15), BaseElement::new(5), BaseElement::ZERO, BaseElement::ZERO];
+        constraint.fill_row(&mut row);
+        assert_eq!(BaseElement::new(5).inv(), row[2]);
+        assert_eq!(BaseElement::new(3), row[3]);
+
+        let frame = EvaluationFrame::from_rows(row.clone(), row);
+        let mut result = [BaseElement::ONE; RationalConstraint::NUM_CONSTRAINTS];
+        constraint.evaluate(&frame, &mut result);
+        assert_eq!([BaseElement::ZERO; RationalConstraint::NUM_CONSTRAINTS], result);
+    }
+
+    #[test]
+    fn rational_constraint_wrong_result() {
+        let constraint = RationalConstraint::new(0, 1, 2, 3);
+        let mut row =
+            vec![BaseElement::new(15), BaseElement::new(5), BaseElement::ZERO, BaseElement::ZERO];
+        constraint.fill_row(&mut row);
+        row[3] = BaseElement::new(4);
+
+        let frame = EvaluationFrame::from_rows(row.clone(), row);
+        let mut result = [BaseElement::ZERO; RationalConstraint::NUM_CONSTRAINTS];
+        constraint.evaluate(&frame, &mut result);
+        assert_eq!(BaseElement::ZERO, result[0]);
+        assert_ne!(BaseElement::ZERO, result[1]);
+    }
+
+    #[test]
+    fn rational_constraint_zero_denominator() {
+        let constraint = RationalConstraint::new(0, 1, 2, 3);
+
+        // a row populated from a zero denominator does not satisfy the constraints
+        let mut row =
+            vec![BaseElement::new(15), BaseElement::ZERO, BaseElement::ZERO, BaseElement::ZERO];
+        constraint.fill_row(&mut row);
+        let frame = EvaluationFrame::from_rows(row.clone(), row.clone());
+        let mut result = [BaseElement::ZERO; RationalConstraint::NUM_CONSTRAINTS];
+        constraint.evaluate(&frame, &mut result);
+        assert_ne!(BaseElement::ZERO, result[0]);
+
+        // and neither does a row with any other value in the inverse or the result columns
+        let values: Vec<BaseElement> = rand_vector(16);
+        for pair in values.chunks(2) {
+            row[2] = pair[0];
+            row[3] = pair[1];
+            let frame = EvaluationFrame::from_rows(row.clone(), row.clone());
+            constraint.evaluate(&frame, &mut result);
+            assert_eq!(-BaseElement::ONE, result[0]);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "inverse column 1 must be distinct from all other columns")]
+    fn rational_constraint_overlapping_columns() {
+        RationalConstraint::new(0, 1, 1, 3);
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{AirContext, ConstraintDivisor, ExtensionOf, FieldElement, TransitionConstraintDegree};
+use utils::collections::Vec;
+
+// WRAPAROUND CONSTRAINTS
+// ================================================================================================
+/// Metadata for wraparound constraints of a computation.
+///
+/// A wraparound constraint relates the last row of the main trace segment to its first row. Such
+/// constraints are evaluated against regular evaluation frames, but unlike transition constraints,
+/// they must hold only at the last step of the trace, where the next row of the frame is the first
+/// row of the trace (since the trace domain is cyclic).
+///
+/// This metadata includes:
+/// - List of wraparound constraint degrees.
+/// - Random composition coefficients for wraparound constraints.
+/// - Divisor of wraparound constraints, which is $(x - g^{n - 1})$.
+pub struct WraparoundConstraints<E: FieldElement> {
+    coefficients: Vec<E>,
+    degrees: Vec<TransitionConstraintDegree>,
+    divisor: ConstraintDivisor<E::BaseField>,
+}
+
+impl<E: FieldElement> WraparoundConstraints<E> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new instance of [WraparoundConstraints] for a computation described by the
+    /// specified AIR context.
+    ///
+    /// # Panics
+    /// Panics if the number of wraparound constraints in the context does not match the number of
+    /// provided composition coefficients.
+    pub fn new(context: &AirContext<E::BaseField>, composition_coefficients: &[E]) -> Self {
+        assert_eq!(
+            context.num_wraparound_constraints(),
+            composition_coefficients.len(),
+            "number of wraparound constraints must match the number of composition coefficients"
+        );
+
+        Self {
+            coefficients: composition_coefficients.to_vec(),
+            degrees: context.wraparound_constraint_degrees().to_vec(),
+            divisor: ConstraintDivisor::from_wraparound(context.trace_len()),
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of wraparound constraints of a computation.
+    pub fn num_constraints(&self) -> usize {
+        self.degrees.len()
+    }
+
+    /// Returns true if a computation does not define any wraparound constraints.
+    pub fn is_empty(&self) -> bool {
+        self.degrees.is_empty()
+    }
+
+    /// Returns a list of wraparound constraint degree descriptors.
+    pub fn degrees(&self) -> &[TransitionConstraintDegree] {
+        &self.degrees
+    }
+
+    /// Returns the random coefficients for wraparound constraints.
+    pub fn coefficients(&self) -> &[E] {
+        &self.coefficients
+    }
+
+    /// Returns the divisor of wraparound constraints.
+    pub fn divisor(&self) -> &ConstraintDivisor<E::BaseField> {
+        &self.divisor
+    }
+
+    // CONSTRAINT COMPOSITION
+    // --------------------------------------------------------------------------------------------
+
+    /// Computes a linear combination of wraparound constraint evaluations.
+    ///
+    /// The result is not divided by the divisor; this is left to the caller so that evaluations
+    /// over a domain can be divided in bulk.
+    pub fn merge_evaluations<F>(&self, evaluations: &[F]) -> E
+    where
+        F: FieldElement<BaseField = E::BaseField>,
+        E: ExtensionOf<F>,
+    {
+        evaluations
+            .iter()
+            .zip(self.coefficients.iter())
+            .fold(E::ZERO, |acc, (&eval, &coef)| acc + coef.mul_base(eval))
+    }
+
+    /// Computes a linear combination of wraparound constraint evaluations and divides the result
+    /// by the wraparound constraint divisor evaluated at `x`.
+    pub fn combine_evaluations<F>(&self, evaluations: &[F], x: F) -> E
+    where
+        F: FieldElement<BaseField = E::BaseField>,
+        E: ExtensionOf<F>,
+    {
+        let z = E::from(self.divisor.evaluate_at(x));
+        self.merge_evaluations(evaluations) / z
+    }
+}
@@ -4,6 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use core::fmt;
+use utils::{string::String, DeserializationError};
 
 // ASSERTION ERROR
 // ================================================================================================
@@ -42,3 +43,55 @@ impl fmt::Display for AssertionError {
         }
     }
 }
+
+// PROOF OPTIONS ERROR
+// ================================================================================================
+/// Represents an error returned when proof options are validated against a base field.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProofOptionsError {
+    /// This error occurs when proof options cannot be used with the specified base field; for
+    /// example, when the options require a field extension which is not supported by the field.
+    IncompatibleField { reason: String },
+}
+
+impl fmt::Display for ProofOptionsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IncompatibleField { reason } => {
+                write!(f, "proof options are incompatible with the base field: {reason}")
+            }
+        }
+    }
+}
+
+// CHECKSUM ERROR
+// ================================================================================================
+/// Represents an error returned when a proof serialized with a trailing checksum is deserialized.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ChecksumError {
+    /// This error occurs when the checksum does not match the bytes preceding it; i.e., the
+    /// proof was truncated or otherwise corrupted during storage or transport.
+    CorruptedProof,
+    /// This error occurs when the checksum matches, but the bytes preceding it do not encode a
+    /// valid proof.
+    InvalidFormat(DeserializationError),
+}
+
+impl fmt::Display for ChecksumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CorruptedProof => {
+                write!(f, "checksum mismatch; proof is truncated or corrupted")
+            }
+            Self::InvalidFormat(err) => {
+                write!(f, "failed to deserialize proof: {err}")
+            }
+        }
+    }
+}
+
+impl From<DeserializationError> for ChecksumError {
+    fn from(err: DeserializationError) -> Self {
+        Self::InvalidFormat(err)
+    }
+}
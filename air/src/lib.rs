@@ -36,15 +36,20 @@ extern crate alloc;
 pub mod proof;
 
 mod errors;
-pub use errors::AssertionError;
+pub use errors::{AssertionError, ChecksumError, ProofOptionsError};
 
 mod options;
-pub use options::{FieldExtension, ProofOptions};
+pub use options::{
+    CommitmentLayout, FieldExtension, OpenedLeaves, ProofOptions, TraceCommitmentOptions,
+};
 
 mod air;
 pub use air::{
-    Air, AirContext, Assertion, AuxTraceRandElements, BoundaryConstraint, BoundaryConstraintGroup,
-    BoundaryConstraints, ConstraintCompositionCoefficients, ConstraintDivisor,
-    DeepCompositionCoefficients, EvaluationFrame, TraceInfo, TraceLayout,
-    TransitionConstraintDegree, TransitionConstraints,
+    lde_coset_offset, lde_domain_position, Air, AirContext, Assertion, AssertionSummary,
+    AuxTraceRandElements, BoundaryConstraint, BoundaryConstraintGroup, BoundaryConstraints,
+    ConstraintCompositionCoefficients, ConstraintDivisor, ConstraintExpr,
+    DeepCompositionCoefficients, EvaluationFrame, HashAccumulator, HashFunction, LdeDomainInfo,
+    PolynomialConstraint, RangeCheckConstraints, RationalConstraint, SparsePeriodicColumn,
+    SymbolicConstraints, SymbolicFrame, TraceInfo, TraceLayout, TransitionConstraintDegree,
+    TransitionConstraintGroup, TransitionConstraints, WraparoundConstraints,
 };
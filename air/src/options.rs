@@ -3,10 +3,13 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use fri::FriOptions;
-use math::{StarkField, ToElements};
+use crate::{proof::HashFunctionId, ProofOptionsError, TraceInfo};
+use crypto::IntegerSampling;
+use fri::{folding::fold_positions, FriOptions};
+use math::{ExtensibleField, StarkField, ToElements};
 use utils::{
     collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+    SliceReader,
 };
 
 // CONSTANTS
@@ -14,7 +17,7 @@ use utils::{
 
 // most of these constants are set so that values fit into a u8 integer.
 
-const MAX_NUM_QUERIES: usize = 255;
+pub(crate) const MAX_NUM_QUERIES: usize = 255;
 
 const MIN_BLOWUP_FACTOR: usize = 2;
 const MAX_BLOWUP_FACTOR: usize = 128;
@@ -25,6 +28,10 @@ const FRI_MIN_FOLDING_FACTOR: usize = 2;
 const FRI_MAX_FOLDING_FACTOR: usize = 16;
 const FRI_MAX_REMAINDER_DEGREE: usize = 255;
 
+// the LDE domain of the shortest allowed trace has 16 rows; this keeps at least two leaves in
+// trace commitment Merkle trees
+const MAX_ROWS_PER_LEAF: usize = 8;
+
 // TYPES AND INTERFACES
 // ================================================================================================
 
@@ -37,7 +44,9 @@ const FRI_MAX_REMAINDER_DEGREE: usize = 255;
 ///
 /// For example, if the size of base field is ~64-bits, a quadratic extension must be use to
 /// achieve ~100 bits of soundness, and a cubic extension must be used to achieve 128+ bits
-/// of soundness.
+/// of soundness. The smallest extension degree sufficient for a given field size and target
+/// security level can be computed using
+/// [recommended_extension_degree()](crate::proof::recommended_extension_degree).
 ///
 /// However, increasing extension degree will increase proof generation time and proof size by
 /// as much as 50%.
@@ -52,6 +61,68 @@ pub enum FieldExtension {
     Cubic = 3,
 }
 
+/// Defines how low-degree extensions of execution trace segments are committed to.
+///
+/// With the row-major layout, each row of a trace segment LDE is hashed into a single leaf of a
+/// Merkle tree, and the root of this tree is the commitment to the segment. This keeps proofs
+/// small, but every query must open all columns of the segment.
+///
+/// With the per-column layout, a separate Merkle tree is built for each column of a trace segment
+/// (each leaf containing a hash of a single value), and the commitment to the segment is a hash
+/// of the concatenated roots of these trees. This makes it possible to open individual columns of
+/// very wide traces at the expense of a Merkle authentication path per column in a proof.
+///
+/// For the row-major layout, several adjacent rows can also be packed into a single leaf (see
+/// [ProofOptions::with_rows_per_leaf()]). This reduces the depth of the Merkle tree at the expense
+/// of opening all rows of a leaf for every query.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CommitmentLayout {
+    /// Each leaf of a single Merkle tree commits to an entire row of a trace segment.
+    RowMajor = 0,
+    /// Each column of a trace segment is committed to with a separate Merkle tree.
+    PerColumn = 1,
+}
+
+/// Parameters of commitments to execution trace segments.
+///
+/// These are derived from [ProofOptions] (see [ProofOptions::trace_commitment_options()]) and
+/// are passed to the prover when it builds the low-degree extension of an execution trace.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TraceCommitmentOptions {
+    /// Layout used to commit to trace segments.
+    pub layout: CommitmentLayout,
+    /// Number of adjacent trace rows packed into a single leaf of a trace commitment. This is
+    /// always 1 for the [CommitmentLayout::PerColumn] layout.
+    pub rows_per_leaf: usize,
+}
+
+impl Default for TraceCommitmentOptions {
+    fn default() -> Self {
+        Self {
+            layout: CommitmentLayout::RowMajor,
+            rows_per_leaf: 1,
+        }
+    }
+}
+
+/// Number of leaves at which each commitment tree of a proof is opened.
+///
+/// This can be used to validate the structure of a proof (e.g., the number of queried values and
+/// authentication paths) before performing any cryptographic checks. The counts are the same as
+/// the ones expected by the verifier (see [ProofOptions::num_opened_leaves()]).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OpenedLeaves {
+    /// Number of leaves opened in the commitment tree of each trace segment. For the
+    /// [CommitmentLayout::PerColumn] layout, this is the number of leaves opened in the tree of
+    /// each column.
+    pub trace: usize,
+    /// Number of leaves opened in the constraint evaluation commitment tree.
+    pub constraint: usize,
+    /// Number of leaves opened in the commitment tree of each FRI layer, excluding the remainder.
+    pub fri_layers: Vec<usize>,
+}
+
 /// STARK protocol parameters.
 ///
 /// These parameters have a direct impact on proof soundness, proof generation time, and proof
@@ -70,10 +141,21 @@ pub enum FieldExtension {
 ///    generation time. More precisely, conjectured proof soundness is bounded by
 ///    `num_queries * log2(blowup_factor) + grinding_factor`.
 ///
+/// Proof options also specify how query positions are derived from the public coin (see
+/// [IntegerSampling]). This does not affect proof soundness, but must match between the prover
+/// and the verifier. Similarly, proof options specify the layout of trace commitments (see
+/// [CommitmentLayout]), which affects proof size and prover time but not proof soundness.
+/// Proof options also specify the order in which FRI query proofs are stored (see
+/// [ProofOptions::with_sorted_fri_queries()]). Finally,
+/// proof options specify whether out-of-domain values which can be recovered by the verifier are
+/// omitted from the proof (see [ProofOptions::with_thin_ood_frame()]).
+///
 /// Another important parameter in defining STARK security level, which is not a part of [ProofOptions]
 /// is the hash function used in the protocol. The soundness of a STARK proof is limited by the
 /// collision resistance of the hash function used by the protocol. For example, if a hash function
 /// with 128-bit collision resistance is used, soundness of a STARK proof cannot exceed 128 bits.
+/// Proof options can, however, record identifiers of separate hash functions used for trace and
+/// constraint commitments and for FRI layer commitments (see [ProofOptions::with_hash_fns()]).
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ProofOptions {
     num_queries: u8,
@@ -82,6 +164,12 @@ pub struct ProofOptions {
     field_extension: FieldExtension,
     fri_folding_factor: u8,
     fri_remainder_max_degree: u8,
+    query_sampling: IntegerSampling,
+    commitment_layout: CommitmentLayout,
+    rows_per_leaf: u8,
+    sorted_fri_queries: bool,
+    thin_ood_frame: bool,
+    hash_fns: Option<(HashFunctionId, HashFunctionId)>,
 }
 
 // PROOF OPTIONS IMPLEMENTATION
@@ -97,10 +185,25 @@ impl ProofOptions {
     /// have a blowup factor smaller than 2.
     pub const MIN_BLOWUP_FACTOR: usize = MIN_BLOWUP_FACTOR;
 
+    /// Largest allowed blowup factor which is currently set to 128.
+    pub const MAX_BLOWUP_FACTOR: usize = MAX_BLOWUP_FACTOR;
+
+    /// Largest allowed number of trace rows packed into a single leaf of a trace commitment, which
+    /// is currently set to 8.
+    pub const MAX_ROWS_PER_LEAF: usize = MAX_ROWS_PER_LEAF;
+
     // CONSTRUCTORS
     // --------------------------------------------------------------------------------------------
     /// Returns a new instance of [ProofOptions] struct constructed from the specified parameters.
     ///
+    /// Query positions for proofs generated with the returned options are derived using
+    /// [IntegerSampling::LowBits] method. This can be changed via
+    /// [with_query_sampling()](ProofOptions::with_query_sampling) method. Similarly, trace segments
+    /// are committed to using [CommitmentLayout::RowMajor] layout, which can be changed via
+    /// [with_commitment_layout()](ProofOptions::with_commitment_layout) method, with a single row
+    /// per leaf, which can be changed via [with_rows_per_leaf()](ProofOptions::with_rows_per_leaf)
+    /// method.
+    ///
     /// # Panics
     /// Panics if:
     /// - `num_queries` is zero or greater than 255.
@@ -147,7 +250,216 @@ impl ProofOptions {
             field_extension,
             fri_folding_factor: fri_folding_factor as u8,
             fri_remainder_max_degree: fri_remainder_max_degree as u8,
+            query_sampling: IntegerSampling::LowBits,
+            commitment_layout: CommitmentLayout::RowMajor,
+            rows_per_leaf: 1,
+            sorted_fri_queries: false,
+            thin_ood_frame: false,
+            hash_fns: None,
+        }
+    }
+
+    /// Returns a new instance of [ProofOptions] struct constructed from the specified parameters
+    /// and validated against the base field `B`.
+    ///
+    /// This is equivalent to calling [new()](ProofOptions::new) followed by
+    /// [validate()](ProofOptions::validate), and makes it possible to catch misconfigurations
+    /// before proof generation starts.
+    ///
+    /// # Errors
+    /// Returns an error if the resulting options cannot be used with the base field `B`.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [new()](ProofOptions::new).
+    pub fn new_for_field<B>(
+        num_queries: usize,
+        blowup_factor: usize,
+        grinding_factor: u32,
+        field_extension: FieldExtension,
+        fri_folding_factor: usize,
+        fri_remainder_max_degree: usize,
+    ) -> Result<ProofOptions, ProofOptionsError>
+    where
+        B: StarkField + ExtensibleField<2> + ExtensibleField<3>,
+    {
+        let options = ProofOptions::new(
+            num_queries,
+            blowup_factor,
+            grinding_factor,
+            field_extension,
+            fri_folding_factor,
+            fri_remainder_max_degree,
+        );
+        options.validate::<B>()?;
+        Ok(options)
+    }
+
+    /// Updates these proof options to use the specified number of queries.
+    ///
+    /// This can be used together with [min_num_queries()](crate::proof::min_num_queries) to size
+    /// the number of queries for a target security level.
+    ///
+    /// # Panics
+    /// Panics if `num_queries` is zero or greater than 255.
+    pub const fn with_num_queries(mut self, num_queries: usize) -> ProofOptions {
+        assert!(num_queries > 0, "number of queries must be greater than 0");
+        assert!(num_queries <= MAX_NUM_QUERIES, "number of queries cannot be greater than 255");
+        self.num_queries = num_queries as u8;
+        self
+    }
+
+    /// Updates these proof options to derive query positions from the public coin using the
+    /// specified integer sampling method.
+    ///
+    /// The verifier draws query positions using the method specified in the proof options of the
+    /// proof being verified, and thus, the method is also bound into the proof context.
+    pub const fn with_query_sampling(mut self, query_sampling: IntegerSampling) -> ProofOptions {
+        self.query_sampling = query_sampling;
+        self
+    }
+
+    /// Updates these proof options to commit to execution trace segments using the specified
+    /// commitment layout.
+    ///
+    /// The verifier checks trace queries against trace commitments using the layout specified in
+    /// the proof options of the proof being verified, and thus, the layout is also bound into the
+    /// proof context.
+    pub const fn with_commitment_layout(mut self, layout: CommitmentLayout) -> ProofOptions {
+        self.commitment_layout = layout;
+        self
+    }
+
+    /// Updates these proof options to pack the specified number of adjacent rows of execution
+    /// trace segments into a single leaf of a trace commitment.
+    ///
+    /// Packing applies only to the [CommitmentLayout::RowMajor] layout: leaf `i` commits to rows
+    /// `[i * rows_per_leaf, (i + 1) * rows_per_leaf)` of a trace segment LDE, and the verifier
+    /// extracts queried rows from the opened leaves. For the [CommitmentLayout::PerColumn] layout,
+    /// this parameter is ignored. The number of rows per leaf is bound into the proof context.
+    ///
+    /// # Panics
+    /// Panics if `rows_per_leaf` is zero, is not a power of two, or is greater than 8.
+    pub const fn with_rows_per_leaf(mut self, rows_per_leaf: usize) -> ProofOptions {
+        assert!(rows_per_leaf.is_power_of_two(), "rows per leaf must be a power of 2");
+        assert!(rows_per_leaf <= MAX_ROWS_PER_LEAF, "rows per leaf cannot be greater than 8");
+        self.rows_per_leaf = rows_per_leaf as u8;
+        self
+    }
+
+    /// Updates these proof options to store FRI query proofs sorted by their positions in the
+    /// evaluation domain of each FRI layer.
+    ///
+    /// By default, queried values of each FRI layer are stored in the order in which their
+    /// positions are derived from the query positions of the previous layer. With this option,
+    /// they are stored in ascending order of positions instead. This allows the verifier to locate
+    /// the queried value for a given position via binary search, and requires proofs for all
+    /// queries of a layer to be stored in sorted order: a proof with the queried values stored out
+    /// of order is rejected. The option does not affect proof size or soundness.
+    pub const fn with_sorted_fri_queries(mut self) -> ProofOptions {
+        self.sorted_fri_queries = true;
+        self
+    }
+
+    /// Updates these proof options to omit out-of-domain values which can be recovered by the
+    /// verifier from the proof.
+    ///
+    /// Evaluations of trace polynomials at the out-of-domain point $z$ and at $z \cdot g^k$ are
+    /// evaluations of independent polynomials at distinct points, and thus, none of them can be
+    /// derived from the others. However, the value of the constraint composition polynomial at
+    /// $z$ is fully determined by the out-of-domain trace frame, and the composition polynomial
+    /// is a combination of its column polynomials $H(x) = \sum_{i=0}^{m-1} x^{i \cdot n}
+    /// H_i(x)$. Thus, in this mode, the prover omits the evaluation of the last column
+    /// $H_{m-1}(z)$, and the verifier recovers it from $H(z)$ computed over the out-of-domain
+    /// trace frame and the evaluations of the remaining columns.
+    ///
+    /// The recovered value is absorbed into the public coin and is checked against the queried
+    /// constraint evaluations during DEEP composition in the same way as when it is sent by the
+    /// prover; thus, this option does not affect proof soundness, but it reduces proof size by
+    /// one field element.
+    pub const fn with_thin_ood_frame(mut self) -> ProofOptions {
+        self.thin_ood_frame = true;
+        self
+    }
+
+    /// Updates these proof options to commit to execution trace segments and constraint
+    /// evaluations with a hash function different from the one used to commit to FRI layers.
+    ///
+    /// This is useful for recursive proof verification: for example, trace and constraint
+    /// commitments can be made with an arithmetization-friendly hash function, while FRI layers
+    /// (which account for most of the hashing done by the verifier) are committed to with a fast
+    /// byte-oriented hash function. The identifiers of both hash functions are bound into the
+    /// proof context. The hash functions themselves are specified via type parameters of the
+    /// prover and the verifier (see `prove_with_fri_hash_fn()` and `verify_with_fri_hash_fn()`).
+    ///
+    /// With this option, roots of FRI layer commitments are hashed with the commitment hash
+    /// function before being absorbed into the public coin.
+    pub const fn with_hash_fns(
+        mut self,
+        commitment_hash_fn: HashFunctionId,
+        fri_hash_fn: HashFunctionId,
+    ) -> ProofOptions {
+        self.hash_fns = Some((commitment_hash_fn, fri_hash_fn));
+        self
+    }
+
+    /// Updates these proof options to disable query seed grinding (i.e., sets grinding factor to
+    /// zero), so that the prover does not perform a proof-of-work search.
+    ///
+    /// **WARNING:** this is intended only to speed up proof generation during AIR development and
+    /// testing. Proofs generated with these options have reduced soundness as they do not get the
+    /// security contribution of the grinding factor. The verifier accepts such proofs only if the
+    /// resulting options are explicitly listed as acceptable, or if the proof still meets the
+    /// required security level.
+    pub const fn insecure_without_grinding(mut self) -> ProofOptions {
+        self.grinding_factor = 0;
+        self
+    }
+
+    // VALIDATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Checks whether these proof options can be used to generate proofs over the base field `B`.
+    ///
+    /// Compatibility of a hash function with the base field is not checked here as it is enforced
+    /// by the type system via the base field of the [ElementHasher](crypto::ElementHasher).
+    ///
+    /// # Errors
+    /// Returns [ProofOptionsError::IncompatibleField] if:
+    /// - The specified field extension is not supported by `B`.
+    /// - The low-degree extension domain of the shortest allowed trace is larger than the
+    ///   largest multiplicative subgroup of `B` with a power of two size.
+    pub fn validate<B>(&self) -> Result<(), ProofOptionsError>
+    where
+        B: StarkField + ExtensibleField<2> + ExtensibleField<3>,
+    {
+        let extension_supported = match self.field_extension {
+            FieldExtension::None => true,
+            FieldExtension::Quadratic => <B as ExtensibleField<2>>::is_supported(),
+            FieldExtension::Cubic => <B as ExtensibleField<3>>::is_supported(),
+        };
+        if !extension_supported {
+            return Err(ProofOptionsError::IncompatibleField {
+                reason: format!(
+                    "field extension of degree {} is not supported by the base field",
+                    self.field_extension.degree()
+                ),
+            });
+        }
+
+        let min_lde_domain_size_log2 = TraceInfo::MIN_TRACE_LENGTH.ilog2() + self.rate_bits();
+        if min_lde_domain_size_log2 > B::TWO_ADICITY {
+            return Err(ProofOptionsError::IncompatibleField {
+                reason: format!(
+                    "blowup factor {} requires an LDE domain of at least 2^{} elements, but the \
+                    base field two-adicity is {}",
+                    self.blowup_factor(),
+                    min_lde_domain_size_log2,
+                    B::TWO_ADICITY
+                ),
+            });
         }
+
+        Ok(())
     }
 
     // PUBLIC ACCESSORS
@@ -172,6 +484,22 @@ impl ProofOptions {
         self.blowup_factor as usize
     }
 
+    /// Returns the rate of the Reed-Solomon code used to encode the execution trace.
+    ///
+    /// The rate is equal to `1 / blowup_factor`, and is the value usually denoted by $\rho$ in
+    /// soundness analysis of FRI and STARK protocols.
+    pub fn code_rate(&self) -> f64 {
+        1.0 / self.blowup_factor() as f64
+    }
+
+    /// Returns the number of bits of security contributed by each query, i.e.,
+    /// `log2(blowup_factor)`.
+    ///
+    /// This is equal to `-log2(code_rate)`.
+    pub const fn rate_bits(&self) -> u32 {
+        self.blowup_factor().ilog2()
+    }
+
     /// Returns query seed grinding factor for a STARK proof.
     ///
     /// Grinding applies Proof-of-Work to the query position seed. An honest prover needs to
@@ -192,35 +520,212 @@ impl ProofOptions {
         self.field_extension
     }
 
+    /// Returns the method used to derive query positions from the public coin.
+    ///
+    /// The prover and the verifier must use the same method; otherwise, they will arrive at
+    /// different query positions.
+    pub const fn query_sampling(&self) -> IntegerSampling {
+        self.query_sampling
+    }
+
+    /// Returns the layout used to commit to execution trace segments.
+    ///
+    /// The prover and the verifier must use the same layout; otherwise, trace queries will not
+    /// match trace commitments.
+    pub const fn commitment_layout(&self) -> CommitmentLayout {
+        self.commitment_layout
+    }
+
+    /// Returns the number of adjacent trace rows packed into a single leaf of a trace commitment.
+    ///
+    /// This is always 1 for the [CommitmentLayout::PerColumn] layout.
+    pub const fn rows_per_leaf(&self) -> usize {
+        match self.commitment_layout {
+            CommitmentLayout::RowMajor => self.rows_per_leaf as usize,
+            CommitmentLayout::PerColumn => 1,
+        }
+    }
+
+    /// Returns the layout and the number of rows per leaf used to commit to execution trace
+    /// segments.
+    pub const fn trace_commitment_options(&self) -> TraceCommitmentOptions {
+        TraceCommitmentOptions {
+            layout: self.commitment_layout(),
+            rows_per_leaf: self.rows_per_leaf(),
+        }
+    }
+
+    /// Returns the cells of a trace segment with the specified number of columns which are
+    /// opened when the segment is queried at the specified positions of the LDE domain.
+    ///
+    /// Each cell is returned as a `(position, register)` tuple, where `position` is a position in
+    /// the LDE domain and `register` is an index of a column in the segment. The cells are sorted
+    /// by position and then by register, and each cell is listed only once.
+    ///
+    /// When several rows are packed into a single leaf of a trace commitment (see
+    /// [rows_per_leaf()](Self::rows_per_leaf)), the prover opens all rows of each leaf containing
+    /// a queried position; thus, in this case, the returned cells include cells of rows which were
+    /// not queried directly.
+    pub fn query_cells(&self, positions: &[usize], trace_width: usize) -> Vec<(usize, usize)> {
+        let rows_per_leaf = self.rows_per_leaf();
+        let mut leaf_positions = positions.iter().map(|&p| p / rows_per_leaf).collect::<Vec<_>>();
+        leaf_positions.sort_unstable();
+        leaf_positions.dedup();
+
+        let mut result = Vec::with_capacity(leaf_positions.len() * rows_per_leaf * trace_width);
+        for leaf_position in leaf_positions {
+            for position in leaf_position * rows_per_leaf..(leaf_position + 1) * rows_per_leaf {
+                for register in 0..trace_width {
+                    result.push((position, register));
+                }
+            }
+        }
+        result
+    }
+
+    /// Returns the number of leaves at which each commitment tree is opened when a proof is
+    /// queried at the specified positions of an LDE domain of the specified size.
+    ///
+    /// Duplicate positions are opened only once. Trace segment trees are opened at leaves
+    /// containing the queried positions (see [query_cells()](Self::query_cells)), the constraint
+    /// evaluation tree is opened at the queried positions, and the tree of each FRI layer is
+    /// opened at the positions folded from the positions of the previous layer (see
+    /// [fold_positions()]).
+    ///
+    /// # Panics
+    /// Panics if `lde_domain_size` is not a power of two.
+    pub fn num_opened_leaves(&self, positions: &[usize], lde_domain_size: usize) -> OpenedLeaves {
+        assert!(lde_domain_size.is_power_of_two(), "LDE domain size must be a power of two");
+
+        let mut unique_positions = positions.to_vec();
+        unique_positions.sort_unstable();
+        unique_positions.dedup();
+
+        let rows_per_leaf = self.rows_per_leaf();
+        let mut leaf_positions =
+            unique_positions.iter().map(|&p| p / rows_per_leaf).collect::<Vec<_>>();
+        leaf_positions.dedup();
+
+        let fri_options = self.to_fri_options();
+        let folding_factor = fri_options.folding_factor();
+        let num_fri_layers = fri_options.num_fri_layers(lde_domain_size);
+        let mut fri_layers = Vec::with_capacity(num_fri_layers);
+        let mut fri_positions = unique_positions.clone();
+        let mut domain_size = lde_domain_size;
+        for _ in 0..num_fri_layers {
+            fri_positions = fold_positions(&fri_positions, domain_size, folding_factor);
+            fri_layers.push(fri_positions.len());
+            domain_size /= folding_factor;
+        }
+
+        OpenedLeaves {
+            trace: leaf_positions.len(),
+            constraint: unique_positions.len(),
+            fri_layers,
+        }
+    }
+
+    /// Returns true if FRI query proofs are stored sorted by their positions in the evaluation
+    /// domain of each FRI layer.
+    pub const fn sorted_fri_queries(&self) -> bool {
+        self.sorted_fri_queries
+    }
+
+    /// Returns true if out-of-domain values which can be recovered by the verifier are omitted
+    /// from the proof.
+    pub const fn has_thin_ood_frame(&self) -> bool {
+        self.thin_ood_frame
+    }
+
+    /// Returns identifiers of the hash functions used to commit to execution trace segments and
+    /// constraint evaluations, and to commit to FRI layers, in that order.
+    ///
+    /// Returns `None` if the same hash function is used for all commitments, in which case the
+    /// hash function is not recorded in the proof options.
+    pub const fn hash_fns(&self) -> Option<(HashFunctionId, HashFunctionId)> {
+        self.hash_fns
+    }
+
     /// Returns the offset by which the low-degree extension domain is shifted in relation to the
     /// trace domain.
     ///
-    /// Currently, this is hard-coded to the primitive element of the underlying base field.
+    /// Currently, this is hard-coded to the primitive element of the underlying base field (see
+    /// [lde_coset_offset()](crate::lde_coset_offset)).
     pub const fn domain_offset<B: StarkField>(&self) -> B {
-        B::GENERATOR
+        crate::lde_coset_offset::<B>()
     }
 
     /// Returns options for FRI protocol instantiated with parameters from this proof options.
     pub fn to_fri_options(&self) -> FriOptions {
         let folding_factor = self.fri_folding_factor as usize;
         let remainder_max_degree = self.fri_remainder_max_degree as usize;
-        FriOptions::new(self.blowup_factor(), folding_factor, remainder_max_degree)
+        let options = FriOptions::new(self.blowup_factor(), folding_factor, remainder_max_degree);
+        if self.sorted_fri_queries {
+            options.with_sorted_queries()
+        } else {
+            options
+        }
+    }
+
+    // SERIALIZATION / DESERIALIZATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Serializes these proof options into a vector of bytes.
+    ///
+    /// The encoding is canonical (i.e., equal options are always encoded into the same bytes), and
+    /// is the same as the encoding of proof options embedded in STARK proofs. Thus, options stored
+    /// this way can be compared byte-for-byte with options of a proof.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Serializable::to_bytes(self)
+    }
+
+    /// Returns proof options read from the specified `bytes`.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The `bytes` do not encode valid proof options (e.g., the encoded blowup factor is not a
+    ///   power of two, or the encoded FRI folding factor is out of range).
+    /// * The `bytes` contain more data than needed to encode proof options.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializationError> {
+        let mut source = SliceReader::new(bytes);
+        let options = Self::read_from(&mut source)?;
+        if source.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+        Ok(options)
     }
 }
 
 impl<E: StarkField> ToElements<E> for ProofOptions {
     fn to_elements(&self) -> Vec<E> {
-        // encode field extension and FRI parameters into a single field element
-        let mut buf = self.field_extension as u32;
+        // encode query sampling method, field extension and FRI parameters into a single field
+        // element
+        let mut buf = self.query_sampling as u32;
+        buf = (buf << 8) | self.field_extension as u32;
         buf = (buf << 8) | self.fri_folding_factor as u32;
         buf = (buf << 8) | self.fri_remainder_max_degree as u32;
 
-        vec![
+        // encode the thin OOD frame and the sorted FRI queries flags, the number of rows per leaf
+        // (as log2), and commitment layout together with the grinding factor
+        let mut grinding = ((self.thin_ood_frame as u32) << 1) | self.sorted_fri_queries as u32;
+        grinding = (grinding << 8) | (self.rows_per_leaf as u32).ilog2();
+        grinding = (grinding << 8) | self.commitment_layout as u32;
+        grinding = (grinding << 8) | self.grinding_factor as u32;
+
+        let mut result = vec![
             E::from(buf),
-            E::from(self.grinding_factor),
+            E::from(grinding),
             E::from(self.blowup_factor),
             E::from(self.num_queries),
-        ]
+        ];
+
+        // hash function identifiers are encoded only if they are specified so that options which
+        // use the same hash function for all commitments are encoded as before
+        if let Some((commitment_hash_fn, fri_hash_fn)) = self.hash_fns {
+            result.push(E::from(((commitment_hash_fn as u32) << 8) | fri_hash_fn as u32));
+        }
+
+        result
     }
 }
 
@@ -233,6 +738,20 @@ impl Serializable for ProofOptions {
         target.write(self.field_extension);
         target.write_u8(self.fri_folding_factor);
         target.write_u8(self.fri_remainder_max_degree);
+        target.write(self.query_sampling);
+        target.write(self.commitment_layout);
+        target.write_u8(self.rows_per_leaf);
+        // the first bit of the flags indicates whether FRI queries are sorted, the second bit
+        // indicates whether hash function identifiers follow, and the third bit indicates whether
+        // the OOD frame is thin
+        let flags = ((self.thin_ood_frame as u8) << 2)
+            | ((self.hash_fns.is_some() as u8) << 1)
+            | self.sorted_fri_queries as u8;
+        target.write_u8(flags);
+        if let Some((commitment_hash_fn, fri_hash_fn)) = self.hash_fns {
+            target.write(commitment_hash_fn);
+            target.write(fri_hash_fn);
+        }
     }
 }
 
@@ -242,14 +761,90 @@ impl Deserializable for ProofOptions {
     /// # Errors
     /// Returns an error of a valid proof options could not be read from the specified `source`.
     fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
-        Ok(ProofOptions::new(
-            source.read_u8()? as usize,
-            source.read_u8()? as usize,
-            source.read_u8()? as u32,
-            FieldExtension::read_from(source)?,
-            source.read_u8()? as usize,
-            source.read_u8()? as usize,
-        ))
+        // validate all parameters before constructing the options so that invalid values are
+        // reported as errors rather than causing a panic
+        let num_queries = source.read_u8()? as usize;
+        if num_queries == 0 {
+            return Err(DeserializationError::InvalidValue(
+                "0 is not a valid number of queries".into(),
+            ));
+        }
+        let blowup_factor = source.read_u8()? as usize;
+        if !blowup_factor.is_power_of_two()
+            || !(MIN_BLOWUP_FACTOR..=MAX_BLOWUP_FACTOR).contains(&blowup_factor)
+        {
+            return Err(DeserializationError::InvalidValue(format!(
+                "{blowup_factor} is not a valid blowup factor"
+            )));
+        }
+        let grinding_factor = source.read_u8()? as u32;
+        if grinding_factor > MAX_GRINDING_FACTOR {
+            return Err(DeserializationError::InvalidValue(format!(
+                "{grinding_factor} is not a valid grinding factor"
+            )));
+        }
+        let field_extension = FieldExtension::read_from(source)?;
+        let fri_folding_factor = source.read_u8()? as usize;
+        if !fri_folding_factor.is_power_of_two()
+            || !(FRI_MIN_FOLDING_FACTOR..=FRI_MAX_FOLDING_FACTOR).contains(&fri_folding_factor)
+        {
+            return Err(DeserializationError::InvalidValue(format!(
+                "{fri_folding_factor} is not a valid FRI folding factor"
+            )));
+        }
+        let fri_remainder_max_degree = source.read_u8()? as usize;
+        if !(fri_remainder_max_degree + 1).is_power_of_two() {
+            return Err(DeserializationError::InvalidValue(format!(
+                "{fri_remainder_max_degree} is not a valid FRI remainder degree"
+            )));
+        }
+
+        let options = ProofOptions::new(
+            num_queries,
+            blowup_factor,
+            grinding_factor,
+            field_extension,
+            fri_folding_factor,
+            fri_remainder_max_degree,
+        );
+        let options = options
+            .with_query_sampling(IntegerSampling::read_from(source)?)
+            .with_commitment_layout(CommitmentLayout::read_from(source)?);
+
+        let rows_per_leaf = source.read_u8()? as usize;
+        if !rows_per_leaf.is_power_of_two() || rows_per_leaf > MAX_ROWS_PER_LEAF {
+            return Err(DeserializationError::InvalidValue(format!(
+                "{rows_per_leaf} is not a valid number of rows per leaf"
+            )));
+        }
+        let options = options.with_rows_per_leaf(rows_per_leaf);
+
+        let flags = source.read_u8()?;
+        if flags > 7 {
+            return Err(DeserializationError::InvalidValue(format!(
+                "{flags} is not a valid value for proof option flags"
+            )));
+        }
+
+        let options = if flags & 1 == 1 {
+            options.with_sorted_fri_queries()
+        } else {
+            options
+        };
+
+        let options = if flags & 4 == 4 {
+            options.with_thin_ood_frame()
+        } else {
+            options
+        };
+
+        if flags & 2 == 2 {
+            let commitment_hash_fn = HashFunctionId::read_from(source)?;
+            let fri_hash_fn = HashFunctionId::read_from(source)?;
+            Ok(options.with_hash_fns(commitment_hash_fn, fri_hash_fn))
+        } else {
+            Ok(options)
+        }
     }
 }
 
@@ -293,13 +888,40 @@ impl Deserializable for FieldExtension {
     }
 }
 
+// COMMITMENT LAYOUT IMPLEMENTATION
+// ================================================================================================
+
+impl Serializable for CommitmentLayout {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self as u8);
+    }
+}
+
+impl Deserializable for CommitmentLayout {
+    /// Reads a commitment layout enum from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            0 => Ok(CommitmentLayout::RowMajor),
+            1 => Ok(CommitmentLayout::PerColumn),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "value {value} cannot be deserialized as CommitmentLayout enum"
+            ))),
+        }
+    }
+}
+
 // TESTS
 // ================================================================================================
 
 #[cfg(test)]
 mod tests {
-    use super::{FieldExtension, ProofOptions, ToElements};
-    use math::fields::f64::BaseElement;
+    use super::{
+        CommitmentLayout, FieldExtension, HashFunctionId, IntegerSampling, ProofOptions,
+        ProofOptionsError, ToElements,
+    };
+    use math::fields::{f128, f62, f64::BaseElement};
+    use utils::{Deserializable, DeserializationError, Serializable, SliceReader};
 
     #[test]
     fn proof_options_to_elements() {
@@ -333,4 +955,307 @@ mod tests {
         );
         assert_eq!(expected, options.to_elements());
     }
+
+    #[test]
+    fn proof_options_code_rate() {
+        for (blowup_factor, rate_bits) in [(2, 1), (4, 2), (8, 3), (16, 4), (64, 6), (128, 7)] {
+            let options = ProofOptions::new(30, blowup_factor, 20, FieldExtension::None, 8, 127);
+            assert_eq!(rate_bits, options.rate_bits());
+            assert_eq!(1.0 / blowup_factor as f64, options.code_rate());
+            assert_eq!(-(rate_bits as f64), options.code_rate().log2());
+        }
+    }
+
+    #[test]
+    fn proof_options_query_sampling() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 8, 127);
+        assert_eq!(IntegerSampling::LowBits, options.query_sampling());
+
+        // query sampling method is encoded in the most significant byte of the first element
+        let options = options.with_query_sampling(IntegerSampling::PackedBits);
+        let ext_fri = u32::from_le_bytes([127, 8, FieldExtension::None as u8, 2]);
+        let elements: Vec<BaseElement> = options.to_elements();
+        assert_eq!(BaseElement::from(ext_fri), elements[0]);
+
+        // query sampling method must survive serialization round trip
+        let bytes = options.to_bytes();
+        let mut reader = SliceReader::new(&bytes);
+        assert_eq!(options, ProofOptions::read_from(&mut reader).unwrap());
+    }
+
+    #[test]
+    fn proof_options_commitment_layout() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 8, 127);
+        assert_eq!(CommitmentLayout::RowMajor, options.commitment_layout());
+
+        // commitment layout is encoded in the second byte of the grinding factor element
+        let options = options.with_commitment_layout(CommitmentLayout::PerColumn);
+        let elements: Vec<BaseElement> = options.to_elements();
+        assert_eq!(BaseElement::from(u32::from_le_bytes([20, 1, 0, 0])), elements[1]);
+
+        // commitment layout must survive serialization round trip
+        let bytes = options.to_bytes();
+        let mut reader = SliceReader::new(&bytes);
+        assert_eq!(options, ProofOptions::read_from(&mut reader).unwrap());
+
+        let mut reader = SliceReader::new(&[2]);
+        assert!(CommitmentLayout::read_from(&mut reader).is_err());
+    }
+
+    #[test]
+    fn proof_options_rows_per_leaf() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 8, 127);
+        assert_eq!(1, options.rows_per_leaf());
+
+        // log2 of the number of rows per leaf is encoded in the third byte of the grinding factor
+        // element
+        let options = options.with_rows_per_leaf(4);
+        assert_eq!(4, options.rows_per_leaf());
+        let elements: Vec<BaseElement> = options.to_elements();
+        assert_eq!(BaseElement::from(u32::from_le_bytes([20, 0, 2, 0])), elements[1]);
+
+        // the number of rows per leaf must survive serialization round trip
+        let bytes = options.to_bytes();
+        let mut reader = SliceReader::new(&bytes);
+        assert_eq!(options, ProofOptions::read_from(&mut reader).unwrap());
+
+        // packing does not apply to the per-column layout
+        let options = options.with_commitment_layout(CommitmentLayout::PerColumn);
+        assert_eq!(1, options.rows_per_leaf());
+
+        // invalid number of rows per leaf is rejected during deserialization
+        let mut bytes = options.to_bytes();
+        let rows_per_leaf_idx = bytes.len() - 2;
+        bytes[rows_per_leaf_idx] = 3;
+        let mut reader = SliceReader::new(&bytes);
+        assert!(ProofOptions::read_from(&mut reader).is_err());
+    }
+
+    #[test]
+    fn proof_options_query_cells() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 8, 127);
+        assert_eq!(vec![(3, 0), (3, 1), (5, 0), (5, 1)], options.query_cells(&[5, 3, 5], 2));
+
+        // all rows of each opened leaf are included
+        let options = options.with_rows_per_leaf(4);
+        let cells = options.query_cells(&[9, 1, 2], 1);
+        assert_eq!(vec![(0, 0), (1, 0), (2, 0), (3, 0), (8, 0), (9, 0), (10, 0), (11, 0)], cells);
+
+        // each column is opened only at the queried positions for the per-column layout
+        let options = options.with_commitment_layout(CommitmentLayout::PerColumn);
+        assert_eq!(vec![(1, 0), (9, 0)], options.query_cells(&[9, 1], 1));
+    }
+
+    #[test]
+    fn proof_options_num_opened_leaves() {
+        let positions = [3, 17, 17, 40, 41, 100, 127];
+
+        // by default, each trace leaf contains a single row, and duplicate positions are opened
+        // only once; FRI layers are folded from 128 to 16 and then to 2 elements
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 8, 0);
+        let opened = options.num_opened_leaves(&positions, 128);
+        assert_eq!(6, opened.trace);
+        assert_eq!(6, opened.constraint);
+        // 3, 17, 40, 41, 100, 127 fold into 3, 1, 8, 9, 4, 15 and then into 1, 0
+        assert_eq!(vec![6, 2], opened.fri_layers);
+
+        // packing 4 rows into a leaf merges 40 and 41 into the same leaf
+        let opened = options.clone().with_rows_per_leaf(4).num_opened_leaves(&positions, 128);
+        assert_eq!(5, opened.trace);
+        assert_eq!(6, opened.constraint);
+
+        // for the per-column layout, rows are never packed
+        let opened = options
+            .with_rows_per_leaf(4)
+            .with_commitment_layout(CommitmentLayout::PerColumn)
+            .num_opened_leaves(&positions, 128);
+        assert_eq!(6, opened.trace);
+
+        // with folding factor 2, positions are folded 4 times before reaching the remainder
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 2, 0);
+        let opened = options.num_opened_leaves(&positions, 128);
+        assert_eq!(vec![6, 6, 6, 5], opened.fri_layers);
+    }
+
+    #[test]
+    fn proof_options_sorted_fri_queries() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 8, 127);
+        assert!(!options.sorted_fri_queries());
+        assert!(!options.to_fri_options().sorted_queries());
+
+        // the sorted FRI queries flag is encoded in the fourth byte of the grinding factor element
+        let options = options.with_sorted_fri_queries();
+        assert!(options.sorted_fri_queries());
+        assert!(options.to_fri_options().sorted_queries());
+        let elements: Vec<BaseElement> = options.to_elements();
+        assert_eq!(BaseElement::from(u32::from_le_bytes([20, 0, 0, 1])), elements[1]);
+
+        // the flag is encoded in the first bit of the flags byte and must survive serialization
+        // round trip
+        let bytes = options.to_bytes();
+        assert_eq!(Some(&1), bytes.last());
+        let mut reader = SliceReader::new(&bytes);
+        assert_eq!(options, ProofOptions::read_from(&mut reader).unwrap());
+
+        // invalid flags value is rejected during deserialization
+        let mut bytes = options.to_bytes();
+        *bytes.last_mut().unwrap() = 8;
+        let mut reader = SliceReader::new(&bytes);
+        assert!(ProofOptions::read_from(&mut reader).is_err());
+    }
+
+    #[test]
+    fn proof_options_hash_fns() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 8, 127);
+        assert_eq!(None, options.hash_fns());
+        let elements: Vec<BaseElement> = options.to_elements();
+        assert_eq!(4, elements.len());
+
+        // hash function identifiers are encoded into an additional element
+        let options = options.with_hash_fns(HashFunctionId::Rp64_256, HashFunctionId::Blake3_256);
+        assert_eq!(
+            Some((HashFunctionId::Rp64_256, HashFunctionId::Blake3_256)),
+            options.hash_fns()
+        );
+        let elements: Vec<BaseElement> = options.to_elements();
+        assert_eq!(5, elements.len());
+        assert_eq!(BaseElement::from(u32::from_le_bytes([2, 5, 0, 0])), elements[4]);
+
+        // the identifiers must survive serialization round trip
+        let bytes = options.to_bytes();
+        assert_eq!(&[2, 5, 2], &bytes[bytes.len() - 3..]);
+        assert_eq!(options, ProofOptions::from_bytes(&bytes).unwrap());
+
+        // invalid identifiers and flags are rejected during deserialization
+        let mut invalid = bytes.clone();
+        *invalid.last_mut().unwrap() = 9;
+        assert!(ProofOptions::from_bytes(&invalid).is_err());
+
+        let mut invalid = bytes.clone();
+        let flag_idx = invalid.len() - 3;
+        invalid[flag_idx] = 8;
+        assert!(ProofOptions::from_bytes(&invalid).is_err());
+    }
+
+    #[test]
+    fn proof_options_thin_ood_frame() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 8, 127);
+        assert!(!options.has_thin_ood_frame());
+        let full_elements: Vec<BaseElement> = options.to_elements();
+
+        // the flag is encoded into the element which also encodes the grinding factor
+        let thin_options = options.clone().with_thin_ood_frame();
+        assert!(thin_options.has_thin_ood_frame());
+        let thin_elements: Vec<BaseElement> = thin_options.to_elements();
+        assert_eq!(full_elements.len(), thin_elements.len());
+        assert_eq!(full_elements[1] + BaseElement::from(2u32 << 24), thin_elements[1]);
+
+        // the flag must survive serialization round trip, also together with hash functions
+        let bytes = thin_options.to_bytes();
+        assert_eq!(options.to_bytes().len(), bytes.len());
+        assert_eq!(thin_options, ProofOptions::from_bytes(&bytes).unwrap());
+
+        let thin_options =
+            thin_options.with_hash_fns(HashFunctionId::Rp64_256, HashFunctionId::Blake3_256);
+        let bytes = thin_options.to_bytes();
+        assert_eq!(6, bytes[bytes.len() - 3]);
+        assert_eq!(thin_options, ProofOptions::from_bytes(&bytes).unwrap());
+    }
+
+    #[test]
+    fn proof_options_field_compatibility() {
+        // all field extensions are supported by the f64 and f62 fields
+        for extension in [FieldExtension::None, FieldExtension::Quadratic, FieldExtension::Cubic] {
+            let options = ProofOptions::new(30, 8, 20, extension, 8, 127);
+            assert!(options.validate::<BaseElement>().is_ok());
+            assert!(options.validate::<f62::BaseElement>().is_ok());
+        }
+
+        // cubic extension is not supported by the f128 field
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::Quadratic, 8, 127);
+        assert!(options.validate::<f128::BaseElement>().is_ok());
+
+        let result = ProofOptions::new_for_field::<f128::BaseElement>(
+            30,
+            8,
+            20,
+            FieldExtension::Cubic,
+            8,
+            127,
+        );
+        assert!(matches!(result, Err(ProofOptionsError::IncompatibleField { .. })));
+
+        // options validated at construction are the same as options constructed without validation
+        let options =
+            ProofOptions::new_for_field::<BaseElement>(30, 8, 20, FieldExtension::Cubic, 8, 127);
+        assert_eq!(Ok(ProofOptions::new(30, 8, 20, FieldExtension::Cubic, 8, 127)), options);
+    }
+
+    #[test]
+    fn proof_options_bytes_round_trip() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::Quadratic, 4, 63);
+        let bytes = options.to_bytes();
+        assert_eq!(vec![30, 8, 20, 2, 4, 63, 0, 0, 1, 0], bytes);
+        assert_eq!(options, ProofOptions::from_bytes(&bytes).unwrap());
+
+        let options = options
+            .with_query_sampling(IntegerSampling::PackedBits)
+            .with_commitment_layout(CommitmentLayout::PerColumn)
+            .with_rows_per_leaf(4)
+            .with_sorted_fri_queries();
+        let bytes = options.to_bytes();
+        assert_eq!(vec![30, 8, 20, 2, 4, 63, 2, 1, 4, 1], bytes);
+        assert_eq!(options, ProofOptions::from_bytes(&bytes).unwrap());
+
+        // the encoding is the same as the one used for options embedded in proofs
+        assert_eq!(Serializable::to_bytes(&options), bytes);
+
+        // options with a thin OOD frame and with separate hash functions survive the round trip
+        // on their own and together with all other flags
+        let thin_options = options.clone().with_thin_ood_frame();
+        let bytes = thin_options.to_bytes();
+        assert_eq!(vec![30, 8, 20, 2, 4, 63, 2, 1, 4, 5], bytes);
+        assert_eq!(thin_options, ProofOptions::from_bytes(&bytes).unwrap());
+
+        let hash_fns_options =
+            options.with_hash_fns(HashFunctionId::Rp64_256, HashFunctionId::Blake3_256);
+        let bytes = hash_fns_options.to_bytes();
+        assert_eq!(vec![30, 8, 20, 2, 4, 63, 2, 1, 4, 3, 5, 2], bytes);
+        assert_eq!(hash_fns_options, ProofOptions::from_bytes(&bytes).unwrap());
+
+        let all_options = hash_fns_options.with_thin_ood_frame();
+        let bytes = all_options.to_bytes();
+        assert_eq!(vec![30, 8, 20, 2, 4, 63, 2, 1, 4, 7, 5, 2], bytes);
+        assert_eq!(all_options, ProofOptions::from_bytes(&bytes).unwrap());
+    }
+
+    #[test]
+    fn proof_options_from_invalid_bytes() {
+        let bytes = ProofOptions::new(30, 8, 20, FieldExtension::None, 8, 127).to_bytes();
+
+        // each of these values is invalid for the parameter encoded at the specified position
+        for (position, value) in [
+            (0, 0),   // number of queries
+            (1, 6),   // blowup factor which is not a power of two
+            (1, 1),   // blowup factor which is too small
+            (2, 33),  // grinding factor
+            (3, 4),   // field extension
+            (4, 32),  // FRI folding factor
+            (5, 100), // FRI remainder degree
+            (6, 9),   // query sampling
+            (7, 2),   // commitment layout
+            (8, 16),  // rows per leaf
+            (9, 8),   // flags
+        ] {
+            let mut invalid = bytes.clone();
+            invalid[position] = value;
+            assert!(ProofOptions::from_bytes(&invalid).is_err());
+        }
+
+        // truncated and extended encodings are rejected as well
+        assert!(ProofOptions::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert_eq!(Err(DeserializationError::UnconsumedBytes), ProofOptions::from_bytes(&extended));
+    }
 }
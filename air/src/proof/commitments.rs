@@ -3,7 +3,8 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crypto::Hasher;
+use crate::ProofOptions;
+use crypto::{Digest, Hasher};
 use utils::{
     collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
     SliceReader,
@@ -40,6 +41,21 @@ impl Commitments {
         Commitments(bytes)
     }
 
+    /// Returns commitments assembled from raw bytes of serialized digests.
+    #[cfg(feature = "flat-layout")]
+    pub(super) fn from_raw_parts(bytes: Vec<u8>) -> Self {
+        Commitments(bytes)
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns raw bytes of all serialized digests.
+    #[cfg(feature = "flat-layout")]
+    pub(super) fn raw_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
     // PUBLIC METHODS
     // --------------------------------------------------------------------------------------------
 
@@ -48,6 +64,29 @@ impl Commitments {
         commitment.write_into(&mut self.0);
     }
 
+    /// Removes the commitment to the main segment of the execution trace (i.e., the first
+    /// commitment in the list) and returns it.
+    ///
+    /// # Errors
+    /// Returns an error if a commitment could not be read from the start of the list.
+    pub fn remove_main_trace_commitment<H: Hasher>(
+        &mut self,
+    ) -> Result<H::Digest, DeserializationError> {
+        let commitment = H::Digest::read_from(&mut SliceReader::new(&self.0))?;
+        self.0.drain(..commitment.to_bytes().len());
+        Ok(commitment)
+    }
+
+    /// Inserts the specified commitment to the main segment of the execution trace at the start
+    /// of the list of commitments.
+    ///
+    /// This is the inverse of [remove_main_trace_commitment()](Self::remove_main_trace_commitment).
+    pub fn insert_main_trace_commitment<H: Hasher>(&mut self, commitment: &H::Digest) {
+        let mut bytes = commitment.to_bytes();
+        bytes.append(&mut self.0);
+        self.0 = bytes;
+    }
+
     // PARSING
     // --------------------------------------------------------------------------------------------
 
@@ -67,6 +106,26 @@ impl Commitments {
         num_trace_segments: usize,
         num_fri_layers: usize,
     ) -> Result<(Vec<H::Digest>, H::Digest, Vec<H::Digest>), DeserializationError> {
+        self.parse_with_fri_hash_fn::<H, H>(num_trace_segments, num_fri_layers)
+    }
+
+    /// Parses the serialized commitments into distinct parts in the same way as
+    /// [parse()](Commitments::parse) does, but reads FRI layer commitments as digests of hash
+    /// function `HF`.
+    ///
+    /// This is used for proofs in which FRI layers are committed to with a hash function
+    /// different from the one used for trace and constraint commitments (see
+    /// [ProofOptions::with_hash_fns()]).
+    ///
+    /// # Errors
+    /// Returns an error if the bytes stored in self could not be parsed into the requested number
+    /// of commitments, or if there are any unconsumed bytes remaining after the parsing completes.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_with_fri_hash_fn<H: Hasher, HF: Hasher>(
+        self,
+        num_trace_segments: usize,
+        num_fri_layers: usize,
+    ) -> Result<(Vec<H::Digest>, H::Digest, Vec<HF::Digest>), DeserializationError> {
         let mut reader = SliceReader::new(&self.0);
 
         // parse trace commitments
@@ -76,7 +135,7 @@ impl Commitments {
         let constraint_commitment = H::Digest::read_from(&mut reader)?;
 
         // read FRI commitments (+ 1 for remainder polynomial commitment)
-        let fri_commitments = H::Digest::read_batch_from(&mut reader, num_fri_layers + 1)?;
+        let fri_commitments = HF::Digest::read_batch_from(&mut reader, num_fri_layers + 1)?;
 
         // make sure we consumed all available commitment bytes
         if reader.has_more_bytes() {
@@ -107,3 +166,130 @@ impl Deserializable for Commitments {
         Ok(Commitments(result))
     }
 }
+
+// FRI LAYER SEEDS
+// ================================================================================================
+
+/// Returns a digest of hash function `H` with which the public coin is reseeded after a FRI layer
+/// with the specified commitment `root` is committed to.
+///
+/// When the proof options do not specify separate hash functions for FRI and for the rest of the
+/// protocol (see [ProofOptions::with_hash_fns()]), FRI layers are committed to with `H`, and thus,
+/// the root itself is returned. Otherwise, the root is hashed with `H`.
+///
+/// # Errors
+/// Returns an error if the options do not specify separate hash functions, but the root could
+/// not be read as a digest of `H`.
+pub fn get_fri_layer_seed<H: Hasher, HF: Hasher>(
+    root: &HF::Digest,
+    options: &ProofOptions,
+) -> Result<H::Digest, DeserializationError> {
+    match options.hash_fns() {
+        Some(_) => Ok(H::hash(&root.to_bytes())),
+        None => H::Digest::read_from_bytes(&root.to_bytes()),
+    }
+}
+
+// COMMITMENT ROOTS
+// ================================================================================================
+/// Roots of all Merkle trees committed to by the prover during commit phase of the protocol.
+///
+/// The roots can be extracted from a proof via
+/// [StarkProof::commitment_roots()](super::StarkProof::commitment_roots) and anchored
+/// externally (e.g., on-chain); the full proof can then be verified off-chain against the
+/// anchored roots.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CommitmentRoots<D: Digest> {
+    trace_roots: Vec<D>,
+    constraint_root: D,
+    fri_roots: Vec<D>,
+}
+
+impl<D: Digest> CommitmentRoots<D> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new set of commitment roots instantiated with the provided roots.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `trace_roots` is empty or contains more than 255 roots.
+    /// * `fri_roots` is empty or contains more than 255 roots.
+    pub fn new(trace_roots: Vec<D>, constraint_root: D, fri_roots: Vec<D>) -> Self {
+        assert!(
+            !trace_roots.is_empty() && trace_roots.len() <= u8::MAX as usize,
+            "number of trace roots must be between 1 and 255, but was {}",
+            trace_roots.len()
+        );
+        assert!(
+            !fri_roots.is_empty() && fri_roots.len() <= u8::MAX as usize,
+            "number of FRI roots must be between 1 and 255, but was {}",
+            fri_roots.len()
+        );
+        CommitmentRoots {
+            trace_roots,
+            constraint_root,
+            fri_roots,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns roots of the extended execution trace commitments; there is one root per
+    /// execution trace segment.
+    pub fn trace_roots(&self) -> &[D] {
+        &self.trace_roots
+    }
+
+    /// Returns the root of the constraint composition polynomial evaluation commitment.
+    pub fn constraint_root(&self) -> &D {
+        &self.constraint_root
+    }
+
+    /// Returns roots of FRI layer commitments; the last root is the commitment to the FRI
+    /// remainder polynomial.
+    pub fn fri_roots(&self) -> &[D] {
+        &self.fri_roots
+    }
+}
+
+impl<D: Digest> Serializable for CommitmentRoots<D> {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(self.trace_roots.len() as u8);
+        target.write(&self.trace_roots);
+        target.write(self.constraint_root);
+        target.write_u8(self.fri_roots.len() as u8);
+        target.write(&self.fri_roots);
+    }
+}
+
+impl<D: Digest> Deserializable for CommitmentRoots<D> {
+    /// Reads commitment roots from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if a valid set of commitment roots could not be read from the specified
+    /// `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_trace_roots = source.read_u8()? as usize;
+        if num_trace_roots == 0 {
+            return Err(DeserializationError::InvalidValue(
+                "number of trace roots must be greater than zero".into(),
+            ));
+        }
+        let trace_roots = D::read_batch_from(source, num_trace_roots)?;
+        let constraint_root = D::read_from(source)?;
+        let num_fri_roots = source.read_u8()? as usize;
+        if num_fri_roots == 0 {
+            return Err(DeserializationError::InvalidValue(
+                "number of FRI roots must be greater than zero".into(),
+            ));
+        }
+        let fri_roots = D::read_batch_from(source, num_fri_roots)?;
+        Ok(CommitmentRoots {
+            trace_roots,
+            constraint_root,
+            fri_roots,
+        })
+    }
+}
@@ -4,6 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::{ProofOptions, TraceInfo, TraceLayout};
+use core::cmp;
 use math::{StarkField, ToElements};
 use utils::{
     collections::Vec, string::ToString, ByteReader, ByteWriter, Deserializable,
@@ -20,20 +21,36 @@ pub struct Context {
     trace_meta: Vec<u8>,
     field_modulus_bytes: Vec<u8>,
     options: ProofOptions,
+    frame_width: usize,
 }
 
 impl Context {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
-    /// Creates a new context for a computation described by the specified field, trace info, and
-    /// proof options.
-    pub fn new<B: StarkField>(trace_info: &TraceInfo, options: ProofOptions) -> Self {
+    /// Creates a new context for a computation described by the specified field, trace info,
+    /// proof options, and width of the frame at which trace polynomials are opened during DEEP
+    /// composition (see [AirContext::frame_width()](crate::AirContext::frame_width)).
+    ///
+    /// # Panics
+    /// Panics if the frame width is smaller than 2, or greater than 255 or than the trace length.
+    pub fn new<B: StarkField>(
+        trace_info: &TraceInfo,
+        options: ProofOptions,
+        frame_width: usize,
+    ) -> Self {
+        assert!(frame_width >= 2, "frame width must be at least 2, but was {frame_width}");
+        let max_width = cmp::min(u8::MAX as usize, trace_info.length());
+        assert!(
+            frame_width <= max_width,
+            "frame width cannot exceed {max_width}, but was {frame_width}"
+        );
         Context {
             trace_layout: trace_info.layout().clone(),
             trace_length: trace_info.length(),
             trace_meta: trace_info.meta().to_vec(),
             field_modulus_bytes: B::get_modulus_le_bytes(),
             options,
+            frame_width,
         }
     }
 
@@ -91,6 +108,12 @@ impl Context {
     pub fn options(&self) -> &ProofOptions {
         &self.options
     }
+
+    /// Returns the number of consecutive trace rows at which trace polynomials are opened during
+    /// DEEP composition for the computation described by this context.
+    pub fn frame_width(&self) -> usize {
+        self.frame_width
+    }
 }
 
 impl<E: StarkField> ToElements<E> for Context {
@@ -104,6 +127,7 @@ impl<E: StarkField> ToElements<E> for Context {
     /// - blowup factor [1 element].
     /// - number of queries [1 element].
     /// - trace length [1 element].
+    /// - frame width [1 element].
     /// - trace metadata [0 or more elements].
     fn to_elements(&self) -> Vec<E> {
         // convert trace layout
@@ -118,6 +142,7 @@ impl<E: StarkField> ToElements<E> for Context {
         // convert proof options and trace length to elements
         result.append(&mut self.options.to_elements());
         result.push(E::from(self.trace_length as u64));
+        result.push(E::from(self.frame_width as u32));
 
         // convert trace metadata to elements; this is done by breaking trace metadata into chunks
         // of bytes which are slightly smaller than the number of bytes needed to encode a field
@@ -137,6 +162,7 @@ impl Serializable for Context {
     fn write_into<W: ByteWriter>(&self, target: &mut W) {
         self.trace_layout.write_into(target);
         target.write_u8(self.trace_length.ilog2() as u8); // store as power of two
+        target.write_u8(self.frame_width as u8);
         target.write_u16(self.trace_meta.len() as u16);
         target.write_bytes(&self.trace_meta);
         assert!(self.field_modulus_bytes.len() < u8::MAX as usize);
@@ -164,8 +190,25 @@ impl Deserializable for Context {
                 trace_length
             )));
         }
+        if trace_length as u32 >= usize::BITS {
+            return Err(DeserializationError::InvalidValue(format!(
+                "trace length cannot be greater than or equal to 2^{}, but was 2^{}",
+                usize::BITS,
+                trace_length
+            )));
+        }
         let trace_length = 2_usize.pow(trace_length as u32);
 
+        // read and validate frame width
+        let frame_width = source.read_u8()? as usize;
+        if frame_width < 2 || frame_width > trace_length {
+            return Err(DeserializationError::InvalidValue(format!(
+                "frame width must be between 2 and {}, but was {}",
+                cmp::min(u8::MAX as usize, trace_length),
+                frame_width
+            )));
+        }
+
         // read trace metadata
         let num_meta_bytes = source.read_u16()? as usize;
         let trace_meta = if num_meta_bytes != 0 {
@@ -192,6 +235,7 @@ impl Deserializable for Context {
             trace_meta,
             field_modulus_bytes,
             options,
+            frame_width,
         })
     }
 }
@@ -221,7 +265,10 @@ fn bytes_to_element<B: StarkField>(bytes: &[u8]) -> B {
 
 #[cfg(test)]
 mod tests {
-    use super::{Context, ProofOptions, ToElements, TraceInfo};
+    use super::{
+        Context, Deserializable, DeserializationError, ProofOptions, Serializable, ToElements,
+        TraceInfo,
+    };
     use crate::{FieldExtension, TraceLayout};
     use math::fields::f64::BaseElement;
 
@@ -258,6 +305,7 @@ mod tests {
             BaseElement::from(blowup_factor as u32),
             BaseElement::from(num_queries as u32),
             BaseElement::from(trace_length as u32),
+            BaseElement::from(2_u32), // frame width
         ];
 
         let options = ProofOptions::new(
@@ -271,7 +319,60 @@ mod tests {
         let layout =
             TraceLayout::new(main_width as usize, [aux_width as usize], [aux_rands as usize]);
         let trace_info = TraceInfo::new_multi_segment(layout, trace_length, vec![]);
-        let context = Context::new::<BaseElement>(&trace_info, options);
+        let context = Context::new::<BaseElement>(&trace_info, options, 2);
         assert_eq!(expected, context.to_elements());
     }
+
+    #[test]
+    fn context_large_trace_length() {
+        // a trace with 2^32 rows is serialized as a power of two, and its length is reduced into a
+        // single element without truncation
+        let trace_length = 1_usize << 32;
+        let options = ProofOptions::new(32, 8, 0, FieldExtension::None, 4, 31);
+        let trace_info = TraceInfo::new(4, trace_length);
+        let context = Context::new::<BaseElement>(&trace_info, options, 2);
+        assert_eq!(trace_length, context.trace_length());
+        assert_eq!(trace_length * 8, context.lde_domain_size());
+        let elements: Vec<BaseElement> = context.to_elements();
+        assert_eq!(BaseElement::from(trace_length as u64), elements[elements.len() - 2]);
+
+        let bytes = context.to_bytes();
+        assert_eq!(context, Context::read_from_bytes(&bytes).unwrap());
+
+        // trace lengths which do not fit into usize are rejected
+        let mut bytes = bytes;
+        let position = trace_info.layout().to_bytes().len();
+        assert_eq!(32, bytes[position]);
+        bytes[position] = usize::BITS as u8;
+        assert!(matches!(
+            Context::read_from_bytes(&bytes),
+            Err(DeserializationError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn context_frame_width() {
+        let options = ProofOptions::new(32, 8, 0, FieldExtension::None, 4, 31);
+        let trace_info = TraceInfo::new(4, 8);
+        let context = Context::new::<BaseElement>(&trace_info, options, 3);
+        assert_eq!(3, context.frame_width());
+        let elements: Vec<BaseElement> = context.to_elements();
+        assert_eq!(BaseElement::from(3_u32), elements[elements.len() - 1]);
+
+        // the frame width is serialized right after the trace length
+        let bytes = context.to_bytes();
+        assert_eq!(context, Context::read_from_bytes(&bytes).unwrap());
+        let position = trace_info.layout().to_bytes().len() + 1;
+        assert_eq!(3, bytes[position]);
+
+        // frame widths smaller than 2 or greater than the trace length are rejected
+        for frame_width in [0, 1, 9] {
+            let mut bytes = bytes.clone();
+            bytes[position] = frame_width;
+            assert!(matches!(
+                Context::read_from_bytes(&bytes),
+                Err(DeserializationError::InvalidValue(_))
+            ));
+        }
+    }
 }
@@ -0,0 +1,105 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::StarkProof;
+use crypto::{hashers::Blake3_256, Digest, Hasher};
+use math::{fields::f64::BaseElement, FieldElement};
+use utils::{
+    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+    SliceReader,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Domain separator for the metadata binding the first proof of a [DualProof] to the public
+/// inputs of the second proof.
+const DUAL_PROOF_DOMAIN: &[u8] = b"winterfell-dual-proof";
+
+// DUAL PROOF
+// ================================================================================================
+/// A pair of STARK proofs for two computations, possibly over different base fields, generated
+/// with a shared transcript.
+///
+/// **This is experimental.** The two computations are described by two independent AIRs, and each
+/// proof is committed to and constrained in the base field of its own AIR. The transcripts of the
+/// proofs are chained via proof metadata (see `Prover::prove_with_metadata()`):
+/// * The first proof is bound to the public inputs of the second computation; that is, its
+///   metadata is a hash of the public inputs of the second computation (see
+///   [first_metadata()](DualProof::first_metadata)).
+/// * The second proof is bound to the entire first proof; that is, its metadata is the
+///   identifier of the first proof (see [second_metadata()](DualProof::second_metadata)).
+///
+/// Thus, neither proof can be replaced or replayed in a different pair. Values shared between the
+/// two computations are not linked by the proof itself; the verifier must check the relation
+/// between the public inputs of the two computations.
+///
+/// Dual proofs are generated via `prove_dual()` function of the prover crate, and verified via
+/// `verify_dual()` function of the verifier crate.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DualProof {
+    /// Proof for the first computation.
+    pub first: StarkProof,
+    /// Proof for the second computation.
+    pub second: StarkProof,
+}
+
+impl DualProof {
+    /// Returns the metadata with which the first proof of a dual proof must be generated, given
+    /// the public inputs of the second computation (as returned by `to_elements()`).
+    pub fn first_metadata<E: FieldElement>(second_pub_inputs: &[E]) -> Vec<u8> {
+        let mut data = DUAL_PROOF_DOMAIN.to_vec();
+        data.extend_from_slice(E::elements_as_bytes(second_pub_inputs));
+        Blake3_256::<BaseElement>::hash(&data).as_bytes().to_vec()
+    }
+
+    /// Returns the metadata with which the second proof of a dual proof must be generated, given
+    /// the first proof.
+    pub fn second_metadata(first: &StarkProof) -> Vec<u8> {
+        first.id().to_vec()
+    }
+
+    /// Serializes this dual proof into a vector of bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        self.write_into(&mut result);
+        result
+    }
+
+    /// Returns a dual proof read from the specified `source`.
+    ///
+    /// # Errors
+    /// Returns an error if a valid dual proof could not be read from the specified `source`.
+    pub fn from_bytes(source: &[u8]) -> Result<Self, DeserializationError> {
+        let mut source = SliceReader::new(source);
+        let proof = Self::read_from(&mut source)?;
+        if source.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+        Ok(proof)
+    }
+}
+
+impl Serializable for DualProof {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        for proof in [&self.first, &self.second] {
+            let bytes = proof.to_bytes();
+            target.write_u32(bytes.len() as u32);
+            target.write_bytes(&bytes);
+        }
+    }
+}
+
+impl Deserializable for DualProof {
+    /// Reads a dual proof from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_bytes = source.read_u32()? as usize;
+        let first = StarkProof::from_bytes(&source.read_vec(num_bytes)?)?;
+        let num_bytes = source.read_u32()? as usize;
+        let second = StarkProof::from_bytes(&source.read_vec(num_bytes)?)?;
+        Ok(DualProof { first, second })
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::ProofSignatureScheme;
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+use utils::{
+    string::ToString, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+};
+
+// ED25519 SIGNATURE SCHEME
+// ================================================================================================
+/// Ed25519 signature scheme for signing proof identifiers.
+///
+/// Proof identifiers are signed with an [Ed25519SecretKey], and signatures are checked
+/// via strict verification (see [VerifyingKey::verify_strict()]), which rejects signatures with
+/// small-order components and non-canonical encodings.
+///
+/// This scheme is available only when `ed25519` feature is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ed25519;
+
+impl ProofSignatureScheme for Ed25519 {
+    type SecretKey = Ed25519SecretKey;
+    type PublicKey = Ed25519PublicKey;
+    type Signature = Ed25519Signature;
+
+    fn sign(secret_key: &Ed25519SecretKey, proof_id: &[u8; 32]) -> Ed25519Signature {
+        Ed25519Signature(secret_key.sign(proof_id))
+    }
+
+    fn verify(
+        public_key: &Ed25519PublicKey,
+        proof_id: &[u8; 32],
+        signature: &Ed25519Signature,
+    ) -> bool {
+        public_key.0.verify_strict(proof_id, &signature.0).is_ok()
+    }
+}
+
+// SECRET KEY
+// ================================================================================================
+/// Ed25519 secret key with which proof identifiers are signed.
+pub type Ed25519SecretKey = SigningKey;
+
+// PUBLIC KEY
+// ================================================================================================
+/// Ed25519 public key with which signatures over proof identifiers are checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ed25519PublicKey(pub VerifyingKey);
+
+impl Ed25519PublicKey {
+    /// Returns the public key corresponding to the specified secret key.
+    pub fn from_secret_key(secret_key: &Ed25519SecretKey) -> Self {
+        Self(secret_key.verifying_key())
+    }
+}
+
+impl Serializable for Ed25519PublicKey {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_bytes(self.0.as_bytes());
+    }
+}
+
+impl Deserializable for Ed25519PublicKey {
+    /// Reads an Ed25519 public key from the specified `source`.
+    ///
+    /// # Errors
+    /// Returns an error if the bytes read from the `source` do not encode a valid curve point.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let bytes = source.read_array()?;
+        let key = VerifyingKey::from_bytes(&bytes)
+            .map_err(|err| DeserializationError::InvalidValue(err.to_string()))?;
+        Ok(Self(key))
+    }
+}
+
+// SIGNATURE
+// ================================================================================================
+/// Ed25519 signature over a proof identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ed25519Signature(pub ed25519_dalek::Signature);
+
+impl Serializable for Ed25519Signature {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_bytes(&self.0.to_bytes());
+    }
+}
+
+impl Deserializable for Ed25519Signature {
+    /// Reads an Ed25519 signature from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let bytes = source.read_array()?;
+        Ok(Self(ed25519_dalek::Signature::from_bytes(&bytes)))
+    }
+}
@@ -0,0 +1,724 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{Commitments, Context, OodFrame, Queries, StarkProof};
+use fri::FriProof;
+use utils::{
+    collections::Vec, string::ToString, ByteReader, ByteWriter, Deserializable,
+    DeserializationError, Serializable, SliceReader,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Magic bytes at the start of every proof serialized in the flat layout.
+pub const FLAT_PROOF_MAGIC: [u8; 4] = *b"WFLT";
+
+/// Version of the flat layout described by [FlatProofHeader].
+pub const FLAT_PROOF_VERSION: u32 = 1;
+
+/// Alignment (in bytes) of the header, the section table, and every section of a flat proof.
+pub const FLAT_PROOF_ALIGNMENT: usize = 8;
+
+/// Number of sections present in every flat proof regardless of the number of trace segments
+/// and FRI layers: context, commitments, constraint query values and paths, OOD trace states and
+/// constraint evaluations, FRI remainder, metadata, and embedded public inputs.
+const NUM_FIXED_SECTIONS: usize = 9;
+
+// FLAT PROOF HEADER
+// ================================================================================================
+/// Fixed-width header of a STARK proof serialized in the flat layout.
+///
+/// The flat layout is an alternative to the compact encoding produced by
+/// [StarkProof::to_bytes()]. It trades proof size for the ability to access proof data in place:
+/// all integers have fixed widths, and every part of the proof starts at an offset which is a
+/// multiple of [FLAT_PROOF_ALIGNMENT]. Thus, as long as the buffer itself is placed at an 8-byte
+/// aligned address, a C struct can be overlaid directly on top of the header and of the section
+/// table.
+///
+/// # Layout
+/// All integers are little-endian. A flat proof consists of a header, a section table, and a
+/// sequence of sections, in that order:
+///
+/// | offset | size | field                    | description                                  |
+/// | -----: | ---: | ------------------------ | -------------------------------------------- |
+/// |      0 |    4 | `magic`                  | always `b"WFLT"`                             |
+/// |      4 |    4 | `version`                | always 1                                     |
+/// |      8 |    8 | `total_size`             | size of the entire proof in bytes            |
+/// |     16 |    8 | `pow_nonce`              | proof-of-work nonce for query seed grinding  |
+/// |     24 |    8 | `trace_length`           | number of rows in the execution trace        |
+/// |     32 |    4 | `main_trace_width`       | number of columns in the main trace segment  |
+/// |     36 |    4 | `aux_trace_width`        | number of columns in all auxiliary segments  |
+/// |     40 |    4 | `num_queries`            | number of queries specified in proof options |
+/// |     44 |    4 | `num_unique_queries`     | number of unique queried positions           |
+/// |     48 |    4 | `blowup_factor`          | trace LDE blowup factor                      |
+/// |     52 |    4 | `field_extension_degree` | degree of the field extension (1, 2, or 3)   |
+/// |     56 |    4 | `num_trace_segments`     | number of execution trace segments (`S`)     |
+/// |     60 |    4 | `num_fri_layers`         | number of FRI layers in the proof (`L`)      |
+/// |     64 |    4 | `num_fri_partitions`     | number of partitions used by the FRI prover  |
+/// |     68 |    4 | `num_sections`           | number of sections; always `9 + 2S + 2L`     |
+///
+/// The header is immediately followed by the section table at offset 72. The table consists of
+/// `num_sections` descriptors of 16 bytes each (see [FlatSection]): descriptor *i* is located
+/// at offset `72 + 16 * i` and contains the offset of section *i* (bytes 0..8) and its length in
+/// bytes (bytes 8..16). Sections appear in the following order:
+///
+/// | index         | section                                                          |
+/// | ------------- | ---------------------------------------------------------------- |
+/// | 0             | proof context in the compact encoding                            |
+/// | 1             | trace, constraint, and FRI layer commitments (digests)           |
+/// | 2 + 2i        | query values of trace segment *i*, for *i* in 0..S               |
+/// | 3 + 2i        | Merkle authentication paths of trace segment *i*, for *i* in 0..S |
+/// | 2 + 2S        | constraint composition query values                              |
+/// | 3 + 2S        | constraint composition Merkle authentication paths               |
+/// | 4 + 2S        | out-of-domain trace states                                       |
+/// | 5 + 2S        | out-of-domain constraint evaluations                             |
+/// | 6 + 2S        | FRI remainder polynomial coefficients                            |
+/// | 7 + 2S + 2j   | query values of FRI layer *j*, for *j* in 0..L                   |
+/// | 8 + 2S + 2j   | Merkle authentication paths of FRI layer *j*, for *j* in 0..L     |
+/// | 7 + 2S + 2L   | application-specific metadata (may be empty)                     |
+/// | 8 + 2S + 2L   | serialized embedded public inputs (may be empty)                 |
+///
+/// The first section starts immediately after the section table. Every subsequent section starts
+/// at the end of the previous one rounded up to a multiple of [FLAT_PROOF_ALIGNMENT]; padding
+/// bytes are always zero. `total_size` is the end of the last section rounded up to a multiple
+/// of [FLAT_PROOF_ALIGNMENT]. Bytes within sections are exactly the bytes stored internally by
+/// the corresponding proof components (e.g., query values are field elements serialized in their
+/// canonical little-endian form).
+///
+/// In C, the header and the section descriptors map onto the following structs:
+///
+/// ```c
+/// struct flat_proof_header {
+///     uint8_t  magic[4];
+///     uint32_t version;
+///     uint64_t total_size;
+///     uint64_t pow_nonce;
+///     uint64_t trace_length;
+///     uint32_t main_trace_width;
+///     uint32_t aux_trace_width;
+///     uint32_t num_queries;
+///     uint32_t num_unique_queries;
+///     uint32_t blowup_factor;
+///     uint32_t field_extension_degree;
+///     uint32_t num_trace_segments;
+///     uint32_t num_fri_layers;
+///     uint32_t num_fri_partitions;
+///     uint32_t num_sections;
+/// };
+///
+/// struct flat_proof_section {
+///     uint64_t offset;
+///     uint64_t length;
+/// };
+/// ```
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct FlatProofHeader {
+    pub magic: [u8; 4],
+    pub version: u32,
+    pub total_size: u64,
+    pub pow_nonce: u64,
+    pub trace_length: u64,
+    pub main_trace_width: u32,
+    pub aux_trace_width: u32,
+    pub num_queries: u32,
+    pub num_unique_queries: u32,
+    pub blowup_factor: u32,
+    pub field_extension_degree: u32,
+    pub num_trace_segments: u32,
+    pub num_fri_layers: u32,
+    pub num_fri_partitions: u32,
+    pub num_sections: u32,
+}
+
+impl FlatProofHeader {
+    /// Size of the header in bytes.
+    pub const SIZE: usize = 72;
+
+    /// Returns the number of sections in a flat proof with the specified number of trace
+    /// segments and FRI layers.
+    pub const fn num_sections(num_trace_segments: usize, num_fri_layers: usize) -> usize {
+        NUM_FIXED_SECTIONS + 2 * num_trace_segments + 2 * num_fri_layers
+    }
+
+    /// Reads a flat proof header from the start of the specified `source`.
+    ///
+    /// # Errors
+    /// Returns an error if `source` is shorter than the header, or if the header does not start
+    /// with [FLAT_PROOF_MAGIC] followed by [FLAT_PROOF_VERSION].
+    pub fn read_from_bytes(source: &[u8]) -> Result<Self, DeserializationError> {
+        let mut source = SliceReader::new(source);
+
+        let magic = source.read_array::<4>()?;
+        if magic != FLAT_PROOF_MAGIC {
+            return Err(DeserializationError::InvalidValue(
+                "flat proof must start with the flat layout magic bytes".to_string(),
+            ));
+        }
+        let version = source.read_u32()?;
+        if version != FLAT_PROOF_VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "flat proof layout version {version} is not supported"
+            )));
+        }
+
+        Ok(FlatProofHeader {
+            magic,
+            version,
+            total_size: source.read_u64()?,
+            pow_nonce: source.read_u64()?,
+            trace_length: source.read_u64()?,
+            main_trace_width: source.read_u32()?,
+            aux_trace_width: source.read_u32()?,
+            num_queries: source.read_u32()?,
+            num_unique_queries: source.read_u32()?,
+            blowup_factor: source.read_u32()?,
+            field_extension_degree: source.read_u32()?,
+            num_trace_segments: source.read_u32()?,
+            num_fri_layers: source.read_u32()?,
+            num_fri_partitions: source.read_u32()?,
+            num_sections: source.read_u32()?,
+        })
+    }
+
+    /// Serializes `self` into exactly [FlatProofHeader::SIZE] bytes and writes them into the
+    /// `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_bytes(&self.magic);
+        target.write_u32(self.version);
+        target.write_u64(self.total_size);
+        target.write_u64(self.pow_nonce);
+        target.write_u64(self.trace_length);
+        target.write_u32(self.main_trace_width);
+        target.write_u32(self.aux_trace_width);
+        target.write_u32(self.num_queries);
+        target.write_u32(self.num_unique_queries);
+        target.write_u32(self.blowup_factor);
+        target.write_u32(self.field_extension_degree);
+        target.write_u32(self.num_trace_segments);
+        target.write_u32(self.num_fri_layers);
+        target.write_u32(self.num_fri_partitions);
+        target.write_u32(self.num_sections);
+    }
+}
+
+// FLAT PROOF SECTION
+// ================================================================================================
+/// Descriptor of a single section of a proof serialized in the flat layout.
+///
+/// See [FlatProofHeader] for the description of the layout.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct FlatSection {
+    /// Offset of the section from the start of the proof; always a multiple of
+    /// [FLAT_PROOF_ALIGNMENT].
+    pub offset: u64,
+    /// Length of the section in bytes, not including padding.
+    pub length: u64,
+}
+
+impl FlatSection {
+    /// Size of a section descriptor in bytes.
+    pub const SIZE: usize = 16;
+}
+
+// STARK PROOF FLAT SERIALIZATION
+// ================================================================================================
+
+impl StarkProof {
+    /// Serializes this proof into a vector of bytes using the flat layout described in
+    /// [FlatProofHeader].
+    ///
+    /// The resulting proof is larger than the one produced by [to_bytes()](StarkProof::to_bytes),
+    /// but can be accessed without parsing.
+    pub fn to_flat_bytes(&self) -> Vec<u8> {
+        let context = self.context.to_bytes();
+        let fri_layers = self.fri_proof.layer_bytes();
+
+        // collect all sections in the order defined by the layout
+        let mut sections: Vec<&[u8]> = Vec::with_capacity(FlatProofHeader::num_sections(
+            self.trace_queries.len(),
+            fri_layers.len(),
+        ));
+        sections.push(&context);
+        sections.push(self.commitments.raw_bytes());
+        for queries in self.trace_queries.iter() {
+            let (values, paths) = queries.raw_parts();
+            sections.push(values);
+            sections.push(paths);
+        }
+        let (values, paths) = self.constraint_queries.raw_parts();
+        sections.push(values);
+        sections.push(paths);
+        let (trace_states, evaluations) = self.ood_frame.raw_parts();
+        sections.push(trace_states);
+        sections.push(evaluations);
+        sections.push(self.fri_proof.remainder_bytes());
+        for &(values, paths) in fri_layers.iter() {
+            sections.push(values);
+            sections.push(paths);
+        }
+        sections.push(&self.metadata);
+        sections.push(&self.pub_inputs);
+
+        // compute section descriptors
+        let mut offset = FlatProofHeader::SIZE + sections.len() * FlatSection::SIZE;
+        let descriptors = sections
+            .iter()
+            .map(|section| {
+                let descriptor = FlatSection {
+                    offset: offset as u64,
+                    length: section.len() as u64,
+                };
+                offset = align_up(offset + section.len());
+                descriptor
+            })
+            .collect::<Vec<_>>();
+
+        let trace_layout = self.context.trace_layout();
+        let options = self.context.options();
+        let header = FlatProofHeader {
+            magic: FLAT_PROOF_MAGIC,
+            version: FLAT_PROOF_VERSION,
+            total_size: offset as u64,
+            pow_nonce: self.pow_nonce,
+            trace_length: self.context.trace_length() as u64,
+            main_trace_width: trace_layout.main_trace_width() as u32,
+            aux_trace_width: trace_layout.aux_trace_width() as u32,
+            num_queries: options.num_queries() as u32,
+            num_unique_queries: self.num_unique_queries as u32,
+            blowup_factor: options.blowup_factor() as u32,
+            field_extension_degree: options.field_extension().degree(),
+            num_trace_segments: self.trace_queries.len() as u32,
+            num_fri_layers: fri_layers.len() as u32,
+            num_fri_partitions: self.fri_proof.num_partitions() as u32,
+            num_sections: sections.len() as u32,
+        };
+
+        // write the header, the section table, and all sections padded to the alignment
+        let mut result = Vec::with_capacity(offset);
+        header.write_into(&mut result);
+        for descriptor in descriptors.iter() {
+            result.write_u64(descriptor.offset);
+            result.write_u64(descriptor.length);
+        }
+        for section in sections {
+            result.write_bytes(section);
+            result.resize(align_up(result.len()), 0);
+        }
+        debug_assert_eq!(result.len(), offset, "flat proof size mismatch");
+
+        result
+    }
+
+    /// Returns a STARK proof read from the specified `source` serialized using the flat layout
+    /// described in [FlatProofHeader].
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The header of the flat proof is invalid or inconsistent with the proof context.
+    /// * The section table is inconsistent with the layout, i.e., sections are not stored in
+    ///   order, are not aligned, or overlap.
+    /// * Padding bytes are not zero, or `source` contains bytes beyond `total_size`.
+    /// * Any of the sections could not be parsed into a valid proof component.
+    pub fn from_flat_bytes(source: &[u8]) -> Result<Self, DeserializationError> {
+        let header = FlatProofHeader::read_from_bytes(source)?;
+        if header.total_size != source.len() as u64 {
+            return Err(DeserializationError::InvalidValue(format!(
+                "flat proof size must be {} bytes, but was {} bytes",
+                header.total_size,
+                source.len()
+            )));
+        }
+
+        // validate the number of sections implied by the header
+        let num_trace_segments = header.num_trace_segments as usize;
+        let num_fri_layers = header.num_fri_layers as usize;
+        let num_sections = FlatProofHeader::num_sections(num_trace_segments, num_fri_layers);
+        if header.num_sections as usize != num_sections {
+            return Err(DeserializationError::InvalidValue(format!(
+                "flat proof must contain {} sections, but {} were specified",
+                num_sections, header.num_sections
+            )));
+        }
+
+        // read the section table and make sure sections are laid out exactly as the layout
+        // prescribes
+        let table_end = num_sections
+            .checked_mul(FlatSection::SIZE)
+            .and_then(|size| size.checked_add(FlatProofHeader::SIZE))
+            .ok_or(DeserializationError::UnexpectedEOF)?;
+        let mut table = SliceReader::new(
+            source
+                .get(FlatProofHeader::SIZE..table_end)
+                .ok_or(DeserializationError::UnexpectedEOF)?,
+        );
+        let mut sections = Vec::with_capacity(num_sections);
+        let mut expected_offset = table_end;
+        for i in 0..num_sections {
+            let offset = table.read_u64()?;
+            let length = table.read_u64()?;
+            if offset != expected_offset as u64 {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "section {i} must start at offset {expected_offset}, but starts at {offset}"
+                )));
+            }
+            let end = usize::try_from(length)
+                .ok()
+                .and_then(|length| expected_offset.checked_add(length))
+                .filter(|&end| end <= source.len())
+                .ok_or(DeserializationError::UnexpectedEOF)?;
+            sections.push(source[expected_offset..end].to_vec());
+
+            expected_offset = align_up(end);
+            let padding =
+                source.get(end..expected_offset).ok_or(DeserializationError::UnexpectedEOF)?;
+            if padding.iter().any(|&byte| byte != 0) {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "padding after section {i} must consist of zero bytes"
+                )));
+            }
+        }
+        if expected_offset != source.len() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+
+        // parse the context and make sure it is consistent with the header
+        let mut sections = sections.into_iter();
+        let mut next_section = || sections.next().expect("not enough sections");
+
+        let context_bytes = next_section();
+        let mut reader = SliceReader::new(&context_bytes);
+        let context = Context::read_from(&mut reader)?;
+        if reader.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+        validate_header(&header, &context)?;
+
+        let commitments = Commitments::from_raw_parts(next_section());
+        let trace_queries = (0..num_trace_segments)
+            .map(|_| Queries::from_raw_parts(next_section(), next_section()))
+            .collect();
+        let constraint_queries = Queries::from_raw_parts(next_section(), next_section());
+        let ood_frame = OodFrame::from_raw_parts(next_section(), next_section());
+        let remainder = next_section();
+        let fri_layers = (0..num_fri_layers).map(|_| (next_section(), next_section())).collect();
+        let fri_proof =
+            FriProof::from_raw_parts(fri_layers, remainder, header.num_fri_partitions as usize)?;
+        let metadata = next_section();
+        let pub_inputs = next_section();
+
+        Ok(StarkProof {
+            context,
+            num_unique_queries: header.num_unique_queries as u8,
+            commitments,
+            trace_queries,
+            constraint_queries,
+            ood_frame,
+            fri_proof,
+            pow_nonce: header.pow_nonce,
+            metadata,
+            pub_inputs,
+        })
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Rounds `offset` up to the nearest multiple of [FLAT_PROOF_ALIGNMENT].
+fn align_up(offset: usize) -> usize {
+    (offset + FLAT_PROOF_ALIGNMENT - 1) & !(FLAT_PROOF_ALIGNMENT - 1)
+}
+
+/// Makes sure the fixed-width values in the `header` are consistent with the proof `context`.
+fn validate_header(
+    header: &FlatProofHeader,
+    context: &Context,
+) -> Result<(), DeserializationError> {
+    let trace_layout = context.trace_layout();
+    let options = context.options();
+
+    let expected = [
+        ("trace length", context.trace_length() as u64, header.trace_length),
+        (
+            "main trace width",
+            trace_layout.main_trace_width() as u64,
+            header.main_trace_width as u64,
+        ),
+        (
+            "aux trace width",
+            trace_layout.aux_trace_width() as u64,
+            header.aux_trace_width as u64,
+        ),
+        ("number of queries", options.num_queries() as u64, header.num_queries as u64),
+        ("blowup factor", options.blowup_factor() as u64, header.blowup_factor as u64),
+        (
+            "field extension degree",
+            options.field_extension().degree() as u64,
+            header.field_extension_degree as u64,
+        ),
+    ];
+    for (name, expected, actual) in expected {
+        if expected != actual {
+            return Err(DeserializationError::InvalidValue(format!(
+                "{name} in flat proof header must be {expected}, but was {actual}"
+            )));
+        }
+    }
+
+    // the prover adds an auxiliary segment to the trace when the computation defines range checks
+    // but no auxiliary segments of its own; thus, the number of trace segments in the proof may
+    // exceed the number of segments in the trace layout by one
+    let num_segments = trace_layout.num_segments() as u32;
+    if header.num_trace_segments < num_segments || header.num_trace_segments > num_segments + 1 {
+        return Err(DeserializationError::InvalidValue(format!(
+            "number of trace segments in flat proof header must be between {} and {}, but was {}",
+            num_segments,
+            num_segments + 1,
+            header.num_trace_segments
+        )));
+    }
+
+    if header.num_unique_queries > u8::MAX as u32 {
+        return Err(DeserializationError::InvalidValue(format!(
+            "number of unique queries cannot exceed {}, but was {}",
+            u8::MAX,
+            header.num_unique_queries
+        )));
+    }
+
+    Ok(())
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        FlatProofHeader, FlatSection, StarkProof, FLAT_PROOF_ALIGNMENT, FLAT_PROOF_MAGIC,
+        FLAT_PROOF_VERSION,
+    };
+    use crate::{
+        proof::{Commitments, Context, OodFrame, Queries},
+        FieldExtension, ProofOptions, TraceInfo, TraceLayout,
+    };
+    use crypto::{hashers::Blake3_256, Hasher, MerkleTree};
+    use fri::FriProof;
+    use math::fields::f64::BaseElement;
+    use utils::{collections::Vec, Serializable};
+
+    type Blake3 = Blake3_256<BaseElement>;
+
+    /// Field names, offsets, and sizes of the header as documented in [FlatProofHeader].
+    const HEADER_TABLE: [(&str, usize, usize); 15] = [
+        ("magic", 0, 4),
+        ("version", 4, 4),
+        ("total_size", 8, 8),
+        ("pow_nonce", 16, 8),
+        ("trace_length", 24, 8),
+        ("main_trace_width", 32, 4),
+        ("aux_trace_width", 36, 4),
+        ("num_queries", 40, 4),
+        ("num_unique_queries", 44, 4),
+        ("blowup_factor", 48, 4),
+        ("field_extension_degree", 52, 4),
+        ("num_trace_segments", 56, 4),
+        ("num_fri_layers", 60, 4),
+        ("num_fri_partitions", 64, 4),
+        ("num_sections", 68, 4),
+    ];
+
+    #[test]
+    fn flat_proof_round_trip() {
+        let proof = build_proof();
+        let bytes = proof.to_flat_bytes();
+        assert_eq!(0, bytes.len() % FLAT_PROOF_ALIGNMENT);
+        assert_eq!(proof, StarkProof::from_flat_bytes(&bytes).unwrap());
+
+        // the flat layout must not affect the compact encoding
+        assert_eq!(proof, StarkProof::from_bytes(&proof.to_bytes()).unwrap());
+    }
+
+    #[test]
+    fn flat_proof_header_layout() {
+        assert_eq!(FlatProofHeader::SIZE, core::mem::size_of::<FlatProofHeader>());
+        assert_eq!(FLAT_PROOF_ALIGNMENT, core::mem::align_of::<FlatProofHeader>());
+        assert_eq!(FlatSection::SIZE, core::mem::size_of::<FlatSection>());
+
+        // offsets of the #[repr(C)] struct must match the documented table
+        let proof = build_proof();
+        let bytes = proof.to_flat_bytes();
+        let header = FlatProofHeader::read_from_bytes(&bytes).unwrap();
+        let base = &header as *const FlatProofHeader as usize;
+        let field_offsets = [
+            &header.magic as *const _ as usize,
+            &header.version as *const _ as usize,
+            &header.total_size as *const _ as usize,
+            &header.pow_nonce as *const _ as usize,
+            &header.trace_length as *const _ as usize,
+            &header.main_trace_width as *const _ as usize,
+            &header.aux_trace_width as *const _ as usize,
+            &header.num_queries as *const _ as usize,
+            &header.num_unique_queries as *const _ as usize,
+            &header.blowup_factor as *const _ as usize,
+            &header.field_extension_degree as *const _ as usize,
+            &header.num_trace_segments as *const _ as usize,
+            &header.num_fri_layers as *const _ as usize,
+            &header.num_fri_partitions as *const _ as usize,
+            &header.num_sections as *const _ as usize,
+        ];
+        let mut expected_offset = 0;
+        for (&(name, offset, size), field_address) in HEADER_TABLE.iter().zip(field_offsets) {
+            assert_eq!(offset, field_address - base, "offset of {name}");
+            assert_eq!(expected_offset, offset, "{name} must immediately follow previous field");
+            expected_offset += size;
+        }
+        assert_eq!(FlatProofHeader::SIZE, expected_offset);
+
+        // serialized values must be located at the documented offsets
+        let read = |name: &str| {
+            let &(_, offset, size) = HEADER_TABLE.iter().find(|(n, ..)| *n == name).unwrap();
+            let mut value = [0_u8; 8];
+            value[..size].copy_from_slice(&bytes[offset..offset + size]);
+            u64::from_le_bytes(value)
+        };
+        assert_eq!(u32::from_le_bytes(FLAT_PROOF_MAGIC) as u64, read("magic"));
+        assert_eq!(FLAT_PROOF_VERSION as u64, read("version"));
+        assert_eq!(bytes.len() as u64, read("total_size"));
+        assert_eq!(proof.pow_nonce, read("pow_nonce"));
+        assert_eq!(proof.trace_length() as u64, read("trace_length"));
+        assert_eq!(3, read("main_trace_width"));
+        assert_eq!(2, read("aux_trace_width"));
+        assert_eq!(proof.options().num_queries() as u64, read("num_queries"));
+        assert_eq!(proof.num_unique_queries as u64, read("num_unique_queries"));
+        assert_eq!(proof.options().blowup_factor() as u64, read("blowup_factor"));
+        assert_eq!(2, read("field_extension_degree"));
+        assert_eq!(2, read("num_trace_segments"));
+        assert_eq!(2, read("num_fri_layers"));
+        assert_eq!(4, read("num_fri_partitions"));
+        assert_eq!(FlatProofHeader::num_sections(2, 2) as u64, read("num_sections"));
+
+        // sections must be aligned, contiguous up to padding, and contain component bytes
+        let num_sections = FlatProofHeader::num_sections(2, 2);
+        let table_end = FlatProofHeader::SIZE + num_sections * FlatSection::SIZE;
+        let section = |i: usize| {
+            let descriptor = FlatProofHeader::SIZE + i * FlatSection::SIZE;
+            let offset = u64::from_le_bytes(bytes[descriptor..descriptor + 8].try_into().unwrap());
+            let length =
+                u64::from_le_bytes(bytes[descriptor + 8..descriptor + 16].try_into().unwrap());
+            (offset as usize, length as usize)
+        };
+        let mut expected_offset = table_end;
+        for i in 0..num_sections {
+            let (offset, length) = section(i);
+            assert_eq!(0, offset % FLAT_PROOF_ALIGNMENT);
+            assert_eq!(expected_offset, offset);
+            expected_offset = (offset + length).next_multiple_of(FLAT_PROOF_ALIGNMENT);
+        }
+        assert_eq!(bytes.len(), expected_offset);
+
+        let content = |i: usize| {
+            let (offset, length) = section(i);
+            &bytes[offset..offset + length]
+        };
+        assert_eq!(proof.context.to_bytes(), content(0));
+        assert_eq!(proof.fri_proof.remainder_bytes(), content(6 + 2 * 2));
+        for (j, (values, paths)) in proof.fri_proof.layer_bytes().into_iter().enumerate() {
+            assert_eq!(values, content(7 + 2 * 2 + 2 * j));
+            assert_eq!(paths, content(8 + 2 * 2 + 2 * j));
+        }
+        assert_eq!(proof.metadata, content(7 + 2 * 2 + 2 * 2));
+        assert_eq!(proof.pub_inputs, content(8 + 2 * 2 + 2 * 2));
+    }
+
+    #[test]
+    fn flat_proof_invalid() {
+        let bytes = build_proof().to_flat_bytes();
+
+        // invalid magic bytes
+        let mut invalid = bytes.clone();
+        invalid[0] = b'X';
+        assert!(StarkProof::from_flat_bytes(&invalid).is_err());
+
+        // unsupported version
+        let mut invalid = bytes.clone();
+        invalid[4] = 2;
+        assert!(StarkProof::from_flat_bytes(&invalid).is_err());
+
+        // truncated proof
+        assert!(StarkProof::from_flat_bytes(&bytes[..bytes.len() - 8]).is_err());
+        assert!(StarkProof::from_flat_bytes(&bytes[..FlatProofHeader::SIZE - 1]).is_err());
+
+        // trailing bytes
+        let mut invalid = bytes.clone();
+        invalid.extend_from_slice(&[0; 8]);
+        assert!(StarkProof::from_flat_bytes(&invalid).is_err());
+
+        // non-zero padding
+        let mut invalid = bytes.clone();
+        let last = invalid.len() - 1;
+        invalid[last] = 1;
+        assert!(StarkProof::from_flat_bytes(&invalid).is_err());
+
+        // header inconsistent with the context
+        let mut invalid = bytes;
+        invalid[32] += 1;
+        assert!(StarkProof::from_flat_bytes(&invalid).is_err());
+    }
+
+    // HELPER FUNCTIONS
+    // --------------------------------------------------------------------------------------------
+
+    fn build_proof() -> StarkProof {
+        let layout = TraceLayout::new(3, [2], [1]);
+        let trace_info = TraceInfo::new_multi_segment(layout, 16, vec![1, 2, 3]);
+        let options = ProofOptions::new(4, 8, 0, FieldExtension::Quadratic, 4, 7);
+        let context = Context::new::<BaseElement>(&trace_info, options, 2);
+
+        let digest = |i: u8| Blake3::hash(&[i]);
+        let commitments =
+            Commitments::new::<Blake3>(vec![digest(0), digest(1)], digest(2), vec![digest(3)]);
+
+        let tree = MerkleTree::<Blake3>::new((0..16).map(digest).collect()).unwrap();
+        let build_queries = |width: u64| {
+            let merkle_proof = tree.prove_batch(&[1, 5]).unwrap();
+            let values = (0..2)
+                .map(|i| (0..width).map(|j| BaseElement::new(i * width + j)).collect())
+                .collect::<Vec<Vec<_>>>();
+            Queries::new::<Blake3, BaseElement>(merkle_proof, values)
+        };
+
+        let mut ood_frame = OodFrame::default();
+        ood_frame.set_trace_states(&[
+            (0..5).map(BaseElement::new).collect(),
+            (5..10).map(BaseElement::new).collect(),
+        ]);
+        ood_frame.set_constraint_evaluations(&[BaseElement::new(11), BaseElement::new(12)]);
+
+        // odd section lengths make sure padding is exercised
+        let fri_proof = FriProof::from_raw_parts(
+            vec![(vec![1; 24], vec![2; 13]), (vec![3; 5], vec![])],
+            vec![4; 16],
+            4,
+        )
+        .unwrap();
+
+        StarkProof {
+            context,
+            num_unique_queries: 2,
+            commitments,
+            trace_queries: vec![build_queries(3), build_queries(2)],
+            constraint_queries: build_queries(1),
+            ood_frame,
+            fri_proof,
+            pow_nonce: 0x0102_0304_0506_0708,
+            metadata: b"block #42".to_vec(),
+            pub_inputs: vec![1, 2, 3],
+        }
+    }
+}
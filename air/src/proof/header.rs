@@ -0,0 +1,337 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::StarkProof;
+use crate::FieldExtension;
+use crypto::{hashers, Hasher};
+use math::{
+    fields::{f128, f62, f64},
+    StarkField,
+};
+use utils::{
+    collections::Vec, string::ToString, ByteReader, ByteWriter, Deserializable,
+    DeserializationError, Serializable, SliceReader,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Magic bytes at the start of every proof serialized with a self-describing header.
+pub const PROOF_HEADER_MAGIC: [u8; 4] = *b"WPRF";
+
+// FIELD IDENTIFIER
+// ================================================================================================
+/// Identifies the base field of a proof in a [ProofHeader].
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FieldId {
+    /// A field other than the ones provided by this library.
+    Unknown = 0,
+    /// 62-bit prime field with modulus $2^{62} - 111 \cdot 2^{39} + 1$.
+    F62 = 1,
+    /// 64-bit prime field with modulus $2^{64} - 2^{32} + 1$.
+    F64 = 2,
+    /// 128-bit prime field with modulus $2^{128} - 45 \cdot 2^{40} + 1$.
+    F128 = 3,
+}
+
+impl FieldId {
+    /// Returns the identifier of the field with the specified modulus encoded in little-endian
+    /// byte order.
+    pub fn from_modulus_bytes(modulus: &[u8]) -> Self {
+        if modulus == f62::BaseElement::get_modulus_le_bytes() {
+            Self::F62
+        } else if modulus == f64::BaseElement::get_modulus_le_bytes() {
+            Self::F64
+        } else if modulus == f128::BaseElement::get_modulus_le_bytes() {
+            Self::F128
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+impl Serializable for FieldId {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self as u8);
+    }
+}
+
+impl Deserializable for FieldId {
+    /// Reads a field identifier from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            0 => Ok(Self::Unknown),
+            1 => Ok(Self::F62),
+            2 => Ok(Self::F64),
+            3 => Ok(Self::F128),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "value {value} cannot be deserialized as FieldId enum"
+            ))),
+        }
+    }
+}
+
+// HASH FUNCTION IDENTIFIER
+// ================================================================================================
+/// Identifies the hash function used to generate a proof in a [ProofHeader].
+///
+/// A proof does not record the hash function it was generated with (unless separate hash
+/// functions are specified via [ProofOptions::with_hash_fns()](crate::ProofOptions::with_hash_fns)),
+/// and thus, the identifier must be supplied when the proof is serialized (see
+/// [StarkProof::to_bytes_with_header()]).
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum HashFunctionId {
+    /// A hash function other than the ones provided by this library.
+    Unknown = 0,
+    /// BLAKE3 hash function with 192-bit output.
+    Blake3_192 = 1,
+    /// BLAKE3 hash function with 256-bit output.
+    Blake3_256 = 2,
+    /// SHA3 hash function with 256-bit output.
+    Sha3_256 = 3,
+    /// Rescue Prime hash function over the 62-bit field with 248-bit output.
+    Rp62_248 = 4,
+    /// Rescue Prime hash function over the 64-bit field with 256-bit output.
+    Rp64_256 = 5,
+    /// Rescue Prime Optimized hash function over the 64-bit field with 256-bit output.
+    RpJive64_256 = 6,
+    /// Griffin hash function over the 64-bit field with 256-bit output.
+    GriffinJive64_256 = 7,
+    /// BLAKE3 hash function with 512-bit output.
+    Blake3_512 = 8,
+}
+
+impl Serializable for HashFunctionId {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self as u8);
+    }
+}
+
+impl Deserializable for HashFunctionId {
+    /// Reads a hash function identifier from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            0 => Ok(Self::Unknown),
+            1 => Ok(Self::Blake3_192),
+            2 => Ok(Self::Blake3_256),
+            3 => Ok(Self::Sha3_256),
+            4 => Ok(Self::Rp62_248),
+            5 => Ok(Self::Rp64_256),
+            6 => Ok(Self::RpJive64_256),
+            7 => Ok(Self::GriffinJive64_256),
+            8 => Ok(Self::Blake3_512),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "value {value} cannot be deserialized as HashFunctionId enum"
+            ))),
+        }
+    }
+}
+
+// IDENTIFIABLE HASHER
+// ================================================================================================
+/// A hash function with a known [HashFunctionId].
+///
+/// This is implemented for all hash functions provided by this library, and is used to make sure
+/// that hash functions recorded in proof options (see
+/// [ProofOptions::with_hash_fns()](crate::ProofOptions::with_hash_fns)) are the ones with which
+/// a proof is generated and verified. Other hash functions can implement this trait with
+/// [HashFunctionId::Unknown] identifier; however, since two such hash functions cannot be told
+/// apart by their identifiers, they cannot be used to generate or verify proofs with separate
+/// hash functions for commitments and FRI.
+pub trait IdentifiableHasher: Hasher {
+    /// Identifier of this hash function.
+    const ID: HashFunctionId;
+}
+
+impl<B: StarkField> IdentifiableHasher for hashers::Blake3_192<B> {
+    const ID: HashFunctionId = HashFunctionId::Blake3_192;
+}
+
+impl<B: StarkField> IdentifiableHasher for hashers::Blake3_256<B> {
+    const ID: HashFunctionId = HashFunctionId::Blake3_256;
+}
+
+impl<B: StarkField> IdentifiableHasher for hashers::Blake3_512<B> {
+    const ID: HashFunctionId = HashFunctionId::Blake3_512;
+}
+
+impl<B: StarkField> IdentifiableHasher for hashers::Sha3_256<B> {
+    const ID: HashFunctionId = HashFunctionId::Sha3_256;
+}
+
+impl IdentifiableHasher for hashers::Rp62_248 {
+    const ID: HashFunctionId = HashFunctionId::Rp62_248;
+}
+
+impl IdentifiableHasher for hashers::Rp64_256 {
+    const ID: HashFunctionId = HashFunctionId::Rp64_256;
+}
+
+impl IdentifiableHasher for hashers::RpJive64_256 {
+    const ID: HashFunctionId = HashFunctionId::RpJive64_256;
+}
+
+impl IdentifiableHasher for hashers::GriffinJive64_256 {
+    const ID: HashFunctionId = HashFunctionId::GriffinJive64_256;
+}
+
+// PROOF HEADER
+// ================================================================================================
+/// Self-describing header of a STARK proof.
+///
+/// The header can be prepended to the binary serialization of a proof (see
+/// [StarkProof::to_bytes_with_header()]) so that a consumer which receives proof bytes without
+/// any context can determine how the proof should be verified. The header can be parsed without
+/// deserializing the rest of the proof via [StarkProof::peek_header()].
+///
+/// # Layout
+/// All integers are little-endian. The header is immediately followed by the proof serialized via
+/// [StarkProof::to_bytes()].
+///
+/// | offset | size | field              | description                                      |
+/// | -----: | ---: | ------------------ | ------------------------------------------------ |
+/// |      0 |    4 | `magic`            | always `b"WPRF"`                                 |
+/// |      4 |    1 | `version`          | format version of the serialized proof           |
+/// |      5 |    1 | `field`            | base field identifier (see [FieldId])            |
+/// |      6 |    1 | `hash_fn`          | hash function identifier (see [HashFunctionId])  |
+/// |      7 |    1 | `field_extension`  | degree of the field extension (1, 2, or 3)       |
+/// |      8 |    2 | `main_trace_width` | number of columns in the main trace segment      |
+/// |     10 |    2 | `aux_trace_width`  | number of columns in all auxiliary segments      |
+/// |     12 |    8 | `trace_length`     | number of rows in the execution trace            |
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ProofHeader {
+    pub version: u8,
+    pub field: FieldId,
+    pub hash_fn: HashFunctionId,
+    pub field_extension: FieldExtension,
+    pub main_trace_width: u16,
+    pub aux_trace_width: u16,
+    pub trace_length: u64,
+}
+
+impl ProofHeader {
+    /// Size of the header in bytes.
+    pub const SIZE: usize = 20;
+
+    /// Returns a header describing the specified proof generated with the specified hash
+    /// function.
+    pub fn new(proof: &StarkProof, hash_fn: HashFunctionId) -> Self {
+        let trace_layout = proof.trace_layout();
+        ProofHeader {
+            version: StarkProof::FORMAT_VERSION,
+            field: FieldId::from_modulus_bytes(proof.context.field_modulus_bytes()),
+            hash_fn,
+            field_extension: proof.options().field_extension(),
+            main_trace_width: trace_layout.main_trace_width() as u16,
+            aux_trace_width: trace_layout.aux_trace_width() as u16,
+            trace_length: proof.trace_length() as u64,
+        }
+    }
+
+    /// Reads a proof header from the start of the specified `source`.
+    ///
+    /// # Errors
+    /// Returns an error if `source` is shorter than the header, if the header does not start with
+    /// [PROOF_HEADER_MAGIC], or if any of the header fields is invalid.
+    pub fn read_from_bytes(source: &[u8]) -> Result<Self, DeserializationError> {
+        let mut source = SliceReader::new(source);
+        Self::read_from(&mut source)
+    }
+}
+
+impl Serializable for ProofHeader {
+    /// Serializes `self` into exactly [ProofHeader::SIZE] bytes and writes them into the
+    /// `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_bytes(&PROOF_HEADER_MAGIC);
+        target.write_u8(self.version);
+        target.write(self.field);
+        target.write(self.hash_fn);
+        target.write(self.field_extension);
+        target.write_u16(self.main_trace_width);
+        target.write_u16(self.aux_trace_width);
+        target.write_u64(self.trace_length);
+    }
+}
+
+impl Deserializable for ProofHeader {
+    /// Reads a proof header from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let magic = source.read_array::<4>()?;
+        if magic != PROOF_HEADER_MAGIC {
+            return Err(DeserializationError::InvalidValue(
+                "proof must start with the proof header magic bytes".to_string(),
+            ));
+        }
+
+        Ok(ProofHeader {
+            version: source.read_u8()?,
+            field: FieldId::read_from(source)?,
+            hash_fn: HashFunctionId::read_from(source)?,
+            field_extension: FieldExtension::read_from(source)?,
+            main_trace_width: source.read_u16()?,
+            aux_trace_width: source.read_u16()?,
+            trace_length: source.read_u64()?,
+        })
+    }
+}
+
+// STARK PROOF SERIALIZATION WITH HEADER
+// ================================================================================================
+
+impl StarkProof {
+    /// Serializes this proof into a vector of bytes prefixed with a self-describing
+    /// [ProofHeader].
+    ///
+    /// Since a proof does not record the hash function it was generated with, the identifier of
+    /// the hash function must be specified via `hash_fn`.
+    pub fn to_bytes_with_header(&self, hash_fn: HashFunctionId) -> Vec<u8> {
+        let mut result = Vec::new();
+        ProofHeader::new(self, hash_fn).write_into(&mut result);
+        result.extend_from_slice(&self.to_bytes());
+        result
+    }
+
+    /// Returns the header of a proof serialized via
+    /// [to_bytes_with_header()](StarkProof::to_bytes_with_header) without deserializing the rest
+    /// of the proof.
+    ///
+    /// # Errors
+    /// Returns an error if a valid header could not be read from the start of `source`.
+    pub fn peek_header(source: &[u8]) -> Result<ProofHeader, DeserializationError> {
+        ProofHeader::read_from_bytes(source)
+    }
+
+    /// Returns a STARK proof read from the specified `source` serialized via
+    /// [to_bytes_with_header()](StarkProof::to_bytes_with_header), together with its header.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * A valid header could not be read from the start of `source`.
+    /// * A valid STARK proof could not be read from the bytes following the header.
+    /// * The header is inconsistent with the proof.
+    pub fn from_bytes_with_header(
+        source: &[u8],
+    ) -> Result<(ProofHeader, Self), DeserializationError> {
+        let header = Self::peek_header(source)?;
+        let proof = Self::from_bytes(&source[ProofHeader::SIZE..])?;
+
+        // the proof may have been serialized with an older compatible format version
+        let expected = ProofHeader {
+            version: source[ProofHeader::SIZE],
+            ..ProofHeader::new(&proof, header.hash_fn)
+        };
+        if header != expected {
+            return Err(DeserializationError::InvalidValue(format!(
+                "proof header {header:?} is inconsistent with the proof; expected {expected:?}"
+            )));
+        }
+        Ok((header, proof))
+    }
+}
@@ -5,22 +5,24 @@
 
 //! Contains STARK proof struct and associated components.
 
-use crate::{ProofOptions, TraceInfo, TraceLayout};
+use crate::{options::MAX_NUM_QUERIES, ChecksumError, ProofOptions, TraceInfo, TraceLayout};
 use core::cmp;
-use crypto::Hasher;
+use crypto::{hashers::Blake3_256, Digest, Hasher};
 use fri::FriProof;
+use math::{fields::f64::BaseElement, StarkField};
 use utils::{
-    collections::Vec, ByteReader, Deserializable, DeserializationError, Serializable, SliceReader,
+    collections::Vec, string::String, ByteReader, ByteWriter, Deserializable, DeserializationError,
+    Serializable, SliceReader,
 };
 
 mod context;
 pub use context::Context;
 
 mod commitments;
-pub use commitments::Commitments;
+pub use commitments::{get_fri_layer_seed, CommitmentRoots, Commitments};
 
 mod queries;
-pub use queries::Queries;
+pub use queries::{hash_column_roots, Queries, QueryResponse};
 
 mod ood_frame;
 pub use ood_frame::OodFrame;
@@ -28,26 +30,78 @@ pub use ood_frame::OodFrame;
 mod table;
 pub use table::Table;
 
+mod dual;
+pub use dual::DualProof;
+
+mod signed;
+pub use signed::{ProofSignatureScheme, SignedProof};
+
+mod verification_key;
+pub use verification_key::VerificationKey;
+
+mod header;
+pub use header::{FieldId, HashFunctionId, IdentifiableHasher, ProofHeader, PROOF_HEADER_MAGIC};
+
+#[cfg(feature = "ed25519")]
+mod ed25519;
+#[cfg(feature = "ed25519")]
+pub use ed25519::{Ed25519, Ed25519PublicKey, Ed25519SecretKey, Ed25519Signature};
+
+#[cfg(feature = "flat-layout")]
+mod flat;
+#[cfg(feature = "flat-layout")]
+pub use flat::{
+    FlatProofHeader, FlatSection, FLAT_PROOF_ALIGNMENT, FLAT_PROOF_MAGIC, FLAT_PROOF_VERSION,
+};
+
 // CONSTANTS
 // ================================================================================================
 
 const GRINDING_CONTRIBUTION_FLOOR: u32 = 80;
 const MAX_PROXIMITY_PARAMETER: u64 = 1000;
+const MAX_EXTENSION_DEGREE: u32 = 4;
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
 
 // STARK PROOF
 // ================================================================================================
 /// A proof generated by Winterfell prover.
 ///
 /// A STARK proof contains information proving that a computation was executed correctly. A proof
-/// also contains basic metadata for the computation, but neither the definition of the computation
-/// itself, nor public inputs consumed by the computation are contained in a proof.
+/// also contains basic metadata for the computation, but the definition of the computation itself
+/// is not contained in a proof. Public inputs consumed by the computation are contained in a proof
+/// only if they were explicitly embedded into it by the prover.
 ///
 /// A proof can be serialized into a sequence of bytes using [to_bytes()](StarkProof::to_bytes)
 /// function, and deserialized from a sequence of bytes using [from_bytes()](StarkProof::from_bytes)
-/// function.
+/// function. When `flat-layout` feature is enabled, a proof can also be serialized into a
+/// fixed-width, aligned layout suitable for zero-copy access via
+/// `to_flat_bytes()` function.
 ///
 /// To estimate soundness of a proof (in bits), [security_level()](StarkProof::security_level)
 /// function can be used.
+///
+/// For sharing proofs as text (e.g., in bug reports), a proof can also be encoded as a lowercase
+/// hex string of its binary serialization via [to_hex()](StarkProof::to_hex) function, and
+/// decoded via [from_hex()](StarkProof::from_hex) function.
+///
+/// To let consumers which receive proof bytes without any context determine how a proof should
+/// be verified, a proof can be serialized with a self-describing [ProofHeader] via
+/// [to_bytes_with_header()](StarkProof::to_bytes_with_header) function; the header can be read
+/// without deserializing the proof via [peek_header()](StarkProof::peek_header) function.
+///
+/// A stable identifier of a proof (e.g., for use as a cache key) can be computed via
+/// [id()](StarkProof::id) function.
+///
+/// To detect accidental truncation or corruption during storage or transport, a proof can be
+/// serialized with a trailing checksum via [to_bytes_with_checksum()](StarkProof::to_bytes_with_checksum)
+/// function, and deserialized via [from_bytes_with_checksum()](StarkProof::from_bytes_with_checksum)
+/// function.
+///
+/// The binary serialization of a proof starts with a format version byte. A proof serialized
+/// with any version between
+/// [MIN_COMPATIBLE_FORMAT_VERSION](StarkProof::MIN_COMPATIBLE_FORMAT_VERSION) and
+/// [FORMAT_VERSION](StarkProof::FORMAT_VERSION) can be read by this version of the crate;
+/// proofs serialized with other versions are rejected during deserialization.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct StarkProof {
     /// Basic metadata about the execution of the computation described by this proof.
@@ -69,9 +123,41 @@ pub struct StarkProof {
     pub fri_proof: FriProof,
     /// Proof-of-work nonce for query seed grinding.
     pub pow_nonce: u64,
+    /// Application-specific metadata bound into the proof transcript; this is empty if the proof
+    /// was generated without metadata.
+    pub metadata: Vec<u8>,
+    /// Serialized public inputs of the computation; this is empty unless the public inputs were
+    /// explicitly embedded into the proof so that the proof can be verified against a commitment
+    /// to the public inputs.
+    pub pub_inputs: Vec<u8>,
 }
 
 impl StarkProof {
+    /// Version of the binary serialization format written by [to_bytes()](StarkProof::to_bytes).
+    ///
+    /// This must be incremented whenever the serialization format changes.
+    pub const FORMAT_VERSION: u8 = 5;
+
+    /// The oldest version of the binary serialization format which can be read by
+    /// [from_bytes()](StarkProof::from_bytes).
+    ///
+    /// This must be set to [FORMAT_VERSION](StarkProof::FORMAT_VERSION) whenever the format
+    /// changes in a way which is not backward compatible.
+    pub const MIN_COMPATIBLE_FORMAT_VERSION: u8 = 5;
+
+    /// Number of bytes in the checksum appended by
+    /// [to_bytes_with_checksum()](StarkProof::to_bytes_with_checksum).
+    pub const CHECKSUM_BYTES: usize = 4;
+
+    /// Returns basic metadata about the execution of the computation described by this proof.
+    ///
+    /// The context exposes trace info, base field modulus, and STARK protocol parameters embedded
+    /// into the proof, and thus can be inspected without public inputs and without verifying the
+    /// proof.
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
     /// Returns STARK protocol parameters used to generate this proof.
     pub fn options(&self) -> &ProofOptions {
         self.context.options()
@@ -98,6 +184,28 @@ impl StarkProof {
         self.context.lde_domain_size()
     }
 
+    /// Returns roots of all commitments contained in this proof.
+    ///
+    /// The roots include commitments to all execution trace segments, the constraint composition
+    /// polynomial evaluation commitment, and commitments to all FRI layers (including the FRI
+    /// remainder). The roots can be anchored externally (e.g., on-chain), and the proof can then
+    /// be verified against the anchored roots (see `verify_with_anchored_roots()` function of the
+    /// verifier crate).
+    ///
+    /// # Errors
+    /// Returns an error if the commitments contained in this proof could not be parsed into the
+    /// number of roots implied by the proof (e.g., because the commitment to the main trace
+    /// segment was omitted from the proof).
+    pub fn commitment_roots<H: Hasher>(
+        &self,
+    ) -> Result<CommitmentRoots<H::Digest>, DeserializationError> {
+        let num_trace_segments = self.trace_queries.len();
+        let num_fri_layers = self.options().to_fri_options().num_fri_layers(self.lde_domain_size());
+        let (trace_roots, constraint_root, fri_roots) =
+            self.commitments.clone().parse::<H>(num_trace_segments, num_fri_layers)?;
+        Ok(CommitmentRoots::new(trace_roots, constraint_root, fri_roots))
+    }
+
     // SECURITY LEVEL
     // --------------------------------------------------------------------------------------------
     /// Returns security level of this proof (in bits).
@@ -106,12 +214,17 @@ impl StarkProof {
     /// security level is returned. Usually, the number of queries needed for provable security is
     /// 2x - 3x higher than the number of queries needed for conjectured security at the same
     /// security level.
+    ///
+    /// Both security levels account for the width of the frame at which trace polynomials are
+    /// opened during DEEP composition, as recorded in the proof context; frames wider than 2 rows
+    /// reduce the security level.
     pub fn security_level<H: Hasher>(&self, conjectured: bool) -> u32 {
         if conjectured {
             get_conjectured_security(
                 self.context.options(),
                 self.context.num_modulus_bits(),
                 self.trace_length(),
+                self.context.frame_width(),
                 H::COLLISION_RESISTANCE,
             )
         } else {
@@ -119,35 +232,71 @@ impl StarkProof {
                 self.context.options(),
                 self.context.num_modulus_bits(),
                 self.trace_length(),
+                self.context.frame_width(),
                 H::COLLISION_RESISTANCE,
             )
         }
     }
 
+    // PROOF IDENTIFIER
+    // --------------------------------------------------------------------------------------------
+    /// Returns a unique identifier of this proof.
+    ///
+    /// The identifier is a BLAKE3 hash of the binary serialization of this proof (as returned by
+    /// [to_bytes()](StarkProof::to_bytes)). Thus, byte-identical proofs have the same identifier,
+    /// while changing any part of a proof changes its identifier. The hash function is fixed and
+    /// does not depend on the hash function used to generate the proof, and so identifiers can
+    /// be used as cache keys for proofs of arbitrary computations.
+    ///
+    /// The identifier is also the message signed by a [SignedProof] to bind authorship to a proof.
+    pub fn id(&self) -> [u8; 32] {
+        Blake3_256::<BaseElement>::hash(&self.to_bytes()).as_bytes()
+    }
+
     // SERIALIZATION / DESERIALIZATION
     // --------------------------------------------------------------------------------------------
 
     /// Serializes this proof into a vector of bytes.
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut result = Vec::new();
+        result.push(Self::FORMAT_VERSION);
         self.context.write_into(&mut result);
         result.push(self.num_unique_queries);
         self.commitments.write_into(&mut result);
+        result.push(self.trace_queries.len() as u8);
         self.trace_queries.write_into(&mut result);
         self.constraint_queries.write_into(&mut result);
         self.ood_frame.write_into(&mut result);
         self.fri_proof.write_into(&mut result);
         result.extend_from_slice(&self.pow_nonce.to_le_bytes());
+        result.write_u32(self.metadata.len() as u32);
+        result.write_bytes(&self.metadata);
+        result.write_u32(self.pub_inputs.len() as u32);
+        result.write_bytes(&self.pub_inputs);
         result
     }
 
     /// Returns a STARK proof read from the specified `source`.
     ///
     /// # Errors
-    /// Returns an error of a valid STARK proof could not be read from the specified `source`.
+    /// Returns an error if:
+    /// * The proof was serialized with a format version which is not compatible with this
+    ///   version of the crate.
+    /// * A valid STARK proof could not be read from the specified `source`.
     pub fn from_bytes(source: &[u8]) -> Result<Self, DeserializationError> {
         let mut source = SliceReader::new(source);
 
+        // make sure the proof was serialized with a compatible format version
+        let version = source.read_u8()?;
+        if !(Self::MIN_COMPATIBLE_FORMAT_VERSION..=Self::FORMAT_VERSION).contains(&version) {
+            return Err(DeserializationError::InvalidValue(format!(
+                "proof format version {} is not compatible; expected a version between {} and {}",
+                version,
+                Self::MIN_COMPATIBLE_FORMAT_VERSION,
+                Self::FORMAT_VERSION
+            )));
+        }
+
         // parse the context
         let context = Context::read_from(&mut source)?;
 
@@ -157,47 +306,240 @@ impl StarkProof {
         // parse the commitments
         let commitments = Commitments::read_from(&mut source)?;
 
-        // parse trace queries
-        let num_trace_segments = context.trace_layout().num_segments();
+        // parse trace queries; the number of trace segments may exceed the number of segments
+        // in the trace layout (e.g., when the prover adds a segment for range checks), and thus,
+        // it is stored explicitly starting with format version 3
+        let num_trace_segments = if version < 3 {
+            context.trace_layout().num_segments()
+        } else {
+            source.read_u8()? as usize
+        };
         let mut trace_queries = Vec::with_capacity(num_trace_segments);
         for _ in 0..num_trace_segments {
             trace_queries.push(Queries::read_from(&mut source)?);
         }
 
         // parse the rest of the proof
+        let constraint_queries = Queries::read_from(&mut source)?;
+        let ood_frame = OodFrame::read_from(&mut source)?;
+        let fri_proof = FriProof::read_from(&mut source)?;
+        let pow_nonce = source.read_u64()?;
+
+        // parse application-specific metadata
+        let num_metadata_bytes = source.read_u32()?;
+        let metadata = source.read_vec(num_metadata_bytes as usize)?;
+
+        // parse embedded public inputs
+        let num_pub_inputs_bytes = source.read_u32()?;
+        let pub_inputs = source.read_vec(num_pub_inputs_bytes as usize)?;
+
         let proof = StarkProof {
             context,
             num_unique_queries,
             commitments,
             trace_queries,
-            constraint_queries: Queries::read_from(&mut source)?,
-            ood_frame: OodFrame::read_from(&mut source)?,
-            fri_proof: FriProof::read_from(&mut source)?,
-            pow_nonce: source.read_u64()?,
+            constraint_queries,
+            ood_frame,
+            fri_proof,
+            pow_nonce,
+            metadata,
+            pub_inputs,
         };
         if source.has_more_bytes() {
             return Err(DeserializationError::UnconsumedBytes);
         }
         Ok(proof)
     }
+
+    /// Serializes this proof into a vector of bytes followed by a checksum over these bytes.
+    ///
+    /// The checksum consists of the first [CHECKSUM_BYTES](StarkProof::CHECKSUM_BYTES) bytes of
+    /// a BLAKE3 hash of the bytes returned by [to_bytes()](StarkProof::to_bytes). The checksum
+    /// is intended to detect accidental truncation or corruption of a proof (e.g., due to bugs in
+    /// storage or transport); it does not provide any protection against deliberate tampering.
+    pub fn to_bytes_with_checksum(&self) -> Vec<u8> {
+        let mut result = self.to_bytes();
+        let checksum = compute_checksum(&result);
+        result.extend_from_slice(&checksum);
+        result
+    }
+
+    /// Returns a STARK proof read from the specified `source` serialized via
+    /// [to_bytes_with_checksum()](StarkProof::to_bytes_with_checksum).
+    ///
+    /// The checksum is verified before the proof is parsed, and thus, a truncated or corrupted
+    /// `source` is reported as [ChecksumError::CorruptedProof] rather than as a format error.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * `source` is shorter than the checksum, or the checksum does not match the preceding
+    ///   bytes.
+    /// * A valid STARK proof could not be read from the bytes preceding the checksum.
+    pub fn from_bytes_with_checksum(source: &[u8]) -> Result<Self, ChecksumError> {
+        if source.len() < Self::CHECKSUM_BYTES {
+            return Err(ChecksumError::CorruptedProof);
+        }
+        let (proof_bytes, checksum) = source.split_at(source.len() - Self::CHECKSUM_BYTES);
+        if compute_checksum(proof_bytes) != checksum {
+            return Err(ChecksumError::CorruptedProof);
+        }
+        Ok(Self::from_bytes(proof_bytes)?)
+    }
+
+    /// Serializes this proof into a lowercase hex string.
+    ///
+    /// The string contains two hex digits for every byte returned by
+    /// [to_bytes()](StarkProof::to_bytes).
+    pub fn to_hex(&self) -> String {
+        let bytes = self.to_bytes();
+        let mut result = String::with_capacity(bytes.len() * 2);
+        for byte in bytes {
+            result.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+            result.push(HEX_DIGITS[(byte & 0xf) as usize] as char);
+        }
+        result
+    }
+
+    /// Returns a STARK proof read from the specified hex string.
+    ///
+    /// The string must contain the binary serialization of a proof (as returned by
+    /// [to_bytes()](StarkProof::to_bytes)) encoded with two hex digits per byte; both lowercase
+    /// and uppercase digits are accepted.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * `source` contains an odd number of characters or any characters other than hex digits.
+    /// * A valid STARK proof could not be read from the decoded bytes.
+    pub fn from_hex(source: &str) -> Result<Self, DeserializationError> {
+        if source.len() % 2 != 0 {
+            return Err(DeserializationError::InvalidValue(format!(
+                "hex string must contain an even number of digits, but contained {}",
+                source.len()
+            )));
+        }
+
+        let mut bytes = Vec::with_capacity(source.len() / 2);
+        for (i, pair) in source.as_bytes().chunks(2).enumerate() {
+            let high = parse_hex_digit(pair[0], 2 * i)?;
+            let low = parse_hex_digit(pair[1], 2 * i + 1)?;
+            bytes.push((high << 4) | low);
+        }
+        Self::from_bytes(&bytes)
+    }
+}
+
+// QUERY SIZING
+// ================================================================================================
+
+/// Returns the smallest number of queries for which a proof generated with the specified options
+/// achieves the target security level (in bits), or `None` if the target cannot be achieved with
+/// any number of queries.
+///
+/// The number of queries in `options` is ignored. The security level is estimated in the same way
+/// as by [StarkProof::security_level()] for a proof of a computation with the specified trace
+/// length in the base field `B` using the hash function `H`, assuming the default frame width of
+/// 2 (see [AirContext::set_frame_width()](crate::AirContext::set_frame_width)); when
+/// `conjectured` is true, the conjectured security level is targeted, otherwise, the proven
+/// security level is targeted.
+///
+/// The target may be unachievable because security is also bounded by the size of the field (in
+/// particular, when a field extension is not used), and by the collision resistance of the hash
+/// function.
+pub fn min_num_queries<B: StarkField, H: Hasher>(
+    target_bits: u32,
+    options: &ProofOptions,
+    trace_length: usize,
+    conjectured: bool,
+) -> Option<usize> {
+    // security level is non-decreasing in the number of queries, and thus, the first number of
+    // queries which achieves the target is the smallest one
+    (1..=MAX_NUM_QUERIES).find(|&num_queries| {
+        let options = options.clone().with_num_queries(num_queries);
+        let security = if conjectured {
+            get_conjectured_security(
+                &options,
+                B::MODULUS_BITS,
+                trace_length,
+                2,
+                H::COLLISION_RESISTANCE,
+            )
+        } else {
+            get_proven_security(&options, B::MODULUS_BITS, trace_length, 2, H::COLLISION_RESISTANCE)
+        };
+        security >= target_bits
+    })
+}
+
+// FIELD EXTENSION SIZING
+// ================================================================================================
+
+/// Returns the smallest degree of a field extension which allows proofs in the base field `B` to
+/// achieve the target conjectured security level (in bits).
+///
+/// Conjectured security of a proof is bounded by the size of the extension field less the number
+/// of bits in the LDE domain size (see [StarkProof::security_level()]). Since the size of the LDE
+/// domain is not known in advance, this function assumes the largest LDE domain supported by the
+/// field (i.e., $2^{n}$ where $n$ is the two-adicity of the field), and thus, the returned degree
+/// is sufficient for any trace length and blowup factor. Security also depends on the number of
+/// queries, the blowup factor, and the collision resistance of the hash function; these need to
+/// be chosen separately (e.g., using [min_num_queries()]).
+///
+/// The returned value is 1, 2, 3, or 4, where 1 corresponds to [FieldExtension::None]. Note that
+/// only extensions of up to degree 3 are currently supported; thus, 4 means that the target
+/// cannot be achieved with any supported extension of the specified field.
+///
+/// [FieldExtension::None]: crate::FieldExtension::None
+pub fn recommended_extension_degree<B: StarkField>(target_soundness: u32) -> usize {
+    (1..=MAX_EXTENSION_DEGREE)
+        .find(|&degree| {
+            let field_security = get_field_security(B::MODULUS_BITS, degree, B::TWO_ADICITY);
+            field_security > target_soundness
+        })
+        .unwrap_or(MAX_EXTENSION_DEGREE) as usize
 }
 
 // HELPER FUNCTIONS
 // ================================================================================================
 
+/// Returns a checksum of the specified bytes; this is a prefix of a BLAKE3 hash of the bytes.
+fn compute_checksum(bytes: &[u8]) -> [u8; StarkProof::CHECKSUM_BYTES] {
+    let digest = Blake3_256::<BaseElement>::hash(bytes).as_bytes();
+    let mut checksum = [0; StarkProof::CHECKSUM_BYTES];
+    checksum.copy_from_slice(&digest[..StarkProof::CHECKSUM_BYTES]);
+    checksum
+}
+
+/// Returns the value of the specified hex digit located at the specified position of a hex string.
+fn parse_hex_digit(digit: u8, position: usize) -> Result<u8, DeserializationError> {
+    (digit as char).to_digit(16).map(|value| value as u8).ok_or_else(|| {
+        DeserializationError::InvalidValue(format!(
+            "invalid hex digit {:?} at position {}",
+            digit as char, position
+        ))
+    })
+}
+
 /// Computes conjectured security level for the specified proof parameters.
 fn get_conjectured_security(
     options: &ProofOptions,
     base_field_bits: u32,
     trace_domain_size: usize,
+    frame_width: usize,
     collision_resistance: u32,
 ) -> u32 {
-    // compute max security we can get for a given field size
-    let field_size = base_field_bits * options.field_extension().degree();
-    let field_security = field_size - (trace_domain_size * options.blowup_factor()).ilog2();
+    // compute max security we can get for a given field size; opening trace polynomials at
+    // `frame_width` points rather than at 2 points increases the number of out-of-domain points
+    // at which a cheating prover may succeed by up to a factor of frame_width / 2, and thus, we
+    // reduce field security by ceil(log2(frame_width / 2)) bits
+    let field_security = get_field_security(
+        base_field_bits,
+        options.field_extension().degree(),
+        (trace_domain_size * options.blowup_factor()).ilog2(),
+    )
+    .saturating_sub((frame_width as u32 - 1).ilog2());
 
     // compute security we get by executing multiple query rounds
-    let security_per_query = options.blowup_factor().ilog2();
+    let security_per_query = options.rate_bits();
     let mut query_security = security_per_query * options.num_queries() as u32;
 
     // include grinding factor contributions only for proofs adequate security
@@ -208,15 +550,23 @@ fn get_conjectured_security(
     cmp::min(cmp::min(field_security, query_security) - 1, collision_resistance)
 }
 
+/// Computes the maximum security level (in bits) which can be achieved in an extension of the
+/// specified degree of a field with `base_field_bits` bits for an LDE domain of size
+/// $2^{lde\_domain\_bits}$.
+fn get_field_security(base_field_bits: u32, extension_degree: u32, lde_domain_bits: u32) -> u32 {
+    (base_field_bits * extension_degree).saturating_sub(lde_domain_bits)
+}
+
 /// Estimates proven security level for the specified proof parameters.
 fn get_proven_security(
     options: &ProofOptions,
     base_field_bits: u32,
     trace_domain_size: usize,
+    frame_width: usize,
     collision_resistance: u32,
 ) -> u32 {
     let m_min: usize = 3;
-    let m_max = compute_upper_m(trace_domain_size);
+    let m_max = compute_upper_m(trace_domain_size, frame_width);
 
     let m_optimal = (m_min as u32..m_max as u32)
         .max_by_key(|&a| {
@@ -224,6 +574,7 @@ fn get_proven_security(
                 options,
                 base_field_bits,
                 trace_domain_size,
+                frame_width,
                 a as usize,
             )
         })
@@ -236,6 +587,7 @@ fn get_proven_security(
             options,
             base_field_bits,
             trace_domain_size,
+            frame_width,
             m_optimal as usize,
         ),
         collision_resistance as u64,
@@ -248,18 +600,20 @@ fn proven_security_protocol_for_m(
     options: &ProofOptions,
     base_field_bits: u32,
     trace_domain_size: usize,
+    frame_width: usize,
     m: usize,
 ) -> u64 {
     let extension_field_bits = (base_field_bits * options.field_extension().degree()) as f64;
     let num_fri_queries = options.num_queries() as f64;
     let m = m as f64;
-    let rho = 1.0 / options.blowup_factor() as f64;
+    let rho = options.code_rate();
     let alpha = (1.0 + 0.5 / m) * sqrt(rho);
     let theta = 1.0 - alpha;
     let max_deg = options.blowup_factor() as f64;
 
     let lde_domain_size = (trace_domain_size * options.blowup_factor()) as f64;
     let trace_domain_size = trace_domain_size as f64;
+    let frame_width = frame_width as f64;
 
     // Computes FRI commit-phase (i.e., pre-query) soundness error.
     // This considers only the first term given in eq. 7 in https://eprint.iacr.org/2022/1216.pdf,
@@ -281,7 +635,8 @@ fn proven_security_protocol_for_m(
     // To apply Theorem 8 in https://eprint.iacr.org/2022/1216.pdf, we need to apply FRI with
     // a slightly larger agreement parameter alpha.
     // More concretely, we need alpha > rho_plus.sqrt() where rho_plus is the rate in function field
-    // F(Z) and defined as (trace_domain_size + 2.0) / lde_domain_size .
+    // F(Z) and defined as (trace_domain_size + frame_width) / lde_domain_size; the frame width is
+    // the number of points at which trace polynomials are opened (2 by default).
     // This means that the range of m needs to be restricted in order to ensure that
     // alpha := 1 - theta := rho.sqrt() * (1 + 1/2m) is greater than rho_plus.sqrt().
     // Determining the range of m is the responsibility of the calling function.
@@ -290,7 +645,7 @@ fn proven_security_protocol_for_m(
     // the list-decoding list size in F(Z).
 
     // Modified rate in function field F(Z)
-    let rho_plus = (trace_domain_size + 2.0) / lde_domain_size;
+    let rho_plus = (trace_domain_size + frame_width) / lde_domain_size;
     // New proximity parameter m_plus, corresponding to rho_plus, needed to make sure that
     //  alpha < rho_plus.sqrt() * (1 + 1 / (2 * m_plus))
     let m_plus = ceil(1.0 / (2.0 * (alpha / sqrt(rho_plus) - 1.0)));
@@ -305,9 +660,9 @@ fn proven_security_protocol_for_m(
     // DEEP related soundness error. Note that this uses that the denominator |F| - |D ∪ H|
     // can be approximated by |F| for all practical domain sizes. We also use the blow-up factor
     // as an upper bound for the maximal constraint degree.
-    let deep_err_bits =
-        -log2(l_plus * (max_deg * (trace_domain_size + 1.0) + (trace_domain_size - 1.0)))
-            + extension_field_bits;
+    let deep_err_bits = -log2(
+        l_plus * (max_deg * (trace_domain_size + frame_width - 1.0) + (trace_domain_size - 1.0)),
+    ) + extension_field_bits;
 
     let min = cmp::min(cmp::min(fri_err_bits, ali_err_bits as u64), deep_err_bits as u64);
     if min < 1 {
@@ -321,10 +676,12 @@ fn proven_security_protocol_for_m(
 // ================================================================================================
 
 /// Computes the largest proximity parameter m needed for Theorem 8
-/// in <https://eprint.iacr.org/2022/1216.pdf> to work.
-fn compute_upper_m(h: usize) -> f64 {
+/// in <https://eprint.iacr.org/2022/1216.pdf> to work when trace polynomials are opened at `w`
+/// points.
+fn compute_upper_m(h: usize, w: usize) -> f64 {
     let h = h as f64;
-    let m_max = ceil(0.25 * h * (1.0 + sqrt(1.0 + 2.0 / h)));
+    let w = w as f64;
+    let m_max = ceil(h * (1.0 + sqrt(1.0 + w / h)) / (2.0 * w));
 
     // We cap the range to 1000 as the optimal m value will be in the lower range of [m_min, m_max]
     // since increasing m too much will lead to a deterioration in the FRI commit soundness making
@@ -371,3 +728,176 @@ pub fn ceil(value: f64) -> f64 {
 pub fn ceil(value: f64) -> f64 {
     libm::ceil(value)
 }
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FieldExtension;
+    use crypto::hashers::Blake3_192;
+    use math::fields::{f128, f62};
+
+    #[test]
+    fn min_num_queries_conjectured() {
+        let trace_length = 1 << 20;
+        for (blowup_factor, grinding_factor, extension) in [
+            (8, 0, FieldExtension::Quadratic),
+            (8, 16, FieldExtension::Quadratic),
+            (16, 20, FieldExtension::Cubic),
+            (4, 0, FieldExtension::Cubic),
+        ] {
+            let options = ProofOptions::new(1, blowup_factor, grinding_factor, extension, 8, 31);
+            for target_bits in [40, 80, 96, 100] {
+                let num_queries = min_num_queries::<BaseElement, Blake3_256<BaseElement>>(
+                    target_bits,
+                    &options,
+                    trace_length,
+                    true,
+                )
+                .unwrap();
+                assert_min_num_queries(&options, trace_length, target_bits, num_queries, true);
+            }
+        }
+    }
+
+    #[test]
+    fn min_num_queries_proven() {
+        let trace_length = 1 << 16;
+        let options = ProofOptions::new(1, 16, 20, FieldExtension::Cubic, 8, 31);
+        for target_bits in [40, 80, 100] {
+            let num_queries = min_num_queries::<BaseElement, Blake3_256<BaseElement>>(
+                target_bits,
+                &options,
+                trace_length,
+                false,
+            )
+            .unwrap();
+            assert_min_num_queries(&options, trace_length, target_bits, num_queries, false);
+        }
+    }
+
+    #[test]
+    fn min_num_queries_unachievable() {
+        let options = ProofOptions::new(1, 8, 0, FieldExtension::Cubic, 8, 31);
+
+        // security is bounded by collision resistance of the hash function
+        let result =
+            min_num_queries::<BaseElement, Blake3_192<BaseElement>>(100, &options, 1 << 20, true);
+        assert!(result.is_none());
+
+        // security is bounded by the size of the field when field extension is not used
+        let options = ProofOptions::new(1, 8, 0, FieldExtension::None, 8, 31);
+        let result =
+            min_num_queries::<BaseElement, Blake3_256<BaseElement>>(80, &options, 1 << 20, true);
+        assert!(result.is_none());
+
+        // but not in a large enough field
+        let result = min_num_queries::<f128::BaseElement, Blake3_256<f128::BaseElement>>(
+            80,
+            &options,
+            1 << 20,
+            true,
+        );
+        assert!(result.is_some());
+    }
+
+    #[test]
+    fn security_with_wide_frame() {
+        let trace_length = 1 << 16;
+
+        // without a field extension, conjectured security is bounded by the size of the field,
+        // and each doubling of the frame width beyond 2 rows reduces it by one bit
+        let options = ProofOptions::new(80, 8, 0, FieldExtension::None, 8, 31);
+        let security = |frame_width| {
+            get_conjectured_security(
+                &options,
+                BaseElement::MODULUS_BITS,
+                trace_length,
+                frame_width,
+                128,
+            )
+        };
+        assert_eq!(security(2) - 1, security(3));
+        assert_eq!(security(2) - 1, security(4));
+        assert_eq!(security(2) - 2, security(5));
+
+        // proven security does not increase with the frame width
+        let options = ProofOptions::new(80, 16, 20, FieldExtension::Cubic, 8, 31);
+        let security = |frame_width| {
+            get_proven_security(&options, BaseElement::MODULUS_BITS, trace_length, frame_width, 128)
+        };
+        assert!(security(2) > 0);
+        assert!(security(3) <= security(2));
+        assert!(security(255) <= security(3));
+    }
+
+    #[test]
+    fn recommended_extension_degree_for_target() {
+        // 64-bit fields need a quadratic extension for ~100 bits of security, and a cubic
+        // extension for 128 bits of security
+        assert_eq!(1, recommended_extension_degree::<BaseElement>(30));
+        assert_eq!(2, recommended_extension_degree::<BaseElement>(80));
+        assert_eq!(2, recommended_extension_degree::<BaseElement>(95));
+        assert_eq!(3, recommended_extension_degree::<BaseElement>(96));
+        assert_eq!(3, recommended_extension_degree::<BaseElement>(128));
+
+        // the 62-bit field supports LDE domains of up to 2^39
+        assert_eq!(2, recommended_extension_degree::<f62::BaseElement>(80));
+        assert_eq!(3, recommended_extension_degree::<f62::BaseElement>(85));
+        assert_eq!(3, recommended_extension_degree::<f62::BaseElement>(128));
+
+        // the 128-bit field supports LDE domains of up to 2^40
+        assert_eq!(1, recommended_extension_degree::<f128::BaseElement>(80));
+        assert_eq!(2, recommended_extension_degree::<f128::BaseElement>(88));
+        assert_eq!(2, recommended_extension_degree::<f128::BaseElement>(128));
+
+        // the recommended degree must be the smallest degree for which the largest LDE domain
+        // supported by the field does not limit conjectured security below the target
+        assert_recommended_extension_degree::<BaseElement>();
+        assert_recommended_extension_degree::<f62::BaseElement>();
+        assert_recommended_extension_degree::<f128::BaseElement>();
+    }
+
+    fn assert_recommended_extension_degree<B: StarkField>() {
+        let blowup_factor = 8usize;
+        let trace_length = 1 << (B::TWO_ADICITY - blowup_factor.ilog2());
+        for target_bits in [40, 80, 100, 128] {
+            let degree = recommended_extension_degree::<B>(target_bits);
+            let security = |extension| {
+                let options = ProofOptions::new(255, blowup_factor, 0, extension, 8, 31);
+                get_conjectured_security(&options, B::MODULUS_BITS, trace_length, 2, 256)
+            };
+            let extensions =
+                [FieldExtension::None, FieldExtension::Quadratic, FieldExtension::Cubic];
+            if let Some(&extension) = extensions.get(degree - 1) {
+                assert!(security(extension) >= target_bits);
+            }
+            if degree > 1 {
+                assert!(security(extensions[degree - 2]) < target_bits);
+            }
+        }
+    }
+
+    fn assert_min_num_queries(
+        options: &ProofOptions,
+        trace_length: usize,
+        target_bits: u32,
+        num_queries: usize,
+        conjectured: bool,
+    ) {
+        let security = |num_queries: usize| {
+            let options = options.clone().with_num_queries(num_queries);
+            if conjectured {
+                get_conjectured_security(&options, BaseElement::MODULUS_BITS, trace_length, 2, 128)
+            } else {
+                get_proven_security(&options, BaseElement::MODULUS_BITS, trace_length, 2, 128)
+            }
+        };
+        assert!(security(num_queries) >= target_bits);
+        if num_queries > 1 {
+            assert!(security(num_queries - 1) < target_bits);
+        }
+    }
+}
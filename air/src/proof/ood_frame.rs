@@ -21,6 +21,8 @@ type ParsedOodFrame<E> = (Vec<E>, Vec<E>);
 /// This struct contains the following evaluations:
 /// * Evaluations of all trace polynomials at *z*.
 /// * Evaluations of all trace polynomials at *z * g*.
+/// * Evaluations of all trace polynomials at *z * g^k* for the remaining shifts *k* when the
+///   frame is wider than two rows.
 /// * Evaluations of constraint composition column polynomials at *z*.
 ///
 /// where *z* is an out-of-domain point and *g* is the generator of the trace domain.
@@ -34,6 +36,28 @@ pub struct OodFrame {
 }
 
 impl OodFrame {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an out-of-domain frame assembled from raw bytes of trace states and constraint
+    /// evaluations.
+    #[cfg(feature = "flat-layout")]
+    pub(super) fn from_raw_parts(trace_states: Vec<u8>, evaluations: Vec<u8>) -> Self {
+        OodFrame {
+            trace_states,
+            evaluations,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns raw bytes of trace states and constraint evaluations (in that order).
+    #[cfg(feature = "flat-layout")]
+    pub(super) fn raw_parts(&self) -> (&[u8], &[u8]) {
+        (&self.trace_states, &self.evaluations)
+    }
+
     // UPDATERS
     // --------------------------------------------------------------------------------------------
 
@@ -65,13 +89,13 @@ impl OodFrame {
 
     /// Updates constraint evaluation portion of this out-of-domain frame.
     ///
+    /// `evaluations` may be empty when all constraint evaluations can be recovered by the
+    /// verifier (i.e., for thin out-of-domain frames with a single constraint composition column).
+    ///
     /// # Panics
-    /// Panics if:
-    /// * Constraint evaluations have already been set.
-    /// * `evaluations` is an empty vector.
+    /// Panics if constraint evaluations have already been set.
     pub fn set_constraint_evaluations<E: FieldElement>(&mut self, evaluations: &[E]) {
         assert!(self.evaluations.is_empty(), "constraint evaluations have already been set");
-        assert!(!evaluations.is_empty(), "cannot set to empty constraint evaluations");
         evaluations.write_into(&mut self.evaluations)
     }
 
@@ -80,11 +104,15 @@ impl OodFrame {
     /// Returns main and auxiliary (if any) trace evaluation frames and a vector of out-of-domain
     /// constraint evaluations contained in `self`.
     ///
+    /// The trace evaluations are expected to contain `frame_width` evaluations (one per row of
+    /// the frame) for each trace polynomial.
+    ///
     /// # Panics
-    /// Panics if either `main_trace_width` or `num_evaluations` are equal to zero.
+    /// Panics if `main_trace_width` is equal to zero.
     ///
     /// # Errors
     /// Returns an error if:
+    /// * The width of the frame stored in `self` is not `frame_width`.
     /// * Valid [EvaluationFrame]s for the specified `main_trace_width` and `aux_trace_width`
     ///   could not be parsed from the internal bytes.
     /// * A vector of evaluations specified by `num_evaluations` could not be parsed from the
@@ -94,14 +122,19 @@ impl OodFrame {
         self,
         main_trace_width: usize,
         aux_trace_width: usize,
+        frame_width: usize,
         num_evaluations: usize,
     ) -> Result<ParsedOodFrame<E>, DeserializationError> {
         assert!(main_trace_width > 0, "trace width cannot be zero");
-        assert!(num_evaluations > 0, "number of evaluations cannot be zero");
 
         // parse main and auxiliary trace evaluation frames
         let mut reader = SliceReader::new(&self.trace_states);
         let frame_size = reader.read_u8()? as usize;
+        if frame_size != frame_width {
+            return Err(DeserializationError::InvalidValue(format!(
+                "expected out-of-domain frame width to be {frame_width}, but was {frame_size}"
+            )));
+        }
         let trace =
             E::read_batch_from(&mut reader, (main_trace_width + aux_trace_width) * frame_size)?;
         if reader.has_more_bytes() {
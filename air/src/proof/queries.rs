@@ -76,6 +76,82 @@ impl Queries {
         Queries { paths, values }
     }
 
+    /// Returns queries constructed from evaluations of a set of functions at some number of points
+    /// in a domain and Merkle authentication paths for each of the functions.
+    ///
+    /// This is used when functions are committed to using separate Merkle trees (see
+    /// [CommitmentLayout::PerColumn](crate::CommitmentLayout::PerColumn)). The i-th proof in
+    /// `merkle_proofs` must authenticate the i-th value of every query, and a hash of each value
+    /// must be equal to a leaf node in the corresponding Merkle authentication path.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * No queries were provided (`query_values` is an empty vector).
+    /// * Any of the queries does not contain any evaluations.
+    /// * Not all queries contain the same number of evaluations.
+    /// * The number of Merkle proofs is not equal to the number of evaluations in a query.
+    pub fn new_per_column<H: Hasher, E: FieldElement>(
+        merkle_proofs: Vec<BatchMerkleProof<H>>,
+        query_values: Vec<Vec<E>>,
+    ) -> Self {
+        assert!(!query_values.is_empty(), "query values cannot be empty");
+        let elements_per_query = query_values[0].len();
+        assert_ne!(elements_per_query, 0, "a query must contain at least one evaluation");
+        assert_eq!(
+            merkle_proofs.len(),
+            elements_per_query,
+            "a Merkle proof must be provided for each evaluation in a query"
+        );
+
+        // concatenate all elements together into a single vector of bytes
+        let num_queries = query_values.len();
+        let mut values = Vec::with_capacity(num_queries * elements_per_query * E::ELEMENT_BYTES);
+        for elements in query_values.iter() {
+            assert_eq!(
+                elements.len(),
+                elements_per_query,
+                "all queries must contain the same number of evaluations"
+            );
+            values.write(elements);
+        }
+
+        // serialize internal nodes of all batch Merkle proofs one after another
+        let paths = merkle_proofs.iter().flat_map(|proof| proof.serialize_nodes()).collect();
+
+        Queries { paths, values }
+    }
+
+    /// Returns queries which do not contain any decommitments.
+    ///
+    /// Empty queries are placed into proofs whose queries are sent separately from the rest of
+    /// the proof (see [QueryResponse]).
+    pub fn empty() -> Self {
+        Queries {
+            paths: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Returns queries assembled from raw bytes of query values and Merkle authentication paths.
+    #[cfg(feature = "flat-layout")]
+    pub(super) fn from_raw_parts(values: Vec<u8>, paths: Vec<u8>) -> Self {
+        Queries { paths, values }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns true if these queries do not contain any decommitments.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty() && self.paths.is_empty()
+    }
+
+    /// Returns raw bytes of query values and Merkle authentication paths (in that order).
+    #[cfg(feature = "flat-layout")]
+    pub(super) fn raw_parts(&self) -> (&[u8], &[u8]) {
+        (&self.values, &self.paths)
+    }
+
     // PARSER
     // --------------------------------------------------------------------------------------------
     /// Convert internally stored bytes into a set of query values and the corresponding Merkle
@@ -126,6 +202,143 @@ impl Queries {
 
         Ok((merkle_proof, query_values))
     }
+
+    /// Convert internally stored bytes into a set of query values and Merkle authentication paths
+    /// for each of the queried functions.
+    ///
+    /// This is the counterpart of [new_per_column()](Queries::new_per_column): the i-th returned
+    /// Merkle proof authenticates the i-th value of every query.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `domain_size` is not a power of two.
+    /// * `num_queries` is zero.
+    /// * `values_per_query` is zero.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_per_column<H, E>(
+        self,
+        domain_size: usize,
+        num_queries: usize,
+        values_per_query: usize,
+    ) -> Result<(Vec<BatchMerkleProof<H>>, Table<E>), DeserializationError>
+    where
+        E: FieldElement,
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        assert!(domain_size.is_power_of_two(), "domain size must be a power of two");
+        assert!(num_queries > 0, "there must be at least one query");
+        assert!(values_per_query > 0, "a query must contain at least one value");
+
+        // make sure we have enough bytes to read the expected number of queries
+        let expected_bytes = num_queries * E::ELEMENT_BYTES * values_per_query;
+        if self.values.len() != expected_bytes {
+            return Err(DeserializationError::InvalidValue(format!(
+                "expected {} query value bytes, but was {}",
+                expected_bytes,
+                self.values.len()
+            )));
+        }
+
+        // read bytes corresponding to each query and convert them into field elements
+        let query_values = Table::<E>::from_bytes(&self.values, num_queries, values_per_query)?;
+
+        // build a batch Merkle proof for each column; leaf nodes of these proofs are hashes of
+        // individual values in the column
+        let mut reader = SliceReader::new(&self.paths);
+        let tree_depth = domain_size.ilog2() as u8;
+        let mut merkle_proofs = Vec::with_capacity(values_per_query);
+        for col_idx in 0..values_per_query {
+            let hashed_values =
+                query_values.rows().map(|row| H::hash_elements(&row[col_idx..col_idx + 1]));
+            let merkle_proof =
+                BatchMerkleProof::deserialize(&mut reader, hashed_values.collect(), tree_depth)?;
+            merkle_proofs.push(merkle_proof);
+        }
+        if reader.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+
+        Ok((merkle_proofs, query_values))
+    }
+
+    /// Convert internally stored bytes into a set of Merkle tree leaves, each containing
+    /// `rows_per_leaf` adjacent rows of query values, and the corresponding Merkle authentication
+    /// paths.
+    ///
+    /// This is used when several adjacent rows of a trace segment are packed into a single leaf
+    /// (see [ProofOptions::with_rows_per_leaf()](crate::ProofOptions::with_rows_per_leaf)). Each
+    /// row of the returned table contains values of an entire leaf (i.e., `rows_per_leaf` rows of
+    /// `values_per_row` values each). The number of leaves is inferred from the number of value
+    /// bytes, since several queries may fall into the same leaf.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `domain_size` is not a power of two.
+    /// * `rows_per_leaf` is not a power of two or is not smaller than `domain_size`.
+    /// * `values_per_row` is zero.
+    pub fn parse_packed<H, E>(
+        self,
+        domain_size: usize,
+        rows_per_leaf: usize,
+        values_per_row: usize,
+    ) -> Result<(BatchMerkleProof<H>, Table<E>), DeserializationError>
+    where
+        E: FieldElement,
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        assert!(domain_size.is_power_of_two(), "domain size must be a power of two");
+        assert!(rows_per_leaf.is_power_of_two(), "rows per leaf must be a power of two");
+        assert!(rows_per_leaf < domain_size, "rows per leaf must be smaller than domain size");
+        assert!(values_per_row > 0, "a query must contain at least one value");
+
+        // make sure the value bytes encode a whole number of leaves, and that there are no more
+        // leaves than in the tree
+        let num_leaf_bytes = E::ELEMENT_BYTES * values_per_row * rows_per_leaf;
+        let num_leaves = self.values.len() / num_leaf_bytes;
+        let tree_size = domain_size / rows_per_leaf;
+        if num_leaves == 0 || num_leaves > tree_size || self.values.len() % num_leaf_bytes != 0 {
+            return Err(DeserializationError::InvalidValue(format!(
+                "{} query value bytes do not encode a valid set of leaves of {} bytes",
+                self.values.len(),
+                num_leaf_bytes
+            )));
+        }
+
+        // read bytes corresponding to each leaf, convert them into field elements, and also hash
+        // them to build leaf nodes of the batch Merkle proof
+        let mut reader = SliceReader::new(&self.values);
+        let leaf_width = values_per_row * rows_per_leaf;
+        let leaf_values = Table::from_elements(
+            E::read_batch_from(&mut reader, num_leaves * leaf_width)?,
+            leaf_width,
+        );
+        let hashed_leaves = leaf_values.rows().map(|leaf| H::hash_elements(leaf)).collect();
+
+        // build batch Merkle proof
+        let mut reader = SliceReader::new(&self.paths);
+        let tree_depth = tree_size.ilog2() as u8;
+        let merkle_proof = BatchMerkleProof::deserialize(&mut reader, hashed_leaves, tree_depth)?;
+        if reader.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+
+        Ok((merkle_proof, leaf_values))
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns a commitment to a set of functions committed to using separate Merkle trees.
+///
+/// The commitment is computed as a hash of the concatenated serialized roots of the trees; thus,
+/// all bytes of each root are bound into the commitment regardless of the digest size.
+pub fn hash_column_roots<H: Hasher>(roots: &[H::Digest]) -> H::Digest {
+    let mut bytes = Vec::with_capacity(roots.len() * 32);
+    for root in roots {
+        root.write_into(&mut bytes);
+    }
+    H::hash(&bytes)
 }
 
 impl Serializable for Queries {
@@ -158,3 +371,49 @@ impl Deserializable for Queries {
         Ok(Queries { paths, values })
     }
 }
+
+// QUERY RESPONSE
+// ================================================================================================
+/// Decommitments to the execution trace and constraint evaluations at a single query position.
+///
+/// A proof normally contains decommitments for all query positions batched together. A prover
+/// may instead send the proof with empty trace and constraint queries, and then send a query
+/// response for each query position separately; this makes it possible to stream responses to
+/// the verifier, which checks each response against the commitments as soon as it arrives.
+///
+/// Responses are sent in the order of query positions (i.e., sorted in increasing order).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct QueryResponse {
+    /// Decommitments to each trace segment at the query position.
+    pub trace_queries: Vec<Queries>,
+    /// Decommitment to constraint evaluations at the query position.
+    pub constraint_queries: Queries,
+}
+
+impl Serializable for QueryResponse {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(self.trace_queries.len() as u8);
+        self.trace_queries.write_into(target);
+        self.constraint_queries.write_into(target);
+    }
+}
+
+impl Deserializable for QueryResponse {
+    /// Reads a query response from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if a valid query response could not be read from the specified source.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_trace_segments = source.read_u8()? as usize;
+        let mut trace_queries = Vec::with_capacity(num_trace_segments);
+        for _ in 0..num_trace_segments {
+            trace_queries.push(Queries::read_from(source)?);
+        }
+        let constraint_queries = Queries::read_from(source)?;
+        Ok(QueryResponse {
+            trace_queries,
+            constraint_queries,
+        })
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::StarkProof;
+use core::fmt::Debug;
+use utils::{
+    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+    SliceReader,
+};
+
+// SIGNATURE SCHEME
+// ================================================================================================
+/// Defines a signature scheme which can be used to sign proof identifiers.
+///
+/// When `ed25519` feature is enabled, this crate provides an implementation of this trait backed
+/// by Ed25519 signatures (see `Ed25519` struct). Other schemes can be plugged in by
+/// implementing this trait for a thin wrapper around an external implementation.
+pub trait ProofSignatureScheme {
+    /// Key used to sign proof identifiers.
+    type SecretKey;
+    /// Key used to verify signatures over proof identifiers.
+    type PublicKey: Clone + Debug + Eq + Serializable + Deserializable;
+    /// Signature over a proof identifier.
+    type Signature: Clone + Debug + Eq + Serializable + Deserializable;
+
+    /// Returns a signature over the specified proof identifier.
+    fn sign(secret_key: &Self::SecretKey, proof_id: &[u8; 32]) -> Self::Signature;
+
+    /// Returns true if the `signature` over the specified proof identifier is valid for the
+    /// specified `public_key`.
+    fn verify(
+        public_key: &Self::PublicKey,
+        proof_id: &[u8; 32],
+        signature: &Self::Signature,
+    ) -> bool;
+}
+
+// SIGNED PROOF
+// ================================================================================================
+/// A STARK proof together with a signature over its identifier.
+///
+/// The signature is computed over the identifier of the proof (see [StarkProof::id()]), and the
+/// public key with which it can be checked is attached to the proof. Since any change to the
+/// proof changes its identifier, the signature binds the proof to the holder of the secret key.
+///
+/// Signed proofs are verified via `verify_signed()` function of the verifier crate, which checks
+/// both the signature and the proof. The verifier does not check whether the attached public key
+/// is trusted; this is left to the caller.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SignedProof<S: ProofSignatureScheme> {
+    /// The signed proof.
+    pub proof: StarkProof,
+    /// Public key with which the signature can be checked.
+    pub public_key: S::PublicKey,
+    /// Signature over the identifier of the proof.
+    pub signature: S::Signature,
+}
+
+impl<S: ProofSignatureScheme> SignedProof<S> {
+    /// Returns the specified proof signed with the specified `secret_key`; `public_key` must be
+    /// the key corresponding to `secret_key`.
+    pub fn sign(proof: StarkProof, secret_key: &S::SecretKey, public_key: S::PublicKey) -> Self {
+        let signature = S::sign(secret_key, &proof.id());
+        SignedProof {
+            proof,
+            public_key,
+            signature,
+        }
+    }
+
+    /// Returns true if the signature attached to this proof is a valid signature over the
+    /// identifier of the proof for the attached public key.
+    pub fn verify_signature(&self) -> bool {
+        S::verify(&self.public_key, &self.proof.id(), &self.signature)
+    }
+
+    /// Serializes this signed proof into a vector of bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        self.write_into(&mut result);
+        result
+    }
+
+    /// Returns a signed proof read from the specified `source`.
+    ///
+    /// # Errors
+    /// Returns an error if a valid signed proof could not be read from the specified `source`.
+    pub fn from_bytes(source: &[u8]) -> Result<Self, DeserializationError> {
+        let mut source = SliceReader::new(source);
+        let proof = Self::read_from(&mut source)?;
+        if source.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+        Ok(proof)
+    }
+}
+
+impl<S: ProofSignatureScheme> Serializable for SignedProof<S> {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        let bytes = self.proof.to_bytes();
+        target.write_u32(bytes.len() as u32);
+        target.write_bytes(&bytes);
+        self.public_key.write_into(target);
+        self.signature.write_into(target);
+    }
+}
+
+impl<S: ProofSignatureScheme> Deserializable for SignedProof<S> {
+    /// Reads a signed proof from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_bytes = source.read_u32()? as usize;
+        let proof = StarkProof::from_bytes(&source.read_vec(num_bytes)?)?;
+        let public_key = S::PublicKey::read_from(source)?;
+        let signature = S::Signature::read_from(source)?;
+        Ok(SignedProof {
+            proof,
+            public_key,
+            signature,
+        })
+    }
+}
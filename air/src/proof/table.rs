@@ -61,6 +61,25 @@ impl<E: FieldElement> Table<E> {
         })
     }
 
+    /// Returns a new [Table] instantiated with the provided elements arranged in rows of
+    /// `num_cols` elements.
+    ///
+    /// Unlike [from_bytes()](Table::from_bytes), this does not limit the number of rows and
+    /// columns in the table.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * Specified number of columns is 0.
+    /// * Provided elements do not fill a whole number of rows.
+    pub fn from_elements(data: Vec<E>, num_cols: usize) -> Self {
+        assert!(num_cols > 0, "number of columns must be greater than 0");
+        assert_eq!(data.len() % num_cols, 0, "elements must fill a whole number of rows");
+        Self {
+            data,
+            row_width: num_cols,
+        }
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -0,0 +1,151 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::Context;
+use crate::{Air, ProofOptions, TraceInfo};
+use math::StarkField;
+use utils::{
+    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+    SliceReader,
+};
+
+// VERIFICATION KEY
+// ================================================================================================
+/// A pin on the parameters of a computation which proofs of the computation must match.
+///
+/// A verification key records the parameters of a computation which do not depend on public
+/// inputs of a specific execution:
+/// * Proof context, which includes the layout and length of the execution trace, the base field,
+///   and the [ProofOptions] used to generate proofs.
+/// * Polynomials of periodic columns defined by the AIR of the computation.
+///
+/// A verification key is not sufficient to verify a proof on its own: constraints, assertions,
+/// and periodic column values used during verification are always taken from the AIR with which
+/// the verifier is instantiated. Instead, the key pins the parameters which the verifier would
+/// otherwise have to accept from the proof. It is generated together with a proof via
+/// `Prover::prove_with_vk()` method of the prover crate, and proofs are checked against it via
+/// `verify_with_vk()` function of the verifier crate: a proof is accepted only if its context
+/// is equal to the context of the key (and thus, only the pinned proof options are acceptable),
+/// and only if the AIR of the verifier defines the same periodic columns as the key.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VerificationKey<B: StarkField> {
+    context: Context,
+    periodic_column_polys: Vec<Vec<B>>,
+}
+
+impl<B: StarkField> VerificationKey<B> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a verification key for the computation described by the specified AIR instance.
+    pub fn new<A: Air<BaseField = B>>(air: &A) -> Self {
+        VerificationKey {
+            context: Context::new::<B>(
+                air.trace_info(),
+                air.options().clone(),
+                air.context().frame_width(),
+            ),
+            periodic_column_polys: air.get_periodic_column_polys(),
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the proof context which proofs verified against this key must have.
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Returns the proof options which proofs verified against this key must be generated with.
+    pub fn options(&self) -> &ProofOptions {
+        self.context.options()
+    }
+
+    /// Returns execution trace info for the computation described by this key.
+    pub fn get_trace_info(&self) -> TraceInfo {
+        self.context.get_trace_info()
+    }
+
+    /// Returns polynomials of periodic columns of the computation described by this key.
+    ///
+    /// The polynomials are in coefficient form, and are in the same order as the polynomials
+    /// returned from [Air::get_periodic_column_polys()].
+    pub fn periodic_column_polys(&self) -> &[Vec<B>] {
+        &self.periodic_column_polys
+    }
+
+    // SERIALIZATION / DESERIALIZATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Serializes this verification key into a vector of bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Serializable::to_bytes(self)
+    }
+
+    /// Returns a verification key read from the specified `source`.
+    ///
+    /// # Errors
+    /// Returns an error if a valid verification key could not be read from the specified bytes,
+    /// or if the bytes contain data beyond the serialized key.
+    pub fn from_bytes(source: &[u8]) -> Result<Self, DeserializationError> {
+        let mut reader = SliceReader::new(source);
+        let key = Self::read_from(&mut reader)?;
+        if reader.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+        Ok(key)
+    }
+}
+
+impl<B: StarkField> Serializable for VerificationKey<B> {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.context.write_into(target);
+        assert!(self.periodic_column_polys.len() <= u16::MAX as usize);
+        target.write_u16(self.periodic_column_polys.len() as u16);
+        for poly in self.periodic_column_polys.iter() {
+            target.write_u8(poly.len().ilog2() as u8); // store as power of two
+            B::write_batch_into(poly, target);
+        }
+    }
+}
+
+impl<B: StarkField> Deserializable for VerificationKey<B> {
+    /// Reads a verification key from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * A valid proof context could not be read from the `source`.
+    /// * The base field of the context is not the field `B`.
+    /// * The length of any periodic column polynomial is smaller than 2 or greater than the trace
+    ///   length.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let context = Context::read_from(source)?;
+        if context.field_modulus_bytes() != B::get_modulus_le_bytes() {
+            return Err(DeserializationError::InvalidValue(
+                "base field of the verification key context does not match the key type".into(),
+            ));
+        }
+
+        let num_polys = source.read_u16()? as usize;
+        let mut periodic_column_polys = Vec::with_capacity(num_polys);
+        for _ in 0..num_polys {
+            let poly_len_log2 = source.read_u8()? as u32;
+            if poly_len_log2 == 0 || poly_len_log2 > context.trace_length().ilog2() {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "periodic column polynomial length must be between 2 and {}, but was 2^{}",
+                    context.trace_length(),
+                    poly_len_log2
+                )));
+            }
+            periodic_column_polys.push(B::read_batch_from(source, 1 << poly_len_log2)?);
+        }
+
+        Ok(VerificationKey {
+            context,
+            periodic_column_polys,
+        })
+    }
+}
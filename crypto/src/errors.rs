@@ -3,6 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use crate::IntegerSampling;
 use core::fmt;
 
 // MERKLE TREE ERROR
@@ -72,6 +73,9 @@ pub enum RandomCoinError {
     /// The required number of integer values could not be drawn from the specified domain after
     /// the specified number of tries.
     FailedToDrawIntegers(usize, usize, usize),
+    /// Integers could not be drawn because the specified sampling method is not supported by the
+    /// random coin.
+    UnsupportedIntegerSampling(IntegerSampling),
 }
 
 impl fmt::Display for RandomCoinError {
@@ -86,6 +90,12 @@ impl fmt::Display for RandomCoinError {
                     "needed to draw {num_expected} integers from a domain, but drew only {num_actual} after {num_tries} tries"
                 )
             }
+            Self::UnsupportedIntegerSampling(sampling) => {
+                write!(
+                    f,
+                    "integer sampling method {sampling:?} is not supported by the random coin"
+                )
+            }
         }
     }
 }
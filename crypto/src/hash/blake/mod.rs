@@ -59,6 +59,58 @@ impl<B: StarkField> ElementHasher for Blake3_256<B> {
     }
 }
 
+// BLAKE3 512-BIT OUTPUT
+// ================================================================================================
+
+/// Implementation of the [Hasher](super::Hasher) trait for BLAKE3 hash function with 512-bit
+/// output.
+///
+/// The output is obtained by reading 64 bytes from the extendable output of BLAKE3. Thus, the
+/// first 32 bytes of a digest are the same as the digest of [Blake3_256] for the same input.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Blake3_512<B: StarkField>(PhantomData<B>);
+
+impl<B: StarkField> Hasher for Blake3_512<B> {
+    type Digest = ByteDigest<64>;
+
+    const COLLISION_RESISTANCE: u32 = 256;
+
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        let mut hasher = BlakeHasher::new();
+        hasher.write_bytes(bytes);
+        ByteDigest(hasher.finalize_512())
+    }
+
+    fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
+        Self::hash(ByteDigest::digests_as_bytes(values))
+    }
+
+    fn merge_with_int(seed: Self::Digest, value: u64) -> Self::Digest {
+        let mut data = [0; 72];
+        data[..64].copy_from_slice(&seed.0);
+        data[64..].copy_from_slice(&value.to_le_bytes());
+        Self::hash(&data)
+    }
+}
+
+impl<B: StarkField> ElementHasher for Blake3_512<B> {
+    type BaseField = B;
+
+    fn hash_elements<E: FieldElement<BaseField = Self::BaseField>>(elements: &[E]) -> Self::Digest {
+        if B::IS_CANONICAL {
+            // when element's internal and canonical representations are the same, we can hash
+            // element bytes directly
+            Self::hash(E::elements_as_bytes(elements))
+        } else {
+            // when elements' internal and canonical representations differ, we need to serialize
+            // them before hashing
+            let mut hasher = BlakeHasher::new();
+            hasher.write(elements);
+            ByteDigest(hasher.finalize_512())
+        }
+    }
+}
+
 // BLAKE3 192-BIT OUTPUT
 // ================================================================================================
 
@@ -127,6 +179,12 @@ impl BlakeHasher {
     pub fn finalize(&self) -> [u8; 32] {
         *self.0.finalize().as_bytes()
     }
+
+    pub fn finalize_512(&self) -> [u8; 64] {
+        let mut result = [0; 64];
+        self.0.finalize_xof().fill(&mut result);
+        result
+    }
 }
 
 impl ByteWriter for BlakeHasher {
@@ -3,9 +3,11 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{Blake3_256, ElementHasher, Hasher};
+use super::{Blake3_256, Blake3_512, ElementHasher, Hasher};
+use crate::{BatchMerkleProof, Digest, MerkleTree};
 use math::{fields::f62::BaseElement, FieldElement};
-use rand_utils::rand_array;
+use rand_utils::{rand_array, rand_vector};
+use utils::{Serializable, SliceReader};
 
 #[test]
 fn hash_padding() {
@@ -28,3 +30,56 @@ fn hash_elements_padding() {
     let r2 = Blake3_256::hash_elements(&e2);
     assert_ne!(r1, r2);
 }
+
+#[test]
+fn hash_512() {
+    let bytes: [u8; 100] = rand_array::<u8, 100>();
+
+    // the first half of a 512-bit digest is the same as the 256-bit digest
+    let r1 = Blake3_512::<BaseElement>::hash(&bytes);
+    let r2 = Blake3_256::<BaseElement>::hash(&bytes);
+    assert_eq!(r2.as_bytes(), r1.as_bytes());
+    assert_eq!(64, r1.to_bytes().len());
+
+    // adding a zero bytes at the end of a byte string should result in a different hash
+    let r3 = Blake3_512::<BaseElement>::hash(&[&bytes[..], &[0]].concat());
+    assert_ne!(r1, r3);
+
+    // merging digests should be the same as hashing their concatenation
+    let merged = Blake3_512::<BaseElement>::merge(&[r1, r3]);
+    let expected = Blake3_512::<BaseElement>::hash(&[r1.to_bytes(), r3.to_bytes()].concat());
+    assert_eq!(expected, merged);
+
+    // elements should be hashed in the same way regardless of their representation
+    let elements = rand_vector::<BaseElement>(8);
+    let mut element_bytes = Vec::new();
+    elements.write_into(&mut element_bytes);
+    assert_eq!(
+        Blake3_512::<BaseElement>::hash(&element_bytes),
+        Blake3_512::hash_elements(&elements)
+    );
+}
+
+#[test]
+fn merkle_tree_512() {
+    let leaves = (0..16u8).map(|i| Blake3_512::<BaseElement>::hash(&[i])).collect::<Vec<_>>();
+    let tree = MerkleTree::<Blake3_512<BaseElement>>::new(leaves.clone()).unwrap();
+    for (i, leaf) in leaves.iter().enumerate() {
+        let path = tree.prove(i).unwrap();
+        assert_eq!(*leaf, path[0]);
+        assert!(MerkleTree::<Blake3_512<BaseElement>>::verify(*tree.root(), i, &path).is_ok());
+    }
+
+    // batch proofs are serialized with full 512-bit nodes
+    let indexes = [1, 5, 9];
+    let proof = tree.prove_batch(&indexes).unwrap();
+    let bytes = proof.serialize_nodes();
+    let leaves = indexes.iter().map(|&i| leaves[i]).collect::<Vec<_>>();
+    let proof = BatchMerkleProof::<Blake3_512<BaseElement>>::deserialize(
+        &mut SliceReader::new(&bytes),
+        leaves,
+        tree.depth() as u8,
+    )
+    .unwrap();
+    assert!(MerkleTree::verify_batch(tree.root(), &indexes, &proof).is_ok());
+}
@@ -8,7 +8,7 @@ use math::{FieldElement, StarkField};
 use utils::{ByteReader, Deserializable, DeserializationError, Serializable};
 
 mod blake;
-pub use blake::{Blake3_192, Blake3_256};
+pub use blake::{Blake3_192, Blake3_256, Blake3_512};
 
 mod sha;
 pub use sha::Sha3_256;
@@ -74,7 +74,9 @@ pub trait Digest:
     /// Ideally, the length of the returned array should be defined by an associated constant, but
     /// using associated constants in const generics is not supported by Rust yet. Thus, we put an
     /// upper limit on the possible digest size. For digests which are smaller than 32 bytes, the
-    /// unused bytes should be set to 0.
+    /// unused bytes should be set to 0. For digests which are larger than 32 bytes, the returned
+    /// array should contain the first 32 bytes of the digest; all bytes of such digests can be
+    /// obtained via their serialization (i.e., [Serializable::to_bytes()]).
     fn as_bytes(&self) -> [u8; 32];
 }
 
@@ -107,7 +109,8 @@ impl<const N: usize> ByteDigest<N> {
 impl<const N: usize> Digest for ByteDigest<N> {
     fn as_bytes(&self) -> [u8; 32] {
         let mut result = [0; 32];
-        result[..N].copy_from_slice(&self.0);
+        let num_bytes = N.min(32);
+        result[..num_bytes].copy_from_slice(&self.0[..num_bytes]);
         result
     }
 }
@@ -143,5 +146,10 @@ mod tests {
         let mut expected = [255_u8; 32];
         expected[31] = 0;
         assert_eq!(expected, d.as_bytes());
+
+        let mut bytes = [255_u8; 64];
+        bytes[32..].fill(1);
+        let d = ByteDigest::new(bytes);
+        assert_eq!([255_u8; 32], d.as_bytes());
     }
 }
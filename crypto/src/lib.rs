@@ -29,6 +29,7 @@ pub mod hashers {
 
     pub use super::hash::Blake3_192;
     pub use super::hash::Blake3_256;
+    pub use super::hash::Blake3_512;
     pub use super::hash::GriffinJive64_256;
     pub use super::hash::Rp62_248;
     pub use super::hash::Rp64_256;
@@ -43,7 +44,10 @@ pub use merkle::{build_merkle_nodes, BatchMerkleProof, MerkleTree};
 pub use merkle::concurrent;
 
 mod random;
-pub use random::{DefaultRandomCoin, RandomCoin};
+pub use random::{DefaultRandomCoin, IntegerSampling, RandomCoin};
+
+#[cfg(feature = "bench")]
+pub use random::{ChallengeSequence, FixedRandomCoin};
 
 mod errors;
 pub use errors::{MerkleTreeError, RandomCoinError};
@@ -172,6 +172,41 @@ impl<H: Hasher> MerkleTree<H> {
         &self.leaves
     }
 
+    // UPDATE METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Replaces the leaf at the specified `index` with `new_leaf` and returns the new root of
+    /// the tree.
+    ///
+    /// Only the internal nodes on the path from the leaf to the root are recomputed; thus, the
+    /// update requires $O(\log n)$ hash computations, and the resulting tree is the same as the
+    /// tree built from scratch from the updated leaves.
+    ///
+    /// # Errors
+    /// Returns an error if the specified index is greater than or equal to the number of leaves
+    /// in the tree.
+    pub fn update_leaf(
+        &mut self,
+        index: usize,
+        new_leaf: H::Digest,
+    ) -> Result<H::Digest, MerkleTreeError> {
+        if index >= self.leaves.len() {
+            return Err(MerkleTreeError::LeafIndexOutOfBounds(self.leaves.len(), index));
+        }
+        self.leaves[index] = new_leaf;
+
+        // recompute the parent of the leaf, and then all nodes on the path to the root
+        let first = index & !1;
+        let mut index = (index + self.nodes.len()) >> 1;
+        self.nodes[index] = H::merge(&[self.leaves[first], self.leaves[first + 1]]);
+        while index > 1 {
+            index >>= 1;
+            self.nodes[index] = H::merge(&[self.nodes[2 * index], self.nodes[2 * index + 1]]);
+        }
+
+        Ok(self.nodes[1])
+    }
+
     // PROVING METHODS
     // --------------------------------------------------------------------------------------------
 
@@ -320,6 +355,40 @@ impl<H: Hasher> MerkleTree<H> {
         }
         Ok(())
     }
+
+    /// Returns true if the specified `leaves`, together with the pruned authentication paths
+    /// specified by `nodes`, resolve to the specified `root` for a tree of the specified `depth`.
+    ///
+    /// This is the same check as performed by [verify_batch()](MerkleTree::verify_batch), but the
+    /// parts of a [BatchMerkleProof] are provided separately. Thus, it can be used to check
+    /// multiproofs received or assembled independently of this crate's proof types:
+    /// * `leaves` must contain the leaf at each of the `indexes`, in the same order as the
+    ///   indexes.
+    /// * `nodes` must contain the pruned set of internal nodes in the same layout as
+    ///   [BatchMerkleProof::nodes].
+    ///
+    /// Returns false if any of the leaves or nodes is incorrect, if the number of leaves differs
+    /// from the number of indexes, if the number of leaves in a tree of the specified `depth` does
+    /// not fit into `usize`, or if the indexes are not valid for a tree of the specified `depth`
+    /// (e.g., there are too many of them, they contain duplicates, or any of them is out of
+    /// bounds).
+    pub fn verify_batch_from_parts(
+        root: &H::Digest,
+        depth: usize,
+        indexes: &[usize],
+        leaves: &[H::Digest],
+        nodes: &[Vec<H::Digest>],
+    ) -> bool {
+        if leaves.len() != indexes.len() || depth == 0 || depth >= usize::BITS as usize {
+            return false;
+        }
+        let proof = BatchMerkleProof::<H> {
+            leaves: leaves.to_vec(),
+            nodes: nodes.to_vec(),
+            depth: depth as u8,
+        };
+        matches!(proof.get_root(indexes), Ok(r) if r == *root)
+    }
 }
 
 // HELPER FUNCTIONS
@@ -115,6 +115,38 @@ fn prove() {
     assert_eq!(proof, tree.prove(6).unwrap());
 }
 
+#[test]
+fn update_leaf() {
+    let leaves = Digest256::bytes_as_digests(&LEAVES8).to_vec();
+    let mut tree = MerkleTree::<Blake3_256>::new(leaves.clone()).unwrap();
+    let mut expected_leaves = leaves.clone();
+
+    // update several leaves, including the first and the last ones, and the same leaf twice;
+    // after each update the tree must be the same as a tree built from the updated leaves
+    let new_leaves = Digest256::bytes_as_digests(&LEAVES4);
+    for (index, new_leaf) in [(0, 0), (5, 1), (7, 2), (5, 3), (2, 0)] {
+        expected_leaves[index] = new_leaves[new_leaf];
+        let root = tree.update_leaf(index, new_leaves[new_leaf]).unwrap();
+
+        let expected = MerkleTree::<Blake3_256>::new(expected_leaves.clone()).unwrap();
+        assert_eq!(expected.root(), &root);
+        assert_eq!(expected.root(), tree.root());
+        assert_eq!(expected.leaves(), tree.leaves());
+        assert_eq!(expected.prove(index).unwrap(), tree.prove(index).unwrap());
+    }
+
+    // restoring the original leaves restores the original root
+    for index in [0, 2, 5, 7] {
+        tree.update_leaf(index, leaves[index]).unwrap();
+    }
+    assert_eq!(MerkleTree::<Blake3_256>::new(leaves).unwrap().root(), tree.root());
+
+    assert_eq!(
+        Err(MerkleTreeError::LeafIndexOutOfBounds(8, 8)),
+        tree.update_leaf(8, new_leaves[0])
+    );
+}
+
 #[test]
 fn verify() {
     // depth 4
@@ -211,6 +243,108 @@ fn verify_batch() {
     assert!(MerkleTree::verify_batch(tree.root(), &[0, 1, 2, 3, 4, 5, 6, 7], &proof).is_ok());
 }
 
+#[test]
+fn verify_batch_from_parts() {
+    for depth in [1, 2, 3, 5, 7] {
+        let num_leaves = 1 << depth;
+        let leaves = (0..num_leaves as u128)
+            .map(|i| Blake3_256::hash(&i.to_le_bytes()))
+            .collect::<Vec<_>>();
+        let tree = MerkleTree::<Blake3_256>::new(leaves.clone()).unwrap();
+        let root = tree.root();
+
+        let index_sets = [
+            vec![0],
+            vec![num_leaves - 1],
+            vec![num_leaves / 2, 0],
+            (0..num_leaves).step_by(3).collect::<Vec<_>>(),
+            (0..num_leaves).collect::<Vec<_>>(),
+        ];
+        for indexes in index_sets.iter() {
+            let proof = tree.prove_batch(indexes).unwrap();
+            let queried = indexes.iter().map(|&i| leaves[i]).collect::<Vec<_>>();
+
+            // correct leaves and pruned nodes resolve to the root
+            assert!(MerkleTree::<Blake3_256>::verify_batch_from_parts(
+                root,
+                depth,
+                indexes,
+                &queried,
+                &proof.nodes
+            ));
+
+            // but not against a different root or a different depth
+            assert!(!MerkleTree::<Blake3_256>::verify_batch_from_parts(
+                &leaves[0],
+                depth,
+                indexes,
+                &queried,
+                &proof.nodes
+            ));
+            assert!(!MerkleTree::<Blake3_256>::verify_batch_from_parts(
+                root,
+                depth + 1,
+                indexes,
+                &queried,
+                &proof.nodes
+            ));
+
+            // an incorrect leaf is rejected
+            for i in 0..queried.len() {
+                let mut corrupted = queried.clone();
+                corrupted[i] = Blake3_256::hash(b"corrupted");
+                assert!(!MerkleTree::<Blake3_256>::verify_batch_from_parts(
+                    root,
+                    depth,
+                    indexes,
+                    &corrupted,
+                    &proof.nodes
+                ));
+            }
+
+            // a missing leaf is rejected
+            assert!(!MerkleTree::<Blake3_256>::verify_batch_from_parts(
+                root,
+                depth,
+                indexes,
+                &queried[1..],
+                &proof.nodes
+            ));
+
+            // a tampered or a missing pruned node is rejected
+            for (i, path) in proof.nodes.iter().enumerate() {
+                for j in 0..path.len() {
+                    let mut tampered = proof.nodes.clone();
+                    tampered[i][j] = Blake3_256::hash(b"tampered");
+                    assert!(!MerkleTree::<Blake3_256>::verify_batch_from_parts(
+                        root, depth, indexes, &queried, &tampered
+                    ));
+
+                    let mut pruned = proof.nodes.clone();
+                    pruned[i].remove(j);
+                    assert!(!MerkleTree::<Blake3_256>::verify_batch_from_parts(
+                        root, depth, indexes, &queried, &pruned
+                    ));
+                }
+            }
+        }
+    }
+
+    // depths for which the number of leaves does not fit into usize are rejected
+    let leaves = (0..2u128).map(|i| Blake3_256::hash(&i.to_le_bytes())).collect::<Vec<_>>();
+    let tree = MerkleTree::<Blake3_256>::new(leaves.clone()).unwrap();
+    let proof = tree.prove_batch(&[0]).unwrap();
+    for depth in [64, 255] {
+        assert!(!MerkleTree::<Blake3_256>::verify_batch_from_parts(
+            tree.root(),
+            depth,
+            &[0],
+            &leaves[..1],
+            &proof.nodes
+        ));
+    }
+}
+
 #[test]
 fn verify_into_paths() {
     let leaves = Digest256::bytes_as_digests(&LEAVES8).to_vec();
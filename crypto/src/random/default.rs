@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::{errors::RandomCoinError, Digest, ElementHasher, RandomCoin};
+use crate::{errors::RandomCoinError, Digest, ElementHasher, IntegerSampling, RandomCoin};
 use core::convert::TryInto;
 use math::{FieldElement, StarkField};
 use utils::collections::Vec;
@@ -156,6 +156,9 @@ impl<B: StarkField, H: ElementHasher<BaseField = B>> RandomCoin for DefaultRando
     /// Returns a vector of integers selected from the range [0, domain_size) after reseeding
     /// the PRNG with the specified `nonce` by setting the new seed to hash(`seed` || `nonce`).
     ///
+    /// This is equivalent to calling [draw_integers_with()](RandomCoin::draw_integers_with) with
+    /// [IntegerSampling::LowBits] method.
+    ///
     /// # Errors
     /// Returns an error if the specified number of integers could not be generated after 1000
     /// calls to the PRNG.
@@ -164,11 +167,35 @@ impl<B: StarkField, H: ElementHasher<BaseField = B>> RandomCoin for DefaultRando
     /// Panics if:
     /// - `domain_size` is not a power of two.
     /// - `num_values` is greater than or equal to `domain_size`.
+    fn draw_integers(
+        &mut self,
+        num_values: usize,
+        domain_size: usize,
+        nonce: u64,
+    ) -> Result<Vec<usize>, RandomCoinError> {
+        self.draw_integers_with(num_values, domain_size, nonce, IntegerSampling::LowBits)
+    }
+
+    /// Returns a vector of integers selected from the range [0, domain_size) after reseeding
+    /// the PRNG with the specified `nonce` by setting the new seed to hash(`seed` || `nonce`).
+    ///
+    /// After reseeding, the counter is reset to 0, and the integers are extracted from the values
+    /// hash(`seed` || 1), hash(`seed` || 2) etc. according to the specified `sampling` method
+    /// (see [IntegerSampling] for details).
+    ///
+    /// # Errors
+    /// Returns an error if the specified number of integers could not be generated after 1000
+    /// calls to the PRNG.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// - `domain_size` is not a power of two, and `sampling` is not [IntegerSampling::Rejection].
+    /// - `num_values` is greater than or equal to `domain_size`.
     ///
     /// # Examples
     /// ```
     /// # use std::collections::HashSet;
-    /// # use winter_crypto::{RandomCoin, DefaultRandomCoin, Hasher, hashers::Blake3_256};
+    /// # use winter_crypto::{RandomCoin, DefaultRandomCoin, Hasher, IntegerSampling, hashers::Blake3_256};
     /// # use math::fields::f128::BaseElement;
     /// // initial elements for seeding the random coin
     /// let seed = &[BaseElement::new(1), BaseElement::new(2), BaseElement::new(3), BaseElement::new(4)];
@@ -178,7 +205,9 @@ impl<B: StarkField, H: ElementHasher<BaseField = B>> RandomCoin for DefaultRando
     /// let num_values = 20;
     /// let domain_size = 64;
     /// let nonce = 0;
-    /// let values = coin.draw_integers(num_values, domain_size, nonce).unwrap();
+    /// let values = coin
+    ///     .draw_integers_with(num_values, domain_size, nonce, IntegerSampling::LowBits)
+    ///     .unwrap();
     ///
     /// assert_eq!(num_values, values.len());
     ///
@@ -186,35 +215,43 @@ impl<B: StarkField, H: ElementHasher<BaseField = B>> RandomCoin for DefaultRando
     ///     assert!(value < domain_size);
     /// }
     /// ```
-    fn draw_integers(
+    fn draw_integers_with(
         &mut self,
         num_values: usize,
         domain_size: usize,
         nonce: u64,
+        sampling: IntegerSampling,
     ) -> Result<Vec<usize>, RandomCoinError> {
-        assert!(domain_size.is_power_of_two(), "domain size must be a power of two");
+        assert!(
+            domain_size.is_power_of_two() || sampling == IntegerSampling::Rejection,
+            "domain size must be a power of two"
+        );
         assert!(num_values < domain_size, "number of values must be smaller than domain size");
 
         // reseed with nonce
         self.seed = H::merge_with_int(self.seed, nonce);
         self.counter = 0;
 
-        // determine how many bits are needed to represent valid values in the domain
-        let v_mask = (domain_size - 1) as u64;
+        // determine how many values can be extracted from a single PRNG output
+        let values_per_draw = sampling.values_per_draw(domain_size);
 
-        // draw values from PRNG until we get as many unique values as specified by num_queries
+        // draw values from PRNG until we get as many values as specified by num_queries
         let mut values = Vec::new();
-        for _ in 0..1000 {
+        'draws: for _ in 0..1000 {
             // get the next pseudo-random value and read the first 8 bytes from it
             let bytes: [u8; 8] = self.next().as_bytes()[..8].try_into().unwrap();
+            let word = u64::from_le_bytes(bytes);
+            if !sampling.accepts(word, domain_size) {
+                continue;
+            }
 
-            // convert to integer and limit the integer to the number of bits which can fit
-            // into the specified domain
-            let value = (u64::from_le_bytes(bytes) & v_mask) as usize;
-
-            values.push(value);
-            if values.len() == num_values {
-                break;
+            // extract as many integers from the word as the sampling method allows; each integer
+            // is limited to the number of bits which can fit into the specified domain
+            for index in 0..values_per_draw {
+                values.push(sampling.extract(word, domain_size, index));
+                if values.len() == num_values {
+                    break 'draws;
+                }
             }
         }
 
@@ -0,0 +1,199 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{errors::RandomCoinError, ElementHasher, Hasher, IntegerSampling, RandomCoin};
+use core::marker::PhantomData;
+use math::{FieldElement, StarkField};
+use utils::collections::Vec;
+
+// CONSTANTS
+// ================================================================================================
+
+/// The only nonce accepted by [FixedRandomCoin::check_leading_zeros()].
+const POW_NONCE: u64 = 1;
+
+// CHALLENGE SEQUENCE
+// ================================================================================================
+
+/// Defines a fixed sequence of 64-bit words from which a [FixedRandomCoin] derives its outputs.
+pub trait ChallengeSequence: Sync {
+    /// Words of the sequence; the sequence is repeated once all words have been consumed.
+    const CHALLENGES: &'static [u64];
+}
+
+// FIXED RANDOM COIN
+// ================================================================================================
+
+/// Random coin which reads its outputs from a fixed challenge sequence, without any hashing.
+///
+/// **This coin is NOT secure and must never be used in production.** It is intended only for
+/// benchmarking, to make the cost of proof generation comparable with provers which do not hash
+/// the transcript (or hash it differently): the coin ignores its seed and all the data it is
+/// reseeded with, and thus, the drawn challenges do not depend on the statement or on the
+/// commitments made by the prover.
+///
+/// The coin works as follows:
+/// - Field elements are drawn by reading `E::ELEMENT_BYTES` bytes from the next words of the
+///   sequence `S` (in little-endian byte order); words which do not encode a valid element are
+///   skipped.
+/// - Integers are extracted from the next words of the sequence according to the specified
+///   [IntegerSampling] method.
+/// - Proof-of-work is replaced with a fixed nonce: only nonce 1 is reported to have leading
+///   zeros.
+///
+/// Proofs generated with this coin can be verified only by a verifier which uses the same coin
+/// instantiated with the same challenge sequence.
+pub struct FixedRandomCoin<H: ElementHasher, S: ChallengeSequence> {
+    position: usize,
+    _hasher: PhantomData<H::Digest>,
+    _sequence: PhantomData<S>,
+}
+
+impl<H: ElementHasher, S: ChallengeSequence> FixedRandomCoin<H, S> {
+    /// Returns the next word of the challenge sequence.
+    fn next(&mut self) -> u64 {
+        let word = S::CHALLENGES[self.position % S::CHALLENGES.len()];
+        self.position += 1;
+        word
+    }
+}
+
+impl<B, H, S> RandomCoin for FixedRandomCoin<H, S>
+where
+    B: StarkField,
+    H: ElementHasher<BaseField = B>,
+    S: ChallengeSequence,
+{
+    type BaseField = B;
+    type Hasher = H;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new random coin positioned at the start of the challenge sequence; the `seed` is
+    /// ignored.
+    ///
+    /// # Panics
+    /// Panics if the challenge sequence is empty.
+    fn new(_seed: &[Self::BaseField]) -> Self {
+        assert!(!S::CHALLENGES.is_empty(), "challenge sequence cannot be empty");
+        Self {
+            position: 0,
+            _hasher: PhantomData,
+            _sequence: PhantomData,
+        }
+    }
+
+    // RESEEDING
+    // --------------------------------------------------------------------------------------------
+
+    /// Does nothing; the outputs of this coin do not depend on the data it is reseeded with.
+    fn reseed(&mut self, _data: <Self::Hasher as Hasher>::Digest) {}
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the maximum number of leading zeros (i.e., 64) for nonce 1, and 0 for all other
+    /// nonces.
+    fn check_leading_zeros(&self, value: u64) -> u32 {
+        if value == POW_NONCE {
+            u64::BITS
+        } else {
+            0
+        }
+    }
+
+    // DRAW METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the next field element read from the challenge sequence.
+    ///
+    /// # Errors
+    /// Returns an error if a valid field element could not be read from the next 1000 chunks of
+    /// the challenge sequence.
+    fn draw<E: FieldElement>(&mut self) -> Result<E, RandomCoinError> {
+        let mut bytes = Vec::with_capacity(E::ELEMENT_BYTES + 8);
+        for _ in 0..1000 {
+            bytes.clear();
+            while bytes.len() < E::ELEMENT_BYTES {
+                bytes.extend_from_slice(&self.next().to_le_bytes());
+            }
+
+            if let Some(element) = E::from_random_bytes(&bytes[..E::ELEMENT_BYTES]) {
+                return Ok(element);
+            }
+        }
+
+        Err(RandomCoinError::FailedToDrawFieldElement(1000))
+    }
+
+    /// Returns a vector of integers selected from the range [0, domain_size), which are
+    /// extracted from the next words of the challenge sequence using [IntegerSampling::LowBits]
+    /// method; the `nonce` is ignored.
+    ///
+    /// # Errors
+    /// Returns an error if the specified number of integers could not be extracted from the next
+    /// 1000 words of the challenge sequence.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// - `domain_size` is not a power of two.
+    /// - `num_values` is greater than or equal to `domain_size`.
+    fn draw_integers(
+        &mut self,
+        num_values: usize,
+        domain_size: usize,
+        nonce: u64,
+    ) -> Result<Vec<usize>, RandomCoinError> {
+        self.draw_integers_with(num_values, domain_size, nonce, IntegerSampling::LowBits)
+    }
+
+    /// Returns a vector of integers selected from the range [0, domain_size), which are
+    /// extracted from the next words of the challenge sequence according to the specified
+    /// `sampling` method; the `nonce` is ignored.
+    ///
+    /// # Errors
+    /// Returns an error if the specified number of integers could not be extracted from the next
+    /// 1000 words of the challenge sequence.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// - `domain_size` is not a power of two, and `sampling` is not [IntegerSampling::Rejection].
+    /// - `num_values` is greater than or equal to `domain_size`.
+    fn draw_integers_with(
+        &mut self,
+        num_values: usize,
+        domain_size: usize,
+        _nonce: u64,
+        sampling: IntegerSampling,
+    ) -> Result<Vec<usize>, RandomCoinError> {
+        assert!(
+            domain_size.is_power_of_two() || sampling == IntegerSampling::Rejection,
+            "domain size must be a power of two"
+        );
+        assert!(num_values < domain_size, "number of values must be smaller than domain size");
+
+        let values_per_draw = sampling.values_per_draw(domain_size);
+        let mut values = Vec::new();
+        'draws: for _ in 0..1000 {
+            let word = self.next();
+            if !sampling.accepts(word, domain_size) {
+                continue;
+            }
+
+            for index in 0..values_per_draw {
+                values.push(sampling.extract(word, domain_size, index));
+                if values.len() == num_values {
+                    break 'draws;
+                }
+            }
+        }
+
+        if values.len() < num_values {
+            return Err(RandomCoinError::FailedToDrawIntegers(num_values, values.len(), 1000));
+        }
+
+        Ok(values)
+    }
+}
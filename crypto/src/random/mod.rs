@@ -5,11 +5,21 @@
 
 use crate::{errors::RandomCoinError, ElementHasher, Hasher};
 use math::{FieldElement, StarkField};
-use utils::collections::Vec;
+use utils::{
+    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+};
 
 mod default;
 pub use default::DefaultRandomCoin;
 
+#[cfg(feature = "bench")]
+mod fixed;
+#[cfg(feature = "bench")]
+pub use fixed::{ChallengeSequence, FixedRandomCoin};
+
+#[cfg(test)]
+mod tests;
+
 // RANDOM COIN TRAIT
 // ================================================================================================
 
@@ -50,6 +60,8 @@ pub trait RandomCoin: Sync {
     /// Returns a vector of integers selected from the range [0, domain_size) after it reseeds
     /// the coin with a nonce.
     ///
+    /// The integers are expected to be derived using [IntegerSampling::LowBits] method.
+    ///
     /// # Errors
     /// Returns an error if the specified number of integers could not be generated after 1000
     /// calls to the PRNG.
@@ -64,4 +76,192 @@ pub trait RandomCoin: Sync {
         domain_size: usize,
         nonce: u64,
     ) -> Result<Vec<usize>, RandomCoinError>;
+
+    // PROVIDED METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a vector of integers selected from the range [0, domain_size) after it reseeds
+    /// the coin with a nonce. The integers are derived from the PRNG outputs using the specified
+    /// `sampling` method.
+    ///
+    /// The default implementation delegates to [draw_integers()](RandomCoin::draw_integers) for
+    /// [IntegerSampling::LowBits] method, and returns an error for all other methods. Random
+    /// coins which support other sampling methods should override this method.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The specified number of integers could not be generated after 1000 calls to the PRNG.
+    /// - The specified `sampling` method is not supported by this random coin.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// - `domain_size` is not a power of two, and `sampling` is not [IntegerSampling::Rejection].
+    /// - `num_values` is greater than or equal to `domain_size`.
+    fn draw_integers_with(
+        &mut self,
+        num_values: usize,
+        domain_size: usize,
+        nonce: u64,
+        sampling: IntegerSampling,
+    ) -> Result<Vec<usize>, RandomCoinError> {
+        match sampling {
+            IntegerSampling::LowBits => self.draw_integers(num_values, domain_size, nonce),
+            _ => Err(RandomCoinError::UnsupportedIntegerSampling(sampling)),
+        }
+    }
+}
+
+// INTEGER SAMPLING
+// ================================================================================================
+
+/// Defines how integers in the range [0, domain_size) are extracted from the outputs of a PRNG.
+///
+/// This is primarily used to derive query positions from the public coin. Since different STARK
+/// implementations derive query positions differently, the method is selectable so that proofs
+/// can be verified by external verifiers (and vice versa). The prover and the verifier must use
+/// the same method, otherwise they will arrive at different query positions.
+///
+/// All methods operate on a single 64-bit *word* per PRNG output. The word is obtained by
+/// reading the first 8 bytes of the output (i.e., of hash(`seed` || `counter`)) as an unsigned
+/// integer in little-endian byte order. Except for [Rejection], domain size must be a power of
+/// two, and thus, each integer consumes exactly `log2(domain_size)` bits of the word; no modular
+/// reduction or rejection sampling is performed, and the resulting integers are uniformly
+/// distributed.
+///
+/// [Rejection] supports domains of arbitrary size by reducing the whole word modulo the domain
+/// size, and rejecting words which would make the reduction biased. For power-of-two domains,
+/// no word is ever rejected and the integers are identical to the ones produced by [LowBits].
+///
+/// Integers are returned in the order in which they were extracted, and may contain duplicates.
+///
+/// For example, for a domain of size 2^4 and a word 0xfedc_ba98_7654_3210, [LowBits] yields 0,
+/// [HighBits] yields 1 (the high nibble of the first byte 0x10), and [PackedBits] yields
+/// 0, 1, 2, ..., 15.
+///
+/// [LowBits]: IntegerSampling::LowBits
+/// [HighBits]: IntegerSampling::HighBits
+/// [PackedBits]: IntegerSampling::PackedBits
+/// [Rejection]: IntegerSampling::Rejection
+#[repr(u8)]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum IntegerSampling {
+    /// One integer is extracted per PRNG output by taking the `log2(domain_size)` least
+    /// significant bits of the word.
+    #[default]
+    LowBits = 0,
+    /// One integer is extracted per PRNG output by reading the first 8 bytes of the output in
+    /// big-endian byte order and taking the `log2(domain_size)` most significant bits of the
+    /// resulting integer.
+    HighBits = 1,
+    /// `floor(64 / log2(domain_size))` integers are extracted per PRNG output by splitting the
+    /// word into consecutive chunks of `log2(domain_size)` bits, starting with the least
+    /// significant bit. Any leftover high bits are discarded.
+    PackedBits = 2,
+    /// One integer is extracted per PRNG output by reducing the word modulo `domain_size`. To
+    /// keep the result unbiased, words greater than or equal to the largest multiple of
+    /// `domain_size` not exceeding 2^64 are rejected, and the next PRNG output is used instead.
+    /// The probability of a rejection is smaller than `domain_size / 2^64`.
+    Rejection = 3,
+}
+
+impl IntegerSampling {
+    /// Returns the number of integers extracted from a single PRNG output for a domain of the
+    /// specified size.
+    ///
+    /// # Panics
+    /// Panics if `domain_size` is not greater than 1, or if it is not a power of two and `self`
+    /// is not [IntegerSampling::Rejection].
+    pub fn values_per_draw(&self, domain_size: usize) -> usize {
+        match self {
+            Self::LowBits | Self::HighBits | Self::Rejection => {
+                self.validate_domain_size(domain_size);
+                1
+            }
+            Self::PackedBits => (u64::BITS / self.bits_per_value(domain_size)) as usize,
+        }
+    }
+
+    /// Returns `true` if integers can be extracted from the provided 64-bit `word` for a domain
+    /// of the specified size.
+    ///
+    /// This is always `true` for all methods except for [IntegerSampling::Rejection], for which
+    /// words in the biased range are rejected.
+    ///
+    /// # Panics
+    /// Panics if `domain_size` is not greater than 1, or if it is not a power of two and `self`
+    /// is not [IntegerSampling::Rejection].
+    pub fn accepts(&self, word: u64, domain_size: usize) -> bool {
+        self.validate_domain_size(domain_size);
+        match self {
+            Self::LowBits | Self::HighBits | Self::PackedBits => true,
+            Self::Rejection => {
+                // 2^64 mod domain_size; words in the top `excess` values of the u64 range
+                // would make reduction modulo the domain size biased
+                let domain_size = domain_size as u64;
+                let excess = (u64::MAX % domain_size + 1) % domain_size;
+                word <= u64::MAX - excess
+            }
+        }
+    }
+
+    /// Returns the integer at the specified `index` extracted from the provided 64-bit `word`.
+    ///
+    /// For [IntegerSampling::Rejection] the caller is expected to check that the word is not
+    /// rejected via [accepts()](IntegerSampling::accepts) before extracting values from it.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// - `domain_size` is not greater than 1, or if it is not a power of two and `self` is not
+    ///   [IntegerSampling::Rejection].
+    /// - `index` is greater than or equal to the number of values per draw.
+    pub fn extract(&self, word: u64, domain_size: usize, index: usize) -> usize {
+        assert!(
+            index < self.values_per_draw(domain_size),
+            "index {index} is out of bounds for {self:?} sampling"
+        );
+        let mask = (domain_size as u64).wrapping_sub(1);
+        let value = match self {
+            Self::LowBits => word & mask,
+            Self::HighBits => word.swap_bytes() >> (u64::BITS - self.bits_per_value(domain_size)),
+            Self::PackedBits => (word >> (index as u32 * self.bits_per_value(domain_size))) & mask,
+            Self::Rejection => word % domain_size as u64,
+        };
+        value as usize
+    }
+
+    /// Returns the number of bits needed to encode a single value in the domain.
+    fn bits_per_value(&self, domain_size: usize) -> u32 {
+        self.validate_domain_size(domain_size);
+        domain_size.ilog2()
+    }
+
+    /// Makes sure integers can be extracted for the specified domain size using this method.
+    fn validate_domain_size(&self, domain_size: usize) {
+        assert!(domain_size > 1, "domain size must be greater than 1");
+        if *self != Self::Rejection {
+            assert!(domain_size.is_power_of_two(), "domain size must be a power of two");
+        }
+    }
+}
+
+impl Serializable for IntegerSampling {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self as u8);
+    }
+}
+
+impl Deserializable for IntegerSampling {
+    /// Reads an integer sampling enum from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            0 => Ok(IntegerSampling::LowBits),
+            1 => Ok(IntegerSampling::HighBits),
+            2 => Ok(IntegerSampling::PackedBits),
+            3 => Ok(IntegerSampling::Rejection),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "value {value} cannot be deserialized as IntegerSampling enum"
+            ))),
+        }
+    }
 }
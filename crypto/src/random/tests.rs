@@ -0,0 +1,229 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{DefaultRandomCoin, IntegerSampling, RandomCoin};
+use crate::{hash::Blake3_256, Digest, ElementHasher, Hasher, RandomCoinError};
+use math::fields::f128::BaseElement;
+use utils::{collections::Vec, Deserializable, Serializable, SliceReader};
+
+type Blake3 = Blake3_256<BaseElement>;
+
+const DOMAIN_SIZE: usize = 1024;
+const NUM_VALUES: usize = 8;
+const NONCE: u64 = 7;
+
+// INTEGER SAMPLING TESTS
+// ================================================================================================
+
+#[test]
+fn integer_sampling_extract() {
+    let word = 0xfedc_ba98_7654_3210_u64;
+
+    assert_eq!(1, IntegerSampling::LowBits.values_per_draw(16));
+    assert_eq!(0, IntegerSampling::LowBits.extract(word, 16, 0));
+
+    assert_eq!(1, IntegerSampling::HighBits.values_per_draw(16));
+    assert_eq!(1, IntegerSampling::HighBits.extract(word, 16, 0));
+
+    assert_eq!(16, IntegerSampling::PackedBits.values_per_draw(16));
+    for i in 0..16 {
+        assert_eq!(i, IntegerSampling::PackedBits.extract(word, 16, i));
+    }
+
+    // for domains which do not evenly divide 64 bits, leftover bits are discarded
+    assert_eq!(6, IntegerSampling::PackedBits.values_per_draw(DOMAIN_SIZE));
+    assert_eq!(0x210, IntegerSampling::PackedBits.extract(word, DOMAIN_SIZE, 0));
+    assert_eq!(0x10c, IntegerSampling::PackedBits.extract(word, DOMAIN_SIZE, 1));
+}
+
+#[test]
+fn integer_sampling_serialization() {
+    for sampling in [
+        IntegerSampling::LowBits,
+        IntegerSampling::HighBits,
+        IntegerSampling::PackedBits,
+        IntegerSampling::Rejection,
+    ] {
+        let bytes = sampling.to_bytes();
+        let mut reader = SliceReader::new(&bytes);
+        assert_eq!(sampling, IntegerSampling::read_from(&mut reader).unwrap());
+    }
+
+    let mut reader = SliceReader::new(&[4]);
+    assert!(IntegerSampling::read_from(&mut reader).is_err());
+}
+
+#[test]
+fn integer_sampling_rejection() {
+    // for power-of-two domains nothing is rejected and values are the same as for low bits
+    for word in [0, 0x0123_4567_89ab_cdef, u64::MAX] {
+        assert!(IntegerSampling::Rejection.accepts(word, DOMAIN_SIZE));
+        assert_eq!(
+            IntegerSampling::LowBits.extract(word, DOMAIN_SIZE, 0),
+            IntegerSampling::Rejection.extract(word, DOMAIN_SIZE, 0)
+        );
+    }
+
+    // 2^64 mod 12 = 4, and thus, the 4 largest words must be rejected
+    assert!(IntegerSampling::Rejection.accepts(u64::MAX - 4, 12));
+    for word in u64::MAX - 3..=u64::MAX {
+        assert!(!IntegerSampling::Rejection.accepts(word, 12));
+    }
+    assert_eq!(11, IntegerSampling::Rejection.extract(u64::MAX - 4, 12, 0));
+    assert_eq!(5, IntegerSampling::Rejection.extract(29, 12, 0));
+}
+
+// DRAW INTEGERS TESTS
+// ================================================================================================
+
+#[test]
+fn draw_integers_fixed_transcript() {
+    // the default method must be the low-bits method
+    let mut coin = build_coin();
+    let values = coin.draw_integers(NUM_VALUES, DOMAIN_SIZE, NONCE).unwrap();
+    assert_eq!(draw(IntegerSampling::LowBits), values);
+
+    // these values are derived from the transcript by the algorithms documented for the
+    // IntegerSampling enum; external implementations can use them as test vectors
+    assert_eq!(vec![1008, 815, 142, 453, 362, 999, 863, 289], draw(IntegerSampling::LowBits));
+    assert_eq!(vec![962, 188, 570, 791, 426, 925, 382, 133], draw(IntegerSampling::HighBits));
+    assert_eq!(vec![1008, 420, 993, 483, 860, 963, 815, 137], draw(IntegerSampling::PackedBits));
+    assert_eq!(draw(IntegerSampling::LowBits), draw(IntegerSampling::Rejection));
+}
+
+#[test]
+fn draw_integers_explicit_bit_extraction() {
+    // derive PRNG words from the transcript directly: seed = hash(elements), the seed is then
+    // updated with the nonce, and the i-th word is the first 8 bytes of hash(seed || i)
+    let seed = Blake3::merge_with_int(Blake3::hash_elements(&seed_elements()), NONCE);
+    let words = (1..=NUM_VALUES as u64)
+        .map(|counter| {
+            let bytes = Blake3::merge_with_int(seed, counter).as_bytes();
+            u64::from_le_bytes(bytes[..8].try_into().unwrap())
+        })
+        .collect::<Vec<_>>();
+
+    let bits = DOMAIN_SIZE.ilog2();
+    let mask = (DOMAIN_SIZE - 1) as u64;
+
+    let expected = words.iter().map(|w| (w & mask) as usize).collect::<Vec<_>>();
+    assert_eq!(expected, draw(IntegerSampling::LowBits));
+
+    let expected = words
+        .iter()
+        .map(|w| (u64::from_be_bytes(w.to_le_bytes()) >> (64 - bits)) as usize)
+        .collect::<Vec<_>>();
+    assert_eq!(expected, draw(IntegerSampling::HighBits));
+
+    let expected = words
+        .iter()
+        .flat_map(|w| (0..64 / bits).map(move |i| ((w >> (i * bits)) & mask) as usize))
+        .take(NUM_VALUES)
+        .collect::<Vec<_>>();
+    assert_eq!(expected, draw(IntegerSampling::PackedBits));
+}
+
+#[test]
+fn draw_integers_rejection_uniform() {
+    // draw integers from a domain which is not a power of two using many different nonces, and
+    // make sure the distribution passes Pearson's chi-squared test for uniformity
+    const DOMAIN: usize = 12;
+    const NUM_NONCES: u64 = 4000;
+
+    let mut coin = build_coin();
+    let mut counts = [0_usize; DOMAIN];
+    for nonce in 0..NUM_NONCES {
+        let values = coin
+            .draw_integers_with(DOMAIN - 1, DOMAIN, nonce, IntegerSampling::Rejection)
+            .unwrap();
+        assert_eq!(DOMAIN - 1, values.len());
+        for value in values {
+            counts[value] += 1;
+        }
+    }
+
+    let num_values = NUM_NONCES as f64 * (DOMAIN - 1) as f64;
+    let expected = num_values / DOMAIN as f64;
+    let chi_squared = counts
+        .iter()
+        .map(|&count| (count as f64 - expected).powi(2) / expected)
+        .sum::<f64>();
+
+    // critical value of the chi-squared distribution with 11 degrees of freedom at p = 0.001
+    assert!(chi_squared < 31.26, "chi-squared statistic {chi_squared} is too large");
+}
+
+#[test]
+fn draw_integers_with_default_implementation() {
+    // a coin which implements only the required methods supports the low-bits method only
+    let mut coin = LowBitsCoin(build_coin());
+    let values = coin
+        .draw_integers_with(NUM_VALUES, DOMAIN_SIZE, NONCE, IntegerSampling::LowBits)
+        .unwrap();
+    assert_eq!(draw(IntegerSampling::LowBits), values);
+
+    for sampling in [
+        IntegerSampling::HighBits,
+        IntegerSampling::PackedBits,
+        IntegerSampling::Rejection,
+    ] {
+        assert_eq!(
+            Err(RandomCoinError::UnsupportedIntegerSampling(sampling)),
+            coin.draw_integers_with(NUM_VALUES, DOMAIN_SIZE, NONCE, sampling)
+        );
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn seed_elements() -> Vec<BaseElement> {
+    (1..=4).map(BaseElement::new).collect()
+}
+
+fn build_coin() -> DefaultRandomCoin<Blake3> {
+    DefaultRandomCoin::<Blake3>::new(&seed_elements())
+}
+
+fn draw(sampling: IntegerSampling) -> Vec<usize> {
+    build_coin()
+        .draw_integers_with(NUM_VALUES, DOMAIN_SIZE, NONCE, sampling)
+        .unwrap()
+}
+
+/// Random coin which implements only the required methods of the [RandomCoin] trait.
+struct LowBitsCoin(DefaultRandomCoin<Blake3>);
+
+impl RandomCoin for LowBitsCoin {
+    type BaseField = BaseElement;
+    type Hasher = Blake3;
+
+    fn new(seed: &[BaseElement]) -> Self {
+        Self(DefaultRandomCoin::new(seed))
+    }
+
+    fn reseed(&mut self, data: <Blake3 as Hasher>::Digest) {
+        self.0.reseed(data)
+    }
+
+    fn check_leading_zeros(&self, value: u64) -> u32 {
+        self.0.check_leading_zeros(value)
+    }
+
+    fn draw<E: math::FieldElement<BaseField = BaseElement>>(
+        &mut self,
+    ) -> Result<E, RandomCoinError> {
+        self.0.draw()
+    }
+
+    fn draw_integers(
+        &mut self,
+        num_values: usize,
+        domain_size: usize,
+        nonce: u64,
+    ) -> Result<Vec<usize>, RandomCoinError> {
+        self.0.draw_integers(num_values, domain_size, nonce)
+    }
+}
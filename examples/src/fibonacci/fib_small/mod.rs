@@ -30,6 +30,7 @@ const TRACE_WIDTH: usize = 2;
 
 type Blake3_192 = winterfell::crypto::hashers::Blake3_192<BaseElement>;
 type Blake3_256 = winterfell::crypto::hashers::Blake3_256<BaseElement>;
+type Blake3_512 = winterfell::crypto::hashers::Blake3_512<BaseElement>;
 type Sha3_256 = winterfell::crypto::hashers::Sha3_256<BaseElement>;
 type Rp64_256 = winterfell::crypto::hashers::Rp64_256;
 type RpJive64_256 = winterfell::crypto::hashers::RpJive64_256;
@@ -51,6 +52,9 @@ pub fn get_example(
         HashFunction::Blake3_256 => {
             Ok(Box::new(FibExample::<Blake3_256>::new(sequence_length, options)))
         }
+        HashFunction::Blake3_512 => {
+            Ok(Box::new(FibExample::<Blake3_512>::new(sequence_length, options)))
+        }
         HashFunction::Sha3_256 => {
             Ok(Box::new(FibExample::<Sha3_256>::new(sequence_length, options)))
         }
@@ -9,8 +9,8 @@ use super::{
 };
 use winterfell::{
     matrix::ColMatrix, AuxTraceRandElements, ConstraintCompositionCoefficients,
-    DefaultConstraintEvaluator, DefaultTraceLde, StarkDomain, Trace, TraceInfo, TracePolyTable,
-    TraceTable,
+    DefaultConstraintEvaluator, DefaultTraceLde, StarkDomain, Trace, TraceCommitmentOptions,
+    TraceInfo, TracePolyTable, TraceTable,
 };
 
 // FIBONACCI PROVER
@@ -73,8 +73,9 @@ where
         trace_info: &TraceInfo,
         main_trace: &ColMatrix<Self::BaseField>,
         domain: &StarkDomain<Self::BaseField>,
+        commitment_options: TraceCommitmentOptions,
     ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
-        DefaultTraceLde::new(trace_info, main_trace, domain)
+        DefaultTraceLde::new(trace_info, main_trace, domain, commitment_options)
     }
 
     fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
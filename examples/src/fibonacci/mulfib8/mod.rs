@@ -4,7 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::utils::compute_mulfib_term;
-use crate::{Blake3_192, Blake3_256, Example, ExampleOptions, HashFunction, Sha3_256};
+use crate::{Blake3_192, Blake3_256, Blake3_512, Example, ExampleOptions, HashFunction, Sha3_256};
 use core::marker::PhantomData;
 use log::debug;
 use std::time::Instant;
@@ -39,6 +39,9 @@ pub fn get_example(
         HashFunction::Blake3_256 => {
             Ok(Box::new(MulFib8Example::<Blake3_256>::new(sequence_length, options)))
         }
+        HashFunction::Blake3_512 => {
+            Ok(Box::new(MulFib8Example::<Blake3_512>::new(sequence_length, options)))
+        }
         HashFunction::Sha3_256 => {
             Ok(Box::new(MulFib8Example::<Sha3_256>::new(sequence_length, options)))
         }
@@ -0,0 +1,106 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{Blake3_192, Blake3_256, Blake3_512, HashFunction, Sha3_256};
+use core::hint::black_box;
+use rand_utils::rand_vector;
+use std::time::Instant;
+use winterfell::crypto::{
+    hashers::{GriffinJive64_256, Rp64_256, RpJive64_256},
+    ElementHasher,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of field elements hashed into a single leaf; this is representative of a row of a
+/// moderately wide execution trace.
+const LEAF_WIDTH: usize = 16;
+
+// HASH FUNCTION THROUGHPUT
+// ================================================================================================
+
+/// Throughput of a hash function measured on the current machine.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HashThroughput {
+    /// Hash function for which the throughput was measured.
+    pub hash_fn: HashFunction,
+    /// Number of Merkle tree leaves (each consisting of 16 field elements) hashed per second.
+    pub leaves_per_second: f64,
+    /// Number of 2-to-1 digest compressions performed per second.
+    pub merges_per_second: f64,
+}
+
+/// Measures throughput of all hash functions listed in [HashFunction] on the current machine.
+///
+/// This can be used to pick a hash function which performs best on the available hardware. Each
+/// hash function performs `num_iterations` leaf hashes and the same number of 2-to-1
+/// compressions. Leaves are hashed in the field the hash function operates over (i.e., `f64` for
+/// algebraic hash functions and `f128` for all others).
+///
+/// # Panics
+/// Panics if `num_iterations` is zero.
+pub fn measure_hash_functions(num_iterations: usize) -> Vec<HashThroughput> {
+    HashFunction::ALL
+        .iter()
+        .map(|&hash_fn| measure_hash_function(hash_fn, num_iterations))
+        .collect()
+}
+
+/// Measures throughput of the specified hash function on the current machine.
+///
+/// # Panics
+/// Panics if `num_iterations` is zero.
+pub fn measure_hash_function(hash_fn: HashFunction, num_iterations: usize) -> HashThroughput {
+    assert!(num_iterations > 0, "number of iterations must be greater than zero");
+    let (leaves_per_second, merges_per_second) = match hash_fn {
+        HashFunction::Blake3_192 => measure::<Blake3_192>(num_iterations),
+        HashFunction::Blake3_256 => measure::<Blake3_256>(num_iterations),
+        HashFunction::Blake3_512 => measure::<Blake3_512>(num_iterations),
+        HashFunction::Sha3_256 => measure::<Sha3_256>(num_iterations),
+        HashFunction::Rp64_256 => measure::<Rp64_256>(num_iterations),
+        HashFunction::RpJive64_256 => measure::<RpJive64_256>(num_iterations),
+        HashFunction::GriffinJive64_256 => measure::<GriffinJive64_256>(num_iterations),
+    };
+
+    HashThroughput {
+        hash_fn,
+        leaves_per_second,
+        merges_per_second,
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the number of leaf hashes and the number of 2-to-1 compressions performed per second
+/// by hash function `H`.
+fn measure<H: ElementHasher>(num_iterations: usize) -> (f64, f64) {
+    let leaf = rand_vector::<H::BaseField>(LEAF_WIDTH);
+
+    let now = Instant::now();
+    let mut digest = H::hash_elements(&leaf);
+    for _ in 1..num_iterations {
+        digest = H::hash_elements(black_box(&leaf));
+    }
+    let leaves_per_second = per_second(num_iterations, now);
+
+    let now = Instant::now();
+    for _ in 0..num_iterations {
+        digest = H::merge(black_box(&[digest, digest]));
+    }
+    let merges_per_second = per_second(num_iterations, now);
+    black_box(digest);
+
+    (leaves_per_second, merges_per_second)
+}
+
+/// Returns the number of operations performed per second given that `num_operations` were
+/// performed since `start`.
+fn per_second(num_operations: usize, start: Instant) -> f64 {
+    // guard against timers with coarse resolution reporting zero elapsed time
+    let elapsed = start.elapsed().as_secs_f64().max(f64::EPSILON);
+    num_operations as f64 / elapsed
+}
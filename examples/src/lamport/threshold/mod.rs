@@ -7,7 +7,7 @@ use super::{
     message_to_elements, rescue, Example, PrivateKey, Signature, CYCLE_LENGTH as HASH_CYCLE_LENGTH,
     NUM_HASH_ROUNDS,
 };
-use crate::{Blake3_192, Blake3_256, ExampleOptions, HashFunction, Sha3_256};
+use crate::{Blake3_192, Blake3_256, Blake3_512, ExampleOptions, HashFunction, Sha3_256};
 use core::marker::PhantomData;
 use log::debug;
 use std::time::Instant;
@@ -48,6 +48,9 @@ pub fn get_example(
         HashFunction::Blake3_256 => {
             Ok(Box::new(LamportThresholdExample::<Blake3_256>::new(num_signers, options)))
         }
+        HashFunction::Blake3_512 => {
+            Ok(Box::new(LamportThresholdExample::<Blake3_512>::new(num_signers, options)))
+        }
         HashFunction::Sha3_256 => {
             Ok(Box::new(LamportThresholdExample::<Sha3_256>::new(num_signers, options)))
         }
@@ -6,12 +6,17 @@
 use structopt::StructOpt;
 use winterfell::{
     crypto::hashers::{GriffinJive64_256, Rp64_256, RpJive64_256},
-    math::fields::f128::BaseElement,
-    FieldExtension, ProofOptions, StarkProof, VerifierError,
+    math::{
+        fields::{f128::BaseElement, f64},
+        StarkField,
+    },
+    FieldExtension, ProofOptions, ProofOptionsError, StarkProof, VerifierError,
 };
 
 pub mod fibonacci;
 #[cfg(feature = "std")]
+pub mod hash_bench;
+#[cfg(feature = "std")]
 pub mod lamport;
 #[cfg(feature = "std")]
 pub mod merkle;
@@ -29,6 +34,7 @@ mod tests;
 
 pub type Blake3_192 = winterfell::crypto::hashers::Blake3_192<BaseElement>;
 pub type Blake3_256 = winterfell::crypto::hashers::Blake3_256<BaseElement>;
+pub type Blake3_512 = winterfell::crypto::hashers::Blake3_512<BaseElement>;
 pub type Sha3_256 = winterfell::crypto::hashers::Sha3_256<BaseElement>;
 
 pub trait Example {
@@ -85,6 +91,7 @@ impl ExampleOptions {
         let hash_fn = match self.hash_fn.as_str() {
             "blake3_192" => HashFunction::Blake3_192,
             "blake3_256" => HashFunction::Blake3_256,
+            "blake3_512" => HashFunction::Blake3_512,
             "sha3_256" => HashFunction::Sha3_256,
             "rp64_256" => HashFunction::Rp64_256,
             "rp_jive64_256" => HashFunction::RpJive64_256,
@@ -110,6 +117,7 @@ impl ExampleOptions {
         let security_level = match self.hash_fn.as_str() {
             "blake3_192" => proof.security_level::<Blake3_192>(conjectured),
             "blake3_256" => proof.security_level::<Blake3_256>(conjectured),
+            "blake3_512" => proof.security_level::<Blake3_512>(conjectured),
             "sha3_256" => proof.security_level::<Sha3_256>(conjectured),
             "rp64_256" => proof.security_level::<Rp64_256>(conjectured),
             "rp_jive64_256" => proof.security_level::<RpJive64_256>(conjectured),
@@ -221,6 +229,11 @@ pub enum HashFunction {
     /// When this function is used in the STARK protocol, proof security cannot exceed 128 bits.
     Blake3_256,
 
+    /// BLAKE3 hash function with 512 bit output.
+    ///
+    /// When this function is used in the STARK protocol, proof security cannot exceed 256 bits.
+    Blake3_512,
+
     /// SHA3 hash function with 256 bit output.
     ///
     /// When this function is used in the STARK protocol, proof security cannot exceed 128 bits.
@@ -243,3 +256,36 @@ pub enum HashFunction {
     /// When this function is used in the STARK protocol, proof security cannot exceed 128 bits.
     GriffinJive64_256,
 }
+
+impl HashFunction {
+    /// All hash functions available for the provided examples.
+    pub const ALL: [HashFunction; 7] = [
+        Self::Blake3_192,
+        Self::Blake3_256,
+        Self::Blake3_512,
+        Self::Sha3_256,
+        Self::Rp64_256,
+        Self::RpJive64_256,
+        Self::GriffinJive64_256,
+    ];
+
+    /// Checks whether this hash function can be used in the STARK protocol instantiated over the
+    /// base field `B`.
+    ///
+    /// # Errors
+    /// Returns [ProofOptionsError::IncompatibleField] if this is an algebraic hash function
+    /// defined over the `f64` field and `B` is a different field.
+    pub fn validate_field<B: StarkField>(&self) -> Result<(), ProofOptionsError> {
+        match self {
+            Self::Blake3_192 | Self::Blake3_256 | Self::Blake3_512 | Self::Sha3_256 => Ok(()),
+            Self::Rp64_256 | Self::RpJive64_256 | Self::GriffinJive64_256 => {
+                if B::get_modulus_le_bytes() != f64::BaseElement::get_modulus_le_bytes() {
+                    return Err(ProofOptionsError::IncompatibleField {
+                        reason: format!("{self:?} hash function works only in f64 field"),
+                    });
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -9,7 +9,7 @@ use crate::utils::rescue::{
 };
 use crate::{
     utils::rescue::{Hash, Rescue128},
-    Blake3_192, Blake3_256, Example, ExampleOptions, HashFunction, Sha3_256,
+    Blake3_192, Blake3_256, Blake3_512, Example, ExampleOptions, HashFunction, Sha3_256,
 };
 use core::marker::PhantomData;
 use log::debug;
@@ -50,6 +50,9 @@ pub fn get_example(
         HashFunction::Blake3_256 => {
             Ok(Box::new(MerkleExample::<Blake3_256>::new(tree_depth, options)))
         }
+        HashFunction::Blake3_512 => {
+            Ok(Box::new(MerkleExample::<Blake3_512>::new(tree_depth, options)))
+        }
         HashFunction::Sha3_256 => Ok(Box::new(MerkleExample::<Sha3_256>::new(tree_depth, options))),
         _ => Err("The specified hash function cannot be used with this example.".to_string()),
     }
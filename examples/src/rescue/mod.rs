@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::{Blake3_192, Blake3_256, Example, ExampleOptions, HashFunction, Sha3_256};
+use crate::{Blake3_192, Blake3_256, Blake3_512, Example, ExampleOptions, HashFunction, Sha3_256};
 use core::marker::PhantomData;
 use log::debug;
 use std::time::Instant;
@@ -48,6 +48,9 @@ pub fn get_example(
         HashFunction::Blake3_256 => {
             Ok(Box::new(RescueExample::<Blake3_256>::new(chain_length, options)))
         }
+        HashFunction::Blake3_512 => {
+            Ok(Box::new(RescueExample::<Blake3_512>::new(chain_length, options)))
+        }
         HashFunction::Sha3_256 => {
             Ok(Box::new(RescueExample::<Sha3_256>::new(chain_length, options)))
         }
@@ -3,8 +3,14 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::Blake3_256;
-use winterfell::{FieldExtension, ProofOptions};
+use super::{
+    compute_hash_chain, BaseElement, Blake3_256, FieldElement, ProofOptions, PublicInputs,
+    RescueAir, RescueProver, Trace,
+};
+use winterfell::{
+    math::{fields::QuadExtension, polynom},
+    Air, EvaluationFrame, FieldExtension,
+};
 
 #[test]
 fn rescue_test_basic_proof_verification() {
@@ -24,6 +30,57 @@ fn rescue_test_basic_proof_verification_fail() {
     crate::tests::test_basic_proof_verification_fail(rescue_eg);
 }
 
+#[test]
+fn rescue_test_evaluate_transition_at() {
+    let seed = [BaseElement::from(42u8), BaseElement::from(43u8)];
+    let chain_length = 8;
+    let prover = RescueProver::<Blake3_256>::new(build_options(false));
+    let trace = prover.build_trace(seed, chain_length);
+    let pub_inputs = PublicInputs {
+        seed,
+        result: compute_hash_chain(seed, chain_length),
+    };
+    let air = RescueAir::new(trace.get_info(), pub_inputs, build_options(false));
+
+    // constraints evaluate to zeros at all valid transitions of the execution trace
+    let g = air.trace_domain_generator();
+    let mut current = vec![BaseElement::ZERO; trace.width()];
+    let mut next = vec![BaseElement::ZERO; trace.width()];
+    for step in 0..trace.length() - 1 {
+        trace.read_row_into(step, &mut current);
+        trace.read_row_into(step + 1, &mut next);
+        let frame = EvaluationFrame::from_rows(current.clone(), next.clone());
+        let x = g.exp((step as u64).into());
+        let periodic_values = air.get_periodic_column_values_at(x);
+        let evaluations = air.evaluate_transition_at(&frame, &periodic_values);
+        assert!(evaluations.iter().all(|&e| e == BaseElement::ZERO));
+    }
+
+    // at an out-of-domain point, the evaluations match the ones computed the same way as the
+    // verifier does it internally
+    type QuadElement = QuadExtension<BaseElement>;
+    let x = QuadElement::new(BaseElement::from(7u8), BaseElement::from(11u8));
+    let current = (0..trace.width()).map(|i| x + QuadElement::from(i as u32)).collect();
+    let next = (0..trace.width()).map(|i| x * QuadElement::from(i as u32 + 2)).collect();
+    let frame = EvaluationFrame::from_rows(current, next);
+
+    let expected_periodic_values = air
+        .get_periodic_column_polys()
+        .iter()
+        .map(|poly| {
+            let num_cycles = air.trace_length() / poly.len();
+            polynom::eval(poly, x.exp((num_cycles as u64).into()))
+        })
+        .collect::<Vec<_>>();
+    let mut expected = vec![QuadElement::ZERO; air.context().num_main_transition_constraints()];
+    air.evaluate_transition(&frame, &expected_periodic_values, &mut expected);
+
+    let periodic_values = air.get_periodic_column_values_at(x);
+    assert_eq!(expected_periodic_values, periodic_values);
+    assert_eq!(expected, air.evaluate_transition_at(&frame, &periodic_values));
+    assert!(expected.iter().any(|&e| e != QuadElement::ZERO));
+}
+
 fn build_options(use_extension_field: bool) -> ProofOptions {
     let extension = if use_extension_field {
         FieldExtension::Quadratic
@@ -3,8 +3,17 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::Blake3_256;
-use winterfell::{FieldExtension, ProofOptions};
+use super::{
+    rescue::STATE_WIDTH, BaseElement, Blake3_256, DefaultRandomCoin, ElementHasher, ExtensionOf,
+    FieldElement, PublicInputs, RescueRapsAir, RescueRapsExample, RescueRapsProver, CYCLE_LENGTH,
+};
+use crate::utils::are_equal;
+use winterfell::{
+    matrix::ColMatrix, AcceptableOptions, Air, AirContext, Assertion, AuxTraceRandElements,
+    CommitmentLayout, ConstraintCompositionCoefficients, DefaultConstraintEvaluator,
+    DefaultTraceLde, EvaluationFrame, FieldExtension, ProofOptions, Prover, StarkDomain,
+    TraceCommitmentOptions, TraceInfo, TracePolyTable, TransitionConstraintDegree,
+};
 
 #[test]
 fn rescue_test_basic_proof_verification() {
@@ -26,6 +35,55 @@ fn rescue_test_basic_proof_verification_fail() {
     crate::tests::test_basic_proof_verification_fail(rescue_eg);
 }
 
+#[test]
+fn rescue_test_basic_proof_verification_per_column_commitment() {
+    let options = build_options(true).with_commitment_layout(CommitmentLayout::PerColumn);
+    let rescue_eg = Box::new(super::RescueRapsExample::<Blake3_256>::new(128, options));
+    crate::tests::test_basic_proof_verification(rescue_eg);
+}
+
+#[test]
+fn rescue_test_basic_proof_verification_rows_per_leaf() {
+    for rows_per_leaf in [1, 4] {
+        let options = build_options(true).with_rows_per_leaf(rows_per_leaf);
+        let rescue_eg = Box::new(super::RescueRapsExample::<Blake3_256>::new(128, options));
+        crate::tests::test_basic_proof_verification(rescue_eg);
+    }
+}
+
+#[test]
+fn rescue_test_aux_conjugates() {
+    let example = RescueRapsExample::<Blake3_256>::new(128, build_options(true));
+    let prover = ConjugateRapsProver(RescueRapsProver::<Blake3_256>::new(example.options.clone()));
+    let trace = prover.0.build_trace(&example.seeds, &example.permuted_seeds, example.result);
+    let proof = prover.prove(trace).unwrap();
+
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let pub_inputs = PublicInputs {
+        result: example.result,
+    };
+    assert!(
+        winterfell::verify::<ConjugateRapsAir, Blake3_256, DefaultRandomCoin<Blake3_256>>(
+            proof.clone(),
+            pub_inputs,
+            &acceptable_options
+        )
+        .is_ok()
+    );
+
+    let pub_inputs = PublicInputs {
+        result: [example.result[1], example.result[0]],
+    };
+    assert!(
+        winterfell::verify::<ConjugateRapsAir, Blake3_256, DefaultRandomCoin<Blake3_256>>(
+            proof,
+            pub_inputs,
+            &acceptable_options
+        )
+        .is_err()
+    );
+}
+
 fn build_options(use_extension_field: bool) -> ProofOptions {
     let extension = if use_extension_field {
         FieldExtension::Quadratic
@@ -34,3 +92,144 @@ fn build_options(use_extension_field: bool) -> ProofOptions {
     };
     ProofOptions::new(28, 8, 0, extension, 4, 31)
 }
+
+// CONJUGATE RAPS AIR
+// ================================================================================================
+
+/// Extends [RescueRapsAir] with a constraint which enforces the permutation argument over the
+/// conjugates of the auxiliary trace columns.
+struct ConjugateRapsAir {
+    inner: RescueRapsAir,
+    context: AirContext<BaseElement>,
+}
+
+impl Air for ConjugateRapsAir {
+    type BaseField = BaseElement;
+    type PublicInputs = PublicInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
+        let main_degrees =
+            vec![TransitionConstraintDegree::with_cycles(3, vec![CYCLE_LENGTH]); 2 * STATE_WIDTH];
+        let aux_degrees = vec![
+            TransitionConstraintDegree::with_cycles(1, vec![CYCLE_LENGTH]),
+            TransitionConstraintDegree::with_cycles(1, vec![CYCLE_LENGTH]),
+            TransitionConstraintDegree::new(2),
+            TransitionConstraintDegree::new(2),
+        ];
+        let context = AirContext::new_multi_segment(
+            trace_info.clone(),
+            main_degrees,
+            aux_degrees,
+            8,
+            2,
+            options.clone(),
+        )
+        .with_aux_conjugates();
+
+        ConjugateRapsAir {
+            inner: RescueRapsAir::new(trace_info, pub_inputs, options),
+            context,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.inner.evaluate_transition(frame, periodic_values, result);
+    }
+
+    fn evaluate_aux_transition<F, E>(
+        &self,
+        main_frame: &EvaluationFrame<F>,
+        aux_frame: &EvaluationFrame<E>,
+        periodic_values: &[F],
+        aux_rand_elements: &AuxTraceRandElements<E>,
+        result: &mut [E],
+    ) where
+        F: FieldElement<BaseField = Self::BaseField>,
+        E: FieldElement<BaseField = Self::BaseField> + ExtensionOf<F>,
+    {
+        self.inner.evaluate_aux_transition(
+            main_frame,
+            aux_frame,
+            periodic_values,
+            aux_rand_elements,
+            &mut result[..3],
+        );
+
+        // the conjugate of the permutation argument column scales at each step by
+        // (conj(aux[0]) + conj(γ)) / (conj(aux[1]) + conj(γ)).
+        let current = aux_frame.current_conjugate();
+        let next = aux_frame.next_conjugate();
+        let gamma = aux_rand_elements.get_segment_elements(0)[2].frobenius();
+        result[3] = are_equal(next[2] * (current[1] + gamma), current[2] * (current[0] + gamma));
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        self.inner.get_assertions()
+    }
+
+    fn get_aux_assertions<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        aux_rand_elements: &AuxTraceRandElements<E>,
+    ) -> Vec<Assertion<E>> {
+        self.inner.get_aux_assertions(aux_rand_elements)
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
+        self.inner.get_periodic_column_values()
+    }
+}
+
+// CONJUGATE RAPS PROVER
+// ================================================================================================
+
+struct ConjugateRapsProver<H: ElementHasher>(RescueRapsProver<H>);
+
+impl<H: ElementHasher> Prover for ConjugateRapsProver<H>
+where
+    H: ElementHasher<BaseField = BaseElement>,
+{
+    type BaseField = BaseElement;
+    type Air = ConjugateRapsAir;
+    type Trace = <RescueRapsProver<H> as Prover>::Trace;
+    type HashFn = H;
+    type RandomCoin = DefaultRandomCoin<Self::HashFn>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> PublicInputs {
+        self.0.get_pub_inputs(trace)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        self.0.options()
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+        commitment_options: TraceCommitmentOptions,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain, commitment_options)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
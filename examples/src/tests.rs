@@ -3,7 +3,11 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::Example;
+use crate::{Example, HashFunction};
+use winterfell::{
+    math::fields::{f128, f64},
+    FieldExtension, ProofOptions, ProofOptionsError,
+};
 
 pub fn test_basic_proof_verification(e: Box<dyn Example>) {
     let proof = e.prove();
@@ -15,3 +19,64 @@ pub fn test_basic_proof_verification_fail(e: Box<dyn Example>) {
     let verified = e.verify_with_wrong_inputs(proof);
     assert!(verified.is_err());
 }
+
+#[test]
+fn hash_function_field_compatibility() {
+    let algebraic = [
+        HashFunction::Rp64_256,
+        HashFunction::RpJive64_256,
+        HashFunction::GriffinJive64_256,
+    ];
+    for hash_fn in algebraic {
+        assert!(hash_fn.validate_field::<f64::BaseElement>().is_ok());
+        assert!(matches!(
+            hash_fn.validate_field::<f128::BaseElement>(),
+            Err(ProofOptionsError::IncompatibleField { .. })
+        ));
+    }
+
+    let byte_oriented = [
+        HashFunction::Blake3_192,
+        HashFunction::Blake3_256,
+        HashFunction::Blake3_512,
+        HashFunction::Sha3_256,
+    ];
+    for hash_fn in byte_oriented {
+        assert!(hash_fn.validate_field::<f64::BaseElement>().is_ok());
+        assert!(hash_fn.validate_field::<f128::BaseElement>().is_ok());
+    }
+}
+
+#[test]
+fn field_extension_field_compatibility() {
+    // f128 field supports quadratic but not cubic extension
+    let result = ProofOptions::new_for_field::<f128::BaseElement>(
+        42,
+        8,
+        0,
+        FieldExtension::Quadratic,
+        4,
+        31,
+    );
+    assert!(result.is_ok());
+    let result =
+        ProofOptions::new_for_field::<f128::BaseElement>(42, 8, 0, FieldExtension::Cubic, 4, 31);
+    assert!(matches!(result, Err(ProofOptionsError::IncompatibleField { .. })));
+
+    // f64 field supports both extensions
+    let result =
+        ProofOptions::new_for_field::<f64::BaseElement>(42, 8, 0, FieldExtension::Cubic, 4, 31);
+    assert!(result.is_ok());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn hash_function_throughput() {
+    let results = crate::hash_bench::measure_hash_functions(16);
+    assert_eq!(HashFunction::ALL.len(), results.len());
+    for (hash_fn, result) in HashFunction::ALL.iter().zip(results) {
+        assert_eq!(*hash_fn, result.hash_fn);
+        assert!(result.leaves_per_second > 0.0);
+        assert!(result.merges_per_second > 0.0);
+    }
+}
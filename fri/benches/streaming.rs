@@ -0,0 +1,142 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use crypto::{hashers::Blake3_256, DefaultRandomCoin};
+use math::{fft, fields::f128::BaseElement, FieldElement};
+use rand_utils::rand_vector;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+use winter_fri::{DefaultProverChannel, FriOptions, FriProof, FriProver, StreamingFriProver};
+
+static DOMAIN_SIZES: [usize; 3] = [65536, 262144, 1048576];
+static BLOWUP_FACTOR: usize = 8;
+static NUM_QUERIES: usize = 32;
+
+type Blake3 = Blake3_256<BaseElement>;
+type Channel = DefaultProverChannel<BaseElement, Blake3, DefaultRandomCoin<Blake3>>;
+
+// PEAK MEMORY TRACKING
+// ================================================================================================
+
+/// A global allocator which keeps track of the peak number of allocated bytes.
+struct PeakAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(allocated, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: PeakAllocator = PeakAllocator;
+
+/// Returns the peak number of bytes allocated while executing `f` in excess of the number of
+/// bytes allocated before `f` was invoked.
+fn measure_peak_memory<F: FnOnce()>(f: F) -> usize {
+    let allocated = ALLOCATED.load(Ordering::SeqCst);
+    PEAK.store(allocated, Ordering::SeqCst);
+    f();
+    PEAK.load(Ordering::SeqCst) - allocated
+}
+
+// BENCHMARKS
+// ================================================================================================
+
+pub fn prove(c: &mut Criterion) {
+    let mut fri_group = c.benchmark_group("FRI streaming prover");
+    fri_group.sample_size(10);
+    fri_group.measurement_time(Duration::from_secs(10));
+
+    let options = FriOptions::new(BLOWUP_FACTOR, 4, 255);
+
+    for &domain_size in &DOMAIN_SIZES {
+        let evaluations = build_evaluations(domain_size);
+
+        // report peak memory used by each prover to generate a proof from the same evaluations;
+        // the evaluations passed to the provers are included in the measurements
+        let in_memory = measure_peak_memory(|| {
+            prove_in_memory(&options, evaluations.clone());
+        });
+        let streaming = measure_peak_memory(|| {
+            prove_streaming(&options, evaluations.clone());
+        });
+        println!(
+            "domain size {domain_size}: peak memory {} KB in memory, {} KB streaming",
+            in_memory / 1024,
+            streaming / 1024
+        );
+
+        fri_group.bench_with_input(
+            BenchmarkId::new("in_memory", domain_size),
+            &evaluations,
+            |b, e| {
+                b.iter_batched(
+                    || e.clone(),
+                    |evaluations| prove_in_memory(&options, evaluations),
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+
+        fri_group.bench_with_input(
+            BenchmarkId::new("streaming", domain_size),
+            &evaluations,
+            |b, e| {
+                b.iter_batched(
+                    || e.clone(),
+                    |evaluations| prove_streaming(&options, evaluations),
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+    }
+}
+
+criterion_group!(fri_streaming_group, prove);
+criterion_main!(fri_streaming_group);
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn prove_in_memory(options: &FriOptions, evaluations: Vec<BaseElement>) -> FriProof {
+    let mut channel = Channel::new(evaluations.len(), NUM_QUERIES);
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations);
+    let positions = channel.draw_query_positions(0);
+    prover.build_proof(&positions)
+}
+
+fn prove_streaming(options: &FriOptions, evaluations: Vec<BaseElement>) -> FriProof {
+    let mut channel = Channel::new(evaluations.len(), NUM_QUERIES);
+    let mut prover = StreamingFriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations);
+    let positions = channel.draw_query_positions(0);
+    prover.build_proof(&positions)
+}
+
+fn build_evaluations(domain_size: usize) -> Vec<BaseElement> {
+    let mut p: Vec<BaseElement> = rand_vector(domain_size / BLOWUP_FACTOR);
+    p.resize(domain_size, BaseElement::ZERO);
+    let twiddles = fft::get_twiddles::<BaseElement>(domain_size);
+    fft::evaluate_poly(&mut p, &twiddles);
+    p
+}
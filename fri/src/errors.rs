@@ -4,6 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use core::fmt;
+use utils::string::String;
 
 use crypto::RandomCoinError;
 
@@ -15,11 +16,17 @@ use crypto::RandomCoinError;
 pub enum VerifierError {
     /// Attempt to draw a random value from a public coin failed.
     RandomCoinError(RandomCoinError),
+    /// FRI proof could not be parsed into layer queries, Merkle paths, and remainder, or the
+    /// number of layers in the proof is inconsistent with the protocol parameters.
+    ProofDeserializationError(String),
     /// Folding factor specified for the protocol is not supported. Currently, supported folding
     /// factors are: 4, 8, and 16.
     UnsupportedFoldingFactor(usize),
     /// Number of query positions does not match the number of provided evaluations.
     NumPositionEvaluationMismatch(usize, usize),
+    /// Number of polynomials in a batch does not match the number of polynomials the verifier
+    /// was instantiated for.
+    NumBatchedPolynomialsMismatch(usize, usize),
     /// Evaluations at queried positions did not match layer commitment made by the prover.
     LayerCommitmentMismatch,
     /// Degree-respecting projection was not performed correctly at one of the layers.
@@ -34,6 +41,10 @@ pub enum VerifierError {
     RemainderDegreeMismatch(usize),
     /// Polynomial degree at one of the FRI layers could not be divided evenly by the folding factor.
     DegreeTruncation(usize, usize, usize),
+    /// Number of α values provided to the verifier does not match the number of FRI layers.
+    NumLayerAlphasMismatch(usize, usize),
+    /// Sampled positions are not a subsequence of the query positions.
+    InvalidSampledPositions,
 }
 
 impl fmt::Display for VerifierError {
@@ -43,12 +54,18 @@ impl fmt::Display for VerifierError {
             Self::RandomCoinError(err) => {
                 write!(f, "failed to draw a random value from the public coin: {err}")
             }
+            Self::ProofDeserializationError(msg) => {
+                write!(f, "FRI proof deserialization failed: {msg}")
+            }
             Self::UnsupportedFoldingFactor(value) => {
                 write!(f, "folding factor {value} is not currently supported")
             }
             Self::NumPositionEvaluationMismatch(num_positions, num_evaluations) => write!(f,
                 "the number of query positions must be the same as the number of polynomial evaluations, but {num_positions} and {num_evaluations} were provided"
             ),
+            Self::NumBatchedPolynomialsMismatch(expected, actual) => write!(f,
+                "expected evaluations of {expected} batched polynomials, but evaluations of {actual} polynomials were provided"
+            ),
             Self::LayerCommitmentMismatch => {
                 write!(f, "FRI queries did not match layer commitment made by the prover")
             }
@@ -70,6 +87,12 @@ impl fmt::Display for VerifierError {
             Self::DegreeTruncation(degree, folding, layer) => {
                 write!(f, "degree reduction from {degree} by {folding} at layer {layer} results in degree truncation")
             }
+            Self::NumLayerAlphasMismatch(expected, actual) => {
+                write!(f, "expected {expected} FRI layer alphas, but {actual} were provided")
+            }
+            Self::InvalidSampledPositions => {
+                write!(f, "sampled positions must be a subsequence of the query positions")
+            }
         }
     }
 }
@@ -87,16 +87,32 @@ where
     B: StarkField,
     E: FieldElement<BaseField = B>,
 {
-    // build offset inverses and twiddles used during polynomial interpolation
     let inv_offsets = get_inv_offsets(values.len(), domain_offset, N);
+    apply_drp_with_offsets(values, &inv_offsets, alpha)
+}
+
+/// Applies degree-respecting projection to evaluations of a polynomial using the specified
+/// inverses of domain offsets for each row of `values`.
+///
+/// This is the same as [apply_drp()], but allows the projection to be applied to a contiguous
+/// chunk of rows: in such a case, `inv_offsets` must be the corresponding chunk of offsets
+/// computed for the entire domain.
+pub(crate) fn apply_drp_with_offsets<B, E, const N: usize>(
+    values: &[[E; N]],
+    inv_offsets: &[B],
+    alpha: E,
+) -> Vec<E>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+{
+    // build twiddles used during polynomial interpolation
     let inv_twiddles = get_inv_twiddles::<B>(N);
     let len_offset = E::inv((N as u64).into());
 
     let mut result = unsafe { uninit_vector(values.len()) };
-    iter_mut!(result)
-        .zip(values)
-        .zip(inv_offsets)
-        .for_each(|((result, values), domain_offset)| {
+    iter_mut!(result).zip(values).zip(inv_offsets).for_each(
+        |((result, values), &domain_offset)| {
             // interpolate the values into a polynomial; this is similar to interpolation with
             // offset implemented in math::fft module
             let mut poly = *values;
@@ -111,7 +127,8 @@ where
 
             // evaluate the polynomial at alpha, and save the result
             *result = polynom::eval(&poly, alpha)
-        });
+        },
+    );
 
     result
 }
@@ -177,7 +194,11 @@ pub fn fold_positions(
 // HELPER FUNCTIONS
 // ================================================================================================
 
-fn get_inv_offsets<B>(domain_size: usize, domain_offset: B, folding_factor: usize) -> Vec<B>
+pub(crate) fn get_inv_offsets<B>(
+    domain_size: usize,
+    domain_offset: B,
+    folding_factor: usize,
+) -> Vec<B>
 where
     B: StarkField,
 {
@@ -44,6 +44,19 @@
 //!    are provided to the [verify()](FriVerifier::verify()) function directly. The values at
 //!    remaining layers, the verifier reads from the specified verifier channel.
 //!
+//! When a FRI proof needs to be verified outside of the STARK protocol, [verify()] function can
+//! be used to execute all of the above steps at once using the default verifier channel.
+//!
+//! # Batched low-degree testing
+//! A single FRI proof can attest to a batch of polynomials evaluated over the same domain. In
+//! this case, the commit phase is executed via
+//! [build_layers_batched()](prover::FriProver::build_layers_batched()) function, which draws a
+//! random coefficient for each polynomial from the [ProverChannel] and builds FRI layers for a
+//! linear combination of the polynomials. On the verifier side, [FriVerifier] should be
+//! instantiated via [new_batched()](FriVerifier::new_batched()) function, and the query phase
+//! should be executed via [verify_batched()](FriVerifier::verify_batched()) function, which
+//! accepts evaluations of every polynomial in the batch at the queried positions.
+//!
 //! # Protocol parameters
 //! The current implementation supports executing FRI protocol with dynamically configurable
 //! parameters including:
@@ -70,13 +83,13 @@ extern crate alloc;
 pub mod folding;
 
 mod prover;
-pub use prover::{DefaultProverChannel, FriProver, ProverChannel};
+pub use prover::{DefaultProverChannel, FriProver, ProverChannel, StreamingFriProver};
 
 mod verifier;
-pub use verifier::{DefaultVerifierChannel, FriVerifier, VerifierChannel};
+pub use verifier::{verify, DefaultVerifierChannel, FriVerifier, VerifierChannel};
 
 mod options;
-pub use options::FriOptions;
+pub use options::{num_fri_layers, FriOptions};
 
 mod proof;
 pub use proof::FriProof;
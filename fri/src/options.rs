@@ -14,6 +14,7 @@ pub struct FriOptions {
     folding_factor: usize,
     remainder_max_degree: usize,
     blowup_factor: usize,
+    sorted_queries: bool,
 }
 
 impl FriOptions {
@@ -40,9 +41,21 @@ impl FriOptions {
             folding_factor,
             remainder_max_degree,
             blowup_factor,
+            sorted_queries: false,
         }
     }
 
+    /// Returns a new [FriOptions] struct with the queried values of each FRI layer stored sorted
+    /// by their positions in the layer evaluation domain.
+    ///
+    /// By default, queried values are stored in the order in which their positions are derived
+    /// from the query positions of the previous layer (see
+    /// [fold_positions()](crate::folding::fold_positions)).
+    pub fn with_sorted_queries(mut self) -> Self {
+        self.sorted_queries = true;
+        self
+    }
+
     /// Returns the offset by which the evaluation domain is shifted.
     ///
     /// The domain is shifted by multiplying every element in the domain by this offset.
@@ -78,17 +91,47 @@ impl FriOptions {
         self.blowup_factor
     }
 
+    /// Returns true if queried values of each FRI layer are stored sorted by their positions in
+    /// the layer evaluation domain.
+    pub fn sorted_queries(&self) -> bool {
+        self.sorted_queries
+    }
+
     /// Computes and return the number of FRI layers required for a domain of the specified size.
     ///
     /// The number of layers for a given domain size is defined by the `folding_factor` and
     /// `remainder_max_degree` and `blowup_factor` settings.
-    pub fn num_fri_layers(&self, mut domain_size: usize) -> usize {
-        let mut result = 0;
-        let max_remainder_size = (self.remainder_max_degree + 1) * self.blowup_factor;
-        while domain_size > max_remainder_size {
-            domain_size /= self.folding_factor;
-            result += 1;
-        }
-        result
+    pub fn num_fri_layers(&self, domain_size: usize) -> usize {
+        num_fri_layers(
+            domain_size,
+            self.folding_factor,
+            self.remainder_max_degree,
+            self.blowup_factor,
+        )
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the number of FRI layers in a proof for an evaluation domain of the specified size.
+///
+/// The domain is folded by `folding_factor` with every layer until it contains no more than
+/// `(remainder_max_degree + 1) * blowup_factor` elements; the last (remainder) layer is not
+/// included in the count. This is the number of layers built by
+/// [FriProver::build_layers()](crate::FriProver::build_layers()) for the same parameters, and
+/// thus, the number of layers in the resulting [FriProof](crate::FriProof).
+pub fn num_fri_layers(
+    mut domain_size: usize,
+    folding_factor: usize,
+    remainder_max_degree: usize,
+    blowup_factor: usize,
+) -> usize {
+    let mut result = 0;
+    let max_remainder_size = (remainder_max_degree + 1) * blowup_factor;
+    while domain_size > max_remainder_size {
+        domain_size /= folding_factor;
+        result += 1;
     }
+    result
 }
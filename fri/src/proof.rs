@@ -67,6 +67,46 @@ impl FriProof {
         }
     }
 
+    /// Returns a FRI proof assembled from raw bytes of layer query values, layer Merkle
+    /// authentication paths, and remainder polynomial coefficients.
+    ///
+    /// This is intended for alternative encodings of a proof; the bytes are not interpreted until
+    /// the proof is parsed.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * Any of the layers does not contain query value bytes.
+    /// * `num_partitions` is zero or is not a power of two.
+    pub fn from_raw_parts(
+        layers: Vec<(Vec<u8>, Vec<u8>)>,
+        remainder: Vec<u8>,
+        num_partitions: usize,
+    ) -> Result<Self, DeserializationError> {
+        if !num_partitions.is_power_of_two() {
+            return Err(DeserializationError::InvalidValue(format!(
+                "number of partitions must be a power of two, but was {num_partitions}"
+            )));
+        }
+        let layers = layers
+            .into_iter()
+            .map(|(values, paths)| {
+                if values.is_empty() {
+                    return Err(DeserializationError::InvalidValue(
+                        "a FRI proof layer must contain at least one queried evaluation"
+                            .to_string(),
+                    ));
+                }
+                Ok(FriProofLayer { values, paths })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(FriProof {
+            layers,
+            remainder,
+            num_partitions: num_partitions.trailing_zeros() as u8,
+        })
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -88,6 +128,20 @@ impl FriProof {
         2usize.pow(self.num_partitions as u32)
     }
 
+    /// Returns raw bytes of query values and Merkle authentication paths for each layer of this
+    /// proof.
+    pub fn layer_bytes(&self) -> Vec<(&[u8], &[u8])> {
+        self.layers
+            .iter()
+            .map(|layer| (layer.values.as_slice(), layer.paths.as_slice()))
+            .collect()
+    }
+
+    /// Returns raw bytes of the remainder polynomial coefficients.
+    pub fn remainder_bytes(&self) -> &[u8] {
+        &self.remainder
+    }
+
     /// Returns the size of this proof in bytes.
     pub fn size(&self) -> usize {
         // +1 for number of layers, +1 for remainder length, +1 for number of partitions
@@ -6,7 +6,7 @@
 use crate::{
     folding::{apply_drp, fold_positions},
     proof::{FriProof, FriProofLayer},
-    utils::hash_values,
+    utils::{combine_batch, hash_values},
     FriOptions,
 };
 use core::marker::PhantomData;
@@ -17,6 +17,9 @@ use utils::{collections::Vec, flatten_vector_elements, group_slice_elements, tra
 mod channel;
 pub use channel::{DefaultProverChannel, ProverChannel};
 
+mod streaming;
+pub use streaming::StreamingFriProver;
+
 #[cfg(test)]
 mod tests;
 
@@ -190,6 +193,39 @@ where
         self.set_remainder(channel, &mut evaluations);
     }
 
+    /// Executes the commit phase of the FRI protocol for a batch of functions evaluated over the
+    /// same domain.
+    ///
+    /// Before the commit phase starts, the prover draws a random coefficient for each of the
+    /// functions from the channel, and then executes the commit phase (as described for
+    /// [build_layers()](FriProver::build_layers())) against a linear combination of
+    /// `evaluations` computed using these coefficients. Thus, the resulting proof attests that
+    /// every function in the batch is a polynomial of bounded degree, and the proof is about as
+    /// large as a proof for a single function. The verifier draws the same coefficients when
+    /// instantiated via [FriVerifier::new_batched()](crate::FriVerifier::new_batched()).
+    ///
+    /// The functions must be bound to the channel (e.g., by writing commitments to their
+    /// evaluations into the channel) before this method is called; otherwise, the prover could
+    /// choose the functions after the coefficients have been drawn.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `evaluations` is empty.
+    /// * The functions are evaluated over domains of different sizes.
+    /// * The prover state is dirty (the vector of layers is not empty).
+    pub fn build_layers_batched(&mut self, channel: &mut C, evaluations: Vec<Vec<E>>) {
+        assert!(!evaluations.is_empty(), "at least one function must be provided");
+        let domain_size = evaluations[0].len();
+        assert!(
+            evaluations.iter().all(|values| values.len() == domain_size),
+            "all functions must be evaluated over the same domain"
+        );
+
+        let coefficients =
+            (0..evaluations.len()).map(|_| channel.draw_fri_alpha()).collect::<Vec<_>>();
+        self.build_layers(channel, combine_batch(&evaluations, &coefficients));
+    }
+
     /// Builds a single FRI layer by first committing to the `evaluations`, then drawing a random
     /// alpha from the channel and use it to perform degree-respecting projection.
     fn build_layer<const N: usize>(&mut self, channel: &mut C, evaluations: &mut Vec<E>) {
@@ -216,10 +252,7 @@ where
 
     /// Creates remainder polynomial in coefficient form from a vector of `evaluations` over a domain.
     fn set_remainder(&mut self, channel: &mut C, evaluations: &mut [E]) {
-        let inv_twiddles = fft::get_inv_twiddles(evaluations.len());
-        fft::interpolate_poly_with_offset(evaluations, &inv_twiddles, self.options.domain_offset());
-        let remainder_poly_size = evaluations.len() / self.options.blowup_factor();
-        let remainder_poly = evaluations[..remainder_poly_size].to_vec();
+        let remainder_poly = build_remainder_poly(evaluations, &self.options);
         let commitment = <H as ElementHasher>::hash_elements(&remainder_poly);
         channel.commit_fri_layer(commitment);
         self.remainder_poly = FriRemainder(remainder_poly);
@@ -250,6 +283,9 @@ where
             // positions, and query the layer at these positions.
             for i in 0..self.layers.len() {
                 positions = fold_positions(&positions, domain_size, folding_factor);
+                if self.options.sorted_queries() {
+                    positions.sort_unstable();
+                }
 
                 // sort of a static dispatch for folding_factor parameter
                 let proof_layer = match folding_factor {
@@ -278,6 +314,19 @@ where
 // HELPER FUNCTIONS
 // ================================================================================================
 
+/// Interpolates evaluations of the remainder layer into a polynomial, and returns coefficients
+/// of the remainder polynomial; `evaluations` are overwritten in the process.
+fn build_remainder_poly<B, E>(evaluations: &mut [E], options: &FriOptions) -> Vec<E>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+{
+    let inv_twiddles = fft::get_inv_twiddles(evaluations.len());
+    fft::interpolate_poly_with_offset(evaluations, &inv_twiddles, options.domain_offset());
+    let remainder_poly_size = evaluations.len() / options.blowup_factor();
+    evaluations[..remainder_poly_size].to_vec()
+}
+
 /// Builds a single proof layer by querying the evaluations of the passed in FRI layer at the
 /// specified positions.
 fn query_layer<B: StarkField, E: FieldElement<BaseField = B>, H: Hasher, const N: usize>(
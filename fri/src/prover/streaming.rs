@@ -0,0 +1,302 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{build_remainder_poly, FriRemainder, ProverChannel};
+use crate::{
+    folding::{apply_drp_with_offsets, fold_positions, get_inv_offsets},
+    proof::{FriProof, FriProofLayer},
+    utils::hash_values,
+    FriOptions,
+};
+use core::marker::PhantomData;
+use crypto::{ElementHasher, MerkleTree};
+use math::{FieldElement, StarkField};
+use utils::collections::Vec;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of rows of a FRI layer (i.e., leaves of the layer Merkle tree) which are read from the
+/// layer evaluations at a time.
+const CHUNK_SIZE: usize = 1024;
+
+// STREAMING FRI PROVER
+// ================================================================================================
+
+/// Implements the prover component of the FRI protocol with bounded memory usage.
+///
+/// This prover generates exactly the same proofs as [FriProver](super::FriProver), and is
+/// parametrized by the same types. However, instead of keeping all FRI layers (evaluations and
+/// Merkle trees) in memory between the commit and the query phases, this prover retains only the
+/// evaluations of the first layer and the α drawn for every layer:
+///
+/// * During the commit phase, executed via
+///   [build_layers()](StreamingFriProver::build_layers()), the Merkle tree and the evaluations of
+///   every layer are discarded as soon as the layer is committed to and folded into the next
+///   layer.
+/// * During the query phase, executed via [build_proof()](StreamingFriProver::build_proof()),
+///   the layers are recomputed one at a time from the evaluations of the first layer, and each
+///   layer is discarded as soon as it is queried.
+///
+/// Moreover, layer evaluations are never transposed in their entirety: leaves of layer Merkle
+/// trees and folded evaluations are computed from chunks of rows read directly from the layer
+/// evaluations. Thus, apart from the evaluations of the first layer, at most the evaluations of
+/// two adjacent layers and a single Merkle tree are held in memory at any time.
+///
+/// This trades recomputation for memory: the query phase rebuilds all layers, and thus, takes
+/// roughly as much time as the commit phase.
+pub struct StreamingFriProver<B, E, C, H>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+    C: ProverChannel<E, Hasher = H>,
+    H: ElementHasher<BaseField = B>,
+{
+    options: FriOptions,
+    evaluations: Vec<E>,
+    alphas: Vec<E>,
+    remainder_poly: FriRemainder<E>,
+    _channel: PhantomData<C>,
+}
+
+impl<B, E, C, H> StreamingFriProver<B, E, C, H>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+    C: ProverChannel<E, Hasher = H>,
+    H: ElementHasher<BaseField = B>,
+{
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new streaming FRI prover instantiated with the provided `options`.
+    pub fn new(options: FriOptions) -> Self {
+        StreamingFriProver {
+            options,
+            evaluations: Vec::new(),
+            alphas: Vec::new(),
+            remainder_poly: FriRemainder(vec![]),
+            _channel: PhantomData,
+        }
+    }
+
+    // ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns folding factor for this prover.
+    pub fn folding_factor(&self) -> usize {
+        self.options.folding_factor()
+    }
+
+    /// Returns offset of the domain over which FRI protocol is executed by this prover.
+    pub fn domain_offset(&self) -> B {
+        self.options.domain_offset()
+    }
+
+    /// Returns number of FRI layers computed during the last execution of the
+    /// [build_layers()](StreamingFriProver::build_layers()) method.
+    pub fn num_layers(&self) -> usize {
+        self.alphas.len()
+    }
+
+    /// Clears the internally stored evaluations of the first layer and the drawn α values.
+    pub fn reset(&mut self) {
+        self.evaluations.clear();
+        self.alphas.clear();
+        self.remainder_poly.0.clear();
+    }
+
+    // COMMIT PHASE
+    // --------------------------------------------------------------------------------------------
+    /// Executes the commit phase of the FRI protocol.
+    ///
+    /// The same commitments are written into the channel and the same α values are drawn from
+    /// it as in [FriProver::build_layers()](super::FriProver::build_layers()); however, only the
+    /// `evaluations` and the α values are retained by the prover.
+    ///
+    /// # Panics
+    /// Panics if the prover state is dirty (the layers of a prior proof have not been queried).
+    pub fn build_layers(&mut self, channel: &mut C, evaluations: Vec<E>) {
+        assert!(
+            self.remainder_poly.0.is_empty(),
+            "a prior proof generation request has not been completed yet"
+        );
+
+        let num_layers = self.options.num_fri_layers(evaluations.len());
+        self.evaluations = evaluations;
+
+        // commit to each layer and fold it into the next one; evaluations of all layers but the
+        // first one are discarded as soon as the next layer is built
+        let mut layer: Option<Vec<E>> = None;
+        for _ in 0..num_layers {
+            let evaluations = layer.as_deref().unwrap_or(&self.evaluations);
+            let root = match self.folding_factor() {
+                2 => *build_layer_tree::<E, H, 2>(evaluations).root(),
+                4 => *build_layer_tree::<E, H, 4>(evaluations).root(),
+                8 => *build_layer_tree::<E, H, 8>(evaluations).root(),
+                16 => *build_layer_tree::<E, H, 16>(evaluations).root(),
+                _ => unimplemented!("folding factor {} is not supported", self.folding_factor()),
+            };
+            channel.commit_fri_layer(root);
+
+            let alpha = channel.draw_fri_alpha();
+            let folded =
+                fold_layer(evaluations, self.folding_factor(), self.domain_offset(), alpha);
+            self.alphas.push(alpha);
+            layer = Some(folded);
+        }
+
+        let mut evaluations = layer.unwrap_or_else(|| self.evaluations.clone());
+        let remainder_poly = build_remainder_poly(&mut evaluations, &self.options);
+        let commitment = <H as ElementHasher>::hash_elements(&remainder_poly);
+        channel.commit_fri_layer(commitment);
+        self.remainder_poly = FriRemainder(remainder_poly);
+    }
+
+    // QUERY PHASE
+    // --------------------------------------------------------------------------------------------
+    /// Executes query phase of FRI protocol.
+    ///
+    /// The layers are recomputed one at a time from the evaluations of the first layer and the
+    /// α values drawn during the commit phase. The resulting proof is the same as the one built
+    /// by [FriProver::build_proof()](super::FriProver::build_proof()).
+    ///
+    /// # Panics
+    /// Panics is the prover state is clean (no FRI layers have been build yet).
+    pub fn build_proof(&mut self, positions: &[usize]) -> FriProof {
+        assert!(!self.remainder_poly.0.is_empty(), "FRI layers have not been built yet");
+
+        let folding_factor = self.folding_factor();
+        let mut layers = Vec::with_capacity(self.alphas.len());
+        let mut positions = positions.to_vec();
+        let mut layer: Option<Vec<E>> = None;
+        for (i, &alpha) in self.alphas.iter().enumerate() {
+            let evaluations = layer.as_deref().unwrap_or(&self.evaluations);
+            positions = fold_positions(&positions, evaluations.len(), folding_factor);
+            if self.options.sorted_queries() {
+                positions.sort_unstable();
+            }
+
+            // sort of a static dispatch for folding_factor parameter
+            let proof_layer = match folding_factor {
+                2 => query_layer::<E, H, 2>(evaluations, &positions),
+                4 => query_layer::<E, H, 4>(evaluations, &positions),
+                8 => query_layer::<E, H, 8>(evaluations, &positions),
+                16 => query_layer::<E, H, 16>(evaluations, &positions),
+                _ => unimplemented!("folding factor {} is not supported", folding_factor),
+            };
+            layers.push(proof_layer);
+
+            // the last layer is folded into the remainder, which is already known
+            if i + 1 < self.alphas.len() {
+                layer = Some(fold_layer(evaluations, folding_factor, self.domain_offset(), alpha));
+            }
+        }
+
+        // use the remaining polynomial values directly as proof
+        let remainder = self.remainder_poly.0.clone();
+
+        // clear the state so that another proof can be generated
+        self.reset();
+
+        FriProof::new(layers, remainder, 1)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Builds a Merkle tree from the specified layer evaluations such that the leaf at position `i`
+/// commits to the `N` evaluations in the i-th row of the transposed evaluations.
+fn build_layer_tree<E, H, const N: usize>(evaluations: &[E]) -> MerkleTree<H>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    let num_rows = evaluations.len() / N;
+    let mut leaves = Vec::with_capacity(num_rows);
+    for start in (0..num_rows).step_by(CHUNK_SIZE) {
+        let end = num_rows.min(start + CHUNK_SIZE);
+        leaves.extend(hash_values::<H, E, N>(&read_rows::<E, N>(evaluations, start, end)));
+    }
+    MerkleTree::new(leaves).expect("failed to construct FRI layer tree")
+}
+
+/// Applies degree-respecting projection to the specified layer evaluations using the folding
+/// factor dispatched to a constant.
+fn fold_layer<B, E>(evaluations: &[E], folding_factor: usize, domain_offset: B, alpha: E) -> Vec<E>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+{
+    match folding_factor {
+        2 => fold_layer_chunks::<B, E, 2>(evaluations, domain_offset, alpha),
+        4 => fold_layer_chunks::<B, E, 4>(evaluations, domain_offset, alpha),
+        8 => fold_layer_chunks::<B, E, 8>(evaluations, domain_offset, alpha),
+        16 => fold_layer_chunks::<B, E, 16>(evaluations, domain_offset, alpha),
+        _ => unimplemented!("folding factor {} is not supported", folding_factor),
+    }
+}
+
+/// Applies degree-respecting projection to the specified layer evaluations one chunk of rows at
+/// a time; the result is the same as applying the projection to all transposed evaluations.
+fn fold_layer_chunks<B, E, const N: usize>(evaluations: &[E], domain_offset: B, alpha: E) -> Vec<E>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+{
+    let num_rows = evaluations.len() / N;
+    let inv_offsets = get_inv_offsets(num_rows, domain_offset, N);
+    let mut result = Vec::with_capacity(num_rows);
+    for start in (0..num_rows).step_by(CHUNK_SIZE) {
+        let end = num_rows.min(start + CHUNK_SIZE);
+        let rows = read_rows::<E, N>(evaluations, start, end);
+        result.extend(apply_drp_with_offsets(&rows, &inv_offsets[start..end], alpha));
+    }
+    result
+}
+
+/// Builds a single proof layer by querying the specified layer evaluations at the specified
+/// positions.
+fn query_layer<E, H, const N: usize>(evaluations: &[E], positions: &[usize]) -> FriProofLayer
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    let proof = build_layer_tree::<E, H, N>(evaluations)
+        .prove_batch(positions)
+        .expect("failed to generate a Merkle proof for FRI layer queries");
+
+    let num_rows = evaluations.len() / N;
+    let queried_values = positions
+        .iter()
+        .map(|&position| read_row::<E, N>(evaluations, num_rows, position))
+        .collect::<Vec<_>>();
+
+    FriProofLayer::new(queried_values, proof)
+}
+
+/// Returns rows `start..end` of the transposed layer evaluations.
+fn read_rows<E: FieldElement, const N: usize>(
+    evaluations: &[E],
+    start: usize,
+    end: usize,
+) -> Vec<[E; N]> {
+    let num_rows = evaluations.len() / N;
+    (start..end).map(|row| read_row::<E, N>(evaluations, num_rows, row)).collect()
+}
+
+/// Returns the specified row of the transposed layer evaluations; that is, the `N` evaluations
+/// located `num_rows` apart starting at position `row`.
+fn read_row<E: FieldElement, const N: usize>(
+    evaluations: &[E],
+    num_rows: usize,
+    row: usize,
+) -> [E; N] {
+    let mut result = [E::ZERO; N];
+    for (i, value) in result.iter_mut().enumerate() {
+        *value = evaluations[row + i * num_rows];
+    }
+    result
+}
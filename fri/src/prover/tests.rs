@@ -3,8 +3,9 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{DefaultProverChannel, FriProver};
+use super::{DefaultProverChannel, FriProver, StreamingFriProver};
 use crate::{
+    num_fri_layers,
     verifier::{DefaultVerifierChannel, FriVerifier},
     FriOptions, FriProof, VerifierError,
 };
@@ -35,6 +36,476 @@ fn fri_folding_4() {
     fri_prove_verify(trace_length_e, lde_blowup_e, folding_factor_e, max_remainder_degree)
 }
 
+#[test]
+fn fri_verify_isolated() {
+    let trace_length = 1 << 10;
+    let lde_blowup = 1 << 3;
+    let options = FriOptions::new(lde_blowup, 4, 31);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, lde_blowup);
+
+    // generate the proof
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone());
+    let positions = channel.draw_query_positions(0);
+    let proof = prover.build_proof(&positions);
+    let commitments = channel.layer_commitments().to_vec();
+    let queried_evaluations = positions.iter().map(|&p| evaluations[p]).collect::<Vec<_>>();
+
+    // the proof should be accepted
+    let result = crate::verify(
+        proof.clone(),
+        commitments.clone(),
+        &queried_evaluations,
+        &positions,
+        trace_length - 1,
+        options.clone(),
+        &mut DefaultRandomCoin::<Blake3>::new(&[]),
+    );
+    assert!(result.is_ok(), "{:}", result.err().unwrap());
+
+    // the proof should be rejected when query values of a layer are corrupted
+    let mut layers = proof
+        .layer_bytes()
+        .into_iter()
+        .map(|(values, paths)| (values.to_vec(), paths.to_vec()))
+        .collect::<Vec<_>>();
+    layers[1].0[0] ^= 1;
+    let corrupted =
+        FriProof::from_raw_parts(layers, proof.remainder_bytes().to_vec(), proof.num_partitions())
+            .unwrap();
+    let result = crate::verify(
+        corrupted,
+        commitments.clone(),
+        &queried_evaluations,
+        &positions,
+        trace_length - 1,
+        options.clone(),
+        &mut DefaultRandomCoin::<Blake3>::new(&[]),
+    );
+    assert_eq!(Err(VerifierError::LayerCommitmentMismatch), result);
+
+    // the proof should be rejected when a layer commitment is missing
+    let result = crate::verify(
+        proof,
+        commitments[1..].to_vec(),
+        &queried_evaluations,
+        &positions,
+        trace_length - 1,
+        options,
+        &mut DefaultRandomCoin::<Blake3>::new(&[]),
+    );
+    assert!(matches!(result, Err(VerifierError::ProofDeserializationError(_))));
+}
+
+#[test]
+fn fri_verify_power_of_two_degree() {
+    // a polynomial of degree 32 is evaluated over a domain of size 64 * blowup
+    let max_degree = 32;
+    let lde_blowup = 8;
+    let domain_size = 64 * lde_blowup;
+    let options = FriOptions::new(lde_blowup, 4, 255);
+    let prove = |degree: usize| {
+        let mut channel = build_prover_channel(domain_size / lde_blowup, &options);
+        let mut evaluations = (0..=degree as u128).map(BaseElement::new).collect::<Vec<_>>();
+        evaluations.resize(domain_size, BaseElement::ZERO);
+        fft::evaluate_poly(&mut evaluations, &fft::get_twiddles::<BaseElement>(domain_size));
+
+        let mut prover = FriProver::new(options.clone());
+        prover.build_layers(&mut channel, evaluations.clone());
+        let positions = channel.draw_query_positions(0);
+        let proof = prover.build_proof(&positions);
+        let queried_evaluations = positions.iter().map(|&p| evaluations[p]).collect::<Vec<_>>();
+        crate::verify(
+            proof,
+            channel.layer_commitments().to_vec(),
+            &queried_evaluations,
+            &positions,
+            max_degree,
+            options.clone(),
+            &mut DefaultRandomCoin::<Blake3>::new(&[]),
+        )
+    };
+
+    // the verifier infers the same domain as the one used by the prover
+    assert_eq!(Ok(()), prove(max_degree));
+
+    // a polynomial of a higher degree is rejected even though it fits into the same domain
+    assert_eq!(Err(VerifierError::RemainderDegreeMismatch(max_degree)), prove(max_degree + 1));
+}
+
+#[test]
+fn fri_verify_sampled() {
+    let trace_length = 1 << 10;
+    let lde_blowup = 1 << 3;
+    let domain_size = trace_length * lde_blowup;
+    let options = FriOptions::new(lde_blowup, 4, 31);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, lde_blowup);
+
+    // generate the proof
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone());
+    let positions = channel.draw_query_positions(0);
+    let proof = prover.build_proof(&positions);
+    let commitments = channel.layer_commitments().to_vec();
+
+    // corrupt the evaluation at the first query position; the proof is rejected when all
+    // positions are checked
+    let mut queried_evaluations = positions.iter().map(|&p| evaluations[p]).collect::<Vec<_>>();
+    queried_evaluations[0] += BaseElement::ONE;
+    let verify = |sampled: &[usize]| {
+        let sampled_evaluations =
+            sampled.iter().map(|&i| queried_evaluations[i]).collect::<Vec<_>>();
+        let sampled_positions = sampled.iter().map(|&i| positions[i]).collect::<Vec<_>>();
+        verify_sampled_proof(
+            proof.clone(),
+            commitments.clone(),
+            &sampled_evaluations,
+            trace_length - 1,
+            domain_size,
+            &positions,
+            &sampled_positions,
+            &options,
+        )
+    };
+    let all = (0..positions.len()).collect::<Vec<_>>();
+    assert!(verify(&all).is_err());
+
+    // the corrupted evaluation is missed when its position is not sampled, and is caught
+    // otherwise
+    assert!(verify(&[1, 3, 5]).is_ok());
+    assert!(verify(&all[1..]).is_ok());
+    assert!(verify(&[0, 3, 5]).is_err());
+
+    // sampled positions must be a subsequence of the query positions
+    let result = verify_sampled_proof(
+        proof.clone(),
+        commitments.clone(),
+        &[evaluations[positions[1]]],
+        trace_length - 1,
+        domain_size,
+        &positions,
+        &[positions[1] + 1],
+        &options,
+    );
+    assert_eq!(Err(VerifierError::InvalidSampledPositions), result);
+    let result = verify_sampled_proof(
+        proof,
+        commitments,
+        &[],
+        trace_length - 1,
+        domain_size,
+        &positions,
+        &[],
+        &options,
+    );
+    assert_eq!(Err(VerifierError::InvalidSampledPositions), result);
+}
+
+#[test]
+fn fri_batched_prove_verify() {
+    let trace_length = 1 << 10;
+    let lde_blowup = 1 << 3;
+    let domain_size = trace_length * lde_blowup;
+    let options = FriOptions::new(lde_blowup, 4, 31);
+
+    // build evaluations of three different polynomials of degree smaller than trace length
+    let polys = (0..3u128)
+        .map(|i| (0..trace_length as u128).map(|j| BaseElement::new(i * j + 1)).collect())
+        .collect::<Vec<_>>();
+    let evaluations = evaluate_polys(&polys, domain_size);
+
+    // generate a single proof for the batch and verify it
+    let (proof, commitments, positions) = build_batched_proof(&options, evaluations.clone());
+    let result = verify_batched_proof(
+        proof.clone(),
+        commitments.clone(),
+        &evaluations,
+        3,
+        trace_length - 1,
+        &positions,
+        &options,
+    );
+    assert!(result.is_ok(), "{:}", result.err().unwrap());
+
+    // the proof should be rejected when evaluations of only some polynomials are provided
+    let result = verify_batched_proof(
+        proof,
+        commitments,
+        &evaluations[..2],
+        3,
+        trace_length - 1,
+        &positions,
+        &options,
+    );
+    assert_eq!(Err(VerifierError::NumBatchedPolynomialsMismatch(3, 2)), result);
+
+    // the proof should be rejected when one of the polynomials has a higher degree
+    let mut polys = polys;
+    polys[1].resize(trace_length * 2, BaseElement::ONE);
+    let evaluations = evaluate_polys(&polys, domain_size);
+    let (proof, commitments, positions) = build_batched_proof(&options, evaluations.clone());
+    let result = verify_batched_proof(
+        proof,
+        commitments,
+        &evaluations,
+        3,
+        trace_length - 1,
+        &positions,
+        &options,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn fri_verify_layer_callback() {
+    let trace_length = 1 << 10;
+    let lde_blowup = 1 << 3;
+    let domain_size = trace_length * lde_blowup;
+    let options = FriOptions::new(lde_blowup, 4, 31);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, lde_blowup);
+
+    // generate the proof
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone());
+    let positions = channel.draw_query_positions(0);
+    let proof = prover.build_proof(&positions);
+    let commitments = channel.layer_commitments().to_vec();
+    let mut queried_evaluations = positions.iter().map(|&p| evaluations[p]).collect::<Vec<_>>();
+
+    // for a valid proof, the callback is invoked once for every layer in order
+    let mut checks = Vec::new();
+    let result = verify_with_layer_callback(
+        proof.clone(),
+        commitments.clone(),
+        &queried_evaluations,
+        trace_length - 1,
+        domain_size,
+        &positions,
+        &options,
+        |depth, is_consistent| checks.push((depth, is_consistent)),
+    );
+    assert!(result.is_ok(), "{:}", result.err().unwrap());
+    let num_layers = options.num_fri_layers(domain_size);
+    assert_eq!((0..num_layers).map(|depth| (depth, true)).collect::<Vec<_>>(), checks);
+
+    // when the first layer is inconsistent with the queried evaluations, the callback reports the
+    // failure and verification stops
+    queried_evaluations[0] += BaseElement::ONE;
+    let mut checks = Vec::new();
+    let result = verify_with_layer_callback(
+        proof,
+        commitments,
+        &queried_evaluations,
+        trace_length - 1,
+        domain_size,
+        &positions,
+        &options,
+        |depth, is_consistent| checks.push((depth, is_consistent)),
+    );
+    assert_eq!(Err(VerifierError::InvalidLayerFolding(0)), result);
+    assert_eq!(vec![(0, false)], checks);
+}
+
+#[test]
+fn fri_verify_with_alphas() {
+    let trace_length = 1 << 10;
+    let lde_blowup = 1 << 3;
+    let domain_size = trace_length * lde_blowup;
+    let options = FriOptions::new(lde_blowup, 4, 31);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, lde_blowup);
+
+    // generate the proof
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone());
+    let positions = channel.draw_query_positions(0);
+    let proof = prover.build_proof(&positions);
+    let commitments = channel.layer_commitments().to_vec();
+    let queried_evaluations = positions.iter().map(|&p| evaluations[p]).collect::<Vec<_>>();
+
+    let build_channel = || {
+        DefaultVerifierChannel::<BaseElement, Blake3>::new(
+            proof.clone(),
+            commitments.clone(),
+            domain_size,
+            options.folding_factor(),
+        )
+        .unwrap()
+    };
+    let verify = |alphas: Vec<BaseElement>| {
+        let mut channel = build_channel();
+        let verifier = FriVerifier::<_, _, _, DefaultRandomCoin<Blake3>>::with_alphas(
+            &mut channel,
+            alphas,
+            options.clone(),
+            trace_length - 1,
+        )?;
+        verifier.verify(&mut channel, &queried_evaluations, &positions)
+    };
+
+    // alphas drawn from the public coin are accepted
+    let mut coin = DefaultRandomCoin::<Blake3>::new(&[]);
+    let verifier =
+        FriVerifier::new(&mut build_channel(), &mut coin, options.clone(), trace_length - 1)
+            .unwrap();
+    let alphas = verifier.layer_alphas().to_vec();
+    assert_eq!(commitments.len(), alphas.len());
+    assert_eq!(Ok(()), verify(alphas.clone()));
+
+    // a different alpha at any layer is rejected
+    let mut invalid = alphas.clone();
+    invalid[1] += BaseElement::ONE;
+    assert!(verify(invalid).is_err());
+
+    // the number of alphas must match the number of layers
+    assert_eq!(
+        Err(VerifierError::NumLayerAlphasMismatch(alphas.len(), alphas.len() - 1)),
+        verify(alphas[1..].to_vec())
+    );
+}
+
+#[test]
+fn fri_num_layers() {
+    let lde_blowup = 8;
+    for folding_factor in [2, 4, 8, 16] {
+        for remainder_max_degree in [7, 31, 255] {
+            for trace_length_e in 3..12 {
+                let trace_length = 1 << trace_length_e;
+                let domain_size = trace_length * lde_blowup;
+                let options = FriOptions::new(lde_blowup, folding_factor, remainder_max_degree);
+                let mut channel = build_prover_channel(trace_length, &options);
+                let evaluations = build_evaluations(trace_length, lde_blowup);
+
+                let mut prover = FriProver::new(options.clone());
+                prover.build_layers(&mut channel, evaluations);
+
+                let expected =
+                    num_fri_layers(domain_size, folding_factor, remainder_max_degree, lde_blowup);
+                assert_eq!(expected, options.num_fri_layers(domain_size));
+                assert_eq!(expected, prover.num_layers());
+
+                // the remainder is committed to in addition to the layers
+                assert_eq!(expected + 1, channel.layer_commitments().len());
+                if expected > 0 {
+                    let positions = channel.draw_query_positions(0);
+                    assert_eq!(expected, prover.build_proof(&positions).num_layers());
+                }
+            }
+        }
+    }
+
+    // hand-computed cases; the domain is not folded when it is already small enough
+    assert_eq!(0, num_fri_layers(64, 4, 7, 8));
+    assert_eq!(1, num_fri_layers(128, 4, 7, 8));
+    assert_eq!(3, num_fri_layers(2048, 4, 7, 8));
+    assert_eq!(3, num_fri_layers(1 << 20, 16, 255, 8));
+}
+
+#[test]
+fn fri_streaming_prover() {
+    // traces long enough for layers to span several chunks are included
+    let lde_blowup = 8;
+    for folding_factor in [2, 4, 8, 16] {
+        for trace_length_e in [3, 6, 12] {
+            let trace_length = 1 << trace_length_e;
+            let domain_size = trace_length * lde_blowup;
+            let options = FriOptions::new(lde_blowup, folding_factor, 7);
+            let evaluations = build_evaluations(trace_length, lde_blowup);
+
+            // build the proof using the in-memory prover
+            let mut channel = build_prover_channel(trace_length, &options);
+            let mut prover = FriProver::new(options.clone());
+            prover.build_layers(&mut channel, evaluations.clone());
+            let positions = channel.draw_query_positions(0);
+            let proof = prover.build_proof(&positions);
+            let commitments = channel.layer_commitments().to_vec();
+
+            // build the proof using the streaming prover
+            let mut channel = build_prover_channel(trace_length, &options);
+            let mut streaming_prover = StreamingFriProver::new(options.clone());
+            streaming_prover.build_layers(&mut channel, evaluations.clone());
+            assert_eq!(options.num_fri_layers(domain_size), streaming_prover.num_layers());
+            assert_eq!(positions, channel.draw_query_positions(0));
+            let streaming_proof = streaming_prover.build_proof(&positions);
+
+            // the commitments and the proofs are identical, and the proof is valid
+            assert_eq!(commitments, channel.layer_commitments());
+            assert_eq!(proof.to_bytes(), streaming_proof.to_bytes());
+            let result = verify_proof(
+                streaming_proof,
+                commitments,
+                &evaluations,
+                trace_length - 1,
+                domain_size,
+                &positions,
+                &options,
+            );
+            assert!(result.is_ok(), "{:}", result.err().unwrap());
+
+            // the prover state is cleared after the proof is built
+            assert_eq!(0, streaming_prover.num_layers());
+        }
+    }
+}
+
+#[test]
+fn fri_sorted_queries() {
+    let trace_length = 1 << 12;
+    let lde_blowup = 8;
+    let domain_size = trace_length * lde_blowup;
+    let evaluations = build_evaluations(trace_length, lde_blowup);
+    for folding_factor in [2, 4, 8, 16] {
+        let options = FriOptions::new(lde_blowup, folding_factor, 7);
+        let sorted_options = options.clone().with_sorted_queries();
+
+        // build proofs with query values stored in derivation order and in sorted order
+        let mut channel = build_prover_channel(trace_length, &options);
+        let mut prover = FriProver::new(options.clone());
+        prover.build_layers(&mut channel, evaluations.clone());
+        let mut positions = channel.draw_query_positions(0);
+        positions.sort_unstable();
+        positions.dedup();
+        let proof = prover.build_proof(&positions);
+        let commitments = channel.layer_commitments().to_vec();
+
+        let mut channel = build_prover_channel(trace_length, &sorted_options);
+        let mut prover = FriProver::new(sorted_options.clone());
+        prover.build_layers(&mut channel, evaluations.clone());
+        let sorted_proof = prover.build_proof(&positions);
+        assert_eq!(commitments, channel.layer_commitments());
+        assert_ne!(proof, sorted_proof);
+
+        // the streaming prover stores query values in the same order
+        let mut channel = build_prover_channel(trace_length, &sorted_options);
+        let mut streaming_prover = StreamingFriProver::new(sorted_options.clone());
+        streaming_prover.build_layers(&mut channel, evaluations.clone());
+        assert_eq!(sorted_proof, streaming_prover.build_proof(&positions));
+
+        // the sorted proof verifies against options requiring sorted storage
+        let verify = |proof: &FriProof, options: &FriOptions| {
+            verify_proof(
+                proof.clone(),
+                commitments.clone(),
+                &evaluations,
+                trace_length - 1,
+                domain_size,
+                &positions,
+                options,
+            )
+        };
+        let result = verify(&sorted_proof, &sorted_options);
+        assert!(result.is_ok(), "{:}", result.err().unwrap());
+        assert!(verify(&proof, &options).is_ok());
+
+        // but a proof with query values stored out of order is rejected, and vice versa
+        assert!(verify(&proof, &sorted_options).is_err());
+        assert!(verify(&sorted_proof, &options).is_err());
+    }
+}
+
 // TEST UTILS
 // ================================================================================================
 
@@ -132,3 +603,104 @@ fn fri_prove_verify(
     );
     assert!(result.is_err());
 }
+
+#[allow(clippy::too_many_arguments)]
+fn verify_sampled_proof(
+    proof: FriProof,
+    commitments: Vec<<Blake3 as Hasher>::Digest>,
+    sampled_evaluations: &[BaseElement],
+    max_degree: usize,
+    domain_size: usize,
+    positions: &[usize],
+    sampled_positions: &[usize],
+    options: &FriOptions,
+) -> Result<(), VerifierError> {
+    let mut channel = DefaultVerifierChannel::<BaseElement, Blake3>::new(
+        proof,
+        commitments,
+        domain_size,
+        options.folding_factor(),
+    )
+    .unwrap();
+    let mut coin = DefaultRandomCoin::<Blake3>::new(&[]);
+    let verifier = FriVerifier::new(&mut channel, &mut coin, options.clone(), max_degree)?;
+    verifier.verify_sampled(&mut channel, sampled_evaluations, positions, sampled_positions)
+}
+
+fn evaluate_polys(polys: &[Vec<BaseElement>], domain_size: usize) -> Vec<Vec<BaseElement>> {
+    let twiddles = fft::get_twiddles::<BaseElement>(domain_size);
+    polys
+        .iter()
+        .map(|poly| {
+            let mut evaluations = poly.clone();
+            evaluations.resize(domain_size, BaseElement::ZERO);
+            fft::evaluate_poly(&mut evaluations, &twiddles);
+            evaluations
+        })
+        .collect()
+}
+
+fn build_batched_proof(
+    options: &FriOptions,
+    evaluations: Vec<Vec<BaseElement>>,
+) -> (FriProof, Vec<<Blake3 as Hasher>::Digest>, Vec<usize>) {
+    let mut channel = DefaultProverChannel::<BaseElement, Blake3, DefaultRandomCoin<Blake3>>::new(
+        evaluations[0].len(),
+        32,
+    );
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers_batched(&mut channel, evaluations);
+    let positions = channel.draw_query_positions(0);
+    let proof = prover.build_proof(&positions);
+    (proof, channel.layer_commitments().to_vec(), positions)
+}
+
+fn verify_batched_proof(
+    proof: FriProof,
+    commitments: Vec<<Blake3 as Hasher>::Digest>,
+    evaluations: &[Vec<BaseElement>],
+    num_polys: usize,
+    max_degree: usize,
+    positions: &[usize],
+    options: &FriOptions,
+) -> Result<(), VerifierError> {
+    let domain_size = evaluations[0].len();
+    let mut channel = DefaultVerifierChannel::<BaseElement, Blake3>::new(
+        proof,
+        commitments,
+        domain_size,
+        options.folding_factor(),
+    )
+    .unwrap();
+    let mut coin = DefaultRandomCoin::<Blake3>::new(&[]);
+    let verifier =
+        FriVerifier::new_batched(&mut channel, &mut coin, options.clone(), max_degree, num_polys)?;
+    let queried_evaluations = evaluations
+        .iter()
+        .map(|values| positions.iter().map(|&p| values[p]).collect())
+        .collect::<Vec<_>>();
+    verifier.verify_batched(&mut channel, &queried_evaluations, positions)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn verify_with_layer_callback<F: FnMut(usize, bool)>(
+    proof: FriProof,
+    commitments: Vec<<Blake3 as Hasher>::Digest>,
+    queried_evaluations: &[BaseElement],
+    max_degree: usize,
+    domain_size: usize,
+    positions: &[usize],
+    options: &FriOptions,
+    callback: F,
+) -> Result<(), VerifierError> {
+    let mut channel = DefaultVerifierChannel::<BaseElement, Blake3>::new(
+        proof,
+        commitments,
+        domain_size,
+        options.folding_factor(),
+    )
+    .unwrap();
+    let mut coin = DefaultRandomCoin::<Blake3>::new(&[]);
+    let verifier = FriVerifier::new(&mut channel, &mut coin, options.clone(), max_degree)?;
+    verifier.verify_with_layer_callback(&mut channel, queried_evaluations, positions, callback)
+}
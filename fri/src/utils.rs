@@ -49,3 +49,15 @@ where
     });
     result
 }
+
+/// Computes a linear combination of the specified evaluation vectors using the provided
+/// coefficients; all evaluation vectors are assumed to have the same length.
+pub(crate) fn combine_batch<E: FieldElement>(evaluations: &[Vec<E>], coefficients: &[E]) -> Vec<E> {
+    let mut result = vec![E::ZERO; evaluations[0].len()];
+    for (values, &coefficient) in evaluations.iter().zip(coefficients) {
+        iter_mut!(result, 1024).zip(values).for_each(|(r, &v)| {
+            *r += v * coefficient;
+        });
+    }
+    result
+}
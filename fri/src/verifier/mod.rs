@@ -5,15 +5,86 @@
 
 //! Contains an implementation of FRI verifier and associated components.
 
-use crate::{folding::fold_positions, utils::map_positions_to_indexes, FriOptions, VerifierError};
-use core::{convert::TryInto, marker::PhantomData, mem};
+use crate::{
+    folding::fold_positions,
+    utils::{combine_batch, map_positions_to_indexes},
+    FriOptions, FriProof, VerifierError,
+};
+use core::{convert::TryInto, marker::PhantomData};
 use crypto::{ElementHasher, RandomCoin};
 use math::{polynom, FieldElement, StarkField};
-use utils::collections::Vec;
+use utils::{collections::Vec, string::ToString};
 
 mod channel;
 pub use channel::{DefaultVerifierChannel, VerifierChannel};
 
+// FRI VERIFICATION
+// ================================================================================================
+/// Verifies that the specified FRI `proof` attests that `evaluations` at the specified
+/// `positions` are evaluations of a polynomial of degree at most `max_poly_degree`.
+///
+/// This executes both phases of the FRI protocol from the verifier's perspective independently of
+/// any larger protocol, and thus can be used to test the FRI implementation in isolation or to
+/// build custom protocols on top of FRI. Specifically:
+/// * `layer_commitments` must contain commitments to all FRI layers followed by the commitment to
+///   the remainder, in the order in which they were written into the prover channel.
+/// * `evaluations` and `positions` describe queries at the first FRI layer; the polynomial is
+///   assumed to be evaluated over a domain of size `max_poly_degree + 1` rounded up to the next
+///   power of two and multiplied by the blowup factor from `options`.
+/// * `public_coin` must be in the same state as the coin used by the prover before the first layer
+///   was committed to; it is reseeded with each layer commitment to draw folding α values.
+///
+/// # Errors
+/// Returns an error if:
+/// * The number of layers in the proof or the number of layer commitments is inconsistent with
+///   `max_poly_degree` and `options`.
+/// * The proof could not be parsed into layer queries, Merkle paths, and remainder.
+/// * Verification of the proof fails for any of the reasons described in
+///   [FriVerifier::verify()].
+pub fn verify<E, H, R>(
+    proof: FriProof,
+    layer_commitments: Vec<H::Digest>,
+    evaluations: &[E],
+    positions: &[usize],
+    max_poly_degree: usize,
+    options: FriOptions,
+    public_coin: &mut R,
+) -> Result<(), VerifierError>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField, Hasher = H>,
+{
+    // make sure the shape of the proof is consistent with the protocol parameters
+    let domain_size = (max_poly_degree + 1).next_power_of_two() * options.blowup_factor();
+    let num_fri_layers = options.num_fri_layers(domain_size);
+    if proof.num_layers() != num_fri_layers {
+        return Err(VerifierError::ProofDeserializationError(format!(
+            "expected {} FRI layers, but proof contains {}",
+            num_fri_layers,
+            proof.num_layers()
+        )));
+    }
+    if layer_commitments.len() != num_fri_layers + 1 {
+        return Err(VerifierError::ProofDeserializationError(format!(
+            "expected {} FRI layer commitments, but {} were provided",
+            num_fri_layers + 1,
+            layer_commitments.len()
+        )));
+    }
+
+    let mut channel = DefaultVerifierChannel::<E, H>::new(
+        proof,
+        layer_commitments,
+        domain_size,
+        options.folding_factor(),
+    )
+    .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+
+    let verifier = FriVerifier::new(&mut channel, public_coin, options, max_poly_degree)?;
+    verifier.verify(&mut channel, evaluations, positions)
+}
+
 // FRI VERIFIER
 // ================================================================================================
 /// Implements the verifier component of the FRI protocol.
@@ -67,6 +138,7 @@ where
     domain_generator: E::BaseField,
     layer_commitments: Vec<H::Digest>,
     layer_alphas: Vec<E>,
+    batching_coefficients: Vec<E>,
     options: FriOptions,
     num_partitions: usize,
     _channel: PhantomData<C>,
@@ -105,26 +177,73 @@ where
         options: FriOptions,
         max_poly_degree: usize,
     ) -> Result<Self, VerifierError> {
-        // infer evaluation domain info
-        let domain_size = max_poly_degree.next_power_of_two() * options.blowup_factor();
-        let domain_generator = E::BaseField::get_root_of_unity(domain_size.ilog2());
-
         let num_partitions = channel.read_fri_num_partitions();
 
         // read layer commitments from the channel and use them to build a list of alphas
         let layer_commitments = channel.read_fri_layer_commitments();
         let mut layer_alphas = Vec::with_capacity(layer_commitments.len());
-        let mut max_degree_plus_1 = max_poly_degree + 1;
-        for (depth, commitment) in layer_commitments.iter().enumerate() {
+        for commitment in layer_commitments.iter() {
             public_coin.reseed(*commitment);
             let alpha = public_coin.draw().map_err(VerifierError::RandomCoinError)?;
             layer_alphas.push(alpha);
+        }
 
-            // make sure the degree can be reduced by the folding factor at all layers
-            // but the remainder layer
-            if depth != layer_commitments.len() - 1
-                && max_degree_plus_1 % options.folding_factor() != 0
-            {
+        Self::build(layer_commitments, layer_alphas, num_partitions, options, max_poly_degree)
+    }
+
+    /// Returns a new instance of FRI verifier which uses the specified α values instead of
+    /// drawing them from a public coin.
+    ///
+    /// This is intended for settings in which the α values are computed externally (e.g.,
+    /// collaboratively by several parties). The verifier reads FRI layer commitments from the
+    /// `channel`, and pairs the commitment at each layer with the α value at the same position
+    /// in `layer_alphas`; the commitments are not used to derive the α values, and thus, the
+    /// caller is responsible for making sure that the α values were generated correctly.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The number of α values is not equal to the number of FRI layers read from the channel.
+    /// * `max_poly_degree` is inconsistent with the number of FRI layers read from the channel
+    ///   and `folding_factor` specified in the `options` parameter.
+    pub fn with_alphas(
+        channel: &mut C,
+        layer_alphas: Vec<E>,
+        options: FriOptions,
+        max_poly_degree: usize,
+    ) -> Result<Self, VerifierError> {
+        let num_partitions = channel.read_fri_num_partitions();
+        let layer_commitments = channel.read_fri_layer_commitments();
+        if layer_alphas.len() != layer_commitments.len() {
+            return Err(VerifierError::NumLayerAlphasMismatch(
+                layer_commitments.len(),
+                layer_alphas.len(),
+            ));
+        }
+
+        Self::build(layer_commitments, layer_alphas, num_partitions, options, max_poly_degree)
+    }
+
+    /// Returns a new instance of FRI verifier for the specified layer commitments and α values.
+    ///
+    /// # Errors
+    /// Returns an error if `max_poly_degree` cannot be reduced by the folding factor at all FRI
+    /// layers but the remainder layer.
+    fn build(
+        layer_commitments: Vec<H::Digest>,
+        layer_alphas: Vec<E>,
+        num_partitions: usize,
+        options: FriOptions,
+        max_poly_degree: usize,
+    ) -> Result<Self, VerifierError> {
+        // infer evaluation domain info
+        let domain_size = (max_poly_degree + 1).next_power_of_two() * options.blowup_factor();
+        let domain_generator = E::BaseField::get_root_of_unity(domain_size.ilog2());
+
+        // make sure the degree can be reduced by the folding factor at all layers but the
+        // remainder layer
+        let mut max_degree_plus_1 = max_poly_degree + 1;
+        for depth in 0..layer_commitments.len().saturating_sub(1) {
+            if max_degree_plus_1 % options.folding_factor() != 0 {
                 return Err(VerifierError::DegreeTruncation(
                     max_degree_plus_1 - 1,
                     options.folding_factor(),
@@ -140,6 +259,7 @@ where
             domain_generator,
             layer_commitments,
             layer_alphas,
+            batching_coefficients: Vec::new(),
             options,
             num_partitions,
             _channel: PhantomData,
@@ -147,6 +267,35 @@ where
         })
     }
 
+    /// Returns a new instance of FRI verifier for a proof attesting to a batch of `num_polys`
+    /// polynomials evaluated over the same domain.
+    ///
+    /// This is the counterpart of [FriProver::build_layers_batched()](crate::FriProver::build_layers_batched()):
+    /// before executing the commit phase in the same way as [new()](FriVerifier::new()) does,
+    /// the verifier draws a random coefficient for each polynomial in the batch from the
+    /// `public_coin`. The coefficients are then used by
+    /// [verify_batched()](FriVerifier::verify_batched()) to combine evaluations of the
+    /// polynomials at the queried positions.
+    ///
+    /// # Errors
+    /// Returns an error for any of the reasons listed for [new()](FriVerifier::new()), or if an
+    /// error was encountered while drawing a batching coefficient from the coin.
+    pub fn new_batched(
+        channel: &mut C,
+        public_coin: &mut R,
+        options: FriOptions,
+        max_poly_degree: usize,
+        num_polys: usize,
+    ) -> Result<Self, VerifierError> {
+        let batching_coefficients = (0..num_polys)
+            .map(|_| public_coin.draw().map_err(VerifierError::RandomCoinError))
+            .collect::<Result<Vec<E>, _>>()?;
+
+        let mut verifier = Self::new(channel, public_coin, options, max_poly_degree)?;
+        verifier.batching_coefficients = batching_coefficients;
+        Ok(verifier)
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -158,8 +307,8 @@ where
     /// Returns size of the domain over which a polynomial commitment checked by this verifier
     /// has been evaluated.
     ///
-    /// The domain size can be computed by rounding `max_poly_degree` to the next power of two
-    /// and multiplying the result by the `blowup_factor` from the protocol options.
+    /// The domain size can be computed by rounding `max_poly_degree + 1` to the next power of
+    /// two and multiplying the result by the `blowup_factor` from the protocol options.
     pub fn domain_size(&self) -> usize {
         self.domain_size
     }
@@ -176,6 +325,11 @@ where
         &self.options
     }
 
+    /// Returns α values used by this verifier at each FRI layer.
+    pub fn layer_alphas(&self) -> &[E] {
+        &self.layer_alphas
+    }
+
     // VERIFICATION PROCEDURE
     // --------------------------------------------------------------------------------------------
     /// Executes the query phase of the FRI protocol.
@@ -207,6 +361,32 @@ where
         evaluations: &[E],
         positions: &[usize],
     ) -> Result<(), VerifierError> {
+        self.verify_with_layer_callback(channel, evaluations, positions, |_, _| {})
+    }
+
+    /// Executes the query phase of the FRI protocol, invoking the specified callback after each
+    /// FRI layer is checked.
+    ///
+    /// This is the same as [verify()](FriVerifier::verify()), but after evaluations queried at a
+    /// given FRI layer are checked against the values implied by the previous layer, `callback`
+    /// is invoked with the index of the layer and a flag indicating whether the folding relation
+    /// held at all queried positions. Verification stops at the first layer for which the check
+    /// fails, and thus, the callback is not invoked for subsequent layers. The callback does not
+    /// affect the verification result; it is intended to make the low-degree test observable
+    /// (e.g., for debugging or teaching purposes).
+    ///
+    /// # Errors
+    /// Returns an error under the same conditions as [verify()](FriVerifier::verify()).
+    pub fn verify_with_layer_callback<F>(
+        &self,
+        channel: &mut C,
+        evaluations: &[E],
+        positions: &[usize],
+        mut callback: F,
+    ) -> Result<(), VerifierError>
+    where
+        F: FnMut(usize, bool),
+    {
         if evaluations.len() != positions.len() {
             return Err(VerifierError::NumPositionEvaluationMismatch(
                 positions.len(),
@@ -214,24 +394,148 @@ where
             ));
         }
 
+        // static dispatch for folding factor parameter
+        let callback = &mut callback;
+        self.verify_dispatch(channel, evaluations, positions, positions, callback)
+    }
+
+    /// Executes the query phase of the FRI protocol, checking the folding relation only at the
+    /// specified subset of the query positions.
+    ///
+    /// **This provides reduced soundness.** Decommitments to layer evaluations are still checked
+    /// against layer commitments for all `positions` (since all queried values are authenticated
+    /// together), but consistency of the degree-respecting projection is checked only for
+    /// `sampled_positions`. `evaluations` must contain evaluations of the polynomial at the first
+    /// FRI layer for `sampled_positions`. When `sampled_positions` is the same as `positions`,
+    /// this is equivalent to [verify()](FriVerifier::verify()).
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * `sampled_positions` is empty or is not a subsequence of `positions`.
+    /// * The length of `evaluations` is not equal to the length of `sampled_positions`.
+    /// * Verification fails for any of the reasons listed for [verify()](FriVerifier::verify()),
+    ///   with the folding relation checked only for the sampled positions.
+    pub fn verify_sampled(
+        &self,
+        channel: &mut C,
+        evaluations: &[E],
+        positions: &[usize],
+        sampled_positions: &[usize],
+    ) -> Result<(), VerifierError> {
+        let mut remaining = positions.iter();
+        if sampled_positions.is_empty()
+            || !sampled_positions.iter().all(|p| remaining.any(|q| q == p))
+        {
+            return Err(VerifierError::InvalidSampledPositions);
+        }
+        if evaluations.len() != sampled_positions.len() {
+            return Err(VerifierError::NumPositionEvaluationMismatch(
+                sampled_positions.len(),
+                evaluations.len(),
+            ));
+        }
+
+        let callback = &mut |_, _| {};
+        self.verify_dispatch(channel, evaluations, positions, sampled_positions, callback)
+    }
+
+    /// Executes the query phase of the FRI protocol for a batch of polynomials.
+    ///
+    /// Returns `Ok(())` if, for every polynomial in the batch, values in `evaluations` represent
+    /// evaluations of a polynomial with degree <= `max_poly_degree` at x coordinates specified by
+    /// the `positions` slice. Evaluations of the i-th polynomial are expected to be located in
+    /// `evaluations[i]`; the evaluations are combined using the coefficients drawn in
+    /// [new_batched()](FriVerifier::new_batched()), and the combination is then verified in the
+    /// same way as in [verify()](FriVerifier::verify()).
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The number of evaluation vectors is not equal to the number of polynomials this verifier
+    ///   was instantiated for.
+    /// * The length of any of the evaluation vectors is not equal to the length of `positions`.
+    /// * Verification of the combined evaluations fails for any of the reasons listed for
+    ///   [verify()](FriVerifier::verify()).
+    pub fn verify_batched(
+        &self,
+        channel: &mut C,
+        evaluations: &[Vec<E>],
+        positions: &[usize],
+    ) -> Result<(), VerifierError> {
+        if evaluations.is_empty() || evaluations.len() != self.batching_coefficients.len() {
+            return Err(VerifierError::NumBatchedPolynomialsMismatch(
+                self.batching_coefficients.len(),
+                evaluations.len(),
+            ));
+        }
+        if let Some(values) = evaluations.iter().find(|values| values.len() != positions.len()) {
+            return Err(VerifierError::NumPositionEvaluationMismatch(
+                positions.len(),
+                values.len(),
+            ));
+        }
+
+        let evaluations = combine_batch(evaluations, &self.batching_coefficients);
+        self.verify(channel, &evaluations, positions)
+    }
+
+    /// Dispatches verification to the implementation for the folding factor of this verifier.
+    fn verify_dispatch(
+        &self,
+        channel: &mut C,
+        evaluations: &[E],
+        positions: &[usize],
+        checked_positions: &[usize],
+        callback: &mut dyn FnMut(usize, bool),
+    ) -> Result<(), VerifierError> {
         // static dispatch for folding factor parameter
         let folding_factor = self.options.folding_factor();
         match folding_factor {
-            2 => self.verify_generic::<2>(channel, evaluations, positions),
-            4 => self.verify_generic::<4>(channel, evaluations, positions),
-            8 => self.verify_generic::<8>(channel, evaluations, positions),
-            16 => self.verify_generic::<16>(channel, evaluations, positions),
+            2 => self.verify_generic::<2>(
+                channel,
+                evaluations,
+                positions,
+                checked_positions,
+                callback,
+            ),
+            4 => self.verify_generic::<4>(
+                channel,
+                evaluations,
+                positions,
+                checked_positions,
+                callback,
+            ),
+            8 => self.verify_generic::<8>(
+                channel,
+                evaluations,
+                positions,
+                checked_positions,
+                callback,
+            ),
+            16 => self.verify_generic::<16>(
+                channel,
+                evaluations,
+                positions,
+                checked_positions,
+                callback,
+            ),
             _ => Err(VerifierError::UnsupportedFoldingFactor(folding_factor)),
         }
     }
 
     /// This is the actual implementation of the verification procedure described above, but it
-    /// also takes folding factor as a generic parameter N.
+    /// also takes folding factor as a generic parameter N, and invokes `callback` after checking
+    /// each FRI layer.
+    ///
+    /// Layer queries are authenticated for all `positions`, while the folding relation is checked
+    /// only for `checked_positions`, which must be a subsequence of `positions`; `evaluations`
+    /// must correspond to `checked_positions`.
     fn verify_generic<const N: usize>(
         &self,
         channel: &mut C,
         evaluations: &[E],
         positions: &[usize],
+        checked_positions: &[usize],
+        callback: &mut dyn FnMut(usize, bool),
     ) -> Result<(), VerifierError> {
         // pre-compute roots of unity used in computing x coordinates in the folded domain
         let folding_roots = (0..N)
@@ -243,12 +547,16 @@ where
         let mut domain_size = self.domain_size;
         let mut max_degree_plus_1 = self.max_poly_degree + 1;
         let mut positions = positions.to_vec();
+        let mut checked_positions = checked_positions.to_vec();
         let mut evaluations = evaluations.to_vec();
 
         for depth in 0..self.options.num_fri_layers(self.domain_size) {
             // determine which evaluations were queried in the folded layer
             let mut folded_positions =
                 fold_positions(&positions, domain_size, self.options.folding_factor());
+            if self.options.sorted_queries() {
+                folded_positions.sort_unstable();
+            }
             // determine where these evaluations are in the commitment Merkle tree
             let position_indexes = map_positions_to_indexes(
                 &folded_positions,
@@ -260,15 +568,44 @@ where
             let layer_commitment = self.layer_commitments[depth];
             // TODO: add layer depth to the potential error message
             let layer_values = channel.read_layer_queries(&position_indexes, &layer_commitment)?;
-            let query_values =
-                get_query_values::<E, N>(&layer_values, &positions, &folded_positions, domain_size);
-            if evaluations != query_values {
+
+            // when only a subset of positions is checked, select the layer values for the
+            // checked positions; all other values were only used to authenticate the layer
+            let (checked_folded_positions, checked_values) =
+                if checked_positions.len() == positions.len() {
+                    (folded_positions.clone(), layer_values)
+                } else {
+                    let mut checked_folded_positions =
+                        fold_positions(&checked_positions, domain_size, N);
+                    if self.options.sorted_queries() {
+                        checked_folded_positions.sort_unstable();
+                    }
+                    let checked_values = checked_folded_positions
+                        .iter()
+                        .map(|p| {
+                            let idx = folded_positions.iter().position(|q| q == p).unwrap();
+                            layer_values[idx]
+                        })
+                        .collect::<Vec<_>>();
+                    (checked_folded_positions, checked_values)
+                };
+
+            let query_values = get_query_values::<E, N>(
+                &checked_values,
+                &checked_positions,
+                &checked_folded_positions,
+                domain_size,
+                self.options.sorted_queries(),
+            );
+            let is_consistent = evaluations == query_values;
+            callback(depth, is_consistent);
+            if !is_consistent {
                 return Err(VerifierError::InvalidLayerFolding(depth));
             }
 
             // build a set of x coordinates for each row polynomial
             #[rustfmt::skip]
-            let xs = folded_positions.iter().map(|&i| {
+            let xs = checked_folded_positions.iter().map(|&i| {
                 let xe = domain_generator.exp_vartime((i as u64).into()) * self.options.domain_offset();
                 folding_roots.iter()
                     .map(|&r| E::from(xe * r))
@@ -277,7 +614,7 @@ where
             .collect::<Vec<_>>();
 
             // interpolate x and y values into row polynomials
-            let row_polys = polynom::interpolate_batch(&xs, &layer_values);
+            let row_polys = polynom::interpolate_batch(&xs, &checked_values);
 
             // calculate the pseudo-random value used for linear combination in layer folding
             let alpha = self.layer_alphas[depth];
@@ -295,7 +632,8 @@ where
             domain_generator = domain_generator.exp_vartime((N as u32).into());
             max_degree_plus_1 /= N;
             domain_size /= N;
-            mem::swap(&mut positions, &mut folded_positions);
+            positions = folded_positions;
+            checked_positions = checked_folded_positions;
         }
 
         // 2 ----- verify the remainder polynomial of the FRI proof -------------------------------
@@ -303,12 +641,12 @@ where
         // read the remainder polynomial from the channel and make sure it agrees with the evaluations
         // from the previous layer.
         let remainder_poly = channel.read_remainder()?;
-        if remainder_poly.len() > max_degree_plus_1 {
+        if polynom::degree_of(&remainder_poly) >= max_degree_plus_1 {
             return Err(VerifierError::RemainderDegreeMismatch(max_degree_plus_1 - 1));
         }
         let offset: E::BaseField = self.options().domain_offset();
 
-        for (&position, evaluation) in positions.iter().zip(evaluations) {
+        for (&position, evaluation) in checked_positions.iter().zip(evaluations) {
             let comp_eval = eval_horner::<E>(
                 &remainder_poly,
                 offset * domain_generator.exp_vartime((position as u64).into()),
@@ -329,12 +667,20 @@ fn get_query_values<E: FieldElement, const N: usize>(
     positions: &[usize],
     folded_positions: &[usize],
     domain_size: usize,
+    sorted: bool,
 ) -> Vec<E> {
     let row_length = domain_size / N;
 
     let mut result = Vec::new();
     for position in positions {
-        let idx = folded_positions.iter().position(|&v| v == position % row_length).unwrap();
+        // when folded positions are sorted, the index of a position can be found via binary
+        // search; otherwise, we need to scan the folded positions
+        let folded_position = position % row_length;
+        let idx = if sorted {
+            folded_positions.binary_search(&folded_position).unwrap()
+        } else {
+            folded_positions.iter().position(|&v| v == folded_position).unwrap()
+        };
         let value = values[idx][position / row_length];
         result.push(value);
     }
@@ -9,6 +9,9 @@ use std::time::Duration;
 use winter_math::{fft, fields::f128::BaseElement, polynom, FieldElement};
 
 const SIZES: [usize; 3] = [262_144, 524_288, 1_048_576];
+const MUL_SIZES: [usize; 6] = [16, 32, 64, 128, 1_024, 16_384];
+const LAGRANGE_SIZES: [usize; 3] = [64, 1_024, 16_384];
+const EVAL_SIZES: [usize; 3] = [16_384, 262_144, 1_048_576];
 
 fn syn_div(c: &mut Criterion) {
     let mut group = c.benchmark_group("syn_div");
@@ -38,5 +41,75 @@ fn syn_div(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(polynom_group, syn_div);
+fn mul(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mul");
+    group.sample_size(10);
+
+    for &size in MUL_SIZES.iter() {
+        let a: Vec<BaseElement> = rand_vector(size);
+        let b: Vec<BaseElement> = rand_vector(size);
+
+        // for small operands, polynom::mul() falls back onto schoolbook multiplication
+        group.bench_function(BenchmarkId::new("mul", size), |bench| {
+            bench.iter(|| polynom::mul(&a, &b));
+        });
+        group.bench_function(BenchmarkId::new("mul_fft", size), |bench| {
+            bench.iter(|| polynom::mul_fft(&a, &b));
+        });
+    }
+
+    group.finish();
+}
+
+fn eval(c: &mut Criterion) {
+    let mut group = c.benchmark_group("eval");
+    group.sample_size(10);
+
+    // high-degree polynomials evaluated at a single point as done when evaluating trace
+    // polynomials at an out-of-domain point
+    for &size in EVAL_SIZES.iter() {
+        let p: Vec<BaseElement> = rand_vector(size);
+        let x = rand_vector::<BaseElement>(1)[0];
+
+        group.bench_function(BenchmarkId::new("horner", size), |bench| {
+            bench.iter(|| polynom::eval_horner(&p, x));
+        });
+        group.bench_function(BenchmarkId::new("estrin", size), |bench| {
+            bench.iter(|| polynom::eval_estrin(&p, x));
+        });
+    }
+
+    group.finish();
+}
+
+fn eval_lagrange_subgroup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("eval_lagrange_subgroup");
+    group.sample_size(10);
+
+    // values of a periodic assertion with many points, evaluated at a single point as done when
+    // evaluating boundary constraints at an out-of-domain point
+    for &size in LAGRANGE_SIZES.iter() {
+        let values: Vec<BaseElement> = rand_vector(size);
+        let inv_twiddles = fft::get_inv_twiddles::<BaseElement>(size);
+        let x = rand_vector::<BaseElement>(1)[0];
+
+        group.bench_function(BenchmarkId::new("interpolate_and_eval", size), |bench| {
+            bench.iter_batched_ref(
+                || values.clone(),
+                |p| {
+                    fft::interpolate_poly(p, &inv_twiddles);
+                    polynom::eval(p, x)
+                },
+                BatchSize::SmallInput,
+            );
+        });
+        group.bench_function(BenchmarkId::new("lagrange", size), |bench| {
+            bench.iter(|| polynom::eval_lagrange_subgroup(&values, x));
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(polynom_group, syn_div, mul, eval, eval_lagrange_subgroup);
 criterion_main!(polynom_group);
@@ -500,7 +500,7 @@ where
         "multiplicative subgroup of size {domain_size} does not exist in the specified base field"
     );
     let root = B::get_root_of_unity(domain_size.ilog2());
-    let inv_root = root.exp((domain_size as u32 - 1).into());
+    let inv_root = root.exp(((domain_size - 1) as u64).into());
     let mut inv_twiddles = get_power_series(inv_root, domain_size / 2);
     permute(&mut inv_twiddles);
     inv_twiddles
@@ -584,6 +584,58 @@ pub fn permute_index(size: usize, index: usize) -> usize {
     index.reverse_bits().wrapping_shr(USIZE_BITS - bits)
 }
 
+/// Permutes `values` from bit-reversed order into natural order in place.
+///
+/// This is useful for aligning outputs of FFT algorithms which produce results in bit-reversed
+/// order (e.g., [FftInputs::fft_in_place()]) with the natural order of the evaluation domain.
+///
+/// Bit-reversal permutation is an involution, and thus, this function is the inverse of
+/// [permute_to_bitrev()].
+///
+/// When `concurrent` feature is enabled, the permutation is performed in multiple threads.
+///
+/// # Panics
+/// Panics if length of `values` is not a power of two.
+///
+/// # Examples
+/// ```
+/// # use winter_math::fft::*;
+/// # use winter_math::fields::f128::BaseElement;
+/// let mut values = (0..8u32).map(BaseElement::from).collect::<Vec<_>>();
+/// permute_to_natural(&mut values);
+///
+/// let expected = [0u32, 4, 2, 6, 1, 5, 3, 7].map(BaseElement::from);
+/// assert_eq!(expected.to_vec(), values);
+///
+/// permute_to_bitrev(&mut values);
+/// assert_eq!((0..8u32).map(BaseElement::from).collect::<Vec<_>>(), values);
+/// ```
+pub fn permute_to_natural<E: FieldElement>(values: &mut [E]) {
+    assert!(
+        values.len().is_power_of_two(),
+        "number of values must be a power of 2, but was {}",
+        values.len()
+    );
+    permute(values);
+}
+
+/// Permutes `values` from natural order into bit-reversed order in place.
+///
+/// This is the inverse of [permute_to_natural()].
+///
+/// When `concurrent` feature is enabled, the permutation is performed in multiple threads.
+///
+/// # Panics
+/// Panics if length of `values` is not a power of two.
+pub fn permute_to_bitrev<E: FieldElement>(values: &mut [E]) {
+    assert!(
+        values.len().is_power_of_two(),
+        "number of values must be a power of 2, but was {}",
+        values.len()
+    );
+    permute(values);
+}
+
 fn permute<E: FieldElement>(v: &mut [E]) {
     if cfg!(feature = "concurrent") && v.len() >= MIN_CONCURRENT_SIZE {
         #[cfg(feature = "concurrent")]
@@ -70,6 +70,64 @@ fn fft_get_twiddles() {
     assert_eq!(expected, twiddles);
 }
 
+// PERMUTATIONS
+// ================================================================================================
+
+#[test]
+fn fft_permute_known_case() {
+    let natural = (0..16u32).map(BaseElement::from).collect::<Vec<_>>();
+    let expected = [0u32, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15]
+        .map(BaseElement::from)
+        .to_vec();
+
+    let mut values = natural.clone();
+    super::permute_to_bitrev(&mut values);
+    assert_eq!(expected, values);
+
+    let mut values = expected;
+    super::permute_to_natural(&mut values);
+    assert_eq!(natural, values);
+}
+
+#[test]
+fn fft_permute_round_trip() {
+    for n in [1, 2, 8, 64, super::MIN_CONCURRENT_SIZE * 2] {
+        let original: Vec<BaseElement> = rand_vector(n);
+
+        let mut values = original.clone();
+        super::permute_to_bitrev(&mut values);
+        for (i, &value) in values.iter().enumerate() {
+            assert_eq!(original[super::permute_index(n, i)], value);
+        }
+        super::permute_to_natural(&mut values);
+        assert_eq!(original, values);
+
+        super::permute_to_natural(&mut values);
+        super::permute_to_bitrev(&mut values);
+        assert_eq!(original, values);
+    }
+}
+
+#[test]
+fn fft_permute_aligns_evaluations() {
+    // evaluations produced by the FFT without the final permutation are in bit-reversed order
+    let n = 32;
+    let mut p: Vec<BaseElement> = rand_vector(n);
+    let expected = polynom::eval_many(&p, &build_domain(n));
+    let twiddles = super::get_twiddles::<BaseElement>(n);
+    p.fft_in_place(&twiddles);
+
+    super::permute_to_natural(&mut p);
+    assert_eq!(expected, p);
+}
+
+#[test]
+#[should_panic(expected = "number of values must be a power of 2, but was 12")]
+fn fft_permute_invalid_length() {
+    let mut values: Vec<BaseElement> = rand_vector(12);
+    super::permute_to_natural(&mut values);
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
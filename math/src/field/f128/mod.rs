@@ -10,7 +10,7 @@
 //! significant thought given to performance, and the implementations of most operations are
 //! sub-optimal as well.
 
-use super::{ExtensibleField, FieldElement, StarkField};
+use super::{ExtensibleField, FieldElement, QuadExtension, StarkField};
 use core::{
     convert::{TryFrom, TryInto},
     fmt::{Debug, Display, Formatter},
@@ -367,6 +367,19 @@ impl From<[u8; 16]> for BaseElement {
     }
 }
 
+impl TryFrom<QuadExtension<BaseElement>> for BaseElement {
+    type Error = String;
+
+    /// Converts a quadratic extension field element into a base field element; returns an error
+    /// if the element does not lie in the base field (i.e., if its second coefficient is not zero).
+    fn try_from(value: QuadExtension<BaseElement>) -> Result<Self, Self::Error> {
+        match value.to_base_elements() {
+            [a, b] if b == Self::ZERO => Ok(a),
+            _ => Err(format!("{value} is not an element of the base field")),
+        }
+    }
+}
+
 impl<'a> TryFrom<&'a [u8]> for BaseElement {
     type Error = String;
 
@@ -8,7 +8,7 @@ use super::{
     StarkField, Vec, M,
 };
 use crate::field::{ExtensionOf, QuadExtension};
-use core::convert::TryFrom;
+use core::convert::{TryFrom, TryInto};
 use num_bigint::BigUint;
 use rand_utils::{rand_value, rand_vector};
 use utils::SliceReader;
@@ -144,6 +144,31 @@ fn quad_mul_base() {
     assert_eq!(expected, a.mul_base(b0));
 }
 
+#[test]
+fn quad_base_conversion() {
+    // base field elements round-trip through the extension field
+    let a: BaseElement = rand_value();
+    let b = <QuadExtension<BaseElement>>::from(a);
+    assert_eq!(<QuadExtension<BaseElement>>::new(a, BaseElement::ZERO), b);
+    assert_eq!(Ok(a), b.try_into());
+
+    // the embedding is compatible with field operations
+    let c: BaseElement = rand_value();
+    assert_eq!(
+        <QuadExtension<BaseElement>>::from(a * c),
+        b * <QuadExtension<BaseElement>>::from(c)
+    );
+    assert_eq!(
+        <QuadExtension<BaseElement>>::from(a + c),
+        b + <QuadExtension<BaseElement>>::from(c)
+    );
+    assert_eq!(Ok(a * c), BaseElement::try_from(b.mul_base(c)));
+
+    // genuine extension field elements cannot be converted into base field elements
+    let d = <QuadExtension<BaseElement>>::new(a, BaseElement::ONE);
+    assert!(BaseElement::try_from(d).is_err());
+}
+
 // SERIALIZATION / DESERIALIZATION
 // ================================================================================================
 
@@ -9,7 +9,7 @@
 //! fast modular arithmetic including branchless multiplication and addition. Base elements are
 //! stored in the Montgomery form using `u64` as the backing type.
 
-use super::{ExtensibleField, FieldElement, StarkField};
+use super::{CubeExtension, ExtensibleField, FieldElement, QuadExtension, StarkField};
 use core::{
     convert::{TryFrom, TryInto},
     fmt::{Debug, Display, Formatter},
@@ -18,8 +18,10 @@ use core::{
     slice,
 };
 use utils::{
-    collections::Vec, string::ToString, AsBytes, ByteReader, ByteWriter, Deserializable,
-    DeserializationError, Randomizable, Serializable,
+    collections::Vec,
+    string::{String, ToString},
+    AsBytes, ByteReader, ByteWriter, Deserializable, DeserializationError, Randomizable,
+    Serializable,
 };
 
 #[cfg(feature = "serde")]
@@ -474,6 +476,33 @@ impl From<BaseElement> for u64 {
     }
 }
 
+impl TryFrom<QuadExtension<BaseElement>> for BaseElement {
+    type Error = String;
+
+    /// Converts a quadratic extension field element into a base field element; returns an error
+    /// if the element does not lie in the base field (i.e., if its second coefficient is not zero).
+    fn try_from(value: QuadExtension<BaseElement>) -> Result<Self, Self::Error> {
+        match value.to_base_elements() {
+            [a, b] if b == Self::ZERO => Ok(a),
+            _ => Err(format!("{value} is not an element of the base field")),
+        }
+    }
+}
+
+impl TryFrom<CubeExtension<BaseElement>> for BaseElement {
+    type Error = String;
+
+    /// Converts a cubic extension field element into a base field element; returns an error if
+    /// the element does not lie in the base field (i.e., if its second or third coefficients are
+    /// not zero).
+    fn try_from(value: CubeExtension<BaseElement>) -> Result<Self, Self::Error> {
+        match value.to_base_elements() {
+            [a, b, c] if b == Self::ZERO && c == Self::ZERO => Ok(a),
+            _ => Err(format!("{value} is not an element of the base field")),
+        }
+    }
+}
+
 impl<'a> TryFrom<&'a [u8]> for BaseElement {
     type Error = DeserializationError;
 
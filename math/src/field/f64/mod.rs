@@ -14,7 +14,7 @@
 //!   and shifts.
 //! * $8$ is the 64th root of unity which opens up potential for optimized FFT implementations.
 
-use super::{ExtensibleField, FieldElement, StarkField};
+use super::{CubeExtension, ExtensibleField, FieldElement, QuadExtension, StarkField};
 use core::{
     convert::{TryFrom, TryInto},
     fmt::{Debug, Display, Formatter},
@@ -23,8 +23,10 @@ use core::{
     slice,
 };
 use utils::{
-    collections::Vec, string::ToString, AsBytes, ByteReader, ByteWriter, Deserializable,
-    DeserializationError, Randomizable, Serializable,
+    collections::Vec,
+    string::{String, ToString},
+    AsBytes, ByteReader, ByteWriter, Deserializable, DeserializationError, Randomizable,
+    Serializable,
 };
 
 #[cfg(feature = "serde")]
@@ -556,6 +558,33 @@ impl From<[u8; 8]> for BaseElement {
     }
 }
 
+impl TryFrom<QuadExtension<BaseElement>> for BaseElement {
+    type Error = String;
+
+    /// Converts a quadratic extension field element into a base field element; returns an error
+    /// if the element does not lie in the base field (i.e., if its second coefficient is not zero).
+    fn try_from(value: QuadExtension<BaseElement>) -> Result<Self, Self::Error> {
+        match value.to_base_elements() {
+            [a, b] if b == Self::ZERO => Ok(a),
+            _ => Err(format!("{value} is not an element of the base field")),
+        }
+    }
+}
+
+impl TryFrom<CubeExtension<BaseElement>> for BaseElement {
+    type Error = String;
+
+    /// Converts a cubic extension field element into a base field element; returns an error if
+    /// the element does not lie in the base field (i.e., if its second or third coefficients are
+    /// not zero).
+    fn try_from(value: CubeExtension<BaseElement>) -> Result<Self, Self::Error> {
+        match value.to_base_elements() {
+            [a, b, c] if b == Self::ZERO && c == Self::ZERO => Ok(a),
+            _ => Err(format!("{value} is not an element of the base field")),
+        }
+    }
+}
+
 impl<'a> TryFrom<&'a [u8]> for BaseElement {
     type Error = DeserializationError;
 
@@ -5,7 +5,7 @@
 
 use super::{BaseElement, DeserializationError, FieldElement, Serializable, StarkField, M};
 use crate::field::{CubeExtension, ExtensionOf, QuadExtension};
-use core::convert::TryFrom;
+use core::convert::{TryFrom, TryInto};
 use num_bigint::BigUint;
 use proptest::prelude::*;
 use rand_utils::rand_value;
@@ -300,6 +300,47 @@ fn quad_conjugate() {
     assert_eq!(expected, a.conjugate());
 }
 
+#[test]
+fn quad_frobenius() {
+    let a = <QuadExtension<BaseElement>>::new(rand_value(), rand_value());
+
+    // frobenius map is x -> x^p
+    assert_eq!(a.exp(BaseElement::MODULUS), a.frobenius());
+
+    // applying frobenius map EXTENSION_DEGREE times yields the original element
+    assert_ne!(a, a.frobenius());
+    assert_eq!(a, a.frobenius().frobenius());
+
+    // frobenius map is the identity on base field elements
+    let b: BaseElement = rand_value();
+    assert_eq!(b, b.frobenius());
+}
+
+#[test]
+fn quad_base_conversion() {
+    // base field elements round-trip through the extension field
+    let a: BaseElement = rand_value();
+    let b = <QuadExtension<BaseElement>>::from(a);
+    assert_eq!(<QuadExtension<BaseElement>>::new(a, BaseElement::ZERO), b);
+    assert_eq!(Ok(a), b.try_into());
+
+    // the embedding is compatible with field operations
+    let c: BaseElement = rand_value();
+    assert_eq!(
+        <QuadExtension<BaseElement>>::from(a * c),
+        b * <QuadExtension<BaseElement>>::from(c)
+    );
+    assert_eq!(
+        <QuadExtension<BaseElement>>::from(a + c),
+        b + <QuadExtension<BaseElement>>::from(c)
+    );
+    assert_eq!(Ok(a * c), BaseElement::try_from(b.mul_base(c)));
+
+    // genuine extension field elements cannot be converted into base field elements
+    let d = <QuadExtension<BaseElement>>::new(a, BaseElement::ONE);
+    assert!(BaseElement::try_from(d).is_err());
+}
+
 // CUBIC EXTENSION
 // ------------------------------------------------------------------------------------------------
 #[test]
@@ -373,6 +414,46 @@ fn cube_mul_base() {
     assert_eq!(expected, a.mul_base(b0));
 }
 
+#[test]
+fn cube_frobenius() {
+    let a = <CubeExtension<BaseElement>>::new(rand_value(), rand_value(), rand_value());
+
+    // frobenius map is x -> x^p
+    assert_eq!(a.exp(BaseElement::MODULUS), a.frobenius());
+
+    // applying frobenius map EXTENSION_DEGREE times yields the original element
+    let b = a.frobenius();
+    let c = b.frobenius();
+    assert_ne!(a, b);
+    assert_ne!(a, c);
+    assert_eq!(a, c.frobenius());
+}
+
+#[test]
+fn cube_base_conversion() {
+    // base field elements round-trip through the extension field
+    let a: BaseElement = rand_value();
+    let b = <CubeExtension<BaseElement>>::from(a);
+    assert_eq!(<CubeExtension<BaseElement>>::new(a, BaseElement::ZERO, BaseElement::ZERO), b);
+    assert_eq!(Ok(a), b.try_into());
+
+    // the embedding is compatible with field operations
+    let c: BaseElement = rand_value();
+    assert_eq!(
+        <CubeExtension<BaseElement>>::from(a * c),
+        b * <CubeExtension<BaseElement>>::from(c)
+    );
+    assert_eq!(
+        <CubeExtension<BaseElement>>::from(a + c),
+        b + <CubeExtension<BaseElement>>::from(c)
+    );
+    assert_eq!(Ok(a * c), BaseElement::try_from(b.mul_base(c)));
+
+    // genuine extension field elements cannot be converted into base field elements
+    let d = <CubeExtension<BaseElement>>::new(a, BaseElement::ZERO, BaseElement::ONE);
+    assert!(BaseElement::try_from(d).is_err());
+}
+
 // RANDOMIZED TESTS
 // ================================================================================================
 
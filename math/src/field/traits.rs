@@ -3,6 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use crate::utils::fill_random;
 use core::{
     convert::TryFrom,
     fmt::{Debug, Display},
@@ -158,6 +159,17 @@ pub trait FieldElement:
     #[must_use]
     fn conjugate(&self) -> Self;
 
+    /// Returns the image of this field element under the Frobenius endomorphism x -> x^p, where
+    /// p is the modulus of the base field.
+    ///
+    /// For base field elements this is the identity map; for extension field elements this is
+    /// the same as [conjugate()](FieldElement::conjugate). Applying this map
+    /// `EXTENSION_DEGREE` times always yields the original element.
+    #[must_use]
+    fn frobenius(&self) -> Self {
+        self.conjugate()
+    }
+
     // BASE ELEMENT CONVERSIONS
     // --------------------------------------------------------------------------------------------
 
@@ -219,6 +231,31 @@ pub trait FieldElement:
     fn zeroed_vector(n: usize) -> Vec<Self> {
         vec![Self::ZERO; n]
     }
+
+    /// Returns a vector of `n` pseudo-random elements deterministically derived from the
+    /// specified seed.
+    ///
+    /// The seed is expanded into a stream of bytes using ChaCha20, and elements are read from
+    /// consecutive chunks of [VALUE_SIZE](Randomizable::VALUE_SIZE) bytes; chunks which do not
+    /// encode a valid element are rejected. Thus, the elements are uniformly distributed, and a
+    /// vector derived from a given seed is always a prefix of a longer vector derived from the
+    /// same seed.
+    fn random_vec(n: usize, seed: [u8; 32]) -> Vec<Self> {
+        let mut result = vec![Self::ZERO; n];
+        fill_random(&mut result, seed);
+        result
+    }
+
+    /// Returns an array of `N` pseudo-random elements deterministically derived from the
+    /// specified seed.
+    ///
+    /// The elements are the same as the first `N` elements returned by
+    /// [random_vec()](FieldElement::random_vec) for the same seed.
+    fn random_array<const N: usize>(seed: [u8; 32]) -> [Self; N] {
+        let mut result = [Self::ZERO; N];
+        fill_random(&mut result, seed);
+        result
+    }
 }
 
 // STARK FIELD
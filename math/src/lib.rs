@@ -53,6 +53,8 @@
 //! [Polynomials](polynom) module implements basic polynomial operations such as:
 //!
 //! * Evaluation of a polynomial at a single or multiple point.
+//! * Evaluation of a polynomial defined by its values over a multiplicative subgroup at a single
+//!   point, without interpolating the polynomial.
 //! * Interpolation of a polynomial from a set of points (using
 //!   [Lagrange](https://en.wikipedia.org/wiki/Lagrange_polynomial) interpolation).
 //! * Addition, multiplication, subtraction, and division of polynomials.
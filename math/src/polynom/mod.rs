@@ -6,8 +6,11 @@
 //! Basic polynomial operations.
 //!
 //! This module provides a set of function for basic polynomial operations, including:
-//! - Polynomial evaluation using Horner method.
+//! - Polynomial evaluation using Horner method or Estrin's scheme.
+//! - Evaluation of polynomials given by their values over multiplicative subgroups using
+//!   Lagrange method.
 //! - Polynomial interpolation using Lagrange method.
+//! - Construction and evaluation of vanishing polynomials of multiplicative subgroups.
 //! - Polynomial addition, subtraction, multiplication, and division.
 //! - Synthetic polynomial division for efficient division by polynomials of the form
 //!   `x`^`a` - `b`.
@@ -24,20 +27,46 @@
 //! let p = [BaseElement::new(3), BaseElement::ZERO, BaseElement::new(4)];
 //! ```
 
-use crate::{field::FieldElement, utils::batch_inversion};
+use crate::{
+    fft,
+    field::{ExtensionOf, FieldElement, StarkField},
+    utils::batch_inversion,
+};
 use core::mem;
-use utils::{collections::Vec, group_vector_elements};
+use utils::{collections::Vec, group_vector_elements, iter_mut};
+
+#[cfg(feature = "concurrent")]
+use utils::iterators::*;
 
 #[cfg(test)]
 mod tests;
 
+// CONSTANTS
+// ================================================================================================
+
+/// Length of the shorter operand starting from which [mul()] multiplies polynomials via FFT;
+/// for shorter operands, schoolbook multiplication is faster.
+const FFT_MUL_THRESHOLD: usize = 32;
+
+/// Number of coefficients starting from which [eval()] evaluates polynomials using Estrin's
+/// scheme when `concurrent` feature is enabled; for shorter polynomials, Horner's method is
+/// faster.
+#[cfg(feature = "concurrent")]
+const ESTRIN_THRESHOLD: usize = 1 << 14;
+
+/// Minimum number of coefficient pairs combined by a single thread in [eval_estrin()].
+#[cfg(feature = "concurrent")]
+const ESTRIN_MIN_BATCH_SIZE: usize = 1024;
+
 // POLYNOMIAL EVALUATION
 // ================================================================================================
 
 /// Evaluates a polynomial at a single point and returns the result.
 ///
 /// Evaluates polynomial `p` at coordinate `x` using
-/// [Horner's method](https://en.wikipedia.org/wiki/Horner%27s_method).
+/// [Horner's method](https://en.wikipedia.org/wiki/Horner%27s_method). When `concurrent`
+/// feature is enabled, polynomials with many coefficients are evaluated using Estrin's scheme
+/// (see [eval_estrin()]) instead; the result is the same in both cases.
 ///
 /// # Examples
 /// ```
@@ -55,10 +84,77 @@ where
     B: FieldElement,
     E: FieldElement + From<B>,
 {
-    // Horner evaluation
+    #[cfg(feature = "concurrent")]
+    if p.len() >= ESTRIN_THRESHOLD {
+        return eval_estrin(p, x);
+    }
+
+    eval_horner(p, x)
+}
+
+/// Evaluates a polynomial at a single point using Horner's method and returns the result.
+///
+/// Evaluation requires a sequence of `p.len()` multiply-add operations, each of which depends on
+/// the result of the previous one.
+///
+/// # Examples
+/// ```
+/// # use winter_math::polynom::*;
+/// # use winter_math::{fields::{f128::BaseElement}, FieldElement};
+/// // define polynomial: f(x) = 3 * x^2 + 2 * x + 1
+/// let p = (1u32..4).map(BaseElement::from).collect::<Vec<_>>();
+///
+/// // evaluate the polynomial at point 4
+/// let x = BaseElement::new(4);
+/// assert_eq!(BaseElement::new(57), eval_horner(&p, x));
+/// ```
+pub fn eval_horner<B, E>(p: &[B], x: E) -> E
+where
+    B: FieldElement,
+    E: FieldElement + From<B>,
+{
     p.iter().rev().fold(E::ZERO, |acc, &coeff| acc * x + E::from(coeff))
 }
 
+/// Evaluates a polynomial at a single point using Estrin's scheme and returns the result.
+///
+/// Estrin's scheme splits `p(x)` into pairs of adjacent coefficients such that
+/// `p(x) = q(x^2)`, where coefficients of `q` are `p_{2i} + p_{2i + 1} * x`, and then evaluates
+/// `q` at `x^2` in the same way until a single coefficient remains. Coefficients at each level
+/// are independent of each other, and thus, when `concurrent` feature is enabled, they are
+/// computed in multiple threads.
+///
+/// The result is the same as the one returned from [eval_horner()].
+///
+/// # Examples
+/// ```
+/// # use winter_math::polynom::*;
+/// # use winter_math::{fields::{f128::BaseElement}, FieldElement};
+/// // define polynomial: f(x) = 3 * x^2 + 2 * x + 1
+/// let p = (1u32..4).map(BaseElement::from).collect::<Vec<_>>();
+///
+/// // evaluate the polynomial at point 4
+/// let x = BaseElement::new(4);
+/// assert_eq!(BaseElement::new(57), eval_estrin(&p, x));
+/// ```
+pub fn eval_estrin<B, E>(p: &[B], x: E) -> E
+where
+    B: FieldElement,
+    E: FieldElement + From<B>,
+{
+    if p.len() <= 1 {
+        return p.first().map_or(E::ZERO, |&coeff| E::from(coeff));
+    }
+
+    let mut values = combine_coefficient_pairs(p, x);
+    let mut x = x.square();
+    while values.len() > 1 {
+        values = combine_coefficient_pairs(&values, x);
+        x = x.square();
+    }
+    values[0]
+}
+
 /// Evaluates a polynomial at multiple points and returns a vector of results.
 ///
 /// Evaluates polynomial `p` at all coordinates in `xs` slice by repeatedly invoking
@@ -83,6 +179,125 @@ where
     xs.iter().map(|x| eval(p, *x)).collect()
 }
 
+/// Evaluates at a single point the polynomial which takes the specified values over a
+/// multiplicative subgroup, without interpolating the polynomial.
+///
+/// The polynomial $p$ is the unique polynomial of degree smaller than $n$ such that
+/// $p(\omega^i) = v_i$, where $n$ is the number of `values` and $\omega$ is the generator of the
+/// multiplicative subgroup of size $n$ in the base field. The polynomial is evaluated at `x` using
+/// the barycentric form of Lagrange interpolation over the subgroup:
+/// $$
+/// p(x) = \frac{x^n - 1}{n} \cdot \sum_{i=0}^{n-1}{\frac{v_i \cdot \omega^i}{x - \omega^i}}
+/// $$
+/// This requires $O(n)$ operations and a single inversion, compared to $O(n \log n)$ operations
+/// needed to interpolate the polynomial via inverse FFT. Values taken over a coset of the
+/// subgroup can be handled by scaling `x` by the inverse of the coset offset.
+///
+/// # Panics
+/// Panics if the number of values is not a power of two or is greater than the largest
+/// multiplicative subgroup of the base field.
+///
+/// # Examples
+/// ```
+/// # use winter_math::polynom::*;
+/// # use winter_math::{fft, fields::{f128::BaseElement}, FieldElement};
+/// # use rand_utils::rand_vector;
+/// let values: Vec<BaseElement> = rand_vector(16);
+///
+/// // interpolate the values into a polynomial over the subgroup of size 16
+/// let mut p = values.clone();
+/// fft::interpolate_poly(&mut p, &fft::get_inv_twiddles::<BaseElement>(16));
+///
+/// let x = BaseElement::new(12345);
+/// assert_eq!(eval(&p, x), eval_lagrange_subgroup(&values, x));
+/// ```
+pub fn eval_lagrange_subgroup<B, E>(values: &[B], x: E) -> E
+where
+    B: FieldElement,
+    E: FieldElement<BaseField = B::BaseField> + ExtensionOf<B>,
+{
+    let n = values.len();
+    assert!(n.is_power_of_two(), "number of values must be a power of two");
+    if n == 1 {
+        return E::from(values[0]);
+    }
+    let omega = B::BaseField::get_root_of_unity(n.ilog2());
+
+    // accumulate the sum as a single fraction so that only one inversion is required; if x is in
+    // the subgroup, return the value at x directly
+    let mut numerator = E::ZERO;
+    let mut denominator = E::ONE;
+    let mut omega_i = B::BaseField::ONE;
+    for &value in values {
+        let term = x - E::from(omega_i);
+        if term == E::ZERO {
+            return E::from(value);
+        }
+        numerator = numerator * term + E::from(value).mul_base(omega_i) * denominator;
+        denominator *= term;
+        omega_i *= omega;
+    }
+
+    let scale = (x.exp((n as u64).into()) - E::ONE) / E::from(n as u64);
+    scale * numerator / denominator
+}
+
+// VANISHING POLYNOMIAL
+// ================================================================================================
+
+/// Returns the vanishing polynomial of a multiplicative subgroup of the specified size.
+///
+/// The returned polynomial is $x^n - 1$, where $n$ is `domain_size`; this polynomial evaluates
+/// to zero at every element of the multiplicative subgroup of size $n$ (e.g., the trace domain),
+/// and at no other point. The polynomial is returned in reverse coefficient form, and thus, has
+/// $n + 1$ coefficients.
+///
+/// # Panics
+/// Panics if `domain_size` is not a power of two.
+///
+/// # Examples
+/// ```
+/// # use winter_math::polynom::*;
+/// # use winter_math::{fields::{f128::BaseElement}, FieldElement, StarkField};
+/// let p = vanishing_poly::<BaseElement>(8);
+/// assert_eq!(9, p.len());
+///
+/// // the polynomial vanishes over the subgroup of size 8
+/// let g = BaseElement::get_root_of_unity(3);
+/// assert_eq!(BaseElement::ZERO, eval(&p, g));
+/// ```
+pub fn vanishing_poly<E: FieldElement>(domain_size: usize) -> Vec<E> {
+    assert!(domain_size.is_power_of_two(), "domain size must be a power of two");
+    let mut result = vec![E::ZERO; domain_size + 1];
+    result[0] = -E::ONE;
+    result[domain_size] = E::ONE;
+    result
+}
+
+/// Evaluates the vanishing polynomial of a multiplicative subgroup of size `n` at `x`.
+///
+/// Computes $x^n - 1$ via $\log_2 n$ squarings, which is the same as (but is much faster than)
+/// evaluating the polynomial returned by [vanishing_poly()] at `x`.
+///
+/// # Panics
+/// Panics if `n` is not a power of two.
+///
+/// # Examples
+/// ```
+/// # use winter_math::polynom::*;
+/// # use winter_math::{fields::{f128::BaseElement}, FieldElement};
+/// let x = BaseElement::new(12345);
+/// assert_eq!(eval(&vanishing_poly::<BaseElement>(1024), x), eval_vanishing(x, 1024));
+/// ```
+pub fn eval_vanishing<E: FieldElement>(x: E, n: usize) -> E {
+    assert!(n.is_power_of_two(), "domain size must be a power of two");
+    let mut result = x;
+    for _ in 0..n.ilog2() {
+        result = result.square();
+    }
+    result - E::ONE
+}
+
 // POLYNOMIAL INTERPOLATION
 // ================================================================================================
 
@@ -296,6 +511,9 @@ where
 /// polynomial will be in the coefficient form as well. The length of the returned vector
 /// will be a.len() + b.len() - 1.
 ///
+/// Small polynomials are multiplied using the schoolbook method; once both operands are
+/// sufficiently large, multiplication is done via FFT (see [mul_fft()]).
+///
 /// # Examples
 /// ```
 /// # use winter_math::polynom::*;
@@ -318,15 +536,71 @@ pub fn mul<E>(a: &[E], b: &[E]) -> Vec<E>
 where
     E: FieldElement,
 {
+    // for small operands, the overhead of FFT is not worth it; we also fall back onto schoolbook
+    // multiplication if the base field does not have a large enough multiplicative subgroup
     let result_len = a.len() + b.len() - 1;
-    let mut result = E::zeroed_vector(result_len);
-    for i in 0..a.len() {
-        for j in 0..b.len() {
-            let s = a[i] * b[j];
-            result[i + j] += s;
-        }
+    if a.len().min(b.len()) < FFT_MUL_THRESHOLD
+        || result_len.next_power_of_two().ilog2() > E::BaseField::TWO_ADICITY
+    {
+        mul_schoolbook(a, b)
+    } else {
+        mul_fft(a, b)
     }
-    result
+}
+
+/// Returns a polynomial resulting from multiplying two polynomials together using FFT.
+///
+/// Polynomials `a` and `b` are expected to be in the coefficient form, and the returned
+/// polynomial will be in the coefficient form as well. The length of the returned vector
+/// will be a.len() + b.len() - 1.
+///
+/// Both polynomials are evaluated over a domain of the smallest power of two size which can
+/// fit the result, the evaluations are multiplied pointwise, and the product is interpolated
+/// back into the coefficient form. The result is exactly the same as for [mul()], but for large
+/// operands this is much faster.
+///
+/// When `concurrent` feature is enabled, the evaluations and the pointwise multiplication are
+/// done in multiple threads.
+///
+/// # Panics
+/// Panics if the base field of `E` does not contain a multiplicative subgroup of size
+/// (a.len() + b.len() - 1).next_power_of_two().
+///
+/// # Examples
+/// ```
+/// # use winter_math::polynom::*;
+/// # use winter_math::{fields::{f128::BaseElement}, FieldElement};
+/// # use rand_utils::rand_vector;
+/// let p1: Vec<BaseElement> = rand_vector(300);
+/// let p2: Vec<BaseElement> = rand_vector(200);
+///
+/// let result = mul_fft(&p1, &p2);
+/// assert_eq!(499, result.len());
+/// assert_eq!(mul(&p1, &p2), result);
+/// ```
+pub fn mul_fft<E>(a: &[E], b: &[E]) -> Vec<E>
+where
+    E: FieldElement,
+{
+    let result_len = a.len() + b.len() - 1;
+    let domain_size = result_len.next_power_of_two().max(2);
+
+    let mut a_evals = E::zeroed_vector(domain_size);
+    a_evals[..a.len()].copy_from_slice(a);
+    let mut b_evals = E::zeroed_vector(domain_size);
+    b_evals[..b.len()].copy_from_slice(b);
+
+    let twiddles = fft::get_twiddles::<E::BaseField>(domain_size);
+    fft::evaluate_poly(&mut a_evals, &twiddles);
+    fft::evaluate_poly(&mut b_evals, &twiddles);
+
+    iter_mut!(a_evals).zip(&b_evals).for_each(|(a, &b)| *a *= b);
+
+    let inv_twiddles = fft::get_inv_twiddles::<E::BaseField>(domain_size);
+    fft::interpolate_poly(&mut a_evals, &inv_twiddles);
+
+    a_evals.truncate(result_len);
+    a_evals
 }
 
 /// Returns a polynomial resulting from multiplying a given polynomial by a scalar value.
@@ -554,6 +828,11 @@ where
 
 /// Returns the degree of the provided polynomial.
 ///
+/// The degree is the index of the highest non-zero coefficient; trailing zero coefficients
+/// (i.e., ZERO coefficients of the highest powers of `x`) are ignored. For the zero polynomial
+/// (including an empty slice), 0 is returned, and thus, the zero polynomial cannot be
+/// distinguished from a constant polynomial by this function alone.
+///
 /// If the size of the provided slice is much larger than the degree of the polynomial (i.e.,
 /// a large number of leading coefficients is ZERO), this operation can be quite inefficient.
 ///
@@ -562,6 +841,7 @@ where
 /// # use winter_math::polynom::*;
 /// # use winter_math::{fields::{f128::BaseElement}, FieldElement};
 /// assert_eq!(0, degree_of::<BaseElement>(&[]));
+/// assert_eq!(0, degree_of(&[BaseElement::ZERO, BaseElement::ZERO]));
 /// assert_eq!(0, degree_of(&[BaseElement::ONE]));
 /// assert_eq!(1, degree_of(&[BaseElement::ONE, BaseElement::new(2)]));
 /// assert_eq!(1, degree_of(&[BaseElement::ONE, BaseElement::new(2), BaseElement::ZERO]));
@@ -623,6 +903,45 @@ where
 
 // HELPER FUNCTIONS
 // ================================================================================================
+
+/// Multiplies two polynomials together using the schoolbook method.
+fn mul_schoolbook<E>(a: &[E], b: &[E]) -> Vec<E>
+where
+    E: FieldElement,
+{
+    let result_len = a.len() + b.len() - 1;
+    let mut result = E::zeroed_vector(result_len);
+    for i in 0..a.len() {
+        for j in 0..b.len() {
+            let s = a[i] * b[j];
+            result[i + j] += s;
+        }
+    }
+    result
+}
+
+/// Combines pairs of adjacent coefficients of `p` into coefficients `p_{2i} + p_{2i + 1} * x`;
+/// if the number of coefficients is odd, the last coefficient is carried over as is.
+fn combine_coefficient_pairs<B, E>(p: &[B], x: E) -> Vec<E>
+where
+    B: FieldElement,
+    E: FieldElement + From<B>,
+{
+    #[cfg(not(feature = "concurrent"))]
+    let pairs = p.chunks(2);
+
+    #[cfg(feature = "concurrent")]
+    let pairs = p.par_chunks(2).with_min_len(ESTRIN_MIN_BATCH_SIZE);
+
+    pairs
+        .map(|pair| match pair {
+            [c0, c1] => E::from(*c0) + E::from(*c1) * x,
+            [c0] => E::from(*c0),
+            _ => unreachable!("coefficients are combined in pairs"),
+        })
+        .collect()
+}
+
 fn get_zero_roots<E: FieldElement>(xs: &[E]) -> Vec<E> {
     let mut result = unsafe { utils::uninit_vector(xs.len() + 1) };
     fill_zero_roots(xs, &mut result);
@@ -3,11 +3,12 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::remove_leading_zeros;
+use super::{degree_of, remove_leading_zeros};
 use crate::{
-    field::{f128::BaseElement, FieldElement, StarkField},
+    field::{f128::BaseElement, f64, FieldElement, QuadExtension, StarkField},
     utils::get_power_series,
 };
+use rand_utils::rand_vector;
 use utils::collections::Vec;
 
 #[test]
@@ -37,6 +38,91 @@ fn eval() {
     assert_eq!(poly[0] + poly[1] * x + poly[2] * x2 + poly[3] * x3, super::eval(&poly, x));
 }
 
+#[test]
+fn eval_estrin() {
+    let x = BaseElement::from(11269864713250585702u128);
+
+    // Estrin's scheme returns exactly the same results as Horner's method for polynomials of
+    // all lengths, including lengths which are not powers of two
+    let poly: Vec<BaseElement> = rand_vector(300);
+    for n in 0..poly.len() {
+        assert_eq!(super::eval_horner(&poly[..n], x), super::eval_estrin(&poly[..n], x));
+    }
+
+    // the same holds for polynomials evaluated at points in an extension field
+    let x = QuadExtension::<BaseElement>::new(x, BaseElement::from(42u8));
+    for n in [0, 1, 2, 7, 64, 299] {
+        assert_eq!(super::eval_horner(&poly[..n], x), super::eval_estrin(&poly[..n], x));
+    }
+
+    // polynomials of high degree are evaluated via Estrin's scheme when `concurrent` feature is
+    // enabled, and via Horner's method otherwise
+    let poly: Vec<BaseElement> = rand_vector((1 << 15) + 3);
+    let x = rand_vector::<BaseElement>(1)[0];
+    let expected = super::eval_horner(&poly, x);
+    assert_eq!(expected, super::eval_estrin(&poly, x));
+    assert_eq!(expected, super::eval(&poly, x));
+}
+
+#[test]
+fn eval_lagrange_subgroup() {
+    // a single value is a constant polynomial
+    let value = BaseElement::new(42);
+    assert_eq!(value, super::eval_lagrange_subgroup(&[value], BaseElement::new(7)));
+
+    for n in [2, 8, 32] {
+        let values: Vec<BaseElement> = rand_vector(n);
+
+        // build the polynomial using generic Lagrange interpolation over the subgroup
+        let omega = BaseElement::get_root_of_unity(n.ilog2());
+        let xs = get_power_series(omega, n);
+        let poly = super::interpolate(&xs, &values, false);
+
+        // evaluations at points outside of the subgroup must match
+        for x in rand_vector::<BaseElement>(4) {
+            assert_eq!(super::eval(&poly, x), super::eval_lagrange_subgroup(&values, x));
+        }
+
+        // evaluations at points in the subgroup must return the values
+        for (&x, &value) in xs.iter().zip(values.iter()) {
+            assert_eq!(value, super::eval_lagrange_subgroup(&values, x));
+        }
+    }
+
+    // values in the base field evaluated at a point in the extension field
+    let values: Vec<f64::BaseElement> = rand_vector(16);
+    let omega = f64::BaseElement::get_root_of_unity(4);
+    let poly = super::interpolate(&get_power_series(omega, 16), &values, false);
+    let x: QuadExtension<f64::BaseElement> = rand_vector(1)[0];
+    assert_eq!(super::eval(&poly, x), super::eval_lagrange_subgroup(&values, x));
+}
+
+#[test]
+fn vanishing_poly() {
+    for log_n in [1, 3, 6] {
+        let n = 1 << log_n;
+        let poly = super::vanishing_poly::<BaseElement>(n);
+        assert_eq!(n, degree_of(&poly));
+
+        // the polynomial vanishes on the domain
+        let g = BaseElement::get_root_of_unity(log_n);
+        for x in get_power_series(g, n) {
+            assert_eq!(BaseElement::ZERO, super::eval(&poly, x));
+            assert_eq!(BaseElement::ZERO, super::eval_vanishing(x, n));
+        }
+
+        // fast evaluation matches evaluation of the coefficients outside of the domain
+        for x in rand_vector::<BaseElement>(4) {
+            assert_eq!(super::eval(&poly, x), super::eval_vanishing(x, n));
+        }
+    }
+
+    // evaluation at a point in the extension field
+    let poly = super::vanishing_poly::<f64::BaseElement>(32);
+    let x: QuadExtension<f64::BaseElement> = rand_vector(1)[0];
+    assert_eq!(super::eval(&poly, x), super::eval_vanishing(x, 32));
+}
+
 #[test]
 fn add() {
     let poly1: [BaseElement; 3] = [
@@ -131,6 +217,30 @@ fn mul() {
     assert_eq!(pr, super::mul(&poly1, &poly2[..2]));
 }
 
+#[test]
+fn mul_fft() {
+    // operand lengths span the crossover between schoolbook and FFT-based multiplication, and
+    // include operands of unequal length
+    let t = super::FFT_MUL_THRESHOLD;
+    let lengths = [1, 2, t - 1, t, t + 1, 2 * t + 3];
+    for &a_len in lengths.iter() {
+        for &b_len in lengths.iter() {
+            let a: Vec<BaseElement> = rand_vector(a_len);
+            let b: Vec<BaseElement> = rand_vector(b_len);
+            let expected = super::mul_schoolbook(&a, &b);
+            assert_eq!(expected, super::mul_fft(&a, &b));
+            assert_eq!(expected, super::mul(&a, &b));
+        }
+    }
+
+    // operands in an extension field
+    let a: Vec<QuadExtension<f64::BaseElement>> = rand_vector(3 * t);
+    let b: Vec<QuadExtension<f64::BaseElement>> = rand_vector(t + 5);
+    let expected = super::mul_schoolbook(&a, &b);
+    assert_eq!(expected, super::mul_fft(&a, &b));
+    assert_eq!(expected, super::mul(&a, &b));
+}
+
 #[test]
 fn div() {
     let poly1 = vec![
@@ -235,3 +345,39 @@ fn syn_div() {
     let result = super::syn_div(&poly, 4, root.exp(4));
     assert_eq!(poly, remove_leading_zeros(&super::mul(&result, &z_poly)));
 }
+
+#[test]
+fn degree_of_poly() {
+    // ----- zero polynomial ----------------------------------------------------------------------
+    assert_eq!(0, degree_of::<BaseElement>(&[]));
+    assert_eq!(0, degree_of(&[BaseElement::ZERO; 8]));
+
+    // ----- constant polynomial ------------------------------------------------------------------
+    assert_eq!(0, degree_of(&[BaseElement::from(5u8)]));
+    let mut poly = vec![BaseElement::ZERO; 8];
+    poly[0] = BaseElement::from(5u8);
+    assert_eq!(0, degree_of(&poly));
+
+    // ----- high-degree polynomial with trailing zeros -------------------------------------------
+    let mut poly = rand_vector::<BaseElement>(1000);
+    poly[999] = BaseElement::ONE;
+    assert_eq!(999, degree_of(&poly));
+    poly.resize(1024, BaseElement::ZERO);
+    assert_eq!(999, degree_of(&poly));
+
+    // zero coefficients below the highest non-zero coefficient do not affect the degree
+    poly[500..999].fill(BaseElement::ZERO);
+    assert_eq!(999, degree_of(&poly));
+
+    // interpolating values of a degree 7 polynomial over a larger domain yields trailing zeros
+    let root = BaseElement::get_root_of_unity(5);
+    let xs = get_power_series(root, 32);
+    let p = rand_vector::<BaseElement>(7)
+        .into_iter()
+        .chain([BaseElement::ONE])
+        .collect::<Vec<_>>();
+    let ys = super::eval_many(&p, &xs);
+    let poly = super::interpolate(&xs, &ys, false);
+    assert_eq!(32, poly.len());
+    assert_eq!(7, degree_of(&poly));
+}
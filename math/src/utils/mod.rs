@@ -9,6 +9,9 @@ use utils::{batch_iter_mut, collections::Vec, iter_mut, uninit_vector};
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
 
+mod prng;
+pub(crate) use prng::fill_random;
+
 // MATH FUNCTIONS
 // ================================================================================================
 
@@ -0,0 +1,219 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use utils::Randomizable;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of bytes in a single ChaCha20 block.
+const BLOCK_SIZE: usize = 64;
+
+/// ChaCha20 constant "expand 32-byte k" as little-endian words.
+const SIGMA: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+// SEED EXPANDER
+// ================================================================================================
+
+/// Deterministically expands a 32-byte seed into a stream of pseudo-random bytes.
+///
+/// The stream is the ChaCha20 keystream keyed by the seed, with zero nonce and the block counter
+/// starting at zero.
+pub struct SeedExpander {
+    key: [u32; 8],
+    counter: u32,
+    block: [u8; BLOCK_SIZE],
+    offset: usize,
+}
+
+impl SeedExpander {
+    /// Returns a new expander keyed by the specified seed.
+    pub fn new(seed: [u8; 32]) -> Self {
+        let mut key = [0u32; 8];
+        for (word, bytes) in key.iter_mut().zip(seed.chunks(4)) {
+            *word = u32::from_le_bytes(bytes.try_into().unwrap());
+        }
+        SeedExpander {
+            key,
+            counter: 0,
+            block: [0; BLOCK_SIZE],
+            offset: BLOCK_SIZE,
+        }
+    }
+
+    /// Fills `dest` with the next bytes of the stream.
+    pub fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let mut filled = 0;
+        while filled < dest.len() {
+            if self.offset == BLOCK_SIZE {
+                self.block = chacha20_block(&self.key, self.counter, &[0; 3]);
+                self.counter = self.counter.checked_add(1).expect("seed expander exhausted");
+                self.offset = 0;
+            }
+            let n = (dest.len() - filled).min(BLOCK_SIZE - self.offset);
+            dest[filled..filled + n].copy_from_slice(&self.block[self.offset..self.offset + n]);
+            self.offset += n;
+            filled += n;
+        }
+    }
+}
+
+/// Fills `result` with values drawn from the stream expanded from the specified seed.
+///
+/// Values are read from consecutive `R::VALUE_SIZE`-byte chunks of the stream; chunks which do
+/// not encode a valid value are rejected, and thus, the values are uniformly distributed.
+pub fn fill_random<R: Randomizable>(result: &mut [R], seed: [u8; 32]) {
+    let mut expander = SeedExpander::new(seed);
+    let mut bytes = [0u8; BLOCK_SIZE];
+    let bytes = &mut bytes[..R::VALUE_SIZE];
+    for value in result.iter_mut() {
+        *value = loop {
+            expander.fill_bytes(bytes);
+            if let Some(value) = R::from_random_bytes(bytes) {
+                break value;
+            }
+        };
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Computes a single ChaCha20 block as specified in RFC 8439.
+fn chacha20_block(key: &[u32; 8], counter: u32, nonce: &[u32; 3]) -> [u8; BLOCK_SIZE] {
+    let mut state = [0u32; 16];
+    state[..4].copy_from_slice(&SIGMA);
+    state[4..12].copy_from_slice(key);
+    state[12] = counter;
+    state[13..].copy_from_slice(nonce);
+
+    let mut working = state;
+    for _ in 0..10 {
+        quarter_round(&mut working, 0, 4, 8, 12);
+        quarter_round(&mut working, 1, 5, 9, 13);
+        quarter_round(&mut working, 2, 6, 10, 14);
+        quarter_round(&mut working, 3, 7, 11, 15);
+        quarter_round(&mut working, 0, 5, 10, 15);
+        quarter_round(&mut working, 1, 6, 11, 12);
+        quarter_round(&mut working, 2, 7, 8, 13);
+        quarter_round(&mut working, 3, 4, 9, 14);
+    }
+
+    let mut result = [0u8; BLOCK_SIZE];
+    for (i, bytes) in result.chunks_mut(4).enumerate() {
+        bytes.copy_from_slice(&working[i].wrapping_add(state[i]).to_le_bytes());
+    }
+    result
+}
+
+#[inline(always)]
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fields::{f62, f64, QuadExtension},
+        FieldElement, StarkField,
+    };
+
+    #[test]
+    fn chacha20_block_test_vector() {
+        // test vector from section 2.3.2 of RFC 8439
+        let mut key = [0u32; 8];
+        for (i, word) in key.iter_mut().enumerate() {
+            let i = (i * 4) as u8;
+            *word = u32::from_le_bytes([i, i + 1, i + 2, i + 3]);
+        }
+        let nonce = [0x09000000, 0x4a000000, 0x00000000];
+        let expected = [
+            0x10, 0xf1, 0xe7, 0xe4, 0xd1, 0x3b, 0x59, 0x15, 0x50, 0x0f, 0xdd, 0x1f, 0xa3, 0x20,
+            0x71, 0xc4, 0xc7, 0xd1, 0xf4, 0xc7, 0x33, 0xc0, 0x68, 0x03, 0x04, 0x22, 0xaa, 0x9a,
+            0xc3, 0xd4, 0x6c, 0x4e, 0xd2, 0x82, 0x64, 0x46, 0x07, 0x9f, 0xaa, 0x09, 0x14, 0xc2,
+            0xd7, 0x05, 0xd9, 0x8b, 0x02, 0xa2, 0xb5, 0x12, 0x9c, 0xd1, 0xde, 0x16, 0x4e, 0xb9,
+            0xcb, 0xd0, 0x83, 0xe8, 0xa2, 0x50, 0x3c, 0x4e,
+        ];
+        assert_eq!(expected, chacha20_block(&key, 1, &nonce));
+    }
+
+    #[test]
+    fn seed_expander_stream() {
+        // the stream does not depend on how it is split into reads
+        let mut expected = [0u8; 200];
+        SeedExpander::new([7; 32]).fill_bytes(&mut expected);
+
+        let mut expander = SeedExpander::new([7; 32]);
+        let mut actual = [0u8; 200];
+        for chunk in actual.chunks_mut(13) {
+            expander.fill_bytes(chunk);
+        }
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn random_vec_deterministic() {
+        let seed = [42; 32];
+        let a = f64::BaseElement::random_vec(1000, seed);
+        assert_eq!(1000, a.len());
+        assert_eq!(a, f64::BaseElement::random_vec(1000, seed));
+
+        // a shorter vector is a prefix of a longer one
+        assert_eq!(a[..100], f64::BaseElement::random_vec(100, seed));
+
+        // arrays contain the same values as vectors
+        let array = f64::BaseElement::random_array::<16>(seed);
+        assert_eq!(a[..16], array);
+
+        // different seeds result in different values
+        let mut other_seed = seed;
+        other_seed[31] ^= 1;
+        assert_ne!(a, f64::BaseElement::random_vec(1000, other_seed));
+
+        // the same holds for extension fields
+        type QuadElement = QuadExtension<f64::BaseElement>;
+        let a = QuadElement::random_vec(100, seed);
+        assert_eq!(a, QuadElement::random_vec(100, seed));
+        assert_ne!(a, QuadElement::random_vec(100, other_seed));
+    }
+
+    #[test]
+    fn random_vec_uniform() {
+        // values in f62 are sampled with a high rejection rate, and values in f64 with a very low
+        // one; in both cases, values should be uniformly distributed over the field
+        assert_uniform(&f62::BaseElement::random_vec(1 << 14, [1; 32]), |e| {
+            e.as_int() as u128 * 16 / f62::BaseElement::MODULUS as u128
+        });
+        assert_uniform(&f64::BaseElement::random_vec(1 << 14, [2; 32]), |e| {
+            e.as_int() as u128 * 16 / f64::BaseElement::MODULUS as u128
+        });
+    }
+
+    /// Asserts that values are uniformly distributed among 16 buckets using a chi-squared test.
+    fn assert_uniform<E, F: Fn(&E) -> u128>(values: &[E], bucket: F) {
+        let mut counts = [0usize; 16];
+        for value in values {
+            counts[bucket(value) as usize] += 1;
+        }
+
+        let expected = values.len() as f64 / 16.0;
+        let chi_squared: f64 =
+            counts.iter().map(|&count| (count as f64 - expected).powi(2) / expected).sum();
+
+        // the critical value for 15 degrees of freedom at significance level 0.001 is 37.7
+        assert!(chi_squared < 37.7, "values are not uniform: {counts:?}");
+    }
+}
@@ -0,0 +1,38 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use math::fields::f64::BaseElement;
+use rand_utils::rand_vector;
+use std::time::Duration;
+use winter_prover::matrix::{ColMatrix, InterpolationMethod};
+
+// CONSTANTS
+// ================================================================================================
+
+const NUM_ROWS: [usize; 5] = [8, 16, 32, 64, 128];
+const NUM_COLUMNS: usize = 32;
+
+fn interpolate_columns(c: &mut Criterion) {
+    let mut group = c.benchmark_group("matrix_interpolate_columns");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(5));
+
+    for &num_rows in NUM_ROWS.iter() {
+        let columns: Vec<Vec<BaseElement>> =
+            (0..NUM_COLUMNS).map(|_| rand_vector(num_rows)).collect();
+        let matrix = ColMatrix::new(columns);
+        group.bench_function(BenchmarkId::new("fft", num_rows), |bench| {
+            bench.iter(|| matrix.interpolate_columns_with(InterpolationMethod::Fft));
+        });
+        group.bench_function(BenchmarkId::new("barycentric", num_rows), |bench| {
+            bench.iter(|| matrix.interpolate_columns_with(InterpolationMethod::Barycentric));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(interpolation_group, interpolate_columns);
+criterion_main!(interpolation_group);
@@ -3,8 +3,9 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use criterion::black_box;
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
-use math::{fft, fields::f64::BaseElement, StarkField};
+use math::{fft, fields::f64::BaseElement, FieldElement, StarkField};
 use rand_utils::rand_vector;
 use std::time::Duration;
 use winter_prover::{
@@ -19,6 +20,10 @@ const SIZE: usize = 524_288;
 const BLOWUP_FACTOR: [usize; 3] = [2, 4, 8];
 const NUM_POLYS: [usize; 3] = [32, 64, 96];
 
+const FRAME_TRACE_LENGTH: usize = 65_536;
+const FRAME_BLOWUP_FACTOR: usize = 8;
+const WIDE_NUM_POLYS: [usize; 2] = [64, 255];
+
 fn evaluate_columns(c: &mut Criterion) {
     let mut group = c.benchmark_group("matrix_evaluate_columns");
     group.sample_size(10);
@@ -68,6 +73,51 @@ fn evaluate_matrix(c: &mut Criterion) {
     group.finish();
 }
 
+fn read_frames(c: &mut Criterion) {
+    let mut group = c.benchmark_group("matrix_read_frames");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(10));
+
+    let lde_size = FRAME_TRACE_LENGTH * FRAME_BLOWUP_FACTOR;
+    for &num_poly in WIDE_NUM_POLYS.iter() {
+        let polys: Vec<Vec<BaseElement>> =
+            (0..num_poly).map(|_| rand_vector(FRAME_TRACE_LENGTH)).collect();
+        let polys = ColMatrix::new(polys);
+
+        // the prover evaluates trace polynomials directly into a row-major matrix, so that
+        // evaluation frames are read from contiguous memory during constraint evaluation
+        let row_matrix = RowMatrix::evaluate_polys::<8>(&polys, FRAME_BLOWUP_FACTOR);
+        let twiddles = fft::get_twiddles::<BaseElement>(FRAME_TRACE_LENGTH);
+        let domain =
+            StarkDomain::from_twiddles(twiddles, FRAME_BLOWUP_FACTOR, BaseElement::GENERATOR);
+        let col_matrix = polys.evaluate_columns_over(&domain);
+
+        let mut current = vec![BaseElement::ZERO; num_poly];
+        let mut next = vec![BaseElement::ZERO; num_poly];
+        group.bench_function(BenchmarkId::new("row_major", num_poly), |bench| {
+            bench.iter(|| {
+                for step in 0..lde_size {
+                    let next_step = (step + FRAME_BLOWUP_FACTOR) % lde_size;
+                    current.copy_from_slice(row_matrix.row(step));
+                    next.copy_from_slice(row_matrix.row(next_step));
+                    black_box((&current, &next));
+                }
+            });
+        });
+        group.bench_function(BenchmarkId::new("column_major", num_poly), |bench| {
+            bench.iter(|| {
+                for step in 0..lde_size {
+                    let next_step = (step + FRAME_BLOWUP_FACTOR) % lde_size;
+                    col_matrix.read_row_into(step, &mut current);
+                    col_matrix.read_row_into(next_step, &mut next);
+                    black_box((&current, &next));
+                }
+            });
+        });
+    }
+    group.finish();
+}
+
 /// Benchmark parameters.
 struct BenchmarkParams {
     num_poly: usize,
@@ -80,5 +130,5 @@ impl std::fmt::Display for BenchmarkParams {
     }
 }
 
-criterion_group!(matrix_group, evaluate_columns, evaluate_matrix);
+criterion_group!(matrix_group, evaluate_columns, evaluate_matrix, read_frames);
 criterion_main!(matrix_group);
@@ -4,7 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use air::{
-    proof::{Commitments, Context, OodFrame, Queries, StarkProof},
+    proof::{get_fri_layer_seed, Commitments, Context, OodFrame, Queries, StarkProof},
     Air, ConstraintCompositionCoefficients, DeepCompositionCoefficients,
 };
 use core::marker::PhantomData;
@@ -19,12 +19,17 @@ use utils::iterators::*;
 // TYPES AND INTERFACES
 // ================================================================================================
 
-pub struct ProverChannel<'a, A, E, H, R>
+/// A channel which simulates interaction between the prover and the verifier.
+///
+/// Trace and constraint commitments are made with hash function `H`, while FRI layers are
+/// committed to with hash function `HF`, which defaults to `H`.
+pub struct ProverChannel<'a, A, E, H, R, HF = H>
 where
     A: Air,
     E: FieldElement<BaseField = A::BaseField>,
     H: ElementHasher<BaseField = A::BaseField>,
     R: RandomCoin<BaseField = E::BaseField, Hasher = H>,
+    HF: ElementHasher<BaseField = A::BaseField>,
 {
     air: &'a A,
     public_coin: R,
@@ -32,39 +37,54 @@ where
     commitments: Commitments,
     ood_frame: OodFrame,
     pow_nonce: u64,
+    metadata: Vec<u8>,
     _field_element: PhantomData<E>,
+    _fri_hasher: PhantomData<HF>,
 }
 
 // PROVER CHANNEL IMPLEMENTATION
 // ================================================================================================
 
-impl<'a, A, E, H, R> ProverChannel<'a, A, E, H, R>
+impl<'a, A, E, H, R, HF> ProverChannel<'a, A, E, H, R, HF>
 where
     A: Air,
     E: FieldElement<BaseField = A::BaseField>,
     H: ElementHasher<BaseField = A::BaseField>,
     R: RandomCoin<BaseField = A::BaseField, Hasher = H>,
+    HF: ElementHasher<BaseField = A::BaseField>,
 {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
-    /// Creates a new prover channel for the specified `air` and public inputs.
-    pub fn new(air: &'a A, mut pub_inputs_elements: Vec<A::BaseField>) -> Self {
-        let context = Context::new::<A::BaseField>(air.trace_info(), air.options().clone());
+    /// Creates a new prover channel for the specified `air`, public inputs, and metadata.
+    pub fn new(air: &'a A, mut pub_inputs_elements: Vec<A::BaseField>, metadata: &[u8]) -> Self {
+        let context = Context::new::<A::BaseField>(
+            air.trace_info(),
+            air.options().clone(),
+            air.context().frame_width(),
+        );
 
         // build a seed for the public coin; the initial seed is a hash of the proof context and
         // the public inputs, but as the protocol progresses, the coin will be reseeded with the
         // info sent to the verifier
         let mut coin_seed_elements = context.to_elements();
         coin_seed_elements.append(&mut pub_inputs_elements);
+        let mut public_coin = R::new(&coin_seed_elements);
+
+        // bind application-specific metadata (if any) to the proof before any randomness is drawn
+        if !metadata.is_empty() {
+            public_coin.reseed(H::hash(metadata));
+        }
 
         ProverChannel {
             air,
-            public_coin: RandomCoin::new(&coin_seed_elements),
+            public_coin,
             context,
             commitments: Commitments::default(),
             ood_frame: OodFrame::default(),
             pow_nonce: 0,
+            metadata: metadata.to_vec(),
             _field_element: PhantomData,
+            _fri_hasher: PhantomData,
         }
     }
 
@@ -92,8 +112,16 @@ where
 
     /// Saves the evaluations of constraint composition polynomial columns at the out-of-domain
     /// point. This also reseeds the public coin wit the hash of the evaluations.
+    ///
+    /// If the out-of-domain frame is thin, the evaluation of the last column is not saved as the
+    /// verifier recovers it from the out-of-domain trace frame; the public coin is still reseeded
+    /// with all evaluations.
     pub fn send_ood_constraint_evaluations(&mut self, evaluations: &[E]) {
-        self.ood_frame.set_constraint_evaluations(evaluations);
+        if self.air.options().has_thin_ood_frame() {
+            self.ood_frame.set_constraint_evaluations(&evaluations[..evaluations.len() - 1]);
+        } else {
+            self.ood_frame.set_constraint_evaluations(evaluations);
+        }
         self.public_coin.reseed(H::hash_elements(evaluations));
     }
 
@@ -136,14 +164,16 @@ where
     /// Returns a set of positions in the LDE domain against which the evaluations of trace and
     /// constraint composition polynomials should be queried.
     ///
-    /// The positions are drawn from the public coin uniformly at random. Duplicate positions
-    /// are removed from the returned vector.
+    /// The positions are drawn from the public coin uniformly at random using the query sampling
+    /// method specified in the proof options. Duplicate positions are removed from the returned
+    /// vector.
     pub fn get_query_positions(&mut self) -> Vec<usize> {
         let num_queries = self.context.options().num_queries();
         let lde_domain_size = self.context.lde_domain_size();
+        let query_sampling = self.context.options().query_sampling();
         let mut positions = self
             .public_coin
-            .draw_integers(num_queries, lde_domain_size, self.pow_nonce)
+            .draw_integers_with(num_queries, lde_domain_size, self.pow_nonce, query_sampling)
             .expect("failed to draw query position");
 
         // remove any duplicate positions from the list
@@ -156,8 +186,15 @@ where
     /// Determines a nonce, which when hashed with the current seed of the public coin results
     /// in a new seed with the number of leading zeros equal to the grinding_factor specified
     /// in the proof options.
+    ///
+    /// When grinding factor is zero, the proof-of-work search is skipped and the nonce is set
+    /// to zero.
     pub fn grind_query_seed(&mut self) {
         let grinding_factor = self.context.options().grinding_factor();
+        if grinding_factor == 0 {
+            self.pow_nonce = 0;
+            return;
+        }
 
         #[cfg(not(feature = "concurrent"))]
         let nonce = (1..u64::MAX)
@@ -195,6 +232,8 @@ where
             fri_proof,
             pow_nonce: self.pow_nonce,
             num_unique_queries: num_query_positions as u8,
+            metadata: self.metadata,
+            pub_inputs: Vec::new(),
         }
     }
 }
@@ -202,19 +241,25 @@ where
 // FRI PROVER CHANNEL IMPLEMENTATION
 // ================================================================================================
 
-impl<'a, A, E, H, R> fri::ProverChannel<E> for ProverChannel<'a, A, E, H, R>
+impl<'a, A, E, H, R, HF> fri::ProverChannel<E> for ProverChannel<'a, A, E, H, R, HF>
 where
     A: Air,
     E: FieldElement<BaseField = A::BaseField>,
     H: ElementHasher<BaseField = A::BaseField>,
     R: RandomCoin<BaseField = A::BaseField, Hasher = H>,
+    HF: ElementHasher<BaseField = A::BaseField>,
 {
-    type Hasher = H;
+    type Hasher = HF;
 
     /// Commits the prover to a FRI layer.
-    fn commit_fri_layer(&mut self, layer_root: H::Digest) {
-        self.commitments.add::<H>(&layer_root);
-        self.public_coin.reseed(layer_root);
+    ///
+    /// If FRI layers are committed to with a separate hash function, the public coin is reseeded
+    /// with a hash of the layer root rather than with the root itself.
+    fn commit_fri_layer(&mut self, layer_root: HF::Digest) {
+        self.commitments.add::<HF>(&layer_root);
+        let seed = get_fri_layer_seed::<H, HF>(&layer_root, self.context.options())
+            .expect("FRI layer root is not a valid digest of the commitment hash function");
+        self.public_coin.reseed(seed);
     }
 
     /// Returns a new alpha drawn from the public coin.
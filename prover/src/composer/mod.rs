@@ -4,13 +4,32 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{constraints::CompositionPoly, StarkDomain, TracePolyTable};
-use air::DeepCompositionCoefficients;
+use air::{DeepCompositionCoefficients, LdeDomainInfo};
 use math::{add_in_place, fft, mul_acc, polynom, ExtensionOf, FieldElement, StarkField};
 use utils::{collections::Vec, iter_mut};
 
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
 
+// DEEP COMPOSITION EVALUATIONS
+// ================================================================================================
+
+/// Evaluations of the DEEP composition polynomial over the LDE domain of a computation.
+///
+/// These are the evaluations which the prover feeds into FRI. They can be obtained via
+/// [Prover::export_deep_composition()](crate::Prover::export_deep_composition) in order to apply
+/// an alternative low-degree (proximity) test to them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeepCompositionEvaluations<E: FieldElement> {
+    /// Evaluations of the DEEP composition polynomial at all points of the LDE domain, in the
+    /// natural order of the domain.
+    pub evaluations: Vec<E>,
+    /// Description of the LDE domain over which the polynomial was evaluated.
+    pub domain: LdeDomainInfo<E::BaseField>,
+    /// Degree of the DEEP composition polynomial; this is always `trace_length - 2`.
+    pub degree: usize,
+}
+
 // DEEP COMPOSITION POLYNOMIAL
 // ================================================================================================
 pub struct DeepCompositionPoly<E: FieldElement> {
@@ -51,15 +70,15 @@ impl<E: FieldElement> DeepCompositionPoly<E> {
     /// Combines all trace polynomials into a single polynomial and saves the result into
     /// the DEEP composition polynomial. The combination is done as follows:
     ///
-    /// - Compute polynomials T'_i(x) = (T_i(x) - T_i(z)) / (x - z) and
-    ///   T''_i(x) = (T_i(x) - T_i(z * g)) / (x - z * g) for all i, where T_i(x) is a trace
-    ///   polynomial for column i.
-    /// - Then, combine together all T'_i(x) and T''_i(x) polynomials using a random linear
-    ///   combination as T(x) = sum((T'_i(x) + T''_i(x)) * cc_i) for all i, where cc_i is
-    ///   the coefficient for the random linear combination drawn from the public coin.
+    /// - Compute polynomials T^(k)_i(x) = (T_i(x) - T_i(z * g^k)) / (x - z * g^k) for all i and
+    ///   for all k in 0..frame_width, where T_i(x) is a trace polynomial for column i. By default,
+    ///   the frame width is 2, and thus, the trace polynomials are opened at z and z * g.
+    /// - Then, combine together all T^(k)_i(x) polynomials using a random linear combination as
+    ///   T(x) = sum((sum_k T^(k)_i(x)) * cc_i) for all i, where cc_i is the coefficient for the
+    ///   random linear combination drawn from the public coin.
     ///
-    /// Note that evaluations of T_i(z) and T_i(z * g) are passed in via the `ood_trace_state`
-    /// parameter.
+    /// Note that evaluations of T_i(z * g^k) are passed in via the `ood_trace_states` parameter,
+    /// one row per shift k.
     pub fn add_trace_polys(
         &mut self,
         trace_polys: TracePolyTable<E>,
@@ -67,15 +86,21 @@ impl<E: FieldElement> DeepCompositionPoly<E> {
     ) {
         assert!(self.coefficients.is_empty());
 
-        // compute a second out-of-domain point offset from z by exactly trace generator; this
-        // point defines the "next" computation state in relation to point z
+        // compute out-of-domain points offset from z by powers of the trace generator; the point
+        // z * g^k defines the computation state k rows after the state at point z
         let trace_length = trace_polys.poly_size();
         let g = E::from(E::BaseField::get_root_of_unity(trace_length.ilog2()));
-        let next_z = self.z * g;
+        let mut shifted_z = Vec::with_capacity(ood_trace_states.len());
+        let mut x = self.z;
+        for _ in 0..ood_trace_states.len() {
+            shifted_z.push(x);
+            x *= g;
+        }
 
-        // combine trace polynomials into 2 composition polynomials T'(x) and T''(x)
-        let mut t1_composition = E::zeroed_vector(trace_length);
-        let mut t2_composition = E::zeroed_vector(trace_length);
+        // combine trace polynomials into one composition polynomial per shift
+        let mut compositions = (0..ood_trace_states.len())
+            .map(|_| E::zeroed_vector(trace_length))
+            .collect::<Vec<_>>();
 
         // index of a trace polynomial; we declare it here so that we can maintain index continuity
         // across all trace segments
@@ -83,55 +108,35 @@ impl<E: FieldElement> DeepCompositionPoly<E> {
 
         // --- merge polynomials of the main trace segment ----------------------------------------
         for poly in trace_polys.main_trace_polys() {
-            // compute T'(x) = T(x) - T(z), multiply it by a pseudo-random coefficient,
-            // and add the result into composition polynomial
-            acc_trace_poly::<E::BaseField, E>(
-                &mut t1_composition,
-                poly,
-                ood_trace_states[0][i],
-                self.cc.trace[i],
-            );
-
-            // compute T''(x) = T(x) - T(z * g), multiply it by a pseudo-random coefficient,
-            // and add the result into composition polynomial
-            acc_trace_poly::<E::BaseField, E>(
-                &mut t2_composition,
-                poly,
-                ood_trace_states[1][i],
-                self.cc.trace[i],
-            );
+            // for each shift k, compute T(x) - T(z * g^k), multiply it by a pseudo-random
+            // coefficient, and add the result into the corresponding composition polynomial
+            for (composition, ood_state) in compositions.iter_mut().zip(ood_trace_states.iter()) {
+                acc_trace_poly::<E::BaseField, E>(
+                    composition,
+                    poly,
+                    ood_state[i],
+                    self.cc.trace[i],
+                );
+            }
 
             i += 1;
         }
 
         // --- merge polynomials of the auxiliary trace segments ----------------------------------
         for poly in trace_polys.aux_trace_polys() {
-            // compute T'(x) = T(x) - T(z), multiply it by a pseudo-random coefficient,
-            // and add the result into composition polynomial
-            acc_trace_poly::<E, E>(
-                &mut t1_composition,
-                poly,
-                ood_trace_states[0][i],
-                self.cc.trace[i],
-            );
-
-            // compute T''(x) = T(x) - T(z * g), multiply it by a pseudo-random coefficient,
-            // and add the result into composition polynomial
-            acc_trace_poly::<E, E>(
-                &mut t2_composition,
-                poly,
-                ood_trace_states[1][i],
-                self.cc.trace[i],
-            );
+            // for each shift k, compute T(x) - T(z * g^k), multiply it by a pseudo-random
+            // coefficient, and add the result into the corresponding composition polynomial
+            for (composition, ood_state) in compositions.iter_mut().zip(ood_trace_states.iter()) {
+                acc_trace_poly::<E, E>(composition, poly, ood_state[i], self.cc.trace[i]);
+            }
 
             i += 1;
         }
 
-        // divide the composition polynomials by (x - z) and (x - z * g), respectively,
-        // and add the resulting polynomials together; the output of this step
-        // is a single trace polynomial T(x) and deg(T(x)) = trace_length - 2.
-        let trace_poly =
-            merge_trace_compositions(vec![t1_composition, t2_composition], vec![self.z, next_z]);
+        // divide the composition polynomials by (x - z * g^k) for the respective k, and add the
+        // resulting polynomials together; the output of this step is a single trace polynomial
+        // T(x) and deg(T(x)) = trace_length - 2.
+        let trace_poly = merge_trace_compositions(compositions, shifted_z);
 
         // set the coefficients of the DEEP composition polynomial
         self.coefficients = trace_poly;
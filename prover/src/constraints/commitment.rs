@@ -51,7 +51,7 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> ConstraintComm
 
     /// Returns constraint evaluations at the specified positions along with Merkle authentication
     /// paths from the root of the commitment to these evaluations.
-    pub fn query(self, positions: &[usize]) -> Queries {
+    pub fn query(&self, positions: &[usize]) -> Queries {
         // build Merkle authentication paths to the leaves specified by positions
         let merkle_proof = self
             .commitment
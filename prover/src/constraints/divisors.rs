@@ -0,0 +1,125 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{evaluation_table::get_inv_divisor_evaluations, ConstraintDivisor, StarkDomain};
+use crate::ProverError;
+use air::Air;
+use math::StarkField;
+use utils::collections::Vec;
+
+// PRECOMPUTED DIVISORS
+// ================================================================================================
+
+/// Constraint divisors of a computation evaluated over its constraint evaluation domain.
+///
+/// Constraint divisors depend only on the structure of an AIR and the length of the execution
+/// trace, but not on the witness. Thus, when many proofs are generated for the same AIR and trace
+/// length, the divisors can be precomputed once and passed to
+/// [Prover::prove_with_divisors()](crate::Prover::prove_with_divisors) so that they are not
+/// re-evaluated for every proof.
+///
+/// The set contains the divisor of transition constraints as well as divisors of all assertions
+/// placed against the main trace segment. Divisors which are not in the set (e.g., divisors of
+/// assertions placed against auxiliary trace segments) are evaluated on the fly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrecomputedDivisors<B: StarkField> {
+    trace_length: usize,
+    ce_domain_size: usize,
+    domain_offset: B,
+    divisors: Vec<ConstraintDivisor<B>>,
+    inv_evaluations: Vec<Vec<B>>,
+}
+
+impl<B: StarkField> PrecomputedDivisors<B> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns constraint divisors of the specified AIR evaluated over its constraint evaluation
+    /// domain.
+    pub fn new<A: Air<BaseField = B>>(air: &A) -> Self {
+        let domain = StarkDomain::new(air);
+
+        // the transition constraint divisor always goes first; assertion divisors follow with
+        // duplicates removed, since many assertions usually share the same divisor
+        let mut divisors = vec![build_transition_divisor(air)];
+        for assertion in air.get_assertions() {
+            let divisor = ConstraintDivisor::from_assertion(&assertion, air.trace_length());
+            if !divisors.contains(&divisor) {
+                divisors.push(divisor);
+            }
+        }
+
+        let inv_evaluations = divisors
+            .iter()
+            .map(|divisor| get_inv_divisor_evaluations(divisor, &domain))
+            .collect();
+
+        Self {
+            trace_length: air.trace_length(),
+            ce_domain_size: domain.ce_domain_size(),
+            domain_offset: domain.offset(),
+            divisors,
+            inv_evaluations,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of divisors in this set.
+    pub fn num_divisors(&self) -> usize {
+        self.divisors.len()
+    }
+
+    /// Returns the length of the execution trace for which the divisors were computed.
+    pub fn trace_length(&self) -> usize {
+        self.trace_length
+    }
+
+    /// Checks that these divisors were computed for a computation with the same context as the
+    /// specified AIR.
+    ///
+    /// # Errors
+    /// Returns an error if the trace length, the constraint evaluation domain, or the transition
+    /// constraint divisor of the specified AIR differ from the ones the divisors were computed
+    /// for.
+    pub fn validate<A: Air<BaseField = B>>(&self, air: &A) -> Result<(), ProverError> {
+        let context = air.context();
+        if self.trace_length != air.trace_length()
+            || self.ce_domain_size != context.ce_domain_size()
+            || self.domain_offset != air.domain_offset()
+            || self.divisors[0] != build_transition_divisor(air)
+        {
+            return Err(ProverError::InconsistentPrecomputedDivisors);
+        }
+        Ok(())
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns inverse evaluations of the specified divisor over the constraint evaluation domain,
+    /// or None if the divisor is not in this set.
+    ///
+    /// The returned evaluations are cyclic: the evaluation at the i-th point of the domain is
+    /// located at position `i % len`.
+    pub(crate) fn get_inv_evaluations(&self, divisor: &ConstraintDivisor<B>) -> Option<&[B]> {
+        self.divisors
+            .iter()
+            .position(|d| d == divisor)
+            .map(|i| self.inv_evaluations[i].as_slice())
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Builds the transition constraint divisor for the specified AIR.
+fn build_transition_divisor<A: Air>(air: &A) -> ConstraintDivisor<A::BaseField> {
+    ConstraintDivisor::from_transition(
+        air.trace_length(),
+        air.context().num_transition_exemptions(),
+    )
+}
@@ -3,7 +3,9 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{CompositionPolyTrace, ConstraintDivisor, StarkDomain};
+use super::{
+    CompositionPolyTrace, ConstraintDivisor, EvaluationDomain, PrecomputedDivisors, StarkDomain,
+};
 use math::{batch_inversion, FieldElement, StarkField};
 use utils::{batch_iter_mut, collections::Vec, iter_mut, uninit_vector};
 
@@ -160,7 +162,13 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
     // --------------------------------------------------------------------------------------------
     /// Divides constraint evaluation columns by their respective divisor (in evaluation form) and
     /// combines the results into a single column.
-    pub fn combine(self) -> CompositionPolyTrace<E> {
+    ///
+    /// If `precomputed_divisors` are provided, evaluations of divisors found in this set are taken
+    /// from it instead of being computed.
+    pub fn combine(
+        self,
+        precomputed_divisors: Option<&PrecomputedDivisors<E::BaseField>>,
+    ) -> CompositionPolyTrace<E> {
         // allocate memory for the combined polynomial
         let mut combined_poly = E::zeroed_vector(self.num_rows());
 
@@ -169,7 +177,12 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
         // together into a single vector
         for (column, divisor) in self.evaluations.into_iter().zip(self.divisors.iter()) {
             // divide the column by the divisor and accumulate the result into combined_poly
-            acc_column(column, divisor, self.domain, &mut combined_poly);
+            match precomputed_divisors.and_then(|divisors| divisors.get_inv_evaluations(divisor)) {
+                Some(inv_evaluations) => {
+                    acc_column_precomputed(column, inv_evaluations, &mut combined_poly)
+                }
+                None => acc_column(column, divisor, self.domain, &mut combined_poly),
+            }
         }
 
         CompositionPolyTrace::new(combined_poly)
@@ -317,7 +330,7 @@ fn make_fragments<E: FieldElement>(
 fn acc_column<E: FieldElement>(
     column: Vec<E>,
     divisor: &ConstraintDivisor<E::BaseField>,
-    domain: &StarkDomain<E::BaseField>,
+    domain: &impl EvaluationDomain<E::BaseField>,
     result: &mut [E],
 ) {
     let numerator = divisor.numerator();
@@ -366,25 +379,65 @@ fn acc_column<E: FieldElement>(
     }
 }
 
+/// Divides column values by a divisor using precomputed inverse evaluations of the divisor, and
+/// accumulates the results into `result`.
+fn acc_column_precomputed<E: FieldElement>(
+    column: Vec<E>,
+    inv_evaluations: &[E::BaseField],
+    result: &mut [E],
+) {
+    iter_mut!(result, 1024)
+        .zip(column)
+        .enumerate()
+        .for_each(|(i, (acc_value, value))| {
+            // determine which inverse evaluation corresponds to the current domain point
+            let z = inv_evaluations[i % inv_evaluations.len()];
+            *acc_value += value.mul_base(z);
+        });
+}
+
+/// Computes inverse evaluations of the divisor over the constraint evaluation domain.
+///
+/// For divisors without exemption points, the returned vector contains only the distinct values
+/// of 1 / (x^a - b), and the evaluation at the i-th point of the domain is located at position
+/// `i % len`. For divisors with exemption points, the returned vector contains e(x) / (x^a - b)
+/// for every point of the domain, where e(x) describes the exemption points.
+pub(super) fn get_inv_divisor_evaluations<B: StarkField>(
+    divisor: &ConstraintDivisor<B>,
+    domain: &impl EvaluationDomain<B>,
+) -> Vec<B> {
+    let numerator = divisor.numerator();
+    assert_eq!(numerator.len(), 1, "complex divisors are not yet supported");
+
+    let z = get_inv_evaluation(divisor, domain);
+    if divisor.exemptions().is_empty() {
+        return z;
+    }
+
+    let mut result = unsafe { uninit_vector(domain.ce_domain_size()) };
+    batch_iter_mut!(
+        &mut result,
+        128, // min batch size
+        |batch: &mut [B], batch_offset: usize| {
+            for (i, evaluation) in batch.iter_mut().enumerate() {
+                let x = domain.get_ce_x_at(batch_offset + i);
+                let e = divisor.evaluate_exemptions_at(x);
+                *evaluation = z[(batch_offset + i) % z.len()] * e;
+            }
+        }
+    );
+    result
+}
+
 /// Computes evaluations of the divisor's numerator over the domain of the specified size and offset.
 fn get_inv_evaluation<B: StarkField>(
     divisor: &ConstraintDivisor<B>,
-    domain: &StarkDomain<B>,
+    domain: &impl EvaluationDomain<B>,
 ) -> Vec<B> {
     let numerator = divisor.numerator();
     let a = numerator[0].0 as u64; // numerator degree
     let b = numerator[0].1;
 
-    // this guarantees that we can use get_ce_x_power_at() below but limits execution trace length
-    // to be at most 2^32. in the future, we should revisit this to allow execution traces of
-    // greater length.
-    assert!(
-        a <= u32::MAX as u64,
-        "constraint divisor numerator degree cannot exceed {}, but was {}",
-        u32::MAX,
-        a
-    );
-
     let n = domain.ce_domain_size() / a as usize;
     let domain_offset_exp = domain.offset().exp(a.into());
 
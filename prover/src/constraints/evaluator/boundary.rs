@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::StarkDomain;
+use super::EvaluationDomain;
 use air::{Air, AuxTraceRandElements, ConstraintDivisor};
 use math::{fft, ExtensionOf, FieldElement};
 use utils::collections::{BTreeMap, Vec};
@@ -90,7 +90,7 @@ impl<E: FieldElement> BoundaryConstraints<E> {
     pub fn evaluate_main(
         &self,
         main_state: &[E::BaseField],
-        domain: &StarkDomain<E::BaseField>,
+        domain: &impl EvaluationDomain<E::BaseField>,
         step: usize,
         result: &mut [E],
     ) {
@@ -106,7 +106,7 @@ impl<E: FieldElement> BoundaryConstraints<E> {
         &self,
         main_state: &[E::BaseField],
         aux_state: &[E],
-        domain: &StarkDomain<E::BaseField>,
+        domain: &impl EvaluationDomain<E::BaseField>,
         step: usize,
         result: &mut [E],
     ) {
@@ -293,13 +293,15 @@ impl<E: FieldElement> BoundaryConstraintGroup<E> {
 // ================================================================================================
 
 /// A constraint where the numerator can be represented by p(x) - v, where v is the asserted value,
-/// and p(x) is the trace polynomial for the column against which the constraint is applied.
+/// and p(x) is the trace polynomial for the column against which the constraint is applied (or a
+/// linear combination of trace polynomials for linear combination assertions).
 struct SingleValueConstraint<F, E>
 where
     F: FieldElement,
     E: FieldElement<BaseField = F::BaseField> + ExtensionOf<F>,
 {
     column: usize,
+    combination: Vec<(usize, F)>,
     value: F,
     coefficients: E,
 }
@@ -315,6 +317,7 @@ where
         debug_assert!(source.poly().len() == 1, "not a single constraint");
         Self {
             column: source.column(),
+            combination: source.combination().to_vec(),
             value: source.poly()[0],
             coefficients: *source.cc(),
         }
@@ -324,7 +327,14 @@ where
     ///
     /// This also multiplies by the composition coefficient.
     pub fn evaluate(&self, state: &[F]) -> E {
-        let evaluation = state[self.column] - self.value;
+        let trace_value = if self.combination.is_empty() {
+            state[self.column]
+        } else {
+            self.combination
+                .iter()
+                .fold(F::ZERO, |acc, &(column, coefficient)| acc + state[column] * coefficient)
+        };
+        let evaluation = trace_value - self.value;
         self.coefficients.mul_base(evaluation)
     }
 }
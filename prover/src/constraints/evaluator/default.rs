@@ -5,11 +5,12 @@
 
 use super::{
     super::EvaluationTableFragment, BoundaryConstraints, CompositionPolyTrace,
-    ConstraintEvaluationTable, ConstraintEvaluator, PeriodicValueTable, StarkDomain, TraceLde,
+    ConstraintEvaluationTable, ConstraintEvaluator, PeriodicValueTable, PrecomputedDivisors,
+    StarkDomain, TraceLde,
 };
 use air::{
     Air, AuxTraceRandElements, ConstraintCompositionCoefficients, EvaluationFrame,
-    TransitionConstraints,
+    RangeCheckConstraints, TransitionConstraints, WraparoundConstraints,
 };
 use math::FieldElement;
 use utils::iter_mut;
@@ -39,8 +40,11 @@ pub struct DefaultConstraintEvaluator<'a, A: Air, E: FieldElement<BaseField = A:
     air: &'a A,
     boundary_constraints: BoundaryConstraints<E>,
     transition_constraints: TransitionConstraints<E>,
+    wraparound_constraints: WraparoundConstraints<E>,
+    range_check_constraints: RangeCheckConstraints<E>,
     aux_rand_elements: AuxTraceRandElements<E>,
     periodic_values: PeriodicValueTable<E::BaseField>,
+    range_check_tables: PeriodicValueTable<E::BaseField>,
 }
 
 impl<'a, A, E> ConstraintEvaluator<E> for DefaultConstraintEvaluator<'a, A, E>
@@ -54,6 +58,82 @@ where
         self,
         trace: &T,
         domain: &StarkDomain<<E as FieldElement>::BaseField>,
+    ) -> CompositionPolyTrace<E> {
+        self.evaluate_constraints(trace, domain, None)
+    }
+
+    fn evaluate_with_divisors<T: TraceLde<E>>(
+        self,
+        trace: &T,
+        domain: &StarkDomain<<E as FieldElement>::BaseField>,
+        divisors: &PrecomputedDivisors<E::BaseField>,
+    ) -> CompositionPolyTrace<E> {
+        self.evaluate_constraints(trace, domain, Some(divisors))
+    }
+}
+
+impl<'a, A, E> DefaultConstraintEvaluator<'a, A, E>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+{
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new evaluator which can be used to evaluate transition and boundary constraints
+    /// over extended execution trace.
+    pub fn new(
+        air: &'a A,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self {
+        // build transition constraint groups; these will be used to compose transition constraint
+        // evaluations
+        let transition_constraints =
+            air.get_transition_constraints(&composition_coefficients.transition);
+
+        // build wraparound constraint metadata; if the AIR defines any wraparound constraints,
+        // their merged evaluations are stored in a separate column of the evaluation table
+        let wraparound_constraints =
+            air.get_wraparound_constraints(&composition_coefficients.wraparound);
+
+        // build range check constraint metadata; if the AIR defines any range checks, their merged
+        // evaluations are also stored in a separate column of the evaluation table, and lookup
+        // tables of range checks are expanded in the same way as periodic columns
+        let range_check_constraints =
+            air.get_range_check_constraints(&composition_coefficients.range_check);
+        let range_check_tables =
+            PeriodicValueTable::from_polys(air, range_check_constraints.table_polys());
+
+        // build periodic value table
+        let periodic_values = PeriodicValueTable::new(air);
+
+        // build boundary constraint groups; these will be used to evaluate and compose boundary
+        // constraint evaluations.
+        let boundary_constraints =
+            BoundaryConstraints::new(air, &aux_rand_elements, &composition_coefficients.boundary);
+
+        DefaultConstraintEvaluator {
+            air,
+            boundary_constraints,
+            transition_constraints,
+            wraparound_constraints,
+            range_check_constraints,
+            aux_rand_elements,
+            periodic_values,
+            range_check_tables,
+        }
+    }
+
+    // EVALUATION HELPERS
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates constraints against the provided extended execution trace, taking evaluations
+    /// of constraint divisors from `precomputed_divisors` when these are provided.
+    fn evaluate_constraints<T: TraceLde<E>>(
+        self,
+        trace: &T,
+        domain: &StarkDomain<E::BaseField>,
+        precomputed_divisors: Option<&PrecomputedDivisors<E::BaseField>>,
     ) -> CompositionPolyTrace<E> {
         assert_eq!(
             trace.trace_len(),
@@ -63,9 +143,16 @@ where
 
         // build a list of constraint divisors; currently, all transition constraints have the same
         // divisor which we put at the front of the list; boundary constraint divisors are appended
-        // after that
+        // after that, followed by the divisors of wraparound and range check constraints (if there
+        // are any)
         let mut divisors = vec![self.transition_constraints.divisor().clone()];
         divisors.append(&mut self.boundary_constraints.get_divisors());
+        if !self.wraparound_constraints.is_empty() {
+            divisors.push(self.wraparound_constraints.divisor().clone());
+        }
+        if !self.range_check_constraints.is_empty() {
+            divisors.push(self.range_check_constraints.divisor().clone());
+        }
 
         // allocate space for constraint evaluations; when we are in debug mode, we also allocate
         // memory to hold all transition constraint evaluations (before they are merged into a
@@ -91,11 +178,11 @@ where
         };
 
         // evaluate constraints for each fragment; if the trace consist of multiple segments
-        // we evaluate constraints for all segments. otherwise, we evaluate constraints only
-        // for the main segment.
+        // (including the segment added for range checks) we evaluate constraints for all segments.
+        // otherwise, we evaluate constraints only for the main segment.
         let mut fragments = evaluation_table.fragments(num_fragments);
         iter_mut!(fragments).for_each(|fragment| {
-            if self.air.trace_info().is_multi_segment() {
+            if trace.trace_layout().num_aux_segments() > 0 {
                 self.evaluate_fragment_full(trace, domain, fragment);
             } else {
                 self.evaluate_fragment_main(trace, domain, fragment);
@@ -108,49 +195,9 @@ where
         evaluation_table.validate_transition_degrees();
 
         // combine all evaluations into a single column and return
-        evaluation_table.combine()
-    }
-}
-
-impl<'a, A, E> DefaultConstraintEvaluator<'a, A, E>
-where
-    A: Air,
-    E: FieldElement<BaseField = A::BaseField>,
-{
-    // CONSTRUCTOR
-    // --------------------------------------------------------------------------------------------
-    /// Returns a new evaluator which can be used to evaluate transition and boundary constraints
-    /// over extended execution trace.
-    pub fn new(
-        air: &'a A,
-        aux_rand_elements: AuxTraceRandElements<E>,
-        composition_coefficients: ConstraintCompositionCoefficients<E>,
-    ) -> Self {
-        // build transition constraint groups; these will be used to compose transition constraint
-        // evaluations
-        let transition_constraints =
-            air.get_transition_constraints(&composition_coefficients.transition);
-
-        // build periodic value table
-        let periodic_values = PeriodicValueTable::new(air);
-
-        // build boundary constraint groups; these will be used to evaluate and compose boundary
-        // constraint evaluations.
-        let boundary_constraints =
-            BoundaryConstraints::new(air, &aux_rand_elements, &composition_coefficients.boundary);
-
-        DefaultConstraintEvaluator {
-            air,
-            boundary_constraints,
-            transition_constraints,
-            aux_rand_elements,
-            periodic_values,
-        }
+        evaluation_table.combine(precomputed_divisors)
     }
 
-    // EVALUATION HELPERS
-    // --------------------------------------------------------------------------------------------
-
     /// Evaluates constraints for a single fragment of the evaluation table.
     ///
     /// This evaluates constraints only over the main segment of the execution trace.
@@ -164,6 +211,7 @@ where
         let mut main_frame = EvaluationFrame::new(trace.trace_layout().main_trace_width());
         let mut evaluations = vec![E::ZERO; fragment.num_columns()];
         let mut t_evaluations = vec![E::BaseField::ZERO; self.num_main_transition_constraints()];
+        let mut w_evaluations = vec![E::BaseField::ZERO; self.num_wraparound_constraints()];
 
         // this will be used to convert steps in constraint evaluation domain to steps in
         // LDE domain
@@ -180,7 +228,9 @@ where
 
             // evaluate transition constraints and save the merged result the first slot of the
             // evaluations buffer
-            evaluations[0] = self.evaluate_main_transition(&main_frame, step, &mut t_evaluations);
+            let x = domain.get_ce_x_at(step);
+            evaluations[0] =
+                self.evaluate_main_transition(&main_frame, step, x, &mut t_evaluations);
 
             // when in debug mode, save transition constraint evaluations
             #[cfg(debug_assertions)]
@@ -196,6 +246,13 @@ where
                 &mut evaluations[1..],
             );
 
+            // evaluate wraparound constraints; the result goes into the last slot of the
+            // evaluations buffer
+            if !self.wraparound_constraints.is_empty() {
+                let last = evaluations.len() - 1;
+                evaluations[last] = self.evaluate_wraparound(&main_frame, &mut w_evaluations);
+            }
+
             // record the result in the evaluation table
             fragment.update_row(i, &evaluations);
         }
@@ -213,9 +270,16 @@ where
     ) {
         // initialize buffers to hold trace values and evaluation results at each step
         let mut main_frame = EvaluationFrame::new(trace.trace_layout().main_trace_width());
-        let mut aux_frame = EvaluationFrame::new(trace.trace_layout().aux_trace_width());
+        let aux_trace_width = trace.trace_layout().aux_trace_width();
+        let mut aux_frame = if self.air.context().has_aux_conjugates() {
+            EvaluationFrame::with_conjugates(aux_trace_width)
+        } else {
+            EvaluationFrame::new(aux_trace_width)
+        };
         let mut tm_evaluations = vec![E::BaseField::ZERO; self.num_main_transition_constraints()];
         let mut ta_evaluations = vec![E::ZERO; self.num_aux_transition_constraints()];
+        let mut w_evaluations = vec![E::BaseField::ZERO; self.num_wraparound_constraints()];
+        let mut r_evaluations = vec![E::ZERO; self.range_check_constraints.num_constraints()];
         let mut evaluations = vec![E::ZERO; fragment.num_columns()];
 
         // this will be used to convert steps in constraint evaluation domain to steps in
@@ -228,13 +292,23 @@ where
             // read both the main and the auxiliary evaluation frames from the trace
             trace.read_main_trace_frame_into(step << lde_shift, &mut main_frame);
             trace.read_aux_trace_frame_into(step << lde_shift, &mut aux_frame);
+            aux_frame.update_conjugates();
 
             // evaluate transition constraints and save the merged result the first slot of the
             // evaluations buffer; we evaluate and compose constraints in the same function, we
             // can just add up the results of evaluating main and auxiliary constraints.
-            evaluations[0] = self.evaluate_main_transition(&main_frame, step, &mut tm_evaluations);
-            evaluations[0] +=
-                self.evaluate_aux_transition(&main_frame, &aux_frame, step, &mut ta_evaluations);
+            let x = domain.get_ce_x_at(step);
+            evaluations[0] =
+                self.evaluate_main_transition(&main_frame, step, x, &mut tm_evaluations);
+            if self.air.trace_info().is_multi_segment() {
+                evaluations[0] += self.evaluate_aux_transition(
+                    &main_frame,
+                    &aux_frame,
+                    step,
+                    x,
+                    &mut ta_evaluations,
+                );
+            }
 
             // when in debug mode, save transition constraint evaluations
             #[cfg(debug_assertions)]
@@ -252,6 +326,18 @@ where
                 &mut evaluations[1..],
             );
 
+            // evaluate range check and wraparound constraints; the results go into the last slots
+            // of the evaluations buffer, with range check constraints being in the very last slot
+            let mut last = evaluations.len();
+            if !self.range_check_constraints.is_empty() {
+                last -= 1;
+                evaluations[last] =
+                    self.evaluate_range_checks(&main_frame, &aux_frame, step, &mut r_evaluations);
+            }
+            if !self.wraparound_constraints.is_empty() {
+                evaluations[last - 1] = self.evaluate_wraparound(&main_frame, &mut w_evaluations);
+            }
+
             // record the result in the evaluation table
             fragment.update_row(i, &evaluations);
         }
@@ -269,6 +355,7 @@ where
         &self,
         main_frame: &EvaluationFrame<E::BaseField>,
         step: usize,
+        x: E::BaseField,
         evaluations: &mut [E::BaseField],
     ) -> E {
         // TODO: use a more efficient way to zero out memory
@@ -279,14 +366,11 @@ where
 
         // evaluate transition constraints over the main segment of the execution trace and save
         // the results into evaluations buffer
-        self.air.evaluate_transition(main_frame, periodic_values, evaluations);
+        self.air.evaluate_main_transition(main_frame, periodic_values, evaluations);
 
         // merge transition constraint evaluations into a single value and return it;
         // we can do this here because all transition constraints have the same divisor.
-        evaluations
-            .iter()
-            .zip(self.transition_constraints.main_constraint_coef().iter())
-            .fold(E::ZERO, |acc, (&const_eval, &coef)| acc + coef.mul_base(const_eval))
+        self.transition_constraints.merge_main_evaluations(evaluations, x)
     }
 
     /// Evaluates all transition constraints (i.e., for main and auxiliary trace segments) at the
@@ -299,6 +383,7 @@ where
         main_frame: &EvaluationFrame<E::BaseField>,
         aux_frame: &EvaluationFrame<E>,
         step: usize,
+        x: E::BaseField,
         evaluations: &mut [E],
     ) -> E {
         // TODO: use a more efficient way to zero out memory
@@ -319,10 +404,43 @@ where
 
         // merge transition constraint evaluations into a single value and return it;
         // we can do this here because all transition constraints have the same divisor.
-        evaluations
-            .iter()
-            .zip(self.transition_constraints.aux_constraint_coef().iter())
-            .fold(E::ZERO, |acc, (&const_eval, &coef)| acc + coef * const_eval)
+        self.transition_constraints.merge_aux_evaluations(evaluations, x)
+    }
+
+    /// Evaluates wraparound constraints of the main execution trace against the specified frame
+    /// and merges the results into a single value.
+    fn evaluate_wraparound(
+        &self,
+        main_frame: &EvaluationFrame<E::BaseField>,
+        evaluations: &mut [E::BaseField],
+    ) -> E {
+        evaluations.fill(E::BaseField::ZERO);
+        self.air.evaluate_wraparound(main_frame, evaluations);
+        self.wraparound_constraints.merge_evaluations(evaluations)
+    }
+
+    /// Evaluates range check constraints against the specified frames at the specified step of
+    /// the constraint evaluation domain, and merges the results into a single value.
+    fn evaluate_range_checks(
+        &self,
+        main_frame: &EvaluationFrame<E::BaseField>,
+        aux_frame: &EvaluationFrame<E>,
+        step: usize,
+        evaluations: &mut [E],
+    ) -> E {
+        // the random element of the lookup is the last random element of the first auxiliary
+        // trace segment
+        let rand_elements = self.aux_rand_elements.get_segment_elements(0);
+        let alpha = rand_elements[rand_elements.len() - 1];
+        let table_values = self.range_check_tables.get_row(step);
+        self.range_check_constraints.evaluate(
+            main_frame,
+            aux_frame,
+            table_values,
+            alpha,
+            evaluations,
+        );
+        self.range_check_constraints.merge_evaluations(evaluations)
     }
 
     // ACCESSORS
@@ -338,4 +456,10 @@ where
     fn num_aux_transition_constraints(&self) -> usize {
         self.transition_constraints.num_aux_constraints()
     }
+
+    /// Returns the number of wraparound constraints applied against the main segment of the
+    /// execution trace.
+    fn num_wraparound_constraints(&self) -> usize {
+        self.wraparound_constraints.num_constraints()
+    }
 }
@@ -3,7 +3,10 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{super::TraceLde, CompositionPolyTrace, ConstraintEvaluationTable, StarkDomain};
+use super::{
+    super::TraceLde, CompositionPolyTrace, ConstraintEvaluationTable, EvaluationDomain,
+    PrecomputedDivisors, StarkDomain,
+};
 use air::Air;
 use math::FieldElement;
 
@@ -38,4 +41,23 @@ pub trait ConstraintEvaluator<E: FieldElement> {
         trace: &T,
         domain: &StarkDomain<E::BaseField>,
     ) -> CompositionPolyTrace<E>;
+
+    /// Evaluates constraints against the provided extended execution trace in the same way as
+    /// [evaluate()](ConstraintEvaluator::evaluate), but takes evaluations of constraint divisors
+    /// from the provided set of precomputed divisors.
+    ///
+    /// The default implementation ignores the precomputed divisors and delegates to
+    /// [evaluate()](ConstraintEvaluator::evaluate).
+    fn evaluate_with_divisors<T: TraceLde<E>>(
+        self,
+        trace: &T,
+        domain: &StarkDomain<E::BaseField>,
+        divisors: &PrecomputedDivisors<E::BaseField>,
+    ) -> CompositionPolyTrace<E>
+    where
+        Self: Sized,
+    {
+        let _ = divisors;
+        self.evaluate(trace, domain)
+    }
 }
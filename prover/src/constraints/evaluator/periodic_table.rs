@@ -23,9 +23,14 @@ impl<B: StarkField> PeriodicValueTable<B> {
     /// values of all periodic columns normalized to the same length. This enables simple lookup
     /// into the able using step index of the constraint evaluation domain.
     pub fn new<A: Air<BaseField = B>>(air: &A) -> PeriodicValueTable<B> {
-        // get a list of polynomials describing periodic columns from AIR. if there are no
-        // periodic columns return an empty table
-        let polys = air.get_periodic_column_polys();
+        Self::from_polys(air, &air.get_periodic_column_polys())
+    }
+
+    /// Builds a table of values of periodic columns described by the specified polynomials for
+    /// the specified AIR. Each polynomial must be in coefficient form, and its length must be a
+    /// power of two not greater than the trace length.
+    pub fn from_polys<A: Air<BaseField = B>>(air: &A, polys: &[Vec<B>]) -> PeriodicValueTable<B> {
+        // if there are no periodic columns return an empty table
         if polys.is_empty() {
             return PeriodicValueTable {
                 values: Vec::new(),
@@ -128,7 +133,7 @@ mod tests {
                 domain
                     .iter()
                     .map(|&x| {
-                        let x = x.exp((num_cycles as u32).into());
+                        let x = x.exp((num_cycles as u64).into());
                         polynom::eval(poly, x)
                     })
                     .collect::<Vec<_>>()
@@ -144,6 +149,11 @@ mod tests {
         }
 
         assert_eq!(expected, actual);
+
+        // periodic values computed by the AIR at each point of ce_domain match the table rows
+        for (i, &x) in domain.iter().enumerate() {
+            assert_eq!(table.get_row(i), air.get_periodic_column_values_at(x));
+        }
     }
 
     fn build_ce_domain(domain_size: usize, domain_offset: BaseElement) -> Vec<BaseElement> {
@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{ColMatrix, ConstraintDivisor, RowMatrix, StarkDomain};
+use super::{ColMatrix, ConstraintDivisor, EvaluationDomain, RowMatrix, StarkDomain};
 
 mod evaluator;
 pub use evaluator::{ConstraintEvaluator, DefaultConstraintEvaluator};
@@ -14,5 +14,8 @@ pub use composition_poly::{CompositionPoly, CompositionPolyTrace};
 mod evaluation_table;
 pub use evaluation_table::{ConstraintEvaluationTable, EvaluationTableFragment};
 
+mod divisors;
+pub use divisors::PrecomputedDivisors;
+
 mod commitment;
 pub use commitment::ConstraintCommitment;
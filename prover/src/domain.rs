@@ -5,12 +5,71 @@
 
 use air::Air;
 use math::{fft, get_power_series, StarkField};
-use utils::collections::Vec;
+use utils::collections::{BTreeMap, Vec};
 
 // TYPES AND INTERFACES
 // ================================================================================================
 
+/// Describes domains over which an execution trace is extended and constraints are evaluated
+/// during proof generation.
+///
+/// An evaluation domain consists of three nested domains: the trace domain, the constraint
+/// evaluation domain, and the low-degree extension (LDE) domain. Sizes of all three domains are
+/// powers of two, and the constraint evaluation and LDE domains are shifted by the domain offset.
+/// The constraint evaluators of the prover access points of the constraint evaluation domain only
+/// through this trait.
+///
+/// [StarkDomain], which is built from multiplicative subgroups of the base field, is the only
+/// implementation of this trait currently used by the prover.
+pub trait EvaluationDomain<B: StarkField>: Send + Sync {
+    // REQUIRED METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns length of the execution trace for this computation.
+    fn trace_length(&self) -> usize;
+
+    /// Returns the size of the constraint evaluation domain for this computation.
+    fn ce_domain_size(&self) -> usize;
+
+    /// Returns blowup factor from constraint evaluation to LDE domain.
+    fn ce_to_lde_blowup(&self) -> usize;
+
+    /// Returns the offset by which the constraint evaluation and LDE domains are shifted.
+    fn offset(&self) -> B;
+
+    /// Returns the point of the constraint evaluation domain at the specified step.
+    fn get_ce_x_at(&self, step: usize) -> B;
+
+    /// Returns the point of the constraint evaluation domain at the specified step raised to the
+    /// specified power.
+    ///
+    /// `offset_exp` must be equal to the domain offset raised to the same power; it is expected to
+    /// be pre-computed by the caller.
+    fn get_ce_x_power_at(&self, step: usize, power: u64, offset_exp: B) -> B;
+
+    // PROVIDED METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns blowup factor from trace to constraint evaluation domain.
+    fn trace_to_ce_blowup(&self) -> usize {
+        self.ce_domain_size() / self.trace_length()
+    }
+
+    /// Returns blowup factor from trace to LDE domain.
+    fn trace_to_lde_blowup(&self) -> usize {
+        self.lde_domain_size() / self.trace_length()
+    }
+
+    /// Returns the size of the low-degree extension domain.
+    fn lde_domain_size(&self) -> usize {
+        self.ce_domain_size() * self.ce_to_lde_blowup()
+    }
+}
+
 /// Info about domains related to specific instance of proof generation.
+///
+/// Points of the constraint evaluation domain are s * g^i, where g is the generator of the
+/// multiplicative subgroup of the base field of the domain size, and s is the domain offset.
 pub struct StarkDomain<B: StarkField> {
     /// Twiddles which can be used to evaluate polynomials in the trace domain. Length of this
     /// vector is half the length of the trace domain size.
@@ -31,6 +90,20 @@ pub struct StarkDomain<B: StarkField> {
     domain_offset: B,
 }
 
+/// FFT twiddles precomputed for a set of domain sizes.
+///
+/// Building a [StarkDomain] requires computing twiddles for the trace domain of a computation.
+/// When many proofs for traces of the same length are generated, a cache of twiddles can be built
+/// once and passed to [Prover::prove_with_twiddle_cache()](crate::Prover::prove_with_twiddle_cache)
+/// so that twiddles are not recomputed for every proof.
+///
+/// Twiddles in the cache are keyed by domain size. Since the cache is parameterized by the base
+/// field `B`, twiddles computed for one field can never be used with another field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TwiddleCache<B: StarkField> {
+    twiddles: BTreeMap<usize, Vec<B>>,
+}
+
 // STARK DOMAIN IMPLEMENTATION
 // ================================================================================================
 
@@ -38,18 +111,20 @@ impl<B: StarkField> StarkDomain<B> {
     /// Returns a new STARK domain initialized with the provided `context`.
     pub fn new<A: Air<BaseField = B>>(air: &A) -> Self {
         let trace_twiddles = fft::get_twiddles(air.trace_length());
+        Self::with_trace_twiddles(air, trace_twiddles)
+    }
 
-        // build constraint evaluation domain
-        let domain_gen = B::get_root_of_unity(air.ce_domain_size().ilog2());
-        let ce_domain = get_power_series(domain_gen, air.ce_domain_size());
-
-        StarkDomain {
-            trace_twiddles,
-            ce_domain,
-            ce_to_lde_blowup: air.lde_domain_size() / air.ce_domain_size(),
-            ce_domain_mod_mask: air.ce_domain_size() - 1,
-            domain_offset: air.domain_offset(),
-        }
+    /// Returns a new STARK domain initialized with the provided `context`, taking trace domain
+    /// twiddles from the specified cache.
+    ///
+    /// If the cache does not contain twiddles for the trace domain of the computation, the
+    /// twiddles are computed in the same way as in [new()](StarkDomain::new).
+    pub fn with_twiddle_cache<A: Air<BaseField = B>>(air: &A, cache: &TwiddleCache<B>) -> Self {
+        let trace_twiddles = match cache.get(air.trace_length()) {
+            Some(twiddles) => twiddles.to_vec(),
+            None => fft::get_twiddles(air.trace_length()),
+        };
+        Self::with_trace_twiddles(air, trace_twiddles)
     }
 
     /// Returns a new STARK domain initialized with the provided custom inputs.
@@ -74,6 +149,22 @@ impl<B: StarkField> StarkDomain<B> {
         }
     }
 
+    /// Returns a new STARK domain initialized with the provided `context` and trace domain
+    /// twiddles.
+    fn with_trace_twiddles<A: Air<BaseField = B>>(air: &A, trace_twiddles: Vec<B>) -> Self {
+        // build constraint evaluation domain
+        let domain_gen = B::get_root_of_unity(air.ce_domain_size().ilog2());
+        let ce_domain = get_power_series(domain_gen, air.ce_domain_size());
+
+        StarkDomain {
+            trace_twiddles,
+            ce_domain,
+            ce_to_lde_blowup: air.lde_domain_size() / air.ce_domain_size(),
+            ce_domain_mod_mask: air.ce_domain_size() - 1,
+            domain_offset: air.domain_offset(),
+        }
+    }
+
     // EXECUTION TRACE
     // --------------------------------------------------------------------------------------------
 
@@ -152,3 +243,148 @@ impl<B: StarkField> StarkDomain<B> {
         self.domain_offset
     }
 }
+
+impl<B: StarkField> EvaluationDomain<B> for StarkDomain<B> {
+    fn trace_length(&self) -> usize {
+        self.trace_length()
+    }
+
+    #[inline(always)]
+    fn ce_domain_size(&self) -> usize {
+        self.ce_domain_size()
+    }
+
+    fn ce_to_lde_blowup(&self) -> usize {
+        self.ce_to_lde_blowup()
+    }
+
+    fn offset(&self) -> B {
+        self.offset()
+    }
+
+    #[inline(always)]
+    fn get_ce_x_at(&self, step: usize) -> B {
+        self.get_ce_x_at(step)
+    }
+
+    #[inline(always)]
+    fn get_ce_x_power_at(&self, step: usize, power: u64, offset_exp: B) -> B {
+        self.get_ce_x_power_at(step, power, offset_exp)
+    }
+}
+
+// TWIDDLE CACHE IMPLEMENTATION
+// ================================================================================================
+
+impl<B: StarkField> TwiddleCache<B> {
+    /// Returns a new empty twiddle cache.
+    pub fn new() -> Self {
+        Self {
+            twiddles: BTreeMap::new(),
+        }
+    }
+
+    /// Computes twiddles for a domain of the specified size and adds them to this cache. If
+    /// twiddles for this domain size are already in the cache, this is a no-op.
+    ///
+    /// # Panics
+    /// Panics if `domain_size` is not a power of two or is smaller than 2.
+    pub fn add_domain(&mut self, domain_size: usize) {
+        assert!(domain_size.is_power_of_two(), "domain size must be a power of 2");
+        assert!(domain_size >= 2, "domain size must be at least 2");
+        self.twiddles
+            .entry(domain_size)
+            .or_insert_with(|| fft::get_twiddles(domain_size));
+    }
+
+    /// Returns twiddles for a domain of the specified size, or None if twiddles for this domain
+    /// size have not been added to this cache.
+    pub fn get(&self, domain_size: usize) -> Option<&[B]> {
+        self.twiddles.get(&domain_size).map(|twiddles| twiddles.as_slice())
+    }
+
+    /// Returns the number of domains for which twiddles are stored in this cache.
+    pub fn num_domains(&self) -> usize {
+        self.twiddles.len()
+    }
+}
+
+impl<B: StarkField> Default for TwiddleCache<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{EvaluationDomain, StarkDomain, TwiddleCache};
+    use crate::tests::MockAir;
+    use air::Air;
+    use math::{fft, fields::f128::BaseElement, StarkField};
+    use utils::collections::{BTreeSet, Vec};
+
+    #[test]
+    fn stark_domain_evaluation_domain_contract() {
+        let air = MockAir::with_trace_length(32);
+        let domain = StarkDomain::new(&air);
+        assert_eq!(air.trace_length(), domain.trace_length());
+        assert_eq!(air.ce_domain_size(), domain.ce_domain_size());
+        assert_eq!(air.lde_domain_size(), domain.lde_domain_size());
+        assert_eq!(air.domain_offset(), domain.offset());
+        check_evaluation_domain(&domain);
+
+        // domains built from cached twiddles or custom twiddles satisfy the contract as well
+        let mut cache = TwiddleCache::new();
+        cache.add_domain(air.trace_length());
+        check_evaluation_domain(&StarkDomain::with_twiddle_cache(&air, &cache));
+
+        let twiddles = fft::get_twiddles::<BaseElement>(16);
+        check_evaluation_domain(&StarkDomain::from_twiddles(twiddles, 4, BaseElement::GENERATOR));
+    }
+
+    /// Checks that the specified domain satisfies the contract of the [EvaluationDomain] trait.
+    fn check_evaluation_domain<B: StarkField, D: EvaluationDomain<B>>(domain: &D) {
+        // domain sizes are powers of two, and the domains are nested
+        let trace_length = domain.trace_length();
+        let ce_domain_size = domain.ce_domain_size();
+        assert!(trace_length.is_power_of_two());
+        assert!(ce_domain_size.is_power_of_two());
+        assert!(domain.ce_to_lde_blowup().is_power_of_two());
+        assert_eq!(ce_domain_size, trace_length * domain.trace_to_ce_blowup());
+        assert_eq!(domain.lde_domain_size(), trace_length * domain.trace_to_lde_blowup());
+        assert_eq!(domain.lde_domain_size(), ce_domain_size * domain.ce_to_lde_blowup());
+
+        // the constraint evaluation domain is a coset of a subgroup of size ce_domain_size, which
+        // starts at the domain offset and contains distinct points
+        let offset = domain.offset();
+        let inv_offset = offset.inv();
+        assert_eq!(offset, domain.get_ce_x_at(0));
+        let points = (0..ce_domain_size).map(|step| domain.get_ce_x_at(step)).collect::<Vec<_>>();
+        for &x in points.iter() {
+            assert_eq!(B::ONE, (x * inv_offset).exp((ce_domain_size as u64).into()));
+        }
+        let distinct_points = points.iter().map(|x| x.as_bytes().to_vec()).collect::<BTreeSet<_>>();
+        assert_eq!(ce_domain_size, distinct_points.len());
+
+        // powers of domain points computed without exponentiation are consistent with the points;
+        // this also holds for powers which do not fit into 32 bits
+        let powers = [
+            1u64,
+            2,
+            3,
+            trace_length as u64,
+            ce_domain_size as u64 + 5,
+            1 << 32,
+            (1 << 33) + 3,
+        ];
+        for power in powers {
+            let offset_exp = offset.exp(power.into());
+            for (step, &x) in points.iter().enumerate() {
+                assert_eq!(x.exp(power.into()), domain.get_ce_x_power_at(step, power, offset_exp));
+            }
+        }
+    }
+}
@@ -0,0 +1,42 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{Prover, ProverError};
+use air::proof::DualProof;
+use math::ToElements;
+
+// DUAL PROVER
+// ================================================================================================
+
+/// Returns a [DualProof] attesting to correct executions of two computations defined by the
+/// provided traces; the computations may be defined over different base fields.
+///
+/// **This is experimental.** The proofs are generated one after another: the first proof is
+/// generated by `first_prover` with metadata committing to the public inputs of the second
+/// computation, and the second proof is generated by `second_prover` with metadata committing to
+/// the first proof. Thus, the transcripts of both proofs are chained together, and the resulting
+/// proof can be verified only as a whole via `verify_dual()` function of the verifier crate.
+///
+/// # Errors
+/// Returns an error if either of the proofs could not be generated.
+pub fn prove_dual<P1, P2>(
+    first_prover: &P1,
+    first_trace: P1::Trace,
+    second_prover: &P2,
+    second_trace: P2::Trace,
+) -> Result<DualProof, ProverError>
+where
+    P1: Prover,
+    P2: Prover,
+{
+    let second_pub_inputs = second_prover.get_pub_inputs(&second_trace).to_elements();
+    let first_metadata = DualProof::first_metadata(&second_pub_inputs);
+    let first = first_prover.prove_with_metadata(first_trace, &first_metadata)?;
+
+    let second_metadata = DualProof::second_metadata(&first);
+    let second = second_prover.prove_with_metadata(second_trace, &second_metadata)?;
+
+    Ok(DualProof { first, second })
+}
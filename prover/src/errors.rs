@@ -21,6 +21,34 @@ pub enum ProverError {
     /// This error occurs when the base field specified by the AIR does not support field extension
     /// of degree specified by proof options.
     UnsupportedFieldExtension(usize),
+    /// This error occurs when precomputed constraint divisors were computed for a computation
+    /// with a context different from the one of the computation being proven.
+    InconsistentPrecomputedDivisors,
+    /// This error occurs when the degree of the field extension requested by the caller differs
+    /// from the degree specified by proof options.
+    MismatchedFieldExtension(usize, usize),
+    /// This error occurs when the low-degree extension domain required for the execution trace
+    /// is larger than the largest multiplicative subgroup of the base field with a power-of-two
+    /// order (i.e., when log2 of the domain size exceeds two-adicity of the base field).
+    DomainExceedsTwoAdicity(u32, u32),
+    /// This error occurs when a value in a column of the execution trace is outside of the range
+    /// asserted for this column by a range check of the AIR; the error contains the index of the
+    /// column and the step at which the value is located.
+    RangeCheckViolation(usize, usize),
+    /// This error occurs when a denominator column of a rational constraint of the AIR contains a
+    /// zero at a step to which the constraint applies; the error contains the index of the column
+    /// and the step at which the zero is located.
+    ZeroDenominator(usize, usize),
+    /// This error occurs when FRI layers are to be committed to with a separate hash function,
+    /// but proof options do not specify hash functions for commitments and FRI.
+    UnspecifiedHashFunctions,
+    /// This error occurs when hash functions specified in proof options differ from the hash
+    /// functions with which the proof is to be generated.
+    InconsistentHashFunctions,
+    /// This error occurs when FRI layers are to be committed to with a separate hash function,
+    /// but one of the hash functions used by the prover has an unknown identifier, and thus,
+    /// cannot be checked against the hash functions specified in proof options.
+    UnknownHashFunction,
 }
 
 impl fmt::Display for ProverError {
@@ -36,6 +64,30 @@ impl fmt::Display for ProverError {
             Self::UnsupportedFieldExtension(degree) => {
                 write!(f, "field extension of degree {degree} is not supported for the specified base field")
             }
+            Self::InconsistentPrecomputedDivisors => {
+                write!(f, "precomputed constraint divisors are inconsistent with the computation context")
+            }
+            Self::MismatchedFieldExtension(expected, actual) => {
+                write!(f, "field extension of degree {actual} does not match degree {expected} specified by proof options")
+            }
+            Self::DomainExceedsTwoAdicity(domain_log2, two_adicity) => {
+                write!(f, "LDE domain of size 2^{domain_log2} cannot be formed in a base field with two-adicity {two_adicity}")
+            }
+            Self::RangeCheckViolation(column, step) => {
+                write!(f, "value in column {column} at step {step} is outside of the range asserted by a range check")
+            }
+            Self::ZeroDenominator(column, step) => {
+                write!(f, "denominator of a rational constraint in column {column} is zero at step {step}")
+            }
+            Self::UnspecifiedHashFunctions => {
+                write!(f, "proof options do not specify hash functions for commitments and FRI")
+            }
+            Self::InconsistentHashFunctions => {
+                write!(f, "hash functions specified in proof options differ from the hash functions used by the prover")
+            }
+            Self::UnknownHashFunction => {
+                write!(f, "hash functions used by the prover must have known identifiers")
+            }
         }
     }
 }
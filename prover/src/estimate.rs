@@ -0,0 +1,324 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use air::{proof::Context, ProofOptions, TraceInfo};
+use core::{hint::black_box, time::Duration};
+use crypto::{ElementHasher, Hasher};
+use math::{fft, StarkField};
+use std::time::Instant;
+use utils::{collections::Vec, Serializable};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of elements processed by each of the calibration benchmarks.
+const CALIBRATION_SIZE: usize = 1 << 12;
+
+// OPERATION COSTS
+// ================================================================================================
+
+/// Approximate costs of basic operations performed during proof generation.
+///
+/// All costs are expressed in nanoseconds of single-threaded execution time on base field
+/// elements; costs of operations on extension field elements are extrapolated from these.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OperationCosts {
+    /// Time of one FFT butterfly level for a single element (i.e., FFT over $n$ elements takes
+    /// $n \cdot \log_2 n$ times this value).
+    pub fft: f64,
+    /// Time of a single multiplication.
+    pub mul: f64,
+    /// Time of hashing a single element as a part of a longer sequence of elements.
+    pub hash_element: f64,
+    /// Time of merging two digests into one.
+    pub hash_merge: f64,
+}
+
+impl OperationCosts {
+    /// Returns operation costs measured by running a tiny benchmark of each operation for field
+    /// `B` and hash function `H` on the current machine.
+    ///
+    /// The benchmark takes a few milliseconds; the resulting costs can be retained and re-used
+    /// for any number of estimates via [prove_dry_run_with_costs()].
+    pub fn calibrate<B, H>() -> Self
+    where
+        B: StarkField,
+        H: ElementHasher<BaseField = B>,
+    {
+        let n = CALIBRATION_SIZE;
+        let values = (0..n as u64).map(B::from).collect::<Vec<_>>();
+
+        let mut poly = values.clone();
+        let twiddles = fft::get_twiddles::<B>(n);
+        let fft =
+            measure(|| fft::evaluate_poly(&mut poly, &twiddles)) / (n * n.ilog2() as usize) as f64;
+
+        let mut acc = B::ONE;
+        let mul = measure(|| {
+            for &value in values.iter() {
+                acc *= black_box(value);
+            }
+        }) / n as f64;
+
+        let mut digest = H::hash_elements(&values[..1]);
+        let hash_element = measure(|| digest = H::hash_elements(&values)) / n as f64;
+        let hash_merge = measure(|| {
+            for _ in 0..n {
+                digest = H::merge(&[digest, digest]);
+            }
+        }) / n as f64;
+
+        // make sure the benchmarked computations are not optimized away
+        black_box((acc, poly, digest));
+
+        OperationCosts {
+            fft,
+            mul,
+            hash_element,
+            hash_merge,
+        }
+    }
+}
+
+// PROOF ESTIMATE
+// ================================================================================================
+
+/// Approximate resources required to generate a STARK proof.
+///
+/// All values are rough estimates intended to help decide whether proof generation should be
+/// started (e.g., by a job scheduler); actual values may differ substantially depending on the
+/// computation, the machine, and on whether proof generation is multi-threaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofEstimate {
+    /// Estimated single-threaded proof generation time.
+    pub proving_time: Duration,
+    /// Estimated peak memory (in bytes) used by the prover.
+    pub peak_memory: usize,
+    /// Estimated size (in bytes) of the serialized proof.
+    pub proof_size: usize,
+}
+
+/// Returns estimated resources required to generate a proof for a computation with the specified
+/// trace info and proof options in field `B` using hash function `H`, without generating the
+/// proof.
+///
+/// Costs of basic operations are calibrated by running a tiny benchmark (see
+/// [OperationCosts::calibrate()]); to avoid re-running the benchmark for every estimate, use
+/// [prove_dry_run_with_costs()].
+pub fn prove_dry_run<B, H>(trace_info: &TraceInfo, options: &ProofOptions) -> ProofEstimate
+where
+    B: StarkField,
+    H: ElementHasher<BaseField = B>,
+{
+    prove_dry_run_with_costs::<B, H>(trace_info, options, &OperationCosts::calibrate::<B, H>())
+}
+
+/// Returns estimated resources required to generate a proof for a computation with the specified
+/// trace info and proof options in field `B` using hash function `H`, based on the provided costs
+/// of basic operations.
+///
+/// The degree of constraints is not known without an AIR, and thus, the constraint composition
+/// polynomial is assumed to have the largest degree supported by the blowup factor. For AIRs with
+/// lower-degree constraints, the estimates will be higher than the actual values.
+pub fn prove_dry_run_with_costs<B, H>(
+    trace_info: &TraceInfo,
+    options: &ProofOptions,
+    costs: &OperationCosts,
+) -> ProofEstimate
+where
+    B: StarkField,
+    H: Hasher,
+{
+    let layout = trace_info.layout();
+    let ext = options.field_extension().degree() as usize;
+    let num_segments = layout.num_segments();
+    let num_queries = options.num_queries();
+    let element_bytes = B::ELEMENT_BYTES;
+    let digest_bytes = H::Digest::default().to_bytes().len();
+
+    // sizes of the trace and LDE domains, and the number of columns (in base field elements) of
+    // the trace and the constraint composition polynomial
+    let n = trace_info.length();
+    let lde_size = n * options.blowup_factor();
+    let num_composition_columns = options.blowup_factor();
+    let ce_size = n * num_composition_columns;
+    let trace_columns = layout.main_trace_width() + layout.aux_trace_width() * ext;
+    let composition_columns = num_composition_columns * ext;
+
+    // sizes of FRI layers
+    let fri_options = options.to_fri_options();
+    let folding_factor = fri_options.folding_factor();
+    let num_fri_layers = fri_options.num_fri_layers(lde_size);
+    let fri_layer_sizes = (0..num_fri_layers)
+        .map(|i| lde_size / folding_factor.pow(i as u32))
+        .collect::<Vec<_>>();
+    let fri_total_size = fri_layer_sizes.iter().sum::<usize>();
+
+    // --- proving time ---------------------------------------------------------------------------
+    let fft = |size: usize| (size * size.ilog2() as usize) as f64 * costs.fft;
+    let commit = |num_rows: usize, num_columns: usize| {
+        (num_rows * num_columns) as f64 * costs.hash_element + num_rows as f64 * costs.hash_merge
+    };
+
+    let trace_time = trace_columns as f64 * (fft(n) + fft(lde_size))
+        + commit(lde_size, trace_columns)
+        + lde_size as f64 * (num_segments - 1) as f64 * costs.hash_merge;
+    let constraint_time = (ce_size * trace_columns * ext) as f64 * costs.mul;
+    let composition_time = ext as f64 * fft(ce_size)
+        + composition_columns as f64 * (fft(n) + fft(lde_size))
+        + commit(lde_size, composition_columns);
+    let deep_time = (lde_size * (trace_columns + composition_columns) * ext * 2) as f64 * costs.mul;
+    let fri_time = (fri_total_size * ext) as f64 * (costs.hash_element + costs.mul)
+        + (fri_total_size / folding_factor) as f64 * costs.hash_merge;
+    let grinding_time = (1u64 << options.grinding_factor()) as f64 * costs.hash_merge;
+
+    let total_time =
+        trace_time + constraint_time + composition_time + deep_time + fri_time + grinding_time;
+
+    // --- peak memory ----------------------------------------------------------------------------
+    // trace and composition polynomials and their LDEs, as well as FRI layers, are retained until
+    // the proof is built; constraint evaluations are dropped before FRI layers are built, but we
+    // count them anyway to err on the side of caution
+    let tree_bytes = |num_leaves: usize| 2 * num_leaves * digest_bytes;
+    let peak_memory = (n + lde_size) * trace_columns * element_bytes
+        + num_segments * tree_bytes(lde_size)
+        + ce_size * ext * element_bytes
+        + (n + lde_size) * composition_columns * element_bytes
+        + tree_bytes(lde_size)
+        + lde_size * ext * element_bytes
+        + fri_total_size * ext * element_bytes
+        + tree_bytes(fri_total_size / folding_factor);
+
+    // --- proof size -----------------------------------------------------------------------------
+    // a batch Merkle proof for q queries against a tree with the specified number of leaves
+    // contains roughly q * (log2(num_leaves) - log2(q)) nodes
+    let batch_proof_bytes = |num_leaves: usize| {
+        let depth = num_leaves.ilog2() as usize;
+        let num_queries = num_queries.min(num_leaves);
+        num_queries * depth.saturating_sub(num_queries.ilog2() as usize) * digest_bytes
+    };
+
+    // the frame width is not known without an AIR, but it does not affect the size of the context
+    let context_bytes = Context::new::<B>(trace_info, options.clone(), 2).to_bytes().len();
+    let commitment_bytes = (num_segments + 1 + num_fri_layers + 1) * digest_bytes;
+    let trace_query_bytes =
+        num_queries * trace_columns * element_bytes + num_segments * batch_proof_bytes(lde_size);
+    let constraint_query_bytes =
+        num_queries * composition_columns * element_bytes + batch_proof_bytes(lde_size);
+    let ood_frame_bytes = (2 * (layout.main_trace_width() + layout.aux_trace_width()) * ext
+        + composition_columns)
+        * element_bytes;
+    let fri_bytes = fri_layer_sizes
+        .iter()
+        .map(|&size| {
+            num_queries * folding_factor * ext * element_bytes
+                + batch_proof_bytes(size / folding_factor)
+        })
+        .sum::<usize>()
+        + (fri_options.remainder_max_degree() + 1) * ext * element_bytes;
+
+    let proof_size = context_bytes
+        + commitment_bytes
+        + trace_query_bytes
+        + constraint_query_bytes
+        + ood_frame_bytes
+        + fri_bytes
+        + core::mem::size_of::<u64>();
+
+    ProofEstimate {
+        proving_time: Duration::from_nanos(total_time as u64),
+        peak_memory,
+        proof_size,
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the time (in nanoseconds) it takes to execute the specified function.
+fn measure<F: FnMut()>(mut f: F) -> f64 {
+    let now = Instant::now();
+    f();
+    now.elapsed().as_nanos() as f64
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use air::{FieldExtension, TraceLayout};
+    use crypto::hashers::Blake3_256;
+    use math::fields::f128::BaseElement;
+
+    type Blake3 = Blake3_256<BaseElement>;
+
+    #[test]
+    fn dry_run_estimates_scale_with_trace_length() {
+        let options = ProofOptions::new(32, 8, 0, FieldExtension::None, 4, 31);
+        let costs = OperationCosts {
+            fft: 1.0,
+            mul: 1.0,
+            hash_element: 1.0,
+            hash_merge: 1.0,
+        };
+
+        let mut prev: Option<ProofEstimate> = None;
+        for log_n in [8, 10, 12, 14] {
+            let trace_info = TraceInfo::new(4, 1 << log_n);
+            let estimate =
+                prove_dry_run_with_costs::<BaseElement, Blake3>(&trace_info, &options, &costs);
+            assert!(estimate.proving_time > Duration::ZERO);
+            assert!(estimate.peak_memory > 0);
+            assert!(estimate.proof_size > 0);
+
+            if let Some(prev) = prev {
+                assert!(estimate.proving_time > prev.proving_time);
+                assert!(estimate.peak_memory > prev.peak_memory);
+                assert!(estimate.proof_size > prev.proof_size);
+            }
+            prev = Some(estimate);
+        }
+
+        // wider traces and auxiliary segments increase the estimates as well
+        let narrow = TraceInfo::new(4, 1 << 12);
+        let wide = TraceInfo::new_multi_segment(TraceLayout::new(4, [4], [1]), 1 << 12, vec![]);
+        let narrow = prove_dry_run_with_costs::<BaseElement, Blake3>(&narrow, &options, &costs);
+        let wide = prove_dry_run_with_costs::<BaseElement, Blake3>(&wide, &options, &costs);
+        assert!(wide.proving_time > narrow.proving_time);
+        assert!(wide.peak_memory > narrow.peak_memory);
+        assert!(wide.proof_size > narrow.proof_size);
+    }
+
+    #[test]
+    fn dry_run_with_calibrated_costs() {
+        // calibrated costs depend on the machine, and thus, only their validity is checked
+        let costs = OperationCosts::calibrate::<BaseElement, Blake3>();
+        for cost in [costs.fft, costs.mul, costs.hash_element, costs.hash_merge] {
+            assert!(cost.is_finite() && cost >= 0.0);
+        }
+
+        // memory and proof size estimates do not depend on operation costs
+        let options = ProofOptions::new(32, 8, 0, FieldExtension::Quadratic, 4, 31);
+        let fixed_costs = OperationCosts {
+            fft: 1.0,
+            mul: 1.0,
+            hash_element: 1.0,
+            hash_merge: 1.0,
+        };
+        for log_n in [10, 16] {
+            let trace_info = TraceInfo::new(4, 1 << log_n);
+            let calibrated = prove_dry_run::<BaseElement, Blake3>(&trace_info, &options);
+            let fixed = prove_dry_run_with_costs::<BaseElement, Blake3>(
+                &trace_info,
+                &options,
+                &fixed_costs,
+            );
+            assert_eq!(fixed.peak_memory, calibrated.peak_memory);
+            assert_eq!(fixed.proof_size, calibrated.proof_size);
+        }
+    }
+}
@@ -44,10 +44,17 @@
 extern crate alloc;
 
 pub use air::{
-    proof, proof::StarkProof, Air, AirContext, Assertion, AuxTraceRandElements, BoundaryConstraint,
-    BoundaryConstraintGroup, ConstraintCompositionCoefficients, ConstraintDivisor,
-    DeepCompositionCoefficients, EvaluationFrame, FieldExtension, ProofOptions, TraceInfo,
-    TraceLayout, TransitionConstraintDegree,
+    lde_coset_offset, lde_domain_position, proof,
+    proof::{
+        HashFunctionId, IdentifiableHasher, Queries, QueryResponse, StarkProof, VerificationKey,
+    },
+    Air, AirContext, Assertion, AssertionSummary, AuxTraceRandElements, BoundaryConstraint,
+    BoundaryConstraintGroup, ChecksumError, CommitmentLayout, ConstraintCompositionCoefficients,
+    ConstraintDivisor, ConstraintExpr, DeepCompositionCoefficients, EvaluationFrame,
+    FieldExtension, HashAccumulator, HashFunction, LdeDomainInfo, OpenedLeaves,
+    PolynomialConstraint, ProofOptions, ProofOptionsError, RationalConstraint,
+    SparsePeriodicColumn, SymbolicConstraints, SymbolicFrame, TraceCommitmentOptions, TraceInfo,
+    TraceLayout, TransitionConstraintDegree, TransitionConstraintGroup,
 };
 pub use utils::{
     iterators, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
@@ -59,6 +66,7 @@ use utils::collections::Vec;
 
 pub use math;
 use math::{
+    batch_inversion,
     fft::infer_degree,
     fields::{CubeExtension, QuadExtension},
     ExtensibleField, FieldElement, StarkField, ToElements,
@@ -73,7 +81,7 @@ use log::debug;
 use std::time::Instant;
 
 mod domain;
-pub use domain::StarkDomain;
+pub use domain::{EvaluationDomain, StarkDomain, TwiddleCache};
 
 pub mod matrix;
 use matrix::{ColMatrix, RowMatrix};
@@ -81,14 +89,18 @@ use matrix::{ColMatrix, RowMatrix};
 mod constraints;
 pub use constraints::{
     CompositionPoly, CompositionPolyTrace, ConstraintCommitment, ConstraintEvaluator,
-    DefaultConstraintEvaluator,
+    DefaultConstraintEvaluator, PrecomputedDivisors,
 };
 
 mod composer;
+pub use composer::DeepCompositionEvaluations;
 use composer::DeepCompositionPoly;
 
 mod trace;
-pub use trace::{DefaultTraceLde, Trace, TraceLde, TracePolyTable, TraceTable, TraceTableFragment};
+pub use trace::{
+    BitPackedTraceTable, DefaultTraceLde, Trace, TraceCheckReport, TraceLde, TracePolyTable,
+    TraceSegment, TraceSource, TraceTable, TraceTableFragment, TraceViolation,
+};
 
 mod channel;
 use channel::ProverChannel;
@@ -96,6 +108,14 @@ use channel::ProverChannel;
 mod errors;
 pub use errors::ProverError;
 
+mod dual;
+pub use dual::prove_dual;
+
+#[cfg(feature = "std")]
+mod estimate;
+#[cfg(feature = "std")]
+pub use estimate::{prove_dry_run, prove_dry_run_with_costs, OperationCosts, ProofEstimate};
+
 #[cfg(test)]
 pub mod tests;
 
@@ -172,6 +192,10 @@ pub trait Prover {
     /// Takes the main trace segment columns as input, interpolates them into polynomials in
     /// coefficient form, and evaluates the polynomials over the LDE domain.
     ///
+    /// Trace segments must be committed to using the layout and the number of rows per leaf
+    /// specified by `commitment_options`, which are the values defined by the [ProofOptions] of
+    /// this prover (see [ProofOptions::trace_commitment_options()]).
+    ///
     /// Returns a tuple containing a [TracePolyTable] with the trace polynomials for the main trace
     /// and a new [TraceLde] instance from which the LDE and trace commitments can be obtained.
     fn new_trace_lde<E>(
@@ -179,6 +203,7 @@ pub trait Prover {
         trace_info: &TraceInfo,
         main_trace: &ColMatrix<Self::BaseField>,
         domain: &StarkDomain<Self::BaseField>,
+        commitment_options: TraceCommitmentOptions,
     ) -> (Self::TraceLde<E>, TracePolyTable<E>)
     where
         E: FieldElement<BaseField = Self::BaseField>;
@@ -204,217 +229,346 @@ pub trait Prover {
     /// the computation described by [Self::Air](Prover::Air) and generated using some set of
     /// secret and public inputs. Public inputs must match the value returned from
     /// [Self::get_pub_inputs()](Prover::get_pub_inputs) for the provided trace.
+    ///
+    /// When the proof options do not specify a field extension, the entire protocol (including
+    /// constraint composition, DEEP composition, and FRI) is instantiated over the base field, and
+    /// thus, no extension field arithmetic is performed.
     fn prove(&self, trace: Self::Trace) -> Result<StarkProof, ProverError> {
-        // figure out which version of the generic proof generation procedure to run. this is a sort
-        // of static dispatch for selecting two generic parameter: extension field and hash function.
-        match self.options().field_extension() {
-            FieldExtension::None => self.generate_proof::<Self::BaseField>(trace),
-            FieldExtension::Quadratic => {
-                if !<QuadExtension<Self::BaseField>>::is_supported() {
-                    return Err(ProverError::UnsupportedFieldExtension(2));
-                }
-                self.generate_proof::<QuadExtension<Self::BaseField>>(trace)
-            }
-            FieldExtension::Cubic => {
-                if !<CubeExtension<Self::BaseField>>::is_supported() {
-                    return Err(ProverError::UnsupportedFieldExtension(3));
-                }
-                self.generate_proof::<CubeExtension<Self::BaseField>>(trace)
-            }
-        }
+        self.prove_with_metadata(trace, &[])
     }
 
-    // HELPER METHODS
-    // --------------------------------------------------------------------------------------------
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace, with the specified application-specific `metadata` attached to the proof.
+    ///
+    /// The metadata (e.g., a timestamp or a block height) is absorbed into the public coin before
+    /// any randomness is drawn, and is also stored in the returned [StarkProof]. Thus, the proof
+    /// can be verified only against the same metadata, and altering the metadata invalidates the
+    /// proof. Empty metadata is equivalent to no metadata, and the resulting proof is the same as
+    /// the one returned from [prove()](Prover::prove).
+    fn prove_with_metadata(
+        &self,
+        trace: Self::Trace,
+        metadata: &[u8],
+    ) -> Result<StarkProof, ProverError> {
+        dispatch_proof::<Self, Self::HashFn>(self, trace, metadata, None, None, None)
+    }
 
-    /// Performs the actual proof generation procedure, generating the proof that the provided
-    /// execution `trace` is valid against this prover's AIR.
-    /// TODO: make this function un-callable externally?
-    #[doc(hidden)]
-    fn generate_proof<E>(&self, mut trace: Self::Trace) -> Result<StarkProof, ProverError>
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace, with public inputs of the computation embedded into the proof.
+    ///
+    /// The proof is the same as the one returned from [prove()](Prover::prove), except that
+    /// serialized public inputs are stored in the returned [StarkProof]. This makes it possible
+    /// to verify the proof given only a commitment to the public inputs (see
+    /// `verify_with_input_commitment()` function of the verifier crate).
+    fn prove_with_embedded_inputs(&self, trace: Self::Trace) -> Result<StarkProof, ProverError>
     where
-        E: FieldElement<BaseField = Self::BaseField>,
+        <Self::Air as Air>::PublicInputs: Serializable,
     {
-        // 0 ----- instantiate AIR and prover channel ---------------------------------------------
-
-        // serialize public inputs; these will be included in the seed for the public coin
         let pub_inputs = self.get_pub_inputs(&trace);
-        let pub_inputs_elements = pub_inputs.to_elements();
+        let mut proof = self.prove(trace)?;
+        proof.pub_inputs = pub_inputs.to_bytes();
+        Ok(proof)
+    }
 
-        // create an instance of AIR for the provided parameters. this takes a generic description
-        // of the computation (provided via AIR type), and creates a description of a specific
-        // execution of the computation for the provided public inputs.
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace, together with a verification key for the computation.
+    ///
+    /// The proof is the same as the one returned from [prove()](Prover::prove). The
+    /// [VerificationKey] pins the proof context and periodic columns of
+    /// [Self::Air](Prover::Air); it can be deployed together with a verifier instantiated with the
+    /// same AIR to reject proofs generated with different parameters (see `verify_with_vk()`
+    /// function of the verifier crate).
+    fn prove_with_vk(
+        &self,
+        trace: Self::Trace,
+    ) -> Result<(StarkProof, VerificationKey<Self::BaseField>), ProverError> {
+        let pub_inputs = self.get_pub_inputs(&trace);
         let air = Self::Air::new(trace.get_info(), pub_inputs, self.options().clone());
+        let vk = VerificationKey::new(&air);
+        let proof = self.prove(trace)?;
+        Ok((proof, vk))
+    }
 
-        // create a channel which is used to simulate interaction between the prover and the
-        // verifier; the channel will be used to commit to values and to draw randomness that
-        // should come from the verifier.
-        let mut channel = ProverChannel::<Self::Air, E, Self::HashFn, Self::RandomCoin>::new(
-            &air,
-            pub_inputs_elements,
-        );
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace, using FFT twiddles from the specified cache.
+    ///
+    /// This is equivalent to [prove()](Prover::prove), but twiddles for the trace domain are
+    /// taken from the `twiddle_cache` (if present there) instead of being recomputed. Thus, the
+    /// same cache can be shared across many proofs for traces of the same length. The resulting
+    /// proof is the same as the one returned from [prove()](Prover::prove).
+    fn prove_with_twiddle_cache(
+        &self,
+        trace: Self::Trace,
+        twiddle_cache: &TwiddleCache<Self::BaseField>,
+    ) -> Result<StarkProof, ProverError> {
+        dispatch_proof::<Self, Self::HashFn>(self, trace, &[], Some(twiddle_cache), None, None)
+    }
 
-        // 1 ----- Commit to the execution trace --------------------------------------------------
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace, using precomputed constraint divisors.
+    ///
+    /// This is equivalent to [prove()](Prover::prove), but evaluations of constraint divisors
+    /// over the constraint evaluation domain are taken from `divisors` instead of being
+    /// recomputed. Thus, the same set of divisors can be shared across many proofs for the same
+    /// AIR and trace length. The resulting proof is the same as the one returned from
+    /// [prove()](Prover::prove).
+    ///
+    /// # Errors
+    /// Returns an error if the divisors were computed for a computation with a different
+    /// context (e.g., a different trace length).
+    fn prove_with_divisors(
+        &self,
+        trace: Self::Trace,
+        divisors: &PrecomputedDivisors<Self::BaseField>,
+    ) -> Result<StarkProof, ProverError> {
+        dispatch_proof::<Self, Self::HashFn>(self, trace, &[], None, Some(divisors), None)
+    }
 
-        // build computation domain; this is used later for polynomial evaluations
-        #[cfg(feature = "std")]
-        let now = Instant::now();
-        let domain = StarkDomain::new(&air);
-        #[cfg(feature = "std")]
-        debug!(
-            "Built domain of 2^{} elements in {} ms",
-            domain.lde_domain_size().ilog2(),
-            now.elapsed().as_millis()
-        );
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace, with FRI layers committed to with hash function `HF`.
+    ///
+    /// Execution trace segments and constraint evaluations are committed to with
+    /// [Self::HashFn](Prover::HashFn) as usual. This makes it possible, for example, to commit to
+    /// the trace with an arithmetization-friendly hash function while using a faster hash function
+    /// for FRI, which reduces the cost of verifying the proof recursively. Identifiers of both
+    /// hash functions must be specified in the proof options of this prover (see
+    /// [ProofOptions::with_hash_fns()]). Such a proof can be verified via
+    /// `verify_with_fri_hash_fn()` function of the verifier crate.
+    ///
+    /// # Errors
+    /// Returns an error if the proof options of this prover do not specify hash functions for
+    /// commitments and FRI, if the specified hash functions are not [Self::HashFn](Prover::HashFn)
+    /// and `HF` respectively, or if either of these hash functions has
+    /// [HashFunctionId::Unknown] identifier.
+    fn prove_with_fri_hash_fn<HF>(&self, trace: Self::Trace) -> Result<StarkProof, ProverError>
+    where
+        Self::HashFn: IdentifiableHasher,
+        HF: ElementHasher<BaseField = Self::BaseField> + IdentifiableHasher,
+    {
+        match self.options().hash_fns() {
+            None => return Err(ProverError::UnspecifiedHashFunctions),
+            Some(_)
+                if Self::HashFn::ID == HashFunctionId::Unknown
+                    || HF::ID == HashFunctionId::Unknown =>
+            {
+                return Err(ProverError::UnknownHashFunction)
+            }
+            Some(hash_fns) if hash_fns != (Self::HashFn::ID, HF::ID) => {
+                return Err(ProverError::InconsistentHashFunctions)
+            }
+            Some(_) => (),
+        }
+        dispatch_proof::<Self, HF>(self, trace, &[], None, None, None)
+    }
 
-        // extend the main execution trace and build a Merkle tree from the extended trace
-        let (mut trace_lde, mut trace_polys): (Self::TraceLde<E>, TracePolyTable<E>) =
-            self.new_trace_lde(&trace.get_info(), trace.main_segment(), &domain);
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace, with the commitment to the main trace segment omitted from the proof.
+    ///
+    /// This is intended for cases when the commitment to the main trace segment is already known
+    /// to the verifier (e.g., because it was published in a prior round of a protocol), and thus,
+    /// does not need to be sent again. The commitment is still bound into the public coin during
+    /// proof generation; the only difference from [prove()](Prover::prove) is that it is removed
+    /// from the returned [StarkProof]. Such a proof can be verified only when the commitment is
+    /// supplied separately (see `verify_with_external_commitment()` function of the verifier
+    /// crate).
+    fn prove_without_trace_commitment(
+        &self,
+        trace: Self::Trace,
+    ) -> Result<StarkProof, ProverError> {
+        let mut proof = self.prove(trace)?;
+        proof
+            .commitments
+            .remove_main_trace_commitment::<Self::HashFn>()
+            .expect("proof must contain a commitment to the main trace segment");
+        Ok(proof)
+    }
 
-        // get the commitment to the main trace segment LDE
-        let main_trace_root = trace_lde.get_main_trace_commitment();
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace, with queries sent separately from the proof as a response for each query
+    /// position.
+    ///
+    /// The returned proof is the same as the one returned from [prove()](Prover::prove), except
+    /// that its trace and constraint queries are empty. Instead, decommitments at each query
+    /// position are returned as a separate [QueryResponse], in the order of query positions.
+    /// Thus, the responses can be streamed to the verifier, which checks each of them as it
+    /// arrives (see `verify_streamed()` function of the verifier crate).
+    fn prove_streamed(
+        &self,
+        trace: Self::Trace,
+    ) -> Result<(StarkProof, Vec<QueryResponse>), ProverError> {
+        let mut query_responses = Vec::new();
+        let proof = dispatch_proof::<Self, Self::HashFn>(
+            self,
+            trace,
+            &[],
+            None,
+            None,
+            Some(&mut query_responses),
+        )?;
+        Ok((proof, query_responses))
+    }
 
-        // commit to the LDE of the main trace by writing the root of its Merkle tree into
-        // the channel
-        channel.commit_trace(main_trace_root);
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// execution trace yielded by the provided trace source.
+    ///
+    /// The trace is read from the `source` and materialized into a [TraceTable], which is then
+    /// converted into [Self::Trace](Prover::Trace). Otherwise, this is equivalent to
+    /// [prove()](Prover::prove).
+    ///
+    /// # Panics
+    /// Panics if the trace yielded by the `source` does not satisfy the requirements of
+    /// [TraceTable::init()].
+    fn prove_from_source<S>(&self, source: S) -> Result<StarkProof, ProverError>
+    where
+        S: TraceSource<BaseField = Self::BaseField>,
+        Self::Trace: From<TraceTable<Self::BaseField>>,
+    {
+        let trace = TraceTable::from_source(source);
+        self.prove(trace.into())
+    }
 
-        // build auxiliary trace segments (if any), and append the resulting segments to trace
-        // commitment and trace polynomial table structs
-        let mut aux_trace_segments = Vec::new();
-        let mut aux_trace_rand_elements = AuxTraceRandElements::new();
-        for i in 0..trace.layout().num_aux_segments() {
-            #[cfg(feature = "std")]
-            let now = Instant::now();
+    /// Checks the provided trace against all assertions and transition constraints of
+    /// [Self::Air](Prover::Air) instantiated for the specified public inputs, and returns a
+    /// report listing all violations.
+    ///
+    /// Unlike the debug-mode validation performed during proof generation, this does not stop
+    /// at the first violation, and thus, can be used to find all locations at which a trace under
+    /// development deviates from the AIR. Auxiliary trace segments (if any) are built over the
+    /// base field, using random elements drawn from a public coin seeded with the public inputs.
+    ///
+    /// NOTE: this is a very expensive operation and is intended for use only during development
+    /// of an AIR.
+    fn check_trace(
+        &self,
+        mut trace: Self::Trace,
+        pub_inputs: <Self::Air as Air>::PublicInputs,
+    ) -> TraceCheckReport {
+        let mut public_coin = Self::RandomCoin::new(&pub_inputs.to_elements());
+        let air = Self::Air::new(trace.get_info(), pub_inputs, self.options().clone());
 
-            // draw a set of random elements required to build an auxiliary trace segment
-            let rand_elements = channel.get_aux_trace_segment_rand_elements(i);
+        // make sure all columns of the main trace segment are computed
+        trace.finalize_main_segment();
 
-            // build the trace segment
+        // build auxiliary trace segments (if any)
+        let mut aux_trace_segments = Vec::new();
+        let mut aux_trace_rand_elements = AuxTraceRandElements::<Self::BaseField>::new();
+        for i in 0..trace.layout().num_aux_segments() {
+            let rand_elements = air
+                .get_aux_trace_segment_random_elements(i, &mut public_coin)
+                .expect("failed to draw random elements for an auxiliary trace segment");
             let aux_segment = trace
                 .build_aux_segment(&aux_trace_segments, &rand_elements)
                 .expect("failed build auxiliary trace segment");
-            #[cfg(feature = "std")]
-            debug!(
-                "Built auxiliary trace segment of {} columns and 2^{} steps in {} ms",
-                aux_segment.num_cols(),
-                aux_segment.num_rows().ilog2(),
-                now.elapsed().as_millis()
-            );
-
-            // extend the auxiliary trace segment and build a Merkle tree from the extended trace
-            let (aux_segment_polys, aux_segment_root) =
-                trace_lde.add_aux_segment(&aux_segment, &domain);
-
-            // commit to the LDE of the extended auxiliary trace segment by writing the root of
-            // its Merkle tree into the channel
-            channel.commit_trace(aux_segment_root);
-
-            trace_polys.add_aux_segment(aux_segment_polys);
             aux_trace_rand_elements.add_segment_elements(rand_elements);
             aux_trace_segments.push(aux_segment);
         }
 
-        // make sure the specified trace (including auxiliary segments) is valid against the AIR.
-        // This checks validity of both, assertions and state transitions. We do this in debug
-        // mode only because this is a very expensive operation.
-        #[cfg(debug_assertions)]
-        trace.validate(&air, &aux_trace_segments, &aux_trace_rand_elements);
-
-        // drop the main trace and aux trace segments as they are no longer needed
-        drop(trace);
-        drop(aux_trace_segments);
-
-        // 2 ----- evaluate constraints -----------------------------------------------------------
-        // evaluate constraints specified by the AIR over the constraint evaluation domain, and
-        // compute random linear combinations of these evaluations using coefficients drawn from
-        // the channel
-        #[cfg(feature = "std")]
-        let now = Instant::now();
-        let constraint_coeffs = channel.get_constraint_composition_coeffs();
-        let evaluator = self.new_evaluator(&air, aux_trace_rand_elements, constraint_coeffs);
-        let composition_poly_trace = evaluator.evaluate(&trace_lde, &domain);
-        #[cfg(feature = "std")]
-        debug!(
-            "Evaluated constraints over domain of 2^{} elements in {} ms",
-            composition_poly_trace.num_rows().ilog2(),
-            now.elapsed().as_millis()
-        );
-
-        // 3 ----- commit to constraint evaluations -----------------------------------------------
-
-        // first, build a commitment to the evaluations of the constraint composition polynomial
-        // columns
-        let (constraint_commitment, composition_poly) = self.build_constraint_commitment::<E>(
-            composition_poly_trace,
-            air.context().num_constraint_composition_columns(),
-            &domain,
-        );
-
-        // then, commit to the evaluations of constraints by writing the root of the constraint
-        // Merkle tree into the channel
-        channel.commit_constraints(constraint_commitment.root());
-
-        // 4 ----- build DEEP composition polynomial ----------------------------------------------
-        #[cfg(feature = "std")]
-        let now = Instant::now();
+        trace.check(&air, &aux_trace_segments, &aux_trace_rand_elements)
+    }
 
-        // draw an out-of-domain point z. Depending on the type of E, the point is drawn either
-        // from the base field or from an extension field defined by E.
-        //
-        // The purpose of sampling from the extension field here (instead of the base field) is to
-        // increase security. Soundness is limited by the size of the field that the random point
-        // is drawn from, and we can potentially save on performance by only drawing this point
-        // from an extension field, rather than increasing the size of the field overall.
-        let z = channel.get_ood_point();
+    /// Returns evaluations of the DEEP composition polynomial over the LDE domain for the
+    /// computation defined by the provided trace, without running FRI.
+    ///
+    /// All steps of the proof generation procedure preceding FRI (i.e., commitments to the
+    /// execution trace and constraint evaluations, and construction of the DEEP composition
+    /// polynomial) are executed exactly as in [prove()](Prover::prove). Thus, the returned
+    /// evaluations are the same as the ones consumed by the built-in FRI protocol, and the
+    /// caller can apply an alternative low-degree test to them.
+    ///
+    /// # Errors
+    /// Returns an error if the extension degree of `E` differs from the field extension degree
+    /// specified by this prover's options.
+    fn export_deep_composition<E>(
+        &self,
+        trace: Self::Trace,
+    ) -> Result<DeepCompositionEvaluations<E>, ProverError>
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        let extension_degree = self.options().field_extension().degree() as usize;
+        if E::EXTENSION_DEGREE != extension_degree {
+            return Err(ProverError::MismatchedFieldExtension(
+                extension_degree,
+                E::EXTENSION_DEGREE,
+            ));
+        }
 
-        // evaluate trace and constraint polynomials at the OOD point z, and send the results to
-        // the verifier. the trace polynomials are actually evaluated over two points: z and z * g,
-        // where g is the generator of the trace domain.
-        let ood_trace_states = trace_polys.get_ood_frame(z);
-        channel.send_ood_trace_states(&ood_trace_states);
+        let pub_inputs = self.get_pub_inputs(&trace);
+        let pub_inputs_elements = pub_inputs.to_elements();
+        validate_lde_domain::<Self::BaseField>(&trace.get_info(), self.options())?;
+        let air = Self::Air::new(trace.get_info(), pub_inputs, self.options().clone());
 
-        let ood_evaluations = composition_poly.evaluate_at(z);
-        channel.send_ood_constraint_evaluations(&ood_evaluations);
+        let state = build_deep_composition::<Self, E, Self::HashFn>(
+            self,
+            &air,
+            pub_inputs_elements,
+            trace,
+            &[],
+            None,
+            None,
+        )?;
+
+        Ok(DeepCompositionEvaluations {
+            evaluations: state.deep_evaluations,
+            domain: air.context().lde_domain_info(),
+            degree: air.trace_length() - 2,
+        })
+    }
 
-        // draw random coefficients to use during DEEP polynomial composition, and use them to
-        // initialize the DEEP composition polynomial
-        let deep_coefficients = channel.get_deep_composition_coeffs();
-        let mut deep_composition_poly = DeepCompositionPoly::new(z, deep_coefficients);
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
 
-        // combine all trace polynomials together and merge them into the DEEP composition
-        // polynomial
-        deep_composition_poly.add_trace_polys(trace_polys, ood_trace_states);
+    /// Performs the actual proof generation procedure, generating the proof that the provided
+    /// execution `trace` is valid against this prover's AIR; FRI layers are committed to with hash
+    /// function `HF`. If `query_responses` is provided, a response for each query position is
+    /// written into it, and the proof is built with empty trace and constraint queries.
+    /// TODO: make this function un-callable externally?
+    #[doc(hidden)]
+    fn generate_proof<E, HF>(
+        &self,
+        trace: Self::Trace,
+        metadata: &[u8],
+        twiddle_cache: Option<&TwiddleCache<Self::BaseField>>,
+        divisors: Option<&PrecomputedDivisors<Self::BaseField>>,
+        query_responses: Option<&mut Vec<QueryResponse>>,
+    ) -> Result<StarkProof, ProverError>
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+        HF: ElementHasher<BaseField = Self::BaseField>,
+    {
+        // 0 ----- instantiate AIR -----------------------------------------------------------------
 
-        // merge columns of constraint composition polynomial into the DEEP composition polynomial;
-        deep_composition_poly.add_composition_poly(composition_poly, ood_evaluations);
+        // serialize public inputs; these will be included in the seed for the public coin
+        let pub_inputs = self.get_pub_inputs(&trace);
+        let pub_inputs_elements = pub_inputs.to_elements();
 
-        #[cfg(feature = "std")]
-        debug!(
-            "Built DEEP composition polynomial of degree {} in {} ms",
-            deep_composition_poly.degree(),
-            now.elapsed().as_millis()
-        );
+        // create an instance of AIR for the provided parameters. this takes a generic description
+        // of the computation (provided via AIR type), and creates a description of a specific
+        // execution of the computation for the provided public inputs. before doing so, make sure
+        // the LDE domain for the trace can be formed in the base field.
+        validate_lde_domain::<Self::BaseField>(&trace.get_info(), self.options())?;
+        let air = Self::Air::new(trace.get_info(), pub_inputs, self.options().clone());
 
-        // make sure the degree of the DEEP composition polynomial is equal to trace polynomial
-        // degree minus 1.
-        assert_eq!(domain.trace_length() - 2, deep_composition_poly.degree());
+        // make sure precomputed divisors (if any) were computed for the same computation context
+        if let Some(divisors) = divisors {
+            divisors.validate(&air)?;
+        }
 
-        // 5 ----- evaluate DEEP composition polynomial over LDE domain ---------------------------
-        #[cfg(feature = "std")]
-        let now = Instant::now();
-        let deep_evaluations = deep_composition_poly.evaluate(&domain);
-        // we check the following condition in debug mode only because infer_degree is an expensive
-        // operation
-        debug_assert_eq!(
-            domain.trace_length() - 2,
-            infer_degree(&deep_evaluations, domain.offset())
-        );
-        #[cfg(feature = "std")]
-        debug!(
-            "Evaluated DEEP composition polynomial over LDE domain (2^{} elements) in {} ms",
-            domain.lde_domain_size().ilog2(),
-            now.elapsed().as_millis()
-        );
+        // 1-5 ----- commit to the trace and constraints, and evaluate DEEP composition polynomial
+        let DeepCompositionState {
+            mut channel,
+            trace_lde,
+            constraint_commitment,
+            deep_evaluations,
+        } = build_deep_composition::<Self, E, HF>(
+            self,
+            &air,
+            pub_inputs_elements,
+            trace,
+            metadata,
+            twiddle_cache,
+            divisors,
+        )?;
 
         // 6 ----- compute FRI layers for the composition polynomial ------------------------------
         #[cfg(feature = "std")]
@@ -451,6 +605,23 @@ pub trait Prover {
         // generate FRI proof
         let fri_proof = fri_prover.build_proof(&query_positions);
 
+        // when queries are streamed, each query position is decommitted separately, and the
+        // proof is built with empty queries
+        if let Some(query_responses) = query_responses {
+            query_responses.extend(query_positions.iter().map(|&position| QueryResponse {
+                trace_queries: trace_lde.query(&[position]),
+                constraint_queries: constraint_commitment.query(&[position]),
+            }));
+            let trace_queries = vec![Queries::empty(); air.committed_trace_layout().num_segments()];
+            let proof = channel.build_proof(
+                trace_queries,
+                Queries::empty(),
+                fri_proof,
+                query_positions.len(),
+            );
+            return Ok(proof);
+        }
+
         // query the execution trace at the selected position; for each query, we need the
         // state of the trace at that position + Merkle authentication path
         let trace_queries = trace_lde.query(&query_positions);
@@ -538,3 +709,461 @@ pub trait Prover {
         (constraint_commitment, composition_poly)
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Runs the version of the generic proof generation procedure of the `prover` which corresponds to
+/// the field extension specified in the prover's options; FRI layers are committed to with hash
+/// function `HF`. If `query_responses` is provided, queries are written into it instead of the
+/// proof.
+fn dispatch_proof<P, HF>(
+    prover: &P,
+    trace: P::Trace,
+    metadata: &[u8],
+    twiddle_cache: Option<&TwiddleCache<P::BaseField>>,
+    divisors: Option<&PrecomputedDivisors<P::BaseField>>,
+    query_responses: Option<&mut Vec<QueryResponse>>,
+) -> Result<StarkProof, ProverError>
+where
+    P: Prover + ?Sized,
+    HF: ElementHasher<BaseField = P::BaseField>,
+{
+    // figure out which version of the generic proof generation procedure to run. this is a sort
+    // of static dispatch for selecting two generic parameter: extension field and hash function.
+    match prover.options().field_extension() {
+        FieldExtension::None => prover.generate_proof::<P::BaseField, HF>(
+            trace,
+            metadata,
+            twiddle_cache,
+            divisors,
+            query_responses,
+        ),
+        FieldExtension::Quadratic => {
+            if !<QuadExtension<P::BaseField>>::is_supported() {
+                return Err(ProverError::UnsupportedFieldExtension(2));
+            }
+            prover.generate_proof::<QuadExtension<P::BaseField>, HF>(
+                trace,
+                metadata,
+                twiddle_cache,
+                divisors,
+                query_responses,
+            )
+        }
+        FieldExtension::Cubic => {
+            if !<CubeExtension<P::BaseField>>::is_supported() {
+                return Err(ProverError::UnsupportedFieldExtension(3));
+            }
+            prover.generate_proof::<CubeExtension<P::BaseField>, HF>(
+                trace,
+                metadata,
+                twiddle_cache,
+                divisors,
+                query_responses,
+            )
+        }
+    }
+}
+
+/// Returns an error if the low-degree extension domain for an execution trace described by
+/// `trace_info` cannot be formed in the base field `B` under the specified proof options.
+///
+/// The LDE domain is a coset of a multiplicative subgroup of `B` with a power-of-two order, and
+/// thus, log2 of its size cannot exceed two-adicity of `B`.
+fn validate_lde_domain<B: StarkField>(
+    trace_info: &TraceInfo,
+    options: &ProofOptions,
+) -> Result<(), ProverError> {
+    let lde_domain_size = trace_info.length() * options.blowup_factor();
+    let domain_log2 = lde_domain_size.ilog2();
+    if domain_log2 > B::TWO_ADICITY {
+        return Err(ProverError::DomainExceedsTwoAdicity(domain_log2, B::TWO_ADICITY));
+    }
+    Ok(())
+}
+
+/// Intermediate state of the proof generation procedure after the DEEP composition polynomial
+/// has been evaluated over the LDE domain.
+struct DeepCompositionState<'a, P, E, HF>
+where
+    P: Prover + ?Sized,
+    E: FieldElement<BaseField = P::BaseField>,
+    HF: ElementHasher<BaseField = P::BaseField>,
+{
+    channel: ProverChannel<'a, P::Air, E, P::HashFn, P::RandomCoin, HF>,
+    trace_lde: P::TraceLde<E>,
+    constraint_commitment: ConstraintCommitment<E, P::HashFn>,
+    deep_evaluations: Vec<E>,
+}
+
+/// Commits to the execution trace and constraint evaluations, and evaluates the DEEP composition
+/// polynomial over the LDE domain; this covers all steps of the proof generation procedure
+/// preceding FRI.
+///
+/// Returns an error if a value in the execution trace violates a range check of the AIR.
+fn build_deep_composition<'a, P, E, HF>(
+    prover: &P,
+    air: &'a P::Air,
+    pub_inputs_elements: Vec<P::BaseField>,
+    mut trace: P::Trace,
+    metadata: &[u8],
+    twiddle_cache: Option<&TwiddleCache<P::BaseField>>,
+    divisors: Option<&PrecomputedDivisors<P::BaseField>>,
+) -> Result<DeepCompositionState<'a, P, E, HF>, ProverError>
+where
+    P: Prover + ?Sized,
+    E: FieldElement<BaseField = P::BaseField>,
+    HF: ElementHasher<BaseField = P::BaseField>,
+{
+    // create a channel which is used to simulate interaction between the prover and the
+    // verifier; the channel will be used to commit to values and to draw randomness that
+    // should come from the verifier.
+    let mut channel = ProverChannel::<P::Air, E, P::HashFn, P::RandomCoin, HF>::new(
+        air,
+        pub_inputs_elements,
+        metadata,
+    );
+
+    // 1 ----- Commit to the execution trace --------------------------------------------------
+
+    // build computation domain; this is used later for polynomial evaluations
+    #[cfg(feature = "std")]
+    let now = Instant::now();
+    let domain = match twiddle_cache {
+        Some(cache) => StarkDomain::with_twiddle_cache(air, cache),
+        None => StarkDomain::new(air),
+    };
+    #[cfg(feature = "std")]
+    debug!(
+        "Built domain of 2^{} elements in {} ms",
+        domain.lde_domain_size().ilog2(),
+        now.elapsed().as_millis()
+    );
+
+    // make sure all columns of the main trace segment are computed
+    trace.finalize_main_segment();
+
+    // if the AIR defines rational constraints, append columns of inverses and quotients to the
+    // main trace segment, followed by columns of multiplicities of range checked values if the
+    // AIR defines range checks; the resulting trace is described by the committed trace layout of
+    // the AIR.
+    let range_checks = air.get_range_checks();
+    let extended_main_trace =
+        if range_checks.is_empty() && air.context().rational_constraints().is_empty() {
+            None
+        } else {
+            Some(extend_main_segment(air, trace.main_segment(), &range_checks)?)
+        };
+    let trace_info = TraceInfo::new_multi_segment(
+        air.committed_trace_layout(),
+        air.trace_length(),
+        air.trace_info().meta().to_vec(),
+    );
+
+    // extend the main execution trace and build a Merkle tree from the extended trace
+    let (mut trace_lde, mut trace_polys): (P::TraceLde<E>, TracePolyTable<E>) = prover
+        .new_trace_lde(
+            &trace_info,
+            extended_main_trace.as_ref().unwrap_or(trace.main_segment()),
+            &domain,
+            air.options().trace_commitment_options(),
+        );
+
+    // get the commitment to the main trace segment LDE
+    let main_trace_root = trace_lde.get_main_trace_commitment();
+
+    // commit to the LDE of the main trace by writing the root of its Merkle tree into
+    // the channel
+    channel.commit_trace(main_trace_root);
+
+    // build auxiliary trace segments (if any), and append the resulting segments to trace
+    // commitment and trace polynomial table structs
+    let mut aux_trace_segments = Vec::new();
+    let mut aux_trace_rand_elements = AuxTraceRandElements::new();
+    for i in 0..trace_info.layout().num_aux_segments() {
+        #[cfg(feature = "std")]
+        let now = Instant::now();
+
+        // draw a set of random elements required to build an auxiliary trace segment
+        let rand_elements = channel.get_aux_trace_segment_rand_elements(i);
+
+        // build the trace segment; if the AIR defines range checks, columns for range checks are
+        // appended to the first segment (which is created if the trace does not define it), and
+        // the last random element of this segment is reserved for range checks
+        let trace_segment = (i < trace.layout().num_aux_segments()).then(|| {
+            let num_rand_elements = trace.layout().get_aux_segment_rand_elements(i);
+            trace
+                .build_aux_segment(&aux_trace_segments, &rand_elements[..num_rand_elements])
+                .expect("failed build auxiliary trace segment")
+        });
+        let range_check_segment = (i == 0 && !range_checks.is_empty()).then(|| {
+            let main_trace = extended_main_trace.as_ref().expect("missing extended main trace");
+            let alpha = rand_elements[rand_elements.len() - 1];
+            build_range_check_segment(air, main_trace, trace_segment.as_ref(), &range_checks, alpha)
+        });
+        let aux_segment = range_check_segment
+            .as_ref()
+            .or(trace_segment.as_ref())
+            .expect("missing auxiliary trace segment");
+        #[cfg(feature = "std")]
+        debug!(
+            "Built auxiliary trace segment of {} columns and 2^{} steps in {} ms",
+            aux_segment.num_cols(),
+            aux_segment.num_rows().ilog2(),
+            now.elapsed().as_millis()
+        );
+
+        // extend the auxiliary trace segment and build a Merkle tree from the extended trace
+        let (aux_segment_polys, aux_segment_root) = trace_lde.add_aux_segment(aux_segment, &domain);
+
+        // commit to the LDE of the extended auxiliary trace segment by writing the root of
+        // its Merkle tree into the channel
+        channel.commit_trace(aux_segment_root);
+
+        trace_polys.add_aux_segment(aux_segment_polys);
+        aux_trace_rand_elements.add_segment_elements(rand_elements);
+        if let Some(segment) = trace_segment {
+            aux_trace_segments.push(segment);
+        }
+    }
+
+    // if auxiliary constraints refer to conjugates of auxiliary trace polynomials, add these
+    // conjugates to the polynomial table so that they are opened at the out-of-domain point
+    if air.context().has_aux_conjugates() {
+        trace_polys.add_aux_conjugates();
+    }
+
+    // make sure the specified trace (including auxiliary segments) is valid against the AIR.
+    // This checks validity of both, assertions and state transitions. We do this in debug
+    // mode only because this is a very expensive operation.
+    #[cfg(debug_assertions)]
+    trace.validate(air, &aux_trace_segments, &aux_trace_rand_elements);
+
+    // drop the main trace and aux trace segments as they are no longer needed
+    drop(trace);
+    drop(extended_main_trace);
+    drop(aux_trace_segments);
+
+    // 2 ----- evaluate constraints -----------------------------------------------------------
+    // evaluate constraints specified by the AIR over the constraint evaluation domain, and
+    // compute random linear combinations of these evaluations using coefficients drawn from
+    // the channel
+    #[cfg(feature = "std")]
+    let now = Instant::now();
+    let constraint_coeffs = channel.get_constraint_composition_coeffs();
+    let evaluator = prover.new_evaluator(air, aux_trace_rand_elements, constraint_coeffs);
+    let composition_poly_trace = match divisors {
+        Some(divisors) => evaluator.evaluate_with_divisors(&trace_lde, &domain, divisors),
+        None => evaluator.evaluate(&trace_lde, &domain),
+    };
+    #[cfg(feature = "std")]
+    debug!(
+        "Evaluated constraints over domain of 2^{} elements in {} ms",
+        composition_poly_trace.num_rows().ilog2(),
+        now.elapsed().as_millis()
+    );
+
+    // 3 ----- commit to constraint evaluations -----------------------------------------------
+
+    // first, build a commitment to the evaluations of the constraint composition polynomial
+    // columns
+    let (constraint_commitment, composition_poly) = prover.build_constraint_commitment::<E>(
+        composition_poly_trace,
+        air.context().num_constraint_composition_columns(),
+        &domain,
+    );
+
+    // then, commit to the evaluations of constraints by writing the root of the constraint
+    // Merkle tree into the channel
+    channel.commit_constraints(constraint_commitment.root());
+
+    // 4 ----- build DEEP composition polynomial ----------------------------------------------
+    #[cfg(feature = "std")]
+    let now = Instant::now();
+
+    // draw an out-of-domain point z. Depending on the type of E, the point is drawn either
+    // from the base field or from an extension field defined by E.
+    //
+    // The purpose of sampling from the extension field here (instead of the base field) is to
+    // increase security. Soundness is limited by the size of the field that the random point
+    // is drawn from, and we can potentially save on performance by only drawing this point
+    // from an extension field, rather than increasing the size of the field overall.
+    let z = channel.get_ood_point();
+
+    // evaluate trace and constraint polynomials at the OOD point z, and send the results to
+    // the verifier. the trace polynomials are actually evaluated over several points: z * g^k
+    // for k in 0..frame_width (i.e., z and z * g by default), where g is the generator of the
+    // trace domain.
+    let ood_trace_states = trace_polys.get_ood_frame(z, air.context().frame_width());
+    channel.send_ood_trace_states(&ood_trace_states);
+
+    let ood_evaluations = composition_poly.evaluate_at(z);
+    channel.send_ood_constraint_evaluations(&ood_evaluations);
+
+    // draw random coefficients to use during DEEP polynomial composition, and use them to
+    // initialize the DEEP composition polynomial
+    let deep_coefficients = channel.get_deep_composition_coeffs();
+    let mut deep_composition_poly = DeepCompositionPoly::new(z, deep_coefficients);
+
+    // combine all trace polynomials together and merge them into the DEEP composition
+    // polynomial
+    deep_composition_poly.add_trace_polys(trace_polys, ood_trace_states);
+
+    // merge columns of constraint composition polynomial into the DEEP composition polynomial;
+    deep_composition_poly.add_composition_poly(composition_poly, ood_evaluations);
+
+    #[cfg(feature = "std")]
+    debug!(
+        "Built DEEP composition polynomial of degree {} in {} ms",
+        deep_composition_poly.degree(),
+        now.elapsed().as_millis()
+    );
+
+    // make sure the degree of the DEEP composition polynomial is equal to trace polynomial
+    // degree minus 1.
+    assert_eq!(domain.trace_length() - 2, deep_composition_poly.degree());
+
+    // 5 ----- evaluate DEEP composition polynomial over LDE domain ---------------------------
+    #[cfg(feature = "std")]
+    let now = Instant::now();
+    let deep_evaluations = deep_composition_poly.evaluate(&domain);
+    // we check the following condition in debug mode only because infer_degree is an expensive
+    // operation
+    debug_assert_eq!(domain.trace_length() - 2, infer_degree(&deep_evaluations, domain.offset()));
+    #[cfg(feature = "std")]
+    debug!(
+        "Evaluated DEEP composition polynomial over LDE domain (2^{} elements) in {} ms",
+        domain.lde_domain_size().ilog2(),
+        now.elapsed().as_millis()
+    );
+
+    Ok(DeepCompositionState {
+        channel,
+        trace_lde,
+        constraint_commitment,
+        deep_evaluations,
+    })
+}
+
+/// Returns a copy of the specified main trace segment extended with the following columns:
+/// * For each rational constraint of the AIR, a column of inverses of the denominator and a
+///   column of quotients; for steps exempt from transition constraints at which the denominator
+///   is zero, both values are zeros.
+/// * For each range check of the AIR, a column of multiplicities of range checked values; the
+///   value at step `i` is the number of times `i` appears in the checked column (or zero if `i`
+///   is outside of the checked range).
+///
+/// # Errors
+/// Returns an error if:
+/// * A denominator column of a rational constraint contains a zero at a step to which the
+///   constraint applies.
+/// * A range checked column contains a value outside of its range.
+fn extend_main_segment<A: Air>(
+    air: &A,
+    main_trace: &ColMatrix<A::BaseField>,
+    range_checks: &[(usize, u32)],
+) -> Result<ColMatrix<A::BaseField>, ProverError> {
+    let trace_length = main_trace.num_rows();
+    let mut columns = main_trace.columns().map(|column| column.to_vec()).collect::<Vec<_>>();
+    let num_constrained_steps = trace_length - air.context().num_transition_exemptions();
+    for constraint in air.context().rational_constraints() {
+        let denominators = main_trace.get_column(constraint.denominator());
+        if let Some(step) = denominators[..num_constrained_steps]
+            .iter()
+            .position(|d| *d == A::BaseField::ZERO)
+        {
+            return Err(ProverError::ZeroDenominator(constraint.denominator(), step));
+        }
+        let inverses = batch_inversion(denominators);
+        let quotients = main_trace
+            .get_column(constraint.numerator())
+            .iter()
+            .zip(inverses.iter())
+            .map(|(&numerator, &inverse)| numerator * inverse)
+            .collect();
+        columns.push(inverses);
+        columns.push(quotients);
+    }
+    for &(column, num_bits) in range_checks {
+        let mut multiplicities = vec![A::BaseField::ZERO; trace_length];
+        for (step, &value) in main_trace.get_column(column).iter().enumerate() {
+            let index = get_range_check_index(value, num_bits)
+                .ok_or(ProverError::RangeCheckViolation(column, step))?;
+            multiplicities[index] += A::BaseField::ONE;
+        }
+        columns.push(multiplicities);
+    }
+    Ok(ColMatrix::new(columns))
+}
+
+/// Returns the specified auxiliary trace segment extended with two columns for each range check of
+/// the AIR: a column of inverses 1 / (alpha - v), where v is the checked value, and a column of
+/// running sums of 1 / (alpha - v) - m / (alpha - t), where m is the multiplicity and t is the
+/// table value at a given step. If the trace does not define the segment, the returned segment
+/// consists of range check columns only.
+///
+/// The main trace segment must contain columns of multiplicities of range checked values (see
+/// [extend_main_segment()]).
+fn build_range_check_segment<A, E>(
+    air: &A,
+    main_trace: &ColMatrix<A::BaseField>,
+    aux_segment: Option<&ColMatrix<E>>,
+    range_checks: &[(usize, u32)],
+    alpha: E,
+) -> ColMatrix<E>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+{
+    let trace_length = main_trace.num_rows();
+    let multiplicities_offset = air.trace_layout().main_trace_width()
+        + air.context().rational_constraints().len() * RationalConstraint::NUM_COLUMNS;
+    let mut columns = match aux_segment {
+        Some(segment) => segment.columns().map(|column| column.to_vec()).collect(),
+        None => Vec::new(),
+    };
+    for (i, &(column, num_bits)) in range_checks.iter().enumerate() {
+        let multiplicities = main_trace.get_column(multiplicities_offset + i);
+        let inverses = batch_inversion(
+            &main_trace
+                .get_column(column)
+                .iter()
+                .map(|&v| alpha - E::from(v))
+                .collect::<Vec<_>>(),
+        );
+        let table_inverses =
+            batch_inversion(&(0..1u64 << num_bits).map(|t| alpha - E::from(t)).collect::<Vec<_>>());
+
+        // multiplicities are zeros outside of the table, and thus, running sums are updated with
+        // table values only for the first 2^num_bits steps
+        let mut sums = Vec::with_capacity(trace_length);
+        let mut sum = E::ZERO;
+        for (step, &inverse) in inverses.iter().enumerate() {
+            sums.push(sum);
+            sum += inverse;
+            if step < table_inverses.len() {
+                sum -= table_inverses[step].mul_base(multiplicities[step]);
+            }
+        }
+        debug_assert_eq!(E::ZERO, sum, "running sum of range check {i} does not wrap around");
+
+        columns.push(inverses);
+        columns.push(sums);
+    }
+    ColMatrix::new(columns)
+}
+
+/// Returns the specified value as an index into the table of a range check with the specified
+/// number of bits, or None if the value is outside of the checked range.
+fn get_range_check_index<B: StarkField>(value: B, num_bits: u32) -> Option<usize> {
+    let value = value.as_int();
+    if value >= B::PositiveInteger::from(1u64 << num_bits) {
+        return None;
+    }
+    let one = B::PositiveInteger::from(1u32);
+    let index = (0..num_bits)
+        .filter(|&bit| (value >> bit) & one == one)
+        .fold(0, |index, bit| index | (1 << bit));
+    Some(index)
+}
@@ -6,12 +6,66 @@
 use crate::StarkDomain;
 use core::{iter::FusedIterator, slice};
 use crypto::{ElementHasher, MerkleTree};
-use math::{fft, polynom, FieldElement};
+use math::{batch_inversion, fft, get_power_series, polynom, FieldElement, StarkField};
 use utils::{batch_iter_mut, collections::Vec, iter, iter_mut, uninit_vector};
 
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
 
+// INTERPOLATION METHOD
+// ================================================================================================
+
+/// Default number of rows at or below which [InterpolationMethod::Adaptive] switches from the
+/// inverse FFT to barycentric interpolation.
+///
+/// This is set to the minimum trace length. According to the `interpolation` benchmark of this
+/// crate, the two methods perform within a few microseconds of each other for columns of 8 rows,
+/// but starting at 16 rows the $O(n^2)$ cost of barycentric interpolation dominates the setup cost
+/// of the FFT.
+pub const DEFAULT_INTERPOLATION_THRESHOLD: usize = 8;
+
+/// Defines how columns of a [ColMatrix] are interpolated into polynomials in coefficient form.
+///
+/// All methods yield exactly the same polynomials; they differ only in performance and in the
+/// domains over which they can be applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterpolationMethod {
+    /// Columns are interpolated using the inverse FFT; this requires the evaluation domain to be
+    /// a multiplicative subgroup of a power-of-two size, and takes $O(n \log n)$ time per
+    /// column.
+    Fft,
+    /// Columns are interpolated from the barycentric form of the Lagrange interpolant; this takes
+    /// $O(n^2)$ time per column, but does not rely on the structure of the evaluation domain
+    /// beyond the points being distinct.
+    Barycentric,
+    /// Columns with at most `threshold` rows are interpolated using the
+    /// [Barycentric](Self::Barycentric) method, and all other columns are interpolated using the
+    /// [Fft](Self::Fft) method.
+    Adaptive { threshold: usize },
+}
+
+impl InterpolationMethod {
+    /// Returns the method which is used to interpolate columns of `num_rows` rows; this is never
+    /// [Adaptive](Self::Adaptive).
+    pub fn resolve(&self, num_rows: usize) -> Self {
+        match *self {
+            Self::Adaptive { threshold } if num_rows <= threshold => Self::Barycentric,
+            Self::Adaptive { .. } => Self::Fft,
+            method => method,
+        }
+    }
+}
+
+impl Default for InterpolationMethod {
+    /// Returns [Adaptive](Self::Adaptive) method with the threshold set to
+    /// [DEFAULT_INTERPOLATION_THRESHOLD].
+    fn default() -> Self {
+        Self::Adaptive {
+            threshold: DEFAULT_INTERPOLATION_THRESHOLD,
+        }
+    }
+}
+
 // COLUMN-MAJOR MATRIX
 // ================================================================================================
 
@@ -179,6 +233,29 @@ impl<E: FieldElement> ColMatrix<E> {
     /// * The resulting polynomials are returned as a single matrix where each column contains
     ///   coefficients of a degree `num_rows - 1` polynomial.
     pub fn interpolate_columns(&self) -> Self {
+        self.interpolate_columns_with(InterpolationMethod::Fft)
+    }
+
+    /// Interpolates columns of the matrix into polynomials in coefficient form using the
+    /// specified interpolation method and returns the result.
+    ///
+    /// Each column of the matrix is interpreted as evaluations of degree `num_rows - 1`
+    /// polynomial over a subgroup of size `num_rows`. The resulting polynomials are the same
+    /// regardless of the interpolation method.
+    pub fn interpolate_columns_with(&self, method: InterpolationMethod) -> Self {
+        if method.resolve(self.num_rows()) == InterpolationMethod::Barycentric {
+            let n = self.num_rows();
+            let domain = get_power_series(E::BaseField::get_root_of_unity(n.ilog2()), n);
+            let vanishing_poly = polynom::vanishing_poly(n);
+            let weights = get_barycentric_weights(&domain, &vanishing_poly);
+            let columns = iter!(self.columns)
+                .map(|evaluations| {
+                    interpolate_barycentric(&domain, &vanishing_poly, &weights, evaluations)
+                })
+                .collect();
+            return Self { columns };
+        }
+
         let inv_twiddles = fft::get_inv_twiddles::<E::BaseField>(self.num_rows());
         let columns = iter!(self.columns)
             .map(|evaluations| {
@@ -429,3 +506,53 @@ impl<'a, E: FieldElement> ExactSizeIterator for MultiColumnIter<'a, E> {
 }
 
 impl<'a, E: FieldElement> FusedIterator for MultiColumnIter<'a, E> {}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns barycentric weights $w_i = 1 / M'(x_i)$ of the specified domain, where $M(x)$ is the
+/// vanishing polynomial of the domain.
+fn get_barycentric_weights<B: StarkField>(domain: &[B], vanishing_poly: &[B]) -> Vec<B> {
+    // M'(x_i) is the value of M(x) / (x - x_i) at x_i
+    let derivatives = iter!(domain)
+        .map(|&x| {
+            let mut quotient = B::ZERO;
+            let mut result = B::ZERO;
+            for &coeff in vanishing_poly[1..].iter().rev() {
+                quotient = coeff + quotient * x;
+                result = result * x + quotient;
+            }
+            result
+        })
+        .collect::<Vec<_>>();
+    batch_inversion(&derivatives)
+}
+
+/// Interpolates the specified evaluations over the specified domain into a polynomial in
+/// coefficient form using the barycentric form of the Lagrange interpolant:
+///
+/// $$
+/// p(x) = \sum_{i} y_i \cdot w_i \cdot \frac{M(x)}{x - x_i}
+/// $$
+///
+/// where $M(x)$ is the vanishing polynomial of the domain and $w_i$ are barycentric weights.
+fn interpolate_barycentric<E: FieldElement>(
+    domain: &[E::BaseField],
+    vanishing_poly: &[E::BaseField],
+    weights: &[E::BaseField],
+    evaluations: &[E],
+) -> Vec<E> {
+    let n = domain.len();
+    let mut result = E::zeroed_vector(n);
+    for ((&x, &weight), &y) in domain.iter().zip(weights).zip(evaluations) {
+        // coefficients of M(x) / (x - x_i) are computed via synthetic division, from the
+        // highest-degree coefficient down
+        let scale = y.mul_base(weight);
+        let mut quotient = E::BaseField::ZERO;
+        for k in (0..n).rev() {
+            quotient = vanishing_poly[k + 1] + quotient * x;
+            result[k] += scale.mul_base(quotient);
+        }
+    }
+    result
+}
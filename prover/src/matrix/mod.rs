@@ -9,7 +9,9 @@ mod row_matrix;
 pub use row_matrix::{build_segments, get_evaluation_offsets, RowMatrix};
 
 mod col_matrix;
-pub use col_matrix::{ColMatrix, ColumnIter, MultiColumnIter};
+pub use col_matrix::{
+    ColMatrix, ColumnIter, InterpolationMethod, MultiColumnIter, DEFAULT_INTERPOLATION_THRESHOLD,
+};
 
 mod segments;
 pub use segments::Segment;
@@ -200,6 +200,93 @@ impl<E: FieldElement> RowMatrix<E> {
         // build Merkle tree out of hashed rows
         MerkleTree::new(row_hashes).expect("failed to construct trace Merkle tree")
     }
+
+    /// Returns a commitment to this matrix such that each leaf commits to several adjacent rows.
+    ///
+    /// The commitment is built as follows:
+    /// * Each group of `rows_per_leaf` adjacent rows of the matrix (i.e., rows
+    ///   `[i * rows_per_leaf, (i + 1) * rows_per_leaf)`) is concatenated and hashed into a single
+    ///   digest of the specified hash function.
+    /// * The resulting values are used to build a binary Merkle tree such that each digest
+    ///   becomes a leaf in the tree. Thus, the number of leaves in the tree is equal to the number
+    ///   of rows in the matrix divided by `rows_per_leaf`.
+    /// * The resulting Merkle tree is returned as the commitment to the entire matrix.
+    ///
+    /// # Panics
+    /// Panics if `rows_per_leaf` is not a power of two or is not smaller than the number of rows
+    /// in the matrix.
+    pub fn commit_to_packed_rows<H>(&self, rows_per_leaf: usize) -> MerkleTree<H>
+    where
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        if rows_per_leaf == 1 {
+            return self.commit_to_rows();
+        }
+        assert!(rows_per_leaf.is_power_of_two(), "rows per leaf must be a power of two");
+        assert!(
+            rows_per_leaf < self.num_rows(),
+            "rows per leaf must be smaller than the number of rows"
+        );
+
+        // allocate vector to store leaf hashes
+        let num_leaves = self.num_rows() / rows_per_leaf;
+        let mut leaf_hashes = unsafe { uninit_vector::<H::Digest>(num_leaves) };
+
+        // iterate though groups of matrix rows, hashing each group
+        batch_iter_mut!(
+            &mut leaf_hashes,
+            128, // min batch size
+            |batch: &mut [H::Digest], batch_offset: usize| {
+                let mut leaf = Vec::with_capacity(rows_per_leaf * self.num_cols());
+                for (i, leaf_hash) in batch.iter_mut().enumerate() {
+                    leaf.clear();
+                    let first_row = (batch_offset + i) * rows_per_leaf;
+                    for row_idx in first_row..first_row + rows_per_leaf {
+                        leaf.extend_from_slice(self.row(row_idx));
+                    }
+                    *leaf_hash = H::hash_elements(&leaf);
+                }
+            }
+        );
+
+        // build Merkle tree out of hashed groups of rows
+        MerkleTree::new(leaf_hashes).expect("failed to construct trace Merkle tree")
+    }
+
+    /// Returns a commitment to each column of this matrix.
+    ///
+    /// The commitments are built as follows:
+    /// * Each value of a column is hashed into a single digest of the specified hash function.
+    /// * The resulting values are used to build a binary Merkle tree such that each value digest
+    ///   becomes a leaf in the tree. Thus, the number of leaves in the tree is equal to the
+    ///   number of rows in the matrix.
+    /// * The resulting Merkle trees are returned in the order of the columns they commit to.
+    pub fn commit_to_columns<H>(&self) -> Vec<MerkleTree<H>>
+    where
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        (0..self.num_cols())
+            .map(|col_idx| {
+                // allocate vector to store value hashes
+                let mut value_hashes = unsafe { uninit_vector::<H::Digest>(self.num_rows()) };
+
+                // iterate though matrix rows, hashing the value of the column in each row
+                batch_iter_mut!(
+                    &mut value_hashes,
+                    128, // min batch size
+                    |batch: &mut [H::Digest], batch_offset: usize| {
+                        for (i, value_hash) in batch.iter_mut().enumerate() {
+                            let row = self.row(batch_offset + i);
+                            *value_hash = H::hash_elements(&row[col_idx..col_idx + 1]);
+                        }
+                    }
+                );
+
+                // build Merkle tree out of hashed values
+                MerkleTree::new(value_hashes).expect("failed to construct column Merkle tree")
+            })
+            .collect()
+    }
 }
 
 // HELPER FUNCTIONS
@@ -233,7 +233,7 @@ mod concurrent {
         // generator of the domain should be in the middle of twiddles
         let n = data.len();
         let g = twiddles[twiddles.len() / 2];
-        debug_assert_eq!(g.exp((n as u32).into()), B::ONE);
+        debug_assert_eq!(g.exp((n as u64).into()), B::ONE);
 
         let inner_len = 1_usize << (n.ilog2() / 2);
         let outer_len = n / inner_len;
@@ -255,7 +255,7 @@ mod concurrent {
         data.par_chunks_mut(outer_len).enumerate().for_each(|(i, row)| {
             if i > 0 {
                 let i = permute_index(inner_len, i);
-                let inner_twiddle = g.exp_vartime((i as u32).into());
+                let inner_twiddle = g.exp_vartime((i as u64).into());
                 let mut outer_twiddle = inner_twiddle;
                 for element in row.iter_mut().skip(1) {
                     for col_idx in 0..N {
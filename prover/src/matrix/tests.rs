@@ -4,8 +4,13 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::{
-    math::{fields::f64::BaseElement, get_power_series, polynom, StarkField},
-    ColMatrix, RowMatrix,
+    math::{
+        fft,
+        fields::{f64::BaseElement, QuadExtension},
+        get_power_series, polynom, FieldElement, StarkField,
+    },
+    matrix::InterpolationMethod,
+    ColMatrix, RowMatrix, StarkDomain,
 };
 use rand_utils::rand_vector;
 use utils::collections::Vec;
@@ -39,10 +44,89 @@ fn test_eval_poly_with_offset_matrix() {
     }
 }
 
+#[test]
+fn test_row_and_column_major_evaluations_match() {
+    let n = 64;
+    let num_polys = 255;
+    let blowup_factor = 4;
+
+    // generate random columns for a wide trace. Each column is a polynomial of degree n - 1.
+    let polys = ColMatrix::new((0..num_polys).map(|_| rand_vector(n)).collect());
+
+    // evaluate columns into a row-major matrix (as done for constraint evaluation) and into a
+    // column-major matrix.
+    let row_matrix = RowMatrix::evaluate_polys::<8>(&polys, blowup_factor);
+    let twiddles = fft::get_twiddles::<BaseElement>(n);
+    let domain = StarkDomain::from_twiddles(twiddles, blowup_factor, BaseElement::GENERATOR);
+    let col_matrix = polys.evaluate_columns_over(&domain);
+
+    // rows read from both matrixes must be identical.
+    let mut row = vec![BaseElement::ZERO; num_polys];
+    for row_idx in 0..n * blowup_factor {
+        col_matrix.read_row_into(row_idx, &mut row);
+        assert_eq!(row, row_matrix.row(row_idx));
+    }
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
 /// Builds a domain of size `size` using the primitive element of the field.
+#[test]
+fn test_interpolation_methods_match() {
+    for n in [2, 8, 64] {
+        // interpolating over the standard subgroup yields the same polynomials via both methods
+        let columns: Vec<Vec<BaseElement>> = (0..4).map(|_| rand_vector(n)).collect();
+        let matrix = ColMatrix::new(columns.clone());
+        let expected = matrix.interpolate_columns_with(InterpolationMethod::Fft);
+        let actual = matrix.interpolate_columns_with(InterpolationMethod::Barycentric);
+        assert_eq!(expected.columns().collect::<Vec<_>>(), actual.columns().collect::<Vec<_>>());
+        assert_eq!(
+            expected.columns().collect::<Vec<_>>(),
+            matrix.interpolate_columns().columns().collect::<Vec<_>>()
+        );
+
+        // the polynomials evaluate to the column values over the subgroup
+        let domain = build_domain(n);
+        for (poly, column) in actual.columns().zip(columns.iter()) {
+            assert_eq!(*column, polynom::eval_many(poly, &domain));
+        }
+
+        // the same holds for columns of extension field elements
+        let columns: Vec<Vec<QuadExtension<BaseElement>>> =
+            (0..2).map(|_| rand_vector(n)).collect();
+        let matrix = ColMatrix::new(columns);
+        let expected = matrix.interpolate_columns_with(InterpolationMethod::Fft);
+        let actual = matrix.interpolate_columns_with(InterpolationMethod::Barycentric);
+        assert_eq!(expected.columns().collect::<Vec<_>>(), actual.columns().collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn test_adaptive_interpolation_threshold() {
+    for n in [4, 8, 16] {
+        let columns: Vec<Vec<BaseElement>> = (0..4).map(|_| rand_vector(n)).collect();
+        let matrix = ColMatrix::new(columns);
+        let expected = matrix.interpolate_columns_with(InterpolationMethod::Fft);
+
+        // below or at the threshold, columns are interpolated using the barycentric method
+        let below = InterpolationMethod::Adaptive { threshold: n };
+        assert_eq!(InterpolationMethod::Barycentric, below.resolve(n));
+        let actual = matrix.interpolate_columns_with(below);
+        assert_eq!(expected.columns().collect::<Vec<_>>(), actual.columns().collect::<Vec<_>>());
+
+        // above the threshold, columns are interpolated using the FFT
+        let above = InterpolationMethod::Adaptive { threshold: n - 1 };
+        assert_eq!(InterpolationMethod::Fft, above.resolve(n));
+        let actual = matrix.interpolate_columns_with(above);
+        assert_eq!(expected.columns().collect::<Vec<_>>(), actual.columns().collect::<Vec<_>>());
+
+        // the default method yields the same polynomials as well
+        let actual = matrix.interpolate_columns_with(InterpolationMethod::default());
+        assert_eq!(expected.columns().collect::<Vec<_>>(), actual.columns().collect::<Vec<_>>());
+    }
+}
+
 fn build_domain(size: usize) -> Vec<BaseElement> {
     let g = BaseElement::get_root_of_unity(size.ilog2());
     get_power_series(g, size)
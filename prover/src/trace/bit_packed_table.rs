@@ -0,0 +1,374 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{ColMatrix, Trace};
+use air::{EvaluationFrame, TraceInfo, TraceLayout};
+use math::{FieldElement, StarkField};
+use utils::{collections::Vec, uninit_vector};
+
+// CONSTANTS
+// ================================================================================================
+
+const BITS_PER_WORD: usize = u64::BITS as usize;
+
+// BIT-PACKED TRACE TABLE
+// ================================================================================================
+/// An implementation of the [Trace] trait which stores boolean columns in bit-packed form.
+///
+/// For AIRs which contain many boolean columns (e.g., bit decompositions of values), storing each
+/// bit as a full field element wastes a lot of memory. This trace table stores every column
+/// designated as a bit column at construction time as a sequence of 64-bit words, with a single
+/// bit per row. All other columns are stored as field elements, same as in
+/// [TraceTable](crate::TraceTable).
+///
+/// Bit columns are unpacked into field elements (ZERO or ONE) whenever their values are read,
+/// and are fully unpacked only right before the trace is committed to (see
+/// [Trace::finalize_main_segment()]). Thus, the execution trace committed to by the prover, and
+/// therefore the interpolated trace polynomials, are identical to the ones which would be produced
+/// from an equivalent [TraceTable](crate::TraceTable).
+///
+/// The trace can be populated either cell by cell via [set()](BitPackedTraceTable::set) and
+/// [set_bit()](BitPackedTraceTable::set_bit) methods, or row by row via
+/// [fill()](BitPackedTraceTable::fill) method, which has the same semantics as
+/// [TraceTable::fill()](crate::TraceTable::fill).
+#[derive(Debug, Clone)]
+pub struct BitPackedTraceTable<B: StarkField> {
+    layout: TraceLayout,
+    length: usize,
+    bit_columns: Vec<bool>,
+    columns: Vec<PackedColumn<B>>,
+    trace: Option<ColMatrix<B>>,
+    meta: Vec<u8>,
+}
+
+impl<B: StarkField> BitPackedTraceTable<B> {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Creates a new execution trace of the specified width and length in which columns at the
+    /// specified indexes are stored in bit-packed form.
+    ///
+    /// This allocates all the required memory for the trace, but does not initialize the columns
+    /// which are not bit columns. It is expected that the trace will be filled using one of the
+    /// data mutator methods. All bit columns are initialized to zeros.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `width` is zero or greater than 255.
+    /// * `length` is smaller than 8, greater than biggest multiplicative subgroup in the field
+    ///   `B`, or is not a power of two.
+    /// * Any of the `bit_columns` is out of bounds for the specified `width`, or is listed more
+    ///   than once.
+    pub fn new(width: usize, length: usize, bit_columns: &[usize]) -> Self {
+        Self::with_meta(width, length, bit_columns, vec![])
+    }
+
+    /// Creates a new execution trace of the specified width and length, and with the specified
+    /// metadata, in which columns at the specified indexes are stored in bit-packed form.
+    ///
+    /// This allocates all the required memory for the trace, but does not initialize the columns
+    /// which are not bit columns. It is expected that the trace will be filled using one of the
+    /// data mutator methods. All bit columns are initialized to zeros.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `width` is zero or greater than 255.
+    /// * `length` is smaller than 8, greater than the biggest multiplicative subgroup in the
+    ///   field `B`, or is not a power of two.
+    /// * Any of the `bit_columns` is out of bounds for the specified `width`, or is listed more
+    ///   than once.
+    /// * Length of `meta` is greater than 65535;
+    pub fn with_meta(width: usize, length: usize, bit_columns: &[usize], meta: Vec<u8>) -> Self {
+        assert!(width > 0, "execution trace must consist of at least one column");
+        assert!(
+            width <= TraceInfo::MAX_TRACE_WIDTH,
+            "execution trace width cannot be greater than {}, but was {}",
+            TraceInfo::MAX_TRACE_WIDTH,
+            width
+        );
+        assert!(
+            length >= TraceInfo::MIN_TRACE_LENGTH,
+            "execution trace must be at least {} steps long, but was {}",
+            TraceInfo::MIN_TRACE_LENGTH,
+            length
+        );
+        assert!(length.is_power_of_two(), "execution trace length must be a power of 2");
+        assert!(
+            length.ilog2() <= B::TWO_ADICITY,
+            "execution trace length cannot exceed 2^{} steps, but was 2^{}",
+            B::TWO_ADICITY,
+            length.ilog2()
+        );
+        assert!(
+            meta.len() <= TraceInfo::MAX_META_LENGTH,
+            "number of metadata bytes cannot be greater than {}, but was {}",
+            TraceInfo::MAX_META_LENGTH,
+            meta.len()
+        );
+
+        let mut is_bit_column = vec![false; width];
+        for &column in bit_columns {
+            assert!(
+                column < width,
+                "bit column index {} is out of bounds for execution trace of width {}",
+                column,
+                width
+            );
+            assert!(!is_bit_column[column], "bit column {} is listed more than once", column);
+            is_bit_column[column] = true;
+        }
+
+        let num_words = length.div_ceil(BITS_PER_WORD);
+        let columns = is_bit_column
+            .iter()
+            .map(|&is_bit_column| {
+                if is_bit_column {
+                    PackedColumn::Bits(vec![0; num_words])
+                } else {
+                    PackedColumn::Elements(unsafe { uninit_vector(length) })
+                }
+            })
+            .collect();
+
+        Self {
+            layout: TraceLayout::new(width, [0], [0]),
+            length,
+            bit_columns: is_bit_column,
+            columns,
+            trace: None,
+            meta,
+        }
+    }
+
+    // DATA MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Updates a value in a single cell of the execution trace.
+    ///
+    /// Specifically, the value in the specified `column` and the specified `step` is set to the
+    /// provided `value`.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * Either `column` or `step` are out of bounds for this execution trace.
+    /// * `column` is a bit column and `value` is neither ZERO nor ONE.
+    pub fn set(&mut self, column: usize, step: usize, value: B) {
+        assert!(
+            !self.bit_columns[column] || value == B::ZERO || value == B::ONE,
+            "value in bit column {} at step {} must be 0 or 1, but was {}",
+            column,
+            step,
+            value
+        );
+
+        match self.trace.as_mut() {
+            Some(trace) => trace.set(column, step, value),
+            None => match &mut self.columns[column] {
+                PackedColumn::Elements(values) => values[step] = value,
+                PackedColumn::Bits(words) => set_bit(words, step, value == B::ONE),
+            },
+        }
+    }
+
+    /// Sets a value in a single cell of the execution trace to ONE if `bit` is true, and to ZERO
+    /// otherwise.
+    ///
+    /// # Panics
+    /// Panics if either `column` or `step` are out of bounds for this execution trace.
+    pub fn set_bit(&mut self, column: usize, step: usize, bit: bool) {
+        let value = if bit { B::ONE } else { B::ZERO };
+        self.set(column, step, value);
+    }
+
+    /// Updates metadata for this execution trace to the specified vector of bytes.
+    ///
+    /// # Panics
+    /// Panics if the length of `meta` is greater than 65535;
+    pub fn set_meta(&mut self, meta: Vec<u8>) {
+        assert!(
+            meta.len() <= TraceInfo::MAX_META_LENGTH,
+            "number of metadata bytes cannot be greater than {}, but was {}",
+            TraceInfo::MAX_META_LENGTH,
+            meta.len()
+        );
+        self.meta = meta
+    }
+
+    /// Fill all rows in the execution trace.
+    ///
+    /// The semantics of this method are identical to the semantics of
+    /// [TraceTable::fill()](crate::TraceTable::fill) method.
+    ///
+    /// # Panics
+    /// Panics if any of the states produced by the closures contains a value other than ZERO or
+    /// ONE in a bit column.
+    pub fn fill<I, U>(&mut self, init: I, mut update: U)
+    where
+        I: FnOnce(&mut [B]),
+        U: FnMut(usize, &mut [B]),
+    {
+        let mut state = vec![B::ZERO; self.width()];
+        init(&mut state);
+        self.update_row(0, &state);
+
+        for i in 0..self.length() - 1 {
+            update(i, &mut state);
+            self.update_row(i + 1, &state);
+        }
+    }
+
+    /// Updates a single row in the execution trace with provided data.
+    ///
+    /// # Panics
+    /// Panics if `state` contains a value other than ZERO or ONE in a bit column.
+    pub fn update_row(&mut self, step: usize, state: &[B]) {
+        for (column, &value) in state.iter().enumerate() {
+            self.set(column, step, value);
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of columns in this execution trace.
+    pub fn width(&self) -> usize {
+        self.main_trace_width()
+    }
+
+    /// Returns true if the column at the specified index is stored in bit-packed form.
+    pub fn is_bit_column(&self, column: usize) -> bool {
+        self.bit_columns[column]
+    }
+
+    /// Returns value of the cell in the specified column at the specified row of this trace.
+    pub fn get(&self, column: usize, step: usize) -> B {
+        match &self.trace {
+            Some(trace) => trace.get(column, step),
+            None => self.columns[column].get(step),
+        }
+    }
+
+    /// Reads a single row from this execution trace into the provided target.
+    pub fn read_row_into(&self, step: usize, target: &mut [B]) {
+        match &self.trace {
+            Some(trace) => trace.read_row_into(step, target),
+            None => {
+                for (value, column) in target.iter_mut().zip(self.columns.iter()) {
+                    *value = column.get(step);
+                }
+            }
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Unpacks all columns of this trace into field elements, and releases the memory used by the
+    /// packed columns.
+    fn unpack_columns(&mut self) {
+        let length = self.length;
+        let columns = self.columns.drain(..).map(|column| column.unpack(length)).collect();
+        self.trace = Some(ColMatrix::new(columns));
+    }
+}
+
+// TRACE TRAIT IMPLEMENTATION
+// ================================================================================================
+
+impl<B: StarkField> Trace for BitPackedTraceTable<B> {
+    type BaseField = B;
+
+    fn layout(&self) -> &TraceLayout {
+        &self.layout
+    }
+
+    fn length(&self) -> usize {
+        self.length
+    }
+
+    fn meta(&self) -> &[u8] {
+        &self.meta
+    }
+
+    fn read_main_frame(&self, row_idx: usize, frame: &mut EvaluationFrame<Self::BaseField>) {
+        let next_row_idx = (row_idx + 1) % self.length();
+        self.read_row_into(row_idx, frame.current_mut());
+        self.read_row_into(next_row_idx, frame.next_mut());
+    }
+
+    fn main_segment(&self) -> &ColMatrix<B> {
+        self.trace
+            .as_ref()
+            .expect("bit columns must be unpacked before the main trace segment is accessed")
+    }
+
+    fn finalize_main_segment(&mut self) {
+        if self.trace.is_none() {
+            self.unpack_columns();
+        }
+    }
+
+    fn build_aux_segment<E>(
+        &mut self,
+        _aux_segments: &[ColMatrix<E>],
+        _rand_elements: &[E],
+    ) -> Option<ColMatrix<E>>
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        None
+    }
+}
+
+// PACKED COLUMN
+// ================================================================================================
+
+/// A column of a [BitPackedTraceTable] which is stored either as a vector of field elements, or as
+/// a vector of bits packed into 64-bit words.
+#[derive(Debug, Clone)]
+enum PackedColumn<B: StarkField> {
+    Elements(Vec<B>),
+    Bits(Vec<u64>),
+}
+
+impl<B: StarkField> PackedColumn<B> {
+    fn get(&self, step: usize) -> B {
+        match self {
+            Self::Elements(values) => values[step],
+            Self::Bits(words) => {
+                if get_bit(words, step) {
+                    B::ONE
+                } else {
+                    B::ZERO
+                }
+            }
+        }
+    }
+
+    fn unpack(self, length: usize) -> Vec<B> {
+        match self {
+            Self::Elements(values) => values,
+            Self::Bits(words) => (0..length)
+                .map(|step| if get_bit(&words, step) { B::ONE } else { B::ZERO })
+                .collect(),
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn get_bit(words: &[u64], step: usize) -> bool {
+    (words[step / BITS_PER_WORD] >> (step % BITS_PER_WORD)) & 1 == 1
+}
+
+fn set_bit(words: &mut [u64], step: usize, bit: bool) {
+    let mask = 1u64 << (step % BITS_PER_WORD);
+    if bit {
+        words[step / BITS_PER_WORD] |= mask;
+    } else {
+        words[step / BITS_PER_WORD] &= !mask;
+    }
+}
@@ -4,8 +4,9 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{matrix::MultiColumnIter, ColMatrix};
-use air::{Air, AuxTraceRandElements, EvaluationFrame, TraceInfo, TraceLayout};
+use air::{Air, AuxTraceRandElements, EvaluationFrame, RationalConstraint, TraceInfo, TraceLayout};
 use math::{polynom, FieldElement, StarkField};
+use utils::{collections::Vec, string::ToString};
 
 mod trace_lde;
 pub use trace_lde::{DefaultTraceLde, TraceLde};
@@ -16,6 +17,15 @@ pub use poly_table::TracePolyTable;
 mod trace_table;
 pub use trace_table::{TraceTable, TraceTableFragment};
 
+mod bit_packed_table;
+pub use bit_packed_table::BitPackedTraceTable;
+
+mod source;
+pub use source::TraceSource;
+
+mod report;
+pub use report::{TraceCheckReport, TraceSegment, TraceViolation};
+
 #[cfg(test)]
 mod tests;
 
@@ -75,6 +85,13 @@ pub trait Trace: Sized {
     // PROVIDED METHODS
     // --------------------------------------------------------------------------------------------
 
+    /// Completes the main segment of this trace before it is committed to.
+    ///
+    /// This is invoked by the prover right before [main_segment()](Trace::main_segment) is used
+    /// to build the trace commitment, and can be used to compute any lazily defined columns. The
+    /// default implementation does nothing.
+    fn finalize_main_segment(&mut self) {}
+
     /// Returns trace info for this trace.
     fn get_info(&self) -> TraceInfo {
         TraceInfo::new_multi_segment(self.layout().clone(), self.length(), self.meta().to_vec())
@@ -94,6 +111,8 @@ pub trait Trace: Sized {
     // --------------------------------------------------------------------------------------------
     /// Checks if this trace is valid against the specified AIR, and panics if not.
     ///
+    /// The panic message describes the first violation found by [check()](Trace::check).
+    ///
     /// NOTE: this is a very expensive operation and is intended for use only in debug mode.
     fn validate<A, E>(
         &self,
@@ -103,6 +122,31 @@ pub trait Trace: Sized {
     ) where
         A: Air<BaseField = Self::BaseField>,
         E: FieldElement<BaseField = Self::BaseField>,
+    {
+        let report = self.check(air, aux_segments, aux_rand_elements);
+        if let Some(violation) = report.violations().first() {
+            panic!("{violation}");
+        }
+    }
+
+    /// Checks this trace against all assertions, transition constraints, and wraparound
+    /// constraints of the specified AIR, and returns a report listing all locations at which the
+    /// trace is not valid.
+    ///
+    /// NOTE: this is a very expensive operation and is intended for use only during development
+    /// of an AIR.
+    ///
+    /// # Panics
+    /// Panics if the width of the main trace segment does not match the trace layout of the AIR.
+    fn check<A, E>(
+        &self,
+        air: &A,
+        aux_segments: &[ColMatrix<E>],
+        aux_rand_elements: &AuxTraceRandElements<E>,
+    ) -> TraceCheckReport
+    where
+        A: Air<BaseField = Self::BaseField>,
+        E: FieldElement<BaseField = Self::BaseField>,
     {
         // make sure the width align; if they don't something went terribly wrong
         assert_eq!(
@@ -113,23 +157,56 @@ pub trait Trace: Sized {
             air.trace_layout().main_trace_width(),
         );
 
-        // --- 1. make sure the assertions are valid ----------------------------------------------
+        let mut violations = Vec::new();
+
+        // --- 1. check assertions ----------------------------------------------------------------
 
         // first, check assertions against the main segment of the execution trace
         for assertion in air.get_assertions() {
+            if assertion.is_linear_combination() {
+                let step = assertion.first_step();
+                let row = (0..self.main_trace_width())
+                    .map(|column| self.main_segment().get(column, step))
+                    .collect::<Vec<_>>();
+                if assertion.values()[0] != assertion.get_trace_value(&row) {
+                    violations.push(TraceViolation::Assertion {
+                        segment: TraceSegment::Main,
+                        assertion: assertion.to_string(),
+                        step,
+                    });
+                }
+                continue;
+            }
+
             assertion.apply(self.length(), |step, value| {
-                assert!(
-                    value == self.main_segment().get(assertion.column(), step),
-                    "trace does not satisfy assertion main_trace({}, {}) == {}",
-                    assertion.column(),
-                    step,
-                    value
-                );
+                if value != self.main_segment().get(assertion.column(), step) {
+                    violations.push(TraceViolation::Assertion {
+                        segment: TraceSegment::Main,
+                        assertion: assertion.to_string(),
+                        step,
+                    });
+                }
             });
         }
 
         // then, check assertions against auxiliary trace segments
         for assertion in air.get_aux_assertions(aux_rand_elements) {
+            if assertion.is_linear_combination() {
+                let step = assertion.first_step();
+                let row = aux_segments
+                    .iter()
+                    .flat_map(|segment| (0..segment.num_cols()).map(move |c| segment.get(c, step)))
+                    .collect::<Vec<_>>();
+                if assertion.values()[0] != assertion.get_trace_value(&row) {
+                    violations.push(TraceViolation::Assertion {
+                        segment: TraceSegment::Aux,
+                        assertion: assertion.to_string(),
+                        step,
+                    });
+                }
+                continue;
+            }
+
             // find which segment the assertion is for and remap assertion column index to the
             // column index in the context of this segment
             let mut column_idx = assertion.column();
@@ -143,19 +220,19 @@ pub trait Trace: Sized {
                 column_idx -= segment_width;
             }
 
-            // get the matrix and verify the assertion against it
+            // get the matrix and check the assertion against it
             assertion.apply(self.length(), |step, value| {
-                assert!(
-                    value == aux_segments[segment_idx].get(column_idx, step),
-                    "trace does not satisfy assertion aux_trace({}, {}) == {}",
-                    assertion.column(),
-                    step,
-                    value
-                );
+                if value != aux_segments[segment_idx].get(column_idx, step) {
+                    violations.push(TraceViolation::Assertion {
+                        segment: TraceSegment::Aux,
+                        assertion: assertion.to_string(),
+                        step,
+                    });
+                }
             });
         }
 
-        // --- 2. make sure this trace satisfies all transition constraints -----------------------
+        // --- 2. check transition constraints ----------------------------------------------------
 
         // collect the info needed to build periodic values for a specific step
         let g = air.trace_domain_generator();
@@ -164,8 +241,14 @@ pub trait Trace: Sized {
 
         // initialize buffers to hold evaluation frames and results of constraint evaluations
         let mut x = Self::BaseField::ONE;
-        let mut main_frame = EvaluationFrame::new(self.main_trace_width());
-        let mut aux_frame = if air.trace_info().is_multi_segment() {
+        let rational_constraints = air.context().rational_constraints();
+        let mut trace_frame = EvaluationFrame::new(self.main_trace_width());
+        let mut main_frame = EvaluationFrame::new(
+            self.main_trace_width() + rational_constraints.len() * RationalConstraint::NUM_COLUMNS,
+        );
+        let mut aux_frame = if air.context().has_aux_conjugates() {
+            Some(EvaluationFrame::<E>::with_conjugates(self.aux_trace_width()))
+        } else if air.trace_info().is_multi_segment() {
             Some(EvaluationFrame::<E>::new(self.aux_trace_width()))
         } else {
             None
@@ -180,25 +263,30 @@ pub trait Trace: Sized {
             // build periodic values
             for (p, v) in periodic_values_polys.iter().zip(periodic_values.iter_mut()) {
                 let num_cycles = air.trace_length() / p.len();
-                let x = x.exp((num_cycles as u32).into());
+                let x = x.exp((num_cycles as u64).into());
                 *v = polynom::eval(p, x);
             }
 
-            // evaluate transition constraints for the main trace segment and make sure they all
-            // evaluate to zeros
-            self.read_main_frame(step, &mut main_frame);
-            air.evaluate_transition(&main_frame, &periodic_values, &mut main_evaluations);
-            for (i, &evaluation) in main_evaluations.iter().enumerate() {
-                assert!(
-                    evaluation == Self::BaseField::ZERO,
-                    "main transition constraint {i} did not evaluate to ZERO at step {step}"
-                );
+            // evaluate transition constraints for the main trace segment and record the ones
+            // which do not evaluate to zeros
+            self.read_main_frame(step, &mut trace_frame);
+            read_rational_frame(rational_constraints, &trace_frame, &mut main_frame);
+            air.evaluate_main_transition(&main_frame, &periodic_values, &mut main_evaluations);
+            for (constraint, &evaluation) in main_evaluations.iter().enumerate() {
+                if evaluation != Self::BaseField::ZERO {
+                    violations.push(TraceViolation::Transition {
+                        segment: TraceSegment::Main,
+                        constraint,
+                        step,
+                    });
+                }
             }
 
-            // evaluate transition constraints for auxiliary trace segments (if any) and make
-            // sure they all evaluate to zeros
+            // evaluate transition constraints for auxiliary trace segments (if any) and record
+            // the ones which do not evaluate to zeros
             if let Some(ref mut aux_frame) = aux_frame {
                 read_aux_frame(aux_segments, step, aux_frame);
+                aux_frame.update_conjugates();
                 air.evaluate_aux_transition(
                     &main_frame,
                     aux_frame,
@@ -206,17 +294,40 @@ pub trait Trace: Sized {
                     aux_rand_elements,
                     &mut aux_evaluations,
                 );
-                for (i, &evaluation) in aux_evaluations.iter().enumerate() {
-                    assert!(
-                        evaluation == E::ZERO,
-                        "auxiliary transition constraint {i} did not evaluate to ZERO at step {step}"
-                    );
+                for (constraint, &evaluation) in aux_evaluations.iter().enumerate() {
+                    if evaluation != E::ZERO {
+                        violations.push(TraceViolation::Transition {
+                            segment: TraceSegment::Aux,
+                            constraint,
+                            step,
+                        });
+                    }
                 }
             }
 
             // update x coordinate of the domain
             x *= g;
         }
+
+        // --- 3. check wraparound constraints ----------------------------------------------------
+
+        // wraparound constraints are checked only at the last step, where the next row of the
+        // frame is the first row of the trace
+        let num_wraparound_constraints = air.context().num_wraparound_constraints();
+        if num_wraparound_constraints > 0 {
+            let step = self.length() - 1;
+            let mut evaluations = vec![Self::BaseField::ZERO; num_wraparound_constraints];
+            self.read_main_frame(step, &mut trace_frame);
+            read_rational_frame(rational_constraints, &trace_frame, &mut main_frame);
+            air.evaluate_wraparound(&main_frame, &mut evaluations);
+            for (constraint, &evaluation) in evaluations.iter().enumerate() {
+                if evaluation != Self::BaseField::ZERO {
+                    violations.push(TraceViolation::Wraparound { constraint, step });
+                }
+            }
+        }
+
+        TraceCheckReport::new(violations)
     }
 }
 
@@ -242,3 +353,22 @@ where
         *next_value = column[next_row_idx];
     }
 }
+
+/// Copies the rows of the specified `trace_frame` into `frame`, and populates columns of the
+/// specified rational constraints in both rows of `frame` in the same way as the prover does when
+/// extending the main trace segment.
+fn read_rational_frame<B>(
+    rational_constraints: &[RationalConstraint],
+    trace_frame: &EvaluationFrame<B>,
+    frame: &mut EvaluationFrame<B>,
+) where
+    B: StarkField,
+{
+    let width = trace_frame.current().len();
+    frame.current_mut()[..width].copy_from_slice(trace_frame.current());
+    frame.next_mut()[..width].copy_from_slice(trace_frame.next());
+    for constraint in rational_constraints {
+        constraint.fill_row(frame.current_mut());
+        constraint.fill_row(frame.next_mut());
+    }
+}
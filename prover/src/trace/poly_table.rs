@@ -47,6 +47,20 @@ impl<E: FieldElement> TracePolyTable<E> {
         self.aux_segment_polys.push(aux_segment_polys);
     }
 
+    /// Adds conjugates of all auxiliary trace polynomials to this polynomial table.
+    ///
+    /// A conjugate of a polynomial is computed by applying frobenius to each of its coefficients.
+    /// The conjugates are appended as an additional auxiliary segment so that they are opened at
+    /// the out-of-domain point and included into the DEEP composition polynomial together with
+    /// the rest of the trace polynomials.
+    pub fn add_aux_conjugates(&mut self) {
+        let conjugates = self
+            .aux_trace_polys()
+            .map(|poly| poly.iter().map(|c| c.frobenius()).collect())
+            .collect();
+        self.aux_segment_polys.push(ColMatrix::new(conjugates));
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -65,10 +79,17 @@ impl<E: FieldElement> TracePolyTable<E> {
     }
 
     /// Returns an out-of-domain evaluation frame constructed by evaluating trace polynomials
-    /// for all columns at points z and z * g, where g is the generator of the trace domain.
-    pub fn get_ood_frame(&self, z: E) -> Vec<Vec<E>> {
+    /// for all columns at points z * g^k for k in 0..frame_width, where g is the generator of the
+    /// trace domain.
+    pub fn get_ood_frame(&self, z: E, frame_width: usize) -> Vec<Vec<E>> {
         let g = E::from(E::BaseField::get_root_of_unity(self.poly_size().ilog2()));
-        vec![self.evaluate_at(z), self.evaluate_at(z * g)]
+        let mut x = z;
+        let mut result = Vec::with_capacity(frame_width);
+        for _ in 0..frame_width {
+            result.push(self.evaluate_at(x));
+            x *= g;
+        }
+        result
     }
 
     /// Returns an iterator over the polynomials of the main trace segment.
@@ -0,0 +1,136 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::fmt::{Display, Formatter};
+use utils::{collections::Vec, string::String};
+
+// TRACE SEGMENT
+// ================================================================================================
+
+/// Segment of an execution trace against which an assertion or a transition constraint is
+/// checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceSegment {
+    /// The main segment of the trace.
+    Main,
+    /// Auxiliary segments of the trace.
+    Aux,
+}
+
+impl Display for TraceSegment {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        match self {
+            Self::Main => write!(f, "main"),
+            Self::Aux => write!(f, "auxiliary"),
+        }
+    }
+}
+
+// TRACE VIOLATION
+// ================================================================================================
+
+/// Describes a single location at which an execution trace is not valid against an AIR.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceViolation {
+    /// The assertion described by `assertion` does not hold at the specified step.
+    Assertion {
+        segment: TraceSegment,
+        assertion: String,
+        step: usize,
+    },
+    /// The transition constraint with the specified index does not evaluate to zero at the
+    /// specified step.
+    Transition {
+        segment: TraceSegment,
+        constraint: usize,
+        step: usize,
+    },
+    /// The wraparound constraint with the specified index does not evaluate to zero at the last
+    /// step of the main trace segment (specified by `step`).
+    Wraparound { constraint: usize, step: usize },
+}
+
+impl TraceViolation {
+    /// Returns the segment of the trace in which this violation occurred.
+    pub fn segment(&self) -> TraceSegment {
+        match self {
+            Self::Assertion { segment, .. } | Self::Transition { segment, .. } => *segment,
+            Self::Wraparound { .. } => TraceSegment::Main,
+        }
+    }
+
+    /// Returns the step of the trace at which this violation occurred.
+    pub fn step(&self) -> usize {
+        match self {
+            Self::Assertion { step, .. }
+            | Self::Transition { step, .. }
+            | Self::Wraparound { step, .. } => *step,
+        }
+    }
+}
+
+impl Display for TraceViolation {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        match self {
+            Self::Assertion { segment, assertion, step } => write!(
+                f,
+                "trace does not satisfy assertion {assertion} against the {segment} trace at step {step}"
+            ),
+            Self::Transition { segment, constraint, step } => write!(
+                f,
+                "{segment} transition constraint {constraint} did not evaluate to ZERO at step {step}"
+            ),
+            Self::Wraparound { constraint, step } => write!(
+                f,
+                "wraparound constraint {constraint} did not evaluate to ZERO at step {step}"
+            ),
+        }
+    }
+}
+
+// TRACE CHECK REPORT
+// ================================================================================================
+
+/// Result of checking an execution trace against all assertions and transition constraints of
+/// an AIR.
+///
+/// Unlike [Trace::validate()](crate::Trace::validate), which panics on the first violation, the
+/// report lists all locations at which the trace is not valid: violated assertions are listed
+/// first (main trace segment followed by auxiliary segments), followed by violated transition
+/// constraints in the order of trace steps, followed by violated wraparound constraints.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceCheckReport {
+    violations: Vec<TraceViolation>,
+}
+
+impl TraceCheckReport {
+    /// Returns a new report listing the specified violations.
+    pub fn new(violations: Vec<TraceViolation>) -> Self {
+        Self { violations }
+    }
+
+    /// Returns true if the checked trace satisfies all assertions and transition constraints.
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Returns all violations found in the checked trace.
+    pub fn violations(&self) -> &[TraceViolation] {
+        &self.violations
+    }
+}
+
+impl Display for TraceCheckReport {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        if self.is_valid() {
+            return write!(f, "trace is valid");
+        }
+        write!(f, "trace is not valid; found {} violation(s):", self.violations.len())?;
+        for violation in self.violations.iter() {
+            write!(f, "\n- {violation}")?;
+        }
+        Ok(())
+    }
+}
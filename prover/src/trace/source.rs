@@ -0,0 +1,67 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use math::StarkField;
+use utils::collections::Vec;
+
+// TRACE SOURCE TRAIT
+// ================================================================================================
+/// Defines a source of execution trace values consumed by the prover.
+///
+/// A trace source decouples storage of an execution trace from proof generation: the trace can be
+/// read from a database, a file, or produced by a generator, and is materialized into a
+/// [TraceTable](crate::TraceTable) only when a proof is about to be generated (see
+/// [Prover::prove_from_source()](crate::Prover::prove_from_source)).
+///
+/// A source must yield the execution trace either row by row via [into_rows()](Self::into_rows),
+/// or column by column via [into_columns()](Self::into_columns). By default, columns are assembled
+/// from the rows yielded by the source; sources which store traces in column-major order can
+/// override this to avoid the transposition.
+///
+/// The yielded trace must satisfy the requirements of [TraceTable::init()](crate::TraceTable::init):
+/// it must have between 1 and 255 columns, and its length must be a power of two no smaller
+/// than 8.
+pub trait TraceSource: Sized {
+    /// Base field for this execution trace.
+    type BaseField: StarkField;
+
+    /// Iterator over rows of this execution trace.
+    type Rows: Iterator<Item = Vec<Self::BaseField>>;
+
+    // REQUIRED METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of columns in this execution trace.
+    fn num_columns(&self) -> usize;
+
+    /// Consumes this source and returns an iterator over rows of the execution trace.
+    fn into_rows(self) -> Self::Rows;
+
+    // PROVIDED METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Consumes this source and returns columns of the execution trace.
+    ///
+    /// # Panics
+    /// Panics if any of the rows yielded by the source does not contain exactly
+    /// [num_columns()](Self::num_columns) values.
+    fn into_columns(self) -> Vec<Vec<Self::BaseField>> {
+        let num_columns = self.num_columns();
+        let mut columns = vec![Vec::new(); num_columns];
+        for row in self.into_rows() {
+            assert_eq!(
+                row.len(),
+                num_columns,
+                "expected a trace row of {} values, but was {}",
+                num_columns,
+                row.len()
+            );
+            for (column, value) in columns.iter_mut().zip(row) {
+                column.push(value);
+            }
+        }
+        columns
+    }
+}
@@ -3,8 +3,12 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::{tests::build_fib_trace, Trace};
-use math::fields::f128::BaseElement;
+use crate::{
+    tests::{build_fib_trace, MockAir},
+    BitPackedTraceTable, Trace, TraceSegment, TraceSource, TraceTable, TraceViolation,
+};
+use air::{Assertion, AuxTraceRandElements};
+use math::{fields::f128::BaseElement, FieldElement, StarkField};
 use utils::collections::Vec;
 
 #[test]
@@ -27,3 +31,162 @@ fn new_trace_table() {
         .collect();
     assert_eq!(expected, trace.get_column(1));
 }
+
+#[test]
+fn trace_table_derived_columns() {
+    let trace_length = 8;
+    let mut trace = build_fib_trace(trace_length * 2);
+    trace.add_derived_column(|row| row[0] * row[1]);
+    trace.add_derived_column(|row| row[2] + row[0]);
+
+    assert_eq!(4, trace.main_trace_width());
+    assert_eq!(4, trace.get_info().width());
+
+    // derived values are computed on demand before the columns are materialized
+    let mut row = vec![BaseElement::ZERO; 4];
+    for step in 0..trace_length {
+        let product = trace.get(0, step) * trace.get(1, step);
+        assert_eq!(product, trace.get(2, step));
+        assert_eq!(product + trace.get(0, step), trace.get(3, step));
+
+        trace.read_row_into(step, &mut row);
+        assert_eq!([trace.get(0, step), trace.get(1, step), product, product + row[0]], row[..]);
+    }
+
+    // materialized columns contain the same values
+    trace.finalize_main_segment();
+    assert_eq!(4, trace.main_segment().num_cols());
+    for step in 0..trace_length {
+        let product = trace.get(0, step) * trace.get(1, step);
+        assert_eq!(product, trace.get_column(2)[step]);
+        assert_eq!(product + trace.get(0, step), trace.get_column(3)[step]);
+    }
+}
+
+#[test]
+fn trace_table_source() {
+    let trace_length = 8;
+    let mut trace = build_fib_trace(trace_length * 2);
+    trace.add_derived_column(|row| row[0] * row[1]);
+    assert_eq!(3, trace.num_columns());
+
+    // rows yielded by the trace include values of derived columns
+    let rows = trace.clone().into_rows().collect::<Vec<_>>();
+    assert_eq!(trace_length, rows.len());
+    for (step, row) in rows.iter().enumerate() {
+        let product = trace.get(0, step) * trace.get(1, step);
+        assert_eq!([trace.get(0, step), trace.get(1, step), product], row[..]);
+    }
+
+    // a trace table built from the source contains the same columns
+    let result = TraceTable::from_source(trace.clone());
+    trace.finalize_main_segment();
+    assert_eq!(trace.width(), result.width());
+    for col_idx in 0..trace.width() {
+        assert_eq!(trace.get_column(col_idx), result.get_column(col_idx));
+    }
+}
+
+#[test]
+fn bit_packed_trace_table() {
+    let trace_length = 128;
+    let fib_trace = build_fib_trace(trace_length * 2);
+
+    // the first two columns are copied from the Fibonacci trace, and the last two columns
+    // contain the least significant bits of the first two columns
+    let mut trace = BitPackedTraceTable::new(4, trace_length, &[2, 3]);
+    assert!(!trace.is_bit_column(0));
+    assert!(trace.is_bit_column(3));
+    let mut row = vec![BaseElement::ZERO; 4];
+    for step in 0..trace_length {
+        fib_trace.read_row_into(step, &mut row[..2]);
+        trace.set(0, step, row[0]);
+        trace.set(1, step, row[1]);
+        trace.set_bit(2, step, row[0].as_int() & 1 == 1);
+        trace.set(3, step, BaseElement::from((row[1].as_int() & 1) as u8));
+    }
+
+    let columns = (0..4)
+        .map(|col_idx| (0..trace_length).map(|step| trace.get(col_idx, step)).collect())
+        .collect::<Vec<Vec<_>>>();
+    let unpacked_trace = TraceTable::init(columns);
+
+    // values read from the packed trace are the same as the values of the unpacked trace
+    let mut unpacked_row = vec![BaseElement::ZERO; 4];
+    for step in 0..trace_length {
+        trace.read_row_into(step, &mut row);
+        unpacked_trace.read_row_into(step, &mut unpacked_row);
+        assert_eq!(unpacked_row, row);
+        assert_eq!(BaseElement::from((row[0].as_int() & 1) as u8), row[2]);
+    }
+
+    // once unpacked, the trace interpolates into the same polynomials as the unpacked trace
+    trace.finalize_main_segment();
+    assert_eq!(trace.get_info(), unpacked_trace.get_info());
+    let polys = trace.main_segment().interpolate_columns();
+    let expected_polys = unpacked_trace.main_segment().interpolate_columns();
+    for (poly, expected_poly) in polys.columns().zip(expected_polys.columns()) {
+        assert_eq!(expected_poly, poly);
+    }
+}
+
+#[test]
+#[should_panic(expected = "value in bit column 1 at step 5 must be 0 or 1, but was 2")]
+fn bit_packed_trace_table_non_binary_value() {
+    let mut trace = BitPackedTraceTable::new(2, 8, &[1]);
+    trace.set(0, 5, BaseElement::from(2u8));
+    trace.set(1, 5, BaseElement::from(2u8));
+}
+
+#[test]
+fn trace_table_linear_combination_assertion() {
+    let trace = build_combination_trace();
+
+    // 2 * col_0 + col_1 - col_3 at step 3 is equal to 2 * 3 + 4 - 6
+    let air = MockAir::with_assertions(vec![build_combination_assertion(4)], 8);
+    trace.validate::<_, BaseElement>(&air, &[], &AuxTraceRandElements::new());
+}
+
+#[test]
+#[should_panic(expected = "trace does not satisfy assertion")]
+fn trace_table_linear_combination_assertion_violated() {
+    let trace = build_combination_trace();
+    let air = MockAir::with_assertions(vec![build_combination_assertion(5)], 8);
+    trace.validate::<_, BaseElement>(&air, &[], &AuxTraceRandElements::new());
+}
+
+#[test]
+fn trace_table_check_reports_all_violations() {
+    let trace = build_combination_trace();
+    let air = MockAir::with_assertions(vec![build_combination_assertion(4)], 8);
+    assert!(trace
+        .check::<_, BaseElement>(&air, &[], &AuxTraceRandElements::new())
+        .is_valid());
+
+    // both violated assertions are reported, rather than only the first one
+    let assertions =
+        vec![build_combination_assertion(5), Assertion::single(2, 6, BaseElement::ONE)];
+    let air = MockAir::with_assertions(assertions, 8);
+    let report = trace.check::<_, BaseElement>(&air, &[], &AuxTraceRandElements::new());
+    assert!(!report.is_valid());
+    assert_eq!(2, report.violations().len());
+    for (violation, step) in report.violations().iter().zip([3, 6]) {
+        assert!(matches!(violation, TraceViolation::Assertion { .. }));
+        assert_eq!(TraceSegment::Main, violation.segment());
+        assert_eq!(step, violation.step());
+    }
+}
+
+/// Builds a trace of 4 columns and 8 rows where the value in column i at step j is i + j.
+fn build_combination_trace() -> TraceTable<BaseElement> {
+    let columns = (0..4u32)
+        .map(|i| (0..8u32).map(|j| BaseElement::from(i + j)).collect())
+        .collect::<Vec<_>>();
+    TraceTable::init(columns)
+}
+
+/// Builds an assertion that 2 * col_0 + col_1 - col_3 at step 3 is equal to the specified value.
+fn build_combination_assertion(value: u32) -> Assertion<BaseElement> {
+    let coefficients = vec![BaseElement::from(2u8), BaseElement::ONE, -BaseElement::ONE];
+    Assertion::linear_combination(coefficients, vec![0, 1, 3], 3, BaseElement::from(value))
+}
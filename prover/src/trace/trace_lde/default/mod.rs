@@ -7,7 +7,8 @@ use super::{
     ColMatrix, ElementHasher, EvaluationFrame, FieldElement, Hasher, Queries, StarkDomain,
     TraceInfo, TraceLayout, TraceLde, TracePolyTable, Vec,
 };
-use crate::{RowMatrix, DEFAULT_SEGMENT_WIDTH};
+use crate::{matrix::InterpolationMethod, RowMatrix, DEFAULT_SEGMENT_WIDTH};
+use air::{proof::hash_column_roots, CommitmentLayout, TraceCommitmentOptions};
 use crypto::MerkleTree;
 
 #[cfg(feature = "std")]
@@ -23,6 +24,9 @@ mod tests;
 /// Contains all segments of the extended execution trace, the commitments to these segments, the
 /// LDE blowup factor, and the [TraceInfo].
 ///
+/// Segments are committed to using the [TraceCommitmentOptions] specified at construction time; the same layout is used for all segments. Likewise, columns of
+/// all segments are interpolated using the [InterpolationMethod] specified at construction time.
+///
 /// Segments are stored in two groups:
 /// - Main segment: this is the first trace segment generated by the prover. Values in this segment
 ///   will always be elements in the base field (even when an extension field is used).
@@ -32,11 +36,13 @@ pub struct DefaultTraceLde<E: FieldElement, H: ElementHasher<BaseField = E::Base
     // low-degree extension of the main segment of the trace
     main_segment_lde: RowMatrix<E::BaseField>,
     // commitment to the main segment of the trace
-    main_segment_tree: MerkleTree<H>,
+    main_segment_commitment: SegmentCommitment<H>,
     // low-degree extensions of the auxiliary segments of the trace
     aux_segment_ldes: Vec<RowMatrix<E>>,
     // commitment to the auxiliary segments of the trace
-    aux_segment_trees: Vec<MerkleTree<H>>,
+    aux_segment_commitments: Vec<SegmentCommitment<H>>,
+    commitment_options: TraceCommitmentOptions,
+    interpolation: InterpolationMethod,
     blowup: usize,
     trace_info: TraceInfo,
 }
@@ -44,8 +50,11 @@ pub struct DefaultTraceLde<E: FieldElement, H: ElementHasher<BaseField = E::Base
 impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> DefaultTraceLde<E, H> {
     /// Takes the main trace segment columns as input, interpolates them into polynomials in
     /// coefficient form, evaluates the polynomials over the LDE domain, commits to the
-    /// polynomial evaluations, and creates a new [DefaultTraceLde] with the LDE of the main trace
-    /// segment and the commitment.
+    /// polynomial evaluations using the specified `commitment_options`, and creates a new
+    /// [DefaultTraceLde] with the LDE of the main trace segment and the commitment.
+    ///
+    /// For the row-major layout, each leaf of the commitment contains the specified number of
+    /// adjacent rows of the LDE; for the per-column layout, the number of rows per leaf is ignored.
     ///
     /// Returns a tuple containing a [TracePolyTable] with the trace polynomials for the main trace
     /// segment and the new [DefaultTraceLde].
@@ -53,17 +62,47 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> DefaultTraceLd
         trace_info: &TraceInfo,
         main_trace: &ColMatrix<E::BaseField>,
         domain: &StarkDomain<E::BaseField>,
+        commitment_options: TraceCommitmentOptions,
+    ) -> (Self, TracePolyTable<E>) {
+        Self::with_interpolation(
+            trace_info,
+            main_trace,
+            domain,
+            commitment_options,
+            InterpolationMethod::default(),
+        )
+    }
+
+    /// Same as [new()](Self::new), but columns of all trace segments are interpolated into
+    /// polynomials using the specified `interpolation` method instead of the default one (i.e.,
+    /// the inverse FFT for all but the smallest traces).
+    ///
+    /// The resulting trace polynomials, LDE, and commitments are the same for all interpolation
+    /// methods.
+    pub fn with_interpolation(
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<E::BaseField>,
+        domain: &StarkDomain<E::BaseField>,
+        commitment_options: TraceCommitmentOptions,
+        interpolation: InterpolationMethod,
     ) -> (Self, TracePolyTable<E>) {
-        // extend the main execution trace and build a Merkle tree from the extended trace
-        let (main_segment_lde, main_segment_tree, main_segment_polys) =
-            build_trace_commitment::<E, E::BaseField, H>(main_trace, domain);
+        // extend the main execution trace and build a commitment to the extended trace
+        let (main_segment_lde, main_segment_commitment, main_segment_polys) =
+            build_trace_commitment::<E, E::BaseField, H>(
+                main_trace,
+                domain,
+                commitment_options,
+                interpolation,
+            );
 
         let trace_poly_table = TracePolyTable::new(main_segment_polys);
         let trace_lde = DefaultTraceLde {
             main_segment_lde,
-            main_segment_tree,
+            main_segment_commitment,
             aux_segment_ldes: Vec::new(),
-            aux_segment_trees: Vec::new(),
+            aux_segment_commitments: Vec::new(),
+            commitment_options,
+            interpolation,
             blowup: domain.trace_to_lde_blowup(),
             trace_info: trace_info.clone(),
         };
@@ -104,8 +143,7 @@ where
 
     /// Returns the commitment to the low-degree extension of the main trace segment.
     fn get_main_trace_commitment(&self) -> <Self::HashFn as Hasher>::Digest {
-        let root_hash = self.main_segment_tree.root();
-        *root_hash
+        self.main_segment_commitment.root()
     }
 
     /// Takes auxiliary trace segment columns as input, interpolates them into polynomials in
@@ -125,9 +163,14 @@ where
         aux_trace: &ColMatrix<E>,
         domain: &StarkDomain<E::BaseField>,
     ) -> (ColMatrix<E>, <Self::HashFn as Hasher>::Digest) {
-        // extend the auxiliary trace segment and build a Merkle tree from the extended trace
-        let (aux_segment_lde, aux_segment_tree, aux_segment_polys) =
-            build_trace_commitment::<E, E, H>(aux_trace, domain);
+        // extend the auxiliary trace segment and build a commitment to the extended trace
+        let (aux_segment_lde, aux_segment_commitment, aux_segment_polys) =
+            build_trace_commitment::<E, E, H>(
+                aux_trace,
+                domain,
+                self.commitment_options,
+                self.interpolation,
+            );
 
         // check errors
         assert!(
@@ -142,8 +185,8 @@ where
 
         // save the lde and commitment
         self.aux_segment_ldes.push(aux_segment_lde);
-        let root_hash = *aux_segment_tree.root();
-        self.aux_segment_trees.push(aux_segment_tree);
+        let root_hash = aux_segment_commitment.root();
+        self.aux_segment_commitments.push(aux_segment_commitment);
 
         (aux_segment_polys, root_hash)
     }
@@ -181,16 +224,13 @@ where
     /// from the commitment root to these rows.
     fn query(&self, positions: &[usize]) -> Vec<Queries> {
         // build queries for the main trace segment
-        let mut result = vec![build_segment_queries(
-            &self.main_segment_lde,
-            &self.main_segment_tree,
-            positions,
-        )];
+        let mut result =
+            vec![self.main_segment_commitment.query(&self.main_segment_lde, positions)];
 
         // build queries for auxiliary trace segments
-        for (i, segment_tree) in self.aux_segment_trees.iter().enumerate() {
+        for (i, segment_commitment) in self.aux_segment_commitments.iter().enumerate() {
             let segment_lde = &self.aux_segment_ldes[i];
-            result.push(build_segment_queries(segment_lde, segment_tree, positions));
+            result.push(segment_commitment.query(segment_lde, positions));
         }
 
         result
@@ -219,15 +259,19 @@ where
 /// domain and builds a commitment to the extended trace.
 ///
 /// The extension is performed by interpolating each column of the execution trace into a
-/// polynomial of degree = trace_length - 1, and then evaluating the polynomial over the LDE
-/// domain.
+/// polynomial of degree = trace_length - 1 using the specified `interpolation` method, and then
+/// evaluating the polynomial over the LDE domain.
 ///
-/// The trace commitment is computed by hashing each row of the extended execution trace, then
-/// building a Merkle tree from the resulting hashes.
+/// For the row-major layout, the trace commitment is computed by hashing each group of
+/// `rows_per_leaf` adjacent rows of the extended execution trace, then building a Merkle tree from
+/// the resulting hashes. For the per-column layout, a separate Merkle tree is built from hashes of
+/// values in each column.
 fn build_trace_commitment<E, F, H>(
     trace: &ColMatrix<F>,
     domain: &StarkDomain<E::BaseField>,
-) -> (RowMatrix<F>, MerkleTree<H>, ColMatrix<F>)
+    commitment_options: TraceCommitmentOptions,
+    interpolation: InterpolationMethod,
+) -> (RowMatrix<F>, SegmentCommitment<H>, ColMatrix<F>)
 where
     E: FieldElement,
     F: FieldElement<BaseField = E::BaseField>,
@@ -236,7 +280,7 @@ where
     // extend the execution trace
     #[cfg(feature = "std")]
     let now = Instant::now();
-    let trace_polys = trace.interpolate_columns();
+    let trace_polys = trace.interpolate_columns_with(interpolation);
     let trace_lde = RowMatrix::evaluate_polys_over::<DEFAULT_SEGMENT_WIDTH>(&trace_polys, domain);
     #[cfg(feature = "std")]
     debug!(
@@ -251,35 +295,133 @@ where
     // build trace commitment
     #[cfg(feature = "std")]
     let now = Instant::now();
-    let trace_tree = trace_lde.commit_to_rows();
+    let trace_commitment = SegmentCommitment::new(&trace_lde, commitment_options);
     #[cfg(feature = "std")]
     debug!(
-        "Computed execution trace commitment (Merkle tree of depth {}) in {} ms",
-        trace_tree.depth(),
+        "Computed execution trace commitment ({} Merkle tree(s) of depth {}) in {} ms",
+        trace_commitment.num_trees(),
+        trace_commitment.tree_depth(trace_lde.num_rows()),
         now.elapsed().as_millis()
     );
 
-    (trace_lde, trace_tree, trace_polys)
+    (trace_lde, trace_commitment, trace_polys)
 }
 
-fn build_segment_queries<E, H>(
-    segment_lde: &RowMatrix<E>,
-    segment_tree: &MerkleTree<H>,
-    positions: &[usize],
-) -> Queries
-where
-    E: FieldElement,
-    H: ElementHasher<BaseField = E::BaseField>,
-{
-    // for each position, get the corresponding row from the trace segment LDE and put all these
-    // rows into a single vector
-    let trace_states =
-        positions.iter().map(|&pos| segment_lde.row(pos).to_vec()).collect::<Vec<_>>();
+// SEGMENT COMMITMENT
+// ================================================================================================
+
+/// Merkle tree(s) committing to the low-degree extension of a single trace segment.
+enum SegmentCommitment<H: Hasher> {
+    /// A single tree such that each leaf is a hash of `rows_per_leaf` entire adjacent rows of the
+    /// segment.
+    RowMajor {
+        tree: MerkleTree<H>,
+        rows_per_leaf: usize,
+    },
+    /// A tree for each column of the segment such that each leaf is a hash of a single value.
+    PerColumn(Vec<MerkleTree<H>>),
+}
 
-    // build Merkle authentication paths to the leaves specified by positions
-    let trace_proof = segment_tree
-        .prove_batch(positions)
-        .expect("failed to generate a Merkle proof for trace queries");
+impl<H: ElementHasher> SegmentCommitment<H> {
+    /// Commits to the provided segment LDE using the specified layout and number of rows per leaf.
+    fn new<E>(segment_lde: &RowMatrix<E>, options: TraceCommitmentOptions) -> Self
+    where
+        E: FieldElement<BaseField = H::BaseField>,
+    {
+        let rows_per_leaf = options.rows_per_leaf;
+        match options.layout {
+            CommitmentLayout::RowMajor => Self::RowMajor {
+                tree: segment_lde.commit_to_packed_rows(rows_per_leaf),
+                rows_per_leaf,
+            },
+            CommitmentLayout::PerColumn => Self::PerColumn(segment_lde.commit_to_columns()),
+        }
+    }
+
+    /// Returns the number of Merkle trees in this commitment.
+    #[cfg(feature = "std")]
+    fn num_trees(&self) -> usize {
+        match self {
+            Self::RowMajor { .. } => 1,
+            Self::PerColumn(trees) => trees.len(),
+        }
+    }
+
+    /// Returns the depth of the Merkle tree(s) in this commitment for a segment with the
+    /// specified number of rows.
+    #[cfg(feature = "std")]
+    fn tree_depth(&self, num_rows: usize) -> u32 {
+        match self {
+            Self::RowMajor { rows_per_leaf, .. } => (num_rows / rows_per_leaf).ilog2(),
+            Self::PerColumn(_) => num_rows.ilog2(),
+        }
+    }
+
+    /// Returns the root of the Merkle tree for the row-major layout, or a hash of the roots of
+    /// all column Merkle trees for the per-column layout.
+    fn root(&self) -> H::Digest {
+        match self {
+            Self::RowMajor { tree, .. } => *tree.root(),
+            Self::PerColumn(trees) => {
+                let roots = trees.iter().map(|tree| *tree.root()).collect::<Vec<_>>();
+                hash_column_roots::<H>(&roots)
+            }
+        }
+    }
 
-    Queries::new(trace_proof, trace_states)
+    /// Returns rows of the `segment_lde` at the specified positions together with Merkle
+    /// authentication paths for these rows.
+    ///
+    /// When several rows are packed into a single leaf, all rows of the leaves containing the
+    /// specified positions are returned; each leaf is opened only once.
+    fn query<E>(&self, segment_lde: &RowMatrix<E>, positions: &[usize]) -> Queries
+    where
+        E: FieldElement<BaseField = H::BaseField>,
+    {
+        match self {
+            Self::RowMajor {
+                tree,
+                rows_per_leaf,
+            } => {
+                // positions are sorted, and thus, positions falling into the same leaf are
+                // adjacent
+                let mut leaf_positions =
+                    positions.iter().map(|&pos| pos / rows_per_leaf).collect::<Vec<_>>();
+                leaf_positions.dedup();
+
+                // for each leaf, concatenate all rows of the trace segment LDE committed to by
+                // the leaf
+                let leaf_states = leaf_positions
+                    .iter()
+                    .map(|&leaf_pos| {
+                        let first_row = leaf_pos * rows_per_leaf;
+                        (first_row..first_row + rows_per_leaf)
+                            .flat_map(|row_idx| segment_lde.row(row_idx).iter().copied())
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+
+                // build Merkle authentication paths to the leaves
+                let trace_proof = tree
+                    .prove_batch(&leaf_positions)
+                    .expect("failed to generate a Merkle proof for trace queries");
+                Queries::new(trace_proof, leaf_states)
+            }
+            Self::PerColumn(trees) => {
+                // for each position, get the corresponding row from the trace segment LDE and
+                // put all these rows into a single vector
+                let trace_states =
+                    positions.iter().map(|&pos| segment_lde.row(pos).to_vec()).collect::<Vec<_>>();
+
+                let column_proofs = trees
+                    .iter()
+                    .map(|tree| {
+                        tree.prove_batch(positions)
+                            .expect("failed to generate a Merkle proof for trace queries")
+                    })
+                    .collect();
+                Queries::new_per_column(column_proofs, trace_states)
+            }
+        }
+    }
 }
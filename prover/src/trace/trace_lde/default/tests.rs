@@ -4,9 +4,11 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::{
+    matrix::InterpolationMethod,
     tests::{build_fib_trace, MockAir},
     DefaultTraceLde, StarkDomain, Trace, TraceLde,
 };
+use air::{proof::hash_column_roots, CommitmentLayout, TraceCommitmentOptions};
 use crypto::{hashers::Blake3_256, ElementHasher, MerkleTree};
 use math::{
     fields::f128::BaseElement, get_power_series, get_power_series_with_offset, polynom,
@@ -29,6 +31,7 @@ fn extend_trace_table() {
         &trace.get_info(),
         trace.main_segment(),
         &domain,
+        TraceCommitmentOptions::default(),
     );
 
     // check the width and length of the extended trace
@@ -66,6 +69,37 @@ fn extend_trace_table() {
     );
 }
 
+#[test]
+fn extend_trace_table_barycentric() {
+    // build the trace and the domain
+    let trace_length = 8;
+    let air = MockAir::with_trace_length(trace_length);
+    let trace = build_fib_trace(trace_length * 2);
+    let domain = StarkDomain::new(&air);
+
+    // the trace polynomials, extended trace, and commitment are the same regardless of the
+    // interpolation method
+    let (expected_lde, expected_polys) = DefaultTraceLde::<BaseElement, Blake3>::new(
+        &trace.get_info(),
+        trace.main_segment(),
+        &domain,
+        TraceCommitmentOptions::default(),
+    );
+    let (trace_lde, trace_polys) = DefaultTraceLde::<BaseElement, Blake3>::with_interpolation(
+        &trace.get_info(),
+        trace.main_segment(),
+        &domain,
+        TraceCommitmentOptions::default(),
+        InterpolationMethod::Barycentric,
+    );
+
+    for i in 0..trace_lde.main_segment_width() {
+        assert_eq!(expected_polys.get_main_trace_poly(i), trace_polys.get_main_trace_poly(i));
+        assert_eq!(expected_lde.get_main_segment_column(i), trace_lde.get_main_segment_column(i));
+    }
+    assert_eq!(expected_lde.get_main_trace_commitment(), trace_lde.get_main_trace_commitment());
+}
+
 #[test]
 fn commit_trace_table() {
     // build the trace and the domain
@@ -79,6 +113,7 @@ fn commit_trace_table() {
         &trace.get_info(),
         trace.main_segment(),
         &domain,
+        TraceCommitmentOptions::default(),
     );
 
     // build Merkle tree from trace rows
@@ -98,6 +133,108 @@ fn commit_trace_table() {
     assert_eq!(*expected_tree.root(), trace_lde.get_main_trace_commitment())
 }
 
+#[test]
+fn commit_trace_table_per_column() {
+    // build the trace and the domain
+    let trace_length = 8;
+    let air = MockAir::with_trace_length(trace_length);
+    let trace = build_fib_trace(trace_length * 2);
+    let domain = StarkDomain::new(&air);
+
+    // build the extended trace and commit to each of its columns separately
+    let (trace_lde, _) = DefaultTraceLde::<BaseElement, Blake3>::new(
+        &trace.get_info(),
+        trace.main_segment(),
+        &domain,
+        TraceCommitmentOptions {
+            layout: CommitmentLayout::PerColumn,
+            rows_per_leaf: 1,
+        },
+    );
+
+    // build a Merkle tree from values of each column
+    let column_roots = (0..trace_lde.main_segment_width())
+        .map(|j| {
+            let hashed_values = trace_lde
+                .get_main_segment_column(j)
+                .iter()
+                .map(|value| Blake3::hash_elements(&[*value]))
+                .collect();
+            *MerkleTree::<Blake3>::new(hashed_values).unwrap().root()
+        })
+        .collect::<Vec<_>>();
+
+    // compare the result
+    let expected_root = hash_column_roots::<Blake3>(&column_roots);
+    assert_eq!(expected_root, trace_lde.get_main_trace_commitment());
+
+    // queries must contain the queried rows and authenticate against the commitment
+    let positions = [1, 7, 30];
+    let queries = trace_lde.query(&positions).remove(0);
+    let (proofs, states) = queries
+        .parse_per_column::<Blake3, BaseElement>(trace_lde.trace_len(), positions.len(), 2)
+        .unwrap();
+    for (i, &position) in positions.iter().enumerate() {
+        assert_eq!(trace_lde.get_main_segment().row(position), states.get_row(i));
+    }
+    let roots = proofs
+        .iter()
+        .map(|proof| proof.get_root(&positions).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(column_roots, roots);
+}
+
+#[test]
+fn commit_trace_table_packed_rows() {
+    // build the trace and the domain
+    let trace_length = 8;
+    let air = MockAir::with_trace_length(trace_length);
+    let trace = build_fib_trace(trace_length * 2);
+    let domain = StarkDomain::new(&air);
+
+    // build the extended trace and commit to it packing 4 rows into each leaf
+    let rows_per_leaf = 4;
+    let (trace_lde, _) = DefaultTraceLde::<BaseElement, Blake3>::new(
+        &trace.get_info(),
+        trace.main_segment(),
+        &domain,
+        TraceCommitmentOptions {
+            layout: CommitmentLayout::RowMajor,
+            rows_per_leaf,
+        },
+    );
+
+    // build Merkle tree from concatenated groups of trace rows
+    let hashed_leaves = (0..trace_lde.trace_len() / rows_per_leaf)
+        .map(|i| {
+            let leaf = (i * rows_per_leaf..(i + 1) * rows_per_leaf)
+                .flat_map(|row_idx| trace_lde.get_main_segment().row(row_idx).to_vec())
+                .collect::<Vec<_>>();
+            Blake3::hash_elements(&leaf)
+        })
+        .collect();
+    let expected_tree = MerkleTree::<Blake3>::new(hashed_leaves).unwrap();
+    assert_eq!(*expected_tree.root(), trace_lde.get_main_trace_commitment());
+
+    // queries falling into the same leaf open this leaf only once
+    let positions = [1, 2, 7, 30];
+    let leaf_positions = [0, 1, 7];
+    let queries = trace_lde.query(&positions).remove(0);
+    let (proof, leaves) = queries
+        .parse_packed::<Blake3, BaseElement>(trace_lde.trace_len(), rows_per_leaf, 2)
+        .unwrap();
+    assert_eq!(leaf_positions.len(), leaves.num_rows());
+    for &position in positions.iter() {
+        let leaf_idx = leaf_positions.iter().position(|&p| p == position / rows_per_leaf).unwrap();
+        let offset = (position % rows_per_leaf) * 2;
+        assert_eq!(
+            trace_lde.get_main_segment().row(position),
+            &leaves.get_row(leaf_idx)[offset..offset + 2]
+        );
+    }
+    assert!(MerkleTree::verify_batch(expected_tree.root(), &leaf_positions, &proof).is_ok());
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
@@ -21,6 +21,14 @@ pub use default::DefaultTraceLde;
 ///   will always be elements in the base field (even when an extension field is used).
 /// - Auxiliary segments: a list of 0 or more segments for traces generated after the prover
 ///   commits to the first trace segment. Currently, at most 1 auxiliary segment is possible.
+///
+/// This trait is the boundary between the prover and the backend which extends and commits to
+/// the execution trace. [DefaultTraceLde] is the CPU backend; a hardware-accelerated backend
+/// (e.g., one which keeps trace segment LDEs in GPU memory and computes FFTs and Merkle trees
+/// on the device) can be used instead by implementing this trait and returning it from
+/// [Prover::new_trace_lde()](crate::Prover::new_trace_lde). The prover accesses trace LDEs only
+/// through the methods of this trait, and thus, the backend is free to decide where the LDEs are
+/// stored, as long as rows requested by the prover can be read into host memory.
 pub trait TraceLde<E: FieldElement>: Sync {
     /// The hash function used for building the Merkle tree commitments to trace segment LDEs.
     type HashFn: ElementHasher<BaseField = E::BaseField>;
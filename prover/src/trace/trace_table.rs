@@ -3,17 +3,27 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{ColMatrix, Trace};
+use super::{ColMatrix, Trace, TraceSource};
 use air::{EvaluationFrame, TraceInfo, TraceLayout};
+use core::fmt;
 use math::{FieldElement, StarkField};
-use utils::{collections::Vec, uninit_vector};
+use utils::{
+    collections::{vec, Vec},
+    uninit_vector,
+};
 
-#[cfg(not(feature = "concurrent"))]
-use utils::collections::vec;
+#[cfg(not(feature = "std"))]
+use alloc::sync::Arc;
+
+#[cfg(feature = "std")]
+use std::sync::Arc;
 
 #[cfg(feature = "concurrent")]
 use utils::{iterators::*, rayon};
 
+#[cfg(feature = "test-utils")]
+use crypto::{hashers::Blake3_256, DefaultRandomCoin, RandomCoin};
+
 // CONSTANTS
 // ================================================================================================
 
@@ -59,10 +69,18 @@ const MIN_FRAGMENT_LENGTH: usize = 2;
 /// [fill()](TraceTableFragment::fill) method to fill all fragments with data in parallel.
 /// The semantics of the fragment's [TraceTableFragment::fill()] method are identical to the
 /// semantics of the [TraceTable::fill()] method.
+///
+/// # Derived columns
+/// Columns which are deterministic functions of other columns (e.g., products or inverses) can be
+/// declared via [add_derived_column()](TraceTable::add_derived_column) method instead of being
+/// filled explicitly. Derived columns are appended after all other columns of the trace, but no
+/// memory is allocated for them until the trace is about to be committed to; until then, their
+/// values are computed on demand from the other columns of the same row.
 #[derive(Debug, Clone)]
 pub struct TraceTable<B: StarkField> {
     layout: TraceLayout,
     trace: ColMatrix<B>,
+    derived_columns: Vec<DerivedColumn<B>>,
     meta: Vec<u8>,
 }
 
@@ -128,6 +146,7 @@ impl<B: StarkField> TraceTable<B> {
         Self {
             layout: TraceLayout::new(width, [0], [0]),
             trace: ColMatrix::new(columns),
+            derived_columns: Vec::new(),
             meta,
         }
     }
@@ -169,10 +188,110 @@ impl<B: StarkField> TraceTable<B> {
         Self {
             layout: TraceLayout::new(columns.len(), [0], [0]),
             trace: ColMatrix::new(columns),
+            derived_columns: Vec::new(),
             meta: vec![],
         }
     }
 
+    /// Creates a new execution trace from the columns yielded by the provided trace source.
+    ///
+    /// The returned trace does not contain any metadata; metadata can be attached to it via
+    /// [set_meta()](TraceTable::set_meta) method.
+    ///
+    /// # Panics
+    /// Panics if the columns yielded by the source do not satisfy the requirements of
+    /// [TraceTable::init()].
+    pub fn from_source<S: TraceSource<BaseField = B>>(source: S) -> Self {
+        Self::init(source.into_columns())
+    }
+
+    /// Creates a new execution trace of the specified width and length which starts from a
+    /// pseudo-random state derived from the specified `seed`.
+    ///
+    /// The first row of the trace is filled with pseudo-random values drawn from a public coin
+    /// seeded with `seed`. All subsequent rows are populated by the `update` closure in the same
+    /// way as in [fill()](TraceTable::fill) method. Thus, if `update` applies a valid transition
+    /// of some computation, the returned trace satisfies transition constraints of the AIR for
+    /// this computation for any seed, which makes it possible to property-test proof generation
+    /// and verification of the AIR against many random traces.
+    ///
+    /// This function is available only when the `test-utils` feature is enabled.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [TraceTable::new()].
+    #[cfg(feature = "test-utils")]
+    pub fn random<U>(width: usize, length: usize, seed: u64, update: U) -> Self
+    where
+        U: FnMut(usize, &mut [B]),
+    {
+        let mut coin = DefaultRandomCoin::<Blake3_256<B>>::new(&[B::from(seed)]);
+        let mut trace = Self::new(width, length);
+        trace.fill(
+            |state| {
+                for value in state.iter_mut() {
+                    *value = coin.draw().expect("failed to draw a random trace value");
+                }
+            },
+            update,
+        );
+        trace
+    }
+
+    // DERIVED COLUMNS
+    // --------------------------------------------------------------------------------------------
+
+    /// Declares a new column of this execution trace whose values are derived from values of
+    /// other columns in the same row.
+    ///
+    /// The `derive` closure receives values of all columns preceding the derived column in a given
+    /// row (i.e., all explicitly filled columns followed by previously declared derived columns),
+    /// and must return the value of the derived column in that row.
+    ///
+    /// Values of derived columns are not stored until the trace is committed to (see
+    /// [Trace::finalize_main_segment()]), and are computed on demand when read via
+    /// [get()](TraceTable::get) or [read_row_into()](TraceTable::read_row_into) methods. The
+    /// explicitly filled columns must be fully populated before the trace is committed to.
+    ///
+    /// # Panics
+    /// Panics if the total number of columns in the trace would exceed 255.
+    pub fn add_derived_column<F>(&mut self, derive: F)
+    where
+        F: Fn(&[B]) -> B + Send + Sync + 'static,
+    {
+        let width = self.width() + 1;
+        assert!(
+            width <= TraceInfo::MAX_TRACE_WIDTH,
+            "execution trace width cannot be greater than {}, but was {}",
+            TraceInfo::MAX_TRACE_WIDTH,
+            width
+        );
+        self.derived_columns.push(DerivedColumn(Arc::new(derive)));
+        self.layout = TraceLayout::new(width, [0], [0]);
+    }
+
+    /// Computes values of all derived columns which have not been materialized yet, and appends
+    /// them to the trace.
+    ///
+    /// This is invoked automatically right before the trace is committed to; thus, calling this
+    /// method directly is needed only to access derived columns via
+    /// [get_column()](TraceTable::get_column) method.
+    pub fn materialize_derived_columns(&mut self) {
+        let mut row = vec![B::ZERO; self.width()];
+        let num_filled_columns = self.trace.num_cols();
+        let mut columns = vec![vec![B::ZERO; self.length()]; self.derived_columns.len()];
+        for step in 0..self.length() {
+            self.read_row_into(step, &mut row);
+            for (column, &value) in columns.iter_mut().zip(&row[num_filled_columns..]) {
+                column[step] = value;
+            }
+        }
+
+        for column in columns {
+            self.trace.merge_column(column);
+        }
+        self.derived_columns.clear();
+    }
+
     // DATA MUTATORS
     // --------------------------------------------------------------------------------------------
 
@@ -212,12 +331,14 @@ impl<B: StarkField> TraceTable<B> {
     ///   - index of the last updated row (starting with 0).
     ///   - a mutable reference to the last updated state; the contents of the state are copied
     ///     into the next row of the trace after the closure returns.
+    ///
+    /// The state does not include derived columns which have not been materialized yet.
     pub fn fill<I, U>(&mut self, init: I, mut update: U)
     where
         I: FnOnce(&mut [B]),
         U: FnMut(usize, &mut [B]),
     {
-        let mut state = vec![B::ZERO; self.main_trace_width()];
+        let mut state = vec![B::ZERO; self.trace.num_cols()];
         init(&mut state);
         self.update_row(0, &state);
 
@@ -307,18 +428,37 @@ impl<B: StarkField> TraceTable<B> {
     }
 
     /// Returns the entire trace column at the specified index.
+    ///
+    /// # Panics
+    /// Panics if the column at the specified index is a derived column which has not been
+    /// materialized yet.
     pub fn get_column(&self, col_idx: usize) -> &[B] {
         self.trace.get_column(col_idx)
     }
 
     /// Returns value of the cell in the specified column at the specified row of this trace.
     pub fn get(&self, column: usize, step: usize) -> B {
-        self.trace.get(column, step)
+        if column < self.trace.num_cols() {
+            self.trace.get(column, step)
+        } else {
+            let mut row = vec![B::ZERO; column + 1];
+            self.read_row_into(step, &mut row);
+            row[column]
+        }
     }
 
     /// Reads a single row from this execution trace into the provided target.
+    ///
+    /// Values of derived columns which have not been materialized yet are computed on the fly.
     pub fn read_row_into(&self, step: usize, target: &mut [B]) {
         self.trace.read_row_into(step, target);
+
+        let num_filled_columns = self.trace.num_cols();
+        let num_derived_columns = target.len().saturating_sub(num_filled_columns);
+        for (i, column) in self.derived_columns.iter().take(num_derived_columns).enumerate() {
+            let col_idx = num_filled_columns + i;
+            target[col_idx] = column.derive(&target[..col_idx]);
+        }
     }
 }
 
@@ -342,14 +482,24 @@ impl<B: StarkField> Trace for TraceTable<B> {
 
     fn read_main_frame(&self, row_idx: usize, frame: &mut EvaluationFrame<Self::BaseField>) {
         let next_row_idx = (row_idx + 1) % self.length();
-        self.trace.read_row_into(row_idx, frame.current_mut());
-        self.trace.read_row_into(next_row_idx, frame.next_mut());
+        self.read_row_into(row_idx, frame.current_mut());
+        self.read_row_into(next_row_idx, frame.next_mut());
     }
 
     fn main_segment(&self) -> &ColMatrix<B> {
+        assert!(
+            self.derived_columns.is_empty(),
+            "derived columns must be materialized before the main trace segment is accessed"
+        );
         &self.trace
     }
 
+    fn finalize_main_segment(&mut self) {
+        if !self.derived_columns.is_empty() {
+            self.materialize_derived_columns();
+        }
+    }
+
     fn build_aux_segment<E>(
         &mut self,
         _aux_segments: &[ColMatrix<E>],
@@ -362,6 +512,57 @@ impl<B: StarkField> Trace for TraceTable<B> {
     }
 }
 
+// TRACE SOURCE IMPLEMENTATION
+// ================================================================================================
+
+impl<B: StarkField> TraceSource for TraceTable<B> {
+    type BaseField = B;
+    type Rows = vec::IntoIter<Vec<B>>;
+
+    fn num_columns(&self) -> usize {
+        self.width()
+    }
+
+    fn into_rows(self) -> Self::Rows {
+        let rows = (0..self.length())
+            .map(|step| {
+                let mut row = vec![B::ZERO; self.width()];
+                self.read_row_into(step, &mut row);
+                row
+            })
+            .collect::<Vec<_>>();
+        rows.into_iter()
+    }
+
+    fn into_columns(mut self) -> Vec<Vec<B>> {
+        self.finalize_main_segment();
+        self.trace.into_columns()
+    }
+}
+
+// DERIVED COLUMN
+// ================================================================================================
+
+/// A function which computes a value of a derived column from the values of preceding columns in
+/// the same row.
+type DeriveFn<B> = dyn Fn(&[B]) -> B + Send + Sync;
+
+/// A column of an execution trace defined by a [DeriveFn] closure.
+#[derive(Clone)]
+struct DerivedColumn<B: StarkField>(Arc<DeriveFn<B>>);
+
+impl<B: StarkField> DerivedColumn<B> {
+    fn derive(&self, row: &[B]) -> B {
+        (self.0)(row)
+    }
+}
+
+impl<B: StarkField> fmt::Debug for DerivedColumn<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DerivedColumn")
+    }
+}
+
 // TRACE FRAGMENTS
 // ================================================================================================
 /// A set of consecutive rows of an execution trace.
@@ -14,6 +14,7 @@ extern crate alloc;
 
 pub mod collections;
 pub mod iterators;
+pub mod parallel;
 pub mod string;
 
 use collections::Vec;
@@ -0,0 +1,111 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Pluggable backends for executing independent tasks.
+//!
+//! Code which can process independent tasks in parallel is written against the [Executor] trait
+//! rather than against a specific thread pool. This makes it possible to supply a custom
+//! executor in environments in which `rayon` is not available (e.g., some WASM or embedded
+//! targets), or to run everything in a single thread via [SequentialExecutor].
+//!
+//! When `concurrent` feature is enabled, [DefaultExecutor] is [RayonExecutor]; otherwise, it is
+//! [SequentialExecutor].
+
+use crate::collections::Vec;
+
+// EXECUTOR
+// ================================================================================================
+
+/// Executes independent tasks, either sequentially or in parallel.
+///
+/// Implementations must return the same results as [SequentialExecutor]; i.e., an executor may
+/// only change where and when tasks are executed, but not what the tasks compute.
+pub trait Executor: Sync {
+    /// Executes closures `a` and `b`, potentially in parallel, and returns their results.
+    fn join<A, B, RA, RB>(&self, a: A, b: B) -> (RA, RB)
+    where
+        A: FnOnce() -> RA + Send,
+        B: FnOnce() -> RB + Send,
+        RA: Send,
+        RB: Send;
+
+    /// Applies `op` to each of the `items`, potentially in parallel, and returns the results in
+    /// the same order as the items.
+    fn map<T, R, F>(&self, items: &[T], op: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&T) -> R + Sync + Send;
+}
+
+// SEQUENTIAL EXECUTOR
+// ================================================================================================
+
+/// Executor which runs all tasks one after another in the current thread.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SequentialExecutor;
+
+impl Executor for SequentialExecutor {
+    fn join<A, B, RA, RB>(&self, a: A, b: B) -> (RA, RB)
+    where
+        A: FnOnce() -> RA + Send,
+        B: FnOnce() -> RB + Send,
+        RA: Send,
+        RB: Send,
+    {
+        (a(), b())
+    }
+
+    fn map<T, R, F>(&self, items: &[T], op: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&T) -> R + Sync + Send,
+    {
+        items.iter().map(op).collect()
+    }
+}
+
+// RAYON EXECUTOR
+// ================================================================================================
+
+/// Executor which runs tasks in the global `rayon` thread pool.
+#[cfg(feature = "concurrent")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RayonExecutor;
+
+#[cfg(feature = "concurrent")]
+impl Executor for RayonExecutor {
+    fn join<A, B, RA, RB>(&self, a: A, b: B) -> (RA, RB)
+    where
+        A: FnOnce() -> RA + Send,
+        B: FnOnce() -> RB + Send,
+        RA: Send,
+        RB: Send,
+    {
+        rayon::join(a, b)
+    }
+
+    fn map<T, R, F>(&self, items: &[T], op: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&T) -> R + Sync + Send,
+    {
+        use rayon::prelude::*;
+        items.par_iter().map(op).collect()
+    }
+}
+
+// DEFAULT EXECUTOR
+// ================================================================================================
+
+/// Executor used when no executor is specified explicitly.
+#[cfg(feature = "concurrent")]
+pub type DefaultExecutor = RayonExecutor;
+
+/// Executor used when no executor is specified explicitly.
+#[cfg(not(feature = "concurrent"))]
+pub type DefaultExecutor = SequentialExecutor;
@@ -0,0 +1,133 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::VerifierError;
+use air::{
+    Air, AuxTraceRandElements, ConstraintCompositionCoefficients, DeepCompositionCoefficients,
+};
+use math::FieldElement;
+use utils::{collections::Vec, string::ToString};
+
+// VERIFIER CHALLENGES
+// ================================================================================================
+/// Random challenges used by the verifier to check a STARK proof.
+///
+/// In the non-interactive version of the protocol, all of these challenges are drawn from a
+/// public coin seeded with the transcript of the proof. The challenges can be derived from a
+/// proof via [derive_challenges()](crate::derive_challenges), or can be computed externally
+/// (e.g., collaboratively by several parties) and then supplied to
+/// [verify_with_challenges()](crate::verify_with_challenges).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifierChallenges<E: FieldElement> {
+    /// Random elements for building auxiliary trace segments.
+    pub aux_rand_elements: AuxTraceRandElements<E>,
+    /// Coefficients for computing a random linear combination of constraints.
+    pub constraint_coefficients: ConstraintCompositionCoefficients<E>,
+    /// Out-of-domain point z at which trace and constraint composition polynomials are opened.
+    pub ood_point: E,
+    /// Coefficients for computing the DEEP composition polynomial.
+    pub deep_coefficients: DeepCompositionCoefficients<E>,
+    /// Random α values for each FRI layer.
+    pub fri_alphas: Vec<E>,
+    /// Unique positions in the LDE domain at which the proof is queried, sorted in increasing
+    /// order.
+    pub query_positions: Vec<usize>,
+}
+
+impl<E: FieldElement> VerifierChallenges<E> {
+    /// Makes sure these challenges have the shape expected by the computation described by the
+    /// specified `air`.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The number of random elements for any of the auxiliary trace segments, or the number of
+    ///   constraint composition or DEEP composition coefficients does not match the `air`.
+    /// * Query positions are empty, are not unique and sorted, or are outside of the LDE domain.
+    pub fn validate<A: Air<BaseField = E::BaseField>>(&self, air: &A) -> Result<(), VerifierError> {
+        let layout = air.committed_trace_layout();
+        if self.aux_rand_elements.num_segments() != layout.num_aux_segments() {
+            return Err(VerifierError::InvalidChallenges(format!(
+                "expected random elements for {} auxiliary segments, but was {}",
+                layout.num_aux_segments(),
+                self.aux_rand_elements.num_segments()
+            )));
+        }
+        for i in 0..layout.num_aux_segments() {
+            let num_elements = self.aux_rand_elements.get_segment_elements(i).len();
+            if num_elements != layout.get_aux_segment_rand_elements(i) {
+                return Err(VerifierError::InvalidChallenges(format!(
+                    "expected {} random elements for auxiliary segment {i}, but was {num_elements}",
+                    layout.get_aux_segment_rand_elements(i)
+                )));
+            }
+        }
+
+        let context = air.context();
+        check_count(
+            "transition constraint coefficients",
+            context.num_transition_constraints(),
+            self.constraint_coefficients.transition.len(),
+        )?;
+        check_count(
+            "boundary constraint coefficients",
+            context.num_assertions(),
+            self.constraint_coefficients.boundary.len(),
+        )?;
+        check_count(
+            "wraparound constraint coefficients",
+            context.num_wraparound_constraints(),
+            self.constraint_coefficients.wraparound.len(),
+        )?;
+        check_count(
+            "range check constraint coefficients",
+            2 * air.get_range_checks().len(),
+            self.constraint_coefficients.range_check.len(),
+        )?;
+        check_count(
+            "DEEP trace coefficients",
+            air.num_committed_ood_trace_polys(),
+            self.deep_coefficients.trace.len(),
+        )?;
+        check_count(
+            "DEEP constraint coefficients",
+            context.num_constraint_composition_columns(),
+            self.deep_coefficients.constraints.len(),
+        )?;
+
+        if self.query_positions.is_empty() {
+            return Err(VerifierError::InvalidChallenges(
+                "query positions must not be empty".to_string(),
+            ));
+        }
+        if self.query_positions.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(VerifierError::InvalidChallenges(
+                "query positions must be unique and sorted in increasing order".to_string(),
+            ));
+        }
+        let lde_domain_size = air.lde_domain_size();
+        let last_position = self.query_positions[self.query_positions.len() - 1];
+        if last_position >= lde_domain_size {
+            return Err(VerifierError::InvalidChallenges(format!(
+                "query position {last_position} is outside of the LDE domain of size {lde_domain_size}"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns an error if the `actual` number of the specified challenges is not equal to the
+/// `expected` number.
+fn check_count(name: &str, expected: usize, actual: usize) -> Result<(), VerifierError> {
+    if expected != actual {
+        return Err(VerifierError::InvalidChallenges(format!(
+            "expected {expected} {name}, but was {actual}"
+        )));
+    }
+    Ok(())
+}
@@ -5,13 +5,13 @@
 
 use crate::VerifierError;
 use air::{
-    proof::{Queries, StarkProof, Table},
-    Air, EvaluationFrame,
+    proof::{get_fri_layer_seed, hash_column_roots, Queries, QueryResponse, StarkProof, Table},
+    Air, CommitmentLayout, EvaluationFrame,
 };
 use crypto::{BatchMerkleProof, ElementHasher, MerkleTree};
 use fri::VerifierChannel as FriVerifierChannel;
 use math::{FieldElement, StarkField};
-use utils::{collections::Vec, string::ToString};
+use utils::{collections::Vec, parallel::Executor, string::ToString, DeserializationError};
 
 // VERIFIER CHANNEL
 // ================================================================================================
@@ -21,7 +21,15 @@ use utils::{collections::Vec, string::ToString};
 /// A channel is instantiated for a specific proof, which is parsed into structs over the
 /// appropriate field (specified by type parameter `E`). This also validates that the proof is
 /// well-formed in the context of the computation for the specified [Air].
-pub struct VerifierChannel<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
+///
+/// Trace and constraint commitments are read as digests of hash function `H`, while FRI layer
+/// commitments are read as digests of hash function `HF`, which defaults to `H`.
+pub struct VerifierChannel<E, H, HF = H>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+    HF: ElementHasher<BaseField = E::BaseField>,
+{
     // trace queries
     trace_roots: Vec<H::Digest>,
     trace_queries: Option<TraceQueries<E, H>>,
@@ -29,8 +37,9 @@ pub struct VerifierChannel<E: FieldElement, H: ElementHasher<BaseField = E::Base
     constraint_root: H::Digest,
     constraint_queries: Option<ConstraintQueries<E, H>>,
     // FRI proof
-    fri_roots: Option<Vec<H::Digest>>,
-    fri_layer_proofs: Vec<BatchMerkleProof<H>>,
+    fri_roots: Option<Vec<HF::Digest>>,
+    fri_seeds: Vec<H::Digest>,
+    fri_layer_proofs: Vec<BatchMerkleProof<HF>>,
     fri_layer_queries: Vec<Vec<E>>,
     fri_remainder: Option<Vec<E>>,
     fri_num_partitions: usize,
@@ -41,13 +50,69 @@ pub struct VerifierChannel<E: FieldElement, H: ElementHasher<BaseField = E::Base
     pow_nonce: u64,
 }
 
-impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChannel<E, H> {
+/// Authenticated trace states and constraint evaluations at the queried positions.
+///
+/// The tuple contains trace states for the main trace segment, trace states for all auxiliary
+/// trace segments merged into a single table (or None if the computation has no auxiliary
+/// segments), and constraint evaluations.
+pub type QueriedStates<E> = (Table<<E as FieldElement>::BaseField>, Option<Table<E>>, Table<E>);
+
+/// Source from which a [VerifierChannel] reads trace and constraint queries.
+enum QuerySource {
+    /// Queries are parsed from the proof.
+    Proof,
+    /// Queries are read from query responses sent separately from the proof.
+    Streamed,
+    /// Queries are not read.
+    None,
+}
+
+impl<E, H, HF> VerifierChannel<E, H, HF>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+    HF: ElementHasher<BaseField = E::BaseField>,
+{
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
     /// Creates and returns a new [VerifierChannel] initialized from the specified `proof`.
     pub fn new<A: Air<BaseField = E::BaseField>>(
         air: &A,
         proof: StarkProof,
+    ) -> Result<Self, VerifierError> {
+        Self::build(air, proof, QuerySource::Proof)
+    }
+
+    /// Creates and returns a new [VerifierChannel] initialized from the specified `proof` whose
+    /// queries are sent separately as a [QueryResponse] for each query position.
+    ///
+    /// Trace and constraint queries contained in the proof must be empty; query responses are
+    /// read via [read_query_response()](VerifierChannel::read_query_response).
+    pub fn new_streamed<A: Air<BaseField = E::BaseField>>(
+        air: &A,
+        proof: StarkProof,
+    ) -> Result<Self, VerifierError> {
+        Self::build(air, proof, QuerySource::Streamed)
+    }
+
+    /// Creates and returns a new [VerifierChannel] initialized from the specified `proof` for
+    /// replaying the proof transcript only.
+    ///
+    /// Trace and constraint queries contained in the proof are not parsed, and thus, they cannot
+    /// be read from the returned channel.
+    pub fn new_transcript_only<A: Air<BaseField = E::BaseField>>(
+        air: &A,
+        proof: StarkProof,
+    ) -> Result<Self, VerifierError> {
+        Self::build(air, proof, QuerySource::None)
+    }
+
+    /// Creates a new [VerifierChannel] from the specified `proof`, reading trace and constraint
+    /// queries from the specified source.
+    fn build<A: Air<BaseField = E::BaseField>>(
+        air: &A,
+        proof: StarkProof,
+        query_source: QuerySource,
     ) -> Result<Self, VerifierError> {
         let StarkProof {
             context,
@@ -58,29 +123,66 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
             ood_frame,
             fri_proof,
             pow_nonce,
+            metadata: _,
+            pub_inputs: _,
         } = proof;
 
         // make sure AIR and proof base fields are the same
         if E::BaseField::get_modulus_le_bytes() != context.field_modulus_bytes() {
             return Err(VerifierError::InconsistentBaseField);
         }
+
+        // make sure the frame width used to estimate security of the proof is the frame width
+        // at which the AIR opens trace polynomials
+        if context.frame_width() != air.context().frame_width() {
+            return Err(VerifierError::InconsistentFrameWidth(
+                air.context().frame_width(),
+                context.frame_width(),
+            ));
+        }
         let constraint_frame_width = air.context().num_constraint_composition_columns();
 
-        let num_trace_segments = air.trace_layout().num_segments();
-        let main_trace_width = air.trace_layout().main_trace_width();
-        let aux_trace_width = air.trace_layout().aux_trace_width();
+        // columns added for range checks (if any) are included in the committed trace layout
+        let trace_layout = air.committed_trace_layout();
+        let num_trace_segments = trace_layout.num_segments();
+        let main_trace_width = trace_layout.main_trace_width();
+        let aux_trace_width = trace_layout.aux_trace_width();
         let lde_domain_size = air.lde_domain_size();
         let fri_options = air.options().to_fri_options();
 
         // --- parse commitments ------------------------------------------------------------------
         let (trace_roots, constraint_root, fri_roots) = commitments
-            .parse::<H>(num_trace_segments, fri_options.num_fri_layers(lde_domain_size))
+            .parse_with_fri_hash_fn::<H, HF>(
+                num_trace_segments,
+                fri_options.num_fri_layers(lde_domain_size),
+            )
+            .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+        let fri_seeds = fri_roots
+            .iter()
+            .map(|root| get_fri_layer_seed::<H, HF>(root, air.options()))
+            .collect::<Result<Vec<_>, _>>()
             .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
 
         // --- parse trace and constraint queries -------------------------------------------------
-        let trace_queries = TraceQueries::new(trace_queries, air, num_unique_queries as usize)?;
-        let constraint_queries =
-            ConstraintQueries::new(constraint_queries, air, num_unique_queries as usize)?;
+        // for streamed proofs, queries are read from query responses one at a time
+        let (trace_queries, constraint_queries) = match query_source {
+            QuerySource::Proof => {
+                let num_queries = num_unique_queries as usize;
+                (
+                    Some(TraceQueries::new(trace_queries, air, num_queries)?),
+                    Some(ConstraintQueries::new(constraint_queries, air, num_queries)?),
+                )
+            }
+            QuerySource::Streamed => {
+                if !trace_queries.iter().all(Queries::is_empty) || !constraint_queries.is_empty() {
+                    return Err(VerifierError::ProofDeserializationError(
+                        "streamed proof must not contain trace or constraint queries".to_string(),
+                    ));
+                }
+                (None, None)
+            }
+            QuerySource::None => (None, None),
+        };
 
         // --- parse FRI proofs -------------------------------------------------------------------
         let fri_num_partitions = fri_proof.num_partitions();
@@ -88,25 +190,40 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
             .parse_remainder()
             .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
         let (fri_layer_queries, fri_layer_proofs) = fri_proof
-            .parse_layers::<H, E>(lde_domain_size, fri_options.folding_factor())
+            .parse_layers::<HF, E>(lde_domain_size, fri_options.folding_factor())
             .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
 
         // --- parse out-of-domain evaluation frame -----------------------------------------------
+        // when auxiliary conjugates are enabled, the frame also contains evaluations of the
+        // conjugates of auxiliary trace polynomials
+        let has_aux_conjugates = air.context().has_aux_conjugates();
+        let frame_width = air.context().frame_width();
+        let ood_aux_width = air.num_committed_ood_trace_polys() - main_trace_width;
+        // the evaluation of the last composition column is omitted from thin frames, as it is
+        // recovered by the verifier from the trace frame
+        let num_ood_constraint_evaluations =
+            constraint_frame_width - air.options().has_thin_ood_frame() as usize;
         let (ood_trace_evaluations, ood_constraint_evaluations) = ood_frame
-            .parse(main_trace_width, aux_trace_width, constraint_frame_width)
+            .parse(main_trace_width, ood_aux_width, frame_width, num_ood_constraint_evaluations)
             .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
-        let ood_trace_frame =
-            TraceOodFrame::new(ood_trace_evaluations, main_trace_width, aux_trace_width);
+        let ood_trace_frame = TraceOodFrame::new(
+            ood_trace_evaluations,
+            main_trace_width,
+            aux_trace_width,
+            has_aux_conjugates,
+            frame_width,
+        );
 
         Ok(VerifierChannel {
             // trace queries
             trace_roots,
-            trace_queries: Some(trace_queries),
+            trace_queries,
             // constraint queries
             constraint_root,
-            constraint_queries: Some(constraint_queries),
+            constraint_queries,
             // FRI proof
             fri_roots: Some(fri_roots),
+            fri_seeds,
             fri_layer_proofs,
             fri_layer_queries,
             fri_remainder: Some(fri_remainder),
@@ -119,6 +236,18 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
         })
     }
 
+    // COMMITMENT OVERRIDES
+    // --------------------------------------------------------------------------------------------
+
+    /// Replaces the commitment to the main trace segment sent by the prover with the specified
+    /// commitment.
+    ///
+    /// The replaced commitment is used to reseed the public coin and to authenticate main trace
+    /// segment queries.
+    pub fn set_main_trace_commitment(&mut self, commitment: H::Digest) {
+        self.trace_roots[0] = commitment;
+    }
+
     // DATA READERS
     // --------------------------------------------------------------------------------------------
 
@@ -135,8 +264,9 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
         self.constraint_root
     }
 
-    /// Returns trace polynomial evaluations at out-of-domain points z and z * g, where g is the
-    /// generator of the LDE domain.
+    /// Returns trace polynomial evaluations at out-of-domain points z * g^k for k in
+    /// 0..frame_width (i.e., z and z * g by default), where g is the generator of the trace
+    /// domain.
     ///
     /// For computations requiring multiple trace segments, evaluations of auxiliary trace
     /// polynomials are also included.
@@ -146,72 +276,107 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
 
     /// Returns evaluations of composition polynomial columns at z^m, where z is the out-of-domain
     /// point, and m is the number of composition polynomial columns.
+    ///
+    /// If the out-of-domain frame is thin, the evaluation of the last column is not included (see
+    /// [ProofOptions::with_thin_ood_frame()](air::ProofOptions::with_thin_ood_frame)).
     pub fn read_ood_constraint_evaluations(&mut self) -> Vec<E> {
         self.ood_constraint_evaluations.take().expect("already read")
     }
 
+    /// Returns trace polynomial evaluations at out-of-domain points sent by the prover without
+    /// consuming them.
+    pub fn ood_trace_frame(&self) -> &TraceOodFrame<E> {
+        self.ood_trace_frame.as_ref().expect("already read")
+    }
+
+    /// Returns evaluations of composition polynomial columns at the out-of-domain point sent by
+    /// the prover without consuming them.
+    pub fn ood_constraint_evaluations(&self) -> &[E] {
+        self.ood_constraint_evaluations.as_ref().expect("already read")
+    }
+
+    /// Returns digests with which the public coin is reseeded for FRI layer commitments sent by
+    /// the prover.
+    ///
+    /// If FRI layers are committed to with the same hash function as the rest of the protocol,
+    /// these are the FRI layer commitments themselves; otherwise, these are hashes of the
+    /// commitments (see [get_fri_layer_seed()]).
+    pub fn fri_layer_seeds(&self) -> &[H::Digest] {
+        &self.fri_seeds
+    }
+
     /// Returns query proof-of-work nonce sent by the prover.
     pub fn read_pow_nonce(&self) -> u64 {
         self.pow_nonce
     }
 
-    /// Returns trace states at the specified positions of the LDE domain. This also checks if
-    /// the trace states are valid against the trace commitment sent by the prover.
+    /// Returns trace states and constraint evaluations at the specified positions of the LDE
+    /// domain. This also checks if the trace states and constraint evaluations are valid against
+    /// the trace and constraint commitments sent by the prover.
     ///
-    /// For computations requiring multiple trace segments, trace states for auxiliary segments
-    /// are also included as the second value of the returned tuple (trace states for all auxiliary
-    /// segments are merged into a single table). Otherwise, the second value is None.
-    #[allow(clippy::type_complexity)]
-    pub fn read_queried_trace_states(
+    /// Trace and constraint queries are authenticated independently of each other, and thus, may
+    /// be authenticated in parallel by the specified `executor`.
+    pub fn read_queried_states<X: Executor>(
         &mut self,
         positions: &[usize],
-    ) -> Result<(Table<E::BaseField>, Option<Table<E>>), VerifierError> {
-        let queries = self.trace_queries.take().expect("already read");
-
-        // make sure the states included in the proof correspond to the trace commitment
-        for (root, proof) in self.trace_roots.iter().zip(queries.query_proofs.iter()) {
-            MerkleTree::verify_batch(root, positions, proof)
-                .map_err(|_| VerifierError::TraceQueryDoesNotMatchCommitment)?;
-        }
-
-        Ok((queries.main_states, queries.aux_states))
+        executor: &X,
+    ) -> Result<QueriedStates<E>, VerifierError> {
+        let trace_queries = self.trace_queries.take().expect("already read");
+        let constraint_queries = self.constraint_queries.take().expect("already read");
+        let trace_roots = &self.trace_roots;
+        let constraint_root = &self.constraint_root;
+
+        let (trace_states, constraint_evaluations) = executor.join(
+            || trace_queries.authenticate(trace_roots, positions, executor),
+            || constraint_queries.authenticate(constraint_root, positions),
+        );
+        let (main_states, aux_states) = trace_states?;
+        Ok((main_states, aux_states, constraint_evaluations?))
     }
 
-    /// Returns constraint evaluations at the specified positions of the LDE domain. This also
-    /// checks if the constraint evaluations are valid against the constraint commitment sent by
-    /// the prover.
-    pub fn read_constraint_evaluations(
-        &mut self,
-        positions: &[usize],
-    ) -> Result<Table<E>, VerifierError> {
-        let queries = self.constraint_queries.take().expect("already read");
-
-        MerkleTree::verify_batch(&self.constraint_root, positions, &queries.query_proofs)
-            .map_err(|_| VerifierError::ConstraintQueryDoesNotMatchCommitment)?;
-
-        Ok(queries.evaluations)
+    /// Returns trace states and constraint evaluations at the specified position of the LDE
+    /// domain read from the specified query `response`. This also checks if the trace states and
+    /// constraint evaluations are valid against the trace and constraint commitments sent by the
+    /// prover.
+    pub fn read_query_response<A: Air<BaseField = E::BaseField>, X: Executor>(
+        &self,
+        air: &A,
+        response: QueryResponse,
+        position: usize,
+        executor: &X,
+    ) -> Result<QueriedStates<E>, VerifierError> {
+        let QueryResponse {
+            trace_queries,
+            constraint_queries,
+        } = response;
+        let (main_states, aux_states) = TraceQueries::<E, H>::new(trace_queries, air, 1)?
+            .authenticate(&self.trace_roots, &[position], executor)?;
+        let constraint_evaluations = ConstraintQueries::<E, H>::new(constraint_queries, air, 1)?
+            .authenticate(&self.constraint_root, &[position])?;
+        Ok((main_states, aux_states, constraint_evaluations))
     }
 }
 
 // FRI VERIFIER CHANNEL IMPLEMENTATION
 // ================================================================================================
 
-impl<E, H> FriVerifierChannel<E> for VerifierChannel<E, H>
+impl<E, H, HF> FriVerifierChannel<E> for VerifierChannel<E, H, HF>
 where
     E: FieldElement,
     H: ElementHasher<BaseField = E::BaseField>,
+    HF: ElementHasher<BaseField = E::BaseField>,
 {
-    type Hasher = H;
+    type Hasher = HF;
 
     fn read_fri_num_partitions(&self) -> usize {
         self.fri_num_partitions
     }
 
-    fn read_fri_layer_commitments(&mut self) -> Vec<H::Digest> {
+    fn read_fri_layer_commitments(&mut self) -> Vec<HF::Digest> {
         self.fri_roots.take().expect("already read")
     }
 
-    fn take_next_fri_layer_proof(&mut self) -> BatchMerkleProof<H> {
+    fn take_next_fri_layer_proof(&mut self) -> BatchMerkleProof<HF> {
         self.fri_layer_proofs.remove(0)
     }
 
@@ -229,11 +394,15 @@ where
 
 /// Container of trace query data, including:
 /// * Queried states for all trace segments.
-/// * Merkle authentication paths for all queries.
+/// * Merkle authentication paths for all queries; for the per-column commitment layout, there
+///   is a separate batch proof for each column of a trace segment.
 ///
-/// Trace states for all auxiliary segments are stored in a single table.
+/// Trace states for all auxiliary segments are stored in a single table. When several trace rows
+/// are packed into a single leaf, each row of these tables contains all rows of an opened leaf.
 struct TraceQueries<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
-    query_proofs: Vec<BatchMerkleProof<H>>,
+    commitment_layout: CommitmentLayout,
+    rows_per_leaf: usize,
+    query_proofs: Vec<Vec<BatchMerkleProof<H>>>,
     main_states: Table<E::BaseField>,
     aux_states: Option<Table<E>>,
 }
@@ -246,20 +415,30 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> TraceQueries<E
         air: &A,
         num_queries: usize,
     ) -> Result<Self, VerifierError> {
-        assert_eq!(
-            queries.len(),
-            air.trace_layout().num_segments(),
-            "expected {} trace segment queries, but received {}",
-            air.trace_layout().num_segments(),
-            queries.len()
-        );
+        let trace_layout = air.committed_trace_layout();
+        if queries.len() != trace_layout.num_segments() {
+            return Err(VerifierError::ProofDeserializationError(format!(
+                "expected {} trace segment queries, but received {}",
+                trace_layout.num_segments(),
+                queries.len()
+            )));
+        }
 
         // parse main trace segment queries; parsing also validates that hashes of each table row
         // form the leaves of Merkle authentication paths in the proofs
-        let main_segment_width = air.trace_layout().main_trace_width();
+        let commitment_layout = air.options().commitment_layout();
+        let rows_per_leaf = air.options().rows_per_leaf();
+        let main_segment_width = trace_layout.main_trace_width();
         let main_segment_queries = queries.remove(0);
-        let (main_segment_query_proofs, main_segment_states) = main_segment_queries
-            .parse::<H, E::BaseField>(air.lde_domain_size(), num_queries, main_segment_width)
+        let (main_segment_query_proofs, main_segment_states) =
+            parse_segment_queries::<H, E::BaseField>(
+                main_segment_queries,
+                commitment_layout,
+                rows_per_leaf,
+                air.lde_domain_size(),
+                num_queries,
+                main_segment_width,
+            )
             .map_err(|err| {
                 VerifierError::ProofDeserializationError(format!(
                     "main trace segment query deserialization failed: {err}"
@@ -272,17 +451,23 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> TraceQueries<E
         // parse auxiliary trace segment queries (if any), and merge resulting tables into a
         // single table; parsing also validates that hashes of each table row form the leaves
         // of Merkle authentication paths in the proofs
-        let aux_trace_states = if air.trace_info().is_multi_segment() {
+        let aux_trace_states = if trace_layout.num_aux_segments() > 0 {
             let mut aux_trace_states = Vec::new();
             for (i, segment_queries) in queries.into_iter().enumerate() {
-                let segment_width = air.trace_layout().get_aux_segment_width(i);
-                let (segment_query_proof, segment_trace_states) = segment_queries
-                    .parse::<H, E>(air.lde_domain_size(), num_queries, segment_width)
-                    .map_err(|err| {
-                        VerifierError::ProofDeserializationError(format!(
-                            "auxiliary trace segment query deserialization failed: {err}"
-                        ))
-                    })?;
+                let segment_width = trace_layout.get_aux_segment_width(i);
+                let (segment_query_proof, segment_trace_states) = parse_segment_queries::<H, E>(
+                    segment_queries,
+                    commitment_layout,
+                    rows_per_leaf,
+                    air.lde_domain_size(),
+                    num_queries,
+                    segment_width,
+                )
+                .map_err(|err| {
+                    VerifierError::ProofDeserializationError(format!(
+                        "auxiliary trace segment query deserialization failed: {err}"
+                    ))
+                })?;
 
                 query_proofs.push(segment_query_proof);
                 aux_trace_states.push(segment_trace_states);
@@ -295,11 +480,127 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> TraceQueries<E
         };
 
         Ok(Self {
+            commitment_layout,
+            rows_per_leaf,
             query_proofs,
             main_states: main_segment_states,
             aux_states: aux_trace_states,
         })
     }
+
+    /// Checks that these queries are valid against the specified trace segment commitments, and
+    /// returns trace states at the specified positions. Queries against different trace segments
+    /// are authenticated via the specified `executor`.
+    ///
+    /// Trace states for all auxiliary segments are returned merged into a single table as the
+    /// second value of the returned tuple; for computations without auxiliary segments, the
+    /// second value is None.
+    #[allow(clippy::type_complexity)]
+    pub fn authenticate<X: Executor>(
+        self,
+        roots: &[H::Digest],
+        positions: &[usize],
+        executor: &X,
+    ) -> Result<(Table<E::BaseField>, Option<Table<E>>), VerifierError> {
+        // when several rows are packed into a single leaf, the prover opens each leaf containing
+        // the queried positions only once
+        let rows_per_leaf = self.rows_per_leaf;
+        let mut leaf_positions = positions.iter().map(|&p| p / rows_per_leaf).collect::<Vec<_>>();
+        leaf_positions.dedup();
+
+        // make sure the states included in the proof correspond to the trace commitment; for the
+        // per-column layout, the commitment is a hash of the roots of all column Merkle trees
+        let commitment_layout = self.commitment_layout;
+        let segments = roots.iter().zip(self.query_proofs.iter()).collect::<Vec<_>>();
+        executor
+            .map(&segments, |(root, proofs)| match commitment_layout {
+                CommitmentLayout::RowMajor => {
+                    MerkleTree::verify_batch(*root, &leaf_positions, &proofs[0])
+                        .map_err(|_| VerifierError::TraceQueryDoesNotMatchCommitment)
+                }
+                CommitmentLayout::PerColumn => {
+                    let column_roots = proofs
+                        .iter()
+                        .map(|proof| proof.get_root(positions))
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| VerifierError::TraceQueryDoesNotMatchCommitment)?;
+                    if **root != hash_column_roots::<H>(&column_roots) {
+                        return Err(VerifierError::TraceQueryDoesNotMatchCommitment);
+                    }
+                    Ok(())
+                }
+            })
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if rows_per_leaf == 1 {
+            return Ok((self.main_states, self.aux_states));
+        }
+
+        // extract the queried rows from the opened leaves
+        let main_states =
+            unpack_leaves(&self.main_states, &leaf_positions, positions, rows_per_leaf);
+        let aux_states = self
+            .aux_states
+            .map(|states| unpack_leaves(&states, &leaf_positions, positions, rows_per_leaf));
+        Ok((main_states, aux_states))
+    }
+}
+
+/// Parses queries for a single trace segment committed to using the specified `layout`.
+///
+/// For the row-major layout, the returned vector contains a single batch Merkle proof; for the
+/// per-column layout, it contains a batch Merkle proof for each column of the segment. When
+/// several rows are packed into a single leaf, each row of the returned table contains all rows
+/// of a leaf.
+#[allow(clippy::type_complexity)]
+fn parse_segment_queries<H, E>(
+    queries: Queries,
+    layout: CommitmentLayout,
+    rows_per_leaf: usize,
+    domain_size: usize,
+    num_queries: usize,
+    segment_width: usize,
+) -> Result<(Vec<BatchMerkleProof<H>>, Table<E>), DeserializationError>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    match layout {
+        CommitmentLayout::RowMajor if rows_per_leaf > 1 => {
+            let (proof, leaves) =
+                queries.parse_packed::<H, E>(domain_size, rows_per_leaf, segment_width)?;
+            Ok((vec![proof], leaves))
+        }
+        CommitmentLayout::RowMajor => {
+            let (proof, states) = queries.parse::<H, E>(domain_size, num_queries, segment_width)?;
+            Ok((vec![proof], states))
+        }
+        CommitmentLayout::PerColumn => {
+            queries.parse_per_column::<H, E>(domain_size, num_queries, segment_width)
+        }
+    }
+}
+
+/// Extracts rows at the specified positions from a table of opened leaves, each containing
+/// `rows_per_leaf` adjacent rows; `leaf_positions` must contain positions of the opened leaves.
+fn unpack_leaves<E: FieldElement>(
+    leaves: &Table<E>,
+    leaf_positions: &[usize],
+    positions: &[usize],
+    rows_per_leaf: usize,
+) -> Table<E> {
+    let row_width = leaves.num_columns() / rows_per_leaf;
+    let mut rows = Vec::with_capacity(positions.len() * row_width);
+    for &position in positions {
+        let leaf_idx = leaf_positions
+            .iter()
+            .position(|&p| p == position / rows_per_leaf)
+            .expect("no leaf for query position");
+        let offset = (position % rows_per_leaf) * row_width;
+        rows.extend_from_slice(&leaves.get_row(leaf_idx)[offset..offset + row_width]);
+    }
+    Table::from_elements(rows, row_width)
 }
 
 // CONSTRAINT QUERIES
@@ -336,6 +637,18 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> ConstraintQuer
             evaluations,
         })
     }
+
+    /// Checks that these queries are valid against the specified constraint commitment, and
+    /// returns constraint evaluations at the specified positions.
+    pub fn authenticate(
+        self,
+        root: &H::Digest,
+        positions: &[usize],
+    ) -> Result<Table<E>, VerifierError> {
+        MerkleTree::verify_batch(root, positions, &self.query_proofs)
+            .map_err(|_| VerifierError::ConstraintQueryDoesNotMatchCommitment)?;
+        Ok(self.evaluations)
+    }
 }
 
 // TRACE OUT-OF-DOMAIN FRAME
@@ -345,14 +658,24 @@ pub struct TraceOodFrame<E: FieldElement> {
     values: Vec<E>,
     main_trace_width: usize,
     aux_trace_width: usize,
+    has_aux_conjugates: bool,
+    frame_width: usize,
 }
 
 impl<E: FieldElement> TraceOodFrame<E> {
-    pub fn new(values: Vec<E>, main_trace_width: usize, aux_trace_width: usize) -> Self {
+    pub fn new(
+        values: Vec<E>,
+        main_trace_width: usize,
+        aux_trace_width: usize,
+        has_aux_conjugates: bool,
+        frame_width: usize,
+    ) -> Self {
         Self {
             values,
             main_trace_width,
             aux_trace_width,
+            has_aux_conjugates,
+            frame_width,
         }
     }
 
@@ -360,11 +683,26 @@ impl<E: FieldElement> TraceOodFrame<E> {
         &self.values
     }
 
-    // The out-of-domain frame is stored as one vector of interleaved values, one from the
-    // current row and the other from the next row. See `OodFrame::set_trace_states`.
+    // Returns evaluations of all opened trace polynomials (main, auxiliary, and conjugates of
+    // auxiliary polynomials, in that order) with one row per shift; that is, the k-th row contains
+    // evaluations of the polynomials at z * g^k.
+    pub fn rows(&self) -> Vec<Vec<E>> {
+        let mut rows =
+            vec![Vec::with_capacity(self.values.len() / self.frame_width); self.frame_width];
+        for poly_values in self.values.chunks(self.frame_width) {
+            for (row, &value) in rows.iter_mut().zip(poly_values) {
+                row.push(value);
+            }
+        }
+        rows
+    }
+
+    // The out-of-domain frame is stored as one vector of interleaved values, one from each row
+    // of the frame, with the first two rows being the current row and the next row. See
+    // `OodFrame::set_trace_states`.
     // Thus we need to untangle the current and next rows stored in `Self::values` and we
     // do that for the main and auxiliary traces separately.
-    // Pictorially, for the main trace portion:
+    // Pictorially, for the main trace portion and the default frame width of 2:
     //
     // Input vector: [a1, b1, a2, b2, ..., an, bn, c1, d1, c2, d2, ..., cm, dm]
     // with n being the main trace width and m the auxiliary trace width.
@@ -379,7 +717,7 @@ impl<E: FieldElement> TraceOodFrame<E> {
         let mut current = vec![E::ZERO; self.main_trace_width];
         let mut next = vec![E::ZERO; self.main_trace_width];
 
-        for (i, a) in self.values.chunks(2).take(self.main_trace_width).enumerate() {
+        for (i, a) in self.values.chunks(self.frame_width).take(self.main_trace_width).enumerate() {
             current[i] = a[0];
             next[i] = a[1];
         }
@@ -407,11 +745,30 @@ impl<E: FieldElement> TraceOodFrame<E> {
             let mut current_aux = vec![E::ZERO; self.aux_trace_width];
             let mut next_aux = vec![E::ZERO; self.aux_trace_width];
 
-            for (i, a) in self.values.chunks(2).skip(self.main_trace_width).enumerate() {
+            let mut aux_values = self.values.chunks(self.frame_width).skip(self.main_trace_width);
+            for (i, a) in aux_values.by_ref().take(self.aux_trace_width).enumerate() {
                 current_aux[i] = a[0];
                 next_aux[i] = a[1];
             }
-            Some(EvaluationFrame::from_rows(current_aux, next_aux))
+
+            if !self.has_aux_conjugates {
+                return Some(EvaluationFrame::from_rows(current_aux, next_aux));
+            }
+
+            // when auxiliary conjugates are enabled, evaluations of the conjugates of auxiliary
+            // trace polynomials follow the auxiliary trace portion and are laid out the same way
+            let mut current_conjugate = vec![E::ZERO; self.aux_trace_width];
+            let mut next_conjugate = vec![E::ZERO; self.aux_trace_width];
+            for (i, a) in aux_values.enumerate() {
+                current_conjugate[i] = a[0];
+                next_conjugate[i] = a[1];
+            }
+            Some(EvaluationFrame::from_rows_with_conjugates(
+                current_aux,
+                next_aux,
+                current_conjugate,
+                next_conjugate,
+            ))
         }
     }
 }
@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use air::{proof::Table, Air, DeepCompositionCoefficients, EvaluationFrame};
+use air::{proof::Table, Air, DeepCompositionCoefficients};
 use math::{batch_inversion, FieldElement};
 use utils::collections::Vec;
 
@@ -13,7 +13,8 @@ use utils::collections::Vec;
 pub struct DeepComposer<E: FieldElement> {
     cc: DeepCompositionCoefficients<E>,
     x_coordinates: Vec<E>,
-    z: [E; 2],
+    z: Vec<E>,
+    has_aux_conjugates: bool,
 }
 
 impl<E: FieldElement> DeepComposer<E> {
@@ -32,10 +33,20 @@ impl<E: FieldElement> DeepComposer<E> {
             .map(|&p| E::from(g_lde.exp_vartime((p as u64).into()) * domain_offset))
             .collect();
 
+        // compute out-of-domain points z * g^k for all shifts k of the frame
+        let g = E::from(air.trace_domain_generator());
+        let mut shifted_z = Vec::with_capacity(air.context().frame_width());
+        let mut x = z;
+        for _ in 0..air.context().frame_width() {
+            shifted_z.push(x);
+            x *= g;
+        }
+
         DeepComposer {
             cc,
             x_coordinates,
-            z: [z, z * E::from(air.trace_domain_generator())],
+            z: shifted_z,
+            has_aux_conjugates: air.context().has_aux_conjugates(),
         }
     }
 
@@ -43,87 +54,84 @@ impl<E: FieldElement> DeepComposer<E> {
     /// their random linear combinations as follows:
     ///
     /// - Assume each column value is an evaluation of a trace polynomial T_i(x).
-    /// - For each T_i(x) compute T'_i(x) = (T_i(x) - T_i(z)) / (x - z) and
-    ///   T''_i = (T_i(x) - T_i(z * g)) / (x - z * g), where z is the out-of-domain point and
-    ///   g is the the LDE domain generator.
-    /// - Then, combine all T'_i(x) and T''_i(x) values together by computing
-    ///   T(x) = sum((T'_i(x) + T''_i(x)) * cc_i) for all i, where cc_i is the coefficient for
+    /// - For each T_i(x) and each shift k of the out-of-domain frame, compute
+    ///   T^(k)_i(x) = (T_i(x) - T_i(z * g^k)) / (x - z * g^k), where z is the out-of-domain
+    ///   point and g is the trace domain generator. By default, the frame has two rows, and
+    ///   thus, T_i(x) is opened at z and z * g.
+    /// - Then, combine all T^(k)_i(x) values together by computing
+    ///   T(x) = sum((sum_k T^(k)_i(x)) * cc_i) for all i, where cc_i is the coefficient for
     ///   for the random linear combination drawn from the public coin.
     ///
-    /// Note that values of T_i(z) and T_i(z * g) are received from the prover and passed into
-    /// this function via the `ood_main_frame` and `ood_aux_frame` parameters.
+    /// Note that values of T_i(z * g^k) are received from the prover and passed into this
+    /// function via the `ood_trace_states` parameter, one row per shift k. Each row contains
+    /// evaluations of the main trace polynomials, followed by evaluations of auxiliary trace
+    /// polynomials and their conjugates (if any).
     pub fn compose_trace_columns(
         &self,
         queried_main_trace_states: Table<E::BaseField>,
         queried_aux_trace_states: Option<Table<E>>,
-        ood_main_frame: EvaluationFrame<E>,
-        ood_aux_frame: Option<EvaluationFrame<E>>,
+        ood_trace_states: Vec<Vec<E>>,
     ) -> Vec<E> {
-        let ood_main_trace_states = [ood_main_frame.current(), ood_main_frame.next()];
+        assert_eq!(ood_trace_states.len(), self.z.len());
 
-        // compose columns of of the main trace segment; we do this separately for numerators of
-        // each query; we also track common denominator for each query separately; this way we can
-        // use a batch inversion in the end.
+        // compose columns of all trace segments; we do this separately for numerators of each
+        // query and each shift, and track common denominator for each query separately; this way
+        // we can use a batch inversion in the end.
         let n = queried_main_trace_states.num_rows();
         let mut result_num = Vec::<E>::with_capacity(n);
         let mut result_den = Vec::<E>::with_capacity(n);
 
-        for ((_, row), &x) in (0..n).zip(queried_main_trace_states.rows()).zip(&self.x_coordinates)
-        {
-            let mut t1_num = E::ZERO;
-            let mut t2_num = E::ZERO;
+        let mut aux_rows = queried_aux_trace_states.as_ref().map(|states| states.rows());
+        for (row, &x) in queried_main_trace_states.rows().zip(&self.x_coordinates) {
+            let mut t_nums = vec![E::ZERO; self.z.len()];
 
             for (i, &value) in row.iter().enumerate() {
                 let value = E::from(value);
-                // compute the numerator of T'_i(x) as (T_i(x) - T_i(z)), multiply it by a
-                // composition coefficient, and add the result to the numerator aggregator
-                t1_num += (value - ood_main_trace_states[0][i]) * self.cc.trace[i];
-
-                // compute the numerator of T''_i(x) as (T_i(x) - T_i(z * g)), multiply it by a
-                // composition coefficient, and add the result to the numerator aggregator
-                t2_num += (value - ood_main_trace_states[1][i]) * self.cc.trace[i];
+                // compute the numerator of T^(k)_i(x) as (T_i(x) - T_i(z * g^k)), multiply it by
+                // a composition coefficient, and add the result to the numerator aggregator
+                for (t_num, ood_state) in t_nums.iter_mut().zip(ood_trace_states.iter()) {
+                    *t_num += (value - ood_state[i]) * self.cc.trace[i];
+                }
             }
-            // compute the common denominator as (x - z) * (x - z * g)
-            let t1_den = x - self.z[0];
-            let t2_den = x - self.z[1];
-            result_den.push(t1_den * t2_den);
-
-            // add the numerators of T'_i(x) and T''_i(x) together; we can do this because later on
-            // we'll use the common denominator computed above.
-            result_num.push(t1_num * t2_den + t2_num * t1_den);
-        }
 
-        // if the trace has auxiliary segments, compose columns from these segments as well; we
-        // also do this separately for numerators and denominators.
-        if let Some(queried_aux_trace_states) = queried_aux_trace_states {
-            let ood_aux_frame = ood_aux_frame.expect("missing auxiliary OOD frame");
-            let ood_aux_trace_states = [ood_aux_frame.current(), ood_aux_frame.next()];
-
-            // we define this offset here because composition of the main trace columns has
-            // consumed some number of composition coefficients already.
-            let cc_offset = queried_main_trace_states.num_columns();
-
-            for ((j, row), &x) in
-                (0..n).zip(queried_aux_trace_states.rows()).zip(&self.x_coordinates)
-            {
-                let mut t1_num = E::ZERO;
-                let mut t2_num = E::ZERO;
-                for (i, &value) in row.iter().enumerate() {
-                    // compute the numerator of T'_i(x) as (T_i(x) - T_i(z)), multiply it by a
-                    // composition coefficient, and add the result to the numerator aggregator
-                    t1_num += (value - ood_aux_trace_states[0][i]) * self.cc.trace[cc_offset + i];
-
-                    // compute the numerator of T''_i(x) as (T_i(x) - T_i(z * g)), multiply it by a
-                    // composition coefficient, and add the result to the numerator aggregator
-                    t2_num += (value - ood_aux_trace_states[1][i]) * self.cc.trace[cc_offset + i];
+            // if the trace has auxiliary segments, compose columns from these segments as well;
+            // composition of the main trace columns has consumed some number of composition
+            // coefficients already, and so we offset coefficient indexes accordingly.
+            if let Some(aux_rows) = aux_rows.as_mut() {
+                let aux_row = aux_rows.next().expect("missing auxiliary trace state");
+                let cc_offset = row.len();
+                for (i, &value) in aux_row.iter().enumerate() {
+                    for (t_num, ood_state) in t_nums.iter_mut().zip(ood_trace_states.iter()) {
+                        *t_num += (value - ood_state[cc_offset + i]) * self.cc.trace[cc_offset + i];
+                    }
                 }
 
-                // compute the common denominators (x - z) and (x - z * g), and use the to aggregate
-                // numerators into the common numerator computed for the main trace of this query
-                let t1_den = x - self.z[0];
-                let t2_den = x - self.z[1];
-                result_num[j] += t1_num * t2_den + t2_num * t1_den;
+                // if the frame provides conjugates of auxiliary trace polynomials, compose them
+                // as well; since x is in the base field, the value of a conjugate polynomial at
+                // x is the frobenius of the queried value.
+                if self.has_aux_conjugates {
+                    let cc_offset = cc_offset + aux_row.len();
+                    for (i, &value) in aux_row.iter().enumerate() {
+                        let value = value.frobenius();
+                        for (t_num, ood_state) in t_nums.iter_mut().zip(ood_trace_states.iter()) {
+                            *t_num +=
+                                (value - ood_state[cc_offset + i]) * self.cc.trace[cc_offset + i];
+                        }
+                    }
+                }
             }
+
+            // add the numerators of T^(k)_i(x) together over the common denominator computed as
+            // the product of (x - z * g^k) for all k
+            let (num, den) = t_nums.iter().zip(self.z.iter()).fold(
+                (E::ZERO, E::ONE),
+                |(num, den), (&t_num, &z)| {
+                    let t_den = x - z;
+                    (num * t_den + t_num * den, den * t_den)
+                },
+            );
+            result_num.push(num);
+            result_den.push(den);
         }
 
         result_den = batch_inversion(&result_den);
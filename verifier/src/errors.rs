@@ -52,6 +52,55 @@ pub enum VerifierError {
     /// This error occurs when the parameters, that were used to generate the proof, do not match
     /// any of the set of parameters expected by the verifier.
     UnacceptableProofOptions,
+    /// This error occurs when the metadata attached to the proof does not match the metadata
+    /// expected by the verifier.
+    InconsistentMetadata,
+    /// This error occurs when the signature attached to a signed proof is not a valid signature
+    /// over the identifier of the proof.
+    InvalidProofSignature,
+    /// This error occurs when public inputs embedded into the proof do not match the commitment
+    /// to public inputs expected by the verifier.
+    InconsistentInputCommitment,
+    /// This error occurs when commitment roots contained in the proof do not match the expected
+    /// (e.g., externally anchored) commitment roots.
+    InconsistentCommitmentRoots,
+    /// This error occurs when a membership proof for the commitment to the main trace segment
+    /// does not resolve to the expected accumulator root.
+    InvalidAccumulatorMembership,
+    /// This error occurs when the number of unique query positions drawn from the public coin
+    /// does not match the number of unique queries contained in the proof.
+    InconsistentNumQueries(usize, usize),
+    /// This error occurs when the length of the execution trace claimed by the proof is greater
+    /// than the maximum trace length accepted by the verifier.
+    TraceLengthLimitExceeded(usize, usize),
+    /// This error occurs when the number of FRI layers in the proof is greater than the maximum
+    /// number of FRI layers accepted by the verifier.
+    FriLayerLimitExceeded(usize, usize),
+    /// This error occurs when the number of queries in the proof is greater than the maximum
+    /// number of queries accepted by the verifier.
+    QueryLimitExceeded(usize, usize),
+    /// This error occurs when the low-degree extension domain implied by the proof is larger than
+    /// the largest multiplicative subgroup of the base field with a power-of-two order.
+    DomainExceedsTwoAdicity(u32, u32),
+    /// This error occurs when externally supplied challenges do not have the shape expected for
+    /// the computation being verified.
+    InvalidChallenges(String),
+    /// This error occurs when the parameters of the proof or the AIR with which the verifier was
+    /// instantiated do not match the verification key.
+    InconsistentVerificationKey,
+    /// This error occurs when a proof is verified with a separate hash function for FRI, but the
+    /// proof options do not specify hash functions for commitments and FRI.
+    UnspecifiedHashFunctions,
+    /// This error occurs when hash functions recorded in the proof options differ from the hash
+    /// functions with which the proof is verified.
+    InconsistentHashFunctions,
+    /// This error occurs when a proof is verified with a separate hash function for FRI, but one
+    /// of the hash functions used for verification has an unknown identifier, and thus, cannot be
+    /// checked against the hash functions recorded in the proof options.
+    UnknownHashFunction,
+    /// This error occurs when the frame width recorded in the proof context does not match the
+    /// frame width of AIR with which the verifier was instantiated.
+    InconsistentFrameWidth(usize, usize),
 }
 
 impl fmt::Display for VerifierError {
@@ -92,6 +141,22 @@ impl fmt::Display for VerifierError {
                 write!(f, "insufficient proof security level: expected at least {minimal_security} bits of proven security, but was {proof_security} bits")
             }
             Self::UnacceptableProofOptions => {write!(f, "invalid proof options: security parameters do not match the acceptable parameter set")}
+            Self::InconsistentMetadata => {write!(f, "proof metadata does not match the expected metadata")}
+            Self::InvalidProofSignature => {write!(f, "proof signature is not valid for the attached public key")}
+            Self::InconsistentInputCommitment => {write!(f, "public inputs embedded into the proof do not match the expected commitment")}
+            Self::InconsistentCommitmentRoots => {write!(f, "commitment roots contained in the proof do not match the expected commitment roots")}
+            Self::InvalidAccumulatorMembership => {write!(f, "trace commitment membership proof does not resolve to the accumulator root")}
+            Self::InconsistentNumQueries(expected, actual) => {write!(f, "expected proof to contain {expected} unique queries, but was {actual}")}
+            Self::TraceLengthLimitExceeded(limit, actual) => {write!(f, "trace length of the proof exceeds the limit: expected at most {limit}, but was {actual}")}
+            Self::FriLayerLimitExceeded(limit, actual) => {write!(f, "number of FRI layers in the proof exceeds the limit: expected at most {limit}, but was {actual}")}
+            Self::QueryLimitExceeded(limit, actual) => {write!(f, "number of queries in the proof exceeds the limit: expected at most {limit}, but was {actual}")}
+            Self::DomainExceedsTwoAdicity(domain_log2, two_adicity) => {write!(f, "LDE domain of size 2^{domain_log2} cannot be formed in a base field with two-adicity {two_adicity}")}
+            Self::InvalidChallenges(msg) => {write!(f, "invalid verifier challenges: {msg}")}
+            Self::InconsistentVerificationKey => {write!(f, "proof parameters or AIR do not match the verification key")}
+            Self::UnspecifiedHashFunctions => {write!(f, "proof options do not specify hash functions for commitments and FRI")}
+            Self::InconsistentHashFunctions => {write!(f, "hash functions recorded in proof options differ from the hash functions used for verification")}
+            Self::UnknownHashFunction => {write!(f, "hash functions used for verification must have known identifiers")}
+            Self::InconsistentFrameWidth(expected, actual) => {write!(f, "frame width of the proof does not match the AIR: expected {expected}, but was {actual}")}
         }
     }
 }
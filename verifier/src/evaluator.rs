@@ -3,10 +3,16 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use air::{Air, AuxTraceRandElements, ConstraintCompositionCoefficients, EvaluationFrame};
-use math::{polynom, FieldElement};
+use air::{
+    Air, AuxTraceRandElements, BoundaryConstraints, ConstraintCompositionCoefficients,
+    EvaluationFrame, RangeCheckConstraints,
+};
+use math::FieldElement;
 use utils::collections::Vec;
 
+#[cfg(feature = "debug")]
+use air::Assertion;
+
 // CONSTRAINT EVALUATION
 // ================================================================================================
 
@@ -17,66 +23,283 @@ pub fn evaluate_constraints<A: Air, E: FieldElement<BaseField = A::BaseField>>(
     main_trace_frame: &EvaluationFrame<E>,
     aux_trace_frame: &Option<EvaluationFrame<E>>,
     aux_rand_elements: AuxTraceRandElements<E>,
+    periodic_values: &[E],
     x: E,
 ) -> E {
     // 1 ----- evaluate transition constraints ----------------------------------------------------
 
-    // initialize a buffer to hold transition constraint evaluations
+    // evaluate transition constraints for the main trace segment and for auxiliary trace segments
     let t_constraints = air.get_transition_constraints(&composition_coefficients.transition);
+    let (t_evaluations1, t_evaluations2) = evaluate_transition_constraints(
+        air,
+        main_trace_frame,
+        aux_trace_frame,
+        &aux_rand_elements,
+        periodic_values,
+    );
+
+    // merge all constraint evaluations into a single value by computing their random linear
+    // combination using coefficients drawn from the public coin. this also divides the result
+    // by the divisor of transition constraints.
+    let mut result = t_constraints.combine_evaluations::<E>(&t_evaluations1, &t_evaluations2, x);
+
+    // 2 ----- evaluate boundary constraints ------------------------------------------------------
+
+    // evaluate boundary constraints directly from the assertions; this avoids interpolating
+    // value polynomials of sequence assertions, which could require a lot of memory for
+    // computations with long sequence assertions
+    result += air.evaluate_boundary_constraints(
+        &aux_rand_elements,
+        &composition_coefficients.boundary,
+        main_trace_frame.current(),
+        aux_trace_frame.as_ref().map(|frame| frame.current()),
+        x,
+    );
+
+    // 3 ----- evaluate wraparound constraints ----------------------------------------------------
+
+    // wraparound constraints relate the last row of the main trace segment to the first one; they
+    // have their own divisor, and thus, are combined separately from transition constraints
+    if air.context().num_wraparound_constraints() > 0 {
+        let w_constraints = air.get_wraparound_constraints(&composition_coefficients.wraparound);
+        let w_evaluations = evaluate_wraparound_constraints(air, main_trace_frame);
+        result += w_constraints.combine_evaluations::<E>(&w_evaluations, x);
+    }
+
+    // 4 ----- evaluate range check constraints ---------------------------------------------------
+
+    // range check constraints must hold on all steps of the trace, and thus, also have their own
+    // divisor; the random element of the lookup is the last random element drawn for the first
+    // auxiliary trace segment
+    let r_constraints = air.get_range_check_constraints(&composition_coefficients.range_check);
+    if !r_constraints.is_empty() {
+        let r_evaluations = evaluate_range_check_constraints(
+            &r_constraints,
+            main_trace_frame,
+            aux_trace_frame,
+            &aux_rand_elements,
+            x,
+        );
+        result += r_constraints.combine_evaluations::<E>(&r_evaluations, x);
+    }
+
+    result
+}
 
-    // compute values of periodic columns at x
-    let periodic_values = air
-        .get_periodic_column_polys()
+/// Evaluates constraints for the specified evaluation frame, and returns the contribution of each
+/// constraint to the value computed by [evaluate_constraints()].
+#[cfg(feature = "debug")]
+pub fn evaluate_constraint_contributions<A: Air, E: FieldElement<BaseField = A::BaseField>>(
+    air: &A,
+    composition_coefficients: ConstraintCompositionCoefficients<E>,
+    main_trace_frame: &EvaluationFrame<E>,
+    aux_trace_frame: &Option<EvaluationFrame<E>>,
+    aux_rand_elements: AuxTraceRandElements<E>,
+    periodic_values: &[E],
+    x: E,
+) -> ConstraintContributions<E> {
+    // compute contributions of transition constraints; all transition constraints share the same
+    // divisor, but each of them may have its own degree adjustment
+    let t_constraints = air.get_transition_constraints(&composition_coefficients.transition);
+    let (t_evaluations1, t_evaluations2) = evaluate_transition_constraints(
+        air,
+        main_trace_frame,
+        aux_trace_frame,
+        &aux_rand_elements,
+        periodic_values,
+    );
+    let t_divisor = t_constraints.divisor().evaluate_at(x);
+    let t_contribution = |evaluation: E, coefficient: E, degree_adjustment: Option<&usize>| {
+        let adjustment = x.exp((degree_adjustment.copied().unwrap_or(0) as u64).into());
+        evaluation * coefficient * adjustment / t_divisor
+    };
+    let main_transition = t_evaluations1
+        .iter()
+        .zip(t_constraints.main_constraint_coef())
+        .enumerate()
+        .map(|(i, (&evaluation, coefficient))| {
+            t_contribution(evaluation, coefficient, t_constraints.main_degree_adjustments().get(i))
+        })
+        .collect();
+    let aux_transition = t_evaluations2
         .iter()
-        .map(|poly| {
-            let num_cycles = air.trace_length() / poly.len();
-            let x = x.exp_vartime((num_cycles as u32).into());
-            polynom::eval(poly, x)
+        .zip(t_constraints.aux_constraint_coef())
+        .enumerate()
+        .map(|(i, (&evaluation, coefficient))| {
+            t_contribution(evaluation, coefficient, t_constraints.aux_degree_adjustments().get(i))
         })
-        .collect::<Vec<_>>();
+        .collect();
+
+    // compute contributions of boundary constraints
+    let aux_assertions = air.get_aux_assertions(&aux_rand_elements);
+    let (main_boundary, aux_boundary) = BoundaryConstraints::evaluate_contributions(
+        air.context(),
+        air.get_assertions(),
+        aux_assertions,
+        &composition_coefficients.boundary,
+        main_trace_frame.current(),
+        aux_trace_frame.as_ref().map(|frame| frame.current()),
+        x,
+    );
+
+    // compute contributions of wraparound constraints
+    let mut wraparound = Vec::new();
+    if air.context().num_wraparound_constraints() > 0 {
+        let w_constraints = air.get_wraparound_constraints(&composition_coefficients.wraparound);
+        let w_evaluations = evaluate_wraparound_constraints(air, main_trace_frame);
+        let w_divisor = w_constraints.divisor().evaluate_at(x);
+        wraparound = w_evaluations
+            .iter()
+            .zip(w_constraints.coefficients())
+            .map(|(&evaluation, &coefficient)| evaluation * coefficient / w_divisor)
+            .collect();
+    }
 
+    // compute contributions of range check constraints
+    let mut range_check = Vec::new();
+    let r_constraints = air.get_range_check_constraints(&composition_coefficients.range_check);
+    if !r_constraints.is_empty() {
+        let r_evaluations = evaluate_range_check_constraints(
+            &r_constraints,
+            main_trace_frame,
+            aux_trace_frame,
+            &aux_rand_elements,
+            x,
+        );
+        let r_divisor = r_constraints.divisor().evaluate_at(x);
+        range_check = r_evaluations
+            .iter()
+            .zip(r_constraints.coefficients())
+            .map(|(&evaluation, &coefficient)| evaluation * coefficient / r_divisor)
+            .collect();
+    }
+
+    ConstraintContributions {
+        main_transition,
+        aux_transition,
+        main_boundary,
+        aux_boundary,
+        wraparound,
+        range_check,
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Evaluates transition constraints for the main trace segment and for auxiliary trace segments
+/// (if any) over the specified frames.
+fn evaluate_transition_constraints<A: Air, E: FieldElement<BaseField = A::BaseField>>(
+    air: &A,
+    main_trace_frame: &EvaluationFrame<E>,
+    aux_trace_frame: &Option<EvaluationFrame<E>>,
+    aux_rand_elements: &AuxTraceRandElements<E>,
+    periodic_values: &[E],
+) -> (Vec<E>, Vec<E>) {
     // evaluate transition constraints for the main trace segment
-    let mut t_evaluations1 = E::zeroed_vector(t_constraints.num_main_constraints());
-    air.evaluate_transition(main_trace_frame, &periodic_values, &mut t_evaluations1);
+    let t_evaluations1 = air.evaluate_transition_at(main_trace_frame, periodic_values);
 
-    // evaluate transition constraints for auxiliary trace segments (if any)
-    let mut t_evaluations2 = E::zeroed_vector(t_constraints.num_aux_constraints());
-    if let Some(aux_trace_frame) = aux_trace_frame {
+    // evaluate transition constraints for auxiliary trace segments (if any); the auxiliary frame
+    // is also present when the auxiliary segment was added only for range checks, in which case
+    // the AIR does not define any auxiliary transition constraints
+    let mut t_evaluations2 = E::zeroed_vector(air.context().num_aux_transition_constraints());
+    let air_aux_trace_frame =
+        aux_trace_frame.as_ref().filter(|_| air.trace_info().is_multi_segment());
+    if let Some(aux_trace_frame) = air_aux_trace_frame {
         air.evaluate_aux_transition(
             main_trace_frame,
             aux_trace_frame,
-            &periodic_values,
-            &aux_rand_elements,
+            periodic_values,
+            aux_rand_elements,
             &mut t_evaluations2,
         );
     }
 
-    // merge all constraint evaluations into a single value by computing their random linear
-    // combination using coefficients drawn from the public coin. this also divides the result
-    // by the divisor of transition constraints.
-    let mut result = t_constraints.combine_evaluations::<E>(&t_evaluations1, &t_evaluations2, x);
+    (t_evaluations1, t_evaluations2)
+}
 
-    // 2 ----- evaluate boundary constraints ------------------------------------------------------
+/// Evaluates wraparound constraints over the specified main trace frame.
+fn evaluate_wraparound_constraints<A: Air, E: FieldElement<BaseField = A::BaseField>>(
+    air: &A,
+    main_trace_frame: &EvaluationFrame<E>,
+) -> Vec<E> {
+    let mut w_evaluations = E::zeroed_vector(air.context().num_wraparound_constraints());
+    air.evaluate_wraparound(main_trace_frame, &mut w_evaluations);
+    w_evaluations
+}
 
-    // get boundary constraints grouped by common divisor from the AIR
-    let b_constraints =
-        air.get_boundary_constraints(&aux_rand_elements, &composition_coefficients.boundary);
+/// Evaluates range check constraints over the specified frames; the random element of the lookup
+/// is the last random element drawn for the first auxiliary trace segment.
+fn evaluate_range_check_constraints<E: FieldElement>(
+    r_constraints: &RangeCheckConstraints<E>,
+    main_trace_frame: &EvaluationFrame<E>,
+    aux_trace_frame: &Option<EvaluationFrame<E>>,
+    aux_rand_elements: &AuxTraceRandElements<E>,
+    x: E,
+) -> Vec<E> {
+    let aux_trace_frame = aux_trace_frame.as_ref().expect("missing auxiliary trace frame");
+    let alpha = aux_rand_elements
+        .get_segment_elements(0)
+        .last()
+        .copied()
+        .expect("missing random element for range checks");
+    let table_values = r_constraints.get_table_values_at(x);
+    let mut r_evaluations = E::zeroed_vector(r_constraints.num_constraints());
+    r_constraints.evaluate::<E>(
+        main_trace_frame,
+        aux_trace_frame,
+        &table_values,
+        alpha,
+        &mut r_evaluations,
+    );
+    r_evaluations
+}
 
-    // iterate over boundary constraint groups for the main trace segment (each group has a
-    // distinct divisor), evaluate constraints in each group and add their combination to the
-    // result
-    for group in b_constraints.main_constraints().iter() {
-        result += group.evaluate_at(main_trace_frame.current(), x);
-    }
+// CONSTRAINT CONTRIBUTIONS
+// ================================================================================================
 
-    // iterate over boundary constraint groups for auxiliary trace segments (each group has a
-    // distinct divisor), evaluate constraints in each group and add their combination to the
-    // result
-    if let Some(aux_trace_frame) = aux_trace_frame {
-        for group in b_constraints.aux_constraints().iter() {
-            result += group.evaluate_at(aux_trace_frame.current(), x);
-        }
-    }
+/// Contributions of individual constraints to the value of the constraint composition polynomial
+/// at a given point.
+///
+/// The contribution of a constraint is its evaluation multiplied by its composition coefficient
+/// (and by its degree adjustment factor, if any) and divided by its divisor. Thus, contributions
+/// of all constraints add up to the value of the constraint composition polynomial at the point;
+/// when the out-of-domain consistency check fails, contributions can be inspected to find the
+/// constraints which are not satisfied by the out-of-domain frame.
+///
+/// This struct is available only when the `debug` feature is enabled.
+#[cfg(feature = "debug")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintContributions<E: FieldElement> {
+    /// Contributions of transition constraints against the main trace segment, in the order in
+    /// which the constraints are evaluated by the AIR.
+    pub main_transition: Vec<E>,
+    /// Contributions of transition constraints against auxiliary trace segments, in the order in
+    /// which the constraints are evaluated by the AIR.
+    pub aux_transition: Vec<E>,
+    /// Contributions of boundary constraints against the main trace segment together with their
+    /// assertions; assertions are sorted in their natural order.
+    pub main_boundary: Vec<(Assertion<E::BaseField>, E)>,
+    /// Contributions of boundary constraints against auxiliary trace segments together with their
+    /// assertions; assertions are sorted in their natural order.
+    pub aux_boundary: Vec<(Assertion<E>, E)>,
+    /// Contributions of wraparound constraints.
+    pub wraparound: Vec<E>,
+    /// Contributions of range check constraints.
+    pub range_check: Vec<E>,
+}
 
-    result
+#[cfg(feature = "debug")]
+impl<E: FieldElement> ConstraintContributions<E> {
+    /// Returns the sum of contributions of all constraints; this is equal to the value of the
+    /// constraint composition polynomial at the point at which the contributions were computed.
+    pub fn total(&self) -> E {
+        let transition = self.main_transition.iter().chain(self.aux_transition.iter());
+        transition
+            .chain(self.main_boundary.iter().map(|(_, value)| value))
+            .chain(self.aux_boundary.iter().map(|(_, value)| value))
+            .chain(self.wraparound.iter())
+            .chain(self.range_check.iter())
+            .fold(E::ZERO, |acc, &value| acc + value)
+    }
 }
@@ -33,121 +33,1635 @@
 extern crate alloc;
 
 pub use air::{
-    proof::StarkProof, Air, AirContext, Assertion, AuxTraceRandElements, BoundaryConstraint,
-    BoundaryConstraintGroup, ConstraintCompositionCoefficients, ConstraintDivisor,
-    DeepCompositionCoefficients, EvaluationFrame, FieldExtension, ProofOptions, TraceInfo,
+    lde_coset_offset, lde_domain_position,
+    proof::{
+        CommitmentRoots, DualProof, HashFunctionId, IdentifiableHasher, ProofSignatureScheme,
+        QueryResponse, SignedProof, StarkProof, VerificationKey,
+    },
+    Air, AirContext, Assertion, AssertionSummary, AuxTraceRandElements, BoundaryConstraint,
+    BoundaryConstraintGroup, ChecksumError, CommitmentLayout, ConstraintCompositionCoefficients,
+    ConstraintDivisor, ConstraintExpr, DeepCompositionCoefficients, EvaluationFrame,
+    FieldExtension, HashAccumulator, HashFunction, LdeDomainInfo, OpenedLeaves,
+    PolynomialConstraint, ProofOptions, ProofOptionsError, RationalConstraint,
+    SparsePeriodicColumn, SymbolicConstraints, SymbolicFrame, TraceInfo,
     TransitionConstraintDegree,
 };
 
 pub use math;
 use math::{
     fields::{CubeExtension, QuadExtension},
-    FieldElement, ToElements,
+    FieldElement, StarkField, ToElements,
 };
 
+use utils::string::ToString;
 pub use utils::{
-    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
-    SliceReader,
+    collections::Vec,
+    parallel::{DefaultExecutor, Executor, SequentialExecutor},
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
 };
 
 pub use crypto;
-use crypto::{ElementHasher, Hasher, RandomCoin};
+use crypto::{DefaultRandomCoin, ElementHasher, Hasher, MerkleTree, RandomCoin};
 
+use air::proof::Table;
 use fri::FriVerifier;
 
+use rand_core::RngCore;
+
 mod channel;
-use channel::VerifierChannel;
+use channel::{QueriedStates, TraceOodFrame, VerifierChannel};
+
+mod evaluator;
+#[cfg(feature = "debug")]
+use evaluator::evaluate_constraint_contributions;
+use evaluator::evaluate_constraints;
+#[cfg(feature = "debug")]
+pub use evaluator::ConstraintContributions;
+
+mod composer;
+use composer::DeepComposer;
+
+mod errors;
+pub use errors::VerifierError;
+
+mod challenges;
+pub use challenges::VerifierChallenges;
+
+#[cfg(feature = "debug")]
+mod replay;
+#[cfg(feature = "debug")]
+pub use replay::{replay_verification, VerificationTranscript};
+
+// VERIFIER
+// ================================================================================================
+/// Verifies that the specified computation was executed correctly against the specified inputs.
+///
+/// Specifically, for a computation specified by `AIR` and `HashFn` type parameter, verifies that
+/// the provided `proof` attests to the correct execution of the computation against public inputs
+/// specified by `pub_inputs`. If the verification is successful, `Ok(())` is returned.
+///
+/// # Errors
+/// Returns an error if combination of the provided proof and public inputs does not attest to
+/// a correct execution of the computation. This could happen for many various reasons, including:
+/// - The specified proof was generated for a different computation.
+/// - The specified proof was generated for this computation but for different public inputs.
+/// - The specified proof was generated with parameters not providing an acceptable security level.
+/// - The specified proof was generated with application-specific metadata attached.
+pub fn verify<AIR, HashFn, RandCoin>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    verify_with_metadata::<AIR, HashFn, RandCoin>(proof, pub_inputs, acceptable_options, &[])
+}
+
+/// Verifies that the specified computation was executed correctly against public inputs embedded
+/// into the proof, given only a commitment to these inputs.
+///
+/// This is the counterpart of `Prover::prove_with_embedded_inputs()`: the public inputs are read
+/// from the `proof`, and are accepted only if a hash of their elements (i.e.,
+/// `HashFn::hash_elements(&pub_inputs.to_elements())`) is equal to `input_commitment`. The proof
+/// is then verified against these inputs in the same way as in [verify()].
+///
+/// # Errors
+/// Returns an error if the proof does not contain valid public inputs, if the embedded public
+/// inputs do not match `input_commitment`, or for any of the reasons listed for [verify()].
+pub fn verify_with_input_commitment<AIR, HashFn, RandCoin>(
+    proof: StarkProof,
+    input_commitment: &HashFn::Digest,
+    acceptable_options: &AcceptableOptions,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    AIR::PublicInputs: Deserializable,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    // read public inputs from the proof and make sure they match the commitment before doing
+    // any other work
+    let pub_inputs = AIR::PublicInputs::read_from_bytes(&proof.pub_inputs)
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+    if HashFn::hash_elements(&pub_inputs.to_elements()) != *input_commitment {
+        return Err(VerifierError::InconsistentInputCommitment);
+    }
+
+    verify::<AIR, HashFn, RandCoin>(proof, pub_inputs, acceptable_options)
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// and that the proof is bound to the specified application-specific `metadata`.
+///
+/// This is the counterpart of `Prover::prove_with_metadata()`: the metadata attached to the
+/// `proof` must be equal to `metadata`, and since the metadata is absorbed into the public coin,
+/// a proof generated for different metadata will not verify even if its stored metadata is
+/// replaced. Verifying against empty metadata is equivalent to [verify()].
+///
+/// # Errors
+/// Returns an error if combination of the provided proof, public inputs, and metadata does not
+/// attest to a correct execution of the computation. In addition to the reasons listed for
+/// [verify()], this happens when the metadata attached to the proof differs from `metadata`.
+pub fn verify_with_metadata<AIR, HashFn, RandCoin>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+    metadata: &[u8],
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    verify_proof::<AIR, HashFn, HashFn, RandCoin, _>(
+        proof,
+        pub_inputs,
+        acceptable_options,
+        metadata,
+        None,
+        &DefaultExecutor::default(),
+        QueryMode::Batch,
+    )
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// where FRI layers of the proof are committed to with hash function `FriHashFn`.
+///
+/// This is the counterpart of `Prover::prove_with_fri_hash_fn()`: execution trace and constraint
+/// commitments are checked with `HashFn`, while FRI layer commitments are checked with
+/// `FriHashFn`. The proof options of the `proof` must record identifiers of both hash functions
+/// (see [ProofOptions::with_hash_fns()]), and these must be the identifiers of `HashFn` and
+/// `FriHashFn` respectively.
+///
+/// Security level of the proof is limited by the weaker of the two hash functions.
+///
+/// # Errors
+/// Returns an error if combination of the provided proof and public inputs does not attest to
+/// a correct execution of the computation. In addition to the reasons listed for [verify()],
+/// this happens when the proof options of the `proof` do not specify separate hash functions, or
+/// when the specified hash functions are not `HashFn` and `FriHashFn`. Proofs cannot be verified
+/// with hash functions which have [HashFunctionId::Unknown] identifier.
+pub fn verify_with_fri_hash_fn<AIR, HashFn, FriHashFn, RandCoin>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField> + IdentifiableHasher,
+    FriHashFn: ElementHasher<BaseField = AIR::BaseField> + IdentifiableHasher,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    match proof.options().hash_fns() {
+        None => return Err(VerifierError::UnspecifiedHashFunctions),
+        Some(_)
+            if HashFn::ID == HashFunctionId::Unknown
+                || FriHashFn::ID == HashFunctionId::Unknown =>
+        {
+            return Err(VerifierError::UnknownHashFunction)
+        }
+        Some(hash_fns) if hash_fns != (HashFn::ID, FriHashFn::ID) => {
+            return Err(VerifierError::InconsistentHashFunctions)
+        }
+        Some(_) => (),
+    }
+    verify_proof::<AIR, HashFn, FriHashFn, RandCoin, _>(
+        proof,
+        pub_inputs,
+        acceptable_options,
+        &[],
+        None,
+        &DefaultExecutor::default(),
+        QueryMode::Batch,
+    )
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// relative to a trusted commitment to the main segment of the execution trace.
+///
+/// This is intended for cases when the verifier already trusts the commitment to the main trace
+/// segment (e.g., because it was published earlier). The commitment to the main trace segment
+/// contained in the `proof` is ignored, and `trace_commitment` is used in its place: it is bound
+/// into the public coin in the same way as the commitment sent by the prover, and trace queries
+/// are authenticated against it. Constraint evaluations, DEEP composition, and FRI are verified
+/// in the same way as in [verify()].
+///
+/// # Errors
+/// Returns an error if the proof does not attest to a correct execution of the computation
+/// relative to `trace_commitment` (e.g., because the proof was generated for a trace with a
+/// different commitment), or for any of the reasons listed for [verify()].
+pub fn verify_with_trusted_commitment<AIR, HashFn, RandCoin>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+    trace_commitment: &HashFn::Digest,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    verify_proof::<AIR, HashFn, HashFn, RandCoin, _>(
+        proof,
+        pub_inputs,
+        acceptable_options,
+        &[],
+        Some(trace_commitment),
+        &DefaultExecutor::default(),
+        QueryMode::Batch,
+    )
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// relative to a commitment to the main trace segment contained in a Merkle accumulator.
+///
+/// This is intended for systems which batch commitments of many proofs into a single Merkle
+/// tree (an accumulator), and publish only the root of this tree. Instead of the raw trace
+/// commitment, the verifier is supplied with a membership proof: the index of the commitment in
+/// the accumulator and the Merkle `path` for this index (as returned by
+/// [MerkleTree::prove()](crypto::MerkleTree::prove)); the first element of the path is the
+/// commitment itself. The path is checked against `accumulator_root` via
+/// [MerkleTree::verify()](crypto::MerkleTree::verify), and the proof is then verified relative
+/// to the commitment contained in the path in the same way as in
+/// [verify_with_trusted_commitment()].
+///
+/// # Errors
+/// Returns an error if `path` does not resolve to `accumulator_root` for the specified `index`,
+/// or for any of the reasons listed for [verify_with_trusted_commitment()].
+pub fn verify_with_accumulator<AIR, HashFn, RandCoin>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+    accumulator_root: &HashFn::Digest,
+    index: usize,
+    path: &[HashFn::Digest],
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    // a valid path contains at least the leaf and its sibling, and the index must refer to one
+    // of the leaves of the tree implied by the length of the path
+    if path.len() < 2 || path.len() > usize::BITS as usize || index >> (path.len() - 1) != 0 {
+        return Err(VerifierError::InvalidAccumulatorMembership);
+    }
+    MerkleTree::<HashFn>::verify(*accumulator_root, index, path)
+        .map_err(|_| VerifierError::InvalidAccumulatorMembership)?;
+
+    let trace_commitment = &path[0];
+    verify_with_trusted_commitment::<AIR, HashFn, RandCoin>(
+        proof,
+        pub_inputs,
+        acceptable_options,
+        trace_commitment,
+    )
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// for a proof from which the commitment to the main trace segment was omitted.
+///
+/// This is intended for proofs generated via `prove_without_trace_commitment()` method of the
+/// prover: such proofs do not contain the commitment to the main trace segment, and instead, the
+/// commitment is supplied by the verifier via `trace_commitment`. The commitment is bound into
+/// the public coin and trace queries are authenticated against it in the same way as in
+/// [verify()].
+///
+/// # Errors
+/// Returns an error if the proof does not attest to a correct execution of the computation
+/// relative to `trace_commitment`, if the proof still contains the commitment to the main trace
+/// segment, or for any of the reasons listed for [verify()].
+pub fn verify_with_external_commitment<AIR, HashFn, RandCoin>(
+    mut proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+    trace_commitment: &HashFn::Digest,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    proof.commitments.insert_main_trace_commitment::<HashFn>(trace_commitment);
+    verify::<AIR, HashFn, RandCoin>(proof, pub_inputs, acceptable_options)
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// and that the proof commits to the specified externally-anchored commitment roots.
+///
+/// This is intended for hybrid protocols in which only the commitment roots of a proof (as
+/// returned by [StarkProof::commitment_roots()]) are anchored externally (e.g., on-chain), while
+/// the full proof is verified elsewhere. The roots contained in the `proof` must be equal to
+/// `anchored_roots`; the proof is then verified in the same way as in [verify()]. Since the
+/// verification authenticates all queries against the roots contained in the proof, a
+/// successfully verified proof attests to the correct execution of the computation relative to
+/// the anchored roots.
+///
+/// # Errors
+/// Returns an error if the commitment roots contained in the proof could not be parsed or do not
+/// match `anchored_roots`, or for any of the reasons listed for [verify()].
+pub fn verify_with_anchored_roots<AIR, HashFn, RandCoin>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+    anchored_roots: &CommitmentRoots<HashFn::Digest>,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    let roots = proof
+        .commitment_roots::<HashFn>()
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+    if roots != *anchored_roots {
+        return Err(VerifierError::InconsistentCommitmentRoots);
+    }
+
+    verify::<AIR, HashFn, RandCoin>(proof, pub_inputs, acceptable_options)
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// relative to the specified verification key.
+///
+/// This is the counterpart of `Prover::prove_with_vk()`. The verification key does not replace
+/// `AIR`: the key only pins the parameters of the computation, and the proof is checked against
+/// the constraints and periodic columns defined by `AIR`. Specifically, the context of the
+/// `proof` (i.e., trace layout and length, base field, and proof options) must be equal to the
+/// context pinned by `vk`, and the periodic columns defined by `AIR` must be the same as the
+/// periodic columns pinned by `vk`. The proof is then verified in the same way as in [verify()],
+/// accepting only the proof options pinned by the key. Thus, the security level of the proof is
+/// determined by the options chosen when the key was generated.
+///
+/// # Errors
+/// Returns an error if the proof context or the periodic columns of `AIR` do not match `vk`, or
+/// for any of the reasons listed for [verify()].
+pub fn verify_with_vk<AIR, HashFn, RandCoin>(
+    proof: StarkProof,
+    vk: &VerificationKey<AIR::BaseField>,
+    pub_inputs: AIR::PublicInputs,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    AIR::PublicInputs: Clone,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    if proof.context != *vk.context() {
+        return Err(VerifierError::InconsistentVerificationKey);
+    }
+
+    // make sure the AIR with which the verifier was instantiated defines the same periodic
+    // columns as the AIR for which the verification key was generated
+    let air = AIR::new(vk.get_trace_info(), pub_inputs.clone(), vk.options().clone());
+    if air.get_periodic_column_polys() != vk.periodic_column_polys() {
+        return Err(VerifierError::InconsistentVerificationKey);
+    }
+
+    let acceptable_options = AcceptableOptions::OptionSet(vec![vk.options().clone()]);
+    verify::<AIR, HashFn, RandCoin>(proof, pub_inputs, &acceptable_options)
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// rejecting proofs which exceed the specified resource limits.
+///
+/// The limits are checked against the parameters embedded into the `proof` before any other
+/// work is done; thus, a proof claiming a huge trace length, number of FRI layers, or number of
+/// queries is rejected before any memory is allocated for its verification. Proofs which are
+/// within the limits are verified in the same way as in [verify()].
+///
+/// # Errors
+/// Returns an error if the proof exceeds any of the specified `limits`, or for any of the
+/// reasons listed for [verify()].
+pub fn verify_with_limits<AIR, HashFn, RandCoin>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+    limits: &VerificationLimits,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    limits.validate(&proof)?;
+    verify::<AIR, HashFn, RandCoin>(proof, pub_inputs, acceptable_options)
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// executing independent checks via the specified `executor`.
+///
+/// The proof is verified in the same way as in [verify()], and the result is the same as the
+/// result of [verify()]; the `executor` only determines how independent checks (e.g.,
+/// authentication of queries against different commitments) are scheduled. This makes it
+/// possible to verify proofs using a custom thread pool, or to force single-threaded
+/// verification via [SequentialExecutor] even when `concurrent` feature is enabled. Functions
+/// which do not take an executor use [DefaultExecutor].
+///
+/// # Errors
+/// Returns an error for any of the reasons listed for [verify()].
+pub fn verify_with_executor<AIR, HashFn, RandCoin, X>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+    executor: &X,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+    X: Executor,
+{
+    verify_proof::<AIR, HashFn, HashFn, RandCoin, X>(
+        proof,
+        pub_inputs,
+        acceptable_options,
+        &[],
+        None,
+        executor,
+        QueryMode::Batch,
+    )
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// fully checking only a random subset of the queries.
+///
+/// **This provides reduced soundness and must not be used in place of [verify()] when soundness
+/// matters.** It is intended for probabilistic fast-checking, e.g., to cheaply filter out
+/// invalid proofs before they are verified in full.
+///
+/// All parts of the proof which are not query-specific (proof parameters, proof-of-work, and
+/// out-of-domain consistency) are checked in the same way as in [verify()], and all queries are
+/// authenticated against the trace, constraint, and FRI layer commitments. However, DEEP
+/// composition and FRI folding are checked only for a fraction of the queries chosen at random
+/// using `rng`. The number of checked queries is `fraction` times the number of queries, rounded
+/// up. Thus, the conjectured security level of the check is reduced roughly proportionally to
+/// `fraction`, and an invalid proof is accepted with a much higher probability than by
+/// [verify()]. With `fraction` set to 1.0, this is equivalent to [verify()].
+///
+/// Since the checked queries are chosen by the verifier rather than derived from the proof, a
+/// prover cannot predict which queries will be checked.
+///
+/// # Errors
+/// Returns an error for any of the reasons listed for [verify()], as long as the reason is
+/// detected by the checked queries.
+///
+/// # Panics
+/// Panics if `fraction` is not in the range (0, 1].
+pub fn verify_sampled<AIR, HashFn, RandCoin, R>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+    fraction: f64,
+    rng: &mut R,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+    R: RngCore,
+{
+    assert!(
+        fraction > 0.0 && fraction <= 1.0,
+        "fraction of sampled queries must be in the range (0, 1], but was {fraction}"
+    );
+    verify_proof::<AIR, HashFn, HashFn, RandCoin, _>(
+        proof,
+        pub_inputs,
+        acceptable_options,
+        &[],
+        None,
+        &DefaultExecutor::default(),
+        QueryMode::Sampled(QuerySampling { fraction, rng }),
+    )
+}
+
+/// Verifies that the specified computation was executed correctly against one of the specified
+/// candidate public inputs, and returns the index of the first candidate the proof verifies
+/// against.
+///
+/// This is intended for protocols in which the exact public inputs are not known to the verifier,
+/// but belong to a small set of candidates. Checks which do not depend on the public inputs (e.g.,
+/// validation of proof parameters) are performed only once; the proof is then verified against
+/// each candidate in order, in the same way as in [verify()], stopping on the first candidate
+/// for which the verification succeeds. `Ok(None)` is returned if the proof does not verify
+/// against any of the candidates.
+///
+/// # Errors
+/// Returns an error if the proof is rejected for a reason which does not depend on the public
+/// inputs; for example, if it was generated with parameters not providing an acceptable security
+/// level, with application-specific metadata attached, or if it cannot be deserialized.
+pub fn verify_any<AIR, HashFn, RandCoin>(
+    proof: StarkProof,
+    candidates: &[AIR::PublicInputs],
+    acceptable_options: &AcceptableOptions,
+) -> Result<Option<usize>, VerifierError>
+where
+    AIR: Air,
+    AIR::PublicInputs: Clone,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    // perform all checks which do not depend on public inputs only once
+    acceptable_options.validate::<HashFn>(&proof)?;
+    if !proof.metadata.is_empty() {
+        return Err(VerifierError::InconsistentMetadata);
+    }
+    validate_lde_domain::<AIR::BaseField>(&proof)?;
+    let context_elements = proof.context.to_elements();
+
+    for (i, pub_inputs) in candidates.iter().enumerate() {
+        match verify_against_inputs::<AIR, HashFn, HashFn, RandCoin, _>(
+            proof.clone(),
+            pub_inputs.clone(),
+            &context_elements,
+            &[],
+            None,
+            &DefaultExecutor::default(),
+            QueryMode::Batch,
+        ) {
+            Ok(()) => return Ok(Some(i)),
+            Err(err) if is_input_dependent(&err) => continue,
+            // the proof cannot be verified against any of the candidates
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(None)
+}
+
+/// Returns true if the specified error may be caused by verifying a valid proof against public
+/// inputs other than the ones the proof was generated for.
+///
+/// Public inputs are absorbed into the public coin, and thus, affect all values drawn from it:
+/// random elements, the out-of-domain point, and query positions. Errors which arise before any
+/// values are drawn (e.g., proof deserialization errors) do not depend on public inputs.
+fn is_input_dependent(err: &VerifierError) -> bool {
+    matches!(
+        err,
+        VerifierError::RandomCoinError
+            | VerifierError::InconsistentOodConstraintEvaluations
+            | VerifierError::TraceQueryDoesNotMatchCommitment
+            | VerifierError::ConstraintQueryDoesNotMatchCommitment
+            | VerifierError::QuerySeedProofOfWorkVerificationFailed
+            | VerifierError::FriVerificationFailed(_)
+            | VerifierError::InconsistentNumQueries(..)
+    )
+}
+
+/// Verifies that two computations, possibly defined over different base fields, were executed
+/// correctly against the specified inputs.
+///
+/// This is the counterpart of `prove_dual()` function of the prover crate. The first proof of
+/// the [DualProof] is verified against `first_pub_inputs` and metadata committing to
+/// `second_pub_inputs`, and the second proof is verified against `second_pub_inputs` and metadata
+/// committing to the first proof. Both proofs are verified in the same way as in
+/// [verify_with_metadata()].
+///
+/// **This is experimental.** Relations between the public inputs of the two computations are not
+/// checked; the caller must verify them independently.
+///
+/// # Errors
+/// Returns an error if either of the proofs does not attest to a correct execution of its
+/// computation, or if the proofs were not generated together.
+pub fn verify_dual<AIR1, HashFn1, RandCoin1, AIR2, HashFn2, RandCoin2>(
+    proof: DualProof,
+    first_pub_inputs: AIR1::PublicInputs,
+    second_pub_inputs: AIR2::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<(), VerifierError>
+where
+    AIR1: Air,
+    HashFn1: ElementHasher<BaseField = AIR1::BaseField>,
+    RandCoin1: RandomCoin<BaseField = AIR1::BaseField, Hasher = HashFn1>,
+    AIR2: Air,
+    HashFn2: ElementHasher<BaseField = AIR2::BaseField>,
+    RandCoin2: RandomCoin<BaseField = AIR2::BaseField, Hasher = HashFn2>,
+{
+    let DualProof { first, second } = proof;
+    let first_metadata = DualProof::first_metadata(&second_pub_inputs.to_elements());
+    let second_metadata = DualProof::second_metadata(&first);
+
+    verify_with_metadata::<AIR1, HashFn1, RandCoin1>(
+        first,
+        first_pub_inputs,
+        acceptable_options,
+        &first_metadata,
+    )?;
+    verify_with_metadata::<AIR2, HashFn2, RandCoin2>(
+        second,
+        second_pub_inputs,
+        acceptable_options,
+        &second_metadata,
+    )
+}
+
+/// Verifies that a computation was executed correctly against the specified inputs, and that the
+/// proof was signed by the holder of the secret key for the attached public key.
+///
+/// The signature attached to the [SignedProof] is checked against the identifier of the proof
+/// before the proof itself is verified in the same way as in [verify()]. The caller is responsible
+/// for checking that the public key attached to the proof is trusted.
+///
+/// # Errors
+/// Returns an error if the signature is not valid for the attached public key, or if the proof
+/// does not attest to a correct execution of the computation.
+pub fn verify_signed<AIR, HashFn, RandCoin, S>(
+    proof: SignedProof<S>,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+    S: ProofSignatureScheme,
+{
+    if !proof.verify_signature() {
+        return Err(VerifierError::InvalidProofSignature);
+    }
+    verify::<AIR, HashFn, RandCoin>(proof.proof, pub_inputs, acceptable_options)
+}
+
+/// Verifies that a computation was executed correctly against the specified inputs, reading
+/// trace and constraint queries from a stream of query responses.
+///
+/// This is the counterpart of `prove_streamed()` method of the prover: the `proof` must contain
+/// empty trace and constraint queries, and `responses` must yield a [QueryResponse] for each
+/// query position, in the order of query positions. Query positions are derived from the proof
+/// in the same way as in [verify()], and each response is checked against the trace and
+/// constraint commitments as soon as it is received; thus, verification fails without reading
+/// further responses once an invalid response is encountered. After all responses are received,
+/// DEEP composition and the FRI low-degree proof are checked against the received values. The
+/// result is the same as the result of [verify()] for the proof with all queries batched
+/// together.
+///
+/// # Errors
+/// Returns an error if the proof or any of the responses does not attest to a correct execution
+/// of the computation, or if the number of responses differs from the number of query positions.
+pub fn verify_streamed<AIR, HashFn, RandCoin, I>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+    responses: I,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+    I: IntoIterator<Item = QueryResponse>,
+{
+    let mut responses = responses.into_iter();
+    verify_proof::<AIR, HashFn, HashFn, RandCoin, _>(
+        proof,
+        pub_inputs,
+        acceptable_options,
+        &[],
+        None,
+        &DefaultExecutor::default(),
+        QueryMode::Streamed(&mut responses),
+    )
+}
+
+/// Verifies the specified proof against the specified inputs and metadata; if
+/// `trusted_trace_commitment` is provided, it replaces the commitment to the main trace segment
+/// contained in the proof. Independent checks are executed via the specified `executor`, and
+/// queries are read and checked as specified by `queries`.
+#[rustfmt::skip]
+fn verify_proof<AIR, HashFn, FriHashFn, RandCoin, X>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+    metadata: &[u8],
+    trusted_trace_commitment: Option<&HashFn::Digest>,
+    executor: &X,
+    queries: QueryMode,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+    FriHashFn: ElementHasher<BaseField = AIR::BaseField>,
+    X: Executor,
+{
+    // check that `proof` was generated with an acceptable set of parameters from the point of view
+    // of the verifier; when FRI uses a separate hash function, security of the proof is limited
+    // by the weaker of the two hash functions
+    acceptable_options.validate::<HashFn>(&proof)?;
+    acceptable_options.validate::<FriHashFn>(&proof)?;
+
+    // make sure the proof is bound to the expected metadata
+    if proof.metadata != metadata {
+        return Err(VerifierError::InconsistentMetadata);
+    }
+
+    // make sure the LDE domain implied by the proof can be formed in the base field
+    validate_lde_domain::<AIR::BaseField>(&proof)?;
+    let context_elements = proof.context.to_elements();
+    verify_against_inputs::<AIR, HashFn, FriHashFn, RandCoin, X>(
+        proof,
+        pub_inputs,
+        &context_elements,
+        metadata,
+        trusted_trace_commitment,
+        executor,
+        queries,
+    )
+}
+
+/// Verifies the specified proof against the specified inputs, assuming that all checks which do
+/// not depend on the inputs have already been performed; `context_elements` must be the elements
+/// of the proof context.
+#[rustfmt::skip]
+fn verify_against_inputs<AIR, HashFn, FriHashFn, RandCoin, X>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    context_elements: &[AIR::BaseField],
+    metadata: &[u8],
+    trusted_trace_commitment: Option<&HashFn::Digest>,
+    executor: &X,
+    queries: QueryMode,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+    FriHashFn: ElementHasher<BaseField = AIR::BaseField>,
+    X: Executor,
+{
+    // build a seed for the public coin; the initial seed is a hash of the proof context and the
+    // public inputs, but as the protocol progresses, the coin will be reseeded with the info
+    // received from the prover
+    let mut public_coin_seed = context_elements.to_vec();
+    public_coin_seed.append(&mut pub_inputs.to_elements());
+
+    // create AIR instance for the computation specified in the proof
+    let air = AIR::new(proof.get_trace_info(), pub_inputs, proof.options().clone());
+
+    // figure out which version of the generic proof verification procedure to run. this is a sort
+    // of static dispatch for selecting two generic parameter: extension field and hash function.
+    match air.options().field_extension() {
+        FieldExtension::None => {
+            let public_coin = build_public_coin::<HashFn, RandCoin>(&public_coin_seed, metadata);
+            let channel = build_channel(&air, proof, trusted_trace_commitment, &queries)?;
+            perform_verification::<AIR, AIR::BaseField, HashFn, FriHashFn, RandCoin, X>(air, channel, public_coin, executor, queries)
+        },
+        FieldExtension::Quadratic => {
+            if !<QuadExtension<AIR::BaseField>>::is_supported() {
+                return Err(VerifierError::UnsupportedFieldExtension(2));
+            }
+            let public_coin = build_public_coin::<HashFn, RandCoin>(&public_coin_seed, metadata);
+            let channel = build_channel(&air, proof, trusted_trace_commitment, &queries)?;
+            perform_verification::<AIR, QuadExtension<AIR::BaseField>, HashFn, FriHashFn, RandCoin, X>(air, channel, public_coin, executor, queries)
+        },
+        FieldExtension::Cubic => {
+            if !<CubeExtension<AIR::BaseField>>::is_supported() {
+                return Err(VerifierError::UnsupportedFieldExtension(3));
+            }
+            let public_coin = build_public_coin::<HashFn, RandCoin>(&public_coin_seed, metadata);
+            let channel = build_channel(&air, proof, trusted_trace_commitment, &queries)?;
+            perform_verification::<AIR, CubeExtension<AIR::BaseField>, HashFn, FriHashFn, RandCoin, X>(air, channel, public_coin, executor, queries)
+        },
+    }
+}
+
+/// Checks that the specified proof is well-formed and that its transcript is consistent, without
+/// performing any of the expensive cryptographic checks.
+///
+/// This is intended as a cheap pre-filter for rejecting obviously malformed proofs (e.g., in a
+/// DoS-resistant front end) before running full verification. Specifically, this checks that:
+/// * The proof was generated with an acceptable set of parameters.
+/// * The proof can be parsed for the specified `AIR`; e.g., it contains the expected number of
+///   trace segment and FRI layer commitments, and queries of the expected shape.
+/// * The proof-of-work nonce is valid for the state of the public coin obtained by replaying the
+///   transcript of the proof.
+/// * The number of unique query positions derived from the public coin is equal to the number of
+///   unique queries declared by the proof, and the proof contains queries for all of them.
+///
+/// Merkle authentication paths, consistency of constraint evaluations at the out-of-domain point,
+/// and the FRI low-degree proof are not checked. Thus, `Ok(())` does not mean that the proof is
+/// valid: a proof which passes this check must still be verified via [verify()] or one of its
+/// variants.
+///
+/// Metadata attached to the proof is absorbed into the public coin in the same way as during full
+/// verification, but is not compared against any expected value.
+///
+/// # Errors
+/// Returns an error if any of the above checks fails.
+#[rustfmt::skip]
+pub fn verify_transcript_only<AIR, HashFn, RandCoin>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    acceptable_options.validate::<HashFn>(&proof)?;
+
+    // initialize the public coin in the same way as the verifier does
+    let mut public_coin_seed = proof.context.to_elements();
+    public_coin_seed.append(&mut pub_inputs.to_elements());
+    let public_coin = build_public_coin::<HashFn, RandCoin>(&public_coin_seed, &proof.metadata);
+
+    validate_lde_domain::<AIR::BaseField>(&proof)?;
+    let air = AIR::new(proof.get_trace_info(), pub_inputs, proof.options().clone());
+    match air.options().field_extension() {
+        FieldExtension::None => {
+            check_transcript::<AIR, AIR::BaseField, HashFn, HashFn, RandCoin>(air, proof, public_coin)
+        },
+        FieldExtension::Quadratic => {
+            if !<QuadExtension<AIR::BaseField>>::is_supported() {
+                return Err(VerifierError::UnsupportedFieldExtension(2));
+            }
+            check_transcript::<AIR, QuadExtension<AIR::BaseField>, HashFn, HashFn, RandCoin>(air, proof, public_coin)
+        },
+        FieldExtension::Cubic => {
+            if !<CubeExtension<AIR::BaseField>>::is_supported() {
+                return Err(VerifierError::UnsupportedFieldExtension(3));
+            }
+            check_transcript::<AIR, CubeExtension<AIR::BaseField>, HashFn, HashFn, RandCoin>(air, proof, public_coin)
+        },
+    }
+}
+
+/// Returns the out-of-domain point z at which trace and constraint composition polynomials were
+/// evaluated by the prover when generating the specified `proof` for the specified public inputs.
+///
+/// The point is derived by replaying the transcript of the proof (i.e., reseeding the public coin
+/// with the commitments from the proof) exactly as it is done during verification. This is
+/// intended for auditing and testing purposes (e.g., for golden-file tests); no part of the proof
+/// other than its commitments is checked.
+///
+/// Type parameter `E` must be the field in which the protocol was instantiated; i.e., the base
+/// field for proofs generated without a field extension, and the corresponding extension of the
+/// base field otherwise.
+///
+/// # Errors
+/// Returns an error if the proof could not be parsed for the specified `AIR` or if a random value
+/// could not be drawn from the public coin.
+///
+/// # Panics
+/// Panics if the extension degree of `E` does not match the field extension specified by the
+/// proof options.
+pub fn derive_ood_point<AIR, E, HashFn, RandCoin>(
+    proof: &StarkProof,
+    pub_inputs: AIR::PublicInputs,
+) -> Result<E, VerifierError>
+where
+    AIR: Air,
+    E: FieldElement<BaseField = AIR::BaseField>,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    assert_eq!(
+        E::EXTENSION_DEGREE,
+        proof.options().field_extension().degree() as usize,
+        "extension degree of the OOD point field does not match proof field extension"
+    );
+
+    // initialize the public coin in the same way as the verifier does
+    let mut public_coin_seed = proof.context.to_elements();
+    public_coin_seed.append(&mut pub_inputs.to_elements());
+    let mut public_coin = build_public_coin::<HashFn, RandCoin>(&public_coin_seed, &proof.metadata);
+
+    validate_lde_domain::<AIR::BaseField>(proof)?;
+    let air = AIR::new(proof.get_trace_info(), pub_inputs, proof.options().clone());
+    let channel = VerifierChannel::<E, HashFn>::new(&air, proof.clone())?;
+    let (_, _, z) =
+        draw_ood_point::<AIR, E, HashFn, HashFn, RandCoin>(&air, &channel, &mut public_coin)?;
+    Ok(z)
+}
+
+/// Returns all random challenges drawn by the verifier when verifying the specified `proof`
+/// against the specified public inputs.
+///
+/// The challenges are derived by replaying the transcript of the proof exactly as it is done
+/// during verification; the returned challenges can then be supplied to
+/// [verify_with_challenges()]. No part of the proof other than its commitments and the
+/// proof-of-work nonce is checked.
+///
+/// Type parameter `E` must be the field in which the protocol was instantiated; i.e., the base
+/// field for proofs generated without a field extension, and the corresponding extension of the
+/// base field otherwise.
+///
+/// # Errors
+/// Returns an error if the proof could not be parsed for the specified `AIR`, if a random value
+/// could not be drawn from the public coin, or if the proof-of-work nonce is not valid.
+///
+/// # Panics
+/// Panics if the extension degree of `E` does not match the field extension specified by the
+/// proof options.
+pub fn derive_challenges<AIR, E, HashFn, RandCoin>(
+    proof: &StarkProof,
+    pub_inputs: AIR::PublicInputs,
+) -> Result<VerifierChallenges<E>, VerifierError>
+where
+    AIR: Air,
+    E: FieldElement<BaseField = AIR::BaseField>,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    assert_eq!(
+        E::EXTENSION_DEGREE,
+        proof.options().field_extension().degree() as usize,
+        "extension degree of the challenge field does not match proof field extension"
+    );
+
+    // initialize the public coin in the same way as the verifier does
+    let mut public_coin_seed = proof.context.to_elements();
+    public_coin_seed.append(&mut pub_inputs.to_elements());
+    let mut public_coin = build_public_coin::<HashFn, RandCoin>(&public_coin_seed, &proof.metadata);
+
+    validate_lde_domain::<AIR::BaseField>(proof)?;
+    let air = AIR::new(proof.get_trace_info(), pub_inputs, proof.options().clone());
+    let channel = VerifierChannel::<E, HashFn>::new(&air, proof.clone())?;
+    draw_challenges::<AIR, E, HashFn, HashFn, RandCoin>(&air, &channel, &mut public_coin)
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// using the specified random challenges instead of deriving them from the transcript of the
+/// proof.
+///
+/// This is intended for settings in which the challenges are computed externally; e.g., when a
+/// proof is verified by several parties which generate the challenges collaboratively (via an
+/// MPC protocol). The proof is checked in the same way as in [verify()], except that:
+/// * The public coin is not used; all challenges are taken from `challenges`. Query positions are
+///   sorted and deduplicated before use.
+/// * Since query positions are not derived from the public coin, the proof-of-work nonce
+///   contained in the proof is not checked.
+/// * Metadata attached to the proof is not checked, as it only affects the derivation of the
+///   challenges.
+///
+/// Soundness of the verification thus relies on the challenges being generated correctly; if the
+/// challenges are derived from the proof via [derive_challenges()], the result is the same as the
+/// result of [verify_with_metadata()] for the metadata attached to the proof.
+///
+/// Type parameter `E` must be the field in which the protocol was instantiated; i.e., the base
+/// field for proofs generated without a field extension, and the corresponding extension of the
+/// base field otherwise.
+///
+/// # Errors
+/// Returns an error if the challenges do not have the shape expected for the computation (see
+/// [VerifierChallenges::validate()]), or if the proof does not attest to a correct execution of
+/// the computation for the specified challenges.
+///
+/// # Panics
+/// Panics if the extension degree of `E` does not match the field extension specified by the
+/// proof options.
+pub fn verify_with_challenges<AIR, E, HashFn>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    mut challenges: VerifierChallenges<E>,
+    acceptable_options: &AcceptableOptions,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    E: FieldElement<BaseField = AIR::BaseField>,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+{
+    assert_eq!(
+        E::EXTENSION_DEGREE,
+        proof.options().field_extension().degree() as usize,
+        "extension degree of the challenge field does not match proof field extension"
+    );
+    acceptable_options.validate::<HashFn>(&proof)?;
+    validate_lde_domain::<AIR::BaseField>(&proof)?;
+
+    let air = AIR::new(proof.get_trace_info(), pub_inputs, proof.options().clone());
+    challenges.query_positions.sort_unstable();
+    challenges.query_positions.dedup();
+    challenges.validate(&air)?;
+
+    let channel = VerifierChannel::<E, HashFn>::new(&air, proof)?;
+    verify_against_challenges(
+        air,
+        channel,
+        challenges,
+        &DefaultExecutor::default(),
+        QueryMode::Batch,
+        None,
+    )
+}
+
+/// Returns an error if the low-degree extension domain implied by the specified proof cannot be
+/// formed in the base field `B`, i.e., if log2 of its size exceeds two-adicity of `B`.
+fn validate_lde_domain<B: StarkField>(proof: &StarkProof) -> Result<(), VerifierError> {
+    let domain_log2 = proof.lde_domain_size().ilog2();
+    if domain_log2 > B::TWO_ADICITY {
+        return Err(VerifierError::DomainExceedsTwoAdicity(domain_log2, B::TWO_ADICITY));
+    }
+    Ok(())
+}
+
+/// Returns a public coin initialized with the specified seed elements; if `metadata` is not
+/// empty, the coin is also reseeded with a hash of the metadata.
+///
+/// This must mirror how the prover channel initializes its public coin.
+fn build_public_coin<H, R>(seed: &[H::BaseField], metadata: &[u8]) -> R
+where
+    H: ElementHasher,
+    R: RandomCoin<BaseField = H::BaseField, Hasher = H>,
+{
+    let mut public_coin = R::new(seed);
+    if !metadata.is_empty() {
+        public_coin.reseed(H::hash(metadata));
+    }
+    public_coin
+}
+
+/// Returns a verifier channel for the specified proof; if `trusted_trace_commitment` is provided,
+/// it replaces the commitment to the main trace segment read from the proof. For streamed
+/// `queries`, the proof must not contain any trace or constraint queries.
+fn build_channel<A, E, H, HF>(
+    air: &A,
+    proof: StarkProof,
+    trusted_trace_commitment: Option<&H::Digest>,
+    queries: &QueryMode,
+) -> Result<VerifierChannel<E, H, HF>, VerifierError>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+    H: ElementHasher<BaseField = A::BaseField>,
+    HF: ElementHasher<BaseField = A::BaseField>,
+{
+    let mut channel = match queries {
+        QueryMode::Streamed(_) => VerifierChannel::new_streamed(air, proof)?,
+        _ => VerifierChannel::new(air, proof)?,
+    };
+    if let Some(&commitment) = trusted_trace_commitment {
+        channel.set_main_trace_commitment(commitment);
+    }
+    Ok(channel)
+}
+
+// VERIFICATION PROCEDURE
+// ================================================================================================
+/// Performs the actual verification by reading the data from the `channel` and making sure it
+/// attests to a correct execution of the computation specified by the provided `air`.
+fn perform_verification<A, E, H, HF, R, X>(
+    air: A,
+    channel: VerifierChannel<E, H, HF>,
+    mut public_coin: R,
+    executor: &X,
+    queries: QueryMode,
+) -> Result<(), VerifierError>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+    H: ElementHasher<BaseField = A::BaseField>,
+    HF: ElementHasher<BaseField = A::BaseField>,
+    R: RandomCoin<BaseField = A::BaseField, Hasher = H>,
+    X: Executor,
+{
+    // replay the transcript of the proof to draw all random challenges from the public coin, and
+    // then check the proof against these challenges
+    let challenges = draw_challenges::<A, E, H, HF, R>(&air, &channel, &mut public_coin)?;
+    verify_against_challenges(air, channel, challenges, executor, queries, None)
+}
+
+/// Makes sure the data read from the `channel` attests to a correct execution of the computation
+/// specified by the provided `air`, given the random challenges of the protocol.
+///
+/// The challenges must be well-formed for the specified `air` (see
+/// [VerifierChallenges::validate()]). Independent checks are executed via the specified
+/// `executor`, and queries are read and checked as specified by `queries`. If a `recorder` is
+/// provided, intermediate values are passed to it as they are computed.
+fn verify_against_challenges<A, E, H, HF, X>(
+    air: A,
+    mut channel: VerifierChannel<E, H, HF>,
+    challenges: VerifierChallenges<E>,
+    executor: &X,
+    queries: QueryMode,
+    mut recorder: Option<&mut dyn VerificationRecorder<A, E>>,
+) -> Result<(), VerifierError>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+    H: ElementHasher<BaseField = A::BaseField>,
+    HF: ElementHasher<BaseField = A::BaseField>,
+    X: Executor,
+{
+    let VerifierChallenges {
+        aux_rand_elements,
+        constraint_coefficients,
+        ood_point: z,
+        deep_coefficients,
+        fri_alphas,
+        query_positions,
+    } = challenges;
+
+    // 1 ----- OOD consistency check --------------------------------------------------------------
+    // make sure that evaluations obtained by evaluating constraints over the out-of-domain frame
+    // are consistent with the evaluations of composition polynomial columns sent by the prover
+
+    // read the out-of-domain trace frames (the main trace frame and auxiliary trace frame, if
+    // provided) sent by the prover and evaluate constraints over them
+    let ood_trace_frame = channel.read_ood_trace_frame();
+    let ood_constraint_evaluation_1 = evaluate_ood_constraints(
+        &air,
+        &ood_trace_frame,
+        constraint_coefficients,
+        aux_rand_elements,
+        z,
+    );
+
+    // read evaluations of composition polynomial columns sent by the prover, and reduce them into
+    // a single value H(z); for thin OOD frames, the evaluation of the last column is recovered
+    // from H(z) computed above, and thus, the check below holds by construction; the recovered
+    // value is still checked against the queried constraint evaluations during DEEP composition
+    let ood_constraint_evaluations = recover_ood_constraint_evaluations(
+        &air,
+        channel.read_ood_constraint_evaluations(),
+        ood_constraint_evaluation_1,
+        z,
+    );
+    let ood_constraint_evaluation_2 =
+        reduce_ood_constraint_evaluations(&air, &ood_constraint_evaluations, z);
+    if let Some(recorder) = recorder.as_deref_mut() {
+        recorder.record_ood_evaluations(
+            &air,
+            &ood_trace_frame,
+            &ood_constraint_evaluations,
+            ood_constraint_evaluation_1,
+            ood_constraint_evaluation_2,
+        );
+    }
+
+    // finally, make sure the values are the same
+    if ood_constraint_evaluation_1 != ood_constraint_evaluation_2 {
+        return Err(VerifierError::InconsistentOodConstraintEvaluations);
+    }
+
+    // 2 ----- FRI commitments --------------------------------------------------------------------
+    // instantiates a FRI verifier with the FRI layer commitments read from the channel and the
+    // alphas drawn for these commitments. From the verifier's perspective, this is equivalent to
+    // executing the commit phase of the FRI protocol.
+    let fri_verifier = FriVerifier::<E, _, HF, DefaultRandomCoin<HF>>::with_alphas(
+        &mut channel,
+        fri_alphas,
+        air.options().to_fri_options(),
+        air.trace_poly_degree(),
+    )
+    .map_err(VerifierError::FriVerificationFailed)?;
+    // TODO: make sure air.lde_domain_size() == fri_verifier.domain_size()
+
+    // 3 ----- trace and constraint queries -------------------------------------------------------
+    // read evaluations of trace and constraint composition polynomials at the queried positions;
+    // this also checks that the read values are valid against trace and constraint commitments.
+    // if only a subset of queries is to be checked, keep only the authenticated states at the
+    // sampled positions
+    let (
+        checked_positions,
+        queried_main_trace_states,
+        queried_aux_trace_states,
+        queried_constraint_evaluations,
+    ) = match queries {
+        QueryMode::Batch => {
+            let (main_states, aux_states, constraint_evaluations) =
+                channel.read_queried_states(&query_positions, executor)?;
+            (query_positions.clone(), main_states, aux_states, constraint_evaluations)
+        }
+        QueryMode::Sampled(mut sampling) => {
+            let (main_states, aux_states, constraint_evaluations) =
+                channel.read_queried_states(&query_positions, executor)?;
+            let indexes = sampling.sample(query_positions.len());
+            (
+                indexes.iter().map(|&i| query_positions[i]).collect(),
+                select_rows(&main_states, &indexes),
+                aux_states.map(|states| select_rows(&states, &indexes)),
+                select_rows(&constraint_evaluations, &indexes),
+            )
+        }
+        QueryMode::Streamed(responses) => {
+            let (main_states, aux_states, constraint_evaluations) =
+                read_query_responses(&air, &channel, &query_positions, responses, executor)?;
+            (query_positions.clone(), main_states, aux_states, constraint_evaluations)
+        }
+    };
+
+    // 4 ----- DEEP composition -------------------------------------------------------------------
+    // compute evaluations of the DEEP composition polynomial at the checked positions
+    let composer = DeepComposer::new(&air, &checked_positions, z, deep_coefficients);
+    let t_composition = composer.compose_trace_columns(
+        queried_main_trace_states,
+        queried_aux_trace_states,
+        ood_trace_frame.rows(),
+    );
+    let c_composition = composer
+        .compose_constraint_evaluations(queried_constraint_evaluations, ood_constraint_evaluations);
+    let deep_evaluations = composer.combine_compositions(t_composition, c_composition);
+    if let Some(recorder) = recorder {
+        recorder.record_deep_evaluations(&deep_evaluations);
+    }
+
+    // 5 ----- Verify low-degree proof -------------------------------------------------------------
+    // make sure that evaluations of the DEEP composition polynomial we computed in the previous
+    // step are in fact evaluations of a polynomial of degree equal to trace polynomial degree
+    fri_verifier
+        .verify_sampled(&mut channel, &deep_evaluations, &query_positions, &checked_positions)
+        .map_err(VerifierError::FriVerificationFailed)
+}
 
-mod evaluator;
-use evaluator::evaluate_constraints;
+/// Receives intermediate values computed by [verify_against_challenges()]; this is used to record
+/// a transcript of the verification.
+trait VerificationRecorder<A: Air, E: FieldElement<BaseField = A::BaseField>> {
+    /// Records the out-of-domain trace frame and the evaluations of composition polynomial columns
+    /// at z sent by the prover, together with the values of the composition polynomial at z
+    /// computed from the trace frame and from the column evaluations.
+    fn record_ood_evaluations(
+        &mut self,
+        air: &A,
+        ood_trace_frame: &TraceOodFrame<E>,
+        ood_constraint_evaluations: &[E],
+        ood_composition_from_trace: E,
+        ood_composition_from_columns: E,
+    );
 
-mod composer;
-use composer::DeepComposer;
+    /// Records evaluations of the DEEP composition polynomial at the checked query positions.
+    fn record_deep_evaluations(&mut self, deep_evaluations: &[E]);
+}
 
-mod errors;
-pub use errors::VerifierError;
+/// Evaluates constraints of the specified `air` over the out-of-domain trace frame sent by the
+/// prover, and merges the results into a single value using the specified composition
+/// coefficients. This computes H(z) from the trace polynomial evaluations at z.
+fn evaluate_ood_constraints<A, E>(
+    air: &A,
+    ood_trace_frame: &TraceOodFrame<E>,
+    constraint_coefficients: ConstraintCompositionCoefficients<E>,
+    aux_rand_elements: AuxTraceRandElements<E>,
+    z: E,
+) -> E
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+{
+    let ood_main_trace_frame = ood_trace_frame.main_frame();
+    let ood_aux_trace_frame = ood_trace_frame.aux_frame();
+    let periodic_values = air.get_periodic_column_values_at(z);
 
-// VERIFIER
-// ================================================================================================
-/// Verifies that the specified computation was executed correctly against the specified inputs.
+    evaluate_constraints(
+        air,
+        constraint_coefficients,
+        &ood_main_trace_frame,
+        &ood_aux_trace_frame,
+        aux_rand_elements,
+        &periodic_values,
+        z,
+    )
+}
+
+/// Computes the contribution of each constraint to the value of the constraint composition
+/// polynomial at z computed by [evaluate_ood_constraints()].
+#[cfg(feature = "debug")]
+fn evaluate_ood_constraint_contributions<A, E>(
+    air: &A,
+    ood_trace_frame: &TraceOodFrame<E>,
+    constraint_coefficients: ConstraintCompositionCoefficients<E>,
+    aux_rand_elements: AuxTraceRandElements<E>,
+    z: E,
+) -> ConstraintContributions<E>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+{
+    let ood_main_trace_frame = ood_trace_frame.main_frame();
+    let ood_aux_trace_frame = ood_trace_frame.aux_frame();
+    let periodic_values = air.get_periodic_column_values_at(z);
+
+    evaluate_constraint_contributions(
+        air,
+        constraint_coefficients,
+        &ood_main_trace_frame,
+        &ood_aux_trace_frame,
+        aux_rand_elements,
+        &periodic_values,
+        z,
+    )
+}
+
+/// Reduces evaluations of composition polynomial columns at z into a single value by computing
+/// \sum_{i=0}^{m-1}(z^(i * l) * value_i), where value_i is the evaluation of the ith column
+/// polynomial H_i(X) at z, l is the trace length and m is the number of composition column
+/// polynomials. This computes H(z) (i.e. the evaluation of the composition polynomial at z) using
+/// the fact that H(X) = \sum_{i=0}^{m-1} X^{i * l} H_i(X).
+fn reduce_ood_constraint_evaluations<A, E>(air: &A, ood_constraint_evaluations: &[E], z: E) -> E
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+{
+    ood_constraint_evaluations
+        .iter()
+        .enumerate()
+        .fold(E::ZERO, |result, (i, &value)| {
+            result + z.exp_vartime(((i * air.trace_length()) as u64).into()) * value
+        })
+}
+
+/// Appends the evaluation of the last composition column polynomial at z to the evaluations of
+/// the other columns if the out-of-domain frame is thin; otherwise, the evaluations are returned
+/// unchanged.
 ///
-/// Specifically, for a computation specified by `AIR` and `HashFn` type parameter, verifies that 
-/// the provided `proof` attests to the correct execution of the computation against public inputs
-/// specified by `pub_inputs`. If the verification is successful, `Ok(())` is returned.
+/// The last evaluation is recovered from H(z) (computed by evaluating constraints over the
+/// out-of-domain trace frame) as (H(z) - \sum_{i=0}^{m-2}(z^(i * l) * value_i)) / z^((m-1) * l).
+fn recover_ood_constraint_evaluations<A, E>(
+    air: &A,
+    mut ood_constraint_evaluations: Vec<E>,
+    ood_constraint_evaluation: E,
+    z: E,
+) -> Vec<E>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+{
+    if !air.options().has_thin_ood_frame() {
+        return ood_constraint_evaluations;
+    }
+
+    let partial_evaluation = reduce_ood_constraint_evaluations(air, &ood_constraint_evaluations, z);
+    let last_column_offset = ood_constraint_evaluations.len() * air.trace_length();
+    let z_offset = z.exp_vartime((last_column_offset as u64).into());
+    ood_constraint_evaluations.push((ood_constraint_evaluation - partial_evaluation) / z_offset);
+    ood_constraint_evaluations
+}
+
+// QUERY MODES
+// ================================================================================================
+
+/// Specifies how trace and constraint queries are read and checked by the verifier.
+enum QueryMode<'a> {
+    /// All queries are read from the proof and checked.
+    Batch,
+    /// All queries are read from the proof and authenticated against the commitments, but DEEP
+    /// composition and FRI folding are checked only for a random subset of the queries.
+    Sampled(QuerySampling<'a>),
+    /// Queries are read from a stream of query responses, one response per query position; each
+    /// response is authenticated against the commitments as soon as it is received.
+    Streamed(&'a mut dyn Iterator<Item = QueryResponse>),
+}
+
+/// Reads a response for each of the specified query positions from `responses`, and returns
+/// authenticated trace states and constraint evaluations at these positions.
 ///
-/// # Errors
-/// Returns an error if combination of the provided proof and public inputs does not attest to
-/// a correct execution of the computation. This could happen for many various reasons, including:
-/// - The specified proof was generated for a different computation.
-/// - The specified proof was generated for this computation but for different public inputs.
-/// - The specified proof was generated with parameters not providing an acceptable security level.
-#[rustfmt::skip]
-pub fn verify<AIR, HashFn, RandCoin>(
-    proof: StarkProof,
-    pub_inputs: AIR::PublicInputs,
-    acceptable_options: &AcceptableOptions,
-) -> Result<(), VerifierError> 
-where 
-    AIR: Air, 
-    HashFn: ElementHasher<BaseField = AIR::BaseField>,
-    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+/// Each response is checked against the trace and constraint commitments before the next one is
+/// read; thus, reading stops at the first invalid response.
+fn read_query_responses<A, E, H, HF, X>(
+    air: &A,
+    channel: &VerifierChannel<E, H, HF>,
+    positions: &[usize],
+    responses: &mut dyn Iterator<Item = QueryResponse>,
+    executor: &X,
+) -> Result<QueriedStates<E>, VerifierError>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+    H: ElementHasher<BaseField = A::BaseField>,
+    HF: ElementHasher<BaseField = A::BaseField>,
+    X: Executor,
 {
-    // check that `proof` was generated with an acceptable set of parameters from the point of view
-    // of the verifier
-    acceptable_options.validate::<HashFn>(&proof)?;
+    let mut main_states = Vec::with_capacity(positions.len());
+    let mut aux_states = Vec::with_capacity(positions.len());
+    let mut constraint_evaluations = Vec::with_capacity(positions.len());
+    for (i, &position) in positions.iter().enumerate() {
+        let response = responses
+            .next()
+            .ok_or(VerifierError::InconsistentNumQueries(positions.len(), i))?;
+        let (main_state, aux_state, evaluations) =
+            channel.read_query_response(air, response, position, executor)?;
+        main_states.push(main_state);
+        aux_states.extend(aux_state);
+        constraint_evaluations.push(evaluations);
+    }
 
-    // build a seed for the public coin; the initial seed is a hash of the proof context and the
-    // public inputs, but as the protocol progresses, the coin will be reseeded with the info
-    // received from the prover
-    let mut public_coin_seed = proof.context.to_elements();
-    public_coin_seed.append(&mut pub_inputs.to_elements());
-    
-    // create AIR instance for the computation specified in the proof
-    let air = AIR::new(proof.get_trace_info(), pub_inputs, proof.options().clone());
+    // make sure there are no responses for positions which were not queried
+    if responses.next().is_some() {
+        return Err(VerifierError::InconsistentNumQueries(positions.len(), positions.len() + 1));
+    }
 
-    // figure out which version of the generic proof verification procedure to run. this is a sort
-    // of static dispatch for selecting two generic parameter: extension field and hash function.
-    match air.options().field_extension() {
-        FieldExtension::None => {
-            let public_coin = RandCoin::new(&public_coin_seed);
-            let channel = VerifierChannel::new(&air, proof)?;
-            perform_verification::<AIR, AIR::BaseField, HashFn, RandCoin>(air, channel, public_coin)
-        },
-        FieldExtension::Quadratic => {
-            if !<QuadExtension<AIR::BaseField>>::is_supported() {
-                return Err(VerifierError::UnsupportedFieldExtension(2));
-            }
-            let public_coin = RandCoin::new(&public_coin_seed);
-            let channel = VerifierChannel::new(&air, proof)?;
-            perform_verification::<AIR, QuadExtension<AIR::BaseField>, HashFn, RandCoin>(air, channel, public_coin)
-        },
-        FieldExtension::Cubic => {
-            if !<CubeExtension<AIR::BaseField>>::is_supported() {
-                return Err(VerifierError::UnsupportedFieldExtension(3));
-            }
-            let public_coin = RandCoin::new(&public_coin_seed);
-            let channel = VerifierChannel::new(&air, proof)?;
-            perform_verification::<AIR, CubeExtension<AIR::BaseField>, HashFn, RandCoin>(air, channel, public_coin)
-        },
+    let aux_states = if aux_states.is_empty() {
+        None
+    } else {
+        Some(merge_rows(aux_states))
+    };
+    Ok((merge_rows(main_states), aux_states, merge_rows(constraint_evaluations)))
+}
+
+/// Returns a table consisting of the rows of all specified tables.
+fn merge_rows<E: FieldElement>(tables: Vec<Table<E>>) -> Table<E> {
+    let num_columns = tables[0].num_columns();
+    let mut elements = Vec::with_capacity(tables.len() * num_columns);
+    for table in tables.iter() {
+        for row in table.rows() {
+            elements.extend_from_slice(row);
+        }
     }
+    Table::from_elements(elements, num_columns)
 }
 
-// VERIFICATION PROCEDURE
+// QUERY SAMPLING
 // ================================================================================================
-/// Performs the actual verification by reading the data from the `channel` and making sure it
-/// attests to a correct execution of the computation specified by the provided `air`.
-fn perform_verification<A, E, H, R>(
+
+/// Specifies the fraction of queries which is checked during sampled verification, together with
+/// the source of randomness used to select the checked queries.
+struct QuerySampling<'a> {
+    fraction: f64,
+    rng: &'a mut dyn RngCore,
+}
+
+impl QuerySampling<'_> {
+    /// Returns indexes of the queries to check out of `num_queries` queries, sorted in increasing
+    /// order.
+    ///
+    /// The number of checked queries is `fraction * num_queries` rounded up, and the queries are
+    /// selected uniformly at random without replacement.
+    fn sample(&mut self, num_queries: usize) -> Vec<usize> {
+        let target = self.fraction * num_queries as f64;
+        let mut num_sampled = target as usize;
+        if (num_sampled as f64) < target {
+            num_sampled += 1;
+        }
+        let num_sampled = num_sampled.clamp(1, num_queries);
+
+        // partial Fisher-Yates shuffle
+        let mut indexes = (0..num_queries).collect::<Vec<_>>();
+        for i in 0..num_sampled {
+            let j = i + (self.rng.next_u64() % (num_queries - i) as u64) as usize;
+            indexes.swap(i, j);
+        }
+        indexes.truncate(num_sampled);
+        indexes.sort_unstable();
+        indexes
+    }
+}
+
+/// Returns a table consisting of the rows of `table` at the specified indexes.
+fn select_rows<E: FieldElement>(table: &Table<E>, indexes: &[usize]) -> Table<E> {
+    let mut elements = Vec::with_capacity(indexes.len() * table.num_columns());
+    for &index in indexes {
+        elements.extend_from_slice(table.get_row(index));
+    }
+    Table::from_elements(elements, table.num_columns())
+}
+
+/// Replays the transcript of the specified `proof` in the same way as [perform_verification()]
+/// does, but skips all checks other than the proof-of-work check and the check that the number of
+/// drawn query positions matches the number of unique queries declared by the proof; then makes
+/// sure that trace and constraint queries contained in the proof are well-formed.
+fn check_transcript<A, E, H, HF, R>(
     air: A,
-    mut channel: VerifierChannel<E, H>,
+    proof: StarkProof,
     mut public_coin: R,
 ) -> Result<(), VerifierError>
 where
     A: Air,
     E: FieldElement<BaseField = A::BaseField>,
     H: ElementHasher<BaseField = A::BaseField>,
+    HF: ElementHasher<BaseField = A::BaseField>,
+    R: RandomCoin<BaseField = A::BaseField, Hasher = H>,
+{
+    // constraints are not evaluated over the out-of-domain frame; the frame and the constraint
+    // evaluations sent by the prover are only absorbed into the public coin
+    let num_unique_queries = proof.num_unique_queries as usize;
+    let channel = VerifierChannel::<E, H, HF>::new_transcript_only(&air, proof.clone())?;
+    let challenges = draw_challenges::<A, E, H, HF, R>(&air, &channel, &mut public_coin)?;
+
+    // make sure the proof declares the number of unique query positions drawn from the coin
+    if challenges.query_positions.len() != num_unique_queries {
+        return Err(VerifierError::InconsistentNumQueries(
+            challenges.query_positions.len(),
+            num_unique_queries,
+        ));
+    }
+
+    // make sure the proof contains queries for all of these positions
+    VerifierChannel::<E, H, HF>::new(&air, proof)?;
+    Ok(())
+}
+
+/// Replays the transcript of the proof read from the `channel` by using the data sent by the
+/// prover to update the `public_coin`, and returns all random challenges drawn from the coin.
+///
+/// This also makes sure that the proof-of-work specified by the grinding factor is satisfied.
+fn draw_challenges<A, E, H, HF, R>(
+    air: &A,
+    channel: &VerifierChannel<E, H, HF>,
+    public_coin: &mut R,
+) -> Result<VerifierChallenges<E>, VerifierError>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+    H: ElementHasher<BaseField = A::BaseField>,
+    HF: ElementHasher<BaseField = A::BaseField>,
+    R: RandomCoin<BaseField = A::BaseField, Hasher = H>,
+{
+    // 1 ----- trace and constraint commitments -------------------------------------------------
+    // replay the commit phase of the protocol to draw random elements for auxiliary trace
+    // segments, constraint composition coefficients, and the out-of-domain point z
+    let (aux_rand_elements, constraint_coefficients, ood_point) =
+        draw_ood_point::<A, E, H, HF, R>(air, channel, public_coin)?;
+
+    // 2 ----- OOD evaluations --------------------------------------------------------------------
+    // reseed the public coin with the OOD trace frame and the OOD constraint evaluations sent by
+    // the prover; for thin OOD frames, the omitted evaluation is recovered by evaluating
+    // constraints over the OOD trace frame first
+    public_coin.reseed(H::hash_elements(channel.ood_trace_frame().values()));
+    if air.options().has_thin_ood_frame() {
+        let ood_constraint_evaluation = evaluate_ood_constraints(
+            air,
+            channel.ood_trace_frame(),
+            constraint_coefficients.clone(),
+            aux_rand_elements.clone(),
+            ood_point,
+        );
+        let ood_constraint_evaluations = recover_ood_constraint_evaluations(
+            air,
+            channel.ood_constraint_evaluations().to_vec(),
+            ood_constraint_evaluation,
+            ood_point,
+        );
+        public_coin.reseed(H::hash_elements(&ood_constraint_evaluations));
+    } else {
+        public_coin.reseed(H::hash_elements(channel.ood_constraint_evaluations()));
+    }
+
+    // 3 ----- FRI commitments --------------------------------------------------------------------
+    // draw coefficients for computing DEEP composition polynomial from the public coin; in the
+    // interactive version of the protocol, the verifier sends these coefficients to the prover
+    // and the prover uses them to compute the DEEP composition polynomial. the prover, then
+    // applies FRI protocol to the evaluations of the DEEP composition polynomial.
+    let deep_coefficients = air
+        .get_deep_composition_coefficients::<E, R>(public_coin)
+        .map_err(|_| VerifierError::RandomCoinError)?;
+
+    // use FRI layer commitments to update the public coin and draw random points alpha from it;
+    // in the interactive version of the protocol, the verifier sends these alphas to the prover,
+    // and the prover uses them to compute and commit to the subsequent FRI layers.
+    let mut fri_alphas = Vec::with_capacity(channel.fri_layer_seeds().len());
+    for &seed in channel.fri_layer_seeds() {
+        public_coin.reseed(seed);
+        fri_alphas.push(public_coin.draw().map_err(|_| VerifierError::RandomCoinError)?);
+    }
+
+    // 4 ----- query positions --------------------------------------------------------------------
+    // read proof-of-work nonce sent by the prover
+    let pow_nonce = channel.read_pow_nonce();
+
+    // make sure the proof-of-work specified by the grinding factor is satisfied
+    if public_coin.check_leading_zeros(pow_nonce) < air.options().grinding_factor() {
+        return Err(VerifierError::QuerySeedProofOfWorkVerificationFailed);
+    }
+
+    // draw pseudo-random query positions for the LDE domain from the public coin using the
+    // sampling method specified by the proof options; in the interactive version of the protocol,
+    // the verifier sends these query positions to the prover, and the prover responds with
+    // decommitments against these positions for trace and constraint composition polynomial
+    // evaluations.
+    let mut query_positions = public_coin
+        .draw_integers_with(
+            air.options().num_queries(),
+            air.lde_domain_size(),
+            pow_nonce,
+            air.options().query_sampling(),
+        )
+        .map_err(|_| VerifierError::RandomCoinError)?;
+
+    // remove any potential duplicates from the positions as the prover will send openings only
+    // for unique queries
+    query_positions.sort_unstable();
+    query_positions.dedup();
+
+    Ok(VerifierChallenges {
+        aux_rand_elements,
+        constraint_coefficients,
+        ood_point,
+        deep_coefficients,
+        fri_alphas,
+        query_positions,
+    })
+}
+
+/// Replays the commit phase of the protocol (up to and including the constraint commitment) by
+/// reading commitments from the `channel` and using them to update the `public_coin`.
+///
+/// Returns random elements for auxiliary trace segments, constraint composition coefficients,
+/// and the out-of-domain point z drawn from the public coin.
+#[allow(clippy::type_complexity)]
+fn draw_ood_point<A, E, H, HF, R>(
+    air: &A,
+    channel: &VerifierChannel<E, H, HF>,
+    public_coin: &mut R,
+) -> Result<(AuxTraceRandElements<E>, ConstraintCompositionCoefficients<E>, E), VerifierError>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+    H: ElementHasher<BaseField = A::BaseField>,
+    HF: ElementHasher<BaseField = A::BaseField>,
     R: RandomCoin<BaseField = A::BaseField, Hasher = H>,
 {
-    // 1 ----- trace commitment -------------------------------------------------------------------
     // Read the commitments to evaluations of the trace polynomials over the LDE domain sent by the
     // prover. The commitments are used to update the public coin, and draw sets of random elements
     // from the coin (in the interactive version of the protocol the verifier sends these random
@@ -165,7 +1679,7 @@ where
     let mut aux_trace_rand_elements = AuxTraceRandElements::<E>::new();
     for (i, commitment) in trace_commitments.iter().skip(1).enumerate() {
         let rand_elements = air
-            .get_aux_trace_segment_random_elements(i, &mut public_coin)
+            .get_aux_trace_segment_random_elements(i, public_coin)
             .map_err(|_| VerifierError::RandomCoinError)?;
         aux_trace_rand_elements.add_segment_elements(rand_elements);
         public_coin.reseed(*commitment);
@@ -173,10 +1687,9 @@ where
 
     // build random coefficients for the composition polynomial
     let constraint_coeffs = air
-        .get_constraint_composition_coefficients(&mut public_coin)
+        .get_constraint_composition_coefficients(public_coin)
         .map_err(|_| VerifierError::RandomCoinError)?;
 
-    // 2 ----- constraint commitment --------------------------------------------------------------
     // read the commitment to evaluations of the constraint composition polynomial over the LDE
     // domain sent by the prover, use it to update the public coin, and draw an out-of-domain point
     // z from the coin; in the interactive version of the protocol, the verifier sends this point z
@@ -186,118 +1699,7 @@ where
     public_coin.reseed(constraint_commitment);
     let z = public_coin.draw::<E>().map_err(|_| VerifierError::RandomCoinError)?;
 
-    // 3 ----- OOD consistency check --------------------------------------------------------------
-    // make sure that evaluations obtained by evaluating constraints over the out-of-domain frame
-    // are consistent with the evaluations of composition polynomial columns sent by the prover
-
-    // read the out-of-domain trace frames (the main trace frame and auxiliary trace frame, if
-    // provided) sent by the prover and evaluate constraints over them; also, reseed the public
-    // coin with the OOD frames received from the prover.
-    let ood_trace_frame = channel.read_ood_trace_frame();
-    let ood_main_trace_frame = ood_trace_frame.main_frame();
-    let ood_aux_trace_frame = ood_trace_frame.aux_frame();
-    let ood_constraint_evaluation_1 = evaluate_constraints(
-        &air,
-        constraint_coeffs,
-        &ood_main_trace_frame,
-        &ood_aux_trace_frame,
-        aux_trace_rand_elements,
-        z,
-    );
-    public_coin.reseed(H::hash_elements(ood_trace_frame.values()));
-
-    // read evaluations of composition polynomial columns sent by the prover, and reduce them into
-    // a single value by computing \sum_{i=0}^{m-1}(z^(i * l) * value_i), where value_i is the
-    // evaluation of the ith column polynomial H_i(X) at z, l is the trace length and m is
-    // the number of composition column polynomials. This computes H(z) (i.e.
-    // the evaluation of the composition polynomial at z) using the fact that
-    // H(X) = \sum_{i=0}^{m-1} X^{i * l} H_i(X).
-    // Also, reseed the public coin with the OOD constraint evaluations received from the prover.
-    let ood_constraint_evaluations = channel.read_ood_constraint_evaluations();
-    let ood_constraint_evaluation_2 =
-        ood_constraint_evaluations
-            .iter()
-            .enumerate()
-            .fold(E::ZERO, |result, (i, &value)| {
-                result + z.exp_vartime(((i * (air.trace_length())) as u32).into()) * value
-            });
-    public_coin.reseed(H::hash_elements(&ood_constraint_evaluations));
-
-    // finally, make sure the values are the same
-    if ood_constraint_evaluation_1 != ood_constraint_evaluation_2 {
-        return Err(VerifierError::InconsistentOodConstraintEvaluations);
-    }
-
-    // 4 ----- FRI commitments --------------------------------------------------------------------
-    // draw coefficients for computing DEEP composition polynomial from the public coin; in the
-    // interactive version of the protocol, the verifier sends these coefficients to the prover
-    // and the prover uses them to compute the DEEP composition polynomial. the prover, then
-    // applies FRI protocol to the evaluations of the DEEP composition polynomial.
-    let deep_coefficients = air
-        .get_deep_composition_coefficients::<E, R>(&mut public_coin)
-        .map_err(|_| VerifierError::RandomCoinError)?;
-
-    // instantiates a FRI verifier with the FRI layer commitments read from the channel. From the
-    // verifier's perspective, this is equivalent to executing the commit phase of the FRI protocol.
-    // The verifier uses these commitments to update the public coin and draw random points alpha
-    // from them; in the interactive version of the protocol, the verifier sends these alphas to
-    // the prover, and the prover uses them to compute and commit to the subsequent FRI layers.
-    let fri_verifier = FriVerifier::new(
-        &mut channel,
-        &mut public_coin,
-        air.options().to_fri_options(),
-        air.trace_poly_degree(),
-    )
-    .map_err(VerifierError::FriVerificationFailed)?;
-    // TODO: make sure air.lde_domain_size() == fri_verifier.domain_size()
-
-    // 5 ----- trace and constraint queries -------------------------------------------------------
-    // read proof-of-work nonce sent by the prover
-    let pow_nonce = channel.read_pow_nonce();
-
-    // make sure the proof-of-work specified by the grinding factor is satisfied
-    if public_coin.check_leading_zeros(pow_nonce) < air.options().grinding_factor() {
-        return Err(VerifierError::QuerySeedProofOfWorkVerificationFailed);
-    }
-
-    // draw pseudo-random query positions for the LDE domain from the public coin; in the
-    // interactive version of the protocol, the verifier sends these query positions to the prover,
-    // and the prover responds with decommitments against these positions for trace and constraint
-    // composition polynomial evaluations.
-    let mut query_positions = public_coin
-        .draw_integers(air.options().num_queries(), air.lde_domain_size(), pow_nonce)
-        .map_err(|_| VerifierError::RandomCoinError)?;
-
-    // remove any potential duplicates from the positions as the prover will send openings only
-    // for unique queries
-    query_positions.sort_unstable();
-    query_positions.dedup();
-
-    // read evaluations of trace and constraint composition polynomials at the queried positions;
-    // this also checks that the read values are valid against trace and constraint commitments
-    let (queried_main_trace_states, queried_aux_trace_states) =
-        channel.read_queried_trace_states(&query_positions)?;
-    let queried_constraint_evaluations = channel.read_constraint_evaluations(&query_positions)?;
-
-    // 6 ----- DEEP composition -------------------------------------------------------------------
-    // compute evaluations of the DEEP composition polynomial at the queried positions
-    let composer = DeepComposer::new(&air, &query_positions, z, deep_coefficients);
-    let t_composition = composer.compose_trace_columns(
-        queried_main_trace_states,
-        queried_aux_trace_states,
-        ood_main_trace_frame,
-        ood_aux_trace_frame,
-    );
-    let c_composition = composer
-        .compose_constraint_evaluations(queried_constraint_evaluations, ood_constraint_evaluations);
-    let deep_evaluations = composer.combine_compositions(t_composition, c_composition);
-
-    // 7 ----- Verify low-degree proof -------------------------------------------------------------
-    // make sure that evaluations of the DEEP composition polynomial we computed in the previous
-    // step are in fact evaluations of a polynomial of degree equal to trace polynomial degree
-    fri_verifier
-        .verify(&mut channel, &deep_evaluations, &query_positions)
-        .map_err(VerifierError::FriVerificationFailed)
+    Ok((aux_trace_rand_elements, constraint_coeffs, z))
 }
 
 // ACCEPTABLE OPTIONS
@@ -344,3 +1746,73 @@ impl AcceptableOptions {
         Ok(())
     }
 }
+
+// VERIFICATION LIMITS
+// ================================================================================================
+/// Upper bounds on the parameters of proofs accepted by [verify_with_limits()].
+///
+/// Resources needed to verify a proof grow with the trace length, the number of FRI layers, and
+/// the number of queries claimed by the proof. Checking these parameters against fixed limits
+/// bounds memory and CPU time spent on verifying a malicious proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerificationLimits {
+    max_trace_length: usize,
+    max_fri_layers: usize,
+    max_num_queries: usize,
+}
+
+impl VerificationLimits {
+    /// Returns a new set of verification limits instantiated with the specified parameters.
+    pub fn new(max_trace_length: usize, max_fri_layers: usize, max_num_queries: usize) -> Self {
+        Self {
+            max_trace_length,
+            max_fri_layers,
+            max_num_queries,
+        }
+    }
+
+    /// Returns the maximum accepted length of the execution trace.
+    pub fn max_trace_length(&self) -> usize {
+        self.max_trace_length
+    }
+
+    /// Returns the maximum accepted number of FRI layers.
+    pub fn max_fri_layers(&self) -> usize {
+        self.max_fri_layers
+    }
+
+    /// Returns the maximum accepted number of queries.
+    pub fn max_num_queries(&self) -> usize {
+        self.max_num_queries
+    }
+
+    /// Checks that parameters of the specified proof do not exceed these limits.
+    ///
+    /// The number of FRI layers is taken to be the larger of the number of layers implied by the
+    /// proof options and the number of layers actually contained in the FRI proof.
+    pub fn validate(&self, proof: &StarkProof) -> Result<(), VerifierError> {
+        let trace_length = proof.trace_length();
+        if trace_length > self.max_trace_length {
+            return Err(VerifierError::TraceLengthLimitExceeded(
+                self.max_trace_length,
+                trace_length,
+            ));
+        }
+
+        let num_fri_layers = proof
+            .options()
+            .to_fri_options()
+            .num_fri_layers(proof.lde_domain_size())
+            .max(proof.fri_proof.num_layers());
+        if num_fri_layers > self.max_fri_layers {
+            return Err(VerifierError::FriLayerLimitExceeded(self.max_fri_layers, num_fri_layers));
+        }
+
+        let num_queries = proof.options().num_queries().max(proof.num_unique_queries as usize);
+        if num_queries > self.max_num_queries {
+            return Err(VerifierError::QueryLimitExceeded(self.max_num_queries, num_queries));
+        }
+
+        Ok(())
+    }
+}
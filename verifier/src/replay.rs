@@ -0,0 +1,209 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    build_public_coin, channel::TraceOodFrame, draw_challenges,
+    evaluate_ood_constraint_contributions, validate_lde_domain, verify_against_challenges,
+    ConstraintContributions, QueryMode, VerificationRecorder, VerifierChallenges, VerifierChannel,
+    VerifierError,
+};
+use air::{proof::StarkProof, Air};
+use crypto::{ElementHasher, RandomCoin};
+use math::{FieldElement, ToElements};
+use utils::{collections::Vec, parallel::DefaultExecutor};
+
+// VERIFICATION TRANSCRIPT
+// ================================================================================================
+/// A record of all steps performed by the verifier when verifying a proof, together with the
+/// intermediate values computed at each step.
+///
+/// A transcript depends only on the proof and the public inputs against which it is replayed;
+/// thus, replaying the same proof always yields the same transcript, and transcripts can be
+/// compared against each other (or against a snapshot of their debug representation) in
+/// regression tests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationTranscript<E: FieldElement> {
+    /// Random challenges drawn from the public coin, including the query positions at which the
+    /// proof is checked.
+    pub challenges: VerifierChallenges<E>,
+    /// Evaluations of trace polynomials at the out-of-domain points sent by the prover, with one
+    /// row per point; i.e., the k-th row contains evaluations at z * g^k.
+    pub ood_trace_rows: Vec<Vec<E>>,
+    /// Evaluations of composition polynomial columns at the out-of-domain point sent by the
+    /// prover; for thin out-of-domain frames, this includes the recovered evaluation of the last
+    /// column.
+    pub ood_constraint_evaluations: Vec<E>,
+    /// Value of the constraint composition polynomial at z computed by evaluating constraints
+    /// over the out-of-domain trace frame.
+    pub ood_composition_from_trace: E,
+    /// Value of the constraint composition polynomial at z computed from the composition column
+    /// evaluations sent by the prover.
+    pub ood_composition_from_columns: E,
+    /// Contributions of individual constraints to the value of the constraint composition
+    /// polynomial at z computed by evaluating constraints over the out-of-domain trace frame; when
+    /// the out-of-domain consistency check fails, these can be used to find the constraints which
+    /// are not satisfied by the out-of-domain frame.
+    pub ood_constraint_contributions: ConstraintContributions<E>,
+    /// Evaluations of the DEEP composition polynomial at the query positions; this is empty if
+    /// the verification failed before the DEEP composition polynomial was evaluated (e.g., if the
+    /// out-of-domain consistency check failed).
+    pub deep_evaluations: Vec<E>,
+    /// Result of the verification.
+    pub result: Result<(), VerifierError>,
+}
+
+// REPLAY
+// ================================================================================================
+/// Replays verification of the specified `proof` against the specified public inputs, and returns
+/// a transcript of all verification steps.
+///
+/// The proof is verified in the same way as in [verify()](crate::verify), except that proof
+/// options are not checked against a set of acceptable options. The outcome of the verification
+/// is recorded in [VerificationTranscript::result]; challenges, out-of-domain evaluations
+/// (including contributions of individual constraints), and DEEP composition evaluations are
+/// recorded as they are computed by the verifier. Values which the verifier did not compute
+/// because the verification failed at an earlier step are left empty.
+///
+/// Type parameter `E` must be the field in which the protocol was instantiated; i.e., the base
+/// field for proofs generated without a field extension, and the corresponding extension of the
+/// base field otherwise.
+///
+/// This function is available only when the `debug` feature is enabled.
+///
+/// # Errors
+/// Returns an error if the verification could not be replayed far enough to record the
+/// out-of-domain evaluations; i.e., if the proof could not be parsed for the specified `AIR`, if
+/// the random challenges could not be drawn, or if the verification failed before the
+/// out-of-domain consistency check.
+///
+/// # Panics
+/// Panics if the extension degree of `E` does not match the field extension specified by the
+/// proof options.
+pub fn replay_verification<AIR, E, HashFn, RandCoin>(
+    proof: &StarkProof,
+    pub_inputs: AIR::PublicInputs,
+) -> Result<VerificationTranscript<E>, VerifierError>
+where
+    AIR: Air,
+    E: FieldElement<BaseField = AIR::BaseField>,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    assert_eq!(
+        E::EXTENSION_DEGREE,
+        proof.options().field_extension().degree() as usize,
+        "extension degree of the transcript field does not match proof field extension"
+    );
+
+    // initialize the public coin in the same way as the verifier does
+    let mut public_coin_seed = proof.context.to_elements();
+    public_coin_seed.append(&mut pub_inputs.to_elements());
+    let mut public_coin = build_public_coin::<HashFn, RandCoin>(&public_coin_seed, &proof.metadata);
+
+    validate_lde_domain::<AIR::BaseField>(proof)?;
+    let air = AIR::new(proof.get_trace_info(), pub_inputs, proof.options().clone());
+    let channel = VerifierChannel::<E, HashFn>::new(&air, proof.clone())?;
+    let challenges =
+        draw_challenges::<AIR, E, HashFn, HashFn, RandCoin>(&air, &channel, &mut public_coin)?;
+
+    // verify the proof against the drawn challenges, recording intermediate values as they are
+    // computed by the verifier
+    let mut recorder = TranscriptRecorder::new(challenges.clone());
+    let result = verify_against_challenges(
+        air,
+        channel,
+        challenges,
+        &DefaultExecutor::default(),
+        QueryMode::Batch,
+        Some(&mut recorder),
+    );
+    recorder.into_transcript(result)
+}
+
+// TRANSCRIPT RECORDER
+// ================================================================================================
+
+/// Out-of-domain values recorded during verification.
+struct OodRecord<E: FieldElement> {
+    trace_rows: Vec<Vec<E>>,
+    constraint_evaluations: Vec<E>,
+    composition_from_trace: E,
+    composition_from_columns: E,
+    constraint_contributions: ConstraintContributions<E>,
+}
+
+/// Records intermediate values computed by the verifier into a [VerificationTranscript].
+struct TranscriptRecorder<E: FieldElement> {
+    challenges: VerifierChallenges<E>,
+    ood: Option<OodRecord<E>>,
+    deep_evaluations: Vec<E>,
+}
+
+impl<E: FieldElement> TranscriptRecorder<E> {
+    /// Returns a new recorder for verification against the specified challenges.
+    fn new(challenges: VerifierChallenges<E>) -> Self {
+        Self {
+            challenges,
+            ood: None,
+            deep_evaluations: Vec::new(),
+        }
+    }
+
+    /// Returns a transcript with the recorded values and the specified verification result, or
+    /// the verification error if the out-of-domain values were not recorded.
+    fn into_transcript(
+        self,
+        result: Result<(), VerifierError>,
+    ) -> Result<VerificationTranscript<E>, VerifierError> {
+        let ood = match self.ood {
+            Some(ood) => ood,
+            None => return Err(result.expect_err("verification succeeded without OOD values")),
+        };
+        Ok(VerificationTranscript {
+            challenges: self.challenges,
+            ood_trace_rows: ood.trace_rows,
+            ood_constraint_evaluations: ood.constraint_evaluations,
+            ood_composition_from_trace: ood.composition_from_trace,
+            ood_composition_from_columns: ood.composition_from_columns,
+            ood_constraint_contributions: ood.constraint_contributions,
+            deep_evaluations: self.deep_evaluations,
+            result,
+        })
+    }
+}
+
+impl<A, E> VerificationRecorder<A, E> for TranscriptRecorder<E>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+{
+    fn record_ood_evaluations(
+        &mut self,
+        air: &A,
+        ood_trace_frame: &TraceOodFrame<E>,
+        ood_constraint_evaluations: &[E],
+        ood_composition_from_trace: E,
+        ood_composition_from_columns: E,
+    ) {
+        let constraint_contributions = evaluate_ood_constraint_contributions(
+            air,
+            ood_trace_frame,
+            self.challenges.constraint_coefficients.clone(),
+            self.challenges.aux_rand_elements.clone(),
+            self.challenges.ood_point,
+        );
+        self.ood = Some(OodRecord {
+            trace_rows: ood_trace_frame.rows(),
+            constraint_evaluations: ood_constraint_evaluations.to_vec(),
+            composition_from_trace: ood_composition_from_trace,
+            composition_from_columns: ood_composition_from_columns,
+            constraint_contributions,
+        });
+    }
+
+    fn record_deep_evaluations(&mut self, deep_evaluations: &[E]) {
+        self.deep_evaluations = deep_evaluations.to_vec();
+    }
+}
@@ -258,7 +258,8 @@
 //!     crypto::{hashers::Blake3_256, DefaultRandomCoin},
 //!     math::{fields::f128::BaseElement, FieldElement, ToElements},
 //!     matrix::ColMatrix,
-//!     DefaultTraceLde, ProofOptions, Prover, StarkDomain, Trace, TracePolyTable, TraceTable,
+//!     DefaultTraceLde, ProofOptions, Prover, StarkDomain, Trace, TraceCommitmentOptions,
+//!     TracePolyTable, TraceTable,
 //! };
 //!
 //! # use winterfell::{
@@ -365,8 +366,9 @@
 //!         trace_info: &TraceInfo,
 //!         main_trace: &ColMatrix<Self::BaseField>,
 //!         domain: &StarkDomain<Self::BaseField>,
+//!         commitment_options: TraceCommitmentOptions,
 //!     ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
-//!         DefaultTraceLde::new(trace_info, main_trace, domain)
+//!         DefaultTraceLde::new(trace_info, main_trace, domain, commitment_options)
 //!     }    
 //!
 //!     fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
@@ -392,7 +394,9 @@
 //! #    crypto::{hashers::Blake3_256, DefaultRandomCoin},
 //! #    math::{fields::f128::BaseElement, FieldElement, ToElements},
 //! #    matrix::ColMatrix,
-//! #    Air, AirContext, Assertion, ByteWriter, DefaultConstraintEvaluator, DefaultTraceLde,
+//! #    Air, AirContext, Assertion, ByteWriter, DefaultConstraintEvaluator,
+//! #    TraceCommitmentOptions,
+//! #    DefaultTraceLde,
 //! #    EvaluationFrame, TraceInfo, TransitionConstraintDegree, TraceTable, FieldExtension,
 //! #    Prover, ProofOptions, StarkDomain, StarkProof, Trace, TracePolyTable,
 //! # };
@@ -504,8 +508,9 @@
 //! #        trace_info: &TraceInfo,
 //! #        main_trace: &ColMatrix<Self::BaseField>,
 //! #        domain: &StarkDomain<Self::BaseField>,
+//! #        commitment_options: TraceCommitmentOptions,
 //! #    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
-//! #        DefaultTraceLde::new(trace_info, main_trace, domain)
+//! #        DefaultTraceLde::new(trace_info, main_trace, domain, commitment_options)
 //! #    }
 //! #  
 //! #    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
@@ -581,12 +586,30 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use prover::{
-    crypto, iterators, math, matrix, Air, AirContext, Assertion, AuxTraceRandElements,
-    BoundaryConstraint, BoundaryConstraintGroup, ByteReader, ByteWriter, CompositionPolyTrace,
-    ConstraintCompositionCoefficients, ConstraintDivisor, ConstraintEvaluator,
-    DeepCompositionCoefficients, DefaultConstraintEvaluator, DefaultTraceLde, Deserializable,
-    DeserializationError, EvaluationFrame, FieldExtension, ProofOptions, Prover, ProverError,
-    Serializable, SliceReader, StarkDomain, StarkProof, Trace, TraceInfo, TraceLayout, TraceLde,
-    TracePolyTable, TraceTable, TraceTableFragment, TransitionConstraintDegree,
+    crypto, iterators, lde_coset_offset, lde_domain_position, math, matrix, proof, prove_dual, Air,
+    AirContext, Assertion, AssertionSummary, AuxTraceRandElements, BitPackedTraceTable,
+    BoundaryConstraint, BoundaryConstraintGroup, ByteReader, ByteWriter, ChecksumError,
+    CommitmentLayout, CompositionPolyTrace, ConstraintCompositionCoefficients, ConstraintDivisor,
+    ConstraintEvaluator, ConstraintExpr, DeepCompositionCoefficients, DeepCompositionEvaluations,
+    DefaultConstraintEvaluator, DefaultTraceLde, Deserializable, DeserializationError,
+    EvaluationDomain, EvaluationFrame, FieldExtension, HashAccumulator, HashFunction,
+    LdeDomainInfo, OpenedLeaves, PolynomialConstraint, PrecomputedDivisors, ProofOptions,
+    ProofOptionsError, Prover, ProverError, RationalConstraint, Serializable, SliceReader,
+    SparsePeriodicColumn, StarkDomain, StarkProof, SymbolicConstraints, SymbolicFrame, Trace,
+    TraceCheckReport, TraceCommitmentOptions, TraceInfo, TraceLayout, TraceLde, TracePolyTable,
+    TraceSegment, TraceSource, TraceTable, TraceTableFragment, TraceViolation,
+    TransitionConstraintDegree, TransitionConstraintGroup, TwiddleCache, VerificationKey,
 };
-pub use verifier::{verify, AcceptableOptions, VerifierError};
+#[cfg(feature = "std")]
+pub use prover::{prove_dry_run, prove_dry_run_with_costs, OperationCosts, ProofEstimate};
+pub use verifier::{
+    derive_challenges, derive_ood_point, verify, verify_any, verify_dual, verify_sampled,
+    verify_signed, verify_streamed, verify_transcript_only, verify_with_accumulator,
+    verify_with_anchored_roots, verify_with_challenges, verify_with_executor,
+    verify_with_external_commitment, verify_with_fri_hash_fn, verify_with_input_commitment,
+    verify_with_limits, verify_with_metadata, verify_with_trusted_commitment, verify_with_vk,
+    AcceptableOptions, DefaultExecutor, Executor, SequentialExecutor, VerificationLimits,
+    VerifierChallenges, VerifierError,
+};
+#[cfg(feature = "debug")]
+pub use verifier::{replay_verification, ConstraintContributions, VerificationTranscript};
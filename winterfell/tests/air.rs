@@ -0,0 +1,1390 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! End-to-end tests of the features through which an AIR describes a computation.
+
+use winterfell::{
+    crypto::{hashers::Rp64_256, DefaultRandomCoin, ElementHasher},
+    math::{
+        fields::f128::BaseElement, fields::f64, fields::QuadExtension, ExtensionOf, FieldElement,
+        StarkField, ToElements,
+    },
+    matrix::ColMatrix,
+    AcceptableOptions, Air, AirContext, Assertion, AssertionSummary, AuxTraceRandElements,
+    EvaluationFrame, ProofOptions, Prover, ProverError, Serializable, StarkProof,
+    SymbolicConstraints, SymbolicFrame, Trace, TraceInfo, TraceLayout, TraceSegment, TraceTable,
+    TraceViolation, TransitionConstraintDegree, TransitionConstraintGroup, VerifierError,
+};
+
+mod common;
+use common::{hash_chain::*, *};
+
+#[test]
+fn derived_product_column() {
+    let sequence_length = 64;
+    let prover = TestProver::<FibProductAir>::new(build_proof_options(false), fib_result);
+
+    // the third column is never filled explicitly; it is computed from the first two columns
+    let mut trace = build_fib_trace(sequence_length);
+    trace.add_derived_column(|row| row[0] * row[1]);
+    assert_eq!(3, trace.width());
+
+    let proof = prover.prove(trace).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    assert!(winterfell::verify::<FibProductAir, Blake3_256, DefaultRandomCoin<Blake3_256>>(
+        proof.clone(),
+        result,
+        &acceptable_options
+    )
+    .is_ok());
+    assert!(winterfell::verify::<FibProductAir, Blake3_256, DefaultRandomCoin<Blake3_256>>(
+        proof,
+        result + BaseElement::ONE,
+        &acceptable_options
+    )
+    .is_err());
+}
+
+#[test]
+fn linear_combination_assertion() {
+    let sequence_length = 64;
+    let prover =
+        TestProver::<FibCombinationAir>::new(build_proof_options(false), combination_result);
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    // the last terms of the sequence are bound by a single assertion against 2 * col_0 + col_1
+    let trace = build_fib_trace(sequence_length);
+    let last_step = trace.length() - 1;
+    let expected = BaseElement::from(2u8) * trace.get(0, last_step) + trace.get(1, last_step);
+    let pub_inputs = prover.get_pub_inputs(&trace);
+    assert_eq!(expected, pub_inputs);
+
+    let proof = prover.prove(trace).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    assert!(winterfell::verify::<FibCombinationAir, Blake3_256, RandCoin>(
+        proof.clone(),
+        pub_inputs,
+        &acceptable_options
+    )
+    .is_ok());
+
+    // the proof does not verify against a different value of the combination
+    assert!(winterfell::verify::<FibCombinationAir, Blake3_256, RandCoin>(
+        proof,
+        pub_inputs + BaseElement::ONE,
+        &acceptable_options
+    )
+    .is_err());
+}
+
+#[test]
+fn assertion_summary() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+
+    // instantiate the AIR for the public inputs and the trace the proof was generated for
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    let air = <FibAir>::new(proof.get_trace_info(), result, proof.options().clone());
+    let summary = air.assertion_summary();
+
+    // the sequence starts with two ones in the first row, and ends with the result in the second
+    // column of the last row
+    let last_step = sequence_length / 2 - 1;
+    let expected = vec![
+        AssertionSummary {
+            columns: vec![0],
+            steps: vec![0],
+            values: vec![BaseElement::ONE],
+            description: "(column=0, step=0, value=1)".to_string(),
+        },
+        AssertionSummary {
+            columns: vec![1],
+            steps: vec![0],
+            values: vec![BaseElement::ONE],
+            description: "(column=1, step=0, value=1)".to_string(),
+        },
+        AssertionSummary {
+            columns: vec![1],
+            steps: vec![last_step],
+            values: vec![result],
+            description: format!("(column=1, step={last_step}, value={result})"),
+        },
+    ];
+    assert_eq!(expected, summary);
+}
+
+#[test]
+fn custom_degree_adjustments() {
+    let sequence_length = 64;
+    let prover = TestProver::<FibAdjustedAir>::new(build_proof_options(false), fib_result);
+    let mut trace = build_fib_trace(sequence_length);
+    trace.add_derived_column(|row| row[0] * row[1]);
+
+    let proof = prover.prove(trace).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+
+    // the degree-1 constraints are shifted by a non-zero amount
+    let air = FibAdjustedAir::new(proof.get_trace_info(), result, proof.options().clone());
+    let adjustments = air.get_transition_degree_adjustments();
+    assert_ne!(0, adjustments[0]);
+    assert_eq!(adjustments[0], adjustments[1]);
+
+    // the proof verifies against the AIR with the same degree adjustments
+    assert!(winterfell::verify::<FibAdjustedAir, Blake3_256, DefaultRandomCoin<Blake3_256>>(
+        proof.clone(),
+        result,
+        &acceptable_options
+    )
+    .is_ok());
+
+    // but not against the same AIR without degree adjustments
+    assert!(winterfell::verify::<FibProductAir, Blake3_256, DefaultRandomCoin<Blake3_256>>(
+        proof,
+        result,
+        &acceptable_options
+    )
+    .is_err());
+}
+
+#[test]
+fn ood_constraint_contributions() {
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    // build a proof in which transition constraints are degree-adjusted, and replay it against
+    // the AIR which does not adjust constraint degrees; the proof is otherwise identical, and
+    // thus, the verifier draws the same challenges for both AIRs
+    let sequence_length = 64;
+    let prover = TestProver::<FibAdjustedAir>::new(build_proof_options(false), fib_result);
+    let mut trace = build_fib_trace(sequence_length);
+    trace.add_derived_column(|row| row[0] * row[1]);
+    let proof = prover.prove(trace).unwrap();
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+
+    let expected =
+        verifier::replay_verification::<FibAdjustedAir, BaseElement, Blake3_256, RandCoin>(
+            &proof, result,
+        )
+        .unwrap();
+    let actual = verifier::replay_verification::<FibProductAir, BaseElement, Blake3_256, RandCoin>(
+        &proof, result,
+    )
+    .unwrap();
+    assert_eq!(expected.challenges, actual.challenges);
+
+    // contributions of all constraints add up to the composition value computed from the trace
+    for transcript in [&expected, &actual] {
+        let contributions = &transcript.ood_constraint_contributions;
+        assert_eq!(3, contributions.main_transition.len());
+        assert_eq!(3, contributions.main_boundary.len());
+        assert_eq!(transcript.ood_composition_from_trace, contributions.total());
+    }
+
+    // the proof is valid only for the AIR with degree adjustments
+    assert_eq!(Ok(()), expected.result);
+    assert_eq!(Err(VerifierError::InconsistentOodConstraintEvaluations), actual.result);
+    assert_eq!(expected.ood_composition_from_trace, actual.ood_composition_from_columns);
+    assert_ne!(actual.ood_composition_from_trace, actual.ood_composition_from_columns);
+
+    // the offending contributions are those of the degree-adjusted constraints; contributions of
+    // all other constraints are the same for both AIRs
+    let expected = expected.ood_constraint_contributions;
+    let actual = actual.ood_constraint_contributions;
+    assert_ne!(expected.main_transition[0], actual.main_transition[0]);
+    assert_ne!(expected.main_transition[1], actual.main_transition[1]);
+    assert_eq!(expected.main_transition[2], actual.main_transition[2]);
+    assert_eq!(expected.main_boundary, actual.main_boundary);
+}
+
+#[test]
+fn thin_ood_frame() {
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    // with a single constraint composition column, the thin OOD frame contains no constraint
+    // evaluations at all
+    check_fib_proof(16, build_proof_options(false).with_thin_ood_frame());
+
+    // with several constraint composition columns, the evaluation of the last column is omitted
+    let sequence_length = 64;
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    for (is_extension, extension_degree) in [(false, 1), (true, 2)] {
+        let prove = |options: ProofOptions| {
+            let prover = TestProver::<FibProductAir>::new(options, fib_result);
+            let mut trace = build_fib_trace(sequence_length);
+            trace.add_derived_column(|row| row[0] * row[1]);
+            prover.prove(trace).unwrap()
+        };
+        let proof = prove(build_proof_options(is_extension));
+        let thin_proof = prove(build_proof_options(is_extension).with_thin_ood_frame());
+        // the options are bound to the proof, and thus, the proofs are opened at different
+        // positions; only the sizes of their OOD frames are directly comparable
+        assert_eq!(
+            proof.ood_frame.to_bytes().len(),
+            thin_proof.ood_frame.to_bytes().len() + extension_degree * BaseElement::ELEMENT_BYTES
+        );
+
+        // thinning does not affect proof security
+        assert_eq!(
+            proof.security_level::<Blake3_256>(true),
+            thin_proof.security_level::<Blake3_256>(true)
+        );
+        assert_eq!(
+            proof.security_level::<Blake3_256>(false),
+            thin_proof.security_level::<Blake3_256>(false)
+        );
+
+        let acceptable_options = AcceptableOptions::OptionSet(vec![thin_proof.options().clone()]);
+        let verify = |proof: StarkProof, result: BaseElement| {
+            winterfell::verify::<FibProductAir, Blake3_256, RandCoin>(
+                proof,
+                result,
+                &acceptable_options,
+            )
+        };
+        assert!(verify(thin_proof.clone(), result).is_ok());
+        assert!(verify(thin_proof.clone(), result + BaseElement::ONE).is_err());
+
+        // the thin OOD frame changes the proof format, and thus, thin proofs are serialized with
+        // a format version which readers of older formats reject
+        let bytes = thin_proof.to_bytes();
+        assert_eq!(StarkProof::FORMAT_VERSION, bytes[0]);
+        assert_eq!(thin_proof, StarkProof::from_bytes(&bytes).unwrap());
+        let mut older = bytes;
+        older[0] = StarkProof::FORMAT_VERSION - 1;
+        assert!(StarkProof::from_bytes(&older).is_err());
+
+        // a thin proof cannot carry a full OOD frame
+        let mut invalid = thin_proof.clone();
+        invalid.ood_frame = proof.ood_frame.clone();
+        assert!(matches!(
+            verify(invalid, result),
+            Err(VerifierError::ProofDeserializationError(_))
+        ));
+
+        // the verifier recovers the omitted evaluation, and the recovered value is consistent
+        // with the evaluations of the composition polynomial columns at the queried positions
+        if !is_extension {
+            let replay = |proof: &StarkProof| {
+                verifier::replay_verification::<FibProductAir, BaseElement, Blake3_256, RandCoin>(
+                    proof, result,
+                )
+                .unwrap()
+            };
+            let transcript = replay(&thin_proof);
+            assert_eq!(Ok(()), transcript.result);
+            assert_eq!(
+                replay(&proof).ood_constraint_evaluations.len(),
+                transcript.ood_constraint_evaluations.len()
+            );
+        }
+    }
+}
+
+#[test]
+fn symbolic_constraints() {
+    let sequence_length = 64;
+    let options = build_proof_options(false);
+    let prover = fib_prover::<Blake3_256>(options.clone());
+    let symbolic_prover = TestProver::<FibSymbolicAir>::new(options, fib_result);
+
+    // degrees inferred from the constraint expressions match the hand-specified ones
+    let trace = build_fib_trace(sequence_length);
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    let trace_info = trace.get_info();
+    let air = <FibAir>::new(trace_info.clone(), result, build_proof_options(false));
+    let symbolic_air = FibSymbolicAir::new(trace_info, result, build_proof_options(false));
+    assert!(air.context() == symbolic_air.context());
+
+    // the AIR built from constraint expressions produces exactly the same proof as the
+    // hand-written AIR
+    let proof = prover.prove(trace.clone()).unwrap();
+    let symbolic_proof = symbolic_prover.prove(trace).unwrap();
+    assert_eq!(proof.to_bytes(), symbolic_proof.to_bytes());
+
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+    assert!(winterfell::verify::<FibSymbolicAir, Blake3_256, RandCoin>(
+        symbolic_proof,
+        result,
+        &acceptable_options
+    )
+    .is_ok());
+}
+
+#[test]
+fn wraparound_constraints() {
+    let trace_length = 64;
+    let options = build_proof_options(false);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![options.clone()]);
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    // a proof for a cyclic trace is accepted
+    let trace = build_cyclic_sum_trace(trace_length, false);
+    let prover = TestProver::<CyclicSumAir<true>>::new(options.clone(), read_sum);
+    let sum = prover.get_pub_inputs(&trace);
+    let proof = prover.prove(trace).unwrap();
+    assert!(winterfell::verify::<CyclicSumAir<true>, Blake3_256, RandCoin>(
+        proof,
+        sum,
+        &acceptable_options
+    )
+    .is_ok());
+
+    // breaking the cycle violates only the wraparound constraint
+    let trace = build_cyclic_sum_trace(trace_length, true);
+    let sum = prover.get_pub_inputs(&trace);
+    let air = CyclicSumAir::<true>::new(trace.get_info(), sum, options.clone());
+    let report = trace.check(&air, &[], &AuxTraceRandElements::<BaseElement>::new());
+    assert_eq!(
+        &[TraceViolation::Wraparound {
+            constraint: 0,
+            step: trace_length - 1
+        }],
+        report.violations()
+    );
+
+    // a proof generated while ignoring the wraparound constraint verifies only when the verifier
+    // ignores the constraint as well
+    let proof = TestProver::<CyclicSumAir<false>>::new(options, read_sum).prove(trace).unwrap();
+    assert!(winterfell::verify::<CyclicSumAir<false>, Blake3_256, RandCoin>(
+        proof.clone(),
+        sum,
+        &acceptable_options
+    )
+    .is_ok());
+    assert_eq!(
+        Err(VerifierError::InconsistentOodConstraintEvaluations),
+        winterfell::verify::<CyclicSumAir<true>, Blake3_256, RandCoin>(
+            proof,
+            sum,
+            &acceptable_options
+        )
+    );
+}
+
+#[test]
+fn range_checks() {
+    let trace_length = 256;
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+    for options in [build_proof_options(false), build_proof_options(true)] {
+        let acceptable_options = AcceptableOptions::OptionSet(vec![options.clone()]);
+        let prover = TestProver::<ByteSumAir<true>>::new(options.clone(), read_sum);
+
+        // a proof for a trace with all values in range is accepted; columns for the range check
+        // are committed to in an auxiliary segment added by the prover
+        let trace = build_byte_sum_trace(trace_length, None);
+        let sum = prover.get_pub_inputs(&trace);
+        let proof = prover.prove(trace).unwrap();
+        assert_eq!(1, proof.trace_layout().num_segments());
+        assert_eq!(2, proof.trace_queries.len());
+        assert_eq!(proof, StarkProof::from_bytes(&proof.to_bytes()).unwrap());
+        assert!(winterfell::verify::<ByteSumAir<true>, Blake3_256, RandCoin>(
+            proof,
+            sum,
+            &acceptable_options
+        )
+        .is_ok());
+
+        // a value which does not fit into 8 bits violates the range check
+        let trace = build_byte_sum_trace(trace_length, Some(100));
+        let sum = prover.get_pub_inputs(&trace);
+        assert_eq!(Err(ProverError::RangeCheckViolation(0, 100)), prover.prove(trace.clone()));
+
+        // a proof generated without the range check is rejected by a verifier which enforces it
+        let proof = TestProver::<ByteSumAir<false>>::new(options, read_sum).prove(trace).unwrap();
+        assert!(winterfell::verify::<ByteSumAir<false>, Blake3_256, RandCoin>(
+            proof.clone(),
+            sum,
+            &acceptable_options
+        )
+        .is_ok());
+        assert!(winterfell::verify::<ByteSumAir<true>, Blake3_256, RandCoin>(
+            proof,
+            sum,
+            &acceptable_options
+        )
+        .is_err());
+    }
+}
+
+#[test]
+fn rational_constraints() {
+    let trace_length = 32;
+    let options = build_proof_options(false);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![options.clone()]);
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    // columns of the rational constraint are appended to the main trace segment by the prover
+    let trace = build_quotient_trace::<1>(trace_length, BaseElement::from(1000u32));
+    let air = QuotientAir::<1>::new(trace.get_info(), read_denominator(&trace), options.clone());
+    assert_eq!(4, air.trace_layout().main_trace_width());
+    assert_eq!(6, air.committed_trace_layout().main_trace_width());
+    assert_eq!(5, air.context().rational_constraints()[0].result());
+    assert_eq!(6, air.context().num_main_transition_constraints());
+
+    // a proof for a trace with non-zero denominators is accepted
+    let prover = TestProver::<QuotientAir<1>>::new(options.clone(), read_denominator);
+    let denominator = prover.get_pub_inputs(&trace);
+    let proof = prover.prove(trace).unwrap();
+    assert!(winterfell::verify::<QuotientAir<1>, Blake3_256, RandCoin>(
+        proof,
+        denominator,
+        &acceptable_options
+    )
+    .is_ok());
+
+    // a trace with a zero denominator cannot satisfy the rational constraint
+    let trace = build_quotient_trace::<1>(trace_length, -BaseElement::from(5u32));
+    assert_eq!(Err(ProverError::ZeroDenominator(1, 5)), prover.prove(trace.clone()));
+
+    // a prover which divides by a different column can satisfy its own rational constraint, but
+    // the proof is rejected by a verifier which checks the quotient against column 1
+    let prover = TestProver::<QuotientAir<2>>::new(options, read_denominator);
+    let denominator = prover.get_pub_inputs(&trace);
+    let proof = prover.prove(build_quotient_trace::<2>(trace_length, denominator)).unwrap();
+    assert_eq!(
+        Err(VerifierError::InconsistentOodConstraintEvaluations),
+        winterfell::verify::<QuotientAir<1>, Blake3_256, RandCoin>(
+            proof,
+            denominator,
+            &acceptable_options
+        )
+    );
+}
+
+#[test]
+fn three_shift_deep_composition() {
+    let sequence_length = 64;
+    let options = build_proof_options(false);
+    let prover = TestProver::<FibWideFrameAir>::new(options.clone(), fib_result);
+
+    // trace polynomials are opened at z, z * g, and z * g^2, and the proof verifies against an
+    // AIR with the same frame width
+    let trace = build_fib_trace(sequence_length);
+    let proof = prover.prove(trace).unwrap();
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+    assert!(winterfell::verify::<FibWideFrameAir, Blake3_256, RandCoin>(
+        proof.clone(),
+        result,
+        &acceptable_options
+    )
+    .is_ok());
+
+    // the frame width is recorded in the proof context and is accounted for in the security
+    // level of the proof
+    assert_eq!(WIDE_FRAME_WIDTH, proof.context.frame_width());
+    let narrow_prover = fib_prover::<Blake3_256>(options);
+    let narrow_proof = narrow_prover.prove(build_fib_trace(sequence_length)).unwrap();
+    assert_eq!(2, narrow_proof.context.frame_width());
+    assert!(
+        proof.security_level::<Blake3_256>(false)
+            <= narrow_proof.security_level::<Blake3_256>(false)
+    );
+
+    // the proof does not verify against an AIR which opens trace polynomials at two points only
+    assert_eq!(
+        Err(VerifierError::InconsistentFrameWidth(2, WIDE_FRAME_WIDTH)),
+        winterfell::verify::<FibAir, Blake3_256, RandCoin>(
+            proof.clone(),
+            result,
+            &acceptable_options
+        )
+    );
+
+    // and a proof cannot claim a narrower frame (and thus, a higher security level) than the
+    // frame of its AIR
+    let mut bytes = proof.to_bytes();
+    let position = 1 + proof.context.trace_layout().to_bytes().len() + 1;
+    assert_eq!(WIDE_FRAME_WIDTH as u8, bytes[position]);
+    bytes[position] = 2;
+    let proof = StarkProof::from_bytes(&bytes).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    assert_eq!(
+        Err(VerifierError::InconsistentFrameWidth(WIDE_FRAME_WIDTH, 2)),
+        winterfell::verify::<FibWideFrameAir, Blake3_256, RandCoin>(
+            proof,
+            result,
+            &acceptable_options
+        )
+    );
+}
+
+#[test]
+fn wide_aux_segment() {
+    let sequence_length = 64;
+    let options = build_proof_options(false);
+    let prover = TestProver::<FibWideAuxAir, WideAuxTrace>::new(options, |trace| {
+        trace.main.get(1, trace.length() - 1)
+    });
+    let trace = WideAuxTrace::new(build_fib_trace(sequence_length));
+    let proof = prover.prove(trace).unwrap();
+
+    // each trace segment is committed to and opened at its own width
+    let trace_info = proof.get_trace_info();
+    assert_eq!(2, trace_info.layout().main_trace_width());
+    assert_eq!(WIDE_AUX_WIDTH, trace_info.layout().aux_trace_width());
+    assert_eq!(2, proof.trace_queries.len());
+    let lde_domain_size = proof.lde_domain_size();
+    let num_queries = proof.num_unique_queries as usize;
+    for (queries, width) in proof.trace_queries.iter().zip([2, WIDE_AUX_WIDTH]) {
+        let (_, table) = queries
+            .clone()
+            .parse::<Blake3_256, BaseElement>(lde_domain_size, num_queries, width)
+            .unwrap();
+        assert_eq!(width, table.num_columns());
+    }
+
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+    assert!(winterfell::verify::<FibWideAuxAir, Blake3_256, RandCoin>(
+        proof.clone(),
+        result,
+        &acceptable_options
+    )
+    .is_ok());
+    assert!(winterfell::verify::<FibWideAuxAir, Blake3_256, RandCoin>(
+        proof,
+        result + BaseElement::ONE,
+        &acceptable_options
+    )
+    .is_err());
+}
+
+#[test]
+fn extension_field_assertions() {
+    type RandCoin = DefaultRandomCoin<ExtFibHasher>;
+
+    let start = [
+        ExtElement::new(1u64.into(), 2u64.into()),
+        ExtElement::new(3u64.into(), 4u64.into()),
+    ];
+    let prover = TestProver::<ExtFibAir>::new(build_proof_options(false), ext_fib_inputs);
+    let trace = build_ext_fib_trace(start, 32);
+    let pub_inputs = prover.get_pub_inputs(&trace);
+    let result = pub_inputs.result;
+    let report = prover.check_trace(trace.clone(), pub_inputs);
+    assert!(report.is_valid(), "{report}");
+
+    let proof = prover.prove(trace.clone()).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    assert!(winterfell::verify::<ExtFibAir, ExtFibHasher, RandCoin>(
+        proof.clone(),
+        pub_inputs,
+        &acceptable_options
+    )
+    .is_ok());
+
+    // the proof does not verify against a result which differs only in the second component
+    let wrong_inputs = ExtFibInputs {
+        start,
+        result: result + ExtElement::new(f64::BaseElement::ZERO, f64::BaseElement::ONE),
+    };
+    assert!(winterfell::verify::<ExtFibAir, ExtFibHasher, RandCoin>(
+        proof,
+        wrong_inputs,
+        &acceptable_options
+    )
+    .is_err());
+
+    // a trace with a wrong value in the second component of the first term is rejected by the
+    // assertion against that component
+    let mut trace = trace;
+    trace.set(1, 0, start[0].base_element(1) + f64::BaseElement::ONE);
+    let report = prover.check_trace(trace, pub_inputs);
+    assert!(report.violations().contains(&TraceViolation::Assertion {
+        segment: TraceSegment::Main,
+        assertion: Assertion::single(1, 0, start[0].base_element(1)).to_string(),
+        step: 0,
+    }));
+}
+
+#[test]
+fn hash_accumulator() {
+    type RandCoin = DefaultRandomCoin<HashChainHasher>;
+
+    let sequence = (0..20u64).map(|i| f64::BaseElement::new(i * 3 + 1)).collect::<Vec<_>>();
+    let digest = Rp64_256::hash_elements(&sequence);
+    let prover = hash_chain_prover(build_proof_options(true));
+    let trace = build_hash_chain_trace(&sequence, 32);
+    let pub_inputs = prover.get_pub_inputs(&trace);
+    assert_eq!(digest.as_elements(), pub_inputs.digest);
+    let proof = prover.prove(trace).unwrap();
+
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    assert!(winterfell::verify::<HashChainAir, HashChainHasher, RandCoin>(
+        proof.clone(),
+        pub_inputs,
+        &acceptable_options
+    )
+    .is_ok());
+
+    // the proof does not verify against a different digest
+    let mut wrong_inputs = pub_inputs;
+    wrong_inputs.digest[0] += f64::BaseElement::ONE;
+    assert!(winterfell::verify::<HashChainAir, HashChainHasher, RandCoin>(
+        proof,
+        wrong_inputs,
+        &acceptable_options
+    )
+    .is_err());
+}
+
+// FIBONACCI WITH PRODUCT COLUMN
+// ================================================================================================
+
+/// Same as the Fibonacci AIR, but with an additional column containing the product of the two
+/// sequence terms in the same row.
+struct FibProductAir {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for FibProductAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: Self::BaseField, options: ProofOptions) -> Self {
+        let degrees = vec![
+            TransitionConstraintDegree::new(1),
+            TransitionConstraintDegree::new(1),
+            TransitionConstraintDegree::new(2),
+        ];
+        assert_eq!(3, trace_info.width());
+        FibProductAir {
+            context: AirContext::new(trace_info, degrees, 3, options),
+            result: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = are_equal(next[0], current[0] + current[1]);
+        result[1] = are_equal(next[1], current[1] + next[0]);
+        result[2] = are_equal(current[2], current[0] * current[1]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, Self::BaseField::ONE),
+            Assertion::single(1, 0, Self::BaseField::ONE),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+}
+
+// FIBONACCI WITH ADJUSTED CONSTRAINT DEGREES
+// ================================================================================================
+
+/// Same as the Fibonacci AIR with product column, but each group of transition constraints is
+/// shifted by a pseudo-random degree adjustment derived from public inputs.
+struct FibAdjustedAir(FibProductAir);
+
+impl Air for FibAdjustedAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: Self::BaseField, options: ProofOptions) -> Self {
+        FibAdjustedAir(FibProductAir::new(trace_info, pub_inputs, options))
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        self.0.context()
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.0.evaluate_transition(frame, periodic_values, result)
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        self.0.get_assertions()
+    }
+
+    fn get_transition_degree_adjustments(&self) -> Vec<usize> {
+        let degrees = [
+            TransitionConstraintDegree::new(1),
+            TransitionConstraintDegree::new(1),
+            TransitionConstraintDegree::new(2),
+        ];
+        let seed = self.0.result.as_int();
+        let mut adjustments = vec![0; degrees.len()];
+        for (i, group) in TransitionConstraintGroup::group_by_degree(&degrees).iter().enumerate() {
+            let max_adjustment = group.degree_adjustment(self.context());
+            let shift = (seed.rotate_left(8 * i as u32) as usize) % (max_adjustment + 1);
+            for &index in group.indexes() {
+                adjustments[index] = shift;
+            }
+        }
+        adjustments
+    }
+}
+
+// FIBONACCI WITH COMBINATION ASSERTION
+// ================================================================================================
+
+/// Same as the Fibonacci AIR, but the last terms of the sequence are bound by a single assertion
+/// against the linear combination `2 * col_0 + col_1`.
+struct FibCombinationAir {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for FibCombinationAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: Self::BaseField, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1), TransitionConstraintDegree::new(1)];
+        FibCombinationAir {
+            context: AirContext::new(trace_info, degrees, 3, options),
+            result: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = are_equal(next[0], current[0] + current[1]);
+        result[1] = are_equal(next[1], current[1] + next[0]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        let coefficients = vec![BaseElement::from(2u8), BaseElement::ONE];
+        vec![
+            Assertion::single(0, 0, Self::BaseField::ONE),
+            Assertion::single(1, 0, Self::BaseField::ONE),
+            Assertion::linear_combination(coefficients, vec![0, 1], last_step, self.result),
+        ]
+    }
+}
+
+fn combination_result(trace: &TraceTable<BaseElement>) -> BaseElement {
+    let last_step = trace.length() - 1;
+    BaseElement::from(2u8) * trace.get(0, last_step) + trace.get(1, last_step)
+}
+
+// FIBONACCI WITH SYMBOLIC CONSTRAINTS
+// ================================================================================================
+
+/// Same as the Fibonacci AIR, but with transition constraints described by constraint
+/// expressions.
+struct FibSymbolicAir {
+    context: AirContext<BaseElement>,
+    constraints: SymbolicConstraints<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for FibSymbolicAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: BaseElement, options: ProofOptions) -> Self {
+        let frame = SymbolicFrame::new();
+        let constraints = SymbolicConstraints::new()
+            .with_constraint(frame.next(0) - frame.current(0) - frame.current(1))
+            .with_constraint(frame.next(1) - frame.current(1) - frame.next(0));
+        FibSymbolicAir {
+            context: AirContext::new(trace_info, constraints.degrees(), 3, options),
+            constraints,
+            result: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.constraints.evaluate(frame, periodic_values, result);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, BaseElement::ONE),
+            Assertion::single(1, 0, BaseElement::ONE),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+}
+
+// CYCLIC SUM
+// ================================================================================================
+
+/// AIR for a cyclic computation: column 0 contains values the last of which must be equal to the
+/// first one, and column 1 contains running sums of these values (excluding the first value).
+///
+/// When `WRAPAROUND` is false, the wraparound constraint is still declared, but always evaluates
+/// to zero; this simulates a prover which ignores the constraint.
+struct CyclicSumAir<const WRAPAROUND: bool> {
+    context: AirContext<BaseElement>,
+    sum: BaseElement,
+}
+
+impl<const WRAPAROUND: bool> Air for CyclicSumAir<WRAPAROUND> {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: BaseElement, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1)];
+        let context = AirContext::new(trace_info, degrees, 2, options)
+            .set_wraparound_constraint_degrees(vec![TransitionConstraintDegree::new(1)]);
+        CyclicSumAir {
+            context,
+            sum: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = next[1] - current[1] - next[0];
+    }
+
+    fn evaluate_wraparound<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        result: &mut [E],
+    ) {
+        // the current row is the last row of the trace, and the next row is the first one
+        if WRAPAROUND {
+            result[0] = frame.next()[0] - frame.current()[0];
+        }
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(1, 0, BaseElement::ZERO),
+            Assertion::single(1, last_step, self.sum),
+        ]
+    }
+}
+
+/// Builds a trace for the cyclic sum computation; when `break_cycle` is true, the last value in
+/// column 0 differs from the first one.
+fn build_cyclic_sum_trace(length: usize, break_cycle: bool) -> TraceTable<BaseElement> {
+    let mut values = (0..length as u32).map(|i| BaseElement::from(i * i + 1)).collect::<Vec<_>>();
+    values[length - 1] = if break_cycle {
+        values[0] + BaseElement::ONE
+    } else {
+        values[0]
+    };
+
+    let mut trace = TraceTable::new(2, length);
+    trace.fill(
+        |state| {
+            state[0] = values[0];
+            state[1] = BaseElement::ZERO;
+        },
+        |step, state| {
+            state[0] = values[step + 1];
+            state[1] += values[step + 1];
+        },
+    );
+    trace
+}
+
+/// Reads the sum from the last row of the trace; this is the same for the cyclic sum and the
+/// range checked sum computations.
+fn read_sum(trace: &TraceTable<BaseElement>) -> BaseElement {
+    trace.get(1, trace.length() - 1)
+}
+
+// QUOTIENT SUM
+// ================================================================================================
+
+/// AIR for a computation which sums up quotients of counter column 0 by column `DENOMINATOR` in
+/// column 3; the quotients are defined via a rational constraint.
+///
+/// Columns 1 and 2 are counters as well: column 1 starts at the value specified by public inputs,
+/// and column 2 starts at 1001. An honest AIR divides by column 1; setting `DENOMINATOR` to 2
+/// simulates a prover which divides by a different column.
+struct QuotientAir<const DENOMINATOR: usize> {
+    context: AirContext<BaseElement>,
+    denominator: BaseElement,
+}
+
+impl<const DENOMINATOR: usize> Air for QuotientAir<DENOMINATOR> {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: BaseElement, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1); 4];
+        QuotientAir {
+            context: AirContext::new(trace_info, degrees, 4, options)
+                .set_rational_constraints(vec![(0, DENOMINATOR)]),
+            denominator: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        let quotient = current[self.context.rational_constraints()[0].result()];
+        result[0] = next[0] - current[0] - E::ONE;
+        result[1] = next[1] - current[1] - E::ONE;
+        result[2] = next[2] - current[2] - E::ONE;
+        result[3] = next[3] - current[3] - quotient;
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        vec![
+            Assertion::single(0, 0, BaseElement::ONE),
+            Assertion::single(1, 0, self.denominator),
+            Assertion::single(2, 0, BaseElement::from(1001u32)),
+            Assertion::single(3, 0, BaseElement::ZERO),
+        ]
+    }
+}
+
+/// Builds a trace for the quotient sum computation in which column 1 starts at `denominator`.
+fn build_quotient_trace<const DENOMINATOR: usize>(
+    length: usize,
+    denominator: BaseElement,
+) -> TraceTable<BaseElement> {
+    let mut trace = TraceTable::new(4, length);
+    trace.fill(
+        |state| {
+            state[0] = BaseElement::ONE;
+            state[1] = denominator;
+            state[2] = BaseElement::from(1001u32);
+            state[3] = BaseElement::ZERO;
+        },
+        |_, state| {
+            state[3] += state[0] * state[DENOMINATOR].inv();
+            state[0] += BaseElement::ONE;
+            state[1] += BaseElement::ONE;
+            state[2] += BaseElement::ONE;
+        },
+    );
+    trace
+}
+
+/// Reads the initial value of column 1 of the quotient sum trace.
+fn read_denominator(trace: &TraceTable<BaseElement>) -> BaseElement {
+    trace.get(1, 0)
+}
+
+// RANGE CHECKED SUM
+// ================================================================================================
+
+/// AIR for a computation which sums up values of column 0 in column 1 (excluding the first
+/// value). When `RANGE_CHECK` is true, values in column 0 are also asserted to fit into 8 bits via
+/// a range check.
+struct ByteSumAir<const RANGE_CHECK: bool> {
+    context: AirContext<BaseElement>,
+    sum: BaseElement,
+}
+
+impl<const RANGE_CHECK: bool> Air for ByteSumAir<RANGE_CHECK> {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: BaseElement, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1)];
+        ByteSumAir {
+            context: AirContext::new(trace_info, degrees, 2, options),
+            sum: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = next[1] - current[1] - next[0];
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(1, 0, BaseElement::ZERO),
+            Assertion::single(1, last_step, self.sum),
+        ]
+    }
+
+    fn get_range_checks(&self) -> Vec<(usize, u32)> {
+        if RANGE_CHECK {
+            vec![(0, 8)]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Builds a trace for the range checked sum computation; all values in column 0 fit into 8 bits,
+/// except for the value at `out_of_range_step` (if specified), which is set to 256.
+fn build_byte_sum_trace(
+    length: usize,
+    out_of_range_step: Option<usize>,
+) -> TraceTable<BaseElement> {
+    let mut values = (0..length as u32)
+        .map(|i| BaseElement::from((i * 37 + 11) % 256))
+        .collect::<Vec<_>>();
+    if let Some(step) = out_of_range_step {
+        values[step] = BaseElement::from(256u32);
+    }
+
+    let mut trace = TraceTable::new(2, length);
+    trace.fill(
+        |state| {
+            state[0] = values[0];
+            state[1] = BaseElement::ZERO;
+        },
+        |step, state| {
+            state[0] = values[step + 1];
+            state[1] += values[step + 1];
+        },
+    );
+    trace
+}
+
+// FIBONACCI WITH WIDE FRAME
+// ================================================================================================
+
+const WIDE_FRAME_WIDTH: usize = 3;
+
+/// Same as the Fibonacci AIR, but with trace polynomials opened at three consecutive rows during
+/// DEEP composition.
+struct FibWideFrameAir {
+    inner: FibAir,
+    context: AirContext<BaseElement>,
+}
+
+impl Air for FibWideFrameAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: BaseElement, options: ProofOptions) -> Self {
+        let inner = <FibAir>::new(trace_info, pub_inputs, options);
+        let context = inner.context().clone().set_frame_width(WIDE_FRAME_WIDTH);
+        Self { inner, context }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.inner.evaluate_transition(frame, periodic_values, result)
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        self.inner.get_assertions()
+    }
+}
+
+// FIBONACCI WITH WIDE AUXILIARY SEGMENT
+// ================================================================================================
+
+const WIDE_AUX_WIDTH: usize = 16;
+
+/// Same as the Fibonacci AIR, but with an auxiliary trace segment which is much wider than the
+/// main segment. Column `j` of the auxiliary segment holds the first column of the main segment
+/// multiplied by `r^(j + 1)`, where `r` is a random element.
+struct FibWideAuxAir {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for FibWideAuxAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: Self::BaseField, options: ProofOptions) -> Self {
+        let main_degrees =
+            vec![TransitionConstraintDegree::new(1), TransitionConstraintDegree::new(1)];
+        let aux_degrees = vec![TransitionConstraintDegree::new(1); WIDE_AUX_WIDTH];
+        assert_eq!(2, trace_info.layout().main_trace_width());
+        assert_eq!(WIDE_AUX_WIDTH, trace_info.layout().aux_trace_width());
+        FibWideAuxAir {
+            context: AirContext::new_multi_segment(
+                trace_info,
+                main_degrees,
+                aux_degrees,
+                3,
+                1,
+                options,
+            ),
+            result: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = are_equal(next[0], current[0] + current[1]);
+        result[1] = are_equal(next[1], current[1] + next[0]);
+    }
+
+    fn evaluate_aux_transition<F, E>(
+        &self,
+        main_frame: &EvaluationFrame<F>,
+        aux_frame: &EvaluationFrame<E>,
+        _periodic_values: &[F],
+        aux_rand_elements: &AuxTraceRandElements<E>,
+        result: &mut [E],
+    ) where
+        F: FieldElement<BaseField = Self::BaseField>,
+        E: FieldElement<BaseField = Self::BaseField> + ExtensionOf<F>,
+    {
+        let r = aux_rand_elements.get_segment_elements(0)[0];
+        let aux = aux_frame.current();
+        result[0] = are_equal(aux[0], E::from(main_frame.current()[0]) * r);
+        for j in 1..WIDE_AUX_WIDTH {
+            result[j] = are_equal(aux[j], aux[j - 1] * r);
+        }
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, Self::BaseField::ONE),
+            Assertion::single(1, 0, Self::BaseField::ONE),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+
+    fn get_aux_assertions<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        aux_rand_elements: &AuxTraceRandElements<E>,
+    ) -> Vec<Assertion<E>> {
+        let r = aux_rand_elements.get_segment_elements(0)[0];
+        vec![Assertion::single(0, 0, r)]
+    }
+}
+
+/// A Fibonacci trace extended with a wide auxiliary segment for [FibWideAuxAir].
+struct WideAuxTrace {
+    layout: TraceLayout,
+    main: TraceTable<BaseElement>,
+}
+
+impl WideAuxTrace {
+    fn new(main: TraceTable<BaseElement>) -> Self {
+        Self {
+            layout: TraceLayout::new(main.width(), [WIDE_AUX_WIDTH], [1]),
+            main,
+        }
+    }
+}
+
+impl Trace for WideAuxTrace {
+    type BaseField = BaseElement;
+
+    fn layout(&self) -> &TraceLayout {
+        &self.layout
+    }
+
+    fn length(&self) -> usize {
+        self.main.length()
+    }
+
+    fn meta(&self) -> &[u8] {
+        &[]
+    }
+
+    fn main_segment(&self) -> &ColMatrix<BaseElement> {
+        self.main.main_segment()
+    }
+
+    fn build_aux_segment<E: FieldElement<BaseField = BaseElement>>(
+        &mut self,
+        aux_segments: &[ColMatrix<E>],
+        rand_elements: &[E],
+    ) -> Option<ColMatrix<E>> {
+        if !aux_segments.is_empty() {
+            return None;
+        }
+        let r = rand_elements[0];
+        let mut columns = Vec::with_capacity(WIDE_AUX_WIDTH);
+        let mut column: Vec<E> = self.main.get_column(0).iter().map(|&v| E::from(v) * r).collect();
+        for _ in 1..WIDE_AUX_WIDTH {
+            let next = column.iter().map(|&v| v * r).collect();
+            columns.push(column);
+            column = next;
+        }
+        columns.push(column);
+        Some(ColMatrix::new(columns))
+    }
+
+    fn read_main_frame(&self, row_idx: usize, frame: &mut EvaluationFrame<BaseElement>) {
+        self.main.read_main_frame(row_idx, frame);
+    }
+}
+
+// FIBONACCI OVER EXTENSION FIELD
+// ================================================================================================
+
+/// Hash function used to generate proofs for the Fibonacci sequence over an extension field.
+type ExtFibHasher = winterfell::crypto::hashers::Blake3_256<f64::BaseElement>;
+
+/// Extension field over which the Fibonacci sequence is computed.
+type ExtElement = QuadExtension<f64::BaseElement>;
+
+/// Public inputs for a Fibonacci sequence over an extension field.
+#[derive(Clone, Copy)]
+struct ExtFibInputs {
+    start: [ExtElement; 2],
+    result: ExtElement,
+}
+
+impl ToElements<f64::BaseElement> for ExtFibInputs {
+    fn to_elements(&self) -> Vec<f64::BaseElement> {
+        ExtElement::slice_as_base_elements(&[self.start[0], self.start[1], self.result]).to_vec()
+    }
+}
+
+/// AIR for a Fibonacci sequence over a quadratic extension field; each row holds two consecutive
+/// terms of the sequence, with each term occupying two columns of the trace.
+struct ExtFibAir {
+    context: AirContext<f64::BaseElement>,
+    pub_inputs: ExtFibInputs,
+}
+
+impl Air for ExtFibAir {
+    type BaseField = f64::BaseElement;
+    type PublicInputs = ExtFibInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: Self::PublicInputs, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1); 4];
+        ExtFibAir {
+            context: AirContext::new(trace_info, degrees, 6, options),
+            pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        // addition in the extension field is component-wise, and thus, the constraints can be
+        // applied to each component separately
+        let current = frame.current();
+        let next = frame.next();
+        for i in 0..2 {
+            result[i] = are_equal(next[i], current[i] + current[i + 2]);
+            result[i + 2] = are_equal(next[i + 2], current[i + 2] + next[i]);
+        }
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        let mut assertions = Assertion::single_ext(0, 0, self.pub_inputs.start[0]);
+        assertions.extend(Assertion::single_ext(2, 0, self.pub_inputs.start[1]));
+        assertions.extend(Assertion::single_ext(2, last_step, self.pub_inputs.result));
+        assertions
+    }
+}
+
+/// Builds a trace of the specified length for a Fibonacci sequence starting with the provided
+/// terms.
+fn build_ext_fib_trace(
+    start: [ExtElement; 2],
+    trace_length: usize,
+) -> TraceTable<f64::BaseElement> {
+    let mut trace = TraceTable::new(4, trace_length);
+    trace.fill(
+        |state| state.copy_from_slice(ExtElement::slice_as_base_elements(&start)),
+        |_, state| {
+            for i in 0..2 {
+                state[i] += state[i + 2];
+                state[i + 2] += state[i];
+            }
+        },
+    );
+    trace
+}
+
+fn ext_fib_inputs(trace: &TraceTable<f64::BaseElement>) -> ExtFibInputs {
+    let last_step = trace.length() - 1;
+    let read = |column, step| ExtElement::new(trace.get(column, step), trace.get(column + 1, step));
+    ExtFibInputs {
+        start: [read(0, 0), read(2, 0)],
+        result: read(2, last_step),
+    }
+}
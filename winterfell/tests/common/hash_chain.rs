@@ -0,0 +1,139 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! AIR for a computation which hashes a private sequence of elements with Rp64_256.
+
+use super::TestProver;
+use winterfell::{
+    crypto::hashers::{self, Rp64_256},
+    math::{fields::f64::BaseElement, FieldElement, ToElements},
+    Air, AirContext, Assertion, EvaluationFrame, HashAccumulator, ProofOptions, Trace, TraceInfo,
+    TraceTable,
+};
+
+/// Hash function used to generate proofs for the hash chain computation.
+pub type HashChainHasher = hashers::Blake3_256<BaseElement>;
+
+/// Prover for the hash chain computation.
+pub type HashChainProver = TestProver<HashChainAir, TraceTable<BaseElement>, HashChainHasher>;
+
+/// Width of the rate portion of the Rp64_256 state; the trace has one input column per rate
+/// element.
+const HASH_CHAIN_RATE: usize = 8;
+
+/// Public inputs for a computation which hashes a private sequence of elements.
+#[derive(Clone, Copy)]
+pub struct HashChainInputs {
+    pub num_elements: usize,
+    pub digest: [BaseElement; 4],
+}
+
+impl ToElements<BaseElement> for HashChainInputs {
+    fn to_elements(&self) -> Vec<BaseElement> {
+        let mut result = vec![BaseElement::new(self.num_elements as u64)];
+        result.extend_from_slice(&self.digest);
+        result
+    }
+}
+
+/// Returns an accumulator with the Rp64_256 state in the first 12 columns, absorbing the last 8
+/// columns of the trace.
+fn build_hash_chain_accumulator() -> HashAccumulator<Rp64_256> {
+    let state_width = Rp64_256::STATE_WIDTH;
+    HashAccumulator::new(0, (state_width..state_width + HASH_CHAIN_RATE).collect())
+}
+
+/// AIR for a computation which hashes a private sequence of elements with Rp64_256.
+pub struct HashChainAir {
+    context: AirContext<BaseElement>,
+    accumulator: HashAccumulator<Rp64_256>,
+    pub_inputs: HashChainInputs,
+}
+
+impl Air for HashChainAir {
+    type BaseField = BaseElement;
+    type PublicInputs = HashChainInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: Self::PublicInputs, options: ProofOptions) -> Self {
+        let accumulator = build_hash_chain_accumulator();
+        let num_assertions = Rp64_256::STATE_WIDTH - HASH_CHAIN_RATE + pub_inputs.digest.len();
+        HashChainAir {
+            context: AirContext::new(trace_info, accumulator.degrees(), num_assertions, options),
+            accumulator,
+            pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.accumulator.evaluate(frame, periodic_values, result);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        self.accumulator
+            .get_assertions(self.pub_inputs.num_elements, &self.pub_inputs.digest)
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
+        self.accumulator.get_periodic_column_values()
+    }
+}
+
+/// Returns a prover for the hash chain computation instantiated with the specified options.
+pub fn hash_chain_prover(options: ProofOptions) -> HashChainProver {
+    TestProver::new(options, hash_chain_inputs)
+}
+
+/// Builds a trace of the specified length which hashes the provided sequence.
+pub fn build_hash_chain_trace(
+    sequence: &[BaseElement],
+    trace_length: usize,
+) -> TraceTable<BaseElement> {
+    let accumulator = build_hash_chain_accumulator();
+    let state_width = Rp64_256::STATE_WIDTH;
+    let cycle_length = accumulator.cycle_length();
+    let (first_chunk, rest) = sequence.split_at(HASH_CHAIN_RATE.min(sequence.len()));
+
+    // the number of hashed elements is passed to the prover via trace metadata
+    let meta = (sequence.len() as u32).to_le_bytes().to_vec();
+    let mut trace = TraceTable::with_meta(state_width + HASH_CHAIN_RATE, trace_length, meta);
+    trace.fill(
+        |row| accumulator.init_row(row, sequence.len(), first_chunk),
+        |step, row| {
+            // inputs absorbed at the end of the current cycle are placed into the input
+            // columns of the last row of the cycle before the next row is computed
+            let current = row.to_vec();
+            accumulator.fill_next_row(step, &current, row);
+            row[state_width..].fill(BaseElement::ZERO);
+            if (step + 2) % cycle_length == 0 {
+                let chunk_idx = (step + 2) / cycle_length - 1;
+                if let Some(chunk) = rest.chunks(HASH_CHAIN_RATE).nth(chunk_idx) {
+                    row[state_width..state_width + chunk.len()].copy_from_slice(chunk);
+                }
+            }
+        },
+    );
+    trace
+}
+
+/// Reads the number of hashed elements and the resulting digest from the trace.
+fn hash_chain_inputs(trace: &TraceTable<BaseElement>) -> HashChainInputs {
+    let accumulator = build_hash_chain_accumulator();
+    let num_elements = u32::from_le_bytes(trace.meta().try_into().unwrap()) as usize;
+    let digest_step = accumulator.digest_step(num_elements);
+    let digest = Rp64_256::DIGEST_RANGE.map(|column| trace.get(column, digest_step));
+    HashChainInputs {
+        num_elements,
+        digest: digest.collect::<Vec<_>>().try_into().unwrap(),
+    }
+}
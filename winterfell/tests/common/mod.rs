@@ -0,0 +1,241 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Components shared by the end-to-end tests of the prover and the verifier.
+
+#![allow(dead_code)]
+
+use core::marker::PhantomData;
+use winterfell::{
+    crypto::{hashers, DefaultRandomCoin, ElementHasher, RandomCoin},
+    math::{fields::f128::BaseElement, ExtensibleField, FieldElement, StarkField},
+    matrix::ColMatrix,
+    AcceptableOptions, Air, AirContext, Assertion, AuxTraceRandElements,
+    ConstraintCompositionCoefficients, DefaultConstraintEvaluator, DefaultTraceLde,
+    EvaluationFrame, FieldExtension, ProofOptions, Prover, StarkDomain, Trace,
+    TraceCommitmentOptions, TraceInfo, TracePolyTable, TraceTable, TransitionConstraintDegree,
+};
+
+pub mod hash_chain;
+
+pub type Blake3_256 = hashers::Blake3_256<BaseElement>;
+pub type Sha3_256 = hashers::Sha3_256<BaseElement>;
+
+// TEST PROVER
+// ================================================================================================
+
+/// A prover which can generate proofs for any AIR.
+///
+/// Traces are extended and constraints are evaluated using the default prover components;
+/// public inputs are read from the trace using the provided function.
+pub struct TestProver<
+    A: Air,
+    T = TraceTable<<A as Air>::BaseField>,
+    H = hashers::Blake3_256<<A as Air>::BaseField>,
+    R = DefaultRandomCoin<H>,
+> {
+    options: ProofOptions,
+    get_pub_inputs: fn(&T) -> A::PublicInputs,
+    _phantom: PhantomData<(A, H, R)>,
+}
+
+impl<A: Air, T, H, R> TestProver<A, T, H, R> {
+    pub fn new(options: ProofOptions, get_pub_inputs: fn(&T) -> A::PublicInputs) -> Self {
+        Self {
+            options,
+            get_pub_inputs,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<A, T, H, R> Prover for TestProver<A, T, H, R>
+where
+    A: Air + 'static,
+    T: Trace<BaseField = A::BaseField>,
+    H: ElementHasher<BaseField = A::BaseField>,
+    R: RandomCoin<BaseField = A::BaseField, Hasher = H>,
+{
+    type BaseField = A::BaseField;
+    type Air = A;
+    type Trace = T;
+    type HashFn = H;
+    type RandomCoin = R;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> A::PublicInputs {
+        (self.get_pub_inputs)(trace)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+        commitment_options: TraceCommitmentOptions,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain, commitment_options)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
+
+// FIBONACCI
+// ================================================================================================
+
+/// AIR for a Fibonacci sequence in which each row of the trace advances the sequence by 2 terms.
+pub struct FibAir<B: StarkField = BaseElement> {
+    context: AirContext<B>,
+    result: B,
+}
+
+impl<B> Air for FibAir<B>
+where
+    B: StarkField + ExtensibleField<2> + ExtensibleField<3>,
+{
+    type BaseField = B;
+    type PublicInputs = B;
+
+    fn new(trace_info: TraceInfo, pub_inputs: B, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1), TransitionConstraintDegree::new(1)];
+        assert_eq!(2, trace_info.width());
+        FibAir {
+            context: AirContext::new(trace_info, degrees, 3, options),
+            result: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<B> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<B>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = are_equal(next[0], current[0] + current[1]);
+        result[1] = are_equal(next[1], current[1] + next[0]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<B>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, B::ONE),
+            Assertion::single(1, 0, B::ONE),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+}
+
+/// Prover for [FibAir] over the base field of the hash function `H`.
+pub type FibProver<H> = TestProver<
+    FibAir<<H as ElementHasher>::BaseField>,
+    TraceTable<<H as ElementHasher>::BaseField>,
+    H,
+>;
+
+/// Returns a prover for [FibAir] instantiated with the specified options.
+pub fn fib_prover<H>(options: ProofOptions) -> FibProver<H>
+where
+    H: ElementHasher,
+    H::BaseField: ExtensibleField<2> + ExtensibleField<3>,
+{
+    TestProver::new(options, fib_result)
+}
+
+/// Builds an execution trace for computing a Fibonacci sequence of the specified length such
+/// that each row advances the sequence by 2 terms.
+pub fn build_fib_trace<B: StarkField>(sequence_length: usize) -> TraceTable<B> {
+    assert!(sequence_length.is_power_of_two(), "sequence length must be a power of 2");
+
+    let mut trace = TraceTable::new(2, sequence_length / 2);
+    trace.fill(
+        |state| {
+            state[0] = B::ONE;
+            state[1] = B::ONE;
+        },
+        |_, state| {
+            state[0] += state[1];
+            state[1] += state[0];
+        },
+    );
+
+    trace
+}
+
+/// Reads the last term of a Fibonacci sequence from the second column of the trace.
+pub fn fib_result<B: StarkField>(trace: &TraceTable<B>) -> B {
+    trace.get(1, trace.length() - 1)
+}
+
+/// Computes the n-th term of the Fibonacci sequence.
+pub fn compute_fib_term<E: FieldElement>(n: usize) -> E {
+    let mut t0 = E::ONE;
+    let mut t1 = E::ONE;
+
+    for _ in 0..(n - 1) {
+        t1 = t0 + t1;
+        core::mem::swap(&mut t0, &mut t1);
+    }
+
+    t1
+}
+
+/// Proves the computation of a Fibonacci sequence with the specified options, and checks that
+/// the proof verifies against the correct result only.
+pub fn check_fib_proof(sequence_length: usize, options: ProofOptions) {
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    let prover = fib_prover::<Blake3_256>(options);
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    assert!(winterfell::verify::<FibAir, Blake3_256, RandCoin>(
+        proof.clone(),
+        result,
+        &acceptable_options
+    )
+    .is_ok());
+    assert!(winterfell::verify::<FibAir, Blake3_256, RandCoin>(
+        proof,
+        result + BaseElement::ONE,
+        &acceptable_options
+    )
+    .is_err());
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+pub fn build_proof_options(use_extension_field: bool) -> ProofOptions {
+    let extension = if use_extension_field {
+        FieldExtension::Quadratic
+    } else {
+        FieldExtension::None
+    };
+    ProofOptions::new(28, 8, 0, extension, 4, 7)
+}
+
+/// Returns zero only when a == b.
+pub fn are_equal<E: FieldElement>(a: E, b: E) -> E {
+    a - b
+}
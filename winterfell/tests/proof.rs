@@ -0,0 +1,505 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! End-to-end tests of the proof format.
+
+use winterfell::{
+    crypto::{DefaultRandomCoin, Digest, Hasher},
+    math::{fields::f128::BaseElement, FieldElement, StarkField},
+    proof::{
+        Ed25519, Ed25519PublicKey, Ed25519SecretKey, FieldId, HashFunctionId, ProofHeader,
+        ProofSignatureScheme, SignedProof,
+    },
+    AcceptableOptions, ByteReader, ByteWriter, ChecksumError, CommitmentLayout, Deserializable,
+    DeserializationError, FieldExtension, ProofOptions, Prover, Serializable, StarkProof, Trace,
+    VerifierError,
+};
+
+mod common;
+use common::*;
+
+#[test]
+fn proof_header() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(true));
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    let bytes = proof.to_bytes_with_header(HashFunctionId::Blake3_256);
+    assert_eq!(ProofHeader::SIZE + proof.to_bytes().len(), bytes.len());
+    assert_eq!(proof.to_bytes(), bytes[ProofHeader::SIZE..]);
+
+    // the header describes the proof and can be parsed from the header bytes alone, without
+    // deserializing the rest of the proof
+    let expected = ProofHeader {
+        version: StarkProof::FORMAT_VERSION,
+        field: FieldId::F128,
+        hash_fn: HashFunctionId::Blake3_256,
+        field_extension: FieldExtension::Quadratic,
+        main_trace_width: 2,
+        aux_trace_width: 0,
+        trace_length: sequence_length as u64 / 2,
+    };
+    assert_eq!(Ok(expected), StarkProof::peek_header(&bytes[..ProofHeader::SIZE]));
+    assert_eq!(Ok(expected), StarkProof::peek_header(&bytes));
+    assert!(StarkProof::peek_header(&bytes[..ProofHeader::SIZE - 1]).is_err());
+
+    // the full proof can be read back together with its header
+    assert_eq!(Ok((expected, proof.clone())), StarkProof::from_bytes_with_header(&bytes));
+
+    // a header with invalid magic bytes or inconsistent with the proof is rejected
+    let mut invalid = bytes.clone();
+    invalid[0] ^= 1;
+    assert!(StarkProof::peek_header(&invalid).is_err());
+    let mut invalid = bytes.clone();
+    invalid[7] = FieldExtension::None as u8;
+    assert_eq!(FieldExtension::None, StarkProof::peek_header(&invalid).unwrap().field_extension);
+    assert!(StarkProof::from_bytes_with_header(&invalid).is_err());
+
+    // proofs without a header are not mistaken for proofs with a header
+    assert!(StarkProof::peek_header(&proof.to_bytes()).is_err());
+}
+
+#[test]
+fn proof_context() {
+    let sequence_length = 64;
+    let options = build_proof_options(false);
+    let prover = fib_prover::<Blake3_256>(options.clone());
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+
+    // proof parameters can be read without the public inputs
+    let context = proof.context();
+    let trace_info = context.get_trace_info();
+    assert_eq!(sequence_length / 2, trace_info.length());
+    assert_eq!(2, trace_info.width());
+    assert_eq!(2, trace_info.layout().main_trace_width());
+    assert_eq!(0, trace_info.layout().num_aux_segments());
+    assert_eq!(&options, context.options());
+    assert_eq!(sequence_length / 2 * options.blowup_factor(), context.lde_domain_size());
+    assert_eq!(BaseElement::get_modulus_le_bytes(), context.field_modulus_bytes());
+    assert_eq!(BaseElement::MODULUS_BITS, context.num_modulus_bits());
+}
+
+#[test]
+fn proof_hex_round_trip() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+
+    // the hex string is a lowercase encoding of the binary serialization of the proof
+    let hex = proof.to_hex();
+    let bytes = proof.to_bytes();
+    assert_eq!(bytes.len() * 2, hex.len());
+    assert!(hex.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c)));
+    assert_eq!(format!("{:02x}", bytes[0]), hex[..2]);
+
+    let decoded = StarkProof::from_hex(&hex).unwrap();
+    assert_eq!(proof, decoded);
+    assert_eq!(proof, StarkProof::from_hex(&hex.to_uppercase()).unwrap());
+
+    // the decoded proof still verifies
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    assert!(winterfell::verify::<FibAir, Blake3_256, DefaultRandomCoin<Blake3_256>>(
+        decoded,
+        result,
+        &acceptable_options
+    )
+    .is_ok());
+}
+
+#[test]
+fn proof_hex_invalid_input() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let hex = prover.prove(build_fib_trace(sequence_length)).unwrap().to_hex();
+
+    // odd number of digits
+    let err = StarkProof::from_hex(&hex[1..]).unwrap_err();
+    assert_eq!(
+        DeserializationError::InvalidValue(format!(
+            "hex string must contain an even number of digits, but contained {}",
+            hex.len() - 1
+        )),
+        err
+    );
+
+    // non-hex characters
+    let err = StarkProof::from_hex(&format!("{}zz{}", &hex[..4], &hex[6..])).unwrap_err();
+    assert_eq!(
+        DeserializationError::InvalidValue("invalid hex digit 'z' at position 4".to_string()),
+        err
+    );
+    assert!(StarkProof::from_hex(&format!("0x{}", &hex[2..])).is_err());
+
+    // valid hex which does not encode a proof
+    assert!(StarkProof::from_hex(&hex[..hex.len() - 2]).is_err());
+    assert!(StarkProof::from_hex("").is_err());
+}
+
+#[test]
+fn proof_format_version() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+
+    // the serialized proof starts with the current format version
+    let bytes = proof.to_bytes();
+    assert_eq!(StarkProof::FORMAT_VERSION, bytes[0]);
+    assert_eq!(proof, StarkProof::from_bytes(&bytes).unwrap());
+
+    // a proof serialized with a newer format version is rejected with a clear error
+    let mut newer = bytes.clone();
+    newer[0] = StarkProof::FORMAT_VERSION + 1;
+    let expected = DeserializationError::InvalidValue(format!(
+        "proof format version {} is not compatible; expected a version between {} and {}",
+        StarkProof::FORMAT_VERSION + 1,
+        StarkProof::MIN_COMPATIBLE_FORMAT_VERSION,
+        StarkProof::FORMAT_VERSION
+    ));
+    assert_eq!(Err(expected), StarkProof::from_bytes(&newer));
+
+    // the same is true for a proof serialized with a version older than the oldest compatible one
+    let mut older = bytes;
+    older[0] = StarkProof::MIN_COMPATIBLE_FORMAT_VERSION - 1;
+    assert!(matches!(
+        StarkProof::from_bytes(&older),
+        Err(DeserializationError::InvalidValue(_))
+    ));
+}
+
+#[test]
+fn proof_checksum() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+
+    // the checksum is appended to the regular serialization of the proof
+    let bytes = proof.to_bytes_with_checksum();
+    assert_eq!(proof.to_bytes(), bytes[..bytes.len() - StarkProof::CHECKSUM_BYTES]);
+    assert_eq!(proof, StarkProof::from_bytes_with_checksum(&bytes).unwrap());
+
+    // a truncated proof is reported as corrupted rather than as a format error
+    for num_bytes in [bytes.len() - 1, bytes.len() / 2, StarkProof::CHECKSUM_BYTES, 0] {
+        assert_eq!(
+            Err(ChecksumError::CorruptedProof),
+            StarkProof::from_bytes_with_checksum(&bytes[..num_bytes])
+        );
+    }
+    assert_eq!(
+        Err(DeserializationError::UnexpectedEOF),
+        StarkProof::from_bytes(&proof.to_bytes()[..bytes.len() / 2])
+    );
+
+    // the same is true for a proof with a flipped bit
+    let mut corrupted = bytes;
+    corrupted[100] ^= 1;
+    assert_eq!(
+        Err(ChecksumError::CorruptedProof),
+        StarkProof::from_bytes_with_checksum(&corrupted)
+    );
+
+    // a valid checksum over bytes which do not encode a proof is reported as a format error
+    let proof_bytes = proof.to_bytes();
+    let mut invalid = proof_bytes[..proof_bytes.len() / 2].to_vec();
+    let checksum = Blake3_256::hash(&invalid).as_bytes();
+    invalid.extend_from_slice(&checksum[..StarkProof::CHECKSUM_BYTES]);
+    assert_eq!(
+        Err(ChecksumError::InvalidFormat(DeserializationError::UnexpectedEOF)),
+        StarkProof::from_bytes_with_checksum(&invalid)
+    );
+}
+
+#[test]
+fn proof_id() {
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let proof = prover.prove(build_fib_trace(64)).unwrap();
+    let other = prover.prove(build_fib_trace(128)).unwrap();
+
+    // byte-identical proofs have the same id
+    let id = proof.id();
+    assert_eq!(id, StarkProof::from_bytes(&proof.to_bytes()).unwrap().id());
+    assert_eq!(id, prover.prove(build_fib_trace(64)).unwrap().id());
+    assert_ne!(id, other.id());
+
+    // modifying any field of the proof changes the id
+    let modifications: Vec<fn(&mut StarkProof, &StarkProof)> = vec![
+        |proof, other| proof.context = other.context.clone(),
+        |proof, _| proof.num_unique_queries -= 1,
+        |proof, other| proof.commitments = other.commitments.clone(),
+        |proof, other| proof.trace_queries = other.trace_queries.clone(),
+        |proof, other| proof.constraint_queries = other.constraint_queries.clone(),
+        |proof, other| proof.ood_frame = other.ood_frame.clone(),
+        |proof, other| proof.fri_proof = other.fri_proof.clone(),
+        |proof, _| proof.pow_nonce += 1,
+        |proof, _| proof.metadata.push(1),
+        |proof, _| proof.pub_inputs.push(1),
+    ];
+    for modify in modifications {
+        let mut modified = proof.clone();
+        modify(&mut modified, &other);
+        assert_ne!(proof, modified);
+        assert_ne!(id, modified.id());
+    }
+}
+
+#[test]
+fn query_cells() {
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    let sequence_length = 64;
+    for rows_per_leaf in [1, 4] {
+        let options = build_proof_options(false).with_rows_per_leaf(rows_per_leaf);
+        let prover = fib_prover::<Blake3_256>(options);
+        let trace = build_fib_trace(sequence_length);
+        let trace_polys = trace.main_segment().interpolate_columns();
+        let proof = prover.prove(trace).unwrap();
+        let result = compute_fib_term::<BaseElement>(sequence_length);
+
+        // compute cells opened at the query positions drawn by the verifier
+        let challenges =
+            winterfell::derive_challenges::<FibAir, BaseElement, Blake3_256, RandCoin>(
+                &proof, result,
+            )
+            .unwrap();
+        let trace_width = trace_polys.num_cols();
+        let cells = proof.options().query_cells(&challenges.query_positions, trace_width);
+
+        // read the cells actually opened in the proof; opened rows (or leaves) are sorted by
+        // position, and thus, the opened values are listed in the same order as the cells
+        let lde_domain_size = proof.lde_domain_size();
+        let queries = proof.trace_queries[0].clone();
+        let (_, opened) = if rows_per_leaf == 1 {
+            let num_queries = challenges.query_positions.len();
+            queries
+                .parse::<Blake3_256, BaseElement>(lde_domain_size, num_queries, trace_width)
+                .unwrap()
+        } else {
+            queries
+                .parse_packed::<Blake3_256, BaseElement>(
+                    lde_domain_size,
+                    rows_per_leaf,
+                    trace_width,
+                )
+                .unwrap()
+        };
+        let opened_values = opened.rows().flatten().copied().collect::<Vec<_>>();
+        assert_eq!(cells.len(), opened_values.len());
+
+        // each opened value must be the value of the trace LDE at the corresponding cell
+        let g = BaseElement::get_root_of_unity(lde_domain_size.ilog2());
+        let offset = proof.options().domain_offset::<BaseElement>();
+        for (&(position, register), &value) in cells.iter().zip(opened_values.iter()) {
+            let x = offset * g.exp((position as u64).into());
+            assert_eq!(trace_polys.evaluate_columns_at(x)[register], value);
+        }
+    }
+}
+
+#[test]
+fn num_opened_leaves() {
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+    let sequence_length = 128;
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+
+    for (folding_factor, rows_per_leaf, layout) in [
+        (2, 1, CommitmentLayout::RowMajor),
+        (4, 4, CommitmentLayout::RowMajor),
+        (8, 2, CommitmentLayout::RowMajor),
+        (16, 1, CommitmentLayout::PerColumn),
+    ] {
+        let options = ProofOptions::new(28, 8, 0, FieldExtension::None, folding_factor, 7)
+            .with_rows_per_leaf(rows_per_leaf)
+            .with_commitment_layout(layout);
+        let prover = fib_prover::<Blake3_256>(options);
+        let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+        let lde_domain_size = proof.lde_domain_size();
+
+        // compute the expected numbers of opened leaves from the query positions of the proof
+        let challenges =
+            winterfell::derive_challenges::<FibAir, BaseElement, Blake3_256, RandCoin>(
+                &proof, result,
+            )
+            .unwrap();
+        let opened =
+            proof.options().num_opened_leaves(&challenges.query_positions, lde_domain_size);
+        assert_eq!(proof.fri_proof.num_layers(), opened.fri_layers.len());
+
+        // the counts must match the structure of the proof
+        assert_eq!(proof.num_unique_queries as usize, opened.constraint);
+        let trace_queries = proof.trace_queries[0].clone();
+        match layout {
+            CommitmentLayout::RowMajor if rows_per_leaf > 1 => {
+                let (_, leaves) = trace_queries
+                    .parse_packed::<Blake3_256, BaseElement>(lde_domain_size, rows_per_leaf, 2)
+                    .unwrap();
+                assert_eq!(opened.trace, leaves.num_rows());
+            }
+            CommitmentLayout::RowMajor => {
+                assert!(trace_queries
+                    .parse::<Blake3_256, BaseElement>(lde_domain_size, opened.trace, 2)
+                    .is_ok());
+            }
+            CommitmentLayout::PerColumn => {
+                assert!(trace_queries
+                    .parse_per_column::<Blake3_256, BaseElement>(lde_domain_size, opened.trace, 2)
+                    .is_ok());
+            }
+        }
+
+        let (layer_queries, _) = proof
+            .fri_proof
+            .clone()
+            .parse_layers::<Blake3_256, BaseElement>(lde_domain_size, folding_factor)
+            .unwrap();
+        let fri_layers = layer_queries
+            .iter()
+            .map(|values| values.len() / folding_factor)
+            .collect::<Vec<_>>();
+        assert_eq!(opened.fri_layers, fri_layers);
+
+        let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+        assert!(winterfell::verify::<FibAir, Blake3_256, RandCoin>(
+            proof,
+            result,
+            &acceptable_options
+        )
+        .is_ok());
+    }
+}
+
+#[test]
+fn signed_proof() {
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let verify = |proof: SignedProof<Lamport>| {
+        winterfell::verify_signed::<FibAir, Blake3_256, RandCoin, Lamport>(
+            proof,
+            result,
+            &acceptable_options,
+        )
+    };
+
+    // a signed proof survives serialization and is accepted
+    let (secret_key, public_key) = Lamport::generate_keys(b"signer");
+    let signed = SignedProof::<Lamport>::sign(proof, &secret_key, public_key);
+    assert!(signed.verify_signature());
+    assert_eq!(signed, SignedProof::from_bytes(&signed.to_bytes()).unwrap());
+    assert_eq!(Ok(()), verify(signed.clone()));
+
+    // a tampered proof does not match the signature
+    let mut tampered = signed.clone();
+    tampered.proof.pow_nonce += 1;
+    assert!(!tampered.verify_signature());
+    assert_eq!(Err(VerifierError::InvalidProofSignature), verify(tampered));
+
+    // neither does a signature made with a different key
+    let mut forged = signed;
+    forged.public_key = Lamport::generate_keys(b"forger").1;
+    assert_eq!(Err(VerifierError::InvalidProofSignature), verify(forged));
+}
+
+#[test]
+fn signed_proof_ed25519() {
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let verify = |proof: SignedProof<Ed25519>| {
+        winterfell::verify_signed::<FibAir, Blake3_256, RandCoin, Ed25519>(
+            proof,
+            result,
+            &acceptable_options,
+        )
+    };
+
+    // a signed proof survives serialization and is accepted
+    let secret_key = Ed25519SecretKey::from_bytes(&[7; 32]);
+    let public_key = Ed25519PublicKey::from_secret_key(&secret_key);
+    let signed = SignedProof::<Ed25519>::sign(proof, &secret_key, public_key);
+    assert!(signed.verify_signature());
+    assert_eq!(signed, SignedProof::from_bytes(&signed.to_bytes()).unwrap());
+    assert_eq!(Ok(()), verify(signed.clone()));
+
+    // a tampered proof does not match the signature
+    let mut tampered = signed.clone();
+    tampered.proof.pow_nonce += 1;
+    assert_eq!(Err(VerifierError::InvalidProofSignature), verify(tampered));
+
+    // neither does a signature made with a different key
+    let mut forged = signed;
+    forged.public_key = Ed25519PublicKey::from_secret_key(&Ed25519SecretKey::from_bytes(&[9; 32]));
+    assert_eq!(Err(VerifierError::InvalidProofSignature), verify(forged));
+}
+
+// LAMPORT SIGNATURES
+// ================================================================================================
+
+/// A one-time Lamport signature scheme over 256-bit proof identifiers; this stands in for a
+/// production signature scheme in tests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Lamport;
+
+/// A list of hashes; this is used for keys and signatures of the Lamport signature scheme.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HashList(Vec<[u8; 32]>);
+
+impl Lamport {
+    /// Generates a key pair deterministically from the specified seed.
+    fn generate_keys(seed: &[u8]) -> (HashList, HashList) {
+        let secret_key = (0..512u32)
+            .map(|i| hash_bytes(&[seed, &i.to_le_bytes()].concat()))
+            .collect::<Vec<_>>();
+        let public_key = secret_key.iter().map(|key| hash_bytes(key)).collect();
+        (HashList(secret_key), HashList(public_key))
+    }
+}
+
+impl ProofSignatureScheme for Lamport {
+    type SecretKey = HashList;
+    type PublicKey = HashList;
+    type Signature = HashList;
+
+    fn sign(secret_key: &HashList, proof_id: &[u8; 32]) -> HashList {
+        HashList((0..256).map(|i| secret_key.0[2 * i + id_bit(proof_id, i)]).collect())
+    }
+
+    fn verify(public_key: &HashList, proof_id: &[u8; 32], signature: &HashList) -> bool {
+        signature.0.len() == 256
+            && (0..256)
+                .all(|i| hash_bytes(&signature.0[i]) == public_key.0[2 * i + id_bit(proof_id, i)])
+    }
+}
+
+impl Serializable for HashList {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u16(self.0.len() as u16);
+        for hash in self.0.iter() {
+            target.write_bytes(hash);
+        }
+    }
+}
+
+impl Deserializable for HashList {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_hashes = source.read_u16()? as usize;
+        let hashes = (0..num_hashes).map(|_| source.read_array()).collect::<Result<_, _>>()?;
+        Ok(HashList(hashes))
+    }
+}
+
+fn hash_bytes(bytes: &[u8]) -> [u8; 32] {
+    Blake3_256::hash(bytes).as_bytes()
+}
+
+fn id_bit(proof_id: &[u8; 32], i: usize) -> usize {
+    ((proof_id[i / 8] >> (i % 8)) & 1) as usize
+}
@@ -0,0 +1,1017 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! End-to-end tests of the proof generation procedures exposed by the prover.
+
+use core::time::Duration;
+use winterfell::{
+    crypto::{DefaultRandomCoin, Hasher},
+    math::{
+        fft::infer_degree,
+        fields::{f128::BaseElement, f64, QuadExtension},
+        FieldElement, StarkField,
+    },
+    matrix::{ColMatrix, InterpolationMethod},
+    proof::{DualProof, Queries},
+    prove_dry_run_with_costs, AcceptableOptions, Air, AirContext, Assertion, AuxTraceRandElements,
+    BitPackedTraceTable, ConstraintCompositionCoefficients, ConstraintEvaluator,
+    DefaultConstraintEvaluator, DefaultTraceLde, EvaluationFrame, FieldExtension, OperationCosts,
+    PrecomputedDivisors, ProofEstimate, ProofOptions, Prover, ProverError, Serializable,
+    StarkDomain, StarkProof, Trace, TraceCommitmentOptions, TraceInfo, TraceLayout, TraceLde,
+    TracePolyTable, TraceSegment, TraceSource, TraceTable, TraceViolation,
+    TransitionConstraintDegree, TwiddleCache, VerifierError,
+};
+
+mod common;
+use common::*;
+
+#[test]
+fn prove_from_source() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+
+    // rows of the trace are produced lazily by an iterator which advances the sequence by 2 terms
+    let rows = (0..sequence_length / 2).scan([BaseElement::ONE; 2], |state, _| {
+        let row = state.to_vec();
+        state[0] += state[1];
+        state[1] += state[0];
+        Some(row)
+    });
+    let proof = prover
+        .prove_from_source(IterTraceSource {
+            rows,
+            num_columns: 2,
+        })
+        .unwrap();
+
+    // the proof is the same as the one generated from a materialized trace
+    let expected = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    assert_eq!(expected.to_bytes(), proof.to_bytes());
+
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    assert!(winterfell::verify::<FibAir, Blake3_256, DefaultRandomCoin<Blake3_256>>(
+        proof,
+        result,
+        &acceptable_options
+    )
+    .is_ok());
+}
+
+#[test]
+fn bit_packed_trace() {
+    let sequence_length = 64;
+    let options = build_proof_options(false);
+    let fib_trace = build_fib_trace::<BaseElement>(sequence_length);
+    let trace_length = fib_trace.length();
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    // the third column alternates between 0 and 1; in the packed trace it is stored as bits
+    let mut packed_trace = BitPackedTraceTable::new(3, trace_length, &[2]);
+    let mut unpacked_trace = TraceTable::new(3, trace_length);
+    let mut row = vec![BaseElement::ZERO; 3];
+    for step in 0..trace_length {
+        fib_trace.read_row_into(step, &mut row[..2]);
+        row[2] = BaseElement::from((step % 2) as u8);
+        packed_trace.update_row(step, &row);
+        unpacked_trace.update_row(step, &row);
+    }
+
+    let packed_prover = fib_parity_prover::<BitPackedTraceTable<BaseElement>>(options.clone());
+    let unpacked_prover = fib_parity_prover::<TraceTable<BaseElement>>(options);
+    let pub_inputs = packed_prover.get_pub_inputs(&packed_trace);
+    assert_eq!(unpacked_prover.get_pub_inputs(&unpacked_trace), pub_inputs);
+    assert_eq!(compute_fib_term::<BaseElement>(sequence_length), pub_inputs);
+
+    // both traces are committed to in the same way, and thus result in identical proofs
+    let proof = packed_prover.prove(packed_trace).unwrap();
+    let unpacked_proof = unpacked_prover.prove(unpacked_trace).unwrap();
+    assert_eq!(unpacked_proof.to_bytes(), proof.to_bytes());
+
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    assert!(winterfell::verify::<FibParityAir, Blake3_256, RandCoin>(
+        proof.clone(),
+        pub_inputs,
+        &acceptable_options
+    )
+    .is_ok());
+    assert!(winterfell::verify::<FibParityAir, Blake3_256, RandCoin>(
+        proof,
+        pub_inputs + BaseElement::ONE,
+        &acceptable_options
+    )
+    .is_err());
+}
+
+#[test]
+fn dual_proof() {
+    type SmallHasher = winterfell::crypto::hashers::Blake3_256<f64::BaseElement>;
+
+    // prove the same statement over the 128-bit field and over the 64-bit field
+    let sequence_length = 64;
+    let first_prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let second_prover = fib_prover::<SmallHasher>(build_proof_options(true));
+    let prove = |first_length: usize, second_length: usize| {
+        winterfell::prove_dual(
+            &first_prover,
+            build_fib_trace(first_length),
+            &second_prover,
+            build_fib_trace(second_length),
+        )
+        .unwrap()
+    };
+    let proof = prove(sequence_length, sequence_length);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![
+        proof.first.options().clone(),
+        proof.second.options().clone(),
+    ]);
+
+    let first_result = compute_fib_term::<BaseElement>(sequence_length);
+    let second_result = compute_fib_term::<f64::BaseElement>(sequence_length);
+    let verify = |proof: DualProof, first: BaseElement, second: f64::BaseElement| {
+        winterfell::verify_dual::<
+            FibAir,
+            Blake3_256,
+            DefaultRandomCoin<Blake3_256>,
+            FibAir<f64::BaseElement>,
+            SmallHasher,
+            DefaultRandomCoin<SmallHasher>,
+        >(proof, first, second, &acceptable_options)
+    };
+    assert_eq!(Ok(()), verify(proof.clone(), first_result, second_result));
+    assert_eq!(Ok(proof.clone()), DualProof::from_bytes(&proof.to_bytes()));
+
+    // the proof does not verify against wrong public inputs of either computation
+    assert!(verify(proof.clone(), first_result + BaseElement::ONE, second_result).is_err());
+    assert!(verify(proof.clone(), first_result, second_result + f64::BaseElement::ONE).is_err());
+
+    // the components of the proof cannot be replaced by standalone proofs, or by the components
+    // of a different dual proof
+    let standalone = first_prover.prove(build_fib_trace(sequence_length)).unwrap();
+    let mut invalid = proof.clone();
+    invalid.first = standalone;
+    assert!(verify(invalid, first_result, second_result).is_err());
+
+    let other = prove(sequence_length * 2, sequence_length);
+    let mut invalid = proof.clone();
+    invalid.second = other.second;
+    assert!(verify(invalid, first_result, second_result).is_err());
+}
+
+#[test]
+fn proof_without_grinding() {
+    let sequence_length = 64;
+    let options = ProofOptions::new(28, 8, 16, FieldExtension::None, 4, 7);
+    let insecure_options = options.clone().insecure_without_grinding();
+    assert_eq!(0, insecure_options.grinding_factor());
+
+    let prover = fib_prover::<Blake3_256>(insecure_options.clone());
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    assert_eq!(0, proof.pow_nonce);
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    // the proof is accepted when options without grinding are explicitly acceptable
+    let acceptable_options = AcceptableOptions::OptionSet(vec![insecure_options]);
+    assert!(winterfell::verify::<FibAir, Blake3_256, RandCoin>(
+        proof.clone(),
+        result,
+        &acceptable_options
+    )
+    .is_ok());
+
+    // the proof is rejected when only options with grinding are acceptable
+    let acceptable_options = AcceptableOptions::OptionSet(vec![options]);
+    assert_eq!(
+        Err(VerifierError::UnacceptableProofOptions),
+        winterfell::verify::<FibAir, Blake3_256, RandCoin>(proof, result, &acceptable_options)
+    );
+}
+
+#[test]
+fn proof_with_512_bit_digests() {
+    type Blake3_512 = winterfell::crypto::hashers::Blake3_512<BaseElement>;
+
+    let sequence_length = 64;
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    let options = ProofOptions::new(64, 16, 0, FieldExtension::Quadratic, 4, 7);
+
+    let prover = fib_prover::<Blake3_512>(options.clone());
+    let proof_512 = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![options.clone()]);
+    assert!(winterfell::verify::<FibAir, Blake3_512, DefaultRandomCoin<Blake3_512>>(
+        proof_512.clone(),
+        result,
+        &acceptable_options
+    )
+    .is_ok());
+    assert!(winterfell::verify::<FibAir, Blake3_512, DefaultRandomCoin<Blake3_512>>(
+        proof_512.clone(),
+        result + BaseElement::ONE,
+        &acceptable_options
+    )
+    .is_err());
+
+    // the proof survives a serialization round trip
+    let proof_bytes = proof_512.to_bytes();
+    assert_eq!(proof_512, StarkProof::from_bytes(&proof_bytes).unwrap());
+
+    // security of a proof generated with 256-bit digests cannot exceed 128 bits, but with
+    // 512-bit digests, security is bounded only by the proof parameters
+    let prover = fib_prover::<Blake3_256>(options);
+    let proof_256 = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    assert_eq!(128, proof_256.security_level::<Blake3_256>(true));
+    assert!(proof_512.security_level::<Blake3_512>(true) > 128);
+
+    // each commitment takes twice as many bytes in a proof with 512-bit digests
+    let commitments_256 = proof_256.commitments.to_bytes().len() - 2;
+    let commitments_512 = proof_512.commitments.to_bytes().len() - 2;
+    assert_eq!(0, commitments_256 % 32);
+    assert_eq!(2 * commitments_256, commitments_512);
+    assert!(proof_bytes.len() > proof_256.to_bytes().len());
+}
+
+#[test]
+fn twiddle_cache() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+
+    // the trace of a sequence of length n has n / 2 rows
+    let mut twiddle_cache = TwiddleCache::new();
+    twiddle_cache.add_domain(sequence_length / 2);
+    assert_eq!(1, twiddle_cache.num_domains());
+
+    // proofs generated with cached twiddles must be the same as proofs generated without them;
+    // the same cache can be used to generate multiple proofs
+    let expected = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    for _ in 0..2 {
+        let trace = build_fib_trace(sequence_length);
+        let proof = prover.prove_with_twiddle_cache(trace, &twiddle_cache).unwrap();
+        assert_eq!(expected.to_bytes(), proof.to_bytes());
+    }
+
+    // if the cache does not contain twiddles for the trace domain, they are computed on the fly
+    let trace = build_fib_trace(sequence_length);
+    let proof = prover.prove_with_twiddle_cache(trace, &TwiddleCache::new()).unwrap();
+    assert_eq!(expected.to_bytes(), proof.to_bytes());
+}
+
+#[test]
+fn precomputed_divisors() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+
+    // divisors depend only on the AIR and the trace length
+    let trace = build_fib_trace(sequence_length);
+    let air =
+        <FibAir>::new(trace.get_info(), prover.get_pub_inputs(&trace), prover.options().clone());
+    let divisors = PrecomputedDivisors::new(&air);
+    assert_eq!(sequence_length / 2, divisors.trace_length());
+
+    // proofs generated with precomputed divisors must be the same as proofs generated without
+    // them; the same divisors can be used to generate multiple proofs
+    let expected = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    for _ in 0..2 {
+        let trace = build_fib_trace(sequence_length);
+        let proof = prover.prove_with_divisors(trace, &divisors).unwrap();
+        assert_eq!(expected.to_bytes(), proof.to_bytes());
+    }
+
+    // divisors computed for a different trace length are rejected
+    let trace = build_fib_trace(sequence_length * 2);
+    assert_eq!(
+        Err(ProverError::InconsistentPrecomputedDivisors),
+        prover.prove_with_divisors(trace, &divisors)
+    );
+}
+
+#[test]
+fn export_deep_composition() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(true));
+
+    // the trace of a sequence of length n has n / 2 rows
+    let trace = build_fib_trace(sequence_length);
+    let deep = prover.export_deep_composition::<QuadExtension<BaseElement>>(trace).unwrap();
+    assert_eq!(sequence_length / 2 - 2, deep.degree);
+    assert_eq!(deep.domain.size, deep.evaluations.len());
+
+    // the exported evaluations must interpolate to a polynomial of the expected degree
+    assert_eq!(deep.degree, infer_degree(&deep.evaluations, deep.domain.offset));
+
+    // the field extension must match the one specified by proof options
+    let trace = build_fib_trace(sequence_length);
+    assert_eq!(
+        Err(ProverError::MismatchedFieldExtension(2, 1)),
+        prover.export_deep_composition::<BaseElement>(trace)
+    );
+}
+
+#[test]
+fn base_field_composition_matches_extension() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let trace = build_fib_trace(sequence_length);
+    let result = prover.get_pub_inputs(&trace);
+    let air = <FibAir>::new(trace.get_info(), result, build_proof_options(false));
+    let domain = StarkDomain::new(&air);
+
+    // draw composition coefficients from the base field
+    let coefficients = ConstraintCompositionCoefficients {
+        transition: (1..=air.context().num_main_transition_constraints() as u32)
+            .map(BaseElement::from)
+            .collect(),
+        boundary: (1..=air.context().num_assertions() as u32)
+            .map(|i| BaseElement::from(i * 7))
+            .collect(),
+        wraparound: Vec::new(),
+        range_check: Vec::new(),
+    };
+
+    // compose constraints entirely in the base field
+    let (trace_lde, _) = DefaultTraceLde::<BaseElement, Blake3_256>::new(
+        &trace.get_info(),
+        trace.main_segment(),
+        &domain,
+        TraceCommitmentOptions::default(),
+    );
+    let evaluator =
+        DefaultConstraintEvaluator::new(&air, AuxTraceRandElements::new(), coefficients.clone());
+    let base_evaluations = evaluator.evaluate(&trace_lde, &domain).into_inner();
+
+    // compose the same constraints using extension field arithmetic
+    let (trace_lde, _) = DefaultTraceLde::<QuadExtension<BaseElement>, Blake3_256>::new(
+        &trace.get_info(),
+        trace.main_segment(),
+        &domain,
+        TraceCommitmentOptions::default(),
+    );
+    let coefficients = ConstraintCompositionCoefficients {
+        transition: coefficients.transition.into_iter().map(QuadExtension::from).collect(),
+        boundary: coefficients.boundary.into_iter().map(QuadExtension::from).collect(),
+        wraparound: Vec::new(),
+        range_check: Vec::new(),
+    };
+    let evaluator =
+        DefaultConstraintEvaluator::new(&air, AuxTraceRandElements::new(), coefficients);
+    let ext_evaluations = evaluator.evaluate(&trace_lde, &domain).into_inner();
+
+    let lifted_evaluations =
+        base_evaluations.into_iter().map(QuadExtension::from).collect::<Vec<_>>();
+    assert_eq!(lifted_evaluations, ext_evaluations);
+}
+
+#[test]
+fn domain_exceeds_two_adicity() {
+    // with blowup factor of 8, a trace with 2^(TWO_ADICITY - 3) steps requires an LDE domain of
+    // exactly 2^TWO_ADICITY elements; a trace twice as long cannot be extended
+    let options = build_proof_options(false);
+    assert_eq!(8, options.blowup_factor());
+    let prover = fib_parity_prover::<ClaimedLengthTrace>(options);
+
+    let trace = ClaimedLengthTrace::new(3, 1 << (BaseElement::TWO_ADICITY - 2));
+    assert_eq!(
+        Err(ProverError::DomainExceedsTwoAdicity(
+            BaseElement::TWO_ADICITY + 1,
+            BaseElement::TWO_ADICITY
+        )),
+        prover.prove(trace)
+    );
+}
+
+#[test]
+fn dry_run() {
+    let options = build_proof_options(false);
+    let prover = fib_prover::<Blake3_256>(options.clone());
+
+    // use fixed operation costs so that estimates do not depend on the machine
+    let costs = OperationCosts {
+        fft: 1.0,
+        mul: 1.0,
+        hash_element: 1.0,
+        hash_merge: 1.0,
+    };
+
+    let mut prev_estimate: Option<ProofEstimate> = None;
+    for sequence_length in [256, 1024, 4096] {
+        let trace = build_fib_trace(sequence_length);
+        let estimate = prove_dry_run_with_costs::<BaseElement, Blake3_256>(
+            &trace.get_info(),
+            &options,
+            &costs,
+        );
+        assert!(estimate.proving_time > Duration::ZERO);
+        assert!(estimate.peak_memory > 0);
+        if let Some(prev) = prev_estimate {
+            assert!(estimate.proving_time > prev.proving_time);
+            assert!(estimate.peak_memory > prev.peak_memory);
+            assert!(estimate.proof_size > prev.proof_size);
+        }
+        prev_estimate = Some(estimate);
+
+        // estimated proof size is in the same ballpark as the actual proof size
+        let proof_size = prover.prove(trace).unwrap().to_bytes().len();
+        assert!(estimate.proof_size > proof_size / 2, "{} vs {proof_size}", estimate.proof_size);
+        assert!(estimate.proof_size < proof_size * 2, "{} vs {proof_size}", estimate.proof_size);
+    }
+}
+
+#[test]
+fn custom_trace_lde_backend() {
+    let sequence_length = 64;
+    let options = build_proof_options(false);
+    let prover = fib_prover::<Blake3_256>(options.clone());
+    let backend_prover = FibBackendProver(fib_prover::<Blake3_256>(options));
+
+    // a backend plugged in via the TraceLde trait produces exactly the same proof as the default
+    // CPU backend
+    let trace = build_fib_trace(sequence_length);
+    let proof = prover.prove(trace.clone()).unwrap();
+    let backend_proof = backend_prover.prove(trace).unwrap();
+    assert_eq!(proof.to_bytes(), backend_proof.to_bytes());
+
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![backend_proof.options().clone()]);
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+    assert!(winterfell::verify::<FibAir, Blake3_256, RandCoin>(
+        backend_proof,
+        result,
+        &acceptable_options
+    )
+    .is_ok());
+}
+
+#[test]
+fn barycentric_interpolation() {
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    // traces of the minimum length are interpolated using the barycentric method by default
+    assert_eq!(InterpolationMethod::Barycentric, InterpolationMethod::default().resolve(8));
+
+    for sequence_length in [16, 32] {
+        let options = build_proof_options(false);
+        let prover = fib_prover::<Blake3_256>(options.clone());
+        let trace = build_fib_trace(sequence_length);
+        let proof = prover.prove(trace.clone()).unwrap();
+
+        // proofs are the same regardless of how trace columns are interpolated
+        for interpolation in [InterpolationMethod::Barycentric, InterpolationMethod::Fft] {
+            let interpolating_prover =
+                FibInterpolationProver(fib_prover::<Blake3_256>(options.clone()), interpolation);
+            let interpolated_proof = interpolating_prover.prove(trace.clone()).unwrap();
+            assert_eq!(proof.to_bytes(), interpolated_proof.to_bytes());
+        }
+
+        let result = compute_fib_term::<BaseElement>(sequence_length);
+        let acceptable_options = AcceptableOptions::OptionSet(vec![options]);
+        assert!(winterfell::verify::<FibAir, Blake3_256, RandCoin>(
+            proof,
+            result,
+            &acceptable_options
+        )
+        .is_ok());
+    }
+}
+
+#[test]
+fn check_trace() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+
+    // a valid trace yields an empty report
+    let trace = build_fib_trace(sequence_length);
+    let report = prover.check_trace(trace.clone(), result);
+    assert!(report.is_valid(), "{report}");
+
+    // changing the first state element violates both the assertion against the first step and
+    // the first transition constraint at the first step; both are reported
+    let mut trace = trace;
+    trace.set(0, 0, BaseElement::new(2));
+    let report = prover.check_trace(trace, result);
+    assert!(!report.is_valid());
+    assert_eq!(
+        &[
+            TraceViolation::Assertion {
+                segment: TraceSegment::Main,
+                assertion: Assertion::single(0, 0, BaseElement::ONE).to_string(),
+                step: 0,
+            },
+            TraceViolation::Transition {
+                segment: TraceSegment::Main,
+                constraint: 0,
+                step: 0,
+            },
+        ],
+        report.violations()
+    );
+}
+
+// FIBONACCI WITH PARITY COLUMN
+// ================================================================================================
+
+/// Same as the Fibonacci AIR, but with an additional binary column which alternates between 0 and
+/// 1 starting with 0.
+struct FibParityAir {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for FibParityAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: Self::BaseField, options: ProofOptions) -> Self {
+        let degrees = vec![
+            TransitionConstraintDegree::new(1),
+            TransitionConstraintDegree::new(1),
+            TransitionConstraintDegree::new(1),
+        ];
+        assert_eq!(3, trace_info.width());
+        FibParityAir {
+            context: AirContext::new(trace_info, degrees, 4, options),
+            result: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = are_equal(next[0], current[0] + current[1]);
+        result[1] = are_equal(next[1], current[1] + next[0]);
+        result[2] = are_equal(next[2], E::ONE - current[2]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, Self::BaseField::ONE),
+            Assertion::single(1, 0, Self::BaseField::ONE),
+            Assertion::single(2, 0, Self::BaseField::ZERO),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+}
+
+/// A prover for [FibParityAir] which is generic over the type of the execution trace.
+type FibParityProver<T> = TestProver<FibParityAir, T>;
+
+fn fib_parity_prover<T: Trace<BaseField = BaseElement>>(
+    options: ProofOptions,
+) -> FibParityProver<T> {
+    TestProver::new(options, |trace| {
+        let mut frame = EvaluationFrame::new(trace.main_trace_width());
+        trace.read_main_frame(trace.length() - 1, &mut frame);
+        frame.current()[1]
+    })
+}
+
+// TRACE WITH CLAIMED LENGTH
+// ================================================================================================
+
+/// A trace which reports the specified length without materializing any of its rows; all reads
+/// from the trace return zeros. This is used to exercise prover checks which must fail before
+/// the main trace segment is accessed.
+struct ClaimedLengthTrace {
+    layout: TraceLayout,
+    length: usize,
+}
+
+impl ClaimedLengthTrace {
+    fn new(width: usize, length: usize) -> Self {
+        Self {
+            layout: TraceLayout::new(width, [0], [0]),
+            length,
+        }
+    }
+}
+
+impl Trace for ClaimedLengthTrace {
+    type BaseField = BaseElement;
+
+    fn layout(&self) -> &TraceLayout {
+        &self.layout
+    }
+
+    fn length(&self) -> usize {
+        self.length
+    }
+
+    fn meta(&self) -> &[u8] {
+        &[]
+    }
+
+    fn main_segment(&self) -> &ColMatrix<BaseElement> {
+        unreachable!("main segment of a trace with claimed length cannot be accessed")
+    }
+
+    fn build_aux_segment<E: FieldElement<BaseField = BaseElement>>(
+        &mut self,
+        _aux_segments: &[ColMatrix<E>],
+        _rand_elements: &[E],
+    ) -> Option<ColMatrix<E>> {
+        None
+    }
+
+    fn read_main_frame(&self, _row_idx: usize, _frame: &mut EvaluationFrame<BaseElement>) {}
+}
+
+// FIBONACCI WITH RANDOM START
+// ================================================================================================
+
+mod random_start {
+    use super::*;
+    use winterfell::math::ToElements;
+
+    #[test]
+    fn random_traces() {
+        let trace_length = 32;
+        let prover =
+            TestProver::<FibRandomStartAir>::new(build_proof_options(false), random_start_inputs);
+        type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+        for seed in 0..8 {
+            // build a Fibonacci sequence starting from two random terms
+            let trace = TraceTable::random(2, trace_length, seed, |_, state| {
+                state[0] += state[1];
+                state[1] += state[0];
+            });
+            assert_ne!(BaseElement::ONE, trace.get(0, 0));
+
+            let pub_inputs = prover.get_pub_inputs(&trace);
+            let proof = prover.prove(trace).unwrap();
+            let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+            assert!(winterfell::verify::<FibRandomStartAir, Blake3_256, RandCoin>(
+                proof.clone(),
+                pub_inputs,
+                &acceptable_options
+            )
+            .is_ok());
+
+            // the proof does not verify against a different starting state
+            let wrong_inputs = FibRandomStartInputs {
+                start: [pub_inputs.start[0] + BaseElement::ONE, pub_inputs.start[1]],
+                result: pub_inputs.result,
+            };
+            assert!(winterfell::verify::<FibRandomStartAir, Blake3_256, RandCoin>(
+                proof,
+                wrong_inputs,
+                &acceptable_options
+            )
+            .is_err());
+        }
+
+        // the same seed always results in the same trace
+        let update = |_: usize, state: &mut [BaseElement]| state[0] += state[1];
+        let trace1 = TraceTable::<BaseElement>::random(2, 8, 42, update);
+        let trace2 = TraceTable::<BaseElement>::random(2, 8, 42, update);
+        assert_eq!(trace1.get_column(0), trace2.get_column(0));
+        assert_eq!(trace1.get_column(1), trace2.get_column(1));
+    }
+
+    /// Public inputs for a Fibonacci sequence which starts from arbitrary terms.
+    #[derive(Clone, Copy)]
+    struct FibRandomStartInputs {
+        start: [BaseElement; 2],
+        result: BaseElement,
+    }
+
+    impl ToElements<BaseElement> for FibRandomStartInputs {
+        fn to_elements(&self) -> Vec<BaseElement> {
+            vec![self.start[0], self.start[1], self.result]
+        }
+    }
+
+    /// Same as the Fibonacci AIR, but the first two terms of the sequence are public inputs.
+    struct FibRandomStartAir {
+        context: AirContext<BaseElement>,
+        pub_inputs: FibRandomStartInputs,
+    }
+
+    impl Air for FibRandomStartAir {
+        type BaseField = BaseElement;
+        type PublicInputs = FibRandomStartInputs;
+
+        fn new(
+            trace_info: TraceInfo,
+            pub_inputs: Self::PublicInputs,
+            options: ProofOptions,
+        ) -> Self {
+            let degrees =
+                vec![TransitionConstraintDegree::new(1), TransitionConstraintDegree::new(1)];
+            FibRandomStartAir {
+                context: AirContext::new(trace_info, degrees, 3, options),
+                pub_inputs,
+            }
+        }
+
+        fn context(&self) -> &AirContext<Self::BaseField> {
+            &self.context
+        }
+
+        fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+            &self,
+            frame: &EvaluationFrame<E>,
+            _periodic_values: &[E],
+            result: &mut [E],
+        ) {
+            let current = frame.current();
+            let next = frame.next();
+            result[0] = are_equal(next[0], current[0] + current[1]);
+            result[1] = are_equal(next[1], current[1] + next[0]);
+        }
+
+        fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+            let last_step = self.trace_length() - 1;
+            vec![
+                Assertion::single(0, 0, self.pub_inputs.start[0]),
+                Assertion::single(1, 0, self.pub_inputs.start[1]),
+                Assertion::single(1, last_step, self.pub_inputs.result),
+            ]
+        }
+    }
+
+    fn random_start_inputs(trace: &TraceTable<BaseElement>) -> FibRandomStartInputs {
+        FibRandomStartInputs {
+            start: [trace.get(0, 0), trace.get(1, 0)],
+            result: trace.get(1, trace.length() - 1),
+        }
+    }
+}
+
+// ITERATOR TRACE SOURCE
+// ================================================================================================
+
+/// A trace source which yields rows of an execution trace from an iterator.
+struct IterTraceSource<I> {
+    rows: I,
+    num_columns: usize,
+}
+
+impl<I: Iterator<Item = Vec<BaseElement>>> TraceSource for IterTraceSource<I> {
+    type BaseField = BaseElement;
+    type Rows = I;
+
+    fn num_columns(&self) -> usize {
+        self.num_columns
+    }
+
+    fn into_rows(self) -> Self::Rows {
+        self.rows
+    }
+}
+
+// FIBONACCI WITH FIXED CHALLENGES
+// ================================================================================================
+
+mod fixed_challenges {
+    use super::*;
+    use winterfell::crypto::{ChallengeSequence, FixedRandomCoin};
+
+    #[test]
+    fn fixed_challenge_sequence() {
+        let sequence_length = 64;
+        let options = ProofOptions::new(28, 8, 16, FieldExtension::None, 4, 7);
+        let prover: FibFixedChallengeProver<ChallengesA> =
+            TestProver::new(options.clone(), fib_result);
+        let trace = build_fib_trace(sequence_length);
+        let acceptable_options = AcceptableOptions::OptionSet(vec![options.clone()]);
+        let result = compute_fib_term::<BaseElement>(sequence_length);
+
+        // a fixed challenge sequence yields the same proof every time
+        let proof = prover.prove(trace.clone()).unwrap();
+        assert_eq!(proof.to_bytes(), prover.prove(trace.clone()).unwrap().to_bytes());
+        assert_eq!(1, proof.pow_nonce);
+
+        // the proof verifies only with the same challenge sequence
+        type CoinA = FixedRandomCoin<Blake3_256, ChallengesA>;
+        type CoinB = FixedRandomCoin<Blake3_256, ChallengesB>;
+        assert!(winterfell::verify::<FibAir, Blake3_256, CoinA>(
+            proof.clone(),
+            result,
+            &acceptable_options
+        )
+        .is_ok());
+        assert!(winterfell::verify::<FibAir, Blake3_256, CoinB>(
+            proof.clone(),
+            result,
+            &acceptable_options
+        )
+        .is_err());
+        assert!(winterfell::verify::<FibAir, Blake3_256, DefaultRandomCoin<Blake3_256>>(
+            proof.clone(),
+            result,
+            &acceptable_options
+        )
+        .is_err());
+
+        // a different challenge sequence yields a different proof
+        let other_prover: FibFixedChallengeProver<ChallengesB> =
+            TestProver::new(options, fib_result);
+        let other_proof = other_prover.prove(trace).unwrap();
+        assert_ne!(proof.to_bytes(), other_proof.to_bytes());
+        assert!(winterfell::verify::<FibAir, Blake3_256, CoinB>(
+            other_proof,
+            result,
+            &acceptable_options
+        )
+        .is_ok());
+    }
+
+    struct ChallengesA;
+
+    impl ChallengeSequence for ChallengesA {
+        const CHALLENGES: &'static [u64] = &[
+            0x243f_6a88_85a3_08d3,
+            0x1319_8a2e_0370_7344,
+            0xa409_3822_299f_31d0,
+            0x082e_fa98_ec4e_6c89,
+            0x4528_21e6_38d0_1377,
+            0xbe54_66cf_34e9_0c6c,
+            0xc0ac_29b7_c97c_50dd,
+            0x3f84_d5b5_b547_0917,
+            0x9216_d5d9_8979_fb1b,
+            0xd131_0ba6_98df_b5ac,
+            0x2ffd_72db_d01a_dfb7,
+            0xb8e1_afed_6a26_7e96,
+            0xba7c_9045_f12c_7f99,
+            0x24a1_9947_b391_6cf7,
+            0x0801_f2e2_858e_fc16,
+            0x6369_20d8_7157_4e69,
+        ];
+    }
+
+    struct ChallengesB;
+
+    impl ChallengeSequence for ChallengesB {
+        const CHALLENGES: &'static [u64] = &[
+            0xb7e1_5162_8aed_2a6a,
+            0xbf71_5880_9cf4_f3c7,
+            0x62e7_160f_38b4_da56,
+            0xa784_d904_5190_cfef,
+            0x324e_7738_926c_fbe5,
+            0xf4bf_8d8d_8c31_d763,
+            0xda06_c80a_bb11_85eb,
+            0x4f7c_7b57_57f5_9584,
+        ];
+    }
+
+    /// Same as the Fibonacci prover, but with challenges read from a fixed sequence.
+    type FibFixedChallengeProver<S> =
+        TestProver<FibAir, TraceTable<BaseElement>, Blake3_256, FixedRandomCoin<Blake3_256, S>>;
+}
+
+// FIBONACCI WITH CUSTOM TRACE LDE BACKEND
+// ================================================================================================
+
+/// A trace LDE backend which delegates all work to the default CPU backend; this stands in for a
+/// hardware-accelerated backend plugged into the prover via the [TraceLde] trait.
+struct DelegatingTraceLde<E: FieldElement<BaseField = BaseElement>>(DefaultTraceLde<E, Blake3_256>);
+
+impl<E: FieldElement<BaseField = BaseElement>> TraceLde<E> for DelegatingTraceLde<E> {
+    type HashFn = Blake3_256;
+
+    fn get_main_trace_commitment(&self) -> <Self::HashFn as Hasher>::Digest {
+        self.0.get_main_trace_commitment()
+    }
+
+    fn add_aux_segment(
+        &mut self,
+        aux_trace: &ColMatrix<E>,
+        domain: &StarkDomain<BaseElement>,
+    ) -> (ColMatrix<E>, <Self::HashFn as Hasher>::Digest) {
+        self.0.add_aux_segment(aux_trace, domain)
+    }
+
+    fn read_main_trace_frame_into(
+        &self,
+        lde_step: usize,
+        frame: &mut EvaluationFrame<BaseElement>,
+    ) {
+        self.0.read_main_trace_frame_into(lde_step, frame)
+    }
+
+    fn read_aux_trace_frame_into(&self, lde_step: usize, frame: &mut EvaluationFrame<E>) {
+        self.0.read_aux_trace_frame_into(lde_step, frame)
+    }
+
+    fn query(&self, positions: &[usize]) -> Vec<Queries> {
+        self.0.query(positions)
+    }
+
+    fn trace_len(&self) -> usize {
+        self.0.trace_len()
+    }
+
+    fn blowup(&self) -> usize {
+        self.0.blowup()
+    }
+
+    fn trace_layout(&self) -> &TraceLayout {
+        self.0.trace_layout()
+    }
+}
+
+/// Same as the Fibonacci prover, but with a custom trace LDE backend.
+struct FibBackendProver(FibProver<Blake3_256>);
+
+impl Prover for FibBackendProver {
+    type BaseField = BaseElement;
+    type Air = FibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Blake3_256;
+    type RandomCoin = DefaultRandomCoin<Self::HashFn>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DelegatingTraceLde<E>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        self.0.get_pub_inputs(trace)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        self.0.options()
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+        commitment_options: TraceCommitmentOptions,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        let (trace_lde, trace_polys) =
+            DefaultTraceLde::new(trace_info, main_trace, domain, commitment_options);
+        (DelegatingTraceLde(trace_lde), trace_polys)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
+
+// FIBONACCI WITH CUSTOM INTERPOLATION
+// ================================================================================================
+
+/// Same as the Fibonacci prover, but with trace columns interpolated using the specified method.
+struct FibInterpolationProver(FibProver<Blake3_256>, InterpolationMethod);
+
+impl Prover for FibInterpolationProver {
+    type BaseField = BaseElement;
+    type Air = FibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Blake3_256;
+    type RandomCoin = DefaultRandomCoin<Self::HashFn>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        self.0.get_pub_inputs(trace)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        self.0.options()
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+        commitment_options: TraceCommitmentOptions,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::with_interpolation(
+            trace_info,
+            main_trace,
+            domain,
+            commitment_options,
+            self.1,
+        )
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
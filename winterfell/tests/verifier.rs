@@ -0,0 +1,1187 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! End-to-end tests of the verification procedures exposed by the verifier.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use rand::{rngs::StdRng, SeedableRng};
+use winterfell::{
+    crypto::{DefaultRandomCoin, ElementHasher, Hasher, IntegerSampling, MerkleTree},
+    math::{fields::f128::BaseElement, fields::f64, fields::QuadExtension, FieldElement},
+    proof::{CommitmentRoots, HashFunctionId, IdentifiableHasher, Queries, QueryResponse},
+    AcceptableOptions, Air, CommitmentLayout, Deserializable, DeserializationError, Executor,
+    FieldExtension, ProofOptions, Prover, ProverError, SequentialExecutor, Serializable,
+    StarkProof, Trace, VerificationKey, VerificationLimits, VerifierChallenges, VerifierError,
+};
+
+mod common;
+use common::{hash_chain::*, *};
+
+#[test]
+fn packed_query_sampling() {
+    check_fib_proof(
+        16,
+        build_proof_options(false).with_query_sampling(IntegerSampling::PackedBits),
+    );
+}
+
+#[test]
+fn rejection_query_sampling() {
+    check_fib_proof(16, build_proof_options(false).with_query_sampling(IntegerSampling::Rejection));
+}
+
+#[test]
+fn per_column_commitment() {
+    check_fib_proof(
+        16,
+        build_proof_options(false).with_commitment_layout(CommitmentLayout::PerColumn),
+    );
+}
+
+#[test]
+fn sorted_fri_queries() {
+    check_fib_proof(64, build_proof_options(false).with_sorted_fri_queries());
+}
+
+#[test]
+fn rows_per_leaf() {
+    for rows_per_leaf in [1, 4] {
+        check_fib_proof(16, build_proof_options(false).with_rows_per_leaf(rows_per_leaf));
+    }
+}
+
+#[test]
+fn trusted_trace_commitment() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    // read the commitment to the main trace segment from the proof
+    let num_fri_layers = proof.options().to_fri_options().num_fri_layers(proof.lde_domain_size());
+    let (trace_roots, _, _) =
+        proof.commitments.clone().parse::<Blake3_256>(1, num_fri_layers).unwrap();
+
+    // the proof verifies relative to the correct trace commitment
+    assert!(winterfell::verify_with_trusted_commitment::<FibAir, Blake3_256, RandCoin>(
+        proof.clone(),
+        result,
+        &acceptable_options,
+        &trace_roots[0],
+    )
+    .is_ok());
+
+    // the proof is rejected relative to a wrong trace commitment
+    let wrong_root = Blake3_256::hash(b"wrong trace commitment");
+    assert!(winterfell::verify_with_trusted_commitment::<FibAir, Blake3_256, RandCoin>(
+        proof,
+        result,
+        &acceptable_options,
+        &wrong_root,
+    )
+    .is_err());
+}
+
+#[test]
+fn accumulated_trace_commitment() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    // build an accumulator which contains the trace commitment of the proof together with
+    // commitments of other proofs
+    let num_fri_layers = proof.options().to_fri_options().num_fri_layers(proof.lde_domain_size());
+    let (trace_roots, _, _) =
+        proof.commitments.clone().parse::<Blake3_256>(1, num_fri_layers).unwrap();
+    let index = 5;
+    let mut leaves = (0..8u8).map(|i| Blake3_256::hash(&[i])).collect::<Vec<_>>();
+    leaves[index] = trace_roots[0];
+    let accumulator = MerkleTree::<Blake3_256>::new(leaves).unwrap();
+    let root = *accumulator.root();
+
+    // the proof verifies with a valid membership proof
+    let path = accumulator.prove(index).unwrap();
+    assert!(winterfell::verify_with_accumulator::<FibAir, Blake3_256, RandCoin>(
+        proof.clone(),
+        result,
+        &acceptable_options,
+        &root,
+        index,
+        &path,
+    )
+    .is_ok());
+
+    // the membership proof is rejected for a wrong index, a tampered path, or a path which is
+    // too short
+    let verify = |index: usize, path: &[<Blake3_256 as Hasher>::Digest]| {
+        winterfell::verify_with_accumulator::<FibAir, Blake3_256, RandCoin>(
+            proof.clone(),
+            result,
+            &acceptable_options,
+            &root,
+            index,
+            path,
+        )
+    };
+    assert_eq!(Err(VerifierError::InvalidAccumulatorMembership), verify(index + 2, &path));
+    assert_eq!(Err(VerifierError::InvalidAccumulatorMembership), verify(index + 8, &path));
+    let mut tampered = path.clone();
+    tampered[2] = Blake3_256::hash(b"wrong sibling");
+    assert_eq!(Err(VerifierError::InvalidAccumulatorMembership), verify(index, &tampered));
+    assert_eq!(Err(VerifierError::InvalidAccumulatorMembership), verify(index, &path[..1]));
+
+    // a valid membership proof for another commitment in the accumulator does not verify the
+    // proof
+    let other_path = accumulator.prove(2).unwrap();
+    let result = verify(2, &other_path);
+    assert!(result.is_err());
+    assert_ne!(Err(VerifierError::InvalidAccumulatorMembership), result);
+}
+
+#[test]
+fn external_trace_commitment() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    let trace = build_fib_trace(sequence_length);
+    let slim_proof = prover.prove_without_trace_commitment(trace).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    // the slimmed proof is smaller than the full proof by exactly one digest
+    let num_fri_layers = proof.options().to_fri_options().num_fri_layers(proof.lde_domain_size());
+    let (trace_roots, _, _) =
+        proof.commitments.clone().parse::<Blake3_256>(1, num_fri_layers).unwrap();
+    let trace_root = trace_roots[0];
+    assert_eq!(
+        proof.to_bytes().len(),
+        slim_proof.to_bytes().len() + trace_root.to_bytes().len()
+    );
+
+    // the slimmed proof survives serialization and verifies relative to the external commitment
+    let slim_proof = StarkProof::from_bytes(&slim_proof.to_bytes()).unwrap();
+    assert!(winterfell::verify_with_external_commitment::<FibAir, Blake3_256, RandCoin>(
+        slim_proof.clone(),
+        result,
+        &acceptable_options,
+        &trace_root,
+    )
+    .is_ok());
+
+    // the slimmed proof is rejected relative to a wrong commitment, or without any commitment
+    let wrong_root = Blake3_256::hash(b"wrong trace commitment");
+    assert!(winterfell::verify_with_external_commitment::<FibAir, Blake3_256, RandCoin>(
+        slim_proof.clone(),
+        result,
+        &acceptable_options,
+        &wrong_root,
+    )
+    .is_err());
+    assert!(winterfell::verify::<FibAir, Blake3_256, RandCoin>(
+        slim_proof,
+        result,
+        &acceptable_options
+    )
+    .is_err());
+
+    // a full proof is rejected when the commitment is also supplied externally
+    assert!(winterfell::verify_with_external_commitment::<FibAir, Blake3_256, RandCoin>(
+        proof,
+        result,
+        &acceptable_options,
+        &trace_root,
+    )
+    .is_err());
+}
+
+#[test]
+fn anchored_commitment_roots() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    // the roots are the same as the parsed commitments, and survive a serialization round trip
+    let roots = proof.commitment_roots::<Blake3_256>().unwrap();
+    let num_fri_layers = proof.options().to_fri_options().num_fri_layers(proof.lde_domain_size());
+    let (trace_roots, constraint_root, fri_roots) =
+        proof.commitments.clone().parse::<Blake3_256>(1, num_fri_layers).unwrap();
+    assert_eq!(trace_roots, roots.trace_roots());
+    assert_eq!(&constraint_root, roots.constraint_root());
+    assert_eq!(fri_roots, roots.fri_roots());
+    assert_eq!(num_fri_layers + 1, roots.fri_roots().len());
+    assert_eq!(roots, CommitmentRoots::read_from_bytes(&roots.to_bytes()).unwrap());
+
+    // the proof verifies against the anchored roots
+    assert!(winterfell::verify_with_anchored_roots::<FibAir, Blake3_256, RandCoin>(
+        proof.clone(),
+        result,
+        &acceptable_options,
+        &roots,
+    )
+    .is_ok());
+
+    // the proof is rejected if any of the anchored roots differ from the roots in the proof
+    let wrong_root = Blake3_256::hash(b"wrong commitment root");
+    let mut wrong_fri_roots = fri_roots.clone();
+    wrong_fri_roots[1] = wrong_root;
+    let wrong_roots = [
+        CommitmentRoots::new(vec![wrong_root], constraint_root, fri_roots.clone()),
+        CommitmentRoots::new(trace_roots.clone(), wrong_root, fri_roots.clone()),
+        CommitmentRoots::new(trace_roots.clone(), constraint_root, wrong_fri_roots),
+        CommitmentRoots::new(trace_roots, constraint_root, fri_roots[1..].to_vec()),
+    ];
+    for anchored_roots in wrong_roots.iter() {
+        assert_eq!(
+            Err(VerifierError::InconsistentCommitmentRoots),
+            winterfell::verify_with_anchored_roots::<FibAir, Blake3_256, RandCoin>(
+                proof.clone(),
+                result,
+                &acceptable_options,
+                anchored_roots,
+            )
+        );
+    }
+
+    // roots of a different proof are rejected as well
+    let other_proof = prover.prove(build_fib_trace(sequence_length * 2)).unwrap();
+    let other_roots = other_proof.commitment_roots::<Blake3_256>().unwrap();
+    assert_eq!(
+        Err(VerifierError::InconsistentCommitmentRoots),
+        winterfell::verify_with_anchored_roots::<FibAir, Blake3_256, RandCoin>(
+            proof,
+            result,
+            &acceptable_options,
+            &other_roots,
+        )
+    );
+}
+
+#[test]
+fn verify_any() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    // exactly one of the candidates matches the proof
+    let candidates =
+        [result + BaseElement::ONE, result.double(), result, result - BaseElement::ONE];
+    assert_eq!(
+        Ok(Some(2)),
+        winterfell::verify_any::<FibAir, Blake3_256, RandCoin>(
+            proof.clone(),
+            &candidates,
+            &acceptable_options
+        )
+    );
+
+    // none of the candidates match the proof
+    assert_eq!(
+        Ok(None),
+        winterfell::verify_any::<FibAir, Blake3_256, RandCoin>(
+            proof.clone(),
+            &[candidates[0], candidates[1], candidates[3]],
+            &acceptable_options
+        )
+    );
+    assert_eq!(
+        Ok(None),
+        winterfell::verify_any::<FibAir, Blake3_256, RandCoin>(
+            proof.clone(),
+            &[],
+            &acceptable_options
+        )
+    );
+
+    // input-independent failures are reported as errors
+    let mut proof_with_metadata = proof.clone();
+    proof_with_metadata.metadata = b"metadata".to_vec();
+    assert_eq!(
+        Err(VerifierError::InconsistentMetadata),
+        winterfell::verify_any::<FibAir, Blake3_256, RandCoin>(
+            proof_with_metadata,
+            &candidates,
+            &acceptable_options
+        )
+    );
+    let other_options = AcceptableOptions::OptionSet(vec![build_proof_options(true)]);
+    assert!(winterfell::verify_any::<FibAir, Blake3_256, RandCoin>(
+        proof.clone(),
+        &candidates,
+        &other_options
+    )
+    .is_err());
+
+    // so are failures to deserialize a malformed proof, even if it contains a matching candidate
+    let mut malformed_proof = proof;
+    malformed_proof.constraint_queries = Queries::empty();
+    assert!(matches!(
+        winterfell::verify_any::<FibAir, Blake3_256, RandCoin>(
+            malformed_proof,
+            &candidates,
+            &acceptable_options
+        ),
+        Err(VerifierError::ProofDeserializationError(_))
+    ));
+}
+
+#[test]
+fn verify_transcript_only() {
+    let sequence_length = 64;
+    let options = ProofOptions::new(28, 8, 16, FieldExtension::None, 4, 7);
+    let prover = fib_prover::<Blake3_256>(options);
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    // a well-formed proof passes the cheap check
+    assert_eq!(
+        Ok(()),
+        winterfell::verify_transcript_only::<FibAir, Blake3_256, RandCoin>(
+            proof.clone(),
+            result,
+            &acceptable_options
+        )
+    );
+
+    // a proof with an invalid proof-of-work nonce is rejected
+    let mut bad_proof = proof.clone();
+    bad_proof.pow_nonce += 1;
+    assert_eq!(
+        Err(VerifierError::QuerySeedProofOfWorkVerificationFailed),
+        winterfell::verify_transcript_only::<FibAir, Blake3_256, RandCoin>(
+            bad_proof,
+            result,
+            &acceptable_options
+        )
+    );
+
+    // a proof which declares a number of unique queries different from the number of positions
+    // drawn from the public coin is rejected
+    let num_unique_queries = proof.num_unique_queries as usize;
+    for num_tampered_queries in [num_unique_queries - 1, num_unique_queries + 1] {
+        let mut bad_proof = proof.clone();
+        bad_proof.num_unique_queries = num_tampered_queries as u8;
+        assert_eq!(
+            Err(VerifierError::InconsistentNumQueries(num_unique_queries, num_tampered_queries)),
+            winterfell::verify_transcript_only::<FibAir, Blake3_256, RandCoin>(
+                bad_proof,
+                result,
+                &acceptable_options
+            )
+        );
+    }
+
+    // a proof which does not contain queries for the drawn positions is rejected
+    let mut bad_proof = proof.clone();
+    bad_proof.constraint_queries = Queries::empty();
+    assert!(matches!(
+        winterfell::verify_transcript_only::<FibAir, Blake3_256, RandCoin>(
+            bad_proof,
+            result,
+            &acceptable_options
+        ),
+        Err(VerifierError::ProofDeserializationError(_))
+    ));
+
+    // a proof generated with unacceptable options is rejected
+    let other_options = AcceptableOptions::OptionSet(vec![build_proof_options(false)]);
+    assert_eq!(
+        Err(VerifierError::UnacceptableProofOptions),
+        winterfell::verify_transcript_only::<FibAir, Blake3_256, RandCoin>(
+            proof,
+            result,
+            &other_options
+        )
+    );
+}
+
+#[test]
+fn verify_with_limits() {
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    let trace_length = proof.trace_length();
+    let num_fri_layers = proof.options().to_fri_options().num_fri_layers(proof.lde_domain_size());
+    let num_queries = proof.options().num_queries();
+
+    // a proof within the limits is verified as usual
+    let limits = VerificationLimits::new(trace_length, num_fri_layers, num_queries);
+    assert_eq!(
+        Ok(()),
+        winterfell::verify_with_limits::<FibAir, Blake3_256, RandCoin>(
+            proof.clone(),
+            result,
+            &acceptable_options,
+            &limits
+        )
+    );
+    assert!(winterfell::verify_with_limits::<FibAir, Blake3_256, RandCoin>(
+        proof.clone(),
+        result + BaseElement::ONE,
+        &acceptable_options,
+        &limits
+    )
+    .is_err());
+
+    // a proof exceeding any of the limits is rejected
+    let limits = VerificationLimits::new(trace_length, num_fri_layers - 1, num_queries);
+    assert_eq!(
+        Err(VerifierError::FriLayerLimitExceeded(num_fri_layers - 1, num_fri_layers)),
+        limits.validate(&proof)
+    );
+
+    let limits = VerificationLimits::new(trace_length, num_fri_layers, num_queries - 1);
+    assert_eq!(
+        Err(VerifierError::QueryLimitExceeded(num_queries - 1, num_queries)),
+        limits.validate(&proof)
+    );
+
+    // the limits are checked before the proof is verified; thus, an oversized proof is rejected
+    // at the limit check even if it would also fail verification
+    let limits = VerificationLimits::new(trace_length / 2, num_fri_layers, num_queries);
+    assert_eq!(
+        Err(VerifierError::TraceLengthLimitExceeded(trace_length / 2, trace_length)),
+        winterfell::verify_with_limits::<FibAir, Blake3_256, RandCoin>(
+            proof,
+            result + BaseElement::ONE,
+            &AcceptableOptions::MinConjecturedSecurity(1000),
+            &limits
+        )
+    );
+}
+
+#[test]
+fn proof_metadata() {
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    let metadata = b"block 42";
+
+    let trace = build_fib_trace(sequence_length);
+    let proof = prover.prove_with_metadata(trace, metadata).unwrap();
+    assert_eq!(metadata.to_vec(), proof.metadata);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+
+    // the proof verifies only against the same metadata
+    assert!(winterfell::verify_with_metadata::<FibAir, Blake3_256, RandCoin>(
+        proof.clone(),
+        result,
+        &acceptable_options,
+        metadata,
+    )
+    .is_ok());
+    assert_eq!(
+        Err(VerifierError::InconsistentMetadata),
+        winterfell::verify_with_metadata::<FibAir, Blake3_256, RandCoin>(
+            proof.clone(),
+            result,
+            &acceptable_options,
+            b"block 43",
+        )
+    );
+    assert_eq!(
+        Err(VerifierError::InconsistentMetadata),
+        winterfell::verify::<FibAir, Blake3_256, RandCoin>(
+            proof.clone(),
+            result,
+            &acceptable_options
+        )
+    );
+
+    // altering the metadata attached to the proof breaks verification, even if the verifier
+    // expects the altered metadata
+    let mut altered_proof = proof;
+    altered_proof.metadata = b"block 43".to_vec();
+    assert!(winterfell::verify_with_metadata::<FibAir, Blake3_256, RandCoin>(
+        altered_proof,
+        result,
+        &acceptable_options,
+        b"block 43",
+    )
+    .is_err());
+
+    // empty metadata is the same as no metadata
+    let trace = build_fib_trace(sequence_length);
+    let proof = prover.prove_with_metadata(trace, &[]).unwrap();
+    assert!(proof.metadata.is_empty());
+    assert!(
+        winterfell::verify::<FibAir, Blake3_256, RandCoin>(proof, result, &acceptable_options)
+            .is_ok()
+    );
+}
+
+#[test]
+fn input_commitment() {
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+
+    let trace = build_fib_trace(sequence_length);
+    let proof = prover.prove_with_embedded_inputs(trace).unwrap();
+    assert_eq!(result.to_bytes(), proof.pub_inputs);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+
+    // the proof verifies against a commitment to the correct public inputs
+    let commitment = Blake3_256::hash_elements(&[result]);
+    assert!(winterfell::verify_with_input_commitment::<FibAir, Blake3_256, RandCoin>(
+        proof.clone(),
+        &commitment,
+        &acceptable_options,
+    )
+    .is_ok());
+
+    // embedding public inputs does not affect regular verification
+    assert!(winterfell::verify::<FibAir, Blake3_256, RandCoin>(
+        proof.clone(),
+        result,
+        &acceptable_options
+    )
+    .is_ok());
+
+    // the proof is rejected against a commitment to different public inputs
+    let wrong_commitment = Blake3_256::hash_elements(&[result + BaseElement::ONE]);
+    assert_eq!(
+        Err(VerifierError::InconsistentInputCommitment),
+        winterfell::verify_with_input_commitment::<FibAir, Blake3_256, RandCoin>(
+            proof.clone(),
+            &wrong_commitment,
+            &acceptable_options,
+        )
+    );
+
+    // replacing embedded public inputs with inputs matching a different commitment is detected
+    // by the regular verification procedure
+    let mut altered_proof = proof;
+    altered_proof.pub_inputs = (result + BaseElement::ONE).to_bytes();
+    assert!(winterfell::verify_with_input_commitment::<FibAir, Blake3_256, RandCoin>(
+        altered_proof,
+        &wrong_commitment,
+        &acceptable_options,
+    )
+    .is_err());
+}
+
+#[test]
+fn derive_ood_point() {
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(true));
+    let trace = build_fib_trace(sequence_length);
+    let result = prover.get_pub_inputs(&trace);
+    let trace_polys = trace.main_segment().interpolate_columns();
+    let proof = prover.prove(trace).unwrap();
+
+    let z =
+        winterfell::derive_ood_point::<FibAir, QuadExtension<BaseElement>, Blake3_256, RandCoin>(
+            &proof, result,
+        )
+        .unwrap();
+
+    // the OOD frame in the proof must contain evaluations of trace polynomials at the derived
+    // point; current and next rows are interleaved for each column
+    let air = <FibAir>::new(proof.get_trace_info(), result, proof.options().clone());
+    let (ood_trace_states, _) = proof
+        .ood_frame
+        .clone()
+        .parse::<QuadExtension<BaseElement>>(
+            trace_polys.num_cols(),
+            0,
+            air.context().frame_width(),
+            air.context().num_constraint_composition_columns(),
+        )
+        .unwrap();
+    let ood_current_row = ood_trace_states
+        .iter()
+        .step_by(2)
+        .copied()
+        .collect::<Vec<QuadExtension<BaseElement>>>();
+    assert_eq!(trace_polys.evaluate_columns_at(z), ood_current_row);
+
+    // a different set of public inputs results in a different OOD point
+    let other_z =
+        winterfell::derive_ood_point::<FibAir, QuadExtension<BaseElement>, Blake3_256, RandCoin>(
+            &proof,
+            result + BaseElement::ONE,
+        )
+        .unwrap();
+    assert_ne!(z, other_z);
+}
+
+#[test]
+fn verify_with_challenges() {
+    type E = QuadExtension<BaseElement>;
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(true));
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let verify = |challenges: VerifierChallenges<E>| {
+        winterfell::verify_with_challenges::<FibAir, E, Blake3_256>(
+            proof.clone(),
+            result,
+            challenges,
+            &acceptable_options,
+        )
+    };
+
+    // challenges derived from the transcript give the same result as standard verification
+    let challenges =
+        winterfell::derive_challenges::<FibAir, E, Blake3_256, RandCoin>(&proof, result).unwrap();
+    assert_eq!(
+        Ok(()),
+        winterfell::verify::<FibAir, Blake3_256, RandCoin>(
+            proof.clone(),
+            result,
+            &acceptable_options
+        )
+    );
+    assert_eq!(Ok(()), verify(challenges.clone()));
+    let fri_options = proof.options().to_fri_options();
+    let num_fri_layers = fri_options.num_fri_layers(proof.lde_domain_size());
+    assert_eq!(num_fri_layers + 1, challenges.fri_alphas.len());
+    assert_eq!(proof.num_unique_queries as usize, challenges.query_positions.len());
+
+    // query positions are sorted and deduplicated before use
+    let mut shuffled = challenges.clone();
+    shuffled.query_positions.reverse();
+    shuffled.query_positions.push(challenges.query_positions[0]);
+    assert_eq!(Ok(()), verify(shuffled));
+
+    // the proof does not verify against different challenges or public inputs
+    let mut invalid = challenges.clone();
+    invalid.ood_point += E::ONE;
+    assert_eq!(Err(VerifierError::InconsistentOodConstraintEvaluations), verify(invalid));
+
+    let mut invalid = challenges.clone();
+    invalid.constraint_coefficients.transition[0] += E::ONE;
+    assert_eq!(Err(VerifierError::InconsistentOodConstraintEvaluations), verify(invalid));
+
+    let mut invalid = challenges.clone();
+    invalid.deep_coefficients.trace[0] += E::ONE;
+    assert!(matches!(verify(invalid), Err(VerifierError::FriVerificationFailed(_))));
+
+    let mut invalid = challenges.clone();
+    invalid.fri_alphas[0] += E::ONE;
+    assert!(matches!(verify(invalid), Err(VerifierError::FriVerificationFailed(_))));
+
+    let mut invalid = challenges.clone();
+    invalid.query_positions[0] ^= 1;
+    assert!(verify(invalid).is_err());
+
+    assert!(winterfell::verify_with_challenges::<FibAir, E, Blake3_256>(
+        proof.clone(),
+        result + BaseElement::ONE,
+        challenges.clone(),
+        &acceptable_options,
+    )
+    .is_err());
+
+    // challenges of unexpected shape are rejected
+    let mut invalid = challenges.clone();
+    invalid.deep_coefficients.constraints.pop();
+    assert!(matches!(verify(invalid), Err(VerifierError::InvalidChallenges(_))));
+
+    let mut invalid = challenges.clone();
+    invalid.query_positions.push(proof.lde_domain_size());
+    assert!(matches!(verify(invalid), Err(VerifierError::InvalidChallenges(_))));
+
+    let mut invalid = challenges;
+    invalid.fri_alphas.pop();
+    assert!(matches!(verify(invalid), Err(VerifierError::FriVerificationFailed(_))));
+}
+
+#[test]
+fn replay_verification() {
+    type E = QuadExtension<BaseElement>;
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(true));
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    let replay = |proof: &StarkProof, result: BaseElement| {
+        verifier::replay_verification::<FibAir, E, Blake3_256, RandCoin>(proof, result)
+    };
+
+    // replaying the same proof always yields the same transcript, also when the proof is
+    // deserialized from bytes
+    let transcript = replay(&proof, result).unwrap();
+    let deserialized = StarkProof::from_bytes(&proof.to_bytes()).unwrap();
+    assert_eq!(transcript, replay(&deserialized, result).unwrap());
+    assert_eq!(format!("{transcript:?}"), format!("{:?}", replay(&proof, result).unwrap()));
+
+    // the transcript records the values computed by the verifier
+    assert_eq!(Ok(()), transcript.result);
+    assert_eq!(
+        winterfell::derive_challenges::<FibAir, E, Blake3_256, RandCoin>(&proof, result).unwrap(),
+        transcript.challenges
+    );
+    assert_eq!(2, transcript.ood_trace_rows.len());
+    assert_eq!(transcript.ood_composition_from_trace, transcript.ood_composition_from_columns);
+    assert_eq!(transcript.challenges.query_positions.len(), transcript.deep_evaluations.len());
+
+    // for different public inputs, the transcript records the values computed by the verifier
+    // up to the failed out-of-domain consistency check
+    let transcript = replay(&proof, result + BaseElement::ONE).unwrap();
+    assert_eq!(Err(VerifierError::InconsistentOodConstraintEvaluations), transcript.result);
+    assert_ne!(transcript.ood_composition_from_trace, transcript.ood_composition_from_columns);
+    assert!(transcript.deep_evaluations.is_empty());
+}
+
+#[test]
+fn verify_with_executor() {
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+
+    // verification with a sequential or a custom executor gives the same result as standard
+    // verification, both for valid and for invalid inputs
+    let executor = CountingExecutor::default();
+    for pub_inputs in [result, result + BaseElement::ONE] {
+        let expected = winterfell::verify::<FibAir, Blake3_256, RandCoin>(
+            proof.clone(),
+            pub_inputs,
+            &acceptable_options,
+        );
+        let sequential = winterfell::verify_with_executor::<FibAir, Blake3_256, RandCoin, _>(
+            proof.clone(),
+            pub_inputs,
+            &acceptable_options,
+            &SequentialExecutor,
+        );
+        let custom = winterfell::verify_with_executor::<FibAir, Blake3_256, RandCoin, _>(
+            proof.clone(),
+            pub_inputs,
+            &acceptable_options,
+            &executor,
+        );
+        assert_eq!(expected, sequential);
+        assert_eq!(expected, custom);
+    }
+    assert!(winterfell::verify::<FibAir, Blake3_256, RandCoin>(
+        proof.clone(),
+        result,
+        &acceptable_options
+    )
+    .is_ok());
+
+    // query authentication is scheduled via the custom executor; for invalid public inputs, the
+    // verification fails before queries are authenticated
+    assert_eq!(1, executor.num_joins.load(Ordering::Relaxed));
+    assert_eq!(1, executor.num_maps.load(Ordering::Relaxed));
+}
+
+#[test]
+fn verify_sampled() {
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let proof = prover.prove(build_fib_trace(sequence_length)).unwrap();
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+
+    // a valid proof passes sampled verification for any fraction of checked queries
+    let mut rng = StdRng::seed_from_u64(42);
+    for fraction in [0.01, 0.25, 0.5, 1.0] {
+        for _ in 0..4 {
+            let result = winterfell::verify_sampled::<FibAir, Blake3_256, RandCoin, _>(
+                proof.clone(),
+                result,
+                &acceptable_options,
+                fraction,
+                &mut rng,
+            );
+            assert!(result.is_ok(), "{:?}", result);
+        }
+    }
+
+    // tamper with the FRI remainder; this does not affect any of the commitments or the
+    // transcript, and thus, can be detected only by checking the queries
+    let remainder = proof.fri_proof.remainder_bytes().to_vec();
+    let mut proof_bytes = proof.to_bytes();
+    let remainder_start = proof_bytes
+        .windows(remainder.len())
+        .position(|window| window == remainder)
+        .unwrap();
+    proof_bytes[remainder_start] ^= 1;
+    let tampered = StarkProof::from_bytes(&proof_bytes).unwrap();
+    assert!(winterfell::verify::<FibAir, Blake3_256, RandCoin>(
+        tampered.clone(),
+        result,
+        &acceptable_options
+    )
+    .is_err());
+
+    // the tampered proof is caught over repeated sampling
+    let num_rejected = (0..16)
+        .filter(|_| {
+            winterfell::verify_sampled::<FibAir, Blake3_256, RandCoin, _>(
+                tampered.clone(),
+                result,
+                &acceptable_options,
+                0.1,
+                &mut rng,
+            )
+            .is_err()
+        })
+        .count();
+    assert!(num_rejected > 0);
+}
+
+#[test]
+fn verify_streamed() {
+    type RandCoin = DefaultRandomCoin<Blake3_256>;
+
+    let sequence_length = 64;
+    let prover = fib_prover::<Blake3_256>(build_proof_options(false));
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+    let (proof, responses) = prover.prove_streamed(build_fib_trace(sequence_length)).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+
+    // responses survive serialization
+    let responses = responses
+        .iter()
+        .map(|response| QueryResponse::read_from_bytes(&response.to_bytes()).unwrap())
+        .collect::<Vec<_>>();
+    assert!(responses.len() > 1);
+
+    // a streamed proof verifies against its responses, but not on its own
+    let verified = winterfell::verify_streamed::<FibAir, Blake3_256, RandCoin, _>(
+        proof.clone(),
+        result,
+        &acceptable_options,
+        responses.clone(),
+    );
+    assert!(verified.is_ok(), "{:?}", verified);
+    assert!(winterfell::verify::<FibAir, Blake3_256, RandCoin>(
+        proof.clone(),
+        result,
+        &acceptable_options
+    )
+    .is_err());
+
+    // a response for a different position is rejected without reading further responses
+    let mut swapped = responses.clone();
+    swapped.swap(0, 1);
+    let num_read = AtomicUsize::new(0);
+    let stream = swapped.into_iter().inspect(|_| {
+        num_read.fetch_add(1, Ordering::Relaxed);
+    });
+    assert!(matches!(
+        winterfell::verify_streamed::<FibAir, Blake3_256, RandCoin, _>(
+            proof.clone(),
+            result,
+            &acceptable_options,
+            stream,
+        ),
+        Err(VerifierError::TraceQueryDoesNotMatchCommitment)
+    ));
+    assert_eq!(1, num_read.load(Ordering::Relaxed));
+
+    // missing and extra responses are rejected
+    let num_responses = responses.len();
+    let mut extra = responses.clone();
+    extra.push(responses[0].clone());
+    for (stream, expected) in [
+        (responses[..num_responses - 1].to_vec(), num_responses - 1),
+        (extra, num_responses + 1),
+    ] {
+        assert_eq!(
+            Err(VerifierError::InconsistentNumQueries(num_responses, expected)),
+            winterfell::verify_streamed::<FibAir, Blake3_256, RandCoin, _>(
+                proof.clone(),
+                result,
+                &acceptable_options,
+                stream,
+            )
+        );
+    }
+}
+
+#[test]
+fn separate_fri_hash_fn() {
+    type RandCoin = DefaultRandomCoin<Sha3_256>;
+    let sequence_length = 64;
+    let result = compute_fib_term::<BaseElement>(sequence_length);
+
+    for extension in [false, true] {
+        // trace and constraints are committed to with SHA3, while FRI layers are committed to
+        // with BLAKE3
+        let options = build_proof_options(extension)
+            .with_hash_fns(HashFunctionId::Sha3_256, HashFunctionId::Blake3_256);
+        let prover = fib_prover::<Sha3_256>(options);
+        let proof = prover
+            .prove_with_fri_hash_fn::<Blake3_256>(build_fib_trace(sequence_length))
+            .unwrap();
+        assert_eq!(
+            Some((HashFunctionId::Sha3_256, HashFunctionId::Blake3_256)),
+            proof.options().hash_fns()
+        );
+        assert_eq!(proof, StarkProof::from_bytes(&proof.to_bytes()).unwrap());
+
+        let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+        assert_eq!(
+            Ok(()),
+            winterfell::verify_with_fri_hash_fn::<FibAir, Sha3_256, Blake3_256, RandCoin>(
+                proof.clone(),
+                result,
+                &acceptable_options
+            )
+        );
+        assert!(winterfell::verify_with_fri_hash_fn::<FibAir, Sha3_256, Blake3_256, RandCoin>(
+            proof.clone(),
+            result + BaseElement::ONE,
+            &acceptable_options
+        )
+        .is_err());
+
+        // the proof does not verify with hash functions other than the ones recorded in the proof
+        assert_eq!(
+            Err(VerifierError::InconsistentHashFunctions),
+            winterfell::verify_with_fri_hash_fn::<FibAir, Sha3_256, Sha3_256, RandCoin>(
+                proof.clone(),
+                result,
+                &acceptable_options
+            )
+        );
+        assert!(winterfell::verify::<FibAir, Sha3_256, RandCoin>(
+            proof,
+            result,
+            &acceptable_options
+        )
+        .is_err());
+
+        // the prover must use the hash functions recorded in the proof options
+        let options = build_proof_options(extension)
+            .with_hash_fns(HashFunctionId::Sha3_256, HashFunctionId::Blake3_256);
+        let trace = build_fib_trace(sequence_length);
+        assert_eq!(
+            Err(ProverError::InconsistentHashFunctions),
+            fib_prover::<Sha3_256>(options.clone())
+                .prove_with_fri_hash_fn::<Sha3_256>(trace.clone())
+        );
+        assert_eq!(
+            Err(ProverError::InconsistentHashFunctions),
+            fib_prover::<Blake3_256>(options).prove_with_fri_hash_fn::<Blake3_256>(trace.clone())
+        );
+
+        // hash functions must be specified in proof options to use a separate hash for FRI
+        let prover = fib_prover::<Sha3_256>(build_proof_options(extension));
+        assert_eq!(
+            Err(ProverError::UnspecifiedHashFunctions),
+            prover.prove_with_fri_hash_fn::<Blake3_256>(trace.clone())
+        );
+        let proof = prover.prove(trace.clone()).unwrap();
+        let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+        assert_eq!(
+            Err(VerifierError::UnspecifiedHashFunctions),
+            winterfell::verify_with_fri_hash_fn::<FibAir, Sha3_256, Blake3_256, RandCoin>(
+                proof,
+                result,
+                &acceptable_options
+            )
+        );
+
+        // hash functions with unknown identifiers cannot be told apart, and thus, are rejected
+        // even when proof options record unknown identifiers
+        let options = build_proof_options(extension)
+            .with_hash_fns(HashFunctionId::Unknown, HashFunctionId::Unknown);
+        let prover = fib_prover::<UnknownHasher>(options);
+        assert_eq!(
+            Err(ProverError::UnknownHashFunction),
+            prover.prove_with_fri_hash_fn::<UnknownHasher>(trace.clone())
+        );
+        let proof = prover.prove(trace).unwrap();
+        let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+        assert_eq!(
+            Err(VerifierError::UnknownHashFunction),
+            winterfell::verify_with_fri_hash_fn::<
+                FibAir,
+                UnknownHasher,
+                UnknownHasher,
+                DefaultRandomCoin<UnknownHasher>,
+            >(proof, result, &acceptable_options)
+        );
+    }
+}
+
+/// BLAKE3 hash function which is not identified as such.
+struct UnknownHasher;
+
+impl Hasher for UnknownHasher {
+    type Digest = <Blake3_256 as Hasher>::Digest;
+
+    const COLLISION_RESISTANCE: u32 = Blake3_256::COLLISION_RESISTANCE;
+
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        Blake3_256::hash(bytes)
+    }
+
+    fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
+        Blake3_256::merge(values)
+    }
+
+    fn merge_with_int(seed: Self::Digest, value: u64) -> Self::Digest {
+        Blake3_256::merge_with_int(seed, value)
+    }
+}
+
+impl ElementHasher for UnknownHasher {
+    type BaseField = BaseElement;
+
+    fn hash_elements<E: FieldElement<BaseField = BaseElement>>(elements: &[E]) -> Self::Digest {
+        Blake3_256::hash_elements(elements)
+    }
+}
+
+impl IdentifiableHasher for UnknownHasher {
+    const ID: HashFunctionId = HashFunctionId::Unknown;
+}
+
+#[test]
+fn verification_key() {
+    type RandCoin = DefaultRandomCoin<HashChainHasher>;
+
+    let sequence = (0..20u64).map(|i| f64::BaseElement::new(i * 3 + 1)).collect::<Vec<_>>();
+    let prover = hash_chain_prover(build_proof_options(true));
+    let trace = build_hash_chain_trace(&sequence, 32);
+    let pub_inputs = prover.get_pub_inputs(&trace);
+    let (proof, vk) = prover.prove_with_vk(trace.clone()).unwrap();
+    assert_eq!(proof.context, *vk.context());
+    assert!(!vk.periodic_column_polys().is_empty());
+
+    // verifying via the verification key gives the same result as the standard verification
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let mut wrong_inputs = pub_inputs;
+    wrong_inputs.digest[0] += f64::BaseElement::ONE;
+    for inputs in [pub_inputs, wrong_inputs] {
+        assert_eq!(
+            winterfell::verify::<HashChainAir, HashChainHasher, RandCoin>(
+                proof.clone(),
+                inputs,
+                &acceptable_options
+            ),
+            winterfell::verify_with_vk::<HashChainAir, HashChainHasher, RandCoin>(
+                proof.clone(),
+                &vk,
+                inputs
+            )
+        );
+    }
+    assert_eq!(
+        Ok(()),
+        winterfell::verify_with_vk::<HashChainAir, HashChainHasher, RandCoin>(
+            proof.clone(),
+            &vk,
+            pub_inputs
+        )
+    );
+
+    // the verification key can be deployed in serialized form
+    let vk_bytes = vk.to_bytes();
+    let deployed_vk = VerificationKey::<f64::BaseElement>::from_bytes(&vk_bytes).unwrap();
+    assert_eq!(vk, deployed_vk);
+    assert_eq!(
+        Ok(()),
+        winterfell::verify_with_vk::<HashChainAir, HashChainHasher, RandCoin>(
+            proof.clone(),
+            &deployed_vk,
+            pub_inputs
+        )
+    );
+
+    // a proof generated with different options is rejected
+    let other_prover = hash_chain_prover(build_proof_options(false));
+    let other_proof = other_prover.prove(trace).unwrap();
+    assert_eq!(
+        Err(VerifierError::InconsistentVerificationKey),
+        winterfell::verify_with_vk::<HashChainAir, HashChainHasher, RandCoin>(
+            other_proof,
+            &vk,
+            pub_inputs
+        )
+    );
+
+    // a verification key with different periodic columns is rejected
+    let mut tampered_bytes = vk_bytes.clone();
+    let position = tampered_bytes.len() - 8;
+    tampered_bytes[position] ^= 1;
+    let tampered_vk = VerificationKey::<f64::BaseElement>::from_bytes(&tampered_bytes).unwrap();
+    assert_eq!(
+        Err(VerifierError::InconsistentVerificationKey),
+        winterfell::verify_with_vk::<HashChainAir, HashChainHasher, RandCoin>(
+            proof,
+            &tampered_vk,
+            pub_inputs
+        )
+    );
+
+    // trailing bytes are not accepted
+    let mut extended_bytes = vk_bytes;
+    extended_bytes.push(0);
+    assert_eq!(
+        Err(DeserializationError::UnconsumedBytes),
+        VerificationKey::<f64::BaseElement>::from_bytes(&extended_bytes)
+    );
+}
+
+/// Sequential executor which counts how many times each of its methods was invoked.
+#[derive(Default)]
+struct CountingExecutor {
+    num_joins: AtomicUsize,
+    num_maps: AtomicUsize,
+}
+
+impl Executor for CountingExecutor {
+    fn join<A, B, RA, RB>(&self, a: A, b: B) -> (RA, RB)
+    where
+        A: FnOnce() -> RA + Send,
+        B: FnOnce() -> RB + Send,
+        RA: Send,
+        RB: Send,
+    {
+        self.num_joins.fetch_add(1, Ordering::Relaxed);
+        SequentialExecutor.join(a, b)
+    }
+
+    fn map<T, R, F>(&self, items: &[T], op: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&T) -> R + Sync + Send,
+    {
+        self.num_maps.fetch_add(1, Ordering::Relaxed);
+        SequentialExecutor.map(items, op)
+    }
+}